@@ -0,0 +1,618 @@
+//! Whole-install export and import
+//!
+//! An app data bundle is a zip holding a manifest, a consistent copy of the
+//! database and the settings as readable JSON. The settings include the
+//! custom capture regions and their per-region OCR profiles; scoring
+//! profiles (archetypes) travel in the database. Importing either replaces
+//! the database with the bundled one, or merges the player's own data
+//! (runs, history, notes, tags, favorites, archetypes and OCR corrections)
+//! into the current database; card data always comes from the running build.
+
+use crate::database::access::DatabaseAccess;
+use crate::database::{self, migrations};
+use crate::settings::{self, AppSettings};
+use crate::zip_archive;
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_ENTRY: &str = "manifest.json";
+pub const DATABASE_ENTRY: &str = "mt2_draft.db";
+pub const SETTINGS_ENTRY: &str = "settings.json";
+
+/// Bundle layout version, bumped when entries change meaning
+const BUNDLE_FORMAT: u32 = 1;
+
+/// Error type for app data bundles
+#[derive(Debug)]
+pub enum AppDataError {
+    Database(String),
+    Io(String),
+    InvalidBundle(String),
+}
+
+impl fmt::Display for AppDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppDataError::Database(msg) => write!(f, "Database error: {}", msg),
+            AppDataError::Io(msg) => write!(f, "I/O error: {}", msg),
+            AppDataError::InvalidBundle(msg) => write!(f, "Invalid app data bundle: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AppDataError {}
+
+impl From<rusqlite::Error> for AppDataError {
+    fn from(err: rusqlite::Error) -> Self {
+        AppDataError::Database(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppDataError {
+    fn from(err: std::io::Error) -> Self {
+        AppDataError::Io(err.to_string())
+    }
+}
+
+impl From<settings::SettingsError> for AppDataError {
+    fn from(err: settings::SettingsError) -> Self {
+        AppDataError::Database(err.to_string())
+    }
+}
+
+/// Description of a bundle, stored as its first entry
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BundleManifest {
+    pub format: u32,
+    pub app_version: String,
+    /// Database migration the bundled database is at
+    pub schema_version: i32,
+    pub exported_at: String,
+}
+
+/// How an import treats data that already exists
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Replace the whole database and all settings with the bundle's
+    Replace,
+    /// Merge, keeping current notes, aliases and settings on conflicts
+    #[default]
+    KeepExisting,
+    /// Merge, taking the bundle's notes, aliases and settings on conflicts
+    PreferImported,
+}
+
+/// Outcome of an import
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AppDataImportReport {
+    pub policy: ConflictPolicy,
+    pub manifest: BundleManifest,
+    pub replaced_database: bool,
+    /// Rows added or updated by a merge
+    pub merged_rows: u32,
+}
+
+/// A temporary file next to the database, removed when dropped
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn beside(db_path: &Path, suffix: &str) -> Self {
+        let mut name = db_path.as_os_str().to_os_string();
+        name.push(suffix);
+        let path = PathBuf::from(name);
+        let _ = fs::remove_file(&path);
+        TempFile(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Pack the database and settings into a zip bundle
+pub fn export(access: &DatabaseAccess) -> Result<Vec<u8>, AppDataError> {
+    // VACUUM INTO writes a consistent, compacted copy even while in use;
+    // on the writer, no write lands between the copy and the settings
+    let copy = TempFile::beside(access.db_path(), ".export.tmp");
+    let copy_path = copy.0.to_string_lossy().into_owned();
+    let (schema_version, app_settings) = access.write_and_wait(move |conn| {
+        conn.execute("VACUUM INTO ?1", [copy_path])?;
+        Ok::<_, AppDataError>((migrations::schema_version(conn)?, settings::read_all(conn)?))
+    })?;
+    let database = fs::read(&copy.0)?;
+
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let manifest = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| AppDataError::Io(format!("Failed to encode manifest: {}", e)))?;
+    let settings = serde_json::to_vec_pretty(&app_settings)
+        .map_err(|e| AppDataError::Io(format!("Failed to encode settings: {}", e)))?;
+
+    Ok(zip_archive::write(&[
+        (MANIFEST_ENTRY, &manifest),
+        (DATABASE_ENTRY, &database),
+        (SETTINGS_ENTRY, &settings),
+    ])?)
+}
+
+/// Rows the player created, merged by `import` outside `Replace`
+///
+/// Each statement copies from the attached `bundle` database into `main`;
+/// `{insert}` is `INSERT OR IGNORE` or `INSERT OR REPLACE` by policy, and
+/// `{run_conflict}` is `KEEP_RUNS` or `PREFER_IMPORTED_RUNS`. Rows
+/// referencing cards this build does not know are skipped.
+const MERGE_STATEMENTS: &[&str] = &[
+    "INSERT INTO main.runs
+         (run_id, champion, champion_path, covenant, did_win, final_ring, started_at, ended_at,
+          archived_at, deleted_at)
+     SELECT run_id, champion, champion_path, covenant, did_win, final_ring, started_at, ended_at,
+            archived_at, deleted_at
+     FROM bundle.runs
+     WHERE true
+     {run_conflict}",
+    "INSERT INTO main.deck_history
+         (run_id, card_id, ring_number, draft_order, champion, covenant,
+          score_at_draft, did_win, created_at)
+     SELECT b.run_id, b.card_id, b.ring_number, b.draft_order, b.champion, b.covenant,
+            b.score_at_draft, b.did_win, b.created_at
+     FROM bundle.deck_history AS b
+     WHERE b.card_id IN (SELECT id FROM main.cards)
+       AND NOT EXISTS (SELECT 1 FROM main.deck_history AS m
+                       WHERE m.run_id = b.run_id AND m.card_id = b.card_id
+                         AND m.draft_order = b.draft_order)",
+    "INSERT INTO main.draft_offers
+         (run_id, ring_number, card_id, was_picked, slot, score, created_at)
+     SELECT run_id, ring_number, card_id, was_picked, slot, score, created_at
+     FROM bundle.draft_offers
+     WHERE card_id IN (SELECT id FROM main.cards)
+       AND run_id NOT IN (SELECT run_id FROM main.draft_offers)",
+    "{insert} INTO main.card_notes (card_id, note, updated_at)
+     SELECT card_id, note, updated_at FROM bundle.card_notes
+     WHERE card_id IN (SELECT id FROM main.cards)",
+    "INSERT OR IGNORE INTO main.card_tags (card_id, tag, created_at)
+     SELECT card_id, tag, created_at FROM bundle.card_tags
+     WHERE card_id IN (SELECT id FROM main.cards)",
+    "INSERT OR IGNORE INTO main.favorites (card_id, created_at)
+     SELECT card_id, created_at FROM bundle.favorites
+     WHERE card_id IN (SELECT id FROM main.cards)",
+    "{insert} INTO main.ocr_aliases (normalized_text, card_id, times_reported, updated_at)
+     SELECT normalized_text, card_id, times_reported, updated_at FROM bundle.ocr_aliases
+     WHERE card_id IN (SELECT id FROM main.cards)",
+    "INSERT INTO main.ocr_corrections (raw_text, wrong_card_id, correct_card_id, reported_at)
+     SELECT b.raw_text, b.wrong_card_id, b.correct_card_id, b.reported_at
+     FROM bundle.ocr_corrections AS b
+     WHERE b.correct_card_id IN (SELECT id FROM main.cards)
+       AND NOT EXISTS (SELECT 1 FROM main.ocr_corrections AS m
+                       WHERE m.raw_text = b.raw_text AND m.correct_card_id = b.correct_card_id
+                         AND m.reported_at IS b.reported_at)",
+    "{insert} INTO main.archetypes
+         (id, name, description, keyword_weights, key_cards, active, ideal_curve)
+     SELECT id, name, description, keyword_weights, key_cards, active, ideal_curve
+     FROM bundle.archetypes
+     EXCEPT
+     SELECT id, name, description, keyword_weights, key_cards, active, ideal_curve
+     FROM main.archetypes",
+];
+
+/// Conflicting runs keep the existing row
+const KEEP_RUNS: &str = "ON CONFLICT(run_id) DO NOTHING";
+
+/// Conflicting runs take the imported row, except that a run archived or
+/// deleted on either side stays so; an import never brings a deleted run back
+const PREFER_IMPORTED_RUNS: &str = "ON CONFLICT(run_id) DO UPDATE SET
+         champion = excluded.champion,
+         champion_path = excluded.champion_path,
+         covenant = excluded.covenant,
+         did_win = excluded.did_win,
+         final_ring = excluded.final_ring,
+         started_at = excluded.started_at,
+         ended_at = excluded.ended_at,
+         archived_at = COALESCE(excluded.archived_at, archived_at),
+         deleted_at = COALESCE(deleted_at, excluded.deleted_at)";
+
+/// Copy the player's data from the database at `bundle_db` into `conn`
+fn merge_database(
+    conn: &Connection,
+    bundle_db: &Path,
+    policy: ConflictPolicy,
+) -> Result<u32, AppDataError> {
+    let (insert, run_conflict) = match policy {
+        ConflictPolicy::PreferImported => ("INSERT OR REPLACE", PREFER_IMPORTED_RUNS),
+        _ => ("INSERT OR IGNORE", KEEP_RUNS),
+    };
+
+    conn.execute(
+        "ATTACH DATABASE ?1 AS bundle",
+        [bundle_db.to_string_lossy()],
+    )?;
+    let merged = run_merge_statements(conn, insert, run_conflict);
+    conn.execute("DETACH DATABASE bundle", [])?;
+    Ok(merged?)
+}
+
+fn run_merge_statements(
+    conn: &Connection,
+    insert: &str,
+    run_conflict: &str,
+) -> rusqlite::Result<u32> {
+    let tx = conn.unchecked_transaction()?;
+    let mut merged = 0;
+    for statement in MERGE_STATEMENTS {
+        let statement = statement
+            .replace("{insert}", insert)
+            .replace("{run_conflict}", run_conflict);
+        merged += tx.execute(&statement, [])? as u32;
+    }
+    tx.commit()?;
+    Ok(merged)
+}
+
+/// Settings to apply after an import, or `None` to leave them unchanged
+///
+/// Outside `PreferImported` and `Replace`, only sections never saved on this
+/// install are taken from the bundle.
+fn settings_to_apply(
+    conn: &Connection,
+    bundled: AppSettings,
+    policy: ConflictPolicy,
+) -> Result<settings::SettingsUpdate, AppDataError> {
+    let take_all = policy != ConflictPolicy::KeepExisting;
+    let missing = |key: &str| -> Result<bool, AppDataError> {
+        Ok(take_all || database::settings::get_setting(conn, key)?.is_none())
+    };
+
+    Ok(settings::SettingsUpdate {
+        ocr: missing(settings::OCR_KEY)?.then_some(bundled.ocr),
+        overlay: missing(settings::OVERLAY_KEY)?.then_some(bundled.overlay),
+        hotkeys: missing(settings::HOTKEYS_KEY)?.then_some(bundled.hotkeys),
+        scoring: missing(settings::SCORING_KEY)?.then_some(bundled.scoring),
+        logging: missing(settings::LOGGING_KEY)?.then_some(bundled.logging),
+        regions: missing(settings::REGIONS_KEY)?.then_some(bundled.regions),
+    })
+}
+
+/// Restore a bundle written by `export` into the database behind `access`
+///
+/// The database part is applied here; the returned settings update still
+/// has to be applied, since settings take effect in the running app.
+pub fn import(
+    access: &DatabaseAccess,
+    bundle: &[u8],
+    policy: ConflictPolicy,
+) -> Result<(AppDataImportReport, settings::SettingsUpdate), AppDataError> {
+    let invalid = |msg: &str| AppDataError::InvalidBundle(msg.to_string());
+
+    let entries = zip_archive::read(bundle).map_err(|e| invalid(&e.to_string()))?;
+    let entry = |name: &str| {
+        entries
+            .iter()
+            .find(|(entry, _)| entry == name)
+            .map(|(_, contents)| contents)
+    };
+
+    let manifest: BundleManifest =
+        serde_json::from_slice(entry(MANIFEST_ENTRY).ok_or_else(|| invalid("missing manifest"))?)
+            .map_err(|e| invalid(&format!("unreadable manifest: {}", e)))?;
+    if manifest.format != BUNDLE_FORMAT {
+        return Err(invalid(&format!("unsupported format {}", manifest.format)));
+    }
+    if manifest.schema_version > migrations::CURRENT_VERSION {
+        return Err(invalid(&format!(
+            "exported by a newer version ({}); update the app first",
+            manifest.app_version
+        )));
+    }
+    let bundled_settings: Option<AppSettings> = entry(SETTINGS_ENTRY)
+        .map(|json| serde_json::from_slice(json))
+        .transpose()
+        .map_err(|e| invalid(&format!("unreadable settings: {}", e)))?;
+
+    let mut report = AppDataImportReport {
+        policy,
+        manifest,
+        replaced_database: false,
+        merged_rows: 0,
+    };
+
+    let staged = match entry(DATABASE_ENTRY) {
+        Some(database) => {
+            let staged = TempFile::beside(access.db_path(), ".import.tmp");
+            fs::write(&staged.0, database)?;
+            // Bring older bundles up to the current schema before using them
+            database::init(&staged.0).map_err(|e| invalid(&format!("unusable database: {}", e)))?;
+            Some(staged)
+        }
+        None => None,
+    };
+
+    let db_path = access.db_path().to_path_buf();
+    let (merged_rows, update) = access.write_and_wait(move |conn| {
+        let merged_rows = match staged {
+            Some(staged) if policy == ConflictPolicy::Replace => {
+                // Restore through SQLite rather than copying the file, which
+                // would leave other connections with a stale WAL. `restore`
+                // needs a connection of its own; running it as a writer job
+                // keeps other writes from interleaving.
+                let mut restore_conn = database::open(&db_path)?;
+                restore_conn.restore(DatabaseName::Main, &staged.0, None::<fn(Progress)>)?;
+                None
+            }
+            Some(staged) => Some(merge_database(conn, &staged.0, policy)?),
+            None => Some(0),
+        };
+        let update = match bundled_settings {
+            Some(bundled) => settings_to_apply(conn, bundled, policy)?,
+            None => settings::SettingsUpdate::default(),
+        };
+        Ok::<_, AppDataError>((merged_rows, update))
+    })?;
+    match merged_rows {
+        Some(merged_rows) => report.merged_rows = merged_rows,
+        None => report.replaced_database = true,
+    }
+
+    Ok((report, update))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::annotations;
+    use crate::ocr::{CardDetectionOptions, NormalizedRegion, PreprocessStep};
+    use crate::scoring::archetypes::{self, Archetype};
+    use crate::settings::{RegionPreferences, RegionProfilePreferences, ScoringPreferences};
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    fn access(db_path: &Path) -> DatabaseAccess {
+        DatabaseAccess::new(db_path.to_path_buf())
+    }
+
+    fn setup(dir: &TempDir, name: &str) -> PathBuf {
+        let db_path = dir.path().join(name);
+        database::init(&db_path).unwrap();
+        db_path
+    }
+
+    fn history_rows(db_path: &Path) -> u32 {
+        Connection::open(db_path)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM deck_history", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn log_run(db_path: &Path, run_id: &str, card_id: &str) {
+        let conn = Connection::open(db_path).unwrap();
+        conn.execute(
+            "INSERT INTO runs (run_id, champion, covenant) VALUES (?1, 'Fel', 10)",
+            [run_id],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO deck_history (run_id, card_id, ring_number, draft_order, champion, covenant)
+             VALUES (?1, ?2, 1, 1, 'Fel', 10)",
+            [run_id, card_id],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_merge_keeps_existing_on_conflict() {
+        let dir = TempDir::new().unwrap();
+        let source = setup(&dir, "source.db");
+        let target = setup(&dir, "target.db");
+
+        log_run(&source, "run-a", "banished_fel");
+        log_run(&target, "run-b", "banished_talos");
+        let conn = Connection::open(&source).unwrap();
+        annotations::set_note(&conn, "banished_fel", "from bundle").unwrap();
+        annotations::toggle_favorite(&conn, "banished_fel").unwrap();
+        let scoring = ScoringPreferences {
+            use_history_adjustment: false,
+            ..Default::default()
+        };
+        settings::save_section(&access(&source), settings::SCORING_KEY, &scoring).unwrap();
+        let conn = Connection::open(&target).unwrap();
+        annotations::set_note(&conn, "banished_fel", "kept").unwrap();
+
+        let bundle = export(&access(&source)).unwrap();
+        let (report, update) =
+            import(&access(&target), &bundle, ConflictPolicy::KeepExisting).unwrap();
+
+        assert!(!report.replaced_database);
+        assert_eq!(report.manifest.schema_version, migrations::CURRENT_VERSION);
+        // The run, its card and the favorite; the conflicting note is kept
+        assert_eq!(report.merged_rows, 3);
+        assert_eq!(history_rows(&target), 2);
+        assert_eq!(
+            annotations::get_note(&conn, "banished_fel")
+                .unwrap()
+                .as_deref(),
+            Some("kept")
+        );
+        assert!(annotations::is_favorite(&conn, "banished_fel").unwrap());
+        // Never saved on the target, so taken from the bundle
+        assert_eq!(update.scoring, Some(scoring));
+
+        // Importing again adds nothing new
+        let (report, _) = import(&access(&target), &bundle, ConflictPolicy::KeepExisting).unwrap();
+        assert_eq!(history_rows(&target), 2);
+        assert_eq!(report.merged_rows, 0);
+    }
+
+    #[test]
+    fn test_prefer_imported_and_replace() {
+        let dir = TempDir::new().unwrap();
+        let source = setup(&dir, "source.db");
+        let target = setup(&dir, "target.db");
+
+        let conn = Connection::open(&source).unwrap();
+        annotations::set_note(&conn, "banished_fel", "from bundle").unwrap();
+        log_run(&target, "run-b", "banished_talos");
+        let conn = Connection::open(&target).unwrap();
+        annotations::set_note(&conn, "banished_fel", "old").unwrap();
+
+        let bundle = export(&access(&source)).unwrap();
+        let (_, update) =
+            import(&access(&target), &bundle, ConflictPolicy::PreferImported).unwrap();
+        assert_eq!(
+            annotations::get_note(&conn, "banished_fel")
+                .unwrap()
+                .as_deref(),
+            Some("from bundle")
+        );
+        assert!(update.ocr.is_some());
+
+        let (report, _) = import(&access(&target), &bundle, ConflictPolicy::Replace).unwrap();
+        assert!(report.replaced_database);
+        assert_eq!(history_rows(&target), 0);
+        assert!(!dir.path().join("target.db.import.tmp").exists());
+    }
+
+    #[test]
+    fn test_merge_keeps_archived_and_deleted_runs() {
+        let dir = TempDir::new().unwrap();
+        let source = setup(&dir, "source.db");
+        let target = setup(&dir, "target.db");
+
+        for run_id in ["run-archived", "run-deleted"] {
+            log_run(&source, run_id, "banished_fel");
+            log_run(&target, run_id, "banished_fel");
+        }
+        log_run(&source, "run-new", "banished_talos");
+        let conn = Connection::open(&source).unwrap();
+        conn.execute(
+            "UPDATE runs SET archived_at = CURRENT_TIMESTAMP WHERE run_id = 'run-new'",
+            [],
+        )
+        .unwrap();
+        let conn = Connection::open(&target).unwrap();
+        conn.execute_batch(
+            "UPDATE runs SET archived_at = CURRENT_TIMESTAMP WHERE run_id = 'run-archived';
+             UPDATE runs SET deleted_at = CURRENT_TIMESTAMP WHERE run_id = 'run-deleted';",
+        )
+        .unwrap();
+
+        let flags = |run_id: &str| -> (bool, bool) {
+            conn.query_row(
+                "SELECT archived_at IS NOT NULL, deleted_at IS NOT NULL FROM runs
+                 WHERE run_id = ?1",
+                [run_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap()
+        };
+
+        let bundle = export(&access(&source)).unwrap();
+        for policy in [ConflictPolicy::KeepExisting, ConflictPolicy::PreferImported] {
+            import(&access(&target), &bundle, policy).unwrap();
+
+            assert_eq!(flags("run-archived"), (true, false));
+            assert_eq!(flags("run-deleted"), (false, true));
+            // Runs new to this install arrive as they were exported
+            assert_eq!(flags("run-new"), (true, false));
+        }
+        let live: u32 = conn
+            .query_row("SELECT COUNT(*) FROM live_runs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(live, 2);
+    }
+
+    #[test]
+    fn test_round_trips_region_and_scoring_profiles() {
+        let dir = TempDir::new().unwrap();
+        let source = setup(&dir, "source.db");
+        let target = setup(&dir, "target.db");
+
+        let regions = RegionPreferences {
+            normalized_regions: vec![NormalizedRegion {
+                x: 0.1,
+                y: 0.2,
+                width: 0.3,
+                height: 0.05,
+                role: None,
+            }],
+            profiles: BTreeMap::from([(
+                0,
+                RegionProfilePreferences {
+                    preprocess_steps: Some(vec![PreprocessStep::Grayscale]),
+                    psm: Some(8),
+                    whitelist: Some("0123456789".to_string()),
+                },
+            )]),
+            ..Default::default()
+        };
+        settings::save_section(&access(&source), settings::REGIONS_KEY, &regions).unwrap();
+        let archetype = Archetype {
+            id: "custom_burst".to_string(),
+            name: "Burst".to_string(),
+            description: String::new(),
+            keyword_weights: BTreeMap::from([("burst".to_string(), 1.0)]),
+            key_cards: Vec::new(),
+            ideal_curve: None,
+            active: true,
+        };
+        let conn = Connection::open(&source).unwrap();
+        archetypes::save_archetype(&conn, &archetype).unwrap();
+
+        let bundle = export(&access(&source)).unwrap();
+        let (report, update) =
+            import(&access(&target), &bundle, ConflictPolicy::KeepExisting).unwrap();
+
+        // Only the new archetype; the seeded ones are the same on both sides
+        assert_eq!(report.merged_rows, 1);
+        let conn = Connection::open(&target).unwrap();
+        let imported = archetypes::load_archetypes(&conn).unwrap();
+        assert!(imported.contains(&archetype));
+
+        assert_eq!(update.regions.as_ref(), Some(&regions));
+        let mut config = CardDetectionOptions::default();
+        update.regions.unwrap().apply(&mut config);
+        assert_eq!(
+            config.capture.normalized_regions,
+            regions.normalized_regions
+        );
+        assert_eq!(config.capture.get_regions().len(), 1);
+        let profile = &config.region_profiles[&0];
+        assert_eq!(
+            profile.preprocess.as_ref().unwrap().steps,
+            vec![PreprocessStep::Grayscale]
+        );
+        let recognize = profile.recognize.as_ref().unwrap();
+        assert_eq!(recognize.psm, 8);
+        assert_eq!(recognize.whitelist.as_deref(), Some("0123456789"));
+    }
+
+    #[test]
+    fn test_rejects_bad_bundles() {
+        let dir = TempDir::new().unwrap();
+        let target = setup(&dir, "target.db");
+
+        assert!(matches!(
+            import(&access(&target), b"not a zip", ConflictPolicy::KeepExisting),
+            Err(AppDataError::InvalidBundle(_))
+        ));
+
+        let newer = BundleManifest {
+            format: BUNDLE_FORMAT,
+            app_version: "99.0.0".to_string(),
+            schema_version: migrations::CURRENT_VERSION + 1,
+            exported_at: String::new(),
+        };
+        let manifest = serde_json::to_vec(&newer).unwrap();
+        let bundle = zip_archive::write(&[(MANIFEST_ENTRY, &manifest)]).unwrap();
+        let err = import(&access(&target), &bundle, ConflictPolicy::Replace).unwrap_err();
+        assert!(err.to_string().contains("newer version"));
+    }
+}
@@ -0,0 +1,5 @@
+//! Headless command line interface; see `cli` in the library
+
+fn main() -> std::process::ExitCode {
+    mt2_draft_assistant_lib::cli::main()
+}
@@ -0,0 +1,420 @@
+//! Headless command line interface
+//!
+//! The `mt2` binary runs the same scoring, OCR and export code as the app,
+//! without the overlay, for scripting, testing and setups where the window
+//! can't run. It uses the app's database unless `--db` points elsewhere.
+
+use crate::commands::export;
+use crate::commands::ocr::{self as ocr_commands, DetectedCardInfo};
+use crate::commands::scoring::{self, DraftScoreRequest};
+use crate::database::access::DatabaseAccess;
+use crate::database::{self, cache::CardSnapshot};
+use crate::error::{AppError, AppResult};
+use crate::logging;
+use crate::ocr::capture::{self, CaptureRegion};
+use crate::ocr::CardDetectionOptions;
+use crate::profiles;
+use crate::scoring::calculator::ScoringResult;
+use crate::settings::{self, OcrPreferences};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::AtomicBool;
+
+pub const USAGE: &str = "\
+Usage: mt2 [--db <path>] [--json] <command> [options]
+
+Commands:
+  score --card <id> --champion <name> [--path <name>] [--deck <id,id,...>] [--ring <n>]
+        [--covenant <n>] [--boss <name>] [--pact-shards <n>]
+        Score a card for a deck
+  detect --image <file> [--region <x,y,width,height>]...
+        Detect cards in a screenshot, in the default regions unless given
+  export-history <file>
+        Write the deck history to a CSV file
+  help
+        Show this message
+
+Options:
+  --db <path>   Database to use instead of the app's
+  --json        Print results as JSON";
+
+/// What to run
+#[derive(Debug)]
+pub enum CliCommand {
+    Score(DraftScoreRequest),
+    Detect {
+        image: PathBuf,
+        /// Regions to read, in image pixels; empty for the defaults
+        regions: Vec<CaptureRegion>,
+    },
+    ExportHistory {
+        path: PathBuf,
+    },
+    Help,
+}
+
+/// Parsed command line
+#[derive(Debug)]
+pub struct Cli {
+    pub db_path: Option<PathBuf>,
+    pub json: bool,
+    pub command: CliCommand,
+}
+
+/// `--name value` options left to a command
+struct Options(Vec<(String, String)>);
+
+impl Options {
+    fn all(&mut self, name: &str) -> Vec<String> {
+        let (matching, rest) = self.0.drain(..).partition(|(n, _)| n == name);
+        self.0 = rest;
+        matching.into_iter().map(|(_, value)| value).collect()
+    }
+
+    fn one(&mut self, name: &str) -> Option<String> {
+        self.all(name).pop()
+    }
+
+    fn required(&mut self, name: &str) -> Result<String, String> {
+        self.one(name)
+            .ok_or_else(|| format!("Missing required option --{}", name))
+    }
+
+    fn number(&mut self, name: &str, default: i32) -> Result<i32, String> {
+        match self.one(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("--{} must be a number, got '{}'", name, value)),
+            None => Ok(default),
+        }
+    }
+
+    /// Fail on any option the command did not use
+    fn finish(self) -> Result<(), String> {
+        match self.0.first() {
+            Some((name, _)) => Err(format!("Unknown option --{}", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_region(value: &str) -> Result<CaptureRegion, String> {
+    let parts: Vec<&str> = value.split(',').map(str::trim).collect();
+    let invalid = || format!("Invalid region '{}', expected x,y,width,height", value);
+    if parts.len() != 4 {
+        return Err(invalid());
+    }
+    let region = CaptureRegion::new(
+        parts[0].parse().map_err(|_| invalid())?,
+        parts[1].parse().map_err(|_| invalid())?,
+        parts[2].parse().map_err(|_| invalid())?,
+        parts[3].parse().map_err(|_| invalid())?,
+    );
+    if !region.is_valid() {
+        return Err(invalid());
+    }
+    Ok(region)
+}
+
+/// Parse the arguments after the program name
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Cli, String> {
+    let mut db_path = None;
+    let mut json = false;
+    let mut name = None;
+    let mut positional = Vec::new();
+    let mut options = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "-h" | "--help" => name = Some("help".to_string()),
+            flag if flag.starts_with("--") => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Missing value for {}", flag))?;
+                match &flag[2..] {
+                    "db" => db_path = Some(PathBuf::from(value)),
+                    option => options.push((option.to_string(), value)),
+                }
+            }
+            _ if name.is_none() => name = Some(arg),
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut options = Options(options);
+    let command = match name.as_deref().unwrap_or("help") {
+        "score" => CliCommand::Score(DraftScoreRequest {
+            card_id: options.required("card")?,
+            current_deck: options
+                .all("deck")
+                .iter()
+                .flat_map(|deck| deck.split(','))
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect(),
+            champion: options.required("champion")?,
+            ring_number: options.number("ring", 1)?,
+            covenant: options.number("covenant", 1)?,
+            champion_path: options.one("path"),
+            upcoming_boss: options.one("boss"),
+            pact_shards: Some(options.number("pact-shards", 0)?),
+            upgrade_count: 0,
+        }),
+        "detect" => CliCommand::Detect {
+            image: PathBuf::from(options.required("image")?),
+            regions: options
+                .all("region")
+                .iter()
+                .map(|region| parse_region(region))
+                .collect::<Result<_, _>>()?,
+        },
+        "export-history" => CliCommand::ExportHistory {
+            path: positional
+                .pop()
+                .map(PathBuf::from)
+                .ok_or("export-history needs an output file")?,
+        },
+        "help" => CliCommand::Help,
+        other => return Err(format!("Unknown command '{}'", other)),
+    };
+    options.finish()?;
+    if let Some(extra) = positional.first() {
+        return Err(format!("Unexpected argument '{}'", extra));
+    }
+
+    Ok(Cli {
+        db_path,
+        json,
+        command,
+    })
+}
+
+/// The database of the profile the app is running as
+fn default_db_path() -> PathBuf {
+    profiles::active_database_path(&logging::log_dir())
+}
+
+fn score(access: &DatabaseAccess, request: &DraftScoreRequest) -> AppResult<ScoringResult> {
+    scoring::validate_request(request)?;
+    access.read(|conn| {
+        let snapshot = CardSnapshot::load(conn)?;
+        Ok(scoring::score_card_with_history(conn, &snapshot, request)?)
+    })
+}
+
+fn detect(
+    access: &DatabaseAccess,
+    image: &Path,
+    regions: &[CaptureRegion],
+) -> AppResult<Vec<DetectedCardInfo>> {
+    let screenshot = image::open(image)
+        .map_err(|e| AppError::InvalidInput(format!("Failed to open image: {}", e)))?
+        .to_rgba8();
+
+    let mut config = CardDetectionOptions::default();
+    match settings::load_section::<OcrPreferences>(access, settings::OCR_KEY) {
+        Ok(prefs) => prefs.apply(&mut config),
+        Err(e) => log::warn!("Failed to load OCR settings, using defaults: {}", e),
+    }
+    config.capture.update_regions(if regions.is_empty() {
+        capture::get_default_card_regions(screenshot.width(), screenshot.height())
+    } else {
+        regions.to_vec()
+    });
+
+    ocr_commands::detect_in_image(access, config, &screenshot)
+}
+
+#[derive(Serialize)]
+struct HistoryExport {
+    path: PathBuf,
+    rows: usize,
+}
+
+fn export_history(access: &DatabaseAccess, path: &Path) -> AppResult<HistoryExport> {
+    let csv = access.read(|conn| export::history_csv(conn, &AtomicBool::new(false)))?;
+    std::fs::write(path, &csv)
+        .map_err(|e| AppError::Internal(format!("Failed to write CSV: {}", e)))?;
+    Ok(HistoryExport {
+        path: path.to_path_buf(),
+        rows: csv.lines().count().saturating_sub(1),
+    })
+}
+
+fn to_json<T: Serialize>(value: &T) -> AppResult<String> {
+    serde_json::to_string_pretty(value).map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Run a parsed command and return what to print
+pub fn execute(cli: &Cli) -> AppResult<String> {
+    if let CliCommand::Help = cli.command {
+        return Ok(USAGE.to_string());
+    }
+
+    let db_path = cli.db_path.clone().unwrap_or_else(default_db_path);
+    if let Some(dir) = db_path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| AppError::Internal(format!("Failed to create data directory: {}", e)))?;
+    }
+    database::init(&db_path)?;
+    let access = DatabaseAccess::new(db_path);
+
+    match &cli.command {
+        CliCommand::Score(request) => {
+            let result = score(&access, request)?;
+            if cli.json {
+                return to_json(&result);
+            }
+            let mut out = format!("{}: {} ({})", request.card_id, result.score, result.tier);
+            if let Some(percentile) = result.percentile {
+                out.push_str(&format!(", percentile {:.0}", percentile));
+            }
+            for reason in &result.reasons {
+                out.push_str(&format!("\n  - {}", reason));
+            }
+            Ok(out)
+        }
+        CliCommand::Detect { image, regions } => {
+            let cards = detect(&access, image, regions)?;
+            if cli.json {
+                return to_json(&cards);
+            }
+            if cards.is_empty() {
+                return Ok("No cards detected".to_string());
+            }
+            Ok(cards
+                .iter()
+                .map(|card| {
+                    format!(
+                        "{} ({}) {:.0}% at {},{} {}x{}",
+                        card.card_name,
+                        card.card_id,
+                        card.confidence * 100.0,
+                        card.region.x,
+                        card.region.y,
+                        card.region.width,
+                        card.region.height
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        CliCommand::ExportHistory { path } => {
+            let export = export_history(&access, path)?;
+            if cli.json {
+                return to_json(&export);
+            }
+            Ok(format!(
+                "Exported {} rows to {}",
+                export.rows,
+                export.path.display()
+            ))
+        }
+        CliCommand::Help => unreachable!(),
+    }
+}
+
+/// Entry point of the `mt2` binary
+pub fn main() -> ExitCode {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let cli = match parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match execute(&cli) {
+        Ok(out) => {
+            println!("{}", out);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            if cli.json {
+                eprintln!(
+                    "{}",
+                    serde_json::to_string(&e).unwrap_or_else(|_| e.to_string())
+                );
+            } else {
+                eprintln!("Error: {}", e);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let cli = parse(args(
+            "--json score --card banished_just_cause --deck a,b --deck c \
+             --champion Fel --ring 3 --db test.db",
+        ))
+        .unwrap();
+        assert!(cli.json);
+        assert_eq!(cli.db_path, Some(PathBuf::from("test.db")));
+        let CliCommand::Score(request) = cli.command else {
+            panic!("expected score");
+        };
+        assert_eq!(request.card_id, "banished_just_cause");
+        assert_eq!(request.current_deck, ["a", "b", "c"]);
+        assert_eq!((request.ring_number, request.covenant), (3, 1));
+
+        let cli = parse(args("detect --image shot.png --region 10,20,300,60")).unwrap();
+        let CliCommand::Detect { regions, .. } = cli.command else {
+            panic!("expected detect");
+        };
+        assert_eq!(regions, [CaptureRegion::new(10, 20, 300, 60)]);
+
+        assert!(matches!(parse(args("")).unwrap().command, CliCommand::Help));
+        assert!(parse(args("score --champion Fel")).is_err());
+        assert!(parse(args("score --card x --champion y --ring two")).is_err());
+        assert!(parse(args("detect --image a.png --region 1,2,3")).is_err());
+        assert!(parse(args("detect --image a.png --colour red")).is_err());
+        assert!(parse(args("export-history")).is_err());
+        assert!(parse(args("draft")).is_err());
+    }
+
+    #[test]
+    fn test_execute_score_and_export() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("mt2_draft.db");
+        let run = |line: String| {
+            let mut cli = parse(args(&line)).unwrap();
+            cli.db_path = Some(db.clone());
+            execute(&cli)
+        };
+
+        let out =
+            run("--json score --card banished_just_cause --champion Fel".to_string()).unwrap();
+        let result: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(result["champion_bonus"], 20);
+
+        let out = run("score --card banished_just_cause --champion Fel".to_string()).unwrap();
+        assert!(out.starts_with("banished_just_cause: "));
+
+        let err = run("score --card no_such_card --champion Fel".to_string()).unwrap_err();
+        assert_eq!(err.code(), crate::error::ErrorCode::NotFound);
+
+        let csv = dir.path().join("history.csv");
+        let out = run(format!("export-history {}", csv.display())).unwrap();
+        assert_eq!(out, format!("Exported 0 rows to {}", csv.display()));
+        assert!(std::fs::read_to_string(&csv)
+            .unwrap()
+            .starts_with(export::HISTORY_CSV_HEADER));
+    }
+}
@@ -1,6 +1,11 @@
-use crate::database::{repository::CardData, DatabaseState};
-use rusqlite::{Connection, Result as SqliteResult};
+use crate::database::access::DatabaseAccess;
+use crate::database::annotations;
+use crate::database::cache::{CardCache, CardSnapshot};
+use crate::database::repository::{self, CardData};
+use crate::error::{AppError, AppResult};
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use tauri::State;
 
 /// Log helper for card commands
@@ -16,12 +21,24 @@ pub struct CardResponse {
     pub card_type: String,
     pub rarity: String,
     pub cost: Option<i32>,
+    /// Floor space the unit takes; `None` for cards that aren't units
+    pub size: Option<i32>,
     pub base_value: i32,
     pub tempo_score: i32,
     pub value_score: i32,
     pub keywords: Vec<String>,
     pub description: String,
     pub expansion: String,
+    /// Name in the requested locale, if translated
+    pub localized_name: Option<String>,
+    /// Description in the requested locale, if translated
+    pub localized_description: Option<String>,
+    /// The player's note on this card
+    pub note: Option<String>,
+    /// The player's tags on this card
+    pub tags: Vec<String>,
+    /// Whether the player marked this card as a favorite
+    pub is_favorite: bool,
 }
 
 impl From<CardData> for CardResponse {
@@ -33,16 +50,80 @@ impl From<CardData> for CardResponse {
             card_type: card.card_type,
             rarity: card.rarity,
             cost: card.cost,
+            size: card.size,
             base_value: card.base_value,
             tempo_score: card.tempo_score,
             value_score: card.value_score,
             keywords: card.keywords,
             description: card.description,
             expansion: card.expansion,
+            localized_name: None,
+            localized_description: None,
+            note: None,
+            tags: Vec::new(),
+            is_favorite: false,
         }
     }
 }
 
+/// The player's note and tags on a card
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CardAnnotations {
+    pub card_id: String,
+    pub note: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// A tag and the number of cards carrying it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TagCount {
+    pub tag: String,
+    pub cards: u32,
+}
+
+/// Longest note accepted, in characters
+const MAX_NOTE_LENGTH: usize = 2000;
+
+/// Longest tag accepted, in characters
+const MAX_TAG_LENGTH: usize = 40;
+
+/// Most IDs accepted by one `get_cards_by_ids` call
+const MAX_BATCH_IDS: usize = 500;
+
+/// Most cards returned by one search
+const MAX_SEARCH_RESULTS: usize = 50;
+
+/// Convert cards to responses with the player's notes, tags and favorites,
+/// filling in translations for `locale`
+fn to_responses(
+    conn: &Connection,
+    cards: Vec<CardData>,
+    locale: Option<&str>,
+) -> Result<Vec<CardResponse>, CardError> {
+    let translations = match locale {
+        Some(locale) => repository::load_card_translations(conn, locale)?,
+        None => Vec::new(),
+    };
+    let mut annotations = annotations::load_all(conn)?;
+
+    Ok(cards
+        .into_iter()
+        .map(|card| {
+            let mut response = CardResponse::from(card);
+            if let Some(t) = translations.iter().find(|t| t.card_id == response.id) {
+                response.localized_name = Some(t.name.clone());
+                response.localized_description = t.description.clone();
+            }
+            if let Some(annotation) = annotations.remove(&response.id) {
+                response.note = annotation.note;
+                response.tags = annotation.tags;
+                response.is_favorite = annotation.favorite;
+            }
+            response
+        })
+        .collect())
+}
+
 /// Custom error type for card-related operations
 #[derive(Debug)]
 pub enum CardError {
@@ -67,211 +148,365 @@ impl From<rusqlite::Error> for CardError {
     }
 }
 
-/// Maps a database row to a CardData struct
-fn row_to_card_data(row: &rusqlite::Row) -> SqliteResult<CardData> {
-    let keywords_json: String = row.get(9)?;
-    let keywords: Vec<String> = serde_json::from_str(&keywords_json).unwrap_or_default();
-
-    Ok(CardData {
-        id: row.get(0)?,
-        name: row.get(1)?,
-        clan: row.get(2)?,
-        card_type: row.get(3)?,
-        rarity: row.get(4)?,
-        cost: row.get(5)?,
-        base_value: row.get(6)?,
-        tempo_score: row.get(7)?,
-        value_score: row.get(8)?,
-        keywords,
-        description: row.get(10)?,
-        expansion: row.get(11)?,
-    })
-}
-
-/// Query to select all card columns
-const SELECT_CARD_SQL: &str = r#"
-    SELECT 
-        id, name, clan, card_type, rarity, cost,
-        base_value, tempo_score, value_score, keywords,
-        description, expansion
-    FROM cards
-"#;
-
 /// Get a single card by exact name match
+///
+/// With a `locale` (e.g. `de`), the card's translated name also matches and
+/// the response includes its localized name.
 #[tauri::command]
 pub fn get_card_by_name(
     name: String,
-    state: State<DatabaseState>,
-) -> Result<Option<CardResponse>, String> {
+    locale: Option<String>,
+    access: State<DatabaseAccess>,
+) -> AppResult<Option<CardResponse>> {
     if name.trim().is_empty() {
-        return Err("Card name cannot be empty".to_string());
+        return Err(AppError::InvalidInput(
+            "Card name cannot be empty".to_string(),
+        ));
     }
 
-    let conn = Connection::open(&state.db_path).map_err(|e| e.to_string())?;
+    access.read(|conn| {
+        let card = repository::get_card_by_name(conn, &name, locale.as_deref())?;
+
+        to_responses(conn, card.into_iter().collect(), locale.as_deref())
+            .map(|cards| cards.into_iter().next())
+            .map_err(AppError::from)
+    })
+}
+
+/// Get a single card by ID
+#[tauri::command]
+pub fn get_card_by_id(
+    id: String,
+    locale: Option<String>,
+    access: State<DatabaseAccess>,
+    cache: State<CardCache>,
+) -> AppResult<Option<CardResponse>> {
+    if id.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Card ID cannot be empty".to_string(),
+        ));
+    }
 
-    let mut stmt = conn
-        .prepare(&format!("{} WHERE name = ?1", SELECT_CARD_SQL))
-        .map_err(|e| e.to_string())?;
+    access.read(|conn| {
+        let snapshot = cache.get(conn)?;
 
-    let card_result = stmt
-        .query_row([&name], row_to_card_data)
-        .map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => CardError::CardNotFound(name.clone()),
-            _ => CardError::DatabaseError(e.to_string()),
-        });
+        let card = snapshot.get(&id).cloned();
 
-    match card_result {
-        Ok(card) => Ok(Some(card.into())),
-        Err(CardError::CardNotFound(_)) => Ok(None),
-        Err(e) => Err(e.to_string()),
+        to_responses(conn, card.into_iter().collect(), locale.as_deref())
+            .map(|cards| cards.into_iter().next())
+            .map_err(AppError::from)
+    })
+}
+
+/// Get several cards by ID, in the order requested
+///
+/// Unknown IDs are skipped, so the result may be shorter than `ids`.
+#[tauri::command]
+pub fn get_cards_by_ids(
+    ids: Vec<String>,
+    locale: Option<String>,
+    access: State<DatabaseAccess>,
+    cache: State<CardCache>,
+) -> AppResult<Vec<CardResponse>> {
+    if ids.len() > MAX_BATCH_IDS {
+        return Err(AppError::InvalidInput(format!(
+            "Cannot fetch more than {} cards at once",
+            MAX_BATCH_IDS
+        )));
     }
+
+    access.read(|conn| {
+        let snapshot = cache.get(conn)?;
+
+        to_responses(conn, snapshot.get_many(&ids), locale.as_deref()).map_err(AppError::from)
+    })
 }
 
-/// Get all cards for a specific clan
+/// Get all cards for a specific clan from active expansions
 #[tauri::command]
 pub fn get_cards_by_clan(
     clan: String,
-    state: State<DatabaseState>,
-) -> Result<Vec<CardResponse>, String> {
+    access: State<DatabaseAccess>,
+    cache: State<CardCache>,
+) -> AppResult<Vec<CardResponse>> {
     if clan.trim().is_empty() {
-        return Err("Clan name cannot be empty".to_string());
+        return Err(AppError::InvalidInput(
+            "Clan name cannot be empty".to_string(),
+        ));
     }
 
-    let conn = Connection::open(&state.db_path).map_err(|e| e.to_string())?;
+    access.read(|conn| {
+        let snapshot = cache.get(conn)?;
 
-    let mut stmt = conn
-        .prepare(&format!("{} WHERE clan = ?1 ORDER BY name", SELECT_CARD_SQL))
-        .map_err(|e| e.to_string())?;
-
-    let cards: Result<Vec<CardData>, _> = stmt
-        .query_map([&clan], row_to_card_data)
-        .map_err(|e| e.to_string())?
-        .collect();
-
-    cards
-        .map(|cards| cards.into_iter().map(Into::into).collect())
-        .map_err(|e| format!("Failed to fetch cards: {}", e))
+        to_responses(conn, get_cards_by_clan_direct(&snapshot, &clan), None)
+            .map_err(AppError::from)
+    })
 }
 
 /// Search cards by partial name match (case-insensitive)
+///
+/// With a `locale`, translated names are searched too. Cards from inactive
+/// expansions are left out.
 #[tauri::command]
 pub fn search_cards(
     query: String,
-    state: State<DatabaseState>,
-) -> Result<Vec<CardResponse>, String> {
+    locale: Option<String>,
+    access: State<DatabaseAccess>,
+    cache: State<CardCache>,
+) -> AppResult<Vec<CardResponse>> {
     if query.trim().is_empty() {
         return Ok(vec![]);
     }
 
-    let conn = Connection::open(&state.db_path).map_err(|e| e.to_string())?;
+    access.read(|conn| {
+        let snapshot = cache.get(conn)?;
 
-    // Use LIKE for case-insensitive partial matching
-    let search_pattern = format!("%{}%", query.trim());
+        let cards = search_cards_direct(&snapshot, &query, locale.as_deref());
+        to_responses(conn, cards, locale.as_deref()).map_err(AppError::from)
+    })
+}
 
-    let mut stmt = conn
-        .prepare(&format!(
-            "{} WHERE name LIKE ?1 ORDER BY name LIMIT 50",
-            SELECT_CARD_SQL
-        ))
-        .map_err(|e| e.to_string())?;
+/// Get all cards from active expansions with a keyword (case-insensitive)
+#[tauri::command]
+pub fn get_cards_by_keyword(
+    keyword: String,
+    access: State<DatabaseAccess>,
+) -> AppResult<Vec<CardResponse>> {
+    if keyword.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Keyword cannot be empty".to_string(),
+        ));
+    }
 
-    let cards: Result<Vec<CardData>, _> = stmt
-        .query_map([&search_pattern], row_to_card_data)
-        .map_err(|e| e.to_string())?
-        .collect();
+    log_command("get_cards_by_keyword", &keyword);
+    access.read(|conn| {
+        let cards = repository::get_cards_by_keyword(conn, keyword.trim())?;
+        to_responses(conn, cards, None).map_err(AppError::from)
+    })
+}
 
-    cards
-        .map(|cards| cards.into_iter().map(Into::into).collect())
-        .map_err(|e| format!("Failed to search cards: {}", e))
+/// Get all cards the player tagged with `tag` (case-insensitive)
+#[tauri::command]
+pub fn get_cards_by_tag(
+    tag: String,
+    access: State<DatabaseAccess>,
+) -> AppResult<Vec<CardResponse>> {
+    if tag.trim().is_empty() {
+        return Err(AppError::InvalidInput("Tag cannot be empty".to_string()));
+    }
+
+    access.read(|conn| {
+        let cards = repository::get_cards_by_tag(conn, tag.trim())?;
+        to_responses(conn, cards, None).map_err(AppError::from)
+    })
+}
+
+/// List every tag in use, most used first
+#[tauri::command]
+pub fn list_card_tags(access: State<DatabaseAccess>) -> AppResult<Vec<TagCount>> {
+    access
+        .read(annotations::list_tags)
+        .map(|tags| {
+            tags.into_iter()
+                .map(|(tag, cards)| TagCount { tag, cards })
+                .collect()
+        })
+        .map_err(AppError::from)
 }
 
-/// Get all cards from the database
+/// Set the player's note on a card; an empty note deletes it
 #[tauri::command]
-pub fn get_all_cards(state: State<DatabaseState>) -> Result<Vec<CardResponse>, String> {
-    let conn = Connection::open(&state.db_path).map_err(|e| e.to_string())?;
+pub fn set_card_note(
+    card_id: String,
+    note: String,
+    access: State<DatabaseAccess>,
+) -> AppResult<CardAnnotations> {
+    let note = note.trim().to_string();
+    if note.chars().count() > MAX_NOTE_LENGTH {
+        return Err(AppError::InvalidInput(format!(
+            "Note cannot exceed {} characters",
+            MAX_NOTE_LENGTH
+        )));
+    }
 
-    let mut stmt = conn
-        .prepare(&format!("{} ORDER BY clan, name", SELECT_CARD_SQL))
-        .map_err(|e| e.to_string())?;
+    access.write_and_wait(move |conn| {
+        ensure_card_exists(conn, &card_id)?;
 
-    let cards: Result<Vec<CardData>, _> = stmt
-        .query_map([], row_to_card_data)
-        .map_err(|e| e.to_string())?
-        .collect();
+        if note.is_empty() {
+            annotations::delete_note(conn, &card_id)?;
+        } else {
+            annotations::set_note(conn, &card_id, &note)?;
+        }
 
-    cards
-        .map(|cards| cards.into_iter().map(Into::into).collect())
-        .map_err(|e| format!("Failed to fetch all cards: {}", e))
+        get_annotations(conn, &card_id).map_err(AppError::from)
+    })
 }
 
-/// Helper function to get a card by name directly from a connection (for testing)
-fn get_card_by_name_direct(conn: &Connection, name: &str) -> Result<Option<CardData>, CardError> {
-    let mut stmt = conn
-        .prepare(&format!("{} WHERE name = ?1", SELECT_CARD_SQL))?;
+/// Delete the player's note on a card
+#[tauri::command]
+pub fn delete_card_note(
+    card_id: String,
+    access: State<DatabaseAccess>,
+) -> AppResult<CardAnnotations> {
+    access.write_and_wait(move |conn| {
+        ensure_card_exists(conn, &card_id)?;
 
-    let card_result = stmt
-        .query_row([name], row_to_card_data)
-        .map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => CardError::CardNotFound(name.to_string()),
-            _ => CardError::DatabaseError(e.to_string()),
-        });
+        annotations::delete_note(conn, &card_id)?;
 
-    match card_result {
-        Ok(card) => Ok(Some(card)),
-        Err(CardError::CardNotFound(_)) => Ok(None),
-        Err(e) => Err(e),
+        get_annotations(conn, &card_id).map_err(AppError::from)
+    })
+}
+
+/// Add a tag to a card; tagging a card twice is a no-op
+#[tauri::command]
+pub fn add_card_tag(
+    card_id: String,
+    tag: String,
+    access: State<DatabaseAccess>,
+) -> AppResult<CardAnnotations> {
+    let tag = tag.trim().to_string();
+    if tag.is_empty() {
+        return Err(AppError::InvalidInput("Tag cannot be empty".to_string()));
+    }
+    if tag.chars().count() > MAX_TAG_LENGTH {
+        return Err(AppError::InvalidInput(format!(
+            "Tag cannot exceed {} characters",
+            MAX_TAG_LENGTH
+        )));
     }
+
+    access.write_and_wait(move |conn| {
+        ensure_card_exists(conn, &card_id)?;
+
+        annotations::add_tag(conn, &card_id, &tag)?;
+
+        get_annotations(conn, &card_id).map_err(AppError::from)
+    })
 }
 
-/// Helper function to get cards by clan directly from a connection (for testing)
-fn get_cards_by_clan_direct(conn: &Connection, clan: &str) -> Result<Vec<CardData>, CardError> {
-    let mut stmt = conn
-        .prepare(&format!("{} WHERE clan = ?1 ORDER BY name", SELECT_CARD_SQL))?;
+/// Remove a tag from a card
+#[tauri::command]
+pub fn remove_card_tag(
+    card_id: String,
+    tag: String,
+    access: State<DatabaseAccess>,
+) -> AppResult<CardAnnotations> {
+    access.write_and_wait(move |conn| {
+        ensure_card_exists(conn, &card_id)?;
 
-    let cards: Result<Vec<CardData>, _> = stmt
-        .query_map([clan], row_to_card_data)?
-        .collect();
+        annotations::remove_tag(conn, &card_id, tag.trim())?;
 
-    cards.map_err(|e| CardError::DatabaseError(e.to_string()))
+        get_annotations(conn, &card_id).map_err(AppError::from)
+    })
 }
 
-/// Helper function to search cards directly from a connection (for testing)
-fn search_cards_direct(conn: &Connection, query: &str) -> Result<Vec<CardData>, CardError> {
-    if query.trim().is_empty() {
-        return Ok(vec![]);
-    }
+/// Get the player's favorite cards
+#[tauri::command]
+pub fn get_favorites(access: State<DatabaseAccess>) -> AppResult<Vec<CardResponse>> {
+    access.read(|conn| {
+        let cards = repository::get_favorite_cards(conn)?;
+        to_responses(conn, cards, None).map_err(AppError::from)
+    })
+}
+
+/// Add a card to the favorites, or remove it if already there
+///
+/// Returns whether the card is now a favorite.
+#[tauri::command]
+pub fn toggle_favorite(card_id: String, access: State<DatabaseAccess>) -> AppResult<bool> {
+    access.write_and_wait(move |conn| {
+        ensure_card_exists(conn, &card_id)?;
 
-    let search_pattern = format!("%{}%", query.trim());
+        annotations::toggle_favorite(conn, &card_id).map_err(AppError::from)
+    })
+}
+
+/// Get all cards from active expansions
+///
+/// Runs on a blocking thread, off the IPC thread.
+#[tauri::command]
+pub async fn get_all_cards(
+    access: State<'_, DatabaseAccess>,
+    cache: State<'_, CardCache>,
+) -> AppResult<Vec<CardResponse>> {
+    let access = access.inner().clone();
+    let cache = cache.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        access.read(|conn| {
+            let snapshot = cache.get(conn)?;
+
+            to_responses(conn, get_all_cards_direct(&snapshot), None).map_err(AppError::from)
+        })
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Helper function to get active cards of a clan from a snapshot
+fn get_cards_by_clan_direct(snapshot: &CardSnapshot, clan: &str) -> Vec<CardData> {
+    snapshot
+        .active_cards()
+        .filter(|c| c.clan == clan)
+        .cloned()
+        .collect()
+}
 
-    let mut stmt = conn
-        .prepare(&format!(
-            "{} WHERE name LIKE ?1 ORDER BY name LIMIT 50",
-            SELECT_CARD_SQL
-        ))?;
+/// Helper function to search active cards in a snapshot, ordered by name
+fn search_cards_direct(
+    snapshot: &CardSnapshot,
+    query: &str,
+    locale: Option<&str>,
+) -> Vec<CardData> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return vec![];
+    }
 
-    let cards: Result<Vec<CardData>, _> = stmt
-        .query_map([&search_pattern], row_to_card_data)?
+    let translated: HashSet<String> = locale
+        .map(|locale| snapshot.translated_names(locale))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, name)| name.to_lowercase().contains(&query))
+        .map(|(card_id, _)| card_id)
         .collect();
 
-    cards.map_err(|e| CardError::DatabaseError(e.to_string()))
+    let mut cards: Vec<CardData> = snapshot
+        .active_cards()
+        .filter(|c| c.name.to_lowercase().contains(&query) || translated.contains(&c.id))
+        .cloned()
+        .collect();
+    cards.sort_by(|a, b| a.name.cmp(&b.name));
+    cards.truncate(MAX_SEARCH_RESULTS);
+    cards
 }
 
-/// Helper function to get all cards directly from a connection (for testing)
-fn get_all_cards_direct(conn: &Connection) -> Result<Vec<CardData>, CardError> {
-    let mut stmt = conn
-        .prepare(&format!("{} ORDER BY clan, name", SELECT_CARD_SQL))?;
+/// Fail with `CardNotFound` unless a card with this ID exists
+fn ensure_card_exists(conn: &Connection, card_id: &str) -> Result<(), CardError> {
+    if repository::card_exists(conn, card_id)? {
+        Ok(())
+    } else {
+        Err(CardError::CardNotFound(card_id.to_string()))
+    }
+}
 
-    let cards: Result<Vec<CardData>, _> = stmt
-        .query_map([], row_to_card_data)?
-        .collect();
+/// The current note and tags on a card
+fn get_annotations(conn: &Connection, card_id: &str) -> Result<CardAnnotations, CardError> {
+    Ok(CardAnnotations {
+        card_id: card_id.to_string(),
+        note: annotations::get_note(conn, card_id)?,
+        tags: annotations::get_tags(conn, card_id)?,
+    })
+}
 
-    cards.map_err(|e| CardError::DatabaseError(e.to_string()))
+/// Helper function to get all active cards from a snapshot, ordered by clan, then name
+fn get_all_cards_direct(snapshot: &CardSnapshot) -> Vec<CardData> {
+    snapshot.active_cards().cloned().collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::database;
+    use crate::database::{self, DatabaseState};
     use tempfile::NamedTempFile;
 
     fn setup_test_db() -> (DatabaseState, NamedTempFile) {
@@ -290,7 +525,7 @@ mod tests {
         let conn = Connection::open(&state.db_path).unwrap();
 
         // Test with a known seeded card
-        let result = get_card_by_name_direct(&conn, "Fel");
+        let result = repository::get_card_by_name(&conn, "Fel", None);
         assert!(result.is_ok());
 
         let card = result.unwrap();
@@ -303,7 +538,7 @@ mod tests {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
 
-        let result = get_card_by_name_direct(&conn, "NonExistentCard");
+        let result = repository::get_card_by_name(&conn, "NonExistentCard", None);
         assert!(result.is_ok());
         assert!(result.unwrap().is_none());
     }
@@ -315,7 +550,7 @@ mod tests {
 
         // Direct helper returns empty result for empty string (not an error)
         // The command wrapper handles the validation
-        let result = get_card_by_name_direct(&conn, "");
+        let result = repository::get_card_by_name(&conn, "", None);
         assert!(result.is_ok());
         // Empty string won't match any card name
         assert!(result.unwrap().is_none());
@@ -325,11 +560,9 @@ mod tests {
     fn test_get_cards_by_clan() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
 
-        let result = get_cards_by_clan_direct(&conn, "Banished");
-        assert!(result.is_ok());
-
-        let cards = result.unwrap();
+        let cards = get_cards_by_clan_direct(&snapshot, "Banished");
         assert!(!cards.is_empty());
         assert!(cards.iter().all(|c| c.clan == "Banished"));
     }
@@ -338,23 +571,20 @@ mod tests {
     fn test_get_cards_by_clan_empty() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
 
         // Direct helper returns empty vec for empty clan
-        let result = get_cards_by_clan_direct(&conn, "");
-        assert!(result.is_ok());
-        assert!(result.unwrap().is_empty());
+        assert!(get_cards_by_clan_direct(&snapshot, "").is_empty());
     }
 
     #[test]
     fn test_search_cards() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
 
         // Search for "Fel" should find "Fel" and potentially others
-        let result = search_cards_direct(&conn, "Fel");
-        assert!(result.is_ok());
-
-        let cards = result.unwrap();
+        let cards = search_cards_direct(&snapshot, "Fel", None);
         assert!(!cards.is_empty());
         assert!(cards.iter().any(|c| c.name.contains("Fel")));
     }
@@ -363,22 +593,19 @@ mod tests {
     fn test_search_cards_empty_query() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
 
-        let result = search_cards_direct(&conn, "");
-        assert!(result.is_ok());
-        assert!(result.unwrap().is_empty());
+        assert!(search_cards_direct(&snapshot, "", None).is_empty());
     }
 
     #[test]
     fn test_search_cards_case_insensitive() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
 
         // Search with lowercase
-        let result = search_cards_direct(&conn, "fel");
-        assert!(result.is_ok());
-
-        let cards = result.unwrap();
+        let cards = search_cards_direct(&snapshot, "fel", None);
         assert!(!cards.is_empty());
         assert!(cards.iter().any(|c| c.name.to_lowercase().contains("fel")));
     }
@@ -387,12 +614,10 @@ mod tests {
     fn test_search_cards_partial_match() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
 
         // Search for partial match
-        let result = search_cards_direct(&conn, "ust");
-        assert!(result.is_ok());
-
-        let cards = result.unwrap();
+        let cards = search_cards_direct(&snapshot, "ust", None);
         // Should find "Just Cause" (contains "ust")
         assert!(cards.iter().any(|c| c.name.contains("Just")));
     }
@@ -401,11 +626,9 @@ mod tests {
     fn test_get_all_cards() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
 
-        let result = get_all_cards_direct(&conn);
-        assert!(result.is_ok());
-
-        let cards = result.unwrap();
+        let cards = get_all_cards_direct(&snapshot);
         assert!(!cards.is_empty());
 
         // Verify ordering (by clan, then name)
@@ -424,4 +647,137 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_keywords_read_from_join_table() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+
+        let fel = repository::get_card_by_name(&conn, "Fel", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(fel.keywords, vec!["multistrike", "valor", "revenge"]);
+
+        let cards = repository::get_cards_by_keyword(&conn, "VALOR").unwrap();
+        assert!(cards.iter().any(|c| c.id == "banished_fel"));
+        assert!(cards
+            .iter()
+            .all(|c| c.keywords.iter().any(|k| k == "valor")));
+
+        assert!(repository::get_cards_by_keyword(&conn, "no_such_keyword")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_keyword_migration_backfills_existing_cards() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+        conn.execute_batch(
+            "DELETE FROM card_keywords;
+             DELETE FROM schema_migrations WHERE version >= 10;",
+        )
+        .unwrap();
+
+        database::migrations::run_all(&conn).unwrap();
+
+        let fel = repository::get_card_by_name(&conn, "Fel", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(fel.keywords, vec!["multistrike", "valor", "revenge"]);
+    }
+
+    #[test]
+    fn test_localized_lookup_and_search() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+
+        assert!(repository::get_card_by_name(&conn, "Pestdoktor", None)
+            .unwrap()
+            .is_none());
+        let card = repository::get_card_by_name(&conn, "Pestdoktor", Some("de-DE"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(card.id, "lazarus_league_plague_doctor");
+
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+        let cards = search_cards_direct(&snapshot, "seigneur", Some("fr"));
+        assert_eq!(cards.len(), 1);
+        let responses = to_responses(&conn, cards, Some("fr")).unwrap();
+        assert_eq!(responses[0].name, "Lord Fenix");
+        assert_eq!(
+            responses[0].localized_name.as_deref(),
+            Some("Seigneur Fenix")
+        );
+
+        // English names still match with a locale set
+        let cards = search_cards_direct(&snapshot, "Fenix", Some("de"));
+        assert!(cards.iter().any(|c| c.id == "pyreborne_lord_fenix"));
+    }
+
+    #[test]
+    fn test_notes_and_tags_in_responses() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+
+        assert!(matches!(
+            ensure_card_exists(&conn, "no_such_card"),
+            Err(CardError::CardNotFound(_))
+        ));
+        annotations::set_note(&conn, "banished_fel", "great vs Seraph").unwrap();
+        annotations::add_tag(&conn, "banished_fel", "Seraph").unwrap();
+        annotations::add_tag(&conn, "banished_cleave", "seraph").unwrap();
+
+        let cards = repository::get_cards_by_tag(&conn, "SERAPH").unwrap();
+        let responses = to_responses(&conn, cards, None).unwrap();
+        let ids: Vec<_> = responses.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["banished_cleave", "banished_fel"]);
+
+        let fel = &responses[1];
+        assert_eq!(fel.note.as_deref(), Some("great vs Seraph"));
+        assert_eq!(fel.tags, vec!["Seraph"]);
+        assert!(responses[0].note.is_none());
+
+        let annotations = get_annotations(&conn, "banished_fel").unwrap();
+        assert_eq!(annotations.tags, vec!["Seraph"]);
+    }
+
+    #[test]
+    fn test_inactive_expansions_are_hidden() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+
+        database::expansions::set_active(&conn, "railforged", false).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+
+        let all = get_all_cards_direct(&snapshot);
+        assert!(!all.is_empty());
+        assert!(all.iter().all(|c| c.expansion != "railforged"));
+        assert!(get_cards_by_clan_direct(&snapshot, "Railforged").is_empty());
+        assert!(search_cards_direct(&snapshot, "Full Throttle", None).is_empty());
+
+        // Direct lookups still resolve, so older history keeps its cards
+        assert!(snapshot.get("railforged_full_throttle").is_some());
+        assert!(repository::get_card_by_name(&conn, "Full Throttle", None)
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_favorites_in_responses() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+
+        annotations::toggle_favorite(&conn, "banished_talos").unwrap();
+
+        let favorites = repository::get_favorite_cards(&conn).unwrap();
+        let favorites = to_responses(&conn, favorites, None).unwrap();
+        assert_eq!(favorites.len(), 1);
+        assert_eq!(favorites[0].id, "banished_talos");
+        assert!(favorites[0].is_favorite);
+
+        let fel = repository::get_card_by_name(&conn, "Fel", None).unwrap();
+        let responses = to_responses(&conn, fel.into_iter().collect(), None).unwrap();
+        assert!(!responses[0].is_favorite);
+    }
 }
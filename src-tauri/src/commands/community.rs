@@ -0,0 +1,396 @@
+//! Community stats commands
+//!
+//! A solo player's own history takes many runs before a card's win rate
+//! means anything. Community sites publish aggregate stats over far more
+//! games; importing a file of them fills `community_stats`, and once the
+//! player opts in, scoring blends each card's community win rate in at the
+//! weight set in `ScoringPreferences`.
+
+use crate::commands::export::{split_csv_line, ImportIssue};
+use crate::database::access::DatabaseAccess;
+use crate::database::audit::{self, AuditAction};
+use crate::error::{AppError, AppResult};
+use crate::settings::{self, ScoringPreferences};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Columns expected in a community stats CSV
+pub(crate) const COMMUNITY_CSV_HEADER: &str = "card_id,games,wins,pick_rate";
+
+/// Aggregate stats for one card
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CommunityCardStats {
+    pub card_id: String,
+    pub games: u32,
+    pub wins: u32,
+    /// Share of offers the card was picked from (0.0-1.0), if published
+    #[serde(default)]
+    pub pick_rate: Option<f64>,
+}
+
+/// Layout of a community stats JSON file
+#[derive(Deserialize, Debug)]
+struct CommunityStatsFile {
+    /// Who published the stats; the file name is used when missing
+    #[serde(default)]
+    source: Option<String>,
+    cards: Vec<CommunityCardStats>,
+}
+
+/// Outcome of `import_community_stats`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CommunityImportReport {
+    pub dry_run: bool,
+    pub source: String,
+    /// Cards in the file
+    pub total_rows: u32,
+    /// Cards written, replacing every earlier import; nothing is written on
+    /// a dry run or if any row is invalid
+    pub imported: u32,
+    /// Problems by CSV line, or by 1-based entry in a JSON file's `cards`
+    pub errors: Vec<ImportIssue>,
+}
+
+fn parse_csv_row(line: &str) -> Result<CommunityCardStats, String> {
+    let fields = split_csv_line(line);
+    if fields.len() != 4 {
+        return Err(format!("Expected 4 columns, found {}", fields.len()));
+    }
+    let field = |i: usize| fields[i].trim();
+    let count = |i: usize, name: &str| {
+        field(i)
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid {}: '{}'", name, field(i)))
+    };
+    let pick_rate = match field(3) {
+        "" => None,
+        value => Some(
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid pick_rate: '{}'", value))?,
+        ),
+    };
+
+    Ok(CommunityCardStats {
+        card_id: field(0).to_string(),
+        games: count(1, "games")?,
+        wins: count(2, "wins")?,
+        pick_rate,
+    })
+}
+
+/// Rows of a parsed file, by CSV line or JSON entry number
+type ParsedRows = Vec<(u32, Result<CommunityCardStats, String>)>;
+
+/// Parse a community stats file and the source it names, if any
+///
+/// Rows that can't be parsed are kept as errors; a file that can't be
+/// read at all is an error.
+fn parse_stats(contents: &str, json: bool) -> Result<(Option<String>, ParsedRows), String> {
+    if json {
+        let file: CommunityStatsFile = serde_json::from_str(contents)
+            .map_err(|e| format!("Invalid community stats JSON: {}", e))?;
+        let rows = file
+            .cards
+            .into_iter()
+            .enumerate()
+            .map(|(i, stats)| (i as u32 + 1, Ok(stats)))
+            .collect();
+        return Ok((file.source, rows));
+    }
+
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    match lines.next() {
+        Some((_, header)) if header.trim() == COMMUNITY_CSV_HEADER => {}
+        _ => return Err(format!("Expected header '{}'", COMMUNITY_CSV_HEADER)),
+    }
+    let rows = lines
+        .map(|(i, line)| (i as u32 + 1, parse_csv_row(line)))
+        .collect();
+    Ok((None, rows))
+}
+
+fn validate_stats(stats: &CommunityCardStats, known_cards: &HashSet<String>) -> Result<(), String> {
+    if !known_cards.contains(&stats.card_id) {
+        return Err(format!("Unknown card ID: '{}'", stats.card_id));
+    }
+    if stats.games == 0 {
+        return Err("Games must be greater than 0".to_string());
+    }
+    if stats.wins > stats.games {
+        return Err(format!(
+            "Wins ({}) exceed games ({})",
+            stats.wins, stats.games
+        ));
+    }
+    if stats
+        .pick_rate
+        .is_some_and(|rate| !(0.0..=1.0).contains(&rate))
+    {
+        return Err("Pick rate must be between 0.0 and 1.0".to_string());
+    }
+    Ok(())
+}
+
+/// Validate a community stats file and, unless `dry_run`, replace the
+/// stored stats with it
+///
+/// `json` selects the format. The table is only replaced if every row is
+/// valid, so a bad file leaves the previous import in place.
+fn import_stats(
+    conn: &Connection,
+    contents: &str,
+    json: bool,
+    default_source: &str,
+    dry_run: bool,
+) -> AppResult<CommunityImportReport> {
+    let (source, rows) = parse_stats(contents, json).map_err(AppError::InvalidInput)?;
+    let mut report = CommunityImportReport {
+        dry_run,
+        source: source.unwrap_or_else(|| default_source.to_string()),
+        total_rows: rows.len() as u32,
+        ..Default::default()
+    };
+
+    let mut stmt = conn.prepare("SELECT id FROM cards")?;
+    let known_cards = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<HashSet<String>>>()?;
+
+    let mut seen = HashSet::new();
+    let mut valid = Vec::new();
+    for (line, row) in rows {
+        let checked = row.and_then(|stats| {
+            validate_stats(&stats, &known_cards)?;
+            if !seen.insert(stats.card_id.clone()) {
+                return Err(format!("Duplicate card ID: '{}'", stats.card_id));
+            }
+            Ok(stats)
+        });
+        match checked {
+            Ok(stats) => valid.push(stats),
+            Err(message) => report.errors.push(ImportIssue { line, message }),
+        }
+    }
+
+    if dry_run || !report.errors.is_empty() || valid.is_empty() {
+        return Ok(report);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM community_stats", [])?;
+    {
+        let mut insert = tx.prepare(
+            "INSERT INTO community_stats (card_id, games, wins, pick_rate, source)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        for stats in &valid {
+            insert.execute(rusqlite::params![
+                stats.card_id,
+                stats.games,
+                stats.wins,
+                stats.pick_rate,
+                report.source
+            ])?;
+        }
+    }
+    tx.commit()?;
+
+    report.imported = valid.len() as u32;
+    Ok(report)
+}
+
+/// Imported community (win rate, games) of every card with stats
+pub(crate) fn query_community_win_rates(
+    conn: &Connection,
+) -> rusqlite::Result<HashMap<String, (f64, u32)>> {
+    let mut stmt = conn.prepare("SELECT card_id, games, wins FROM community_stats")?;
+    let rows = stmt.query_map([], |row| {
+        let (games, wins): (u32, u32) = (row.get(1)?, row.get(2)?);
+        Ok((row.get(0)?, (wins as f64 / games as f64, games)))
+    })?;
+    rows.collect()
+}
+
+/// Tauri command: Import a community stats file (`.csv` or `.json`)
+///
+/// Replaces any earlier import. With `dry_run`, only validates the file.
+/// Imported stats affect scoring only after `set_community_stats_enabled`.
+#[tauri::command]
+pub fn import_community_stats(
+    path: String,
+    dry_run: Option<bool>,
+    access: State<'_, DatabaseAccess>,
+) -> AppResult<CommunityImportReport> {
+    log::info!("[Import] Importing community stats: {}", path);
+
+    let file = Path::new(&path);
+    let json = match file.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("json") => true,
+        Some(ext) if ext.eq_ignore_ascii_case("csv") => false,
+        _ => {
+            return Err(AppError::InvalidInput(
+                "Community stats must be a .csv or .json file".to_string(),
+            ))
+        }
+    };
+    let contents = std::fs::read_to_string(file)
+        .map_err(|e| AppError::InvalidInput(format!("Failed to read file: {}", e)))?;
+    let default_source = file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let report = access.write_and_wait(move |conn| {
+        let report = import_stats(
+            conn,
+            &contents,
+            json,
+            &default_source,
+            dry_run.unwrap_or(false),
+        )?;
+        if report.imported > 0 {
+            audit::record(
+                conn,
+                "community_stats",
+                &report.source,
+                AuditAction::Import,
+                None,
+                Some(serde_json::json!({ "cards": report.imported })),
+            )?;
+        }
+        Ok::<_, AppError>(report)
+    })?;
+
+    log::info!(
+        "[Import] Community stats from {}: {} rows, {} errors, {} written",
+        report.source,
+        report.total_rows,
+        report.errors.len(),
+        report.imported
+    );
+    Ok(report)
+}
+
+/// Tauri command: Turn the community stats blend on or off
+///
+/// `weight` (0.0-1.0) sets how much of the community adjustment is applied;
+/// it keeps its current value when omitted.
+#[tauri::command]
+pub fn set_community_stats_enabled(
+    enabled: bool,
+    weight: Option<f64>,
+    app: AppHandle,
+    access: State<DatabaseAccess>,
+) -> Result<ScoringPreferences, String> {
+    let mut prefs: ScoringPreferences =
+        settings::load_section(&access, settings::SCORING_KEY).map_err(|e| e.to_string())?;
+    prefs.use_community_stats = enabled;
+    if let Some(weight) = weight {
+        prefs.community_weight = weight;
+    }
+    prefs.validate().map_err(|e| e.to_string())?;
+
+    settings::save_section(&access, settings::SCORING_KEY, &prefs).map_err(|e| e.to_string())?;
+    settings::notify_changed(&app, &access);
+    Ok(prefs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::OptionalExtension;
+    use tempfile::NamedTempFile;
+
+    fn setup_test_db() -> (Connection, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        (conn, temp_file)
+    }
+
+    fn query_community_stats(
+        conn: &Connection,
+        card_id: &str,
+    ) -> rusqlite::Result<Option<CommunityCardStats>> {
+        conn.query_row(
+            "SELECT card_id, games, wins, pick_rate FROM community_stats WHERE card_id = ?1",
+            [card_id],
+            |row| {
+                Ok(CommunityCardStats {
+                    card_id: row.get(0)?,
+                    games: row.get(1)?,
+                    wins: row.get(2)?,
+                    pick_rate: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+    }
+
+    #[test]
+    fn test_import_csv() {
+        let (conn, _temp) = setup_test_db();
+        let csv = "card_id,games,wins,pick_rate\n\
+                   banished_fel,1200,660,\n\
+                   banished_cleave,400,180,0.35\n";
+
+        let report = import_stats(&conn, csv, false, "stats.csv", true).unwrap();
+        assert_eq!(report.total_rows, 2);
+        assert_eq!(report.imported, 0);
+        assert!(query_community_stats(&conn, "banished_fel")
+            .unwrap()
+            .is_none());
+
+        let report = import_stats(&conn, csv, false, "stats.csv", false).unwrap();
+        assert_eq!(report.source, "stats.csv");
+        assert_eq!(report.imported, 2);
+        let stats = query_community_stats(&conn, "banished_cleave")
+            .unwrap()
+            .unwrap();
+        assert_eq!((stats.games, stats.wins), (400, 180));
+        assert_eq!(stats.pick_rate, Some(0.35));
+        let win_rates = query_community_win_rates(&conn).unwrap();
+        assert_eq!(win_rates.len(), 2);
+        assert_eq!(win_rates["banished_cleave"], (0.45, 400));
+
+        // A newer file replaces the whole table
+        let json = r#"{ "source": "Community", "cards": [
+            { "card_id": "banished_talos", "games": 90, "wins": 50 }
+        ] }"#;
+        let report = import_stats(&conn, json, true, "stats.json", false).unwrap();
+        assert_eq!(report.source, "Community");
+        assert_eq!(report.imported, 1);
+        assert!(query_community_stats(&conn, "banished_cleave")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_rows() {
+        let (conn, _temp) = setup_test_db();
+        let csv = "card_id,games,wins,pick_rate\n\
+                   banished_fel,100,60,\n\
+                   not_a_card,100,60,\n\
+                   banished_cleave,10,20,\n\
+                   banished_talos,0,0,\n\
+                   banished_fel,50,20,1.5\n";
+
+        let report = import_stats(&conn, csv, false, "stats.csv", false).unwrap();
+        let lines: Vec<u32> = report.errors.iter().map(|issue| issue.line).collect();
+        assert_eq!(lines, [3, 4, 5, 6]);
+        assert_eq!(report.imported, 0);
+        assert!(query_community_stats(&conn, "banished_fel")
+            .unwrap()
+            .is_none());
+
+        assert!(import_stats(&conn, "card,games\n", false, "stats.csv", false).is_err());
+        assert!(import_stats(&conn, "{}", true, "stats.json", false).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! Database maintenance commands
+
+use crate::database::access::DatabaseAccess;
+use crate::database::audit::{self, AuditAction, AuditEntry, AuditFilter};
+use crate::database::cache::CardCache;
+use crate::database::expansions::{self, Expansion};
+use crate::database::integrity::{self, IntegrityIssue, IssueKind};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::State;
+
+/// Result of a database validation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IntegrityReport {
+    /// Issues found before any fixes were applied
+    pub issues: Vec<IntegrityIssue>,
+    /// Number of issues repaired
+    pub fixed: usize,
+}
+
+/// Tauri command: Report orphaned rows and malformed card data
+///
+/// With `auto_fix`, orphaned synergies and overrides are deleted and
+/// malformed keywords reset. Draft history is never modified.
+#[tauri::command]
+pub fn validate_database(
+    auto_fix: Option<bool>,
+    access: State<DatabaseAccess>,
+    cache: State<CardCache>,
+) -> Result<IntegrityReport, String> {
+    let (issues, fixed) = access
+        .write_and_wait(move |conn| {
+            let issues = integrity::check(conn)
+                .map_err(|e| format!("Failed to validate database: {}", e))?;
+            let fixed = if auto_fix.unwrap_or(false) {
+                integrity::fix(conn, &issues)
+                    .map_err(|e| format!("Failed to repair database: {}", e))?
+            } else {
+                0
+            };
+            if fixed > 0 {
+                for issue in issues.iter().filter(|issue| issue.kind.is_fixable()) {
+                    let action = match issue.kind {
+                        IssueKind::MalformedKeywords => AuditAction::Update,
+                        _ => AuditAction::Delete,
+                    };
+                    let before = serde_json::to_value(issue).ok();
+                    audit::record(conn, &issue.table, &issue.row_id, action, before, None)
+                        .map_err(|e| format!("Failed to log repair: {}", e))?;
+                }
+            }
+            Ok::<_, AppError>((issues, fixed))
+        })
+        .map_err(|e| e.to_string())?;
+    if fixed > 0 {
+        cache.invalidate();
+    }
+
+    if !issues.is_empty() {
+        log::info!(
+            "Database validation found {} issue(s), fixed {}",
+            issues.len(),
+            fixed
+        );
+    }
+
+    Ok(IntegrityReport { issues, fixed })
+}
+
+/// Tauri command: List the game expansions and whether each is in play
+#[tauri::command]
+pub fn get_expansions(access: State<DatabaseAccess>) -> Result<Vec<Expansion>, String> {
+    access
+        .read(expansions::list)
+        .map_err(|e| format!("Failed to fetch expansions: {}", e))
+}
+
+/// Tauri command: Enable or disable an expansion's cards
+///
+/// Inactive expansions are left out of card listings and OCR matching.
+#[tauri::command]
+pub fn set_expansion_active(
+    id: String,
+    active: bool,
+    access: State<DatabaseAccess>,
+    cache: State<CardCache>,
+) -> Result<(), String> {
+    let expansion_id = id.clone();
+    let found = access
+        .write_and_wait(move |conn| {
+            let was_active = expansions::list(conn)
+                .map_err(|e| format!("Failed to fetch expansions: {}", e))?
+                .into_iter()
+                .find(|expansion| expansion.id == expansion_id)
+                .map(|expansion| expansion.is_active);
+            let found = expansions::set_active(conn, &expansion_id, active)
+                .map_err(|e| format!("Failed to update expansion: {}", e))?;
+            if !found {
+                return Ok(false);
+            }
+
+            let action = if active {
+                AuditAction::Enable
+            } else {
+                AuditAction::Disable
+            };
+            audit::record(
+                conn,
+                "expansion",
+                &expansion_id,
+                action,
+                was_active.map(|was_active| json!({ "active": was_active })),
+                Some(json!({ "active": active })),
+            )
+            .map_err(|e| format!("Failed to log expansion change: {}", e))?;
+            Ok::<_, AppError>(true)
+        })
+        .map_err(|e| e.to_string())?;
+    if !found {
+        return Err(format!("Expansion not found: {}", id));
+    }
+    cache.invalidate();
+
+    log::info!(
+        "Expansion {} {}",
+        id,
+        if active { "enabled" } else { "disabled" }
+    );
+    Ok(())
+}
+
+/// Tauri command: List logged edits to scoring data, newest first
+///
+/// Archetype edits, expansion toggles, community stats imports and
+/// database repairs are logged with the value before and after.
+#[tauri::command]
+pub fn get_audit_log(
+    filter: Option<AuditFilter>,
+    access: State<DatabaseAccess>,
+) -> Result<Vec<AuditEntry>, String> {
+    let filter = filter.unwrap_or_default();
+    if filter.limit == Some(0) {
+        return Err("Limit must be greater than 0".to_string());
+    }
+
+    access
+        .read(|conn| audit::list(conn, &filter))
+        .map_err(|e| format!("Failed to fetch audit log: {}", e))
+}
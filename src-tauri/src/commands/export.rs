@@ -1,10 +1,57 @@
 //! Export/Import commands for deck data
 
-use crate::database::DatabaseState;
+use crate::app_data::{self, AppDataImportReport, ConflictPolicy};
+use crate::commands::history::{self, RunCard, RunOverview};
+use crate::commands::ocr::OcrState;
+use crate::commands::scoring::{self, DraftScoreRequest, ScoringError};
+use crate::commands::settings;
+use crate::database::access::DatabaseAccess;
+use crate::database::cache::{CardCache, CardSnapshot};
+use crate::database::{repository, LoadProgress, PROGRESS_EVENT};
+use crate::deck_image::{self, DeckImage, DeckImageRow};
+use crate::error::{AppError, AppResult};
+use crate::hotkeys::HotkeyState;
+use crate::scoring::calculator::{ScoreCalculator, ScoringResult};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 
-/// Deck export format
+/// Layouts of exported deck files, oldest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeckExportVersion {
+    /// Original layout; some early files lack `champion_path`
+    V1_0,
+    /// Adds `artifacts`
+    V1_1,
+}
+
+impl DeckExportVersion {
+    /// Version written by `export_deck`
+    pub const CURRENT: Self = DeckExportVersion::V1_1;
+
+    pub fn parse(version: &str) -> Option<Self> {
+        match version {
+            "1.0" => Some(DeckExportVersion::V1_0),
+            "1.1" => Some(DeckExportVersion::V1_1),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeckExportVersion::V1_0 => "1.0",
+            DeckExportVersion::V1_1 => "1.1",
+        }
+    }
+}
+
+/// Deck export format, in the current version's layout
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DeckExport {
     pub version: String,
@@ -14,9 +61,68 @@ pub struct DeckExport {
     pub covenant: i32,
     pub ring: i32,
     pub cards: Vec<ExportedCard>,
+    /// Artifact names, in the order they were picked up
+    #[serde(default)]
+    pub artifacts: Vec<String>,
     pub metadata: ExportMetadata,
 }
 
+/// Deck file layout of version 1.0
+#[derive(Deserialize, Debug)]
+struct DeckExportV1_0 {
+    exported_at: String,
+    champion: String,
+    #[serde(default)]
+    champion_path: Option<String>,
+    covenant: i32,
+    ring: i32,
+    cards: Vec<ExportedCard>,
+    metadata: ExportMetadata,
+}
+
+impl From<DeckExportV1_0> for DeckExport {
+    fn from(old: DeckExportV1_0) -> Self {
+        DeckExport {
+            version: DeckExportVersion::CURRENT.as_str().to_string(),
+            exported_at: old.exported_at,
+            champion: old.champion,
+            champion_path: old.champion_path.unwrap_or_default(),
+            covenant: old.covenant,
+            ring: old.ring,
+            cards: old.cards,
+            artifacts: Vec::new(),
+            metadata: old.metadata,
+        }
+    }
+}
+
+/// Parse a deck file of any supported version, upgraded to the current layout
+fn parse_deck_export(json: &str) -> Result<DeckExport, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse deck data: {}", e))?;
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Deck file has no version".to_string())?;
+    let parsed = DeckExportVersion::parse(version)
+        .ok_or_else(|| format!("Unsupported deck version: {}", version))?;
+
+    let deck = match parsed {
+        DeckExportVersion::V1_0 => serde_json::from_value::<DeckExportV1_0>(value).map(Into::into),
+        DeckExportVersion::V1_1 => serde_json::from_value::<DeckExport>(value),
+    }
+    .map_err(|e| format!("Failed to parse deck data: {}", e))?;
+
+    if parsed != DeckExportVersion::CURRENT {
+        log::info!(
+            "[Import] Upgraded deck from version {} to {}",
+            parsed.as_str(),
+            deck.version
+        );
+    }
+    Ok(deck)
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ExportedCard {
     pub id: String,
@@ -33,25 +139,219 @@ pub struct ExportMetadata {
     pub synergy_count: i32,
 }
 
-/// Export the current deck to a JSON file
+/// File format a deck is exported in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DeckFormat {
+    /// Full deck data, the only format `import_deck` reads back
+    #[default]
+    Json,
+    /// Table for Reddit and Discord posts
+    Markdown,
+    /// One card per line
+    Text,
+}
+
+/// Synergy descriptions for each deck card whose partner is also in the deck
+///
+/// The champion counts as in the deck, so champion synergies are listed on
+/// the cards they pair with.
+fn deck_synergies(
+    conn: &Connection,
+    deck: &DeckExport,
+    snapshot: &CardSnapshot,
+) -> rusqlite::Result<HashMap<String, Vec<String>>> {
+    let mut ids: HashSet<&str> = deck.cards.iter().map(|c| c.id.as_str()).collect();
+    ids.extend(
+        snapshot
+            .cards()
+            .iter()
+            .filter(|c| c.name == deck.champion)
+            .map(|c| c.id.as_str()),
+    );
+
+    repository::get_synergy_notes(conn, &ids)
+}
+
+/// Escape a value for a Markdown table cell
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+/// Render a deck as a Markdown table
+fn render_markdown(
+    deck: &DeckExport,
+    snapshot: &CardSnapshot,
+    synergies: &HashMap<String, Vec<String>>,
+) -> String {
+    let mut out = format!(
+        "# {} ({})\n\nCovenant {} · Ring {} · {} cards · Total value {}\n\n",
+        markdown_cell(&deck.champion),
+        markdown_cell(&deck.champion_path),
+        deck.covenant,
+        deck.ring,
+        deck.cards.len(),
+        deck.metadata.total_value
+    );
+    out.push_str("| # | Card | Clan | Cost | Score | Synergies |\n");
+    out.push_str("|---|------|------|------|-------|-----------|\n");
+
+    for (i, exported) in deck.cards.iter().enumerate() {
+        let card = snapshot.get(&exported.id);
+        let notes = synergies
+            .get(&exported.id)
+            .map(|notes| notes.join("; "))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            i + 1,
+            markdown_cell(&exported.name),
+            card.map(|c| markdown_cell(&c.clan)).unwrap_or_default(),
+            card.and_then(|c| c.cost)
+                .map(|cost| cost.to_string())
+                .unwrap_or_default(),
+            card.map(|c| c.base_value.to_string()).unwrap_or_default(),
+            markdown_cell(&notes)
+        ));
+    }
+    out
+}
+
+/// Render a deck as a plain-text card list
+fn render_text(deck: &DeckExport, snapshot: &CardSnapshot) -> String {
+    let mut out = format!(
+        "{} ({}) - Covenant {}, Ring {}\n\n",
+        deck.champion, deck.champion_path, deck.covenant, deck.ring
+    );
+    for (i, exported) in deck.cards.iter().enumerate() {
+        out.push_str(&format!("{}. {}", i + 1, exported.name));
+        if let Some(card) = snapshot.get(&exported.id) {
+            match card.cost {
+                Some(cost) => out.push_str(&format!(" ({}, {} ember)", card.clan, cost)),
+                None => out.push_str(&format!(" ({})", card.clan)),
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render a deck in `format`, reading card details from the database if needed
+fn render_deck(
+    deck: &DeckExport,
+    format: DeckFormat,
+    access: &DatabaseAccess,
+    cache: &CardCache,
+) -> Result<String, String> {
+    if format == DeckFormat::Json {
+        return serde_json::to_string_pretty(deck)
+            .map_err(|e| format!("Failed to serialize deck: {}", e));
+    }
+
+    access
+        .read(|conn| {
+            let snapshot = cache.get(conn)?;
+            Ok(match format {
+                DeckFormat::Markdown => {
+                    let synergies = deck_synergies(conn, deck, &snapshot)?;
+                    render_markdown(deck, &snapshot, &synergies)
+                }
+                _ => render_text(deck, &snapshot),
+            })
+        })
+        .map_err(|e: rusqlite::Error| format!("Failed to load deck details: {}", e))
+}
+
+async fn write_deck(
+    deck: &DeckExport,
+    file_path: &str,
+    format: DeckFormat,
+    access: &DatabaseAccess,
+    cache: &CardCache,
+) -> Result<(), String> {
+    let content = render_deck(deck, format, access, cache)?;
+
+    tokio::fs::write(file_path, content)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Export the current deck to a file, as JSON unless another format is given
 #[tauri::command]
 pub async fn export_deck(
-    deck_data: DeckExport,
+    mut deck_data: DeckExport,
     file_path: String,
+    format: Option<DeckFormat>,
+    access: State<'_, DatabaseAccess>,
+    cache: State<'_, CardCache>,
 ) -> Result<(), String> {
-    log::info!("[Export] Exporting deck to: {}", file_path);
-    
-    let json = serde_json::to_string_pretty(&deck_data)
-        .map_err(|e| format!("Failed to serialize deck: {}", e))?;
-    
-    tokio::fs::write(&file_path, json)
-        .await
-        .map_err(|e| format!("Failed to write file: {}", e))?;
-    
+    let format = format.unwrap_or_default();
+    log::info!("[Export] Exporting deck as {:?} to: {}", format, file_path);
+    deck_data.version = DeckExportVersion::CURRENT.as_str().to_string();
+
+    write_deck(&deck_data, &file_path, format, &access, &cache).await?;
+
     log::info!("[Export] Successfully exported deck to: {}", file_path);
     Ok(())
 }
 
+/// Rows and headings of the deck image
+fn deck_image_data(
+    deck: &DeckExport,
+    snapshot: &CardSnapshot,
+    synergies: &HashMap<String, Vec<String>>,
+) -> DeckImage {
+    let rows = deck
+        .cards
+        .iter()
+        .map(|exported| {
+            let card = snapshot.get(&exported.id);
+            DeckImageRow {
+                name: exported.name.clone(),
+                cost: card.and_then(|c| c.cost),
+                tier: card.map(|c| ScoreCalculator::tier_for(c.base_value)),
+                synergies: synergies.get(&exported.id).cloned().unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    DeckImage {
+        title: format!("{} ({})", deck.champion, deck.champion_path),
+        subtitle: format!(
+            "Covenant {} - Ring {} - {} cards - Total value {}",
+            deck.covenant,
+            deck.ring,
+            deck.cards.len(),
+            deck.metadata.total_value
+        ),
+        rows,
+    }
+}
+
+/// Render the deck list to a PNG file
+#[tauri::command]
+pub fn export_deck_image(
+    deck: DeckExport,
+    path: String,
+    access: State<'_, DatabaseAccess>,
+    cache: State<'_, CardCache>,
+) -> AppResult<()> {
+    log::info!("[Export] Exporting deck image to: {}", path);
+
+    let image = access.read(|conn| -> AppResult<DeckImage> {
+        let snapshot = cache.get(conn)?;
+        let synergies = deck_synergies(conn, &deck, &snapshot)?;
+        Ok(deck_image_data(&deck, &snapshot, &synergies))
+    })?;
+
+    deck_image::render(&image)
+        .save_with_format(&path, image::ImageFormat::Png)
+        .map_err(|e| AppError::Internal(format!("Failed to write image: {}", e)))?;
+
+    log::info!("[Export] Successfully exported deck image to: {}", path);
+    Ok(())
+}
+
 /// Import a deck from a JSON file
 #[tauri::command]
 pub async fn import_deck(file_path: String) -> Result<DeckExport, String> {
@@ -61,40 +361,512 @@ pub async fn import_deck(file_path: String) -> Result<DeckExport, String> {
         .await
         .map_err(|e| format!("Failed to read file: {}", e))?;
     
-    let deck: DeckExport = serde_json::from_str(&json)
-        .map_err(|e| format!("Failed to parse deck data: {}", e))?;
-    
-    // Validate version
-    if deck.version != "1.0" {
-        return Err(format!("Unsupported deck version: {}", deck.version));
-    }
-    
+    let deck = parse_deck_export(&json)?;
+
     log::info!("[Import] Successfully imported deck with {} cards", deck.cards.len());
     Ok(deck)
 }
 
-/// Export deck history to CSV
+/// Copy the deck to the clipboard, as JSON unless another format is given
 #[tauri::command]
-pub fn export_history_csv(
-    state: State<'_, DatabaseState>,
-    file_path: String,
+pub fn copy_deck_to_clipboard(
+    app: AppHandle,
+    mut deck_data: DeckExport,
+    format: Option<DeckFormat>,
+    access: State<'_, DatabaseAccess>,
+    cache: State<'_, CardCache>,
 ) -> Result<(), String> {
-    use rusqlite::Connection;
-    
-    log::info!("[Export] Exporting history to CSV: {}", file_path);
-    
-    let conn = Connection::open(&state.db_path)
-        .map_err(|e| format!("Failed to open database: {}", e))?;
-    
+    let format = format.unwrap_or_default();
+    deck_data.version = DeckExportVersion::CURRENT.as_str().to_string();
+
+    let content = render_deck(&deck_data, format, &access, &cache)?;
+    app.clipboard()
+        .write_text(content)
+        .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+
+    log::info!(
+        "[Export] Copied deck with {} cards to the clipboard as {:?}",
+        deck_data.cards.len(),
+        format
+    );
+    Ok(())
+}
+
+/// Import a deck from JSON on the clipboard
+#[tauri::command]
+pub fn import_deck_from_clipboard(app: AppHandle) -> Result<DeckExport, String> {
+    let json = app
+        .clipboard()
+        .read_text()
+        .map_err(|e| format!("Failed to read clipboard: {}", e))?;
+    if !json.trim_start().starts_with('{') {
+        return Err("Clipboard does not contain an exported deck".to_string());
+    }
+
+    let deck = parse_deck_export(&json)?;
+
+    log::info!(
+        "[Import] Imported deck with {} cards from the clipboard",
+        deck.cards.len()
+    );
+    Ok(deck)
+}
+
+/// First byte of every share code, bumped when the layout changes
+const SHARE_CODE_VERSION: u8 = 1;
+
+/// Longest share code accepted, well above any real deck
+const MAX_SHARE_CODE_LEN: usize = 4096;
+
+/// A deck as carried by a share code
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SharedDeck {
+    pub champion: String,
+    pub covenant: i32,
+    /// Card IDs in deck order; duplicates are kept
+    pub card_ids: Vec<String>,
+}
+
+/// Card IDs in share code order
+///
+/// Cards are referenced by their index in the sorted ID list, so a code
+/// only decodes to the same deck on a build with the same card set.
+fn share_code_order(snapshot: &CardSnapshot) -> Vec<&str> {
+    let mut ids: Vec<&str> = snapshot.cards().iter().map(|c| c.id.as_str()).collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// Append `value` as an LEB128 varint
+fn write_varint(out: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        out.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Read an LEB128 varint, or `None` if the bytes run out or it is too long
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> Option<u32> {
+    let mut value = 0u32;
+    for shift in (0..32).step_by(7) {
+        let byte = bytes.next()?;
+        value |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Encode a deck as a share code
+///
+/// Layout before base64: version byte, champion name as a varint length and
+/// UTF-8 bytes, covenant byte, then one varint card index per card.
+fn encode_share_code(deck: &SharedDeck, order: &[&str]) -> AppResult<String> {
+    let covenant = u8::try_from(deck.covenant)
+        .map_err(|_| AppError::InvalidInput(format!("Covenant out of range: {}", deck.covenant)))?;
+
+    let mut bytes = vec![SHARE_CODE_VERSION];
+    write_varint(&mut bytes, deck.champion.len() as u32);
+    bytes.extend_from_slice(deck.champion.as_bytes());
+    bytes.push(covenant);
+    for card_id in &deck.card_ids {
+        let index = order
+            .binary_search(&card_id.as_str())
+            .map_err(|_| AppError::NotFound(format!("Card '{}' not found", card_id)))?;
+        write_varint(&mut bytes, index as u32);
+    }
+
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Decode a share code produced by `encode_share_code`
+fn decode_share_code(code: &str, order: &[&str]) -> AppResult<SharedDeck> {
+    let invalid = |reason: &str| AppError::InvalidInput(format!("Invalid share code: {}", reason));
+
+    // Tolerate padding and surrounding whitespace from chat clients
+    let code = code.trim().trim_end_matches('=');
+    if code.is_empty() {
+        return Err(invalid("empty"));
+    }
+    if code.len() > MAX_SHARE_CODE_LEN {
+        return Err(invalid("too long"));
+    }
+    let bytes = URL_SAFE_NO_PAD
+        .decode(code)
+        .map_err(|_| invalid("not base64"))?;
+    let mut bytes = bytes.into_iter().peekable();
+
+    let version = bytes.next().ok_or_else(|| invalid("empty"))?;
+    if version != SHARE_CODE_VERSION {
+        return Err(AppError::InvalidInput(format!(
+            "Unsupported share code version: {}",
+            version
+        )));
+    }
+
+    let len = read_varint(&mut bytes).ok_or_else(|| invalid("truncated"))? as usize;
+    let champion: Vec<u8> = bytes.by_ref().take(len).collect();
+    if champion.len() != len {
+        return Err(invalid("truncated"));
+    }
+    let champion = String::from_utf8(champion).map_err(|_| invalid("champion is not UTF-8"))?;
+    let covenant = bytes.next().ok_or_else(|| invalid("truncated"))?;
+
+    let mut card_ids = Vec::new();
+    while bytes.peek().is_some() {
+        let index = read_varint(&mut bytes).ok_or_else(|| invalid("truncated"))?;
+        let card_id = order
+            .get(index as usize)
+            .ok_or_else(|| invalid("unknown card"))?;
+        card_ids.push(card_id.to_string());
+    }
+
+    Ok(SharedDeck {
+        champion,
+        covenant: i32::from(covenant),
+        card_ids,
+    })
+}
+
+/// Encode a deck as a compact share code for pasting into chat
+#[tauri::command]
+pub fn export_share_code(
+    deck: SharedDeck,
+    access: State<'_, DatabaseAccess>,
+    cache: State<'_, CardCache>,
+) -> AppResult<String> {
+    let snapshot = access.read(|conn| cache.get(conn))?;
+    let code = encode_share_code(&deck, &share_code_order(&snapshot))?;
+
+    log::info!(
+        "[Export] Created share code for {} cards",
+        deck.card_ids.len()
+    );
+    Ok(code)
+}
+
+/// Decode a deck from a share code
+#[tauri::command]
+pub fn import_share_code(
+    code: String,
+    access: State<'_, DatabaseAccess>,
+    cache: State<'_, CardCache>,
+) -> AppResult<SharedDeck> {
+    let snapshot = access.read(|conn| cache.get(conn))?;
+    let deck = decode_share_code(&code, &share_code_order(&snapshot))?;
+
+    log::info!(
+        "[Import] Imported share code with {} cards",
+        deck.card_ids.len()
+    );
+    Ok(deck)
+}
+
+/// Columns written by `export_history_csv` and expected by `import_history_csv`
+pub(crate) const HISTORY_CSV_HEADER: &str =
+    "run_id,card_id,ring_number,draft_order,champion,covenant,score_at_draft,did_win,created_at";
+
+/// Timestamp format SQLite's `CURRENT_TIMESTAMP` produces
+const SQLITE_TIMESTAMP: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Rows written between progress reports of a history import
+const IMPORT_PROGRESS_INTERVAL: usize = 500;
+
+/// A problem with one line of an imported CSV
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ImportIssue {
+    /// 1-based line number in the file
+    pub line: u32,
+    pub message: String,
+}
+
+/// Outcome of `import_history_csv`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct HistoryImportReport {
+    pub dry_run: bool,
+    /// Data rows in the file, excluding the header
+    pub total_rows: u32,
+    /// Valid rows not yet in the database
+    pub new_rows: u32,
+    /// Rows already in the database or repeated in the file, skipped
+    pub duplicates: u32,
+    /// Rows written; nothing is written on a dry run or if any row is invalid
+    pub imported: u32,
+    /// Runs added for imported rows whose run was not known yet
+    pub runs_created: u32,
+    pub errors: Vec<ImportIssue>,
+}
+
+/// One parsed `deck_history` row
+#[derive(Debug, Clone, PartialEq)]
+struct HistoryCsvRow {
+    run_id: String,
+    card_id: String,
+    ring_number: i32,
+    draft_order: i32,
+    champion: String,
+    covenant: i32,
+    score_at_draft: Option<i32>,
+    did_win: Option<bool>,
+    created_at: String,
+}
+
+/// Split a CSV line into fields, honoring double-quoted fields
+pub(crate) fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Normalize an exported timestamp to SQLite's format
+///
+/// Accepts SQLite timestamps and RFC 3339, which is converted to UTC.
+fn parse_timestamp(value: &str) -> Option<String> {
+    if let Ok(timestamp) = chrono::NaiveDateTime::parse_from_str(value, SQLITE_TIMESTAMP) {
+        return Some(timestamp.format(SQLITE_TIMESTAMP).to_string());
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|timestamp| timestamp.naive_utc().format(SQLITE_TIMESTAMP).to_string())
+}
+
+fn parse_history_row(line: &str, known_cards: &HashSet<String>) -> Result<HistoryCsvRow, String> {
+    let fields = split_csv_line(line);
+    if fields.len() != 9 {
+        return Err(format!("Expected 9 columns, found {}", fields.len()));
+    }
+    let field = |i: usize| fields[i].trim();
+    let number = |i: usize, name: &str| {
+        field(i)
+            .parse::<i32>()
+            .map_err(|_| format!("Invalid {}: '{}'", name, field(i)))
+    };
+
+    let run_id = field(0);
+    if run_id.is_empty() {
+        return Err("Run ID is empty".to_string());
+    }
+    let card_id = field(1);
+    if !known_cards.contains(card_id) {
+        return Err(format!("Unknown card ID: '{}'", card_id));
+    }
+    let champion = field(4);
+    if champion.is_empty() {
+        return Err("Champion is empty".to_string());
+    }
+    let score_at_draft = match field(6) {
+        "" => None,
+        _ => Some(number(6, "score_at_draft")?),
+    };
+    let did_win = match field(7) {
+        "" => None,
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        other => return Err(format!("Invalid did_win: '{}'", other)),
+    };
+    let created_at =
+        parse_timestamp(field(8)).ok_or_else(|| format!("Invalid date: '{}'", field(8)))?;
+
+    Ok(HistoryCsvRow {
+        run_id: run_id.to_string(),
+        card_id: card_id.to_string(),
+        ring_number: number(2, "ring_number")?,
+        draft_order: number(3, "draft_order")?,
+        champion: champion.to_string(),
+        covenant: number(5, "covenant")?,
+        score_at_draft,
+        did_win,
+        created_at,
+    })
+}
+
+/// Validate a history CSV and, unless `dry_run`, insert its new rows
+///
+/// Rows are written in one transaction and only if every row is valid, so a
+/// bad file never leaves a partial import behind. `on_progress` is told
+/// every `IMPORT_PROGRESS_INTERVAL` rows written.
+fn import_history(
+    conn: &Connection,
+    csv: &str,
+    dry_run: bool,
+    on_progress: &mut dyn FnMut(LoadProgress),
+) -> rusqlite::Result<HistoryImportReport> {
+    let mut report = HistoryImportReport {
+        dry_run,
+        ..Default::default()
+    };
+
+    let mut lines = csv
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    match lines.next() {
+        Some((_, header)) if header.trim() == HISTORY_CSV_HEADER => {}
+        Some((i, _)) => {
+            report.errors.push(ImportIssue {
+                line: i as u32 + 1,
+                message: format!("Expected header '{}'", HISTORY_CSV_HEADER),
+            });
+            return Ok(report);
+        }
+        None => return Ok(report),
+    }
+
+    let mut stmt = conn.prepare("SELECT id FROM cards")?;
+    let known_cards = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<rusqlite::Result<HashSet<String>>>()?;
+
+    let mut exists = conn.prepare(
+        "SELECT EXISTS(SELECT 1 FROM deck_history
+                       WHERE run_id = ?1 AND card_id = ?2 AND draft_order = ?3)",
+    )?;
+    let mut seen = HashSet::new();
+    let mut rows = Vec::new();
+
+    for (i, line) in lines {
+        report.total_rows += 1;
+        let row = match parse_history_row(line, &known_cards) {
+            Ok(row) => row,
+            Err(message) => {
+                report.errors.push(ImportIssue {
+                    line: i as u32 + 1,
+                    message,
+                });
+                continue;
+            }
+        };
+
+        let key = (row.run_id.clone(), row.card_id.clone(), row.draft_order);
+        let in_database: bool = exists.query_row(
+            rusqlite::params![row.run_id, row.card_id, row.draft_order],
+            |r| r.get(0),
+        )?;
+        if in_database || !seen.insert(key) {
+            report.duplicates += 1;
+            continue;
+        }
+        rows.push(row);
+    }
+    report.new_rows = rows.len() as u32;
+
+    if dry_run || !report.errors.is_empty() || rows.is_empty() {
+        return Ok(report);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut insert = tx.prepare(
+            "INSERT INTO deck_history
+                (run_id, card_id, ring_number, draft_order, champion, covenant,
+                 score_at_draft, did_win, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        )?;
+        for (i, row) in rows.iter().enumerate() {
+            insert.execute(rusqlite::params![
+                row.run_id,
+                row.card_id,
+                row.ring_number,
+                row.draft_order,
+                row.champion,
+                row.covenant,
+                row.score_at_draft,
+                row.did_win,
+                row.created_at
+            ])?;
+            let done = i + 1;
+            if done % IMPORT_PROGRESS_INTERVAL == 0 || done == rows.len() {
+                on_progress(LoadProgress {
+                    stage: "history".to_string(),
+                    done: done as u32,
+                    total: rows.len() as u32,
+                });
+            }
+        }
+    }
+    // Same backfill as the `runs` migration, for runs only known from history
+    report.runs_created = tx.execute(
+        "INSERT OR IGNORE INTO runs (run_id, champion, covenant, did_win, final_ring, started_at)
+         SELECT run_id, MAX(champion), MAX(covenant), MAX(did_win), MAX(ring_number), MIN(created_at)
+         FROM deck_history
+         GROUP BY run_id",
+        [],
+    )? as u32;
+    tx.commit()?;
+
+    report.imported = rows.len() as u32;
+    Ok(report)
+}
+
+/// Import deck history from a CSV written by `export_history_csv`
+///
+/// With `dry_run`, only validates the file and reports what would change.
+/// Large imports report how far they are with `PROGRESS_EVENT`.
+#[tauri::command]
+pub fn import_history_csv(
+    path: String,
+    dry_run: Option<bool>,
+    app: AppHandle,
+    access: State<'_, DatabaseAccess>,
+) -> AppResult<HistoryImportReport> {
+    log::info!("[Import] Importing history from CSV: {}", path);
+
+    let csv = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::InvalidInput(format!("Failed to read file: {}", e)))?;
+    let report = access.write_and_wait(move |conn| {
+        import_history(conn, &csv, dry_run.unwrap_or(false), &mut |progress| {
+            if let Err(e) = app.emit(PROGRESS_EVENT, progress) {
+                log::warn!("Failed to emit import progress: {}", e);
+            }
+        })
+    })?;
+
+    log::info!(
+        "[Import] History import: {} new, {} duplicates, {} errors, {} written",
+        report.new_rows,
+        report.duplicates,
+        report.errors.len(),
+        report.imported
+    );
+    Ok(report)
+}
+
+/// Lets a running history export be cancelled
+///
+/// Managed by Tauri. Starting an export clears an earlier cancel.
+#[derive(Default)]
+pub struct ExportState {
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Deck history as CSV, newest first, in the format `import_history_csv` reads
+///
+/// Stops with `AppError::Cancelled` once `cancelled` is set.
+pub(crate) fn history_csv(conn: &Connection, cancelled: &AtomicBool) -> AppResult<String> {
     let mut stmt = conn.prepare(
         "SELECT run_id, card_id, ring_number, draft_order, champion, covenant, score_at_draft, did_win, created_at 
-         FROM deck_history 
+         FROM live_deck_history 
          ORDER BY created_at DESC"
-    ).map_err(|e| format!("Failed to prepare query: {}", e))?;
+    ).map_err(|e| AppError::Database(format!("Failed to prepare query: {}", e)))?;
     
-    let mut csv_content = String::from(
-        "run_id,card_id,ring_number,draft_order,champion,covenant,score_at_draft,did_win,created_at\n"
-    );
+    let mut csv_content = format!("{}\n", HISTORY_CSV_HEADER);
     
     let rows = stmt.query_map([], |row| {
         Ok((
@@ -108,11 +880,14 @@ pub fn export_history_csv(
             row.get::<_, Option<bool>>(7)?,
             row.get::<_, String>(8)?,
         ))
-    }).map_err(|e| format!("Failed to query history: {}", e))?;
+    }).map_err(|e| AppError::Database(format!("Failed to query history: {}", e)))?;
     
     for row in rows {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(AppError::Cancelled("Export cancelled".to_string()));
+        }
         let (run_id, card_id, ring, order, champion, covenant, score, did_win, created_at) = 
-            row.map_err(|e| format!("Failed to read row: {}", e))?;
+            row.map_err(|e| AppError::Database(format!("Failed to read row: {}", e)))?;
         
         csv_content.push_str(&format!(
             "{},{},{},{},{},{},{},{},{}\n",
@@ -127,14 +902,343 @@ pub fn export_history_csv(
             created_at
         ));
     }
-    
-    std::fs::write(&file_path, csv_content)
-        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+
+    Ok(csv_content)
+}
+
+/// Export deck history to CSV
+///
+/// Runs on a blocking thread; `cancel_export` stops it with a `cancelled`
+/// error before the file is written.
+#[tauri::command]
+pub async fn export_history_csv(
+    access: State<'_, DatabaseAccess>,
+    export_state: State<'_, ExportState>,
+    file_path: String,
+) -> AppResult<()> {
+    log::info!("[Export] Exporting history to CSV: {}", file_path);
+
+    let access = access.inner().clone();
+    let cancelled = export_state.cancelled.clone();
+    cancelled.store(false, Ordering::Relaxed);
+    let csv_content =
+        tokio::task::spawn_blocking(move || access.read(|conn| history_csv(conn, &cancelled)))
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))??;
+
+    tokio::fs::write(&file_path, csv_content)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to write CSV: {}", e)))?;
     
     log::info!("[Export] Successfully exported history to: {}", file_path);
     Ok(())
 }
 
+/// Cancel a history export in progress
+#[tauri::command]
+pub fn cancel_export(export_state: State<'_, ExportState>) {
+    log::info!("[Export] Cancelling history export");
+    export_state.cancelled.store(true, Ordering::Relaxed);
+}
+
+/// File format a run report is exported in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Markdown,
+}
+
+/// A card offered alongside a pick
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReportAlternative {
+    pub card_id: String,
+    pub card_name: String,
+    /// `None` for cards this build does not know
+    pub score: Option<i32>,
+    pub tier: Option<String>,
+}
+
+/// One pick of a run and the reasoning behind its score
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReportPick {
+    #[serde(flatten)]
+    pub card: RunCard,
+    /// Score recomputed against the deck as it stood before the pick
+    pub breakdown: Option<ScoringResult>,
+    /// Other cards offered in the same ring, best first; empty when no
+    /// offers were recorded
+    pub alternatives: Vec<ReportAlternative>,
+    /// The pick judged against the run's outcome, once it has one
+    pub hindsight: Option<String>,
+}
+
+/// Pick-by-pick review of a run
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunReport {
+    #[serde(flatten)]
+    pub overview: RunOverview,
+    pub generated_at: String,
+    pub picks: Vec<ReportPick>,
+}
+
+/// Comment on a pick given the run's outcome and the best-scoring
+/// alternative, if one scored higher than the pick
+fn hindsight(
+    did_win: Option<bool>,
+    pick_score: Option<i32>,
+    alternatives: &[ReportAlternative],
+) -> Option<String> {
+    let did_win = did_win?;
+    let better = alternatives
+        .first()
+        .filter(|alt| alt.score.is_some() && alt.score > pick_score);
+
+    Some(match (did_win, better) {
+        (true, Some(alt)) => format!(
+            "Run won despite passing on {}, which scored {}",
+            alt.card_name,
+            alt.score.unwrap_or_default()
+        ),
+        (false, Some(alt)) => format!(
+            "Run lost; {} scored higher ({} vs {}) and may have been the better pick",
+            alt.card_name,
+            alt.score.unwrap_or_default(),
+            pick_score.unwrap_or_default()
+        ),
+        (true, None) if !alternatives.is_empty() => {
+            "Run won; the pick scored at least as well as every alternative".to_string()
+        }
+        (false, None) if !alternatives.is_empty() => {
+            "Run lost, though the pick scored at least as well as every alternative".to_string()
+        }
+        (true, None) => "Run won".to_string(),
+        (false, None) => "Run lost".to_string(),
+    })
+}
+
+/// Build the report for `run_id`
+///
+/// Scores are recomputed with the current card data and without the
+/// player's history adjustment, which would count the run itself.
+fn build_run_report(
+    conn: &Connection,
+    snapshot: &CardSnapshot,
+    run_id: &str,
+) -> AppResult<RunReport> {
+    let summary = history::query_run_summary(conn, run_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Run '{}' not found", run_id)))?;
+    let champion = summary.overview.champion.clone().unwrap_or_default();
+    let covenant = summary.overview.covenant.unwrap_or_default();
+
+    let mut offers: HashMap<i32, Vec<String>> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT ring_number, card_id FROM draft_offers
+         WHERE run_id = ?1 AND was_picked = 0
+         ORDER BY id",
+    )?;
+    let rows = stmt.query_map([run_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    for row in rows {
+        let (ring, card_id) = row?;
+        offers.entry(ring).or_default().push(card_id);
+    }
+
+    let mut picks = Vec::with_capacity(summary.cards.len());
+    for (i, card) in summary.cards.iter().enumerate() {
+        let deck: Vec<String> = summary.cards[..i]
+            .iter()
+            .map(|c| c.card_id.clone())
+            .collect();
+        let score = |card_id: &str| -> AppResult<Option<ScoringResult>> {
+            let request = DraftScoreRequest {
+                card_id: card_id.to_string(),
+                current_deck: deck.clone(),
+                champion: champion.clone(),
+                ring_number: card.ring_number,
+                covenant,
+                champion_path: None,
+                upcoming_boss: None,
+                pact_shards: None,
+                upgrade_count: 0,
+            };
+            match scoring::score_card(conn, snapshot, &request) {
+                Ok(result) => Ok(Some(result)),
+                Err(ScoringError::CardNotFound(_)) => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        };
+
+        let breakdown = score(&card.card_id)?;
+        let mut alternatives = Vec::new();
+        for card_id in offers.get(&card.ring_number).into_iter().flatten() {
+            // Offers picked from later in the same ring are not alternatives
+            if summary
+                .cards
+                .iter()
+                .any(|c| c.card_id == *card_id && c.ring_number == card.ring_number)
+            {
+                continue;
+            }
+            let result = score(card_id)?;
+            alternatives.push(ReportAlternative {
+                card_id: card_id.clone(),
+                card_name: snapshot
+                    .get(card_id)
+                    .map(|c| c.name.clone())
+                    .unwrap_or_else(|| card_id.clone()),
+                score: result.as_ref().map(|r| r.score),
+                tier: result.map(|r| r.tier),
+            });
+        }
+        alternatives.sort_by_key(|alt| std::cmp::Reverse(alt.score));
+
+        let hindsight = hindsight(
+            summary.overview.did_win,
+            breakdown.as_ref().map(|b| b.score),
+            &alternatives,
+        );
+        picks.push(ReportPick {
+            card: card.clone(),
+            breakdown,
+            alternatives,
+            hindsight,
+        });
+    }
+
+    Ok(RunReport {
+        overview: summary.overview,
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        picks,
+    })
+}
+
+/// Render a run report as Markdown
+fn render_run_report(report: &RunReport) -> String {
+    let overview = &report.overview;
+    let outcome = match overview.did_win {
+        Some(true) => "Won",
+        Some(false) => "Lost",
+        None => "No outcome",
+    };
+    let mut out = format!(
+        "# Run {}\n\n{} · Covenant {} · {} · {} picks · Started {}\n",
+        overview.run_id,
+        overview.champion.as_deref().unwrap_or("Unknown champion"),
+        overview.covenant.unwrap_or_default(),
+        outcome,
+        report.picks.len(),
+        overview.started_at
+    );
+
+    for pick in &report.picks {
+        out.push_str(&format!(
+            "\n## {}. {} (ring {})\n\n",
+            pick.card.draft_order, pick.card.card_name, pick.card.ring_number
+        ));
+        if let Some(score) = pick.card.score_at_draft {
+            out.push_str(&format!("- Score at draft: {}\n", score));
+        }
+        if let Some(b) = &pick.breakdown {
+            out.push_str(&format!(
+                "- Recomputed score: {} ({})\n- Base value {} · Synergy x{:.2} · Context {:+} · Champion {:+}\n",
+                b.score, b.tier, b.base_value, b.synergy_multiplier, b.context_bonus, b.champion_bonus
+            ));
+            for reason in &b.reasons {
+                out.push_str(&format!("  - {}\n", reason));
+            }
+        }
+        if !pick.alternatives.is_empty() {
+            let alternatives: Vec<_> = pick
+                .alternatives
+                .iter()
+                .map(|alt| match (&alt.score, &alt.tier) {
+                    (Some(score), Some(tier)) => {
+                        format!("{} ({}, {})", alt.card_name, score, tier)
+                    }
+                    _ => alt.card_name.clone(),
+                })
+                .collect();
+            out.push_str(&format!("- Passed on: {}\n", alternatives.join(", ")));
+        }
+        if let Some(note) = &pick.hindsight {
+            out.push_str(&format!("\n> {}\n", note));
+        }
+    }
+    out
+}
+
+/// Export a pick-by-pick report of a run for post-run review
+#[tauri::command]
+pub fn export_run_report(
+    run_id: String,
+    path: String,
+    format: Option<ReportFormat>,
+    access: State<'_, DatabaseAccess>,
+    cache: State<'_, CardCache>,
+) -> AppResult<()> {
+    log::info!("[Export] Exporting report for run {} to: {}", run_id, path);
+
+    let report = access.read(|conn| {
+        let snapshot = cache.get(conn)?;
+        build_run_report(conn, &snapshot, &run_id)
+    })?;
+    let content = match format.unwrap_or_default() {
+        ReportFormat::Json => serde_json::to_string_pretty(&report)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize report: {}", e)))?,
+        ReportFormat::Markdown => render_run_report(&report),
+    };
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::Internal(format!("Failed to write file: {}", e)))?;
+
+    log::info!("[Export] Successfully exported run report to: {}", path);
+    Ok(())
+}
+
+/// Export the database and settings to a single zip bundle
+#[tauri::command]
+pub fn export_app_data(path: String, access: State<'_, DatabaseAccess>) -> AppResult<()> {
+    log::info!("[Export] Exporting app data to: {}", path);
+
+    let bundle = app_data::export(&access)?;
+    std::fs::write(&path, bundle)
+        .map_err(|e| AppError::Internal(format!("Failed to write file: {}", e)))?;
+
+    log::info!("[Export] Successfully exported app data to: {}", path);
+    Ok(())
+}
+
+/// Import a bundle written by `export_app_data`
+///
+/// `policy` defaults to merging while keeping existing data on conflicts.
+/// Imported settings are applied right away, as with `update_settings`.
+#[tauri::command]
+pub fn import_app_data(
+    path: String,
+    policy: Option<ConflictPolicy>,
+    app: AppHandle,
+    access: State<'_, DatabaseAccess>,
+    cache: State<'_, CardCache>,
+    ocr_state: State<'_, OcrState>,
+    hotkey_state: State<'_, HotkeyState>,
+) -> AppResult<AppDataImportReport> {
+    log::info!("[Import] Importing app data from: {}", path);
+
+    let bundle = std::fs::read(&path)
+        .map_err(|e| AppError::InvalidInput(format!("Failed to read file: {}", e)))?;
+    let (report, update) = app_data::import(&access, &bundle, policy.unwrap_or_default())?;
+    cache.invalidate();
+    settings::apply_update(update, &app, &access, &ocr_state, &hotkey_state)?;
+
+    log::info!(
+        "[Import] App data import ({:?}): replaced database: {}, {} rows merged",
+        report.policy,
+        report.replaced_database,
+        report.merged_rows
+    );
+    Ok(report)
+}
+
 /// Get available export formats
 #[tauri::command]
 pub fn get_export_formats() -> Vec<ExportFormat> {
@@ -151,6 +1255,18 @@ pub fn get_export_formats() -> Vec<ExportFormat> {
             extension: "csv".to_string(),
             description: "Simple card list".to_string(),
         },
+        ExportFormat {
+            id: "markdown".to_string(),
+            name: "Markdown".to_string(),
+            extension: "md".to_string(),
+            description: "Card table with clan, cost, score and synergies".to_string(),
+        },
+        ExportFormat {
+            id: "text".to_string(),
+            name: "Plain text".to_string(),
+            extension: "txt".to_string(),
+            description: "Numbered card list for chat".to_string(),
+        },
     ]
 }
 
@@ -165,12 +1281,14 @@ pub struct ExportFormat {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::ErrorCode;
+    use std::path::PathBuf;
     use tempfile::NamedTempFile;
     
     #[tokio::test]
     async fn test_export_import_roundtrip() {
         let deck = DeckExport {
-            version: "1.0".to_string(),
+            version: "1.1".to_string(),
             exported_at: chrono::Utc::now().to_rfc3339(),
             champion: "Fel".to_string(),
             champion_path: "Unchained".to_string(),
@@ -184,6 +1302,7 @@ mod tests {
                     ring_number: 1,
                 },
             ],
+            artifacts: Vec::new(),
             metadata: ExportMetadata {
                 total_value: 75,
                 unit_count: 0,
@@ -196,7 +1315,11 @@ mod tests {
         let path = temp_file.path().to_str().unwrap().to_string();
         
         // Export
-        export_deck(deck.clone(), path.clone()).await.unwrap();
+        let access = DatabaseAccess::new(PathBuf::new());
+        let cache = CardCache::default();
+        write_deck(&deck, &path, DeckFormat::Json, &access, &cache)
+            .await
+            .unwrap();
         
         // Import
         let imported = import_deck(path).await.unwrap();
@@ -204,4 +1327,268 @@ mod tests {
         assert_eq!(imported.champion, deck.champion);
         assert_eq!(imported.cards.len(), deck.cards.len());
     }
+
+    #[test]
+    fn test_share_code_roundtrip() {
+        let order = ["banished_cleave", "banished_fel", "banished_talos"];
+        let deck = SharedDeck {
+            champion: "Fel".to_string(),
+            covenant: 10,
+            card_ids: vec![
+                "banished_talos".to_string(),
+                "banished_cleave".to_string(),
+                "banished_cleave".to_string(),
+            ],
+        };
+
+        let code = encode_share_code(&deck, &order).unwrap();
+        assert!(code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(
+            decode_share_code(&format!(" {}= ", code), &order).unwrap(),
+            deck
+        );
+
+        let unknown = SharedDeck {
+            card_ids: vec!["missing".to_string()],
+            ..deck
+        };
+        assert!(matches!(
+            encode_share_code(&unknown, &order),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_share_code_rejects_bad_input() {
+        let order = ["banished_fel"];
+        assert!(decode_share_code("", &order).is_err());
+        assert!(decode_share_code("not base64!", &order).is_err());
+
+        // Wrong version byte
+        let code = URL_SAFE_NO_PAD.encode([9, 0, 1]);
+        assert!(decode_share_code(&code, &order).is_err());
+
+        // Card index past the end of the card list
+        let code = URL_SAFE_NO_PAD.encode([SHARE_CODE_VERSION, 0, 1, 5]);
+        assert!(decode_share_code(&code, &order).is_err());
+
+        // Champion name longer than the remaining bytes
+        let code = URL_SAFE_NO_PAD.encode([SHARE_CODE_VERSION, 10, b'F']);
+        assert!(decode_share_code(&code, &order).is_err());
+    }
+
+    #[test]
+    fn test_varint_roundtrip() {
+        for value in [0, 1, 127, 128, 300, 16_384, u32::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value);
+            assert_eq!(read_varint(&mut bytes.into_iter()), Some(value));
+        }
+        assert_eq!(read_varint(&mut [0x80u8].into_iter()), None);
+    }
+
+    #[test]
+    fn test_markdown_and_text_formats() {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let cache = CardCache::default();
+
+        let exported = |id: &str, name: &str, order: i32| ExportedCard {
+            id: id.to_string(),
+            name: name.to_string(),
+            draft_order: order,
+            ring_number: 1,
+        };
+        let deck = DeckExport {
+            version: "1.1".to_string(),
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            champion: "Fel".to_string(),
+            champion_path: "Unchained".to_string(),
+            covenant: 10,
+            ring: 3,
+            cards: vec![
+                exported("banished_just_cause", "Just Cause", 1),
+                exported("unknown_card", "Card | With Pipe", 2),
+            ],
+            artifacts: Vec::new(),
+            metadata: ExportMetadata {
+                total_value: 75,
+                unit_count: 0,
+                spell_count: 2,
+                synergy_count: 1,
+            },
+        };
+
+        let access = DatabaseAccess::new(temp_file.path().to_path_buf());
+        let markdown = render_deck(&deck, DeckFormat::Markdown, &access, &cache).unwrap();
+        assert!(markdown.starts_with("# Fel (Unchained)"));
+        assert!(
+            markdown.contains("| 1 | Just Cause | Banished | 0 | 75 | Shift triggers Valor gain |")
+        );
+        assert!(markdown.contains("| 2 | Card \\| With Pipe |  |  |  |  |"));
+        // Karmic Censer is not in the deck
+        assert!(!markdown.contains("Shift triggers twice"));
+
+        let text = render_deck(&deck, DeckFormat::Text, &access, &cache).unwrap();
+        assert!(text.contains("1. Just Cause (Banished, 0 ember)\n"));
+        assert!(text.contains("2. Card | With Pipe\n"));
+    }
+
+    fn history_csv(rows: &[&str]) -> String {
+        let mut csv = format!("{}\n", HISTORY_CSV_HEADER);
+        for row in rows {
+            csv.push_str(row);
+            csv.push('\n');
+        }
+        csv
+    }
+
+    #[test]
+    fn test_import_history_dry_run_then_import() {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+
+        let csv = history_csv(&[
+            "run-1,banished_fel,1,1,Fel,10,88,true,2024-05-01 12:00:00",
+            "run-1,banished_just_cause,2,2,Fel,10,,true,2024-05-01T12:05:00+02:00",
+            "run-1,banished_fel,1,1,Fel,10,88,true,2024-05-01 12:00:00",
+        ]);
+        let history_rows = |conn: &Connection| -> u32 {
+            conn.query_row("SELECT COUNT(*) FROM deck_history", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        let report = import_history(&conn, &csv, true, &mut |_| {}).unwrap();
+        assert_eq!(report.total_rows, 3);
+        assert_eq!(report.new_rows, 2);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(report.imported, 0);
+        assert!(report.errors.is_empty());
+        assert_eq!(history_rows(&conn), 0);
+
+        let report = import_history(&conn, &csv, false, &mut |_| {}).unwrap();
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.runs_created, 1);
+        assert_eq!(history_rows(&conn), 2);
+        let created_at: String = conn
+            .query_row(
+                "SELECT created_at FROM deck_history WHERE card_id = 'banished_just_cause'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(created_at, "2024-05-01 10:05:00");
+
+        // Importing the same file again only finds duplicates
+        let report = import_history(&conn, &csv, false, &mut |_| {}).unwrap();
+        assert_eq!(report.duplicates, 3);
+        assert_eq!(report.imported, 0);
+
+        let exported = super::history_csv(&conn, &AtomicBool::new(false)).unwrap();
+        assert_eq!(exported.lines().count(), 3);
+        let err = super::history_csv(&conn, &AtomicBool::new(true)).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Cancelled);
+    }
+
+    #[test]
+    fn test_import_history_rejects_invalid_rows() {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+
+        let csv = history_csv(&[
+            "run-1,banished_fel,1,1,Fel,10,88,true,2024-05-01 12:00:00",
+            "run-1,no_such_card,1,2,Fel,10,,,2024-05-01 12:00:00",
+            "run-1,banished_just_cause,1,3,Fel,10,,maybe,2024-05-01 12:00:00",
+            "run-1,banished_just_cause,1,4,Fel,10,,,yesterday",
+            "\"run, 2\",banished_fel,1,1,Fel,10,,,2024-05-01 12:00:00",
+        ]);
+        let report = import_history(&conn, &csv, false, &mut |_| {}).unwrap();
+        assert_eq!(report.total_rows, 5);
+        assert_eq!(report.new_rows, 2);
+        assert_eq!(report.imported, 0);
+        let lines: Vec<u32> = report.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, [3, 4, 5]);
+        assert!(report.errors[0].message.contains("no_such_card"));
+
+        let report = import_history(&conn, "card_id,run_id\n", true, &mut |_| {}).unwrap();
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.total_rows, 0);
+    }
+
+    #[test]
+    fn test_parse_deck_export_upgrades_old_versions() {
+        // Early 1.0 file without champion_path
+        let json = r#"{
+            "version": "1.0",
+            "exported_at": "2024-05-01T12:00:00Z",
+            "champion": "Fel",
+            "covenant": 10,
+            "ring": 5,
+            "cards": [{ "id": "banished_fel", "name": "Fel", "draft_order": 1, "ring_number": 1 }],
+            "metadata": { "total_value": 75, "unit_count": 1, "spell_count": 0, "synergy_count": 0 }
+        }"#;
+        let deck = parse_deck_export(json).unwrap();
+        assert_eq!(deck.version, DeckExportVersion::CURRENT.as_str());
+        assert_eq!(deck.champion, "Fel");
+        assert_eq!(deck.champion_path, "");
+        assert!(deck.artifacts.is_empty());
+        assert_eq!(deck.cards.len(), 1);
+
+        let upgraded = serde_json::to_string(&deck).unwrap();
+        assert_eq!(parse_deck_export(&upgraded).unwrap().cards.len(), 1);
+
+        let err = parse_deck_export(&json.replace("\"1.0\"", "\"9.0\"")).unwrap_err();
+        assert_eq!(err, "Unsupported deck version: 9.0");
+        assert!(parse_deck_export("{}").is_err());
+    }
+
+    #[test]
+    fn test_run_report_scores_picks_and_alternatives() {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+
+        for (card_id, order) in [("banished_fel", 1), ("banished_just_cause", 2)] {
+            conn.execute(
+                "INSERT INTO deck_history
+                     (run_id, card_id, ring_number, draft_order, champion, covenant, did_win)
+                 VALUES ('run-1', ?1, 1, ?2, 'Fel', 10, 0)",
+                rusqlite::params![card_id, order],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO draft_offers (run_id, ring_number, card_id, was_picked)
+             VALUES ('run-1', 1, 'banished_just_cause', 1), ('run-1', 1, 'banished_talos', 0)",
+            [],
+        )
+        .unwrap();
+
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+        let report = build_run_report(&conn, &snapshot, "run-1").unwrap();
+        assert_eq!(report.picks.len(), 2);
+
+        let pick = &report.picks[1];
+        assert_eq!(pick.card.card_id, "banished_just_cause");
+        // Fel's override for Just Cause
+        assert_eq!(pick.breakdown.as_ref().unwrap().champion_bonus, 20);
+        let alternatives: Vec<_> = pick.alternatives.iter().map(|a| &a.card_id).collect();
+        assert_eq!(alternatives, ["banished_talos"]);
+        assert!(pick.alternatives[0].score.is_some());
+        assert!(pick.hindsight.as_deref().unwrap().starts_with("Run lost"));
+
+        let markdown = render_run_report(&report);
+        assert!(markdown.starts_with("# Run run-1\n\nFel · Covenant 10 · Lost · 2 picks"));
+        assert!(markdown.contains("## 2. Just Cause (ring 1)"));
+        assert!(markdown.contains("- Passed on: Talos ("));
+
+        assert!(matches!(
+            build_run_report(&conn, &snapshot, "missing"),
+            Err(AppError::NotFound(_))
+        ));
+    }
 }
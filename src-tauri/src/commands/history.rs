@@ -0,0 +1,1484 @@
+//! Draft history analytics commands
+//!
+//! Aggregates `deck_history` (cards drafted per run) and `draft_offers`
+//! (every card offered at each draft) into per-card performance data,
+//! per-run summaries and per-champion stats from `runs`. Card and champion
+//! stats are read from the summary tables in `crate::database::analytics`.
+//!
+//! Runs can be archived, which only hides them from the run list, or
+//! deleted. Deleted runs are read through the `live_*` views so they drop
+//! out of every stat, and stay restorable until they are purged.
+
+use crate::commands::scoring::{self, DraftScoreRequest};
+use crate::database::access::DatabaseAccess;
+use crate::database::analytics;
+use crate::database::cache::{CardCache, CardSnapshot};
+use crate::database::repository::CardData;
+use crate::error::AppResult;
+use crate::scoring::hindsight::{self, PassedOver, RecordedPick, RunReview};
+use crate::scoring::pool::CardPool;
+use crate::session::SessionState;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+/// Number of companion cards returned in a performance drill-down
+const COMPANION_LIMIT: u32 = 5;
+
+/// A card frequently drafted in the same runs as another card
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CompanionCard {
+    pub card_id: String,
+    pub card_name: String,
+    pub runs_together: u32,
+}
+
+/// Aggregated historical performance of a single card
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CardPerformance {
+    pub card_id: String,
+    pub card_name: String,
+    /// Number of draft offers that included this card
+    pub times_offered: u32,
+    /// Number of those offers where this card was picked
+    pub times_picked: u32,
+    /// `times_picked / times_offered`, if the card was ever offered
+    pub pick_rate: Option<f64>,
+    /// Number of runs this card was drafted in
+    pub runs_drafted: u32,
+    /// Drafted runs with a recorded outcome
+    pub runs_completed: u32,
+    pub wins: u32,
+    /// `wins / runs_completed`, if any drafted run has an outcome
+    pub win_rate: Option<f64>,
+    /// Average ring the card was drafted in
+    pub average_ring: Option<f64>,
+    pub common_companions: Vec<CompanionCard>,
+}
+
+/// Number of most-picked cards returned with run aggregates
+const MOST_PICKED_LIMIT: u32 = 10;
+
+/// Default number of runs returned by `get_run_history`
+const DEFAULT_RUN_LIMIT: u32 = 50;
+
+/// One row per run matching the filters. `?1`-`?5` bind champion, covenant,
+/// start date, end date and outcome; `NULL` disables a filter. Archived runs
+/// are left out unless `?6` is true.
+const FILTERED_RUNS: &str = "
+    SELECT run_id,
+           MAX(champion) AS champion,
+           MAX(covenant) AS covenant,
+           MAX(did_win) AS did_win,
+           COUNT(*) AS card_count,
+           AVG(score_at_draft) AS average_score,
+           MIN(created_at) AS started_at,
+           run_id IN (SELECT run_id FROM runs WHERE archived_at IS NOT NULL) AS archived
+    FROM live_deck_history
+    GROUP BY run_id
+    HAVING (?1 IS NULL OR LOWER(MAX(champion)) = LOWER(?1))
+       AND (?2 IS NULL OR MAX(covenant) = ?2)
+       AND (?3 IS NULL OR date(MIN(created_at)) >= date(?3))
+       AND (?4 IS NULL OR date(MIN(created_at)) <= date(?4))
+       AND (?5 IS NULL OR MAX(did_win) = ?5)
+       AND (?6 OR NOT archived)";
+
+/// A player's own record with a card, for the card detail panel
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CardStats {
+    pub card_id: String,
+    /// Number of runs this card was drafted in
+    pub times_picked: u32,
+    /// Win rate of drafted runs with a recorded outcome
+    pub win_rate: Option<f64>,
+    pub average_ring: Option<f64>,
+    pub average_score: Option<f64>,
+}
+
+/// Number of winning cards returned per champion
+const WINNING_CARDS_LIMIT: u32 = 5;
+
+/// Results of a champion's runs at one covenant
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CovenantStats {
+    pub covenant: i32,
+    pub games: u32,
+    pub wins: u32,
+    /// `wins / completed games`, if any game has an outcome
+    pub win_rate: Option<f64>,
+}
+
+/// Aggregated results of one champion and upgrade path
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChampionStats {
+    pub champion: String,
+    pub champion_path: Option<String>,
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub win_rate: Option<f64>,
+    pub by_covenant: Vec<CovenantStats>,
+    /// Cards most often drafted in won runs
+    pub winning_cards: Vec<PickedCard>,
+    /// Average number of cards drafted per run
+    pub average_deck_size: Option<f64>,
+}
+
+/// Filters for `get_run_history`; omitted fields match every run
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct RunHistoryFilter {
+    pub champion: Option<String>,
+    pub covenant: Option<i32>,
+    /// Earliest run start date (`YYYY-MM-DD`), inclusive
+    pub from_date: Option<String>,
+    /// Latest run start date (`YYYY-MM-DD`), inclusive
+    pub to_date: Option<String>,
+    /// Only won (`true`) or lost (`false`) runs
+    pub won: Option<bool>,
+    /// Maximum number of runs listed (default 50)
+    pub limit: Option<u32>,
+    /// List archived runs too
+    pub include_archived: bool,
+}
+
+/// A card and how many runs drafted it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PickedCard {
+    pub card_id: String,
+    pub card_name: String,
+    pub times_picked: u32,
+}
+
+/// Overview of a single run
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunOverview {
+    pub run_id: String,
+    pub champion: Option<String>,
+    pub covenant: Option<i32>,
+    /// `None` while the run has no recorded outcome
+    pub did_win: Option<bool>,
+    pub card_count: u32,
+    pub average_score: Option<f64>,
+    pub started_at: String,
+    /// Hidden from the run list unless archived runs are asked for
+    pub archived: bool,
+}
+
+/// Runs matching a filter, with aggregates over all of them
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunHistory {
+    /// Most recent runs first, up to the filter's limit
+    pub runs: Vec<RunOverview>,
+    pub total_runs: u32,
+    pub wins: u32,
+    pub losses: u32,
+    /// `wins / (wins + losses)`, if any run has an outcome
+    pub win_rate: Option<f64>,
+    /// Average score at draft over every card in the matching runs
+    pub average_score_at_draft: Option<f64>,
+    pub most_picked: Vec<PickedCard>,
+}
+
+/// A card drafted in a run
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunCard {
+    pub card_id: String,
+    pub card_name: String,
+    pub ring_number: i32,
+    pub draft_order: i32,
+    pub score_at_draft: Option<i32>,
+}
+
+/// Full detail of a single run
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunSummary {
+    #[serde(flatten)]
+    pub overview: RunOverview,
+    /// Cards in draft order
+    pub cards: Vec<RunCard>,
+}
+
+/// Fewest offers before a card's pick rate is ranked
+const MIN_OFFERS_FOR_PICK_RATE: u32 = 3;
+/// Fewest completed runs before a synergy pair's win rate is ranked
+const MIN_RUNS_FOR_PAIR: u32 = 2;
+/// Number of rows returned per meta stats table
+const META_STATS_LIMIT: u32 = 10;
+
+/// How often a card was picked when it was offered
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PickRate {
+    pub card_id: String,
+    pub card_name: String,
+    pub times_offered: u32,
+    pub times_picked: u32,
+    pub pick_rate: f64,
+}
+
+/// Average draft scores of won and lost runs' decks
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoreByOutcome {
+    pub winning_runs: u32,
+    pub losing_runs: u32,
+    /// Mean over won runs of each deck's average score at draft
+    pub winning_average: Option<f64>,
+    pub losing_average: Option<f64>,
+}
+
+/// A synergy pair drafted together and how those runs ended
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SynergyPairStats {
+    pub card_a_id: String,
+    pub card_a_name: String,
+    pub card_b_id: String,
+    pub card_b_name: String,
+    pub synergy_type: String,
+    /// Completed runs that drafted both cards
+    pub runs: u32,
+    pub wins: u32,
+    pub win_rate: f64,
+}
+
+/// Tables for the stats dashboard, across every recorded run
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetaStats {
+    /// Highest pick rate first, among cards offered often enough
+    pub most_picked: Vec<PickRate>,
+    pub score_by_outcome: ScoreByOutcome,
+    /// Highest win rate first, among pairs drafted in enough completed runs
+    pub synergy_pairs: Vec<SynergyPairStats>,
+}
+
+/// Rows removed by `purge_deleted_runs`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PurgeReport {
+    pub runs: usize,
+    /// `deck_history` rows
+    pub picks: usize,
+    /// `draft_offers` rows
+    pub offers: usize,
+}
+
+/// Record the options shown at one draft and which one was picked
+///
+/// Returns the number of offer rows written.
+fn record_offer(
+    conn: &Connection,
+    run_id: &str,
+    ring_number: i32,
+    offered_card_ids: &[String],
+    picked_card_id: Option<&str>,
+) -> rusqlite::Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO draft_offers (run_id, ring_number, card_id, was_picked, slot)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+
+        for (i, card_id) in offered_card_ids.iter().enumerate() {
+            let was_picked = picked_card_id == Some(card_id.as_str());
+            let slot = i as i32 + 1;
+            stmt.execute(rusqlite::params![run_id, ring_number, card_id, was_picked, slot])?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(offered_card_ids.len())
+}
+
+/// Aggregate every historical appearance of a card
+///
+/// Returns `None` if the card does not exist.
+pub(crate) fn query_card_performance(
+    conn: &Connection,
+    card_id: &str,
+) -> rusqlite::Result<Option<CardPerformance>> {
+    let card_name: Option<String> = conn
+        .query_row("SELECT name FROM cards WHERE id = ?1", [card_id], |row| row.get(0))
+        .optional()?;
+
+    let Some(card_name) = card_name else {
+        return Ok(None);
+    };
+
+    let (times_offered, times_picked): (u32, u32) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(was_picked), 0) FROM live_draft_offers WHERE card_id = ?1",
+        [card_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let (runs_drafted, runs_completed, wins, average_ring): (u32, u32, u32, Option<f64>) = conn
+        .query_row(
+            "SELECT COUNT(DISTINCT run_id),
+                    COUNT(DISTINCT CASE WHEN did_win IS NOT NULL THEN run_id END),
+                    COUNT(DISTINCT CASE WHEN did_win = 1 THEN run_id END),
+                    AVG(ring_number)
+             FROM live_deck_history
+             WHERE card_id = ?1",
+            [card_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT other.card_id, COALESCE(c.name, other.card_id), COUNT(DISTINCT other.run_id) AS together
+         FROM live_deck_history AS this
+         JOIN live_deck_history AS other
+              ON other.run_id = this.run_id AND other.card_id != this.card_id
+         LEFT JOIN cards AS c ON c.id = other.card_id
+         WHERE this.card_id = ?1
+         GROUP BY other.card_id
+         ORDER BY together DESC, other.card_id
+         LIMIT ?2",
+    )?;
+    let common_companions = stmt
+        .query_map(rusqlite::params![card_id, COMPANION_LIMIT], |row| {
+            Ok(CompanionCard {
+                card_id: row.get(0)?,
+                card_name: row.get(1)?,
+                runs_together: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(Some(CardPerformance {
+        card_id: card_id.to_string(),
+        card_name,
+        times_offered,
+        times_picked,
+        pick_rate: (times_offered > 0).then(|| times_picked as f64 / times_offered as f64),
+        runs_drafted,
+        runs_completed,
+        wins,
+        win_rate: (runs_completed > 0).then(|| wins as f64 / runs_completed as f64),
+        average_ring,
+        common_companions,
+    }))
+}
+
+/// (win rate, completed runs) of every drafted card, counted as
+/// `query_card_performance` counts them
+pub(crate) fn query_win_rates(
+    conn: &Connection,
+) -> rusqlite::Result<HashMap<String, (Option<f64>, u32)>> {
+    let mut stmt = conn.prepare(
+        "SELECT card_id,
+                COUNT(DISTINCT CASE WHEN did_win IS NOT NULL THEN run_id END),
+                COUNT(DISTINCT CASE WHEN did_win = 1 THEN run_id END)
+         FROM live_deck_history
+         GROUP BY card_id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let (runs_completed, wins): (u32, u32) = (row.get(1)?, row.get(2)?);
+        let win_rate = (runs_completed > 0).then(|| wins as f64 / runs_completed as f64);
+        Ok((row.get(0)?, (win_rate, runs_completed)))
+    })?;
+    rows.collect()
+}
+
+/// Summarize the player's drafts of a card from the analytics summaries
+///
+/// The summaries are read as they stand; refresh them first.
+pub(crate) fn query_card_stats(conn: &Connection, card_id: &str) -> rusqlite::Result<CardStats> {
+    let stats = conn
+        .query_row(
+            "SELECT runs_drafted, runs_completed, wins, average_ring, average_score
+             FROM card_stats_summary
+             WHERE card_id = ?1",
+            [card_id],
+            |row| {
+                let completed: u32 = row.get(1)?;
+                let wins: u32 = row.get(2)?;
+                Ok(CardStats {
+                    card_id: card_id.to_string(),
+                    times_picked: row.get(0)?,
+                    win_rate: (completed > 0).then(|| wins as f64 / completed as f64),
+                    average_ring: row.get(3)?,
+                    average_score: row.get(4)?,
+                })
+            },
+        )
+        .optional()?;
+
+    // A card never drafted has no summary row
+    Ok(stats.unwrap_or_else(|| CardStats {
+        card_id: card_id.to_string(),
+        times_picked: 0,
+        win_rate: None,
+        average_ring: None,
+        average_score: None,
+    }))
+}
+
+/// Aggregate every recorded run by champion and upgrade path
+///
+/// Reads the analytics summaries as they stand; refresh them first.
+pub(crate) fn query_champion_stats(conn: &Connection) -> rusqlite::Result<Vec<ChampionStats>> {
+    let mut stmt = conn.prepare(
+        "SELECT champion, champion_path, SUM(games), SUM(wins), SUM(losses),
+                CAST(SUM(cards) AS REAL) / SUM(games)
+         FROM champion_stats_summary
+         GROUP BY champion, champion_path
+         ORDER BY SUM(games) DESC, champion, champion_path",
+    )?;
+    let mut champions = stmt
+        .query_map([], |row| {
+            let wins: u32 = row.get(3)?;
+            let losses: u32 = row.get(4)?;
+            Ok(ChampionStats {
+                champion: row.get(0)?,
+                champion_path: row.get(1)?,
+                games: row.get(2)?,
+                wins,
+                losses,
+                win_rate: (wins + losses > 0).then(|| wins as f64 / (wins + losses) as f64),
+                by_covenant: Vec::new(),
+                winning_cards: Vec::new(),
+                average_deck_size: row.get(5)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut covenant_stmt = conn.prepare(
+        "SELECT covenant, games, wins, wins + losses
+         FROM champion_stats_summary
+         WHERE champion = ?1 AND champion_path IS ?2
+         ORDER BY covenant",
+    )?;
+    let mut cards_stmt = conn.prepare(
+        "SELECT h.card_id, COALESCE(c.name, h.card_id), COUNT(DISTINCT h.run_id) AS picked
+         FROM live_runs AS r
+         JOIN deck_history AS h ON h.run_id = r.run_id
+         LEFT JOIN cards AS c ON c.id = h.card_id
+         WHERE r.champion = ?1 AND r.champion_path IS ?2 AND r.did_win = 1
+         GROUP BY h.card_id
+         ORDER BY picked DESC, h.card_id
+         LIMIT ?3",
+    )?;
+
+    for stats in &mut champions {
+        stats.by_covenant = covenant_stmt
+            .query_map(rusqlite::params![stats.champion, stats.champion_path], |row| {
+                let wins: u32 = row.get(2)?;
+                let completed: u32 = row.get(3)?;
+                Ok(CovenantStats {
+                    covenant: row.get(0)?,
+                    games: row.get(1)?,
+                    wins,
+                    win_rate: (completed > 0).then(|| wins as f64 / completed as f64),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        stats.winning_cards = cards_stmt
+            .query_map(
+                rusqlite::params![stats.champion, stats.champion_path, WINNING_CARDS_LIMIT],
+                |row| {
+                    Ok(PickedCard {
+                        card_id: row.get(0)?,
+                        card_name: row.get(1)?,
+                        times_picked: row.get(2)?,
+                    })
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+    }
+
+    Ok(champions)
+}
+
+fn read_run_overview(row: &rusqlite::Row<'_>) -> rusqlite::Result<RunOverview> {
+    Ok(RunOverview {
+        run_id: row.get(0)?,
+        champion: row.get(1)?,
+        covenant: row.get(2)?,
+        did_win: row.get(3)?,
+        card_count: row.get(4)?,
+        average_score: row.get(5)?,
+        started_at: row.get(6)?,
+        archived: row.get(7)?,
+    })
+}
+
+/// List the runs matching `filter` with win rate, score and pick aggregates
+pub(crate) fn query_run_history(
+    conn: &Connection,
+    filter: &RunHistoryFilter,
+) -> rusqlite::Result<RunHistory> {
+    let params = rusqlite::params![
+        filter.champion,
+        filter.covenant,
+        filter.from_date,
+        filter.to_date,
+        filter.won,
+        filter.include_archived,
+    ];
+
+    let mut stmt = conn.prepare(&format!(
+        "{} ORDER BY started_at DESC, run_id DESC",
+        FILTERED_RUNS
+    ))?;
+    let all_runs = stmt
+        .query_map(params, read_run_overview)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let average_score_at_draft: Option<f64> = conn.query_row(
+        &format!(
+            "WITH filtered AS ({})
+             SELECT AVG(h.score_at_draft)
+             FROM deck_history AS h
+             JOIN filtered ON filtered.run_id = h.run_id",
+            FILTERED_RUNS
+        ),
+        params,
+        |row| row.get(0),
+    )?;
+
+    let mut stmt = conn.prepare(&format!(
+        "WITH filtered AS ({})
+         SELECT h.card_id, COALESCE(c.name, h.card_id), COUNT(DISTINCT h.run_id) AS picked
+         FROM deck_history AS h
+         JOIN filtered ON filtered.run_id = h.run_id
+         LEFT JOIN cards AS c ON c.id = h.card_id
+         GROUP BY h.card_id
+         ORDER BY picked DESC, h.card_id
+         LIMIT {}",
+        FILTERED_RUNS, MOST_PICKED_LIMIT
+    ))?;
+    let most_picked = stmt
+        .query_map(params, |row| {
+            Ok(PickedCard {
+                card_id: row.get(0)?,
+                card_name: row.get(1)?,
+                times_picked: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let wins = all_runs.iter().filter(|r| r.did_win == Some(true)).count() as u32;
+    let losses = all_runs.iter().filter(|r| r.did_win == Some(false)).count() as u32;
+    let total_runs = all_runs.len() as u32;
+
+    let limit = filter.limit.unwrap_or(DEFAULT_RUN_LIMIT) as usize;
+    let runs = all_runs.into_iter().take(limit).collect();
+
+    Ok(RunHistory {
+        runs,
+        total_runs,
+        wins,
+        losses,
+        win_rate: (wins + losses > 0).then(|| wins as f64 / (wins + losses) as f64),
+        average_score_at_draft,
+        most_picked,
+    })
+}
+
+/// Summarize one run
+///
+/// Returns `None` if no cards were recorded for the run.
+pub(crate) fn query_run_summary(
+    conn: &Connection,
+    run_id: &str,
+) -> rusqlite::Result<Option<RunSummary>> {
+    let overview = conn
+        .query_row(
+            "SELECT run_id, MAX(champion), MAX(covenant), MAX(did_win), COUNT(*),
+                    AVG(score_at_draft), MIN(created_at),
+                    run_id IN (SELECT run_id FROM runs WHERE archived_at IS NOT NULL)
+             FROM live_deck_history
+             WHERE run_id = ?1
+             GROUP BY run_id",
+            [run_id],
+            read_run_overview,
+        )
+        .optional()?;
+
+    let Some(overview) = overview else {
+        return Ok(None);
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT h.card_id, COALESCE(c.name, h.card_id), h.ring_number, h.draft_order, h.score_at_draft
+         FROM live_deck_history AS h
+         LEFT JOIN cards AS c ON c.id = h.card_id
+         WHERE h.run_id = ?1
+         ORDER BY h.draft_order, h.id",
+    )?;
+    let cards = stmt
+        .query_map([run_id], |row| {
+            Ok(RunCard {
+                card_id: row.get(0)?,
+                card_name: row.get(1)?,
+                ring_number: row.get(2)?,
+                draft_order: row.get(3)?,
+                score_at_draft: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(Some(RunSummary { overview, cards }))
+}
+
+/// Review a run's picks with hindsight against its final deck
+///
+/// Returns `None` if no cards were recorded for the run. Cards no longer
+/// in the database are left out.
+pub(crate) fn review_run(
+    conn: &Connection,
+    snapshot: &CardSnapshot,
+    run_id: &str,
+) -> Result<Option<RunReview>, String> {
+    let Some(summary) = query_run_summary(conn, run_id)
+        .map_err(|e| format!("Failed to fetch run summary: {}", e))?
+    else {
+        return Ok(None);
+    };
+    let champion_path: Option<String> = conn
+        .query_row(
+            "SELECT champion_path FROM runs WHERE run_id = ?1",
+            [run_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to fetch run: {}", e))?
+        .flatten();
+    let mut stmt = conn
+        .prepare(
+            "SELECT ring_number, card_id, MAX(score) FROM draft_offers
+             WHERE run_id = ?1 AND was_picked = 0
+             GROUP BY ring_number, card_id
+             ORDER BY MIN(id)",
+        )
+        .map_err(|e| e.to_string())?;
+    let passed_over: Vec<(i32, String, Option<i32>)> = stmt
+        .query_map([run_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .and_then(|rows| rows.collect())
+        .map_err(|e| format!("Failed to fetch draft offers: {}", e))?;
+
+    let champion = summary.overview.champion.clone().unwrap_or_default();
+    let covenant = summary.overview.covenant.unwrap_or(0);
+    let request = DraftScoreRequest {
+        card_id: String::new(),
+        current_deck: Vec::new(),
+        champion: champion.clone(),
+        ring_number: 1,
+        covenant,
+        champion_path,
+        upcoming_boss: None,
+        pact_shards: None,
+        upgrade_count: 0,
+    };
+    let tables = scoring::load_scoring_tables(conn, &request).map_err(|e| e.to_string())?;
+
+    let picks: Vec<RecordedPick> = summary
+        .cards
+        .iter()
+        .filter_map(|card| {
+            snapshot.get(&card.card_id).map(|data| RecordedPick {
+                card: data.clone(),
+                ring_number: card.ring_number,
+                draft_order: card.draft_order,
+                score_at_draft: card.score_at_draft,
+            })
+        })
+        .collect();
+    // Offers picked from later in the same ring are not alternatives
+    let passed_over: Vec<PassedOver> = passed_over
+        .into_iter()
+        .filter(|(ring, id, _)| {
+            !summary
+                .cards
+                .iter()
+                .any(|c| c.card_id == *id && c.ring_number == *ring)
+        })
+        .filter_map(|(ring_number, id, score_at_draft)| {
+            snapshot.get(&id).map(|card| PassedOver {
+                card: card.clone(),
+                ring_number,
+                score_at_draft,
+            })
+        })
+        .collect();
+    let final_deck: Vec<CardData> = picks.iter().map(|pick| pick.card.clone()).collect();
+    let pool = CardPool::new(snapshot, &tables, &champion, covenant, 0, &final_deck);
+
+    let reviews = hindsight::review_picks(&pool, &picks, &passed_over);
+    let missed: Vec<i32> = reviews
+        .iter()
+        .filter(|review| review.missed)
+        .filter_map(|review| {
+            let alternative = review.best_alternative.as_ref()?;
+            Some(alternative.hindsight_score - review.hindsight_score)
+        })
+        .collect();
+
+    Ok(Some(RunReview {
+        run_id: summary.overview.run_id,
+        champion,
+        covenant,
+        did_win: summary.overview.did_win,
+        picks: reviews,
+        missed_picks: missed.len(),
+        value_lost: missed.iter().sum(),
+    }))
+}
+
+/// Build the stats dashboard tables from `draft_offers` and `deck_history`
+pub(crate) fn query_meta_stats(conn: &Connection) -> rusqlite::Result<MetaStats> {
+    let mut stmt = conn.prepare(
+        "SELECT o.card_id, COALESCE(c.name, o.card_id), COUNT(*) AS offered,
+                SUM(o.was_picked = 1) AS picked
+         FROM live_draft_offers AS o
+         LEFT JOIN cards AS c ON c.id = o.card_id
+         GROUP BY o.card_id
+         HAVING offered >= ?1
+         ORDER BY CAST(picked AS REAL) / offered DESC, offered DESC, o.card_id
+         LIMIT ?2",
+    )?;
+    let most_picked = stmt
+        .query_map(rusqlite::params![MIN_OFFERS_FOR_PICK_RATE, META_STATS_LIMIT], |row| {
+            let times_offered: u32 = row.get(2)?;
+            let times_picked: u32 = row.get(3)?;
+            Ok(PickRate {
+                card_id: row.get(0)?,
+                card_name: row.get(1)?,
+                times_offered,
+                times_picked,
+                pick_rate: times_picked as f64 / times_offered as f64,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let score_by_outcome = conn.query_row(
+        "SELECT COALESCE(SUM(did_win = 1), 0),
+                COALESCE(SUM(did_win = 0), 0),
+                AVG(CASE WHEN did_win = 1 THEN average_score END),
+                AVG(CASE WHEN did_win = 0 THEN average_score END)
+         FROM (SELECT MAX(did_win) AS did_win, AVG(score_at_draft) AS average_score
+               FROM live_deck_history
+               GROUP BY run_id)",
+        [],
+        |row| {
+            Ok(ScoreByOutcome {
+                winning_runs: row.get(0)?,
+                losing_runs: row.get(1)?,
+                winning_average: row.get(2)?,
+                losing_average: row.get(3)?,
+            })
+        },
+    )?;
+
+    // Wildcard synergies name no second card, so the join drops them
+    let mut stmt = conn.prepare(
+        "SELECT s.card_a_id, COALESCE(ca.name, s.card_a_id),
+                s.card_b_id, COALESCE(cb.name, s.card_b_id),
+                s.synergy_type,
+                COUNT(DISTINCT a.run_id) AS runs,
+                COUNT(DISTINCT CASE WHEN a.did_win = 1 THEN a.run_id END) AS wins
+         FROM synergies AS s
+         JOIN live_deck_history AS a ON a.card_id = s.card_a_id
+         JOIN live_deck_history AS b
+              ON b.run_id = a.run_id AND b.card_id = s.card_b_id AND b.id <> a.id
+         LEFT JOIN cards AS ca ON ca.id = s.card_a_id
+         LEFT JOIN cards AS cb ON cb.id = s.card_b_id
+         WHERE a.did_win IS NOT NULL
+         GROUP BY s.card_a_id, s.card_b_id, s.synergy_type
+         HAVING runs >= ?1
+         ORDER BY CAST(wins AS REAL) / runs DESC, runs DESC, s.card_a_id, s.card_b_id
+         LIMIT ?2",
+    )?;
+    let synergy_pairs = stmt
+        .query_map(rusqlite::params![MIN_RUNS_FOR_PAIR, META_STATS_LIMIT], |row| {
+            let runs: u32 = row.get(5)?;
+            let wins: u32 = row.get(6)?;
+            Ok(SynergyPairStats {
+                card_a_id: row.get(0)?,
+                card_a_name: row.get(1)?,
+                card_b_id: row.get(2)?,
+                card_b_name: row.get(3)?,
+                synergy_type: row.get(4)?,
+                runs,
+                wins,
+                win_rate: wins as f64 / runs as f64,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(MetaStats {
+        most_picked,
+        score_by_outcome,
+        synergy_pairs,
+    })
+}
+
+/// Archive or unarchive a run
+///
+/// Returns `false` if there is no such run, or it was deleted.
+pub(crate) fn set_run_archived(
+    conn: &Connection,
+    run_id: &str,
+    archived: bool,
+) -> rusqlite::Result<bool> {
+    let changed = conn.execute(
+        "UPDATE runs
+         SET archived_at = CASE WHEN ?2 THEN COALESCE(archived_at, CURRENT_TIMESTAMP) END
+         WHERE run_id = ?1 AND deleted_at IS NULL",
+        rusqlite::params![run_id, archived],
+    )?;
+    Ok(changed > 0)
+}
+
+/// Soft-delete or restore a run
+///
+/// A deleted run keeps its rows until `purge_runs`, but drops out of the
+/// run list and every stat. Returns `false` if there is no such run.
+pub(crate) fn set_run_deleted(
+    conn: &Connection,
+    run_id: &str,
+    deleted: bool,
+) -> rusqlite::Result<bool> {
+    let changed = conn.execute(
+        "UPDATE runs
+         SET deleted_at = CASE WHEN ?2 THEN COALESCE(deleted_at, CURRENT_TIMESTAMP) END
+         WHERE run_id = ?1",
+        rusqlite::params![run_id, deleted],
+    )?;
+    Ok(changed > 0)
+}
+
+/// Permanently remove deleted runs with their picks, offers and queued uploads
+pub(crate) fn purge_runs(conn: &Connection) -> rusqlite::Result<PurgeReport> {
+    let tx = conn.unchecked_transaction()?;
+    let picks = tx.execute(
+        "DELETE FROM deck_history
+         WHERE run_id IN (SELECT run_id FROM runs WHERE deleted_at IS NOT NULL)",
+        [],
+    )?;
+    let offers = tx.execute(
+        "DELETE FROM draft_offers
+         WHERE run_id IN (SELECT run_id FROM runs WHERE deleted_at IS NOT NULL)",
+        [],
+    )?;
+    tx.execute(
+        "DELETE FROM upload_queue
+         WHERE run_id IN (SELECT run_id FROM runs WHERE deleted_at IS NOT NULL)",
+        [],
+    )?;
+    let runs = tx.execute("DELETE FROM runs WHERE deleted_at IS NOT NULL", [])?;
+    tx.commit()?;
+
+    Ok(PurgeReport {
+        runs,
+        picks,
+        offers,
+    })
+}
+
+/// Tauri command: Record the cards offered at a draft and the pick made
+#[tauri::command]
+pub fn record_draft_offer(
+    run_id: String,
+    ring_number: i32,
+    offered_card_ids: Vec<String>,
+    picked_card_id: Option<String>,
+    access: State<DatabaseAccess>,
+) -> Result<usize, String> {
+    if run_id.trim().is_empty() {
+        return Err("Run ID cannot be empty".to_string());
+    }
+    if offered_card_ids.is_empty() {
+        return Err("At least one offered card is required".to_string());
+    }
+    if let Some(ref picked) = picked_card_id {
+        if !offered_card_ids.contains(picked) {
+            return Err(format!("Picked card '{}' was not offered", picked));
+        }
+    }
+
+    access
+        .write_and_wait(move |conn| {
+            record_offer(
+                conn,
+                &run_id,
+                ring_number,
+                &offered_card_ids,
+                picked_card_id.as_deref(),
+            )
+        })
+        .map_err(|e| format!("Failed to record draft offer: {}", e))
+}
+
+/// Bring the analytics summaries up to date on the writer, so reads after
+/// it see every recorded run
+fn refresh_summaries(access: &DatabaseAccess) -> rusqlite::Result<()> {
+    access.write_and_wait(|conn| analytics::refresh_analytics(conn).map(|_| ()))
+}
+
+/// Tauri command: Get cross-run performance data for a card
+#[tauri::command]
+pub fn get_card_performance(
+    card_id: String,
+    access: State<DatabaseAccess>,
+) -> Result<CardPerformance, String> {
+    if card_id.trim().is_empty() {
+        return Err("Card ID cannot be empty".to_string());
+    }
+
+    access
+        .read(|conn| query_card_performance(conn, &card_id))
+        .map_err(|e| format!("Failed to fetch card performance: {}", e))?
+        .ok_or_else(|| format!("Card '{}' not found", card_id))
+}
+
+/// Tauri command: Get the player's own stats for a card
+#[tauri::command]
+pub fn get_card_stats(card_id: String, access: State<DatabaseAccess>) -> Result<CardStats, String> {
+    if card_id.trim().is_empty() {
+        return Err("Card ID cannot be empty".to_string());
+    }
+
+    refresh_summaries(&access)
+        .and_then(|()| access.read(|conn| query_card_stats(conn, &card_id)))
+        .map_err(|e| format!("Failed to fetch card stats: {}", e))
+}
+
+/// Tauri command: Get win rates and common cards for each champion and path
+#[tauri::command]
+pub fn get_champion_stats(access: State<DatabaseAccess>) -> Result<Vec<ChampionStats>, String> {
+    refresh_summaries(&access)
+        .and_then(|()| access.read(query_champion_stats))
+        .map_err(|e| format!("Failed to fetch champion stats: {}", e))
+}
+
+/// Tauri command: List past runs matching the filters, with aggregate stats
+#[tauri::command]
+pub fn get_run_history(
+    filters: Option<RunHistoryFilter>,
+    access: State<DatabaseAccess>,
+) -> Result<RunHistory, String> {
+    let filters = filters.unwrap_or_default();
+    if filters.limit == Some(0) {
+        return Err("Limit must be greater than 0".to_string());
+    }
+
+    access
+        .read(|conn| query_run_history(conn, &filters))
+        .map_err(|e| format!("Failed to fetch run history: {}", e))
+}
+
+/// Tauri command: Get the cards and stats of a single run
+#[tauri::command]
+pub fn get_run_summary(
+    run_id: String,
+    access: State<DatabaseAccess>,
+) -> Result<RunSummary, String> {
+    if run_id.trim().is_empty() {
+        return Err("Run ID cannot be empty".to_string());
+    }
+
+    access
+        .read(|conn| query_run_summary(conn, &run_id))
+        .map_err(|e| format!("Failed to fetch run summary: {}", e))?
+        .ok_or_else(|| format!("Run '{}' not found", run_id))
+}
+
+/// Tauri command: Rescore a run's picks against its final deck and flag
+/// the ones a passed-over card would have beaten
+#[tauri::command]
+pub fn analyze_run(
+    run_id: String,
+    access: State<DatabaseAccess>,
+    cache: State<CardCache>,
+) -> Result<RunReview, String> {
+    if run_id.trim().is_empty() {
+        return Err("Run ID cannot be empty".to_string());
+    }
+
+    let review = access
+        .read(|conn| -> AppResult<_> {
+            let snapshot = cache.get(conn)?;
+            Ok(review_run(conn, &snapshot, &run_id)?)
+        })
+        .map_err(|e| e.to_string())?;
+    review.ok_or_else(|| format!("Run '{}' not found", run_id))
+}
+
+/// Tauri command: Get pick rates, scores by outcome and winning synergy
+/// pairs across every recorded run
+#[tauri::command]
+pub fn get_meta_stats(access: State<DatabaseAccess>) -> Result<MetaStats, String> {
+    access
+        .read(query_meta_stats)
+        .map_err(|e| format!("Failed to fetch meta stats: {}", e))
+}
+
+/// Tauri command: Archive a run, or unarchive it with `archived: false`
+///
+/// Archived runs are hidden from the run list but still count in stats.
+#[tauri::command]
+pub fn archive_run(
+    run_id: String,
+    archived: Option<bool>,
+    access: State<DatabaseAccess>,
+) -> Result<(), String> {
+    let archived = archived.unwrap_or(true);
+    let id = run_id.clone();
+    let found = access
+        .write_and_wait(move |conn| set_run_archived(conn, &id, archived))
+        .map_err(|e| format!("Failed to archive run: {}", e))?;
+    if !found {
+        return Err(format!("Run '{}' not found", run_id));
+    }
+    Ok(())
+}
+
+/// Tauri command: Delete a run, leaving it out of the history and stats
+///
+/// The run can be restored until `purge_deleted_runs` removes it for good.
+/// The run in progress can't be deleted.
+#[tauri::command]
+pub fn delete_run(
+    run_id: String,
+    access: State<DatabaseAccess>,
+    session_state: State<SessionState>,
+) -> Result<(), String> {
+    if session_state.snapshot()?.run_id == run_id {
+        return Err("Cannot delete the run in progress".to_string());
+    }
+
+    let id = run_id.clone();
+    let found = access
+        .write_and_wait(move |conn| set_run_deleted(conn, &id, true))
+        .map_err(|e| format!("Failed to delete run: {}", e))?;
+    if !found {
+        return Err(format!("Run '{}' not found", run_id));
+    }
+    log::info!("Deleted run {}", run_id);
+    Ok(())
+}
+
+/// Tauri command: Restore a deleted run that hasn't been purged
+#[tauri::command]
+pub fn restore_run(run_id: String, access: State<DatabaseAccess>) -> Result<(), String> {
+    let id = run_id.clone();
+    let found = access
+        .write_and_wait(move |conn| set_run_deleted(conn, &id, false))
+        .map_err(|e| format!("Failed to restore run: {}", e))?;
+    if !found {
+        return Err(format!("Run '{}' not found", run_id));
+    }
+    Ok(())
+}
+
+/// Tauri command: Permanently remove every deleted run
+#[tauri::command]
+pub fn purge_deleted_runs(access: State<DatabaseAccess>) -> Result<PurgeReport, String> {
+    let report = access
+        .write_and_wait(purge_runs)
+        .map_err(|e| format!("Failed to purge runs: {}", e))?;
+    log::info!(
+        "Purged {} deleted runs ({} picks, {} offers)",
+        report.runs,
+        report.picks,
+        report.offers
+    );
+    Ok(report)
+}
+
+/// Refresh the analytics summaries on the writer thread
+///
+/// Queued after each completed run, so the stats commands usually find
+/// nothing left to recompute.
+pub fn refresh_analytics_in_background(access: &DatabaseAccess) {
+    access.write(|conn| match analytics::refresh_analytics(conn) {
+        Ok(report) => log::debug!(
+            "Refreshed analytics for {} cards and {} champions",
+            report.cards,
+            report.champions
+        ),
+        Err(e) => log::warn!("Background analytics refresh failed: {}", e),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn setup_test_db() -> (Connection, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        (conn, temp_file)
+    }
+
+    fn insert_drafted(conn: &Connection, run_id: &str, card_id: &str, ring: i32, did_win: Option<bool>) {
+        conn.execute(
+            "INSERT INTO deck_history (run_id, card_id, ring_number, draft_order, champion, covenant, did_win)
+             VALUES (?1, ?2, ?3, 1, 'Fel', 10, ?4)",
+            rusqlite::params![run_id, card_id, ring, did_win],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_card_performance_aggregates_history() {
+        let (conn, _temp) = setup_test_db();
+
+        let offered = vec!["banished_talos".to_string(), "banished_cleave".to_string()];
+        record_offer(&conn, "run1", 1, &offered, Some("banished_talos")).unwrap();
+        record_offer(&conn, "run2", 2, &offered, Some("banished_cleave")).unwrap();
+
+        insert_drafted(&conn, "run1", "banished_talos", 1, Some(true));
+        insert_drafted(&conn, "run1", "banished_fel", 1, Some(true));
+        insert_drafted(&conn, "run2", "banished_talos", 3, Some(false));
+        insert_drafted(&conn, "run2", "banished_fel", 2, Some(false));
+        insert_drafted(&conn, "run3", "banished_talos", 2, None);
+
+        let perf = query_card_performance(&conn, "banished_talos").unwrap().unwrap();
+        assert_eq!(perf.card_name, "Talos");
+        assert_eq!(perf.times_offered, 2);
+        assert_eq!(perf.times_picked, 1);
+        assert_eq!(perf.pick_rate, Some(0.5));
+        assert_eq!(perf.runs_drafted, 3);
+        assert_eq!(perf.runs_completed, 2);
+        assert_eq!(perf.wins, 1);
+        assert_eq!(perf.win_rate, Some(0.5));
+        assert!((perf.average_ring.unwrap() - 2.0).abs() < 0.001);
+        assert_eq!(perf.common_companions.len(), 1);
+        assert_eq!(perf.common_companions[0].card_id, "banished_fel");
+        assert_eq!(perf.common_companions[0].runs_together, 2);
+
+        let win_rates = query_win_rates(&conn).unwrap();
+        assert_eq!(win_rates["banished_talos"], (Some(0.5), 2));
+        assert_eq!(win_rates["banished_fel"], (Some(0.5), 2));
+        assert!(!win_rates.contains_key("banished_cleave"));
+    }
+
+    #[test]
+    fn test_card_performance_without_history() {
+        let (conn, _temp) = setup_test_db();
+
+        let perf = query_card_performance(&conn, "banished_fel").unwrap().unwrap();
+        assert_eq!(perf.times_offered, 0);
+        assert!(perf.pick_rate.is_none());
+        assert!(perf.win_rate.is_none());
+        assert!(perf.average_ring.is_none());
+        assert!(perf.common_companions.is_empty());
+
+        assert!(query_card_performance(&conn, "not_a_card").unwrap().is_none());
+    }
+
+    /// Run ID, champion, covenant and outcome shared by a run's rows
+    type Run<'a> = (&'a str, &'a str, i32, Option<bool>);
+
+    const RUN1: Run = ("run1", "Fel", 10, Some(true));
+    const RUN2: Run = ("run2", "Talos", 5, Some(false));
+    const RUN3: Run = ("run3", "Fel", 10, None);
+
+    fn insert_run_card(conn: &Connection, run: Run, card_id: &str, score: i32, created_at: &str) {
+        let (run_id, champion, covenant, did_win) = run;
+        conn.execute(
+            "INSERT INTO deck_history
+                (run_id, card_id, ring_number, draft_order, champion, covenant, score_at_draft, did_win, created_at)
+             VALUES (?1, ?2, 1, (SELECT COUNT(*) + 1 FROM deck_history WHERE run_id = ?1), ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![run_id, card_id, champion, covenant, score, did_win, created_at],
+        )
+        .unwrap();
+    }
+
+    fn seed_runs(conn: &Connection) {
+        insert_run_card(conn, RUN1, "banished_fel", 80, "2026-01-05 10:00:00");
+        insert_run_card(conn, RUN1, "banished_cleave", 60, "2026-01-05 10:05:00");
+        insert_run_card(conn, RUN2, "banished_talos", 40, "2026-02-10 12:00:00");
+        insert_run_card(conn, RUN2, "banished_cleave", 50, "2026-02-10 12:03:00");
+        insert_run_card(conn, RUN3, "banished_fel", 70, "2026-03-01 09:00:00");
+    }
+
+    #[test]
+    fn test_run_history_aggregates_all_runs() {
+        let (conn, _temp) = setup_test_db();
+        seed_runs(&conn);
+
+        let history = query_run_history(&conn, &RunHistoryFilter::default()).unwrap();
+        assert_eq!(history.total_runs, 3);
+        let ids: Vec<_> = history.runs.iter().map(|r| r.run_id.as_str()).collect();
+        assert_eq!(ids, ["run3", "run2", "run1"]);
+        assert_eq!(history.wins, 1);
+        assert_eq!(history.losses, 1);
+        assert_eq!(history.win_rate, Some(0.5));
+        assert!((history.average_score_at_draft.unwrap() - 60.0).abs() < 0.001);
+
+        let top: Vec<_> = history
+            .most_picked
+            .iter()
+            .map(|c| (c.card_id.as_str(), c.times_picked))
+            .collect();
+        assert_eq!(top[..2], [("banished_cleave", 2), ("banished_fel", 2)]);
+        assert_eq!(history.most_picked[0].card_name, "Cleave");
+    }
+
+    #[test]
+    fn test_run_history_filters() {
+        let (conn, _temp) = setup_test_db();
+        seed_runs(&conn);
+
+        let by_champion = RunHistoryFilter {
+            champion: Some("fel".to_string()),
+            ..Default::default()
+        };
+        let history = query_run_history(&conn, &by_champion).unwrap();
+        assert_eq!(history.total_runs, 2);
+        assert_eq!(history.win_rate, Some(1.0));
+        assert_eq!(history.most_picked[0].card_id, "banished_fel");
+
+        let lost = RunHistoryFilter {
+            won: Some(false),
+            ..Default::default()
+        };
+        let history = query_run_history(&conn, &lost).unwrap();
+        assert_eq!(history.runs.len(), 1);
+        assert_eq!(history.runs[0].run_id, "run2");
+        assert_eq!(history.runs[0].covenant, Some(5));
+
+        let february = RunHistoryFilter {
+            from_date: Some("2026-02-01".to_string()),
+            to_date: Some("2026-02-10".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query_run_history(&conn, &february).unwrap().total_runs, 1);
+
+        let limited = RunHistoryFilter {
+            covenant: Some(10),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let history = query_run_history(&conn, &limited).unwrap();
+        assert_eq!(history.total_runs, 2);
+        assert_eq!(history.runs.len(), 1);
+        assert_eq!(history.runs[0].run_id, "run3");
+    }
+
+    #[test]
+    fn test_archived_and_deleted_runs() {
+        let (conn, _temp) = setup_test_db();
+        seed_runs(&conn);
+        conn.execute_batch(
+            "INSERT INTO runs (run_id, champion, covenant, did_win)
+             VALUES ('run1', 'Fel', 10, 1), ('run2', 'Talos', 5, 0);",
+        )
+        .unwrap();
+        let offered = vec!["banished_talos".to_string(), "banished_cleave".to_string()];
+        record_offer(&conn, "run2", 1, &offered, Some("banished_talos")).unwrap();
+
+        // Archived runs leave the run list but still count in stats
+        assert!(set_run_archived(&conn, "run1", true).unwrap());
+        assert_eq!(query_run_history(&conn, &RunHistoryFilter::default()).unwrap().total_runs, 2);
+        let all = RunHistoryFilter {
+            include_archived: true,
+            ..Default::default()
+        };
+        let history = query_run_history(&conn, &all).unwrap();
+        assert_eq!(history.total_runs, 3);
+        assert!(history.runs.iter().any(|run| run.run_id == "run1" && run.archived));
+        let fel = query_card_performance(&conn, "banished_fel").unwrap().unwrap();
+        assert_eq!(fel.runs_drafted, 2);
+
+        // Deleted runs drop out of everything until restored
+        assert!(set_run_deleted(&conn, "run2", true).unwrap());
+        assert_eq!(query_run_history(&conn, &all).unwrap().total_runs, 2);
+        assert!(query_run_summary(&conn, "run2").unwrap().is_none());
+        let talos = query_card_performance(&conn, "banished_talos").unwrap().unwrap();
+        assert_eq!((talos.times_offered, talos.runs_drafted), (0, 0));
+        analytics::refresh_analytics(&conn).unwrap();
+        let champions = query_champion_stats(&conn).unwrap();
+        assert!(champions.iter().all(|stats| stats.champion != "Talos"));
+        assert!(!set_run_archived(&conn, "run2", true).unwrap());
+        assert!(set_run_deleted(&conn, "run2", false).unwrap());
+        assert_eq!(query_run_history(&conn, &all).unwrap().total_runs, 3);
+
+        // Purging removes a deleted run's rows for good
+        assert!(set_run_deleted(&conn, "run2", true).unwrap());
+        let report = purge_runs(&conn).unwrap();
+        assert_eq!(
+            report,
+            PurgeReport {
+                runs: 1,
+                picks: 2,
+                offers: 2
+            }
+        );
+        assert!(!set_run_deleted(&conn, "run2", false).unwrap());
+    }
+
+    #[test]
+    fn test_run_summary() {
+        let (conn, _temp) = setup_test_db();
+        seed_runs(&conn);
+
+        let summary = query_run_summary(&conn, "run1").unwrap().unwrap();
+        assert_eq!(summary.overview.champion.as_deref(), Some("Fel"));
+        assert_eq!(summary.overview.did_win, Some(true));
+        assert_eq!(summary.overview.card_count, 2);
+        assert_eq!(summary.overview.average_score, Some(70.0));
+        assert_eq!(summary.overview.started_at, "2026-01-05 10:00:00");
+        assert_eq!(summary.cards.len(), 2);
+        assert_eq!(summary.cards[0].card_id, "banished_fel");
+        assert_eq!(summary.cards[1].draft_order, 2);
+        assert_eq!(summary.cards[1].score_at_draft, Some(60));
+
+        assert!(query_run_summary(&conn, "missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_review_run() {
+        let (conn, _temp) = setup_test_db();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+        insert_drafted(&conn, "hindsight", "banished_fel", 1, Some(false));
+
+        // Cleave over Fel's favorite Just Cause
+        let offered = ["banished_cleave".to_string(), "banished_just_cause".to_string()];
+        record_offer(&conn, "hindsight", 1, &offered, Some("banished_cleave")).unwrap();
+        insert_drafted(&conn, "hindsight", "banished_cleave", 1, Some(false));
+        // A sound pick over an off-clan unit
+        let offered = [
+            "banished_steadfast_crusader".to_string(),
+            "pyreborne_gildmonger".to_string(),
+        ];
+        record_offer(&conn, "hindsight", 2, &offered, Some("banished_steadfast_crusader"))
+            .unwrap();
+        insert_drafted(&conn, "hindsight", "banished_steadfast_crusader", 2, Some(false));
+        conn.execute(
+            "UPDATE draft_offers SET score = 90 WHERE card_id = 'banished_just_cause'",
+            [],
+        )
+        .unwrap();
+
+        let review = review_run(&conn, &snapshot, "hindsight").unwrap().unwrap();
+        // The champion isn't a pick to review
+        assert_eq!(review.picks.len(), 2);
+        assert_eq!(review.picks[0].card_id, "banished_cleave");
+        assert!(review.picks[0].missed);
+        let alternative = review.picks[0].best_alternative.as_ref().unwrap();
+        assert_eq!(alternative.card_id, "banished_just_cause");
+        assert_eq!(alternative.score_at_draft, Some(90));
+        assert!(!review.picks[1].missed);
+        assert_eq!(review.missed_picks, 1);
+        assert_eq!(
+            review.value_lost,
+            alternative.hindsight_score - review.picks[0].hindsight_score
+        );
+        assert!(review_run(&conn, &snapshot, "missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_meta_stats() {
+        let (conn, _temp) = setup_test_db();
+        seed_runs(&conn);
+
+        let offered = ["banished_cleave".to_string(), "banished_talos".to_string()];
+        for (run_id, ring) in [("run1", 1), ("run2", 1), ("run2", 2)] {
+            record_offer(&conn, run_id, ring, &offered, Some("banished_cleave")).unwrap();
+        }
+        // Just Cause with Fel wins once and loses once; with Karmic Censer, always wins
+        for (run_id, did_win) in [("pair1", true), ("pair2", false), ("pair3", true)] {
+            insert_drafted(&conn, run_id, "banished_just_cause", 1, Some(did_win));
+        }
+        insert_drafted(&conn, "pair1", "banished_fel", 1, Some(true));
+        insert_drafted(&conn, "pair2", "banished_fel", 1, Some(false));
+        insert_drafted(&conn, "pair1", "banished_karmic_censer", 1, Some(true));
+        insert_drafted(&conn, "pair3", "banished_karmic_censer", 1, Some(true));
+
+        let stats = query_meta_stats(&conn).unwrap();
+        let rates: Vec<_> = stats
+            .most_picked
+            .iter()
+            .map(|c| (c.card_id.as_str(), c.times_offered, c.pick_rate))
+            .collect();
+        assert_eq!(rates, [("banished_cleave", 3, 1.0), ("banished_talos", 3, 0.0)]);
+
+        // The pair runs have no scores, so only run1 and run2 are averaged
+        let outcome = &stats.score_by_outcome;
+        assert_eq!((outcome.winning_runs, outcome.losing_runs), (3, 2));
+        assert_eq!(outcome.winning_average, Some(70.0));
+        assert_eq!(outcome.losing_average, Some(45.0));
+
+        let pairs: Vec<_> = stats
+            .synergy_pairs
+            .iter()
+            .map(|p| (p.card_a_id.as_str(), p.runs, p.wins))
+            .collect();
+        assert_eq!(pairs, [("banished_karmic_censer", 2, 2), ("banished_fel", 2, 1)]);
+        assert_eq!(stats.synergy_pairs[1].card_b_name, "Just Cause");
+        assert_eq!(stats.synergy_pairs[1].win_rate, 0.5);
+    }
+
+    #[test]
+    fn test_card_stats() {
+        let (conn, _temp) = setup_test_db();
+        seed_runs(&conn);
+        analytics::refresh_analytics(&conn).unwrap();
+
+        let stats = query_card_stats(&conn, "banished_cleave").unwrap();
+        assert_eq!(stats.times_picked, 2);
+        assert_eq!(stats.win_rate, Some(0.5));
+        assert_eq!(stats.average_ring, Some(1.0));
+        assert_eq!(stats.average_score, Some(55.0));
+
+        let stats = query_card_stats(&conn, "banished_just_cause").unwrap();
+        assert_eq!(stats.times_picked, 0);
+        assert!(stats.win_rate.is_none());
+        assert!(stats.average_score.is_none());
+    }
+
+    #[test]
+    fn test_champion_stats() {
+        let (conn, _temp) = setup_test_db();
+        seed_runs(&conn);
+        conn.execute_batch(
+            "INSERT INTO runs (run_id, champion, champion_path, covenant, did_win) VALUES
+                ('run1', 'Fel', 'Unchained', 10, 1),
+                ('run2', 'Talos', NULL, 5, 0),
+                ('run3', 'Fel', 'Unchained', 10, NULL),
+                ('run4', 'Fel', 'Unchained', 5, 0);",
+        )
+        .unwrap();
+        analytics::refresh_analytics(&conn).unwrap();
+
+        let stats = query_champion_stats(&conn).unwrap();
+        assert_eq!(stats.len(), 2);
+
+        let fel = &stats[0];
+        assert_eq!(fel.champion, "Fel");
+        assert_eq!(fel.champion_path.as_deref(), Some("Unchained"));
+        assert_eq!((fel.games, fel.wins, fel.losses), (3, 1, 1));
+        assert_eq!(fel.win_rate, Some(0.5));
+        assert!((fel.average_deck_size.unwrap() - 1.0).abs() < 0.001);
+        assert_eq!(
+            fel.by_covenant,
+            vec![
+                CovenantStats {
+                    covenant: 5,
+                    games: 1,
+                    wins: 0,
+                    win_rate: Some(0.0),
+                },
+                CovenantStats {
+                    covenant: 10,
+                    games: 2,
+                    wins: 1,
+                    win_rate: Some(1.0),
+                },
+            ]
+        );
+        let winning: Vec<_> = fel.winning_cards.iter().map(|c| c.card_id.as_str()).collect();
+        assert_eq!(winning, ["banished_cleave", "banished_fel"]);
+
+        let talos = &stats[1];
+        assert!(talos.champion_path.is_none());
+        assert_eq!(talos.win_rate, Some(0.0));
+        assert!(talos.winning_cards.is_empty());
+    }
+}
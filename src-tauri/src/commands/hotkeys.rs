@@ -0,0 +1,45 @@
+//! Global hotkey commands
+//!
+//! Reads and reconfigures the shortcuts registered by `crate::hotkeys`.
+
+use crate::database::access::DatabaseAccess;
+use crate::hotkeys::{self, HotkeyBindings, HotkeyState};
+use crate::settings;
+use tauri::{AppHandle, State};
+
+/// Tauri command: Get the current hotkey bindings
+#[tauri::command]
+pub fn get_hotkey_bindings(hotkey_state: State<HotkeyState>) -> Result<HotkeyBindings, String> {
+    Ok(hotkey_state.bindings())
+}
+
+/// Tauri command: Replace the hotkey bindings
+///
+/// Accelerators are normalized (e.g. `ctrl+shift+d` becomes `Ctrl+Shift+D`)
+/// and the saved bindings are returned. A `null` or empty accelerator
+/// unbinds that action.
+#[tauri::command]
+pub fn set_hotkey_bindings(
+    bindings: HotkeyBindings,
+    app: AppHandle,
+    access: State<DatabaseAccess>,
+    hotkey_state: State<HotkeyState>,
+) -> Result<HotkeyBindings, String> {
+    let saved =
+        hotkeys::rebind(&app, &access, &hotkey_state, &bindings).map_err(|e| e.to_string())?;
+    settings::notify_changed(&app, &access);
+    Ok(saved)
+}
+
+/// Tauri command: Restore the default hotkey bindings
+#[tauri::command]
+pub fn reset_hotkey_bindings(
+    app: AppHandle,
+    access: State<DatabaseAccess>,
+    hotkey_state: State<HotkeyState>,
+) -> Result<HotkeyBindings, String> {
+    let saved = hotkeys::rebind(&app, &access, &hotkey_state, &HotkeyBindings::default())
+        .map_err(|e| e.to_string())?;
+    settings::notify_changed(&app, &access);
+    Ok(saved)
+}
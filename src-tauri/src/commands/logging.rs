@@ -0,0 +1,154 @@
+//! Log, log level, diagnostics, crash report and performance commands
+
+use crate::commands::ocr::OcrState;
+use crate::crash::{self, CrashReport};
+use crate::database::access::DatabaseAccess;
+use crate::diagnostics::{self, DiagnosticsSources, DiagnosticsSummary};
+use crate::error::{AppError, AppResult};
+use crate::logging::{self, LogEntry, LogFileInfo, LogQuery};
+use crate::ocr;
+use crate::perf::{self, StageMetrics};
+use crate::settings::{self, LoggingPreferences};
+use log::LevelFilter;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, State};
+
+/// Tauri command: List the current log file and its rotated archives
+///
+/// The current file comes first, then archives from newest to oldest.
+#[tauri::command]
+pub fn get_log_files() -> Vec<LogFileInfo> {
+    logging::list_log_files(&logging::log_dir())
+}
+
+/// Tauri command: Set the log level and, optionally, per-module levels
+///
+/// `modules` maps a module such as `ocr` to its own level; when omitted,
+/// the saved module levels are kept. Takes effect immediately and is saved
+/// with the other settings.
+#[tauri::command]
+pub fn set_log_level(
+    level: String,
+    modules: Option<BTreeMap<String, String>>,
+    app: AppHandle,
+    access: State<DatabaseAccess>,
+) -> Result<LoggingPreferences, String> {
+    let mut prefs: LoggingPreferences =
+        settings::load_section(&access, settings::LOGGING_KEY).map_err(|e| e.to_string())?;
+    prefs.level = level;
+    if let Some(modules) = modules {
+        prefs.modules = modules;
+    }
+    prefs.validate().map_err(|e| e.to_string())?;
+
+    settings::save_section(&access, settings::LOGGING_KEY, &prefs).map_err(|e| e.to_string())?;
+    prefs.apply().map_err(|e| e.to_string())?;
+    log::info!(
+        "Log level set to {} ({} module overrides)",
+        prefs.level,
+        prefs.modules.len()
+    );
+
+    settings::notify_changed(&app, &access);
+    Ok(prefs)
+}
+
+/// Tauri command: Read recent log entries for the diagnostics panel
+///
+/// Entries come newest first, from the current file and then the rotated
+/// ones. `level` keeps that level and more severe ones, `since` takes
+/// RFC 3339 or a local `YYYY-MM-DD[ HH:MM:SS]`, and `contains` matches the
+/// message or module case-insensitively.
+#[tauri::command]
+pub fn query_logs(
+    level: Option<String>,
+    since: Option<String>,
+    contains: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let level = level
+        .map(|level| {
+            level
+                .trim()
+                .parse::<LevelFilter>()
+                .map_err(|_| format!("Unknown log level '{}'", level))
+        })
+        .transpose()?;
+    let since = since
+        .map(|since| {
+            logging::parse_since(&since).ok_or_else(|| format!("Invalid date '{}'", since))
+        })
+        .transpose()?;
+
+    Ok(logging::query(
+        &logging::log_dir(),
+        &LogQuery {
+            level,
+            since,
+            contains: contains.filter(|text| !text.trim().is_empty()),
+            limit,
+        },
+    ))
+}
+
+/// Tauri command: Write a diagnostics zip for a bug report to `path`
+///
+/// Holds logs, app, OS, GPU and display info, the OCR configuration and
+/// status, settings, the database schema version and recent OCR debug
+/// images. Returns the summary stored in the bundle.
+#[tauri::command]
+pub fn generate_diagnostics_bundle(
+    path: String,
+    access: State<DatabaseAccess>,
+    ocr_state: State<OcrState>,
+) -> AppResult<DiagnosticsSummary> {
+    log::info!("Generating diagnostics bundle: {}", path);
+
+    let config = ocr_state.detection_config()?;
+    let log_dir = logging::log_dir();
+    let (bundle, summary) = diagnostics::build(DiagnosticsSources {
+        access: &access,
+        log_dir: &log_dir,
+        debug_image_dir: config.debug_image_path.as_deref(),
+        ocr_status: ocr::status::ocr_status(&config.recognize),
+        capture_regions: config
+            .capture
+            .get_regions()
+            .iter()
+            .copied()
+            .map(Into::into)
+            .collect(),
+    })
+    .map_err(|e| AppError::Internal(format!("Failed to build diagnostics bundle: {}", e)))?;
+
+    std::fs::write(&path, bundle)
+        .map_err(|e| AppError::Internal(format!("Failed to write file: {}", e)))?;
+    Ok(summary)
+}
+
+/// Tauri command: Rolling latency of each pipeline stage
+///
+/// One entry per stage seen since startup (`ocr.capture`, `ocr.preprocess`,
+/// `ocr.recognize`, `ocr.match`, `db.query`, `scoring.calculate`), with the
+/// average and p95 over the last `perf::WINDOW` runs.
+#[tauri::command]
+pub fn get_perf_metrics() -> Vec<StageMetrics> {
+    perf::snapshot()
+}
+
+/// Tauri command: The crash report left by a previous run, if any
+///
+/// The report stays until `dismiss_crash_report` is called, so it is shown
+/// again if the app is closed before the user sees it.
+#[tauri::command]
+pub fn get_last_crash_report() -> AppResult<Option<CrashReport>> {
+    crash::read_report(&logging::log_dir())
+        .map_err(|e| AppError::Internal(format!("Failed to read crash report: {}", e)))
+}
+
+/// Tauri command: Delete the stored crash report
+#[tauri::command]
+pub fn dismiss_crash_report() -> AppResult<()> {
+    crash::clear_report(&logging::log_dir())
+        .map_err(|e| AppError::Internal(format!("Failed to delete crash report: {}", e)))
+}
@@ -0,0 +1,154 @@
+//! Map route commands
+
+use crate::commands::scoring::{self, DraftScoreRequest, ScoringError};
+use crate::database::access::DatabaseAccess;
+use crate::database::cache::{CardCache, CardSnapshot};
+use crate::error::AppResult;
+use crate::map::{self, MapNode, RouteRecommendation};
+use crate::scoring::pool::CardPool;
+use crate::session::SessionState;
+use rusqlite::Connection;
+use tauri::State;
+
+/// Recommend a branch for a filled-in request's deck
+///
+/// `request.card_id` is ignored. Unknown deck cards are skipped, as when
+/// scoring.
+fn route_request(
+    conn: &Connection,
+    snapshot: &CardSnapshot,
+    request: &DraftScoreRequest,
+    nodes: &[MapNode],
+    gold: i32,
+    pyre_health: Option<i32>,
+) -> Result<RouteRecommendation, ScoringError> {
+    scoring::validate_context(request)?;
+    if gold < 0 {
+        return Err(ScoringError::InvalidInput(
+            "Gold cannot be negative".to_string(),
+        ));
+    }
+    if pyre_health.is_some_and(|health| !(0..=100).contains(&health)) {
+        return Err(ScoringError::InvalidInput(
+            "Pyre health must be a percentage between 0 and 100".to_string(),
+        ));
+    }
+
+    let tables = scoring::load_scoring_tables(conn, request)?;
+    let deck = snapshot.get_many(&request.current_deck);
+    let pool = CardPool::new(
+        snapshot,
+        &tables,
+        &request.champion,
+        request.covenant,
+        request.pact_shards.unwrap_or(0),
+        &deck,
+    );
+    let weaknesses = map::find_weaknesses(
+        &pool,
+        &tables.archetypes,
+        &deck,
+        request.ring_number,
+        pyre_health,
+    );
+    Ok(map::recommend_route(nodes, weaknesses, gold))
+}
+
+/// Value each branch of the map for the deck's weaknesses and recommend one
+///
+/// An empty deck and the champion, ring and covenant default to the draft
+/// session's. `pyre_health` is a percentage; without it healing is never
+/// recommended.
+#[tauri::command]
+pub fn recommend_route(
+    map_nodes: Vec<MapNode>,
+    deck: Vec<String>,
+    gold: i32,
+    pyre_health: Option<i32>,
+    access: State<DatabaseAccess>,
+    session_state: State<SessionState>,
+    cache: State<CardCache>,
+) -> AppResult<RouteRecommendation> {
+    let mut request = DraftScoreRequest {
+        card_id: String::new(),
+        current_deck: deck,
+        champion: String::new(),
+        ring_number: 0,
+        covenant: 0,
+        champion_path: None,
+        upcoming_boss: None,
+        pact_shards: None,
+        upgrade_count: 0,
+    };
+    session_state.with_session(|session| {
+        session.fill_request(&mut request);
+        Ok(())
+    })?;
+
+    access.read(|conn| {
+        let snapshot = cache.get(conn)?;
+        Ok(route_request(
+            conn,
+            &snapshot,
+            &request,
+            &map_nodes,
+            gold,
+            pyre_health,
+        )?)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::{MapNodeKind, Weakness};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_route_request() {
+        let temp = NamedTempFile::new().unwrap();
+        crate::database::init(temp.path()).unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+
+        // Spells only: no frontline and no units
+        let request = DraftScoreRequest {
+            card_id: String::new(),
+            current_deck: vec![
+                "banished_fel".to_string(),
+                "banished_cleave".to_string(),
+                "banished_deadly_plunge".to_string(),
+                "banished_karmic_censer".to_string(),
+                "pyreborne_fanning_the_flame".to_string(),
+            ],
+            champion: "Fel".to_string(),
+            ring_number: 4,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
+        };
+        let nodes = [
+            MapNode {
+                branch: 0,
+                kind: MapNodeKind::SpellBanner,
+            },
+            MapNode {
+                branch: 1,
+                kind: MapNodeKind::UnitBanner,
+            },
+        ];
+
+        let route = route_request(&conn, &snapshot, &request, &nodes, 0, Some(90)).unwrap();
+        assert!(route.weaknesses.contains(&Weakness::NoFrontline));
+        assert!(route.weaknesses.contains(&Weakness::FewUnits));
+        assert!(!route.weaknesses.contains(&Weakness::FewSpells));
+        assert_eq!(route.recommended, Some(1));
+
+        assert!(matches!(
+            route_request(&conn, &snapshot, &request, &nodes, 0, Some(101)),
+            Err(ScoringError::InvalidInput(_))
+        ));
+    }
+}
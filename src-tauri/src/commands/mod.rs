@@ -1,5 +1,16 @@
 pub mod cards;
+pub mod community;
+pub mod database;
 pub mod export;
+pub mod history;
+pub mod hotkeys;
+pub mod logging;
+pub mod map;
 pub mod ocr;
+pub mod profiles;
 pub mod scoring;
+pub mod server;
+pub mod session;
+pub mod settings;
+pub mod sync;
 pub mod window;
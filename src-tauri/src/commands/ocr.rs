@@ -3,15 +3,37 @@
 //! This module provides Tauri command handlers for OCR operations,
 //! including card detection on screen and OCR region calibration.
 
-use crate::database::DatabaseState;
+use crate::database::access::DatabaseAccess;
+use crate::database::cache::{CardCache, CardSnapshot};
+use crate::database::repository;
+use crate::database::{annotations, DatabaseState};
+use crate::error::{AppError, AppResult};
 use crate::ocr::{
-    self, capture::CaptureRegion, CalibrationReport, CardDetectionOptions,
-    DetectedCard, OcrPipeline,
+    self,
+    archive::{ArchiveConfig, CompactionReport},
+    capture::{self, CaptureBackendStatus, CaptureRegion, DisplaySession},
+    confidence::{CalibrationSample, ConfidenceCalibration, ConfidenceWeights},
+    controller::{LoopStatus, OcrController, OcrSettings, DEFAULT_LOOP_INTERVAL},
+    glyph::{self, GlyphAtlas, LearnReport},
+    pips,
+    status::{OcrStatus, OcrUnavailable},
+    tessdata::{self, LanguagePack},
+    BannerColor, CalibrationReport, CancelToken, CandidateFilter, CardDetectionOptions, CardPoolEntry,
+    DetectedCard, FrameGate, MatchCandidate, NormalizedRegion, OcrPipeline, OcrPipelineResult,
+    CardText, OfferTracker, PreprocessStep, RulesTextCheck,
+    RegionPreview, RegionRole, SessionRecorder,
 };
+use crate::server::{self, CardScore};
+use crate::session::SessionState;
+use crate::settings::{self, OcrPreferences, RegionPreferences, RegionProfilePreferences};
+use base64::Engine;
+use image::DynamicImage;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-use tauri::State;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{Emitter, Manager, State};
 
 /// Response structure for card detection
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -21,6 +43,105 @@ pub struct CardDetectionResponse {
     pub success: bool,
     pub error: Option<String>,
     pub details: Vec<DetectedCardInfo>,
+    /// Set when OCR cannot run on this machine, with remediation steps
+    pub unavailable: Option<OcrUnavailable>,
+    /// Image hash of each capture region, for telling draft offers apart
+    #[serde(skip)]
+    pub region_hashes: Vec<Option<u64>>,
+    /// Set on the first continuous detection result of a new draft offer
+    #[serde(default)]
+    pub new_offer: bool,
+}
+
+/// Fewest detected cards for a frame to count as a draft screen
+const MIN_DRAFT_SCREEN_CARDS: usize = 2;
+
+impl CardDetectionResponse {
+    /// Whether this frame shows a card draft; a single stray match is not
+    /// enough, since card names also appear in the deck view and tooltips
+    pub fn is_draft_screen(&self) -> bool {
+        self.success && self.detected_cards.len() >= MIN_DRAFT_SCREEN_CARDS
+    }
+}
+
+/// Votes on continuous detection results across frames
+///
+/// A card is only reported once it was detected in `stable_votes` of the
+/// last `stable_window` frames, so a single misread doesn't flicker onto
+/// the overlay. Reported cards are held until the draft screen changes: a
+/// draft screen showing none of them, or a full window without one.
+#[derive(Debug, Default)]
+pub struct DetectionStabilizer {
+    frames: VecDeque<Vec<DetectedCardInfo>>,
+    held: Vec<DetectedCardInfo>,
+}
+
+impl DetectionStabilizer {
+    /// Record a frame and return the stabilized result
+    ///
+    /// Failed frames pass through unchanged and don't count as frames.
+    pub fn update(
+        &mut self,
+        config: &CardDetectionOptions,
+        response: &CardDetectionResponse,
+    ) -> CardDetectionResponse {
+        if !response.success {
+            return response.clone();
+        }
+        let window = config.stable_window.max(1);
+        let votes = config.stable_votes.clamp(1, window);
+
+        let shows_held = response
+            .details
+            .iter()
+            .any(|card| self.held.iter().any(|held| held.card_id == card.card_id));
+        if response.is_draft_screen() && !self.held.is_empty() && !shows_held {
+            self.frames.clear();
+            self.held.clear();
+        }
+
+        self.frames.push_back(response.details.clone());
+        while self.frames.len() > window {
+            self.frames.pop_front();
+        }
+        if self.frames.len() == window
+            && self.frames.iter().all(|frame| frame.len() < MIN_DRAFT_SCREEN_CARDS)
+        {
+            self.held.clear();
+        }
+
+        // Newest sighting of each card, most recent frame first
+        let mut latest: Vec<&DetectedCardInfo> = Vec::new();
+        for card in self.frames.iter().rev().flatten() {
+            if !latest.iter().any(|seen| seen.card_id == card.card_id) {
+                latest.push(card);
+            }
+        }
+        for card in latest {
+            let seen_in = self
+                .frames
+                .iter()
+                .filter(|frame| frame.iter().any(|c| c.card_id == card.card_id))
+                .count();
+            match self.held.iter_mut().find(|held| held.card_id == card.card_id) {
+                Some(held) => *held = card.clone(),
+                None if seen_in >= votes => self.held.push(card.clone()),
+                None => {}
+            }
+        }
+
+        let confidence = if self.held.is_empty() {
+            0.0
+        } else {
+            self.held.iter().map(|card| card.confidence).sum::<f64>() / self.held.len() as f64
+        };
+        CardDetectionResponse {
+            detected_cards: self.held.iter().map(|card| card.card_name.clone()).collect(),
+            confidence,
+            details: self.held.clone(),
+            ..response.clone()
+        }
+    }
 }
 
 /// Detailed information about a detected card
@@ -33,15 +154,46 @@ pub struct DetectedCardInfo {
     pub match_score: i32,
     pub raw_text: String,
     pub region: CaptureRegionInfo,
+    /// Whether other cards scored close enough that the user should confirm
+    pub ambiguous: bool,
+    /// Top candidates to choose from when `ambiguous` is set
+    pub candidates: Vec<MatchCandidate>,
+    /// Rarity and clan inferred from the card's frame and banner colors
+    pub banner: Option<BannerColor>,
+    /// Whether the player marked this card as a favorite, for highlighting
+    #[serde(default)]
+    pub is_favorite: bool,
+    /// Rules text read from the card's text region, if one is configured
+    #[serde(default)]
+    pub rules_text: Option<String>,
+    /// How `rules_text` differs from the database description
+    #[serde(default)]
+    pub text_check: Option<RulesTextCheck>,
+    /// Upgrade pips counted on the card, if a pip region is configured
+    #[serde(default)]
+    pub upgrade_count: Option<u32>,
 }
 
 /// Information about a capture region
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct CaptureRegionInfo {
     pub x: i32,
     pub y: i32,
     pub width: u32,
     pub height: u32,
+    /// What the region shows, so detections needn't be told apart by index
+    #[serde(default)]
+    pub role: Option<RegionRole>,
+}
+
+impl CaptureRegionInfo {
+    /// The capture region this describes
+    pub fn to_region(&self) -> CaptureRegion {
+        CaptureRegion {
+            role: self.role,
+            ..CaptureRegion::new(self.x, self.y, self.width, self.height)
+        }
+    }
 }
 
 impl From<ocr::capture::CaptureRegion> for CaptureRegionInfo {
@@ -51,6 +203,7 @@ impl From<ocr::capture::CaptureRegion> for CaptureRegionInfo {
             y: region.y,
             width: region.width,
             height: region.height,
+            role: region.role,
         }
     }
 }
@@ -65,6 +218,13 @@ impl From<DetectedCard> for DetectedCardInfo {
             match_score: card.match_score,
             raw_text: card.raw_ocr_text,
             region: card.region.into(),
+            ambiguous: !card.candidates.is_empty(),
+            candidates: card.candidates,
+            banner: card.banner,
+            is_favorite: false,
+            rules_text: card.rules_text,
+            text_check: None,
+            upgrade_count: card.upgrade_count,
         }
     }
 }
@@ -76,6 +236,8 @@ pub struct CalibrationResult {
     pub message: String,
     pub screen_width: u32,
     pub screen_height: u32,
+    /// Display scale factor (1.25 at 125% scaling)
+    pub scale_factor: f32,
     pub recommended_regions: Vec<CaptureRegionInfo>,
     pub success_rate: f64,
 }
@@ -103,18 +265,78 @@ impl From<CalibrationReport> for CalibrationResult {
             message,
             screen_width: report.screen_dimensions.0,
             screen_height: report.screen_dimensions.1,
+            scale_factor: report.scale_factor,
             recommended_regions,
             success_rate: report.success_rate(),
         }
     }
 }
 
+/// A capture region as the OCR sees it, with images as base64-encoded PNGs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegionPreviewInfo {
+    pub region: CaptureRegionInfo,
+    pub raw_png: Option<String>,
+    pub preprocessed_png: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Encode an image as a base64 PNG
+fn png_base64(image: DynamicImage) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageOutputFormat::Png)
+        .map_err(|e| format!("Failed to encode preview: {}", e))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+impl From<RegionPreview> for RegionPreviewInfo {
+    fn from(preview: RegionPreview) -> Self {
+        let mut error = preview.error;
+        let mut encode = |image: Option<DynamicImage>| {
+            image.and_then(|image| png_base64(image).map_err(|e| error = Some(e)).ok())
+        };
+
+        let raw_png = encode(preview.raw_image.map(DynamicImage::ImageRgba8));
+        let preprocessed_png = encode(preview.preprocessed_image.map(DynamicImage::ImageLuma8));
+
+        Self {
+            region: preview.region.into(),
+            raw_png,
+            preprocessed_png,
+            error,
+        }
+    }
+}
+
 /// Request to set custom capture regions
+///
+/// Regions are given either in pixels or, to survive resolution changes,
+/// as fractions (0.0-1.0) of the screen; normalized regions take precedence.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SetRegionsRequest {
+    #[serde(default)]
     pub regions: Vec<CaptureRegionInfo>,
+    #[serde(default)]
+    pub normalized_regions: Vec<NormalizedRegion>,
 }
 
+/// Downscaled screenshot for picking capture regions
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FullscreenCapture {
+    /// Base64-encoded PNG
+    pub image_png: String,
+    pub image_width: u32,
+    pub image_height: u32,
+    /// Physical screen size the screenshot was taken at
+    pub screen_width: u32,
+    pub screen_height: u32,
+    pub scale_factor: f32,
+}
+
+/// Default width of the calibration screenshot
+const DEFAULT_CALIBRATION_WIDTH: u32 = 1280;
+
 /// Response for region setting
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SetRegionsResult {
@@ -123,133 +345,1007 @@ pub struct SetRegionsResult {
     pub regions_set: usize,
 }
 
-/// Application state for OCR configuration
-/// This is managed by Tauri and persists across calls
+/// State of the OCR debug session recorder
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordingStatus {
+    pub recording: bool,
+    /// Session folder being (or last) recorded to
+    pub session_dir: Option<String>,
+}
+
+/// Application state for OCR
+///
+/// A handle to the OCR controller thread, which owns detection settings and
+/// the continuous detection loop. Managed by Tauri; cheap to share.
 pub struct OcrState {
-    pub config: Mutex<CardDetectionOptions>,
+    controller: OcrController<CardDetectionResponse>,
+    /// Aborts the detection the controller is running
+    cancel: CancelToken,
 }
 
 impl OcrState {
-    pub fn new() -> Self {
-        Self {
-            config: Mutex::new(CardDetectionOptions::default()),
-        }
+    /// Spawn the OCR controller with default settings
+    pub fn new(db_path: PathBuf) -> Self {
+        Self::spawn(
+            DatabaseAccess::new(db_path),
+            CardCache::default(),
+            OcrSettings::default(),
+            |_| {},
+        )
     }
 
-    pub fn with_config(config: CardDetectionOptions) -> Self {
-        Self {
-            config: Mutex::new(config),
+    /// Spawn the OCR controller with custom detection options
+    pub fn with_config(db_path: PathBuf, config: CardDetectionOptions) -> Self {
+        let settings = OcrSettings {
+            detection: config,
+            ..Default::default()
+        };
+        Self::spawn(
+            DatabaseAccess::new(db_path),
+            CardCache::default(),
+            settings,
+            |_| {},
+        )
+    }
+
+    /// Spawn the OCR controller; `on_loop_result` receives continuous detection
+    /// results, voted on across frames by `DetectionStabilizer`
+    ///
+    /// The first result of each new draft offer, told apart by `OfferTracker`
+    /// from the region image hashes, has `new_offer` set.
+    ///
+    /// Detection reads cards through `cards`, shared with the card commands,
+    /// and queues its history writes through `access`.
+    pub fn spawn<L>(
+        access: DatabaseAccess,
+        cards: CardCache,
+        mut settings: OcrSettings,
+        mut on_loop_result: L,
+    ) -> Self
+    where
+        L: FnMut(&CardDetectionResponse) + Send + 'static,
+    {
+        // Prefer downloaded language packs when no data path is configured
+        if settings.detection.recognize.tesseract_data_path.is_none() {
+            if let Some(dir) = app_tessdata_path(access.db_path(), &settings.detection.recognize.language) {
+                settings.detection.recognize.tesseract_data_path = Some(dir);
+            }
+        }
+
+        // Weight Tesseract's decoding toward the words of card names
+        if access.db_path().is_file() {
+            let locale = settings.detection.card_locale.as_deref();
+            match write_card_user_words(&access, &cards, locale) {
+                Ok(path) => {
+                    for recognize in settings.detection.recognize_configs_mut() {
+                        recognize.user_words_path.get_or_insert_with(|| path.clone());
+                    }
+                }
+                Err(e) => log::warn!("Failed to write the card name vocabulary: {}", e),
+            }
+        }
+
+        // Use a glyph atlas built from this install's recorded sessions
+        if let Some(path) = app_glyph_atlas_path(access.db_path()) {
+            for recognize in settings.detection.recognize_configs_mut() {
+                recognize.glyph_atlas_path.get_or_insert_with(|| path.clone());
+            }
+        }
+
+        // Count upgrade pips with the sprites saved in the app data folder
+        if settings.detection.pip_sprites_dir.is_none() {
+            settings.detection.pip_sprites_dir = app_pip_sprites_dir(access.db_path());
         }
+
+        // Use confidence weights calibrated on this install's detection history
+        if settings.detection.recognize.confidence_weights == ConfidenceWeights::default() {
+            if let Some(weights) = load_confidence_weights(&access) {
+                settings.detection.recognize.confidence_weights = weights;
+            }
+        }
+
+        let mut gate = FrameGate::new();
+        let mut gate_revision = settings.revision;
+        let mut gate_aliases_revision = None;
+        let mut recorder: Option<SessionRecorder> = None;
+        let mut stabilizer = DetectionStabilizer::default();
+        let mut offers = OfferTracker::default();
+        let cancel = CancelToken::default();
+        let detection_cancel = cancel.clone();
+
+        let controller = OcrController::spawn(
+            settings,
+            move |settings: &OcrSettings| {
+                // Stored region results were matched under the old settings
+                if settings.revision != gate_revision {
+                    gate.clear();
+                    gate_revision = settings.revision;
+                }
+                // or before a reported misdetection taught a new alias, even
+                // if the screen is static
+                let aliases_revision = access.read(repository::ocr_aliases_revision).ok();
+                if aliases_revision != gate_aliases_revision {
+                    gate.clear();
+                    gate_aliases_revision = aliases_revision;
+                }
+
+                let recording_dir = settings.detection.recording_dir.as_deref();
+                if recorder.as_ref().map(|r| r.dir()) != recording_dir {
+                    recorder = recording_dir.and_then(|dir| {
+                        SessionRecorder::start(dir.to_path_buf())
+                            .map_err(|e| log::warn!("Failed to start OCR recording: {}", e))
+                            .ok()
+                    });
+                }
+
+                let _running = detection_cancel.begin();
+                run_detection(
+                    &access,
+                    &cards,
+                    &settings.detection,
+                    &mut gate,
+                    recorder.as_mut(),
+                    &detection_cancel,
+                )
+            },
+            move |settings: &OcrSettings, response: &CardDetectionResponse| {
+                let mut stable = stabilizer.update(&settings.detection, response);
+                // Both the raw frame and the voted cards must show the offer
+                let shows_offer = response.is_draft_screen() && stable.is_draft_screen();
+                stable.new_offer = offers.update(&response.region_hashes, shows_offer);
+                on_loop_result(&stable)
+            },
+        );
+        Self { controller, cancel }
+    }
+
+    /// Handle to the OCR controller
+    pub fn controller(&self) -> &OcrController<CardDetectionResponse> {
+        &self.controller
+    }
+
+    /// Abort the detection in flight; returns whether one was running
+    pub fn cancel_detection(&self) -> bool {
+        self.cancel.cancel()
     }
-}
 
-impl Default for OcrState {
-    fn default() -> Self {
-        Self::new()
+    /// Snapshot of the current detection options
+    pub fn detection_config(&self) -> Result<CardDetectionOptions, String> {
+        self.controller
+            .settings()
+            .map(|settings| settings.detection)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Modify detection options on the controller thread
+    pub fn update_detection_config<F>(&self, update: F) -> Result<CardDetectionOptions, String>
+    where
+        F: FnOnce(&mut CardDetectionOptions) + Send + 'static,
+    {
+        self.controller
+            .update_settings(move |settings| update(&mut settings.detection))
+            .map(|settings| settings.detection)
+            .map_err(|e| e.to_string())
     }
 }
 
-/// Get all card names from the database
-fn get_card_names_from_db(db_path: &std::path::Path) -> Result<Vec<(String, String)>, String> {
-    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+/// App data folder, which holds the database
+fn app_data_dir(db_path: &Path) -> Result<&Path, String> {
+    db_path
+        .parent()
+        .ok_or_else(|| "Cannot determine app data folder".to_string())
+}
 
-    let mut stmt = conn
-        .prepare("SELECT id, name FROM cards ORDER BY name")
-        .map_err(|e| e.to_string())?;
+/// The app tessdata folder, if it has packs for `language`
+fn app_tessdata_path(db_path: &Path, language: &str) -> Option<String> {
+    let app_dir = tessdata::app_tessdata_dir(app_data_dir(db_path).ok()?);
+    tessdata::resolve_data_path(&app_dir, language).map(|dir| dir.to_string_lossy().to_string())
+}
 
-    let card_names: Vec<(String, String)> = stmt
-        .query_map([], |row| {
-            let id: String = row.get(0)?;
-            let name: String = row.get(1)?;
-            Ok((id, name))
+/// Write every card name, alias and, with `locale`, translated name to the
+/// Tesseract user-words file in the app tessdata folder
+fn write_card_user_words(
+    access: &DatabaseAccess,
+    cards: &CardCache,
+    locale: Option<&str>,
+) -> Result<String, String> {
+    let (snapshot, aliases) = access
+        .read(|conn| {
+            let snapshot = cards.get(conn)?;
+            repository::load_card_aliases(conn).map(|aliases| (snapshot, aliases))
         })
-        .map_err(|e| e.to_string())?
-        .filter_map(|r| r.ok())
+        .map_err(|e| e.to_string())?;
+    let translated = locale.map_or_else(Vec::new, |locale| snapshot.translated_names(locale));
+
+    let names = snapshot
+        .cards()
+        .iter()
+        .map(|card| card.name.as_str())
+        .chain(aliases.iter().chain(&translated).map(|(_, name)| name.as_str()));
+    let app_dir = tessdata::app_tessdata_dir(app_data_dir(access.db_path())?);
+    let path = tessdata::write_user_words(&app_dir, names).map_err(|e| e.to_string())?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// The app glyph atlas, if one has been built
+fn app_glyph_atlas_path(db_path: &Path) -> Option<String> {
+    let path = app_data_dir(db_path).ok()?.join(glyph::ATLAS_FILE);
+    path.is_file().then(|| path.to_string_lossy().to_string())
+}
+
+/// The app upgrade pip sprite folder, if it exists
+fn app_pip_sprites_dir(db_path: &Path) -> Option<PathBuf> {
+    let dir = app_data_dir(db_path).ok()?.join(pips::PIP_SPRITES_DIR);
+    dir.is_dir().then_some(dir)
+}
+
+/// Every card allowed by `filter`, with the attributes it checks
+pub(crate) fn card_pool(snapshot: &CardSnapshot, filter: &CandidateFilter) -> Vec<CardPoolEntry> {
+    snapshot
+        .pool()
+        .iter()
+        .filter(|entry| filter.allows(entry))
+        .cloned()
+        .collect()
+}
+
+fn card_names(pool: &[CardPoolEntry]) -> Vec<(String, String)> {
+    pool.iter()
+        .map(|entry| (entry.card_id.clone(), entry.card_name.clone()))
+        .collect()
+}
+
+/// Keywords and description openings of the cards in `pool`
+fn card_text(snapshot: &CardSnapshot, pool: &[CardPoolEntry]) -> Vec<CardText> {
+    pool.iter()
+        .filter_map(|entry| snapshot.get(&entry.card_id))
+        .map(|card| CardText::new(&card.id, &card.keywords, &card.description))
+        .collect()
+}
+
+/// Default number of rows returned by `get_detection_history`
+const DEFAULT_HISTORY_LIMIT: u32 = 100;
+/// Upper bound on rows returned by `get_detection_history`
+const MAX_HISTORY_LIMIT: u32 = 1000;
+
+/// Default number of cards returned by `match_text_to_cards`
+const DEFAULT_MANUAL_MATCH_LIMIT: usize = 5;
+/// Upper bound on cards returned by `match_text_to_cards`
+const MAX_MANUAL_MATCH_LIMIT: usize = 50;
+/// Match score typed text needs to list a card; lower than detection's
+/// minimum, since the player picks the card from the list
+const MANUAL_MATCH_MIN_SCORE: i32 = 40;
+
+/// A stored OCR detection from the `ocr_detections` table
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DetectionHistoryEntry {
+    pub id: i64,
+    pub card_id: String,
+    pub card_name: String,
+    pub raw_text: String,
+    pub ocr_confidence: i32,
+    pub match_score: i32,
+    pub overall_confidence: f64,
+    pub region: CaptureRegionInfo,
+    pub detected_at: String,
+}
+
+/// Persist detected cards so accuracy can be analyzed later
+fn record_detections(conn: &Connection, cards: &[DetectedCard]) -> rusqlite::Result<usize> {
+    if cards.is_empty() {
+        return Ok(0);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO ocr_detections
+             (card_id, card_name, raw_text, ocr_confidence, match_score, overall_confidence,
+              region_x, region_y, region_width, region_height)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )?;
+
+        for card in cards {
+            stmt.execute(rusqlite::params![
+                card.card_id,
+                card.card_name,
+                card.raw_ocr_text,
+                card.ocr_confidence,
+                card.match_score,
+                card.overall_confidence,
+                card.region.x,
+                card.region.y,
+                card.region.width,
+                card.region.height,
+            ])?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(cards.len())
+}
+
+/// Query stored detections, newest first, optionally restricted to one card
+fn query_detection_history(
+    conn: &Connection,
+    limit: u32,
+    card_id: Option<&str>,
+) -> rusqlite::Result<Vec<DetectionHistoryEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, card_id, card_name, raw_text, ocr_confidence, match_score, overall_confidence,
+                region_x, region_y, region_width, region_height, detected_at
+         FROM ocr_detections
+         WHERE ?1 IS NULL OR card_id = ?1
+         ORDER BY id DESC
+         LIMIT ?2",
+    )?;
+
+    let entries = stmt
+        .query_map(rusqlite::params![card_id, limit], |row| {
+            Ok(DetectionHistoryEntry {
+                id: row.get(0)?,
+                card_id: row.get(1)?,
+                card_name: row.get(2)?,
+                raw_text: row.get(3)?,
+                ocr_confidence: row.get(4)?,
+                match_score: row.get(5)?,
+                overall_confidence: row.get(6)?,
+                region: CaptureRegionInfo {
+                    x: row.get(7)?,
+                    y: row.get(8)?,
+                    width: row.get(9)?,
+                    height: row.get(10)?,
+                    role: None,
+                },
+                detected_at: row.get(11)?,
+            })
+        })?
         .collect();
 
-    Ok(card_names)
+    entries
 }
 
-/// Tauri command: Detect cards on screen
+/// Most recent detections used to calibrate confidence weights
+const MAX_CALIBRATION_SAMPLES: u32 = 5000;
+
+/// Label recent detections for confidence calibration
 ///
-/// This command captures screen regions, runs OCR, and matches
-/// against known card names from the database.
-#[tauri::command]
-pub fn detect_cards_on_screen(
-    db_state: State<DatabaseState>,
-    ocr_state: State<OcrState>,
-) -> Result<CardDetectionResponse, String> {
-    // Get card names from database
-    let card_names = get_card_names_from_db(&db_state.db_path)?;
+/// A detection is wrong if the user later corrected its OCR text away from
+/// the detected card; every other detection counts as confirmed.
+fn load_calibration_samples(conn: &Connection) -> rusqlite::Result<Vec<CalibrationSample>> {
+    let mut stmt = conn.prepare(
+        "SELECT d.ocr_confidence, d.match_score,
+                NOT EXISTS(SELECT 1 FROM ocr_corrections AS c
+                           WHERE c.raw_text = d.raw_text AND c.wrong_card_id = d.card_id)
+         FROM ocr_detections AS d
+         ORDER BY d.id DESC
+         LIMIT ?1",
+    )?;
 
-    if card_names.is_empty() {
-        return Ok(CardDetectionResponse {
-            detected_cards: vec![],
-            confidence: 0.0,
-            success: false,
-            error: Some("No cards found in database".to_string()),
-            details: vec![],
+    let samples = stmt
+        .query_map([MAX_CALIBRATION_SAMPLES], |row| {
+            Ok(CalibrationSample {
+                ocr_confidence: row.get(0)?,
+                match_score: row.get(1)?,
+                correct: row.get(2)?,
+            })
+        })?
+        .collect();
+
+    samples
+}
+
+/// Persist fitted confidence weights, replacing any previous calibration
+fn store_confidence_calibration(
+    conn: &Connection,
+    calibration: &ConfidenceCalibration,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO ocr_confidence_calibration
+         (id, ocr_weight, match_weight, sample_count, corrected_count, brier_score)
+         VALUES (1, ?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            calibration.weights.ocr_weight,
+            calibration.weights.match_weight,
+            calibration.sample_count as i64,
+            calibration.corrected_count as i64,
+            calibration.brier_after,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Calibrated confidence weights stored for this install, if any
+fn load_confidence_weights(access: &DatabaseAccess) -> Option<ConfidenceWeights> {
+    // Read connections are read-only, so a missing database is not created
+    access
+        .read(|conn| {
+            conn.query_row(
+                "SELECT ocr_weight, match_weight FROM ocr_confidence_calibration WHERE id = 1",
+                [],
+                |row| {
+                    Ok(ConfidenceWeights {
+                        ocr_weight: row.get(0)?,
+                        match_weight: row.get(1)?,
+                    })
+                },
+            )
+        })
+        .ok()
+}
+
+/// Default number of pairs and regions in `get_ocr_confusion_report`
+const DEFAULT_CONFUSION_LIMIT: u32 = 20;
+/// Upper bound on pairs and regions in `get_ocr_confusion_report`
+const MAX_CONFUSION_LIMIT: u32 = 100;
+
+/// Two cards OCR mixed up, from the user's corrections
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConfusedPair {
+    /// Card OCR detected
+    pub detected_card_id: String,
+    pub detected_card_name: String,
+    /// Card it actually was
+    pub actual_card_id: String,
+    pub actual_card_name: String,
+    pub corrections: u32,
+    pub last_reported_at: String,
+}
+
+/// Detection confidence in one capture region
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RegionConfidence {
+    pub region: CaptureRegionInfo,
+    /// Position of this region among the current capture regions, if it is
+    /// still one of them
+    pub region_index: Option<usize>,
+    pub detections: u32,
+    pub average_confidence: f64,
+    /// Detections in this region the user later corrected
+    pub corrected: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OcrConfusionReport {
+    /// Most often corrected first
+    pub confused_pairs: Vec<ConfusedPair>,
+    /// Lowest average confidence first
+    pub regions: Vec<RegionConfidence>,
+}
+
+/// Build the confusion report from detections and corrections
+///
+/// `current_regions` are the configured capture regions, used to point each
+/// region's results at the region to recalibrate.
+fn query_confusion_report(
+    conn: &Connection,
+    limit: u32,
+    current_regions: &[CaptureRegion],
+) -> rusqlite::Result<OcrConfusionReport> {
+    let mut stmt = conn.prepare(
+        "SELECT c.wrong_card_id, COALESCE(w.name, c.wrong_card_id),
+                c.correct_card_id, COALESCE(r.name, c.correct_card_id),
+                COUNT(*), MAX(c.reported_at)
+         FROM ocr_corrections AS c
+         LEFT JOIN cards AS w ON w.id = c.wrong_card_id
+         LEFT JOIN cards AS r ON r.id = c.correct_card_id
+         WHERE c.wrong_card_id IS NOT NULL
+         GROUP BY c.wrong_card_id, c.correct_card_id
+         ORDER BY COUNT(*) DESC, MAX(c.reported_at) DESC
+         LIMIT ?1",
+    )?;
+    let confused_pairs = stmt
+        .query_map([limit], |row| {
+            Ok(ConfusedPair {
+                detected_card_id: row.get(0)?,
+                detected_card_name: row.get(1)?,
+                actual_card_id: row.get(2)?,
+                actual_card_name: row.get(3)?,
+                corrections: row.get(4)?,
+                last_reported_at: row.get(5)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    // A detection is corrected the same way `load_calibration_samples` labels it
+    let mut stmt = conn.prepare(
+        "SELECT d.region_x, d.region_y, d.region_width, d.region_height,
+                COUNT(*), AVG(d.overall_confidence),
+                SUM(EXISTS(SELECT 1 FROM ocr_corrections AS c
+                           WHERE c.raw_text = d.raw_text AND c.wrong_card_id = d.card_id))
+         FROM ocr_detections AS d
+         GROUP BY d.region_x, d.region_y, d.region_width, d.region_height
+         ORDER BY AVG(d.overall_confidence), COUNT(*) DESC
+         LIMIT ?1",
+    )?;
+    let regions = stmt
+        .query_map([limit], |row| {
+            let mut region = CaptureRegion::new(row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?);
+            // Roles aren't stored with detections, so match on position and size
+            let region_index = current_regions
+                .iter()
+                .position(|r| CaptureRegion { role: None, ..*r } == region);
+            if let Some(i) = region_index {
+                region.role = current_regions[i].role;
+            }
+            Ok(RegionConfidence {
+                region_index,
+                region: region.into(),
+                detections: row.get(4)?,
+                average_confidence: row.get(5)?,
+                corrected: row.get(6)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(OcrConfusionReport {
+        confused_pairs,
+        regions,
+    })
+}
+
+/// Persist a user correction and update the learned alias for its OCR text
+///
+/// Returns `Ok(false)` if `correct_card_id` is not a known card.
+fn record_correction(
+    conn: &Connection,
+    raw_text: &str,
+    wrong_card_id: &str,
+    correct_card_id: &str,
+) -> rusqlite::Result<bool> {
+    if !repository::card_exists(conn, correct_card_id)? {
+        return Ok(false);
+    }
+
+    let normalized = ocr::normalize_card_name(raw_text);
+    let wrong_card_id = Some(wrong_card_id).filter(|id| !id.is_empty());
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "INSERT INTO ocr_corrections (raw_text, wrong_card_id, correct_card_id) VALUES (?1, ?2, ?3)",
+        rusqlite::params![raw_text, wrong_card_id, correct_card_id],
+    )?;
+    tx.execute(
+        "INSERT INTO ocr_aliases (normalized_text, card_id) VALUES (?1, ?2)
+         ON CONFLICT(normalized_text) DO UPDATE SET
+             times_reported = CASE WHEN card_id = excluded.card_id THEN times_reported + 1 ELSE 1 END,
+             card_id = excluded.card_id,
+             updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![normalized, correct_card_id],
+    )?;
+    repository::bump_ocr_aliases_revision(&tx)?;
+    tx.commit()?;
+
+    Ok(true)
+}
+
+/// Create an OCR pipeline recognizing the cards in `pool`
+///
+/// Learned aliases and, with a card locale set, translated names are added
+/// when they can be loaded; they are an accuracy aid, so detection still
+/// runs without them.
+pub(crate) fn build_pipeline(
+    access: &DatabaseAccess,
+    snapshot: &CardSnapshot,
+    pool: &[CardPoolEntry],
+    config: CardDetectionOptions,
+) -> OcrPipelineResult<OcrPipeline> {
+    let (alternate_names, aliases) = access
+        .read(|conn| -> rusqlite::Result<_> {
+            let mut alternate_names = repository::load_card_aliases(conn)?;
+            if let Some(ref locale) = config.card_locale {
+                alternate_names.extend(snapshot.translated_names(locale));
+            }
+            Ok((alternate_names, repository::load_ocr_aliases(conn)?))
+        })
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to load OCR aliases: {}", e);
+            (Vec::new(), HashMap::new())
         });
+
+    let card_text = if config.match_card_text {
+        card_text(snapshot, pool)
+    } else {
+        Vec::new()
+    };
+
+    Ok(OcrPipeline::new(card_names(pool), config)?
+        .with_alternate_names(alternate_names)
+        .with_aliases(aliases)
+        .with_card_attributes(pool)
+        .with_card_text(card_text))
+}
+
+/// Detect cards in a screenshot rather than on screen
+///
+/// Used by the headless CLI. Nothing is recorded to the detection history.
+pub(crate) fn detect_in_image(
+    access: &DatabaseAccess,
+    config: CardDetectionOptions,
+    screenshot: &image::RgbaImage,
+) -> AppResult<Vec<DetectedCardInfo>> {
+    let snapshot = access.read(CardSnapshot::load)?;
+    let pool = card_pool(&snapshot, &config.candidate_filter);
+    if pool.is_empty() {
+        return Err(AppError::NotFound("No cards found in database".to_string()));
     }
+    ocr::status::check_availability(&config.recognize)
+        .map_err(|unavailable| AppError::Unavailable(unavailable.reason))?;
 
-    // Get OCR configuration from state
-    let config = ocr_state
-        .config
-        .lock()
-        .map_err(|e| format!("Failed to lock OCR config: {}", e))?
-        .clone();
+    let pipeline = build_pipeline(access, &snapshot, &pool, config)?;
+    let result = pipeline.detect_cards_in_image(screenshot);
+    Ok(result.detected_cards.into_iter().map(Into::into).collect())
+}
 
-    // Check if OCR feature is enabled
-    #[cfg(not(feature = "ocr"))]
-    {
-        log::warn!("OCR feature is disabled - detect_cards_on_screen will return empty results");
+/// Run one detection pass: capture, OCR, match against the database, and record results
+fn run_detection(
+    access: &DatabaseAccess,
+    cards: &CardCache,
+    config: &CardDetectionOptions,
+    gate: &mut FrameGate<Option<DetectedCard>>,
+    recorder: Option<&mut SessionRecorder>,
+    cancel: &CancelToken,
+) -> CardDetectionResponse {
+    let failed = |error: String| CardDetectionResponse {
+        detected_cards: vec![],
+        confidence: 0.0,
+        success: false,
+        error: Some(error),
+        details: vec![],
+        unavailable: None,
+        region_hashes: vec![],
+        new_offer: false,
+    };
+
+    // Get the cards allowed by the run filter
+    let snapshot = match access.read(|conn| cards.get(conn)) {
+        Ok(snapshot) => snapshot,
+        Err(e) => return failed(format!("Failed to load card names: {}", e)),
+    };
+    let pool = card_pool(&snapshot, &config.candidate_filter);
+    if pool.is_empty() {
+        let error = if config.candidate_filter.is_empty() {
+            "No cards found in database"
+        } else {
+            "No cards match the current run filter"
+        };
+        return failed(error.to_string());
     }
 
-    // Create OCR pipeline
-    let pipeline = match OcrPipeline::new(card_names.clone(), config) {
-        Ok(p) => p,
-        Err(e) => {
-            return Ok(CardDetectionResponse {
-                detected_cards: vec![],
-                confidence: 0.0,
-                success: false,
-                error: Some(format!("Failed to initialize OCR: {}", e)),
-                details: vec![],
-            });
+    // Report a missing Tesseract install as a structured, fixable problem
+    if let Err(unavailable) = ocr::status::check_availability(&config.recognize) {
+        log::warn!("OCR unavailable: {}", unavailable.reason);
+        return CardDetectionResponse {
+            error: None,
+            unavailable: Some(unavailable),
+            ..failed(String::new())
+        };
+    }
+
+    // Normalized regions follow the current resolution
+    let mut config = config.clone();
+    if !config.capture.normalized_regions.is_empty() {
+        if let Ok((width, height)) = ocr::get_primary_screen_dimensions() {
+            config.capture.resolve_for_screen(width, height);
         }
+    }
+    let region_count = config.capture.get_regions().len();
+
+    // Create OCR pipeline
+    let pipeline = match build_pipeline(access, &snapshot, &pool, config) {
+        Ok(p) => p.with_cancel_token(cancel.clone()),
+        Err(e) => return failed(format!("Failed to initialize OCR: {}", e)),
     };
 
     // Run detection
-    match pipeline.detect_cards() {
+    match pipeline.detect_cards_gated(gate, recorder) {
         Ok(result) => {
+            // A static screen would otherwise log the same cards every cycle
+            let screen_changed = result.unchanged_regions < region_count;
+
+            // History logging is queued, so it never slows or fails the
+            // detection itself
+            if screen_changed && !result.detected_cards.is_empty() {
+                let detected = result.detected_cards.clone();
+                access.write(move |conn| {
+                    if let Err(e) = record_detections(conn, &detected) {
+                        log::warn!("Failed to record OCR detections: {}", e);
+                    }
+                });
+            }
+
             let detected_cards: Vec<String> = result
                 .detected_cards
                 .iter()
                 .map(|c| c.card_name.clone())
                 .collect();
 
-            let details: Vec<DetectedCardInfo> = result
+            let mut details: Vec<DetectedCardInfo> = result
                 .detected_cards
                 .into_iter()
                 .map(|c| c.into())
                 .collect();
 
-            Ok(CardDetectionResponse {
+            // Favorites are only a highlight; detection succeeds without them
+            match access.read(annotations::favorite_ids) {
+                Ok(favorites) => {
+                    for detail in &mut details {
+                        detail.is_favorite = favorites.contains(&detail.card_id);
+                    }
+                }
+                Err(e) => log::warn!("Failed to load favorites: {}", e),
+            }
+
+            // Flag upgraded copies by their rules text
+            for detail in &mut details {
+                let card = snapshot.get(&detail.card_id);
+                if let (Some(text), Some(card)) = (&detail.rules_text, card) {
+                    detail.text_check = Some(ocr::check_rules_text(text, &card.description));
+                }
+            }
+
+            CardDetectionResponse {
                 detected_cards,
                 confidence: result.average_confidence,
                 success: result.success,
                 error: result.error_message,
                 details,
-            })
+                unavailable: None,
+                region_hashes: result.region_hashes,
+                new_offer: false,
+            }
         }
-        Err(e) => Ok(CardDetectionResponse {
-            detected_cards: vec![],
-            confidence: 0.0,
-            success: false,
-            error: Some(format!("Detection failed: {}", e)),
-            details: vec![],
-        }),
+        Err(e) => failed(format!("Detection failed: {}", e)),
+    }
+}
+
+/// Tauri command: Detect cards on screen
+///
+/// This command captures screen regions, runs OCR, and matches
+/// against known card names from the database. Detection runs on the
+/// OCR controller thread, serialized with the continuous loop.
+#[tauri::command]
+pub fn detect_cards_on_screen(
+    ocr_state: State<OcrState>,
+    session_state: State<SessionState>,
+) -> Result<CardDetectionResponse, String> {
+    let response = ocr_state.controller().detect_once().map_err(|e| e.to_string())?;
+    session_state.note_detection(&response);
+    Ok(response)
+}
+
+/// Tauri command: Start continuous detection
+///
+/// Results are emitted as `ocr-detection` events. Calling this while the
+/// loop is running only changes its interval.
+#[tauri::command]
+pub fn start_continuous_detection(
+    interval_ms: Option<u64>,
+    ocr_state: State<OcrState>,
+    access: State<DatabaseAccess>,
+) -> Result<LoopStatus, String> {
+    let interval = match interval_ms {
+        Some(ms) => Duration::from_millis(ms),
+        None => settings::load_section::<OcrPreferences>(&access, settings::OCR_KEY)
+            .map(|prefs| Duration::from_millis(prefs.loop_interval_ms))
+            .unwrap_or(DEFAULT_LOOP_INTERVAL),
+    };
+
+    ocr_state.controller().start(interval).map_err(|e| e.to_string())
+}
+
+/// Tauri command: Stop continuous detection
+#[tauri::command]
+pub fn stop_continuous_detection(ocr_state: State<OcrState>) -> Result<LoopStatus, String> {
+    ocr_state.controller().stop().map_err(|e| e.to_string())
+}
+
+/// Tauri command: Abort the detection pass in flight
+///
+/// The pass stops at its current capture or OCR stage and reports a
+/// cancelled error; the continuous loop, if running, goes on with its next
+/// pass. Returns whether a detection was running. Cancelling first lets
+/// `stop_continuous_detection` return without waiting for a slow pass.
+#[tauri::command]
+pub fn cancel_detection(ocr_state: State<OcrState>) -> bool {
+    let cancelled = ocr_state.cancel_detection();
+    if cancelled {
+        log::info!("OCR detection cancelled");
+    }
+    cancelled
+}
+
+/// Tauri command: Get continuous detection status
+#[tauri::command]
+pub fn get_continuous_detection_status(ocr_state: State<OcrState>) -> Result<LoopStatus, String> {
+    ocr_state.controller().status().map_err(|e| e.to_string())
+}
+
+/// Tauri command: Get recorded OCR detections
+///
+/// Returns the most recent detections first. `limit` defaults to 100
+/// and is capped at 1000; `card_id` restricts results to a single card.
+#[tauri::command]
+pub fn get_detection_history(
+    limit: Option<u32>,
+    card_id: Option<String>,
+    access: State<DatabaseAccess>,
+) -> Result<Vec<DetectionHistoryEntry>, String> {
+    let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT).min(MAX_HISTORY_LIMIT);
+
+    access
+        .read(|conn| query_detection_history(conn, limit, card_id.as_deref()))
+        .map_err(|e| format!("Failed to fetch detection history: {}", e))
+}
+
+/// Tauri command: Report an OCR misdetection
+///
+/// Stores the correction and teaches the matcher that `raw_text` refers to
+/// `correct_card_id`, so the same misread is fixed on future detections.
+/// Bumping the alias revision makes detection re-match regions it already
+/// read, even if the screen hasn't changed.
+#[tauri::command]
+pub fn report_misdetection(
+    raw_text: String,
+    wrong_card_id: String,
+    correct_card_id: String,
+    access: State<DatabaseAccess>,
+) -> Result<bool, String> {
+    if ocr::normalize_card_name(&raw_text).is_empty() {
+        return Err("OCR text cannot be empty".to_string());
+    }
+
+    let correction = (
+        raw_text.clone(),
+        wrong_card_id.clone(),
+        correct_card_id.clone(),
+    );
+    let recorded = access.write_and_wait(move |conn| {
+        let (raw_text, wrong_card_id, correct_card_id) = correction;
+        record_correction(conn, &raw_text, &wrong_card_id, &correct_card_id)
+    });
+    match recorded {
+        Ok(true) => {
+            log::info!(
+                "Learned OCR alias '{}' -> {} (was {})",
+                raw_text,
+                correct_card_id,
+                wrong_card_id
+            );
+            Ok(true)
+        }
+        Ok(false) => Err(format!("Unknown card: {}", correct_card_id)),
+        Err(e) => Err(format!("Failed to record correction: {}", e)),
+    }
+}
+
+/// Cards matching typed text, best first, using the detection matcher
+///
+/// Matches the cards the detection filter allows, with the same alternate
+/// names, translated names and, when enabled, card text as detection.
+fn match_text(
+    conn: &Connection,
+    snapshot: &CardSnapshot,
+    config: &CardDetectionOptions,
+    text: &str,
+    limit: usize,
+) -> rusqlite::Result<Vec<MatchCandidate>> {
+    let pool = card_pool(snapshot, &config.candidate_filter);
+    let Ok(matcher) = ocr::recognize::CardMatcher::new(card_names(&pool), MANUAL_MATCH_MIN_SCORE)
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut alternate_names = repository::load_card_aliases(conn)?;
+    if let Some(ref locale) = config.card_locale {
+        alternate_names.extend(snapshot.translated_names(locale));
     }
+
+    let mut matcher = matcher.with_alternate_names(alternate_names);
+    if config.match_card_text {
+        matcher = matcher.with_card_text(card_text(snapshot, &pool));
+    }
+
+    Ok(matcher
+        .find_all_matches(text, MANUAL_MATCH_MIN_SCORE)
+        .into_iter()
+        .take(limit)
+        .map(|card_match| MatchCandidate {
+            card_id: card_match.card_id,
+            card_name: card_match.card_name,
+            match_score: card_match.match_score,
+        })
+        .collect())
+}
+
+/// Tauri command: Match typed text to cards
+///
+/// A manual fallback when OCR can't read a card: the player types what they
+/// see and picks from the returned cards. `limit` defaults to 5 and is
+/// capped at 50.
+#[tauri::command]
+pub fn match_text_to_cards(
+    text: String,
+    limit: Option<usize>,
+    access: State<DatabaseAccess>,
+    cache: State<CardCache>,
+    ocr_state: State<OcrState>,
+) -> Result<Vec<MatchCandidate>, String> {
+    let limit = limit
+        .unwrap_or(DEFAULT_MANUAL_MATCH_LIMIT)
+        .min(MAX_MANUAL_MATCH_LIMIT);
+    let config = ocr_state.detection_config()?;
+
+    access
+        .read(|conn| {
+            let snapshot = cache.get(conn)?;
+            match_text(conn, &snapshot, &config, &text, limit)
+        })
+        .map_err(|e| format!("Failed to match card text: {}", e))
+}
+
+/// Tauri command: Calibrate overall confidence against detection history
+///
+/// Fits the OCR confidence / match score weights to past detections and
+/// the user's corrections, stores them for this install, and applies them
+/// to future detections.
+#[tauri::command]
+pub fn calibrate_ocr_confidence(
+    access: State<DatabaseAccess>,
+    ocr_state: State<OcrState>,
+) -> Result<ConfidenceCalibration, String> {
+    let samples = access
+        .read(load_calibration_samples)
+        .map_err(|e| e.to_string())?;
+    let current = ocr_state.detection_config()?.recognize.confidence_weights;
+
+    let calibration = ocr::confidence::fit_weights(&samples, &current).ok_or_else(|| {
+        format!(
+            "Not enough detection history to calibrate: need at least {} detections, \
+             including some corrected ones ({} available)",
+            ocr::confidence::MIN_CALIBRATION_SAMPLES,
+            samples.len()
+        )
+    })?;
+
+    let stored = calibration.clone();
+    access
+        .write_and_wait(move |conn| store_confidence_calibration(conn, &stored))
+        .map_err(|e| format!("Failed to store confidence calibration: {}", e))?;
+
+    let weights = calibration.weights;
+    ocr_state.update_detection_config(move |config| {
+        for recognize in config.recognize_configs_mut() {
+            recognize.confidence_weights = weights;
+        }
+    })?;
+
+    log::info!(
+        "Calibrated OCR confidence weights: ocr {:.2}, match {:.2} ({} detections)",
+        weights.ocr_weight,
+        weights.match_weight,
+        calibration.sample_count
+    );
+    Ok(calibration)
+}
+
+/// Tauri command: Report which cards OCR confuses and which regions read worst
+///
+/// Pairs come from the user's misdetection reports. Regions with a
+/// `region_index` are current capture regions worth recalibrating when their
+/// confidence is low. `limit` defaults to 20 and is capped at 100.
+#[tauri::command]
+pub fn get_ocr_confusion_report(
+    limit: Option<u32>,
+    access: State<DatabaseAccess>,
+    ocr_state: State<OcrState>,
+) -> Result<OcrConfusionReport, String> {
+    let limit = limit.unwrap_or(DEFAULT_CONFUSION_LIMIT).min(MAX_CONFUSION_LIMIT);
+    let config = ocr_state.detection_config()?;
+
+    access
+        .read(|conn| query_confusion_report(conn, limit, config.capture.get_regions()))
+        .map_err(|e| format!("Failed to build OCR confusion report: {}", e))
 }
 
 /// Tauri command: Calibrate OCR regions
@@ -260,11 +1356,7 @@ pub fn detect_cards_on_screen(
 pub fn calibrate_ocr_regions(
     ocr_state: State<OcrState>,
 ) -> Result<CalibrationResult, String> {
-    let config = ocr_state
-        .config
-        .lock()
-        .map_err(|e| format!("Failed to lock OCR config: {}", e))?
-        .clone();
+    let config = ocr_state.detection_config()?;
 
     #[cfg(not(feature = "ocr"))]
     {
@@ -278,45 +1370,131 @@ pub fn calibrate_ocr_regions(
             message: format!("Calibration failed: {}", e),
             screen_width: 0,
             screen_height: 0,
+            scale_factor: 1.0,
             recommended_regions: vec![],
             success_rate: 0.0,
         }),
     }
 }
 
+/// Tauri command: Preview what the OCR sees in each configured region
+///
+/// Captures every region and returns its raw and preprocessed images as
+/// base64 PNGs, without running OCR, so calibration can be checked visually.
+#[tauri::command]
+pub fn preview_capture_regions(
+    ocr_state: State<OcrState>,
+) -> Result<Vec<RegionPreviewInfo>, String> {
+    let config = ocr_state.detection_config()?;
+
+    Ok(ocr::preview_regions(&config)
+        .into_iter()
+        .map(RegionPreviewInfo::from)
+        .collect())
+}
+
+/// Tauri command: Capture the whole screen for the region picker
+///
+/// Returns a screenshot downscaled to at most `max_width` pixels wide
+/// (default 1280), with the physical screen size it maps to. Regions picked
+/// on it can be sent to `set_capture_regions` as normalized coordinates.
+#[tauri::command]
+pub fn capture_fullscreen_for_calibration(
+    max_width: Option<u32>,
+    ocr_state: State<OcrState>,
+) -> AppResult<FullscreenCapture> {
+    let backend = ocr_state.detection_config()?.capture.backend;
+    let display = ocr::get_primary_display_metrics()?;
+    let (screen_width, screen_height) = display.physical_size();
+    let screenshot = ocr::capture_fullscreen_with(backend)?;
+
+    let max_width = max_width.unwrap_or(DEFAULT_CALIBRATION_WIDTH).max(1);
+    let screenshot = if screenshot.width() > max_width {
+        let height = (screenshot.height() as u64 * max_width as u64 / screenshot.width() as u64)
+            .max(1) as u32;
+        image::imageops::resize(&screenshot, max_width, height, image::imageops::FilterType::Triangle)
+    } else {
+        screenshot
+    };
+
+    Ok(FullscreenCapture {
+        image_width: screenshot.width(),
+        image_height: screenshot.height(),
+        image_png: png_base64(DynamicImage::ImageRgba8(screenshot))?,
+        screen_width,
+        screen_height,
+        scale_factor: display.scale_factor,
+    })
+}
+
+/// Save the capture regions, keeping each region's overrides
+fn save_capture_regions(
+    app: &tauri::AppHandle,
+    access: &DatabaseAccess,
+    regions: Vec<CaptureRegionInfo>,
+    normalized_regions: Vec<NormalizedRegion>,
+) -> Result<(), String> {
+    let mut prefs: RegionPreferences =
+        settings::load_section(access, settings::REGIONS_KEY).map_err(|e| e.to_string())?;
+    prefs.regions = regions;
+    prefs.normalized_regions = normalized_regions;
+    settings::save_section(access, settings::REGIONS_KEY, &prefs).map_err(|e| e.to_string())?;
+    settings::notify_changed(app, access);
+    Ok(())
+}
+
 /// Tauri command: Set custom capture regions
+///
+/// The regions are saved and restored on the next start.
 #[tauri::command]
 pub fn set_capture_regions(
     request: SetRegionsRequest,
     ocr_state: State<OcrState>,
+    access: State<DatabaseAccess>,
+    app: tauri::AppHandle,
 ) -> Result<SetRegionsResult, String> {
+    if !request.normalized_regions.is_empty() {
+        let regions = request.normalized_regions;
+        if let Some(i) = regions.iter().position(|r| !r.is_valid()) {
+            return Err(format!("Normalized region {} must lie within 0.0-1.0", i + 1));
+        }
+
+        let (width, height) = ocr::get_primary_screen_dimensions().map_err(|e| e.to_string())?;
+        let count = regions.len();
+        let saved = regions.clone();
+        ocr_state.update_detection_config(move |config| {
+            config.capture.set_normalized_regions(regions, width, height)
+        })?;
+        save_capture_regions(&app, &access, Vec::new(), saved)?;
+
+        return Ok(SetRegionsResult {
+            success: true,
+            message: format!("Set {} capture regions for {}x{}", count, width, height),
+            regions_set: count,
+        });
+    }
+
     let regions: Vec<CaptureRegion> = request
         .regions
-        .into_iter()
-        .map(|r| CaptureRegion::new(r.x, r.y, r.width, r.height))
+        .iter()
+        .map(CaptureRegionInfo::to_region)
         .collect();
 
-    let mut config = ocr_state
-        .config
-        .lock()
-        .map_err(|e| format!("Failed to lock OCR config: {}", e))?;
-
-    config.capture.update_regions(regions.clone());
+    let count = regions.len();
+    ocr_state.update_detection_config(move |config| config.capture.update_regions(regions))?;
+    save_capture_regions(&app, &access, request.regions, Vec::new())?;
 
     Ok(SetRegionsResult {
         success: true,
-        message: format!("Set {} capture regions", regions.len()),
-        regions_set: regions.len(),
+        message: format!("Set {} capture regions", count),
+        regions_set: count,
     })
 }
 
 /// Tauri command: Get current capture regions
 #[tauri::command]
 pub fn get_capture_regions(ocr_state: State<OcrState>) -> Result<Vec<CaptureRegionInfo>, String> {
-    let config = ocr_state
-        .config
-        .lock()
-        .map_err(|e| format!("Failed to lock OCR config: {}", e))?;
+    let config = ocr_state.detection_config()?;
 
     let regions: Vec<CaptureRegionInfo> = config
         .capture
@@ -333,18 +1511,18 @@ pub fn get_capture_regions(ocr_state: State<OcrState>) -> Result<Vec<CaptureRegi
 #[tauri::command]
 pub fn reset_capture_regions(
     ocr_state: State<OcrState>,
+    access: State<DatabaseAccess>,
+    app: tauri::AppHandle,
 ) -> Result<SetRegionsResult, String> {
-    let mut config = ocr_state
-        .config
-        .lock()
-        .map_err(|e| format!("Failed to lock OCR config: {}", e))?;
-
     // Get screen dimensions and reset to default regions
     match ocr::capture::get_primary_screen_dimensions() {
         Ok((width, height)) => {
             let default_regions = ocr::capture::get_default_card_regions(width, height);
             let count = default_regions.len();
-            config.capture.update_regions(default_regions);
+            ocr_state.update_detection_config(move |config| {
+                config.capture.update_regions(default_regions)
+            })?;
+            save_capture_regions(&app, &access, Vec::new(), Vec::new())?;
 
             Ok(SetRegionsResult {
                 success: true,
@@ -361,32 +1539,384 @@ pub fn reset_capture_regions(
 }
 
 /// Tauri command: Update OCR configuration
+///
+/// `preprocess_steps` replaces the whole preprocessing pipeline; it must
+/// start with a grayscale step.
 #[tauri::command]
 pub fn update_ocr_config(
     min_confidence: Option<f64>,
     save_debug: Option<bool>,
+    preprocess_steps: Option<Vec<PreprocessStep>>,
+    ocr_state: State<OcrState>,
+    access: State<DatabaseAccess>,
+    app: tauri::AppHandle,
+) -> Result<bool, String> {
+    if let Some(ref steps) = preprocess_steps {
+        ocr::preprocess::validate_steps(steps).map_err(|e| e.to_string())?;
+    }
+
+    let persist = min_confidence.is_some() || save_debug.is_some();
+    if persist {
+        let mut prefs: OcrPreferences =
+            settings::load_section(&access, settings::OCR_KEY).map_err(|e| e.to_string())?;
+        if let Some(confidence) = min_confidence {
+            prefs.min_confidence = confidence.clamp(0.0, 1.0);
+        }
+        if let Some(debug) = save_debug {
+            prefs.save_debug_images = debug;
+        }
+        settings::save_section(&access, settings::OCR_KEY, &prefs).map_err(|e| e.to_string())?;
+    }
+
+    ocr_state.update_detection_config(move |config| {
+        if let Some(steps) = preprocess_steps {
+            config.preprocess.steps = steps;
+        }
+
+        if let Some(confidence) = min_confidence {
+            config.min_overall_confidence = confidence.clamp(0.0, 1.0);
+        }
+
+        if let Some(debug) = save_debug {
+            config.save_debug_images = debug;
+        }
+    })?;
+
+    if persist {
+        settings::notify_changed(&app, &access);
+    }
+
+    Ok(true)
+}
+
+/// Tauri command: Give one capture region its own preprocessing and OCR settings
+///
+/// Each setting left out keeps the global one; `whitelist` of `""` allows
+/// every character. With none given, the region goes back to the global
+/// settings. For a HUD number, pass a digit whitelist and `psm` 8. The
+/// profile is saved and restored on the next start.
+#[tauri::command]
+pub fn set_region_profile(
+    region_index: usize,
+    preprocess_steps: Option<Vec<PreprocessStep>>,
+    psm: Option<i32>,
+    whitelist: Option<String>,
     ocr_state: State<OcrState>,
+    access: State<DatabaseAccess>,
+    app: tauri::AppHandle,
 ) -> Result<bool, String> {
-    let mut config = ocr_state
-        .config
-        .lock()
-        .map_err(|e| format!("Failed to lock OCR config: {}", e))?;
+    let profile = RegionProfilePreferences {
+        preprocess_steps,
+        psm,
+        whitelist,
+    };
+    profile.validate().map_err(|e| e.to_string())?;
 
-    if let Some(confidence) = min_confidence {
-        config.min_overall_confidence = confidence.clamp(0.0, 1.0);
+    let region_count = ocr_state.detection_config()?.capture.get_regions().len();
+    if region_index >= region_count {
+        return Err(format!(
+            "Region {} does not exist ({} regions configured)",
+            region_index, region_count
+        ));
     }
 
-    if let Some(debug) = save_debug {
-        config.save_debug_images = debug;
+    let mut prefs: RegionPreferences =
+        settings::load_section(&access, settings::REGIONS_KEY).map_err(|e| e.to_string())?;
+    if profile.is_empty() {
+        prefs.profiles.remove(&region_index);
+    } else {
+        prefs.profiles.insert(region_index, profile.clone());
     }
+    settings::save_section(&access, settings::REGIONS_KEY, &prefs).map_err(|e| e.to_string())?;
+
+    ocr_state.update_detection_config(move |config| {
+        if profile.is_empty() {
+            config.region_profiles.remove(&region_index);
+        } else {
+            let region_profile = profile.to_profile(&config.recognize);
+            config.region_profiles.insert(region_index, region_profile);
+        }
+    })?;
+    settings::notify_changed(&app, &access);
 
     Ok(true)
 }
 
+/// Tauri command: Restrict matching to cards plausible in the current run
+///
+/// Pass the player's clans, the expansions in play, and the current ring;
+/// `None` clears the filter so every active card is matched again.
+#[tauri::command]
+pub fn set_detection_filter(
+    filter: Option<CandidateFilter>,
+    ocr_state: State<OcrState>,
+) -> Result<CandidateFilter, String> {
+    ocr_state
+        .update_detection_config(move |config| {
+            config.candidate_filter = filter.unwrap_or_default();
+        })
+        .map(|config| config.candidate_filter)
+}
+
+/// Tauri command: Configure dataset archival
+///
+/// When enabled, the background compaction job periodically moves old
+/// detection rows and debug crops into compressed cold storage.
+#[tauri::command]
+pub fn set_archive_config(
+    enabled: bool,
+    max_age_days: Option<u32>,
+    ocr_state: State<OcrState>,
+) -> Result<ArchiveConfig, String> {
+    ocr_state
+        .controller()
+        .update_settings(move |settings| {
+            settings.archive.enabled = enabled;
+            if let Some(days) = max_age_days {
+                settings.archive.max_age_days = days;
+            }
+        })
+        .map(|settings| settings.archive)
+        .map_err(|e| e.to_string())
+}
+
+/// Tauri command: Run an archive compaction pass immediately
+#[tauri::command]
+pub fn compact_ocr_archive(
+    access: State<DatabaseAccess>,
+    ocr_state: State<OcrState>,
+) -> Result<CompactionReport, String> {
+    let settings = ocr_state.controller().settings().map_err(|e| e.to_string())?;
+    let image_dir = settings.detection.debug_image_path;
+
+    ocr::archive::run_compaction(&access, image_dir.as_deref(), &settings.archive)
+        .map_err(|e| e.to_string())
+}
+
+/// Folder, next to the database, that holds recorded OCR sessions
+const RECORDINGS_DIR: &str = "ocr-recordings";
+
+/// Tauri command: Start recording OCR passes for a bug report
+///
+/// Every detection pass (manual or continuous) saves each region's raw
+/// capture, preprocessed image, OCR text and match decision into a new
+/// timestamped session folder with a `manifest.json`.
+#[tauri::command]
+pub fn start_ocr_recording(
+    db_state: State<DatabaseState>,
+    ocr_state: State<OcrState>,
+) -> Result<RecordingStatus, String> {
+    let parent = app_data_dir(&db_state.db_path)?.join(RECORDINGS_DIR);
+    let session_dir = SessionRecorder::session_dir(&parent);
+    std::fs::create_dir_all(&session_dir)
+        .map_err(|e| format!("Failed to create recording folder: {}", e))?;
+
+    let dir = session_dir.clone();
+    ocr_state.update_detection_config(move |config| config.recording_dir = Some(dir))?;
+
+    log::info!("OCR session recording started in {}", session_dir.display());
+    Ok(RecordingStatus {
+        recording: true,
+        session_dir: Some(session_dir.to_string_lossy().to_string()),
+    })
+}
+
+/// Tauri command: Stop recording OCR passes
+///
+/// Returns the folder that was recorded to, if a recording was active.
+#[tauri::command]
+pub fn stop_ocr_recording(ocr_state: State<OcrState>) -> Result<RecordingStatus, String> {
+    let previous = ocr_state.detection_config()?.recording_dir;
+    ocr_state.update_detection_config(|config| config.recording_dir = None)?;
+
+    if let Some(ref dir) = previous {
+        log::info!("OCR session recording stopped ({})", dir.display());
+    }
+    Ok(RecordingStatus {
+        recording: false,
+        session_dir: previous.map(|dir| dir.to_string_lossy().to_string()),
+    })
+}
+
+/// Tauri command: Teach the template glyph engine from a recorded OCR session
+///
+/// Every recorded region whose text matched a card is cut into glyphs and
+/// added to the app glyph atlas, which the `template_glyph` engine reads
+/// without Tesseract. Learning from several sessions extends one atlas.
+#[tauri::command]
+pub fn build_glyph_atlas(
+    session_dir: String,
+    db_state: State<DatabaseState>,
+    ocr_state: State<OcrState>,
+) -> Result<LearnReport, String> {
+    let atlas_path = app_data_dir(&db_state.db_path)?.join(glyph::ATLAS_FILE);
+    let mut atlas = if atlas_path.is_file() {
+        GlyphAtlas::load(&atlas_path).map_err(|e| e.to_string())?
+    } else {
+        GlyphAtlas::default()
+    };
+
+    let report = atlas
+        .learn_from_session(Path::new(&session_dir))
+        .map_err(|e| e.to_string())?;
+    atlas.save(&atlas_path).map_err(|e| e.to_string())?;
+
+    let path = atlas_path.to_string_lossy().to_string();
+    ocr_state.update_detection_config(move |config| {
+        for recognize in config.recognize_configs_mut() {
+            recognize.glyph_atlas_path = Some(path.clone());
+        }
+    })?;
+
+    log::info!(
+        "Glyph atlas learned {} samples from {} ({} characters)",
+        report.samples_used,
+        session_dir,
+        report.characters.chars().count()
+    );
+    Ok(report)
+}
+
+/// Tauri command: Report whether OCR can run, with version and data path
+#[tauri::command]
+pub fn get_ocr_status(ocr_state: State<OcrState>) -> Result<OcrStatus, String> {
+    let config = ocr_state.detection_config()?;
+    Ok(ocr::status::ocr_status(&config.recognize))
+}
+
+/// Tauri command: Report which capture backend is in use and which can run
+///
+/// With `probe`, a full-screen test capture is taken through the active
+/// backend and its error, if any, is reported; under Wayland this may show
+/// the desktop portal's permission prompt.
+#[tauri::command]
+pub fn get_capture_backend_status(
+    probe: Option<bool>,
+    ocr_state: State<OcrState>,
+) -> Result<CaptureBackendStatus, String> {
+    let backend = ocr_state.detection_config()?.capture.backend;
+    let mut status = capture::capture_backend_status(backend, DisplaySession::detect());
+    if probe.unwrap_or(false) {
+        status.probe_error = ocr::capture_fullscreen_with(backend)
+            .err()
+            .map(|e| e.to_string());
+    }
+    Ok(status)
+}
+
+/// Tauri command: List installed Tesseract language packs
+///
+/// Includes packs downloaded into the app data folder and those found in
+/// the usual system tessdata locations.
+#[tauri::command]
+pub fn list_tesseract_languages(db_state: State<DatabaseState>) -> Result<Vec<LanguagePack>, String> {
+    let app_dir = tessdata::app_tessdata_dir(app_data_dir(&db_state.db_path)?);
+    Ok(tessdata::list_installed(&app_dir, &tessdata::system_tessdata_dirs()))
+}
+
+/// Tauri command: Download a Tesseract language pack (e.g. `eng`)
+///
+/// The pack is saved in the app data folder. If it completes the configured
+/// OCR language, `tesseract_data_path` is pointed at that folder.
+#[tauri::command]
+pub async fn download_tesseract_language(
+    language: String,
+    db_state: State<'_, DatabaseState>,
+    ocr_state: State<'_, OcrState>,
+) -> Result<LanguagePack, String> {
+    let db_path = db_state.db_path.clone();
+    let app_dir = tessdata::app_tessdata_dir(app_data_dir(&db_path)?);
+
+    let pack = tokio::task::spawn_blocking(move || tessdata::download_language(&app_dir, &language))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    ocr_state.update_detection_config(move |config| {
+        for recognize in config.recognize_configs_mut() {
+            if let Some(dir) = app_tessdata_path(&db_path, &recognize.language) {
+                recognize.tesseract_data_path = Some(dir);
+            }
+        }
+    })?;
+
+    Ok(pack)
+}
+
+/// Interval between background archive compaction passes
+const ARCHIVE_COMPACTION_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Start the background job that compacts old OCR data while archival is enabled
+pub fn spawn_archive_compaction(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(ARCHIVE_COMPACTION_INTERVAL);
+
+        let settings = match app.state::<OcrState>().controller().settings() {
+            Ok(settings) if settings.archive.enabled => settings,
+            _ => continue,
+        };
+        let image_dir = settings.detection.debug_image_path;
+
+        let access = app.state::<DatabaseAccess>();
+        if let Err(e) =
+            ocr::archive::run_compaction(&access, image_dir.as_deref(), &settings.archive)
+        {
+            log::warn!("Background archive compaction failed: {}", e);
+        }
+    });
+}
+
+/// Run one detection off the calling thread and emit it as `ocr-detection`
+///
+/// For triggers that run on threads which must not block, such as shortcut
+/// handlers and the continuous loop's result callback.
+pub fn detect_in_background(app: &tauri::AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || match app.state::<OcrState>().controller().detect_once() {
+        Ok(response) => {
+            app.state::<SessionState>().note_detection(&response);
+            if let Err(e) = app.emit("ocr-detection", response) {
+                log::warn!("Failed to emit OCR detection: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Background detection failed: {}", e),
+    });
+}
+
+/// Payload of the `new-offer` event
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewOffer {
+    pub cards: Vec<DetectedCardInfo>,
+    /// Scores of the offered cards, empty until a champion is chosen
+    pub scores: Vec<CardScore>,
+}
+
+/// Score a new draft offer and emit it as `new-offer`
+///
+/// Called for continuous detection results with `new_offer` set, so each
+/// offer is scored once rather than on every frame it stays on screen.
+pub fn emit_new_offer(app: &tauri::AppHandle, detection: &CardDetectionResponse) {
+    let scores = match app.state::<SessionState>().snapshot() {
+        Ok(session) => server::score_for_session(app, &session, detection),
+        Err(e) => {
+            log::warn!("Failed to read session for offer scores: {}", e);
+            Vec::new()
+        }
+    };
+    let offer = NewOffer {
+        cards: detection.details.clone(),
+        scores,
+    };
+    if let Err(e) = app.emit("new-offer", offer) {
+        log::warn!("Failed to emit new offer: {}", e);
+    }
+}
+
 /// Tauri command: Test OCR on a specific region
 ///
-/// This is useful for debugging OCR issues on specific screen regions.
-/// Only available when OCR feature is enabled.
+/// This is useful for debugging OCR issues on specific screen regions. The
+/// text is matched with the same matcher, settings and normalized
+/// confidence as live detection. Only available when OCR feature is enabled.
 #[cfg(feature = "ocr")]
 #[tauri::command]
 pub fn test_ocr_region(
@@ -394,53 +1924,55 @@ pub fn test_ocr_region(
     y: i32,
     width: u32,
     height: u32,
-    db_state: State<DatabaseState>,
-) -> Result<DetectedCardInfo, String> {
+    access: State<DatabaseAccess>,
+    cache: State<CardCache>,
+    ocr_state: State<OcrState>,
+) -> AppResult<DetectedCardInfo> {
     use crate::ocr::capture::capture_region;
     use crate::ocr::preprocess::preprocess_default;
-    use crate::ocr::recognize::OcrEngine;
-    use fuzzy_matcher::skim::SkimMatcherV2;
-    use fuzzy_matcher::FuzzyMatcher;
+    use crate::ocr::recognize::{CardMatcher, OcrEngine};
 
-    // Get card names from database
-    let card_names = get_card_names_from_db(&db_state.db_path)?;
+    // Get the card pool from the card cache
+    let snapshot = access.read(|conn| cache.get(conn))?;
+    let pool = card_pool(&snapshot, &CandidateFilter::default());
+    let config = ocr_state.detection_config()?.recognize;
 
     // Capture the region
     let region = CaptureRegion::new(x, y, width, height);
-    let rgba_image = capture_region(&region).map_err(|e| e.to_string())?;
+    let rgba_image = capture_region(&region)?;
 
     // Preprocess
-    let gray_image = preprocess_default(&rgba_image).map_err(|e| e.to_string())?;
+    let gray_image = preprocess_default(&rgba_image)?;
 
     // Run OCR
-    let ocr_engine = OcrEngine::new().map_err(|e| e.to_string())?;
-    let ocr_result = ocr_engine.recognize(&gray_image).map_err(|e| e.to_string())?;
+    let ocr_engine = OcrEngine::with_config(config.clone())?;
+    let ocr_result = ocr_engine.recognize(&gray_image)?;
 
     // Find best matching card
-    let matcher = SkimMatcherV2::default();
-    let mut best_match: Option<(String, String, i64)> = None;
-    let ocr_text = ocr_result.text.to_lowercase();
-
-    for (card_id, card_name) in &card_names {
-        if let Some(score) = matcher.fuzzy_match(&card_name.to_lowercase(), &ocr_text) {
-            if best_match.as_ref().map_or(true, |(_, _, s)| score > *s) {
-                best_match = Some((card_id.clone(), card_name.clone(), score));
-            }
-        }
-    }
+    let matcher = CardMatcher::new(card_names(&pool), config.min_match_score)?
+        .with_ambiguity(config.ambiguity_margin, config.max_candidates)
+        .with_confidence_weights(config.confidence_weights)
+        .with_card_attributes(&pool);
+    let banner = ocr::analyze_banner(&rgba_image);
+    let mut card_match = matcher
+        .find_best_match_with_banner(&ocr_result.text, banner.as_ref())
+        .ok_or_else(|| AppError::NotFound("No matching card found".to_string()))?;
+    matcher.apply_confidence(&mut card_match, ocr_result.confidence);
 
-    match best_match {
-        Some((card_id, card_name, match_score)) => Ok(DetectedCardInfo {
-            card_id,
-            card_name,
-            confidence: match_score as f64 / 100.0,
-            ocr_confidence: ocr_result.confidence,
-            match_score: match_score.min(100) as i32,
-            raw_text: ocr_result.text,
-            region: region.into(),
-        }),
-        None => Err("No matching card found".to_string()),
+    Ok(DetectedCard {
+        card_id: card_match.card_id,
+        card_name: card_match.card_name,
+        region,
+        ocr_confidence: card_match.ocr_confidence,
+        match_score: card_match.match_score,
+        overall_confidence: card_match.overall_confidence,
+        raw_ocr_text: ocr_result.text,
+        candidates: card_match.candidates,
+        banner: card_match.banner,
+        rules_text: None,
+        upgrade_count: None,
     }
+    .into())
 }
 
 /// Mock implementation when OCR feature is disabled
@@ -451,10 +1983,15 @@ pub fn test_ocr_region(
     _y: i32,
     _width: u32,
     _height: u32,
-    _db_state: State<DatabaseState>,
-) -> Result<DetectedCardInfo, String> {
+    _access: State<DatabaseAccess>,
+    _cache: State<CardCache>,
+    _ocr_state: State<OcrState>,
+) -> AppResult<DetectedCardInfo> {
     log::error!("test_ocr_region called but OCR feature is disabled");
-    Err("OCR feature is not enabled. Rebuild with --features ocr to use this functionality.".to_string())
+    Err(AppError::Unavailable(
+        "OCR feature is not enabled. Rebuild with --features ocr to use this functionality."
+            .to_string(),
+    ))
 }
 
 #[cfg(test)]
@@ -481,6 +2018,10 @@ mod tests {
             match_score: 90,
             overall_confidence: 0.87,
             raw_ocr_text: "Test".to_string(),
+            candidates: vec![],
+            banner: None,
+            rules_text: None,
+            upgrade_count: None,
         };
 
         let info: DetectedCardInfo = card.into();
@@ -501,6 +2042,9 @@ mod tests {
             success: true,
             error: None,
             details: vec![],
+            unavailable: None,
+            region_hashes: vec![],
+            new_offer: false,
         };
 
         assert_eq!(response.detected_cards.len(), 2);
@@ -508,6 +2052,79 @@ mod tests {
         assert!(response.error.is_none());
     }
 
+    #[test]
+    fn test_is_draft_screen() {
+        let mut response = CardDetectionResponse {
+            detected_cards: vec!["Card A".to_string(), "Card B".to_string()],
+            confidence: 0.85,
+            success: true,
+            error: None,
+            details: vec![],
+            unavailable: None,
+            region_hashes: vec![],
+            new_offer: false,
+        };
+        assert!(response.is_draft_screen());
+
+        response.detected_cards.pop();
+        assert!(!response.is_draft_screen());
+
+        response.detected_cards.push("Card C".to_string());
+        response.success = false;
+        assert!(!response.is_draft_screen());
+    }
+
+    #[test]
+    fn test_stabilizer_votes_and_holds_until_screen_changes() {
+        let frame = |ids: &[&str]| {
+            let details: Vec<DetectedCardInfo> =
+                ids.iter().map(|id| detected(id, id, 0.8).into()).collect();
+            CardDetectionResponse {
+                detected_cards: ids.iter().map(|id| id.to_string()).collect(),
+                confidence: 0.8,
+                success: true,
+                error: None,
+                details,
+                unavailable: None,
+                region_hashes: vec![],
+                new_offer: false,
+            }
+        };
+        let config = CardDetectionOptions::default();
+        assert_eq!((config.stable_window, config.stable_votes), (3, 2));
+        let mut stabilizer = DetectionStabilizer::default();
+
+        // Nothing is shown until a card is seen in 2 of 3 frames
+        assert!(stabilizer.update(&config, &frame(&["fel", "talos"])).detected_cards.is_empty());
+        let stable = stabilizer.update(&config, &frame(&["fel", "cleave"]));
+        assert_eq!(stable.detected_cards, vec!["fel"]);
+        let stable = stabilizer.update(&config, &frame(&["fel", "talos"]));
+        assert_eq!(stable.detected_cards, vec!["fel", "talos"]);
+        assert!(stable.is_draft_screen());
+
+        // A frame missing a card, or a stray misread, doesn't change the result
+        let stable = stabilizer.update(&config, &frame(&["fel"]));
+        assert_eq!(stable.detected_cards, vec!["fel", "talos"]);
+        let stable = stabilizer.update(&config, &frame(&["fel", "talos", "cleave"]));
+        assert_eq!(stable.detected_cards, vec!["fel", "talos"]);
+
+        // A new offer replaces the held cards once it is stable
+        assert!(stabilizer.update(&config, &frame(&["a", "b"])).detected_cards.is_empty());
+        let stable = stabilizer.update(&config, &frame(&["a", "b"]));
+        assert_eq!(stable.detected_cards, vec!["a", "b"]);
+
+        // Leaving the draft screen releases them after a full window
+        assert_eq!(stabilizer.update(&config, &frame(&[])).detected_cards.len(), 2);
+        assert_eq!(stabilizer.update(&config, &frame(&[])).detected_cards.len(), 2);
+        assert!(stabilizer.update(&config, &frame(&[])).detected_cards.is_empty());
+
+        let failed = CardDetectionResponse {
+            success: false,
+            ..frame(&["fel", "talos"])
+        };
+        assert!(!stabilizer.update(&config, &failed).success);
+    }
+
     #[test]
     fn test_set_regions_request() {
         let request = SetRegionsRequest {
@@ -517,18 +2134,295 @@ mod tests {
                     y: 200,
                     width: 300,
                     height: 400,
+                    role: Some(RegionRole::Gold),
                 },
             ],
+            normalized_regions: vec![],
         };
 
         assert_eq!(request.regions.len(), 1);
         assert_eq!(request.regions[0].x, 100);
+
+        // Either coordinate system may be omitted
+        let request: SetRegionsRequest = serde_json::from_str(
+            r#"{"normalized_regions": [
+                {"x": 0.1, "y": 0.2, "width": 0.3, "height": 0.05, "role": "ring"}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(request.regions.is_empty());
+        assert!(request.normalized_regions[0].is_valid());
+        assert_eq!(
+            request.normalized_regions[0].to_pixels(1920, 1080).role,
+            Some(RegionRole::Ring)
+        );
+    }
+
+    fn setup_test_db() -> (Connection, tempfile::NamedTempFile) {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        (conn, temp_file)
+    }
+
+    fn detected(card_id: &str, card_name: &str, confidence: f64) -> DetectedCard {
+        DetectedCard {
+            card_id: card_id.to_string(),
+            card_name: card_name.to_string(),
+            region: ocr::capture::CaptureRegion::new(10, 20, 300, 60),
+            ocr_confidence: 80,
+            match_score: 90,
+            overall_confidence: confidence,
+            raw_ocr_text: card_name.to_lowercase(),
+            candidates: vec![],
+            banner: None,
+            rules_text: None,
+            upgrade_count: None,
+        }
+    }
+
+    #[test]
+    fn test_record_and_query_detection_history() {
+        let (conn, _temp) = setup_test_db();
+
+        let cards = vec![
+            detected("banished_fel", "Fel", 0.86),
+            detected("banished_talos", "Talos", 0.72),
+        ];
+        assert_eq!(record_detections(&conn, &cards).unwrap(), 2);
+        assert_eq!(record_detections(&conn, &[]).unwrap(), 0);
+
+        let history = query_detection_history(&conn, 10, None).unwrap();
+        assert_eq!(history.len(), 2);
+        // Newest first
+        assert_eq!(history[0].card_id, "banished_talos");
+        assert_eq!(history[1].raw_text, "fel");
+        assert_eq!(history[1].region.width, 300);
+        assert!(!history[1].detected_at.is_empty());
+
+        let filtered = query_detection_history(&conn, 10, Some("banished_fel")).unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert!((filtered[0].overall_confidence - 0.86).abs() < 0.001);
+
+        let limited = query_detection_history(&conn, 1, None).unwrap();
+        assert_eq!(limited.len(), 1);
+    }
+
+    #[test]
+    fn test_load_seeded_card_aliases() {
+        let (conn, _temp) = setup_test_db();
+
+        let aliases = repository::load_card_aliases(&conn).unwrap();
+        assert!(aliases
+            .iter()
+            .any(|(card_id, alias)| card_id == "banished_talos" && alias == "Ta1os"));
+
+        // Re-seeding is idempotent
+        let count = aliases.len();
+        repository::seed_card_aliases(&conn).unwrap();
+        assert_eq!(repository::load_card_aliases(&conn).unwrap().len(), count);
+    }
+
+    #[test]
+    fn test_record_correction_updates_aliases() {
+        let (conn, _temp) = setup_test_db();
+
+        assert!(record_correction(&conn, "Ta1os", "banished_fel", "banished_talos").unwrap());
+        assert!(record_correction(&conn, "TA1OS ", "", "banished_talos").unwrap());
+        assert!(!record_correction(&conn, "Ta1os", "", "not_a_card").unwrap());
+
+        let aliases = repository::load_ocr_aliases(&conn).unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases.get("ta1os").map(String::as_str), Some("banished_talos"));
+
+        let (corrections, times_reported): (i64, i64) = conn
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM ocr_corrections), times_reported FROM ocr_aliases",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(corrections, 2);
+        assert_eq!(times_reported, 2);
+        // Only the corrections of known cards changed the aliases
+        assert_eq!(repository::ocr_aliases_revision(&conn).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_confidence_calibration_samples_and_storage() {
+        let (conn, temp) = setup_test_db();
+
+        let mut cards = vec![detected("banished_fel", "Fel", 0.86); 3];
+        cards.push(detected("banished_talos", "Talos", 0.72));
+        record_detections(&conn, &cards).unwrap();
+        record_correction(&conn, "talos", "banished_talos", "banished_cleave").unwrap();
+
+        let samples = load_calibration_samples(&conn).unwrap();
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples.iter().filter(|s| !s.correct).count(), 1);
+        // Newest first
+        assert!(!samples[0].correct);
+
+        let access = DatabaseAccess::new(temp.path().to_path_buf());
+        assert!(load_confidence_weights(&access).is_none());
+        let calibration = ConfidenceCalibration {
+            weights: ConfidenceWeights {
+                ocr_weight: 0.25,
+                match_weight: 0.7,
+            },
+            sample_count: 4,
+            corrected_count: 1,
+            brier_before: 0.2,
+            brier_after: 0.1,
+        };
+        store_confidence_calibration(&conn, &calibration).unwrap();
+        store_confidence_calibration(&conn, &calibration).unwrap();
+        assert_eq!(load_confidence_weights(&access), Some(calibration.weights));
+
+        // Stored weights are picked up when the controller starts
+        let state = OcrState::new(temp.path().to_path_buf());
+        let config = state.detection_config().unwrap();
+        assert_eq!(config.recognize.confidence_weights, calibration.weights);
+    }
+
+    #[test]
+    fn test_confusion_report_ranks_pairs_and_regions() {
+        let (conn, _temp) = setup_test_db();
+
+        let mut cards = vec![detected("banished_fel", "Fel", 0.86); 2];
+        let mut low = detected("banished_talos", "Talos", 0.41);
+        low.region = ocr::capture::CaptureRegion::new(400, 20, 300, 60);
+        cards.push(low);
+        record_detections(&conn, &cards).unwrap();
+        record_correction(&conn, "talos", "banished_talos", "banished_cleave").unwrap();
+        record_correction(&conn, "talos", "banished_talos", "banished_cleave").unwrap();
+        record_correction(&conn, "fel", "banished_fel", "banished_talos").unwrap();
+        // Corrections without a detected card aren't confusions
+        record_correction(&conn, "???", "", "banished_fel").unwrap();
+
+        let current =
+            [ocr::capture::CaptureRegion::new(10, 20, 300, 60).with_role(RegionRole::CardName1)];
+        let report = query_confusion_report(&conn, 10, &current).unwrap();
+        assert_eq!(report.confused_pairs.len(), 2);
+        let top = &report.confused_pairs[0];
+        assert_eq!(top.detected_card_name, "Talos");
+        assert_eq!(top.actual_card_id, "banished_cleave");
+        assert_eq!(top.corrections, 2);
+
+        assert_eq!(report.regions.len(), 2);
+        assert_eq!(report.regions[0].region.x, 400);
+        assert_eq!(report.regions[0].region_index, None);
+        assert_eq!(report.regions[0].region.role, None);
+        assert_eq!(report.regions[0].corrected, 1);
+        assert_eq!(report.regions[1].region_index, Some(0));
+        assert_eq!(report.regions[1].region.role, Some(RegionRole::CardName1));
+        assert_eq!(report.regions[1].detections, 2);
+        assert_eq!(report.regions[1].corrected, 2);
+
+        assert_eq!(query_confusion_report(&conn, 1, &current).unwrap().regions.len(), 1);
+    }
+
+    #[test]
+    fn test_region_preview_info_encodes_png() {
+        let preview = RegionPreview {
+            region: CaptureRegion::new(10, 20, 4, 2),
+            raw_image: Some(image::RgbaImage::from_pixel(4, 2, image::Rgba([200, 10, 10, 255]))),
+            preprocessed_image: None,
+            error: Some("Preprocessing failed".to_string()),
+        };
+
+        let info = RegionPreviewInfo::from(preview);
+        let png = base64::engine::general_purpose::STANDARD
+            .decode(info.raw_png.unwrap())
+            .unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+        assert_eq!(image::load_from_memory(&png).unwrap().width(), 4);
+        assert!(info.preprocessed_png.is_none());
+        assert_eq!(info.error.as_deref(), Some("Preprocessing failed"));
+        assert_eq!(info.region.x, 10);
+    }
+
+    #[test]
+    fn test_card_pool_respects_candidate_filter() {
+        let (conn, _temp) = setup_test_db();
+
+        let cache = CardCache::default();
+        let all = card_pool(&cache.get(&conn).unwrap(), &CandidateFilter::default());
+        assert!(all.iter().any(|entry| entry.card_id == "banished_fel"));
+
+        let filter = CandidateFilter {
+            clans: vec!["Banished".to_string()],
+            expansions: vec![],
+            ring_number: Some(1),
+        };
+        let pool = card_pool(&cache.get(&conn).unwrap(), &filter);
+        assert!(pool.iter().any(|entry| entry.card_id == "banished_just_cause"));
+        assert!(!pool.iter().any(|entry| entry.card_id == "banished_fel"));
+        assert!(!pool.iter().any(|entry| entry.card_id == "pyreborne_lord_fenix"));
+
+        // Cards from a disabled expansion are never matched
+        conn.execute("UPDATE expansions SET is_active = 0 WHERE id = 'base'", [])
+            .unwrap();
+        cache.invalidate();
+        let pool = card_pool(&cache.get(&conn).unwrap(), &CandidateFilter::default());
+        assert!(pool.len() < all.len());
+        assert!(!pool.iter().any(|entry| entry.card_id == "banished_just_cause"));
+    }
+
+    #[test]
+    fn test_match_text_lists_close_cards() {
+        let (conn, _temp) = setup_test_db();
+        let snapshot = CardCache::default().get(&conn).unwrap();
+        let config = CardDetectionOptions::default();
+
+        let matches = match_text(&conn, &snapshot, &config, "just caus", 3).unwrap();
+        assert_eq!(matches[0].card_id, "banished_just_cause");
+        assert!(matches.len() <= 3);
+        assert!(matches.windows(2).all(|pair| pair[0].match_score >= pair[1].match_score));
+
+        // Seeded misreads match their card too
+        let matches = match_text(&conn, &snapshot, &config, "Ta1os", 1).unwrap();
+        assert_eq!(matches[0].card_id, "banished_talos");
+
+        assert!(match_text(&conn, &snapshot, &config, "  ", 5).unwrap().is_empty());
+
+        // Card text is only matched once enabled
+        let opening = "0-cost Advance spell";
+        assert!(match_text(&conn, &snapshot, &config, opening, 1).unwrap().is_empty());
+        let config = CardDetectionOptions {
+            match_card_text: true,
+            ..config
+        };
+        let matches = match_text(&conn, &snapshot, &config, opening, 1).unwrap();
+        assert_eq!(matches[0].card_id, "banished_just_cause");
     }
 
     #[test]
     fn test_ocr_state_new() {
-        let state = OcrState::new();
-        let config = state.config.lock().unwrap();
+        let state = OcrState::new(PathBuf::from("unused.db"));
+        let config = state.detection_config().unwrap();
         assert!(!config.save_debug_images);
     }
+
+    #[test]
+    fn test_ocr_state_update_detection_config() {
+        let state = OcrState::new(PathBuf::from("unused.db"));
+        state
+            .update_detection_config(|config| config.min_overall_confidence = 0.75)
+            .unwrap();
+        assert!((state.detection_config().unwrap().min_overall_confidence - 0.75).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_ocr_state_detect_once_without_cards() {
+        let (_conn, temp) = setup_test_db();
+        let conn = Connection::open(temp.path()).unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = OFF; DELETE FROM card_aliases; DELETE FROM cards;")
+            .unwrap();
+
+        let state = OcrState::new(temp.path().to_path_buf());
+        let response = state.controller().detect_once().unwrap();
+        assert!(!response.success);
+        assert_eq!(response.error.as_deref(), Some("No cards found in database"));
+    }
 }
@@ -0,0 +1,55 @@
+//! Player profile commands
+
+use crate::database::{DatabaseState, PROGRESS_EVENT};
+use crate::error::{AppError, AppResult};
+use crate::profiles::{self, Profile, ProfileList};
+use std::path::Path;
+use tauri::{AppHandle, Emitter, State};
+
+/// The app data folder, which holds every profile's database
+fn app_dir(db_path: &Path) -> AppResult<&Path> {
+    db_path
+        .parent()
+        .ok_or_else(|| AppError::Internal("Cannot determine app data folder".to_string()))
+}
+
+/// Tauri command: List the profiles and which one is active
+#[tauri::command]
+pub fn list_profiles(db_state: State<DatabaseState>) -> AppResult<ProfileList> {
+    profiles::load(app_dir(&db_state.db_path)?)
+}
+
+/// Tauri command: Add a profile with its own empty history and settings
+///
+/// The new profile isn't switched to. Seeding its card data is reported
+/// with `PROGRESS_EVENT`.
+#[tauri::command]
+pub fn create_profile(
+    name: String,
+    app: AppHandle,
+    db_state: State<DatabaseState>,
+) -> AppResult<Profile> {
+    let profile = profiles::create(app_dir(&db_state.db_path)?, &name, &mut |progress| {
+        if let Err(e) = app.emit(PROGRESS_EVENT, progress) {
+            log::warn!("Failed to emit seeding progress: {}", e);
+        }
+    })?;
+    log::info!("Created profile '{}' ({})", profile.name, profile.id);
+    Ok(profile)
+}
+
+/// Tauri command: Switch to another profile, restarting the app on its database
+#[tauri::command]
+pub fn switch_profile(
+    id: String,
+    app: AppHandle,
+    db_state: State<DatabaseState>,
+) -> AppResult<ProfileList> {
+    let app_dir = app_dir(&db_state.db_path)?;
+    let list = profiles::set_active(app_dir, &id)?;
+    if profiles::database_path(app_dir, &id) != db_state.db_path {
+        log::info!("Switching to profile '{}', restarting", id);
+        app.request_restart();
+    }
+    Ok(list)
+}
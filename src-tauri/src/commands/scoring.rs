@@ -1,28 +1,67 @@
-use crate::database::repository::CardData;
-use crate::database::DatabaseState;
+use crate::commands::community;
+use crate::commands::history;
+use crate::database::access::DatabaseAccess;
+use crate::database::audit::{self, AuditAction};
+use crate::database::cache::{CardCache, CardSnapshot};
+use crate::database::repository;
+use crate::error::{AppError, AppResult};
 use crate::scoring::{
-    calculator::{ScoreCalculator, ScoringResult},
-    context::ContextModifier,
-    synergies::Synergy,
+    archetypes::{self, Archetype, ArchetypeMatch},
+    bosses::{self, Boss},
+    calculator::{ScoreReason, ScoringResult},
+    champion_paths::{self, ChampionPath},
+    compare::{self, PickComparisons},
+    fixtures::{self, FixtureReport, ScoringFixture},
+    forecast::{self, DraftForecast, Forecaster},
+    pool::{CardPool, ResultAdjustments, ScoringTables},
+    shop::{ShopAdvisor, ShopEvaluation, ShopOffer},
 };
+use crate::session::SessionState;
+use crate::settings::{self, ScoringPreferences};
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use tauri::State;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DraftScoreRequest {
     pub card_id: String,
+    /// Defaults to the draft session's deck when empty
+    #[serde(default)]
     pub current_deck: Vec<String>,
+    /// Defaults to the draft session's champion when empty
+    #[serde(default)]
     pub champion: String,
+    /// Defaults to the draft session's ring when 0
+    #[serde(default)]
     pub ring_number: i32,
+    /// Defaults to the draft session's covenant when 0
+    #[serde(default)]
     pub covenant: i32,
+    /// Champion upgrade path; defaults to the draft session's when scoring
+    /// for the session's champion
+    #[serde(default)]
+    pub champion_path: Option<String>,
+    /// ID or name of the boss at the end of the ring, so cards that counter
+    /// it score higher
+    #[serde(default)]
+    pub upcoming_boss: Option<String>,
+    /// Pact shards in an endless run, where enemies keep scaling; defaults
+    /// to the draft session's
+    #[serde(default)]
+    pub pact_shards: Option<i32>,
+    /// Upgrades the offered card already has, e.g. counted from its pips
+    #[serde(default)]
+    pub upgrade_count: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DraftScoreResponse {
     pub score: i32,
     pub tier: String,
-    pub reasons: Vec<String>,
+    pub reasons: Vec<ScoreReason>,
+    /// Where the score falls among the champion's card pool, from 0 to 100
+    pub percentile: Option<f64>,
 }
 
 impl From<ScoringResult> for DraftScoreResponse {
@@ -31,6 +70,7 @@ impl From<ScoringResult> for DraftScoreResponse {
             score: result.score,
             tier: result.tier,
             reasons: result.reasons,
+            percentile: result.percentile,
         }
     }
 }
@@ -59,259 +99,552 @@ impl From<rusqlite::Error> for ScoringError {
     }
 }
 
-/// Query a card by its ID from the database
-fn get_card_by_id(conn: &Connection, card_id: &str) -> Result<Option<CardData>, ScoringError> {
-    let mut stmt = conn.prepare(
-        r#"
-        SELECT 
-            id, name, clan, card_type, rarity, cost,
-            base_value, tempo_score, value_score, keywords,
-            description, expansion
-        FROM cards
-        WHERE id = ?1
-        "#
-    )?;
-
-    let card_result = stmt.query_row([card_id], |row| {
-        let keywords_json: String = row.get(9)?;
-        let keywords: Vec<String> = serde_json::from_str(&keywords_json).unwrap_or_default();
-
-        Ok(CardData {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            clan: row.get(2)?,
-            card_type: row.get(3)?,
-            rarity: row.get(4)?,
-            cost: row.get(5)?,
-            base_value: row.get(6)?,
-            tempo_score: row.get(7)?,
-            value_score: row.get(8)?,
-            keywords,
-            description: row.get(10)?,
-            expansion: row.get(11)?,
-        })
-    });
-
-    match card_result {
-        Ok(card) => Ok(Some(card)),
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(e.into()),
+/// Check the run context of a filled-in request
+pub(crate) fn validate_context(request: &DraftScoreRequest) -> Result<(), ScoringError> {
+    if request.champion.trim().is_empty() {
+        return Err(ScoringError::InvalidInput(
+            "Champion cannot be empty".to_string(),
+        ));
+    }
+    if !(1..=10).contains(&request.ring_number) {
+        return Err(ScoringError::InvalidInput(
+            "Ring number must be between 1 and 10".to_string(),
+        ));
     }
+    if !(1..=25).contains(&request.covenant) {
+        return Err(ScoringError::InvalidInput(
+            "Covenant must be between 1 and 25".to_string(),
+        ));
+    }
+    if request.pact_shards.is_some_and(|shards| shards < 0) {
+        return Err(ScoringError::InvalidInput(
+            "Pact shards cannot be negative".to_string(),
+        ));
+    }
+    Ok(())
 }
 
-/// Get multiple cards by their IDs
-fn get_cards_by_ids(conn: &Connection, card_ids: &[String]) -> Result<Vec<CardData>, ScoringError> {
-    if card_ids.is_empty() {
-        return Ok(vec![]);
+/// Check a filled-in score request before any lookups
+pub(crate) fn validate_request(request: &DraftScoreRequest) -> Result<(), ScoringError> {
+    if request.card_id.trim().is_empty() {
+        return Err(ScoringError::InvalidInput(
+            "Card ID cannot be empty".to_string(),
+        ));
     }
-
-    // Build parameterized query with placeholders
-    let placeholders: Vec<String> = card_ids.iter().map(|_| "?".to_string()).collect();
-    let sql = format!(
-        r#"
-        SELECT 
-            id, name, clan, card_type, rarity, cost,
-            base_value, tempo_score, value_score, keywords,
-            description, expansion
-        FROM cards
-        WHERE id IN ({})
-        "#,
-        placeholders.join(", ")
-    );
-
-    let mut stmt = conn.prepare(&sql)?;
-
-    let cards: Result<Vec<CardData>, rusqlite::Error> = stmt
-        .query_map(rusqlite::params_from_iter(card_ids.iter()), |row| {
-            let keywords_json: String = row.get(9)?;
-            let keywords: Vec<String> = serde_json::from_str(&keywords_json).unwrap_or_default();
-
-            Ok(CardData {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                clan: row.get(2)?,
-                card_type: row.get(3)?,
-                rarity: row.get(4)?,
-                cost: row.get(5)?,
-                base_value: row.get(6)?,
-                tempo_score: row.get(7)?,
-                value_score: row.get(8)?,
-                keywords,
-                description: row.get(10)?,
-                expansion: row.get(11)?,
-            })
-        })?
-        .collect();
-
-    cards.map_err(|e| e.into())
+    validate_context(request)
 }
 
-/// Get all synergies for a specific card
-fn get_synergies_for_card(
+/// Score a validated request from the card data and scoring tables
+///
+/// Covers every step except the adjustments from the player's own history
+/// and community stats; see `score_card_with_history`.
+pub(crate) fn score_card(
     conn: &Connection,
-    card_id: &str,
-) -> Result<Vec<Synergy>, ScoringError> {
-    let mut stmt = conn.prepare(
-        r#"
-        SELECT 
-            card_a_id, card_b_id, synergy_type, weight, description, bidirectional
-        FROM synergies
-        WHERE card_a_id = ?1 
-           OR (card_b_id = ?1 AND bidirectional = 1)
-           OR card_b_id = '*'
-        "#
-    )?;
-
-    let synergies: Result<Vec<Synergy>, rusqlite::Error> = stmt
-        .query_map([card_id], |row| {
-            let bidirectional: bool = row.get(5)?;
-
-            Ok(Synergy {
-                card_a_id: row.get(0)?,
-                card_b_id: row.get(1)?,
-                synergy_type: row.get(2)?,
-                weight: row.get(3)?,
-                description: row.get(4)?,
-                bidirectional,
-            })
-        })?
-        .collect();
+    snapshot: &CardSnapshot,
+    request: &DraftScoreRequest,
+) -> Result<ScoringResult, ScoringError> {
+    score_card_with(conn, snapshot, request, ResultAdjustments::default())
+}
 
-    synergies.map_err(|e| e.into())
+/// Score a validated request with every step, including the adjustments
+/// from recorded results that the scoring settings turn on
+pub(crate) fn score_card_with_history(
+    conn: &Connection,
+    snapshot: &CardSnapshot,
+    request: &DraftScoreRequest,
+) -> Result<ScoringResult, ScoringError> {
+    let results = load_result_adjustments(conn);
+    score_card_with(conn, snapshot, request, results)
 }
 
-/// Get all active context modifiers
-fn get_active_context_modifiers(conn: &Connection) -> Result<Vec<ContextModifier>, ScoringError> {
-    let mut stmt = conn.prepare(
-        r#"
-        SELECT condition, card_tag, modifier, priority, description
-        FROM context_modifiers
-        WHERE active = 1
-        "#
-    )?;
-
-    let modifiers: Result<Vec<ContextModifier>, rusqlite::Error> = stmt
-        .query_map([], |row| {
-            Ok(ContextModifier {
-                condition: row.get(0)?,
-                card_tag: row.get(1)?,
-                modifier: row.get(2)?,
-                priority: row.get(3)?,
-                description: row.get(4)?,
-            })
-        })?
-        .collect();
+fn score_card_with(
+    conn: &Connection,
+    snapshot: &CardSnapshot,
+    request: &DraftScoreRequest,
+    results: ResultAdjustments,
+) -> Result<ScoringResult, ScoringError> {
+    // 1. Look up the card being evaluated
+    let card = snapshot
+        .get(&request.card_id)
+        .ok_or_else(|| ScoringError::CardNotFound(request.card_id.clone()))?;
+
+    // 2. Look up the current deck cards
+    let current_deck = snapshot.get_many(&request.current_deck);
+
+    // 3. Load the tables the card and the rest of its pool are scored with
+    let db_span = tracing::info_span!("db.query").entered();
+    let tables = ScoringTables {
+        results,
+        ..load_scoring_tables(conn, request)?
+    };
+    drop(db_span);
+
+    // 4. Calculate the final score the same way as the pool's
+    let _span = tracing::info_span!("scoring.calculate").entered();
+    let pool = CardPool::new(
+        snapshot,
+        &tables,
+        &request.champion,
+        request.covenant,
+        request.pact_shards.unwrap_or(0),
+        &current_deck,
+    );
+    let mut result = pool.result(
+        card,
+        &current_deck,
+        request.ring_number,
+        request.upgrade_count,
+    );
 
-    modifiers.map_err(|e| e.into())
+    // 5. Place the score within the champion's card pool
+    result.percentile = Some(pool.percentile(
+        result.score,
+        &current_deck,
+        request.ring_number,
+        request.upgrade_count,
+    ));
+    Ok(result)
 }
 
-/// Get champion override value for a specific card and champion
-fn get_champion_override(
+/// Load everything needed to score a champion's card pool from memory
+///
+/// An unknown champion path or upcoming boss scores as if none were given.
+pub(crate) fn load_scoring_tables(
     conn: &Connection,
-    card_id: &str,
-    champion: &str,
-    _path: Option<&str>, // Path can be used for more specific matching
-) -> Result<Option<i32>, ScoringError> {
-    // First try exact champion match
-    let mut stmt = conn.prepare(
-        r#"
-        SELECT value_override
-        FROM champion_overrides
-        WHERE card_id = ?1 AND champion = ?2
-        ORDER BY 
-            CASE path
-                WHEN 'Any' THEN 0
-                ELSE 1
-            END
-        LIMIT 1
-        "#
-    )?;
-
-    let result = stmt.query_row([card_id, champion], |row| {
-        let value: i32 = row.get(0)?;
-        Ok(value)
-    });
-
-    match result {
-        Ok(value) => Ok(Some(value)),
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(e.into()),
-    }
+    request: &DraftScoreRequest,
+) -> Result<ScoringTables, ScoringError> {
+    let champion_path = match request.champion_path.as_deref() {
+        Some(path) => champion_paths::load_champion_path(conn, &request.champion, path)?,
+        None => None,
+    };
+    let upcoming_boss = match request.upcoming_boss.as_deref() {
+        Some(boss) => bosses::load_boss(conn, boss)?,
+        None => None,
+    };
+    Ok(ScoringTables {
+        synergies: repository::get_all_synergies(conn)?,
+        context_modifiers: repository::get_active_context_modifiers(conn)?,
+        champion_overrides: repository::get_champion_overrides(conn, &request.champion)?,
+        archetypes: archetypes::load_archetypes(conn)?,
+        champion_path,
+        upcoming_boss,
+        results: ResultAdjustments::default(),
+    })
 }
 
 /// Calculate draft score with real database data
+///
+/// Scoring runs on a blocking thread, off the IPC thread.
 #[tauri::command]
-pub fn calculate_draft_score(
-    request: DraftScoreRequest,
-    state: State<DatabaseState>,
-) -> Result<DraftScoreResponse, String> {
-    // Validate input
-    if request.card_id.trim().is_empty() {
-        return Err("Card ID cannot be empty".to_string());
-    }
-    if request.champion.trim().is_empty() {
-        return Err("Champion cannot be empty".to_string());
+pub async fn calculate_draft_score(
+    mut request: DraftScoreRequest,
+    access: State<'_, DatabaseAccess>,
+    session_state: State<'_, SessionState>,
+    cache: State<'_, CardCache>,
+) -> AppResult<DraftScoreResponse> {
+    session_state.with_session(|session| {
+        session.fill_request(&mut request);
+        Ok(())
+    })?;
+
+    validate_request(&request)?;
+
+    let access = access.inner().clone();
+    let cache = cache.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        access.read(|conn| {
+            let snapshot = cache.get(conn)?;
+            let result = score_card_with_history(conn, &snapshot, &request)?;
+            Ok(result.into())
+        })
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Win rates for scoring steps 8 and 9: the player's own results, unless
+/// disabled, then imported community results once opted in
+///
+/// Best effort: whatever can't be read is left out, and scores stand
+/// unadjusted by it.
+pub(crate) fn load_result_adjustments(conn: &Connection) -> ResultAdjustments {
+    let prefs: ScoringPreferences = settings::read_section(conn, settings::SCORING_KEY)
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to load scoring settings: {}", e);
+            ScoringPreferences::default()
+        });
+    let mut results = ResultAdjustments {
+        community_weight: prefs.community_weight,
+        ..Default::default()
+    };
+    if prefs.use_history_adjustment {
+        match history::query_win_rates(conn) {
+            Ok(history) => results.history = history,
+            Err(e) => log::warn!("Failed to fetch card performance: {}", e),
+        }
     }
-    if request.ring_number < 1 || request.ring_number > 10 {
-        return Err("Ring number must be between 1 and 10".to_string());
+    if prefs.use_community_stats {
+        match community::query_community_win_rates(conn) {
+            Ok(community) => results.community = community,
+            Err(e) => log::warn!("Failed to fetch community stats: {}", e),
+        }
     }
-    if request.covenant < 1 || request.covenant > 25 {
-        return Err("Covenant must be between 1 and 25".to_string());
+    results
+}
+
+/// Forecast a filled-in request for each of the `offered` card IDs
+///
+/// `request.card_id` is ignored. Unknown deck cards are skipped, as when
+/// scoring; unknown offered cards are an error.
+fn forecast_request(
+    conn: &Connection,
+    snapshot: &CardSnapshot,
+    request: &DraftScoreRequest,
+    offered: &[String],
+    simulations: usize,
+    seed: u64,
+) -> Result<DraftForecast, ScoringError> {
+    validate_context(request)?;
+    if !(1..=forecast::MAX_SIMULATIONS).contains(&simulations) {
+        return Err(ScoringError::InvalidInput(format!(
+            "Simulations must be between 1 and {}",
+            forecast::MAX_SIMULATIONS
+        )));
     }
+    let offered = offered
+        .iter()
+        .map(|id| {
+            snapshot
+                .get(id)
+                .cloned()
+                .ok_or_else(|| ScoringError::CardNotFound(id.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let conn = Connection::open(&state.db_path).map_err(|e| e.to_string())?;
+    let tables = load_scoring_tables(conn, request)?;
+    let deck = snapshot.get_many(&request.current_deck);
+    let pool = CardPool::new(
+        snapshot,
+        &tables,
+        &request.champion,
+        request.covenant,
+        request.pact_shards.unwrap_or(0),
+        &deck,
+    );
+    let forecaster = Forecaster::new(pool);
+    Ok(forecaster.forecast(&deck, &offered, request.ring_number, simulations, seed))
+}
 
-    // 1. Query the card being evaluated
-    let card = get_card_by_id(&conn, &request.card_id)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| format!("Card '{}' not found", request.card_id))?;
+/// Simulate the rest of the draft to value each offered card by how it
+/// changes the expected final deck
+///
+/// Empty or zero arguments default to the draft session, as for
+/// `calculate_draft_score`, and `offered` to the last detected offer. `n`
+/// is the number of simulations.
+#[tauri::command]
+pub fn forecast_draft(
+    deck: Vec<String>,
+    champion: String,
+    ring: i32,
+    covenant: i32,
+    n: Option<usize>,
+    offered: Option<Vec<String>>,
+    access: State<DatabaseAccess>,
+    session_state: State<SessionState>,
+    cache: State<CardCache>,
+) -> AppResult<DraftForecast> {
+    let mut request = DraftScoreRequest {
+        card_id: String::new(),
+        current_deck: deck,
+        champion,
+        ring_number: ring,
+        covenant,
+        champion_path: None,
+        upcoming_boss: None,
+        pact_shards: None,
+        upgrade_count: 0,
+    };
+    let offered = session_state.with_session(|session| {
+        session.fill_request(&mut request);
+        Ok(offered.unwrap_or_else(|| session.offered_card_ids.clone()))
+    })?;
+
+    access.read(|conn| {
+        let snapshot = cache.get(conn)?;
+        Ok(forecast_request(
+            conn,
+            &snapshot,
+            &request,
+            &offered,
+            n.unwrap_or(forecast::DEFAULT_SIMULATIONS),
+            rand::random(),
+        )?)
+    })
+}
 
-    // 2. Query the current deck cards
-    let current_deck = get_cards_by_ids(&conn, &request.current_deck)
-        .map_err(|e| format!("Failed to fetch deck cards: {}", e))?;
+/// Compare adding each of `card_ids` to a filled-in request's deck
+///
+/// `request.card_id` is ignored. Unknown deck cards are skipped, as when
+/// scoring; unknown candidates are an error.
+fn compare_request(
+    conn: &Connection,
+    snapshot: &CardSnapshot,
+    request: &DraftScoreRequest,
+    card_ids: &[String],
+) -> Result<PickComparisons, ScoringError> {
+    validate_context(request)?;
+    let candidates = card_ids
+        .iter()
+        .map(|id| {
+            snapshot
+                .get(id)
+                .cloned()
+                .ok_or_else(|| ScoringError::CardNotFound(id.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
-    // 3. Query synergies for the card
-    let synergies = get_synergies_for_card(&conn, &request.card_id)
-        .map_err(|e| format!("Failed to fetch synergies: {}", e))?;
+    let tables = load_scoring_tables(conn, request)?;
+    let deck = snapshot.get_many(&request.current_deck);
+    let pool = CardPool::new(
+        snapshot,
+        &tables,
+        &request.champion,
+        request.covenant,
+        request.pact_shards.unwrap_or(0),
+        &deck,
+    );
+    Ok(compare::compare_picks(&pool, &tables, &deck, &candidates, request.ring_number))
+}
+
+/// Compare what picking each card would do to the deck: synergies gained,
+/// weaknesses fixed or introduced, and the shift in the energy curve
+///
+/// Empty or zero arguments default to the draft session, as for
+/// `forecast_draft`, and `card_ids` to the last detected offer.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn compare_picks(
+    card_ids: Option<Vec<String>>,
+    deck: Vec<String>,
+    champion: String,
+    ring: i32,
+    covenant: i32,
+    access: State<DatabaseAccess>,
+    session_state: State<SessionState>,
+    cache: State<CardCache>,
+) -> AppResult<PickComparisons> {
+    let mut request = DraftScoreRequest {
+        card_id: String::new(),
+        current_deck: deck,
+        champion,
+        ring_number: ring,
+        covenant,
+        champion_path: None,
+        upcoming_boss: None,
+        pact_shards: None,
+        upgrade_count: 0,
+    };
+    let card_ids = session_state.with_session(|session| {
+        session.fill_request(&mut request);
+        Ok(card_ids.unwrap_or_else(|| session.offered_card_ids.clone()))
+    })?;
+
+    access.read(|conn| {
+        let snapshot = cache.get(conn)?;
+        Ok(compare_request(conn, &snapshot, &request, &card_ids)?)
+    })
+}
 
-    // 4. Query context modifiers
-    let context_modifiers = get_active_context_modifiers(&conn)
-        .map_err(|e| format!("Failed to fetch context modifiers: {}", e))?;
+/// Most gold a shop evaluation accepts
+const MAX_SHOP_GOLD: i32 = 10_000;
 
-    // 5. Query champion override
-    let champion_override = get_champion_override(&conn, &request.card_id, &request.champion, None)
-        .map_err(|e| format!("Failed to fetch champion override: {}", e))?;
+/// Evaluate shop offers for a filled-in request with `gold` to spend
+///
+/// `request.card_id` is ignored. Unknown deck cards are skipped, as when
+/// scoring; unknown offered cards are an error.
+fn shop_request(
+    conn: &Connection,
+    snapshot: &CardSnapshot,
+    request: &DraftScoreRequest,
+    offers: &[ShopOffer],
+    gold: i32,
+) -> Result<ShopEvaluation, ScoringError> {
+    validate_context(request)?;
+    if !(0..=MAX_SHOP_GOLD).contains(&gold) {
+        return Err(ScoringError::InvalidInput(format!(
+            "Gold must be between 0 and {}",
+            MAX_SHOP_GOLD
+        )));
+    }
+    for offer in offers {
+        if offer.price() < 0 {
+            return Err(ScoringError::InvalidInput(
+                "Prices cannot be negative".to_string(),
+            ));
+        }
+        if let Some(id) = offer.card_ids().into_iter().find(|id| snapshot.get(id).is_none()) {
+            return Err(ScoringError::CardNotFound(id.to_string()));
+        }
+    }
 
-    // 6. Calculate the score
-    let calculator = ScoreCalculator::new();
-    let result = calculator.calculate_full(
-        &card,
-        &current_deck,
+    let tables = load_scoring_tables(conn, request)?;
+    let deck = snapshot.get_many(&request.current_deck);
+    let pool = CardPool::new(
+        snapshot,
+        &tables,
         &request.champion,
-        request.ring_number,
         request.covenant,
-        &synergies,
-        &context_modifiers,
-        champion_override,
+        request.pact_shards.unwrap_or(0),
+        &deck,
     );
+    let advisor = ShopAdvisor::new(&pool, snapshot, request.ring_number);
+    Ok(advisor.evaluate(offers, gold, &deck))
+}
+
+/// Value the merchant's cards, upgrades and card removal for the deck and
+/// recommend what to buy with `gold`
+///
+/// An empty deck and the champion, ring and covenant default to the draft
+/// session's.
+#[tauri::command]
+pub fn evaluate_shop(
+    offers: Vec<ShopOffer>,
+    gold: i32,
+    deck: Vec<String>,
+    access: State<DatabaseAccess>,
+    session_state: State<SessionState>,
+    cache: State<CardCache>,
+) -> AppResult<ShopEvaluation> {
+    let mut request = DraftScoreRequest {
+        card_id: String::new(),
+        current_deck: deck,
+        champion: String::new(),
+        ring_number: 0,
+        covenant: 0,
+        champion_path: None,
+        upcoming_boss: None,
+        pact_shards: None,
+        upgrade_count: 0,
+    };
+    session_state.with_session(|session| {
+        session.fill_request(&mut request);
+        Ok(())
+    })?;
+
+    access.read(|conn| {
+        let snapshot = cache.get(conn)?;
+        Ok(shop_request(conn, &snapshot, &request, &offers, gold)?)
+    })
+}
+
+/// Archetypes the deck fits, best first, with their confidence
+///
+/// An empty deck defaults to the draft session's.
+#[tauri::command]
+pub fn detect_archetype(
+    deck: Vec<String>,
+    access: State<DatabaseAccess>,
+    session_state: State<SessionState>,
+    cache: State<CardCache>,
+) -> AppResult<Vec<ArchetypeMatch>> {
+    let deck = if deck.is_empty() {
+        session_state.snapshot()?.deck_ids()
+    } else {
+        deck
+    };
+
+    access.read(|conn| {
+        let snapshot = cache.get(conn)?;
+        let archetypes = archetypes::load_archetypes(conn)?;
+        Ok(archetypes::detect(&snapshot.get_many(&deck), &archetypes))
+    })
+}
+
+/// Get every archetype definition, including inactive ones
+#[tauri::command]
+pub fn get_archetypes(access: State<DatabaseAccess>) -> AppResult<Vec<Archetype>> {
+    Ok(access.read(archetypes::load_archetypes)?)
+}
+
+/// The saved archetype with `id`, as JSON for the audit log
+fn archetype_value(conn: &Connection, id: &str) -> AppResult<Option<serde_json::Value>> {
+    Ok(archetypes::load_archetypes(conn)?
+        .into_iter()
+        .find(|archetype| archetype.id == id)
+        .and_then(|archetype| serde_json::to_value(archetype).ok()))
+}
+
+/// Add an archetype definition, or replace the one with the same ID
+#[tauri::command]
+pub fn save_archetype(archetype: Archetype, access: State<DatabaseAccess>) -> AppResult<()> {
+    archetype.validate().map_err(AppError::InvalidInput)?;
+    access.write_and_wait(move |conn| {
+        let before = archetype_value(conn, &archetype.id)?;
+        archetypes::save_archetype(conn, &archetype)?;
+
+        let action = if before.is_some() {
+            AuditAction::Update
+        } else {
+            AuditAction::Create
+        };
+        let after = serde_json::to_value(&archetype).ok();
+        audit::record(conn, "archetype", &archetype.id, action, before, after)?;
+        Ok(())
+    })
+}
+
+/// Delete an archetype definition
+#[tauri::command]
+pub fn delete_archetype(id: String, access: State<DatabaseAccess>) -> AppResult<()> {
+    access.write_and_wait(move |conn| {
+        let before = archetype_value(conn, &id)?;
+        if !archetypes::delete_archetype(conn, &id)? {
+            return Err(AppError::NotFound(format!("Archetype '{}' not found", id)));
+        }
+        audit::record(conn, "archetype", &id, AuditAction::Delete, before, None)?;
+        Ok(())
+    })
+}
+
+/// Get the upgrade paths with scaling data, for one champion or all of them
+#[tauri::command]
+pub fn get_champion_paths(
+    champion: Option<String>,
+    access: State<DatabaseAccess>,
+) -> AppResult<Vec<ChampionPath>> {
+    let mut paths = access.read(champion_paths::load_champion_paths)?;
+    if let Some(champion) = champion.as_deref().map(str::trim) {
+        paths.retain(|p| p.champion.eq_ignore_ascii_case(champion));
+    }
+    Ok(paths)
+}
 
-    Ok(result.into())
+/// Get the boss lineup, or only the bosses a run at `covenant` can still
+/// meet from `ring` on when both are given
+#[tauri::command]
+pub fn get_bosses(
+    ring: Option<i32>,
+    covenant: Option<i32>,
+    access: State<DatabaseAccess>,
+) -> AppResult<Vec<Boss>> {
+    access.read(|conn| {
+        Ok(match (ring, covenant) {
+            (Some(ring), Some(covenant)) => bosses::load_lineup(conn, ring, covenant)?,
+            _ => bosses::load_bosses(conn)?,
+        })
+    })
 }
 
 /// Get synergies for a specific card
 #[tauri::command]
-pub fn get_synergies(
-    card_id: String,
-    state: State<DatabaseState>,
-) -> Result<Vec<String>, String> {
+pub fn get_synergies(card_id: String, access: State<DatabaseAccess>) -> AppResult<Vec<String>> {
     if card_id.trim().is_empty() {
-        return Err("Card ID cannot be empty".to_string());
+        return Err(AppError::InvalidInput(
+            "Card ID cannot be empty".to_string(),
+        ));
     }
 
-    let conn = Connection::open(&state.db_path).map_err(|e| e.to_string())?;
-
-    let synergies = get_synergies_for_card(&conn, &card_id)
-        .map_err(|e| format!("Failed to fetch synergies: {}", e))?;
+    let synergies = access.read(|conn| repository::get_synergies_for_card(conn, &card_id))?;
 
     // Return formatted synergy descriptions
     let descriptions: Vec<String> = synergies
@@ -324,11 +657,8 @@ pub fn get_synergies(
 
 /// Get all active context modifiers
 #[tauri::command]
-pub fn get_context_modifiers(state: State<DatabaseState>) -> Result<Vec<String>, String> {
-    let conn = Connection::open(&state.db_path).map_err(|e| e.to_string())?;
-
-    let modifiers = get_active_context_modifiers(&conn)
-        .map_err(|e| format!("Failed to fetch context modifiers: {}", e))?;
+pub fn get_context_modifiers(access: State<DatabaseAccess>) -> AppResult<Vec<String>> {
+    let modifiers = access.read(repository::get_active_context_modifiers)?;
 
     // Return formatted modifier descriptions
     let descriptions: Vec<String> = modifiers
@@ -339,61 +669,73 @@ pub fn get_context_modifiers(state: State<DatabaseState>) -> Result<Vec<String>,
     Ok(descriptions)
 }
 
+/// Score each fixture's candidate and check it against the expected result
+///
+/// The player's own history is left out so results don't depend on who
+/// runs them.
+fn run_fixtures(
+    conn: &Connection,
+    snapshot: &CardSnapshot,
+    fixtures: &[ScoringFixture],
+) -> Vec<FixtureReport> {
+    fixtures
+        .iter()
+        .map(|fixture| {
+            let request = DraftScoreRequest {
+                card_id: fixture.candidate.clone(),
+                current_deck: fixture.deck.clone(),
+                champion: fixture.champion.clone(),
+                ring_number: fixture.ring,
+                covenant: fixture.covenant,
+                champion_path: fixture.champion_path.clone(),
+                upcoming_boss: fixture.upcoming_boss.clone(),
+                pact_shards: fixture.pact_shards,
+                upgrade_count: 0,
+            };
+            match validate_request(&request).and_then(|_| score_card(conn, snapshot, &request)) {
+                Ok(result) => fixture.check(&result),
+                Err(e) => FixtureReport::error(fixture, e.to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Run the scoring fixtures in `dir` and report on each
+///
+/// A development tool for checking scoring changes against contributed
+/// scenarios, such as a checkout's `src-tauri/tests/fixtures`.
+#[tauri::command]
+pub fn validate_scoring_fixtures(
+    dir: String,
+    access: State<DatabaseAccess>,
+    cache: State<CardCache>,
+) -> AppResult<Vec<FixtureReport>> {
+    let fixtures = fixtures::load_fixtures(Path::new(&dir)).map_err(AppError::InvalidInput)?;
+
+    access.read(|conn| {
+        let snapshot = cache.get(conn)?;
+        Ok(run_fixtures(conn, &snapshot, &fixtures))
+    })
+}
+
 /// Internal function to calculate draft score directly from a connection (for testing)
 fn calculate_draft_score_internal(
     conn: &Connection,
     request: DraftScoreRequest,
 ) -> Result<DraftScoreResponse, ScoringError> {
-    // Validate input
-    if request.card_id.trim().is_empty() {
-        return Err(ScoringError::InvalidInput("Card ID cannot be empty".to_string()));
-    }
-    if request.champion.trim().is_empty() {
-        return Err(ScoringError::InvalidInput("Champion cannot be empty".to_string()));
-    }
-    if request.ring_number < 1 || request.ring_number > 10 {
-        return Err(ScoringError::InvalidInput("Ring number must be between 1 and 10".to_string()));
-    }
-    if request.covenant < 1 || request.covenant > 25 {
-        return Err(ScoringError::InvalidInput("Covenant must be between 1 and 25".to_string()));
-    }
-
-    // 1. Query the card being evaluated
-    let card = get_card_by_id(conn, &request.card_id)?
-        .ok_or_else(|| ScoringError::CardNotFound(request.card_id.clone()))?;
-
-    // 2. Query the current deck cards
-    let current_deck = get_cards_by_ids(conn, &request.current_deck)?;
+    validate_request(&request)?;
 
-    // 3. Query synergies for the card
-    let synergies = get_synergies_for_card(conn, &request.card_id)?;
-
-    // 4. Query context modifiers
-    let context_modifiers = get_active_context_modifiers(conn)?;
-
-    // 5. Query champion override
-    let champion_override = get_champion_override(conn, &request.card_id, &request.champion, None)?;
-
-    // 6. Calculate the score
-    let calculator = ScoreCalculator::new();
-    let result = calculator.calculate_full(
-        &card,
-        &current_deck,
-        &request.champion,
-        request.ring_number,
-        request.covenant,
-        &synergies,
-        &context_modifiers,
-        champion_override,
-    );
-
-    Ok(result.into())
+    let snapshot = CardSnapshot::load(conn)?;
+    Ok(score_card(conn, &snapshot, &request)?.into())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::database;
+    use crate::database::{self, DatabaseState};
+    use crate::map::Weakness;
+    use crate::scoring::context::PenaltyKind;
+    use crate::scoring::shop::ShopReason;
     use tempfile::NamedTempFile;
 
     fn setup_test_db() -> (DatabaseState, NamedTempFile) {
@@ -411,14 +753,15 @@ mod tests {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
 
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+
         // Test finding a known card
-        let card = get_card_by_id(&conn, "banished_fel").unwrap();
+        let card = snapshot.get("banished_fel");
         assert!(card.is_some());
         assert_eq!(card.unwrap().name, "Fel");
 
         // Test not found
-        let not_found = get_card_by_id(&conn, "nonexistent").unwrap();
-        assert!(not_found.is_none());
+        assert!(snapshot.get("nonexistent").is_none());
     }
 
     #[test]
@@ -427,7 +770,7 @@ mod tests {
         let conn = Connection::open(&state.db_path).unwrap();
 
         let ids = vec!["banished_fel".to_string(), "pyreborne_lord_fenix".to_string()];
-        let cards = get_cards_by_ids(&conn, &ids).unwrap();
+        let cards = CardSnapshot::load(&conn).unwrap().get_many(&ids);
 
         assert_eq!(cards.len(), 2);
         assert!(cards.iter().any(|c| c.id == "banished_fel"));
@@ -439,65 +782,527 @@ mod tests {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
 
-        let cards = get_cards_by_ids(&conn, &[]).unwrap();
+        let cards = CardSnapshot::load(&conn).unwrap().get_many(&[]);
         assert!(cards.is_empty());
     }
 
     #[test]
-    fn test_get_synergies_for_card() {
+    fn test_calculate_draft_score() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
 
-        // banished_fel has synergies defined in seed data
-        let synergies = get_synergies_for_card(&conn, "banished_fel").unwrap();
-        assert!(!synergies.is_empty());
+        let request = DraftScoreRequest {
+            card_id: "banished_fel".to_string(),
+            current_deck: vec!["banished_just_cause".to_string()],
+            champion: "Fel".to_string(),
+            ring_number: 1,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
+        };
+
+        let result = calculate_draft_score_internal(&conn, request);
+        assert!(result.is_ok());
+
+        let response = result.unwrap();
+        assert!(response.score > 0);
+        assert!(!response.tier.is_empty());
+        // Fel + Just Cause should have synergy bonus
+        assert!(response
+            .reasons
+            .iter()
+            .any(|r| matches!(r, ScoreReason::SynergyBonus { .. })));
+        let percentile = response.percentile.unwrap();
+        assert!((0.0..=100.0).contains(&percentile));
+
+        // Fel's favorite ranks near the top of the Banished pool
+        let favorite = DraftScoreRequest {
+            card_id: "banished_just_cause".to_string(),
+            current_deck: Vec::new(),
+            champion: "Fel".to_string(),
+            ring_number: 1,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
+        };
+        let response = calculate_draft_score_internal(&conn, favorite).unwrap();
+        assert!(response.percentile.unwrap() >= 90.0);
     }
 
     #[test]
-    fn test_get_active_context_modifiers() {
+    fn test_percentile_ranks_final_scores() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
 
-        let modifiers = get_active_context_modifiers(&conn).unwrap();
-        assert!(!modifiers.is_empty());
+        let request = |upgrade_count: u32| DraftScoreRequest {
+            card_id: "banished_talos".to_string(),
+            current_deck: vec!["banished_just_cause".to_string()],
+            champion: "Fel".to_string(),
+            ring_number: 2,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count,
+        };
+
+        // The pool is ranked as if offered with the same upgrades
+        let plain = score_card(&conn, &snapshot, &request(0)).unwrap();
+        let upgraded = score_card(&conn, &snapshot, &request(2)).unwrap();
+        assert_eq!(upgraded.score, plain.score + 8);
+        assert_eq!(upgraded.percentile, plain.percentile);
+
+        // History adjusts the pool as well as the card
+        for ring in 1..=5 {
+            let run_id = format!("run{}", ring);
+            conn.execute(
+                "INSERT INTO runs (run_id, champion, covenant, did_win) VALUES (?1, 'Fel', 10, 1)",
+                [&run_id],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO deck_history
+                 (run_id, card_id, ring_number, draft_order, champion, covenant, did_win)
+                 VALUES (?1, 'banished_talos', 1, 1, 'Fel', 10, 1)",
+                [&run_id],
+            )
+            .unwrap();
+        }
+        let adjusted = score_card_with_history(&conn, &snapshot, &request(0)).unwrap();
+        assert_eq!(adjusted.score, plain.score + 10);
+        assert!(adjusted.percentile >= plain.percentile);
     }
 
     #[test]
-    fn test_get_champion_override() {
+    fn test_champion_path_scaling() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
 
-        // Fel has an override for "Just Cause" with champion "Fel"
-        let override_val = get_champion_override(&conn, "banished_just_cause", "Fel", None).unwrap();
-        assert!(override_val.is_some());
-        assert_eq!(override_val.unwrap(), 95);
+        let request = |path: Option<&str>| DraftScoreRequest {
+            card_id: "banished_just_cause".to_string(),
+            current_deck: vec!["banished_karmic_censer".to_string()],
+            champion: "Fel".to_string(),
+            ring_number: 4,
+            covenant: 10,
+            champion_path: path.map(str::to_string),
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
+        };
+        let flat = calculate_draft_score_internal(&conn, request(None)).unwrap();
+        let unchained = calculate_draft_score_internal(&conn, request(Some("unchained"))).unwrap();
+
+        // Full feed plus one Shift card already in the deck
+        assert_eq!(unchained.score, flat.score + 5);
+        assert!(unchained.reasons.contains(&ScoreReason::PathScaling {
+            path: "Unchained".to_string(),
+            bonus: 5,
+        }));
+
+        // Just Cause feeds nothing on the Savior path
+        let savior = calculate_draft_score_internal(&conn, request(Some("Savior"))).unwrap();
+        assert_eq!(savior.score, flat.score);
+    }
 
-        // No override for non-matching champion
-        let no_override = get_champion_override(&conn, "banished_just_cause", "Random", None).unwrap();
-        assert!(no_override.is_none());
+    #[test]
+    fn test_capacity_pressure() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+
+        let request = |sentries: usize| DraftScoreRequest {
+            card_id: "railforged_forge_steward".to_string(),
+            current_deck: vec!["hellhorned_titan_sentry".to_string(); sentries],
+            champion: "Herzal".to_string(),
+            ring_number: 4,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
+        };
+        let is_pressure = |r: &ScoreReason| {
+            matches!(
+                r,
+                ScoreReason::ContextPenalty {
+                    kind: PenaltyKind::Capacity { .. },
+                    ..
+                }
+            )
+        };
+
+        // 9 of 15 floor space used leaves room
+        let roomy = calculate_draft_score_internal(&conn, request(3)).unwrap();
+        assert!(!roomy.reasons.iter().any(is_pressure));
+
+        // 12 used: a size 2 unit still fits, barely
+        let tight = calculate_draft_score_internal(&conn, request(4)).unwrap();
+        assert!(tight.reasons.contains(&ScoreReason::ContextPenalty {
+            kind: PenaltyKind::Capacity {
+                space: 14,
+                capacity: 15,
+            },
+            penalty: -5,
+        }));
+
+        // 15 used: no room at all
+        let full = calculate_draft_score_internal(&conn, request(5)).unwrap();
+        assert!(full.reasons.iter().any(|r| matches!(
+            r,
+            ScoreReason::ContextPenalty { penalty: -15, .. }
+        )));
+        assert!(full.score < tight.score);
     }
 
     #[test]
-    fn test_calculate_draft_score() {
+    fn test_energy_curve() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+
+        let request = |card_id: &str, deck: &[&str]| DraftScoreRequest {
+            card_id: card_id.to_string(),
+            current_deck: deck.iter().map(|id| id.to_string()).collect(),
+            champion: "Herzal".to_string(),
+            ring_number: 4,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
+        };
+        let curve_adjustment = |response: &DraftScoreResponse| {
+            response.reasons.iter().find_map(|r| match r {
+                ScoreReason::EnergyCurve { adjustment, .. } => Some(*adjustment),
+                _ => None,
+            })
+        };
+
+        // Four 3-cost units make a top-heavy deck
+        let heavy = ["hellhorned_titan_sentry"; 4];
+        let cheap =
+            calculate_draft_score_internal(&conn, request("railforged_smith", &heavy)).unwrap();
+        assert!(curve_adjustment(&cheap).unwrap() > 0);
+        let bomb = calculate_draft_score_internal(
+            &conn,
+            request("melting_remnant_lady_of_the_house", &heavy),
+        )
+        .unwrap();
+        assert!(curve_adjustment(&bomb).unwrap() < 0);
+
+        // A deck with one card at each cost is on curve
+        let balanced = [
+            "banished_just_cause",
+            "banished_cleave",
+            "underlegion_morel_mistress",
+            "hellhorned_titan_sentry",
+        ];
+        let bomb = calculate_draft_score_internal(
+            &conn,
+            request("melting_remnant_lady_of_the_house", &balanced),
+        )
+        .unwrap();
+        assert_eq!(curve_adjustment(&bomb), None);
+    }
+
+    #[test]
+    fn test_endless_pact_shards() {
         let (state, _temp) = setup_test_db();
         let conn = Connection::open(&state.db_path).unwrap();
 
+        let request = |card_id: &str, pact_shards: i32| DraftScoreRequest {
+            card_id: card_id.to_string(),
+            current_deck: Vec::new(),
+            champion: "Lord Fenix".to_string(),
+            ring_number: 4,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: Some(pact_shards),
+            upgrade_count: 0,
+        };
+        let score = |card_id: &str, pact_shards: i32| {
+            calculate_draft_score_internal(&conn, request(card_id, pact_shards))
+                .unwrap()
+                .score
+        };
+
+        // Scaling gains from 25 shards, scaling damage and snowball from 50
+        let glaive = "luna_coven_moonlit_glaive";
+        assert_eq!(score(glaive, 24), score(glaive, 0));
+        assert_eq!(score(glaive, 25), score(glaive, 0) + 10);
+        let flame = "pyreborne_fanning_the_flame";
+        assert_eq!(score(flame, 60), score(flame, 0) + 18);
+
+        // Tempo cards fade
+        let tempo =
+            calculate_draft_score_internal(&conn, request("banished_just_cause", 60)).unwrap();
+        assert!(tempo.reasons.iter().any(|r| matches!(
+            r,
+            ScoreReason::ContextPenalty {
+                kind: PenaltyKind::PactShards { pact_shards: 60 },
+                penalty: -5,
+            }
+        )));
+
+        assert!(matches!(
+            calculate_draft_score_internal(&conn, request(glaive, -1)),
+            Err(ScoringError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_upcoming_boss() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+
+        let request = |card_id: &str, boss: Option<&str>| DraftScoreRequest {
+            card_id: card_id.to_string(),
+            current_deck: Vec::new(),
+            champion: "Fel".to_string(),
+            ring_number: 9,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: boss.map(str::to_string),
+            pact_shards: None,
+            upgrade_count: 0,
+        };
+        let score = |card_id: &str, boss: Option<&str>| {
+            calculate_draft_score_internal(&conn, request(card_id, boss))
+                .unwrap()
+                .score
+        };
+
+        // Removal counters the Seraph's relentless climb
+        let removal = "banished_deadly_plunge";
+        assert_eq!(
+            score(removal, Some("Seraph the Relentless")),
+            score(removal, None) + 8
+        );
+        // Unknown bosses are ignored, and the Swarm Herald isn't relentless
+        assert_eq!(score(removal, Some("nobody")), score(removal, None));
+        assert_eq!(score(removal, Some("swarm_herald")), score(removal, None));
+
+        // Burnout units expire before a relentless boss does
+        let burnout = calculate_draft_score_internal(
+            &conn,
+            request("melting_remnant_lady_of_the_house", Some("seraph_the_relentless")),
+        )
+        .unwrap();
+        assert!(burnout.reasons.iter().any(|r| matches!(
+            r,
+            ScoreReason::ContextPenalty {
+                kind: PenaltyKind::Boss { boss },
+                penalty: -5,
+            } if boss == "seraph_the_relentless"
+        )));
+    }
+
+    #[test]
+    fn test_evaluate_shop() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+
         let request = DraftScoreRequest {
-            card_id: "banished_fel".to_string(),
-            current_deck: vec!["banished_just_cause".to_string()],
+            card_id: String::new(),
+            current_deck: vec![
+                "banished_fel".to_string(),
+                "banished_cleave".to_string(),
+                "banished_steadfast_crusader".to_string(),
+                "pyreborne_gildmonger".to_string(),
+            ],
             champion: "Fel".to_string(),
-            ring_number: 1,
+            ring_number: 4,
             covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
         };
+        let card = |id: &str, price: i32| ShopOffer::Card {
+            card_id: id.to_string(),
+            price,
+        };
+        let offers = vec![
+            card("banished_just_cause", 100),
+            card("banished_deadly_plunge", 120),
+            card("banished_karmic_censer", 60),
+            card("luna_coven_witchweave", 40),
+            ShopOffer::Upgrade {
+                name: "Stone".to_string(),
+                price: 80,
+                target_card_id: None,
+            },
+            ShopOffer::Removal { price: 50 },
+            card("pyreborne_lord_fenix", 500),
+        ];
+        let shop = shop_request(&conn, &snapshot, &request, &offers, 200).unwrap();
+
+        // Three cheaper cards beat Deadly Plunge plus the best card it leaves gold for
+        let recommended: Vec<&ShopOffer> = shop
+            .offers
+            .iter()
+            .filter(|o| o.recommended)
+            .map(|o| &o.offer)
+            .collect();
+        assert_eq!(recommended, vec![&offers[0], &offers[2], &offers[3]]);
+        assert_eq!((shop.spent, shop.remaining_gold), (200, 0));
+        assert_eq!(
+            shop.total_value,
+            shop.offers[..3].iter().map(|o| o.value).sum::<i32>()
+        );
+
+        let find = |offer: &ShopOffer| shop.offers.iter().find(|o| &o.offer == offer).unwrap();
+        let plunge = find(&offers[1]);
+        assert!(plunge.affordable && plunge.value > 0);
+        assert!(plunge
+            .reasons
+            .iter()
+            .any(|r| matches!(r, ShopReason::OpportunityCost { value_lost } if *value_lost > 0)));
+        assert!(find(&offers[4])
+            .reasons
+            .contains(&ShopReason::Upgrades {
+                card_name: "Steadfast Crusader".to_string()
+            }));
+        assert_eq!(find(&offers[5]).reasons, vec![ShopReason::NothingToRemove]);
+        assert!(find(&offers[6])
+            .reasons
+            .contains(&ShopReason::Unaffordable { short_by: 300 }));
+
+        assert!(matches!(
+            shop_request(&conn, &snapshot, &request, &[card("nonexistent", 10)], 100),
+            Err(ScoringError::CardNotFound(_))
+        ));
+        assert!(matches!(
+            shop_request(&conn, &snapshot, &request, &[card("banished_cleave", -1)], 100),
+            Err(ScoringError::InvalidInput(_))
+        ));
+    }
 
-        let result = calculate_draft_score_internal(&conn, request);
-        assert!(result.is_ok());
+    #[test]
+    fn test_scoring_fixtures() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
 
-        let response = result.unwrap();
-        assert!(response.score > 0);
-        assert!(!response.tier.is_empty());
-        // Fel + Just Cause should have synergy bonus
-        assert!(!response.reasons.is_empty());
+        let fixtures = fixtures::load_fixtures(Path::new(fixtures::FIXTURES_DIR)).unwrap();
+        assert!(!fixtures.is_empty());
+        for report in run_fixtures(&conn, &snapshot, &fixtures) {
+            assert!(report.passed, "{}: {}", report.name, report.failures.join("; "));
+        }
+
+        // A fixture that can't be scored fails instead of aborting the run
+        let mut unknown = fixtures[0].clone();
+        unknown.candidate = "nonexistent".to_string();
+        let reports = run_fixtures(&conn, &snapshot, &[unknown]);
+        assert!(!reports[0].passed);
+        assert_eq!(reports[0].score, None);
+    }
+
+    #[test]
+    fn test_compare_picks() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+
+        // Spells only, so the deck has no frontline
+        let request = DraftScoreRequest {
+            card_id: String::new(),
+            current_deck: vec![
+                "banished_fel".to_string(),
+                "banished_cleave".to_string(),
+                "banished_deadly_plunge".to_string(),
+                "banished_just_cause".to_string(),
+            ],
+            champion: "Fel".to_string(),
+            ring_number: 3,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
+        };
+        let ids = ["hellhorned_titan_sentry".to_string(), "railforged_smith".to_string()];
+        let comparisons = compare_request(&conn, &snapshot, &request, &ids).unwrap();
+        assert!(comparisons.current.weaknesses.contains(&Weakness::NoFrontline));
+        assert_eq!(comparisons.picks.len(), 2);
+        assert!(comparisons.picks[0].score >= comparisons.picks[1].score);
+
+        let pick = |id: &str| comparisons.picks.iter().find(|p| p.card_id == id).unwrap();
+        // The tank fixes the missing frontline; the spell doesn't
+        assert!(pick("hellhorned_titan_sentry").fixes.contains(&Weakness::NoFrontline));
+        assert!(!pick("railforged_smith").fixes.contains(&Weakness::NoFrontline));
+        assert!(pick("railforged_smith")
+            .metrics
+            .weaknesses
+            .contains(&Weakness::NoFrontline));
+
+        assert!(matches!(
+            compare_request(&conn, &snapshot, &request, &["nonexistent".to_string()]),
+            Err(ScoringError::CardNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_forecast_draft() {
+        let (state, _temp) = setup_test_db();
+        let conn = Connection::open(&state.db_path).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+
+        let request = DraftScoreRequest {
+            card_id: String::new(),
+            current_deck: vec!["banished_fel".to_string()],
+            champion: "Fel".to_string(),
+            ring_number: 8,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
+        };
+        let offered = vec![
+            "banished_just_cause".to_string(),
+            "pyreborne_lord_fenix".to_string(),
+        ];
+        let forecast = forecast_request(&conn, &snapshot, &request, &offered, 40, 7).unwrap();
+
+        assert_eq!(forecast.simulations, 40);
+        assert_eq!(forecast.remaining_picks, 4);
+        assert_eq!(forecast.offers.len(), 2);
+        assert!(forecast.expected_quality > 0.0);
+        assert!(forecast.offers[0].marginal_value >= forecast.offers[1].marginal_value);
+        assert_eq!(forecast.recommended.as_ref(), Some(&forecast.offers[0].card_id));
+        for offer in &forecast.offers {
+            let gained = offer.expected_quality - forecast.expected_quality;
+            assert!((offer.marginal_value - gained).abs() < 1e-9);
+        }
+
+        // The same seed plays out the same offers
+        let again = forecast_request(&conn, &snapshot, &request, &offered, 40, 7).unwrap();
+        assert_eq!(again, forecast);
+
+        // Nothing left to draft at the final ring
+        let last_ring = DraftScoreRequest {
+            ring_number: 10,
+            ..request
+        };
+        let forecast = forecast_request(&conn, &snapshot, &last_ring, &offered, 5, 7).unwrap();
+        assert_eq!(forecast.remaining_picks, 0);
+        assert_eq!(forecast.expected_quality, forecast.current_quality);
+
+        let unknown = vec!["nonexistent".to_string()];
+        assert!(matches!(
+            forecast_request(&conn, &snapshot, &last_ring, &unknown, 5, 7),
+            Err(ScoringError::CardNotFound(_))
+        ));
+        assert!(matches!(
+            forecast_request(&conn, &snapshot, &last_ring, &offered, 0, 7),
+            Err(ScoringError::InvalidInput(_))
+        ));
     }
 
     #[test]
@@ -511,6 +1316,10 @@ mod tests {
             champion: "Fel".to_string(),
             ring_number: 1,
             covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
         };
 
         let result = calculate_draft_score_internal(&conn, request);
@@ -528,6 +1337,10 @@ mod tests {
             champion: "Fel".to_string(),
             ring_number: 99, // Invalid
             covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
         };
 
         let result = calculate_draft_score_internal(&conn, request);
@@ -549,6 +1362,10 @@ mod tests {
             champion: "".to_string(),
             ring_number: 1,
             covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
         };
 
         let result = calculate_draft_score_internal(&conn, request);
@@ -565,7 +1382,7 @@ mod tests {
         let conn = Connection::open(&state.db_path).unwrap();
 
         // Test the internal function directly
-        let synergies = get_synergies_for_card(&conn, "banished_fel").unwrap();
+        let synergies = repository::get_synergies_for_card(&conn, "banished_fel").unwrap();
         assert!(!synergies.is_empty());
 
         // Format descriptions like the command does
@@ -582,9 +1399,24 @@ mod tests {
         let conn = Connection::open(&state.db_path).unwrap();
 
         // Test the internal function directly
-        let modifiers = get_active_context_modifiers(&conn).unwrap();
+        let modifiers = repository::get_active_context_modifiers(&conn).unwrap();
         assert!(!modifiers.is_empty());
 
+        // Every seeded condition parses; one that doesn't is skipped
+        let active: usize = conn
+            .query_row("SELECT COUNT(*) FROM context_modifiers WHERE active = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(modifiers.len(), active);
+        conn.execute(
+            "INSERT INTO context_modifiers (condition, card_tag, modifier, priority, description)
+             VALUES ('gold < 100', 'gold', 15, 'Medium', 'Low gold')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(repository::get_active_context_modifiers(&conn).unwrap().len(), active);
+
         // Format descriptions like the command does
         let descriptions: Vec<String> = modifiers
             .into_iter()
@@ -0,0 +1,56 @@
+//! Overlay server commands
+
+use crate::database::access::DatabaseAccess;
+use crate::error::{AppError, AppResult};
+use crate::server::{self, ServerState, ServerStatus};
+use tauri::State;
+
+/// Tauri command: Start the local overlay server, and on later launches too
+///
+/// `port` changes the saved port; `regenerate_token` replaces the token,
+/// disconnecting clients that used the old one. A token is created the
+/// first time the server starts.
+#[tauri::command]
+pub fn start_overlay_server(
+    port: Option<u16>,
+    regenerate_token: Option<bool>,
+    access: State<DatabaseAccess>,
+    server_state: State<ServerState>,
+) -> AppResult<ServerStatus> {
+    let mut config = server::load_config(&access)?;
+    if let Some(port) = port {
+        if port == 0 {
+            return Err(AppError::InvalidInput("Port cannot be 0".to_string()));
+        }
+        config.port = port;
+    }
+
+    server_state.start(&mut config, regenerate_token.unwrap_or(false))?;
+    config.enabled = true;
+    server::save_config(&access, &config)?;
+    Ok(server_state.status(&config))
+}
+
+/// Tauri command: Stop the overlay server and keep it off on launch
+#[tauri::command]
+pub fn stop_overlay_server(
+    access: State<DatabaseAccess>,
+    server_state: State<ServerState>,
+) -> AppResult<ServerStatus> {
+    server_state.stop();
+
+    let mut config = server::load_config(&access)?;
+    config.enabled = false;
+    server::save_config(&access, &config)?;
+    Ok(server_state.status(&config))
+}
+
+/// Tauri command: Whether the overlay server is running, and where
+#[tauri::command]
+pub fn get_overlay_server_status(
+    access: State<DatabaseAccess>,
+    server_state: State<ServerState>,
+) -> AppResult<ServerStatus> {
+    let config = server::load_config(&access)?;
+    Ok(server_state.status(&config))
+}
@@ -0,0 +1,165 @@
+//! Draft session commands
+//!
+//! Manage the run tracked by `crate::session`. Each command returns the
+//! updated session so the frontend never has to keep its own copy.
+
+use crate::commands::history;
+use crate::commands::ocr::OcrState;
+use crate::database::access::DatabaseAccess;
+use crate::session::{DraftSession, SessionState};
+use crate::sync;
+use tauri::State;
+
+/// Tauri command: Get the current draft session
+#[tauri::command]
+pub fn session_get_state(session_state: State<SessionState>) -> Result<DraftSession, String> {
+    session_state.snapshot()
+}
+
+/// Tauri command: Draft a card into the session deck
+///
+/// The card is logged to the draft history, along with the detected offer
+/// it was picked from, if any.
+#[tauri::command]
+pub fn session_add_card(
+    card_id: String,
+    score_at_draft: Option<i32>,
+    session_state: State<SessionState>,
+    access: State<DatabaseAccess>,
+) -> Result<DraftSession, String> {
+    session_state.write_session(&access, move |session, conn| {
+        session.add_card(conn, &card_id, score_at_draft)?;
+        Ok(session.clone())
+    })
+}
+
+/// Tauri command: Remove the most recently drafted copy of a card
+#[tauri::command]
+pub fn session_remove_card(
+    card_id: String,
+    session_state: State<SessionState>,
+    access: State<DatabaseAccess>,
+) -> Result<DraftSession, String> {
+    session_state.write_session(&access, move |session, conn| {
+        session.remove_card(conn, &card_id)?;
+        Ok(session.clone())
+    })
+}
+
+/// Tauri command: Undo the last card added to or removed from the deck
+#[tauri::command]
+pub fn undo_last_pick(
+    session_state: State<SessionState>,
+    access: State<DatabaseAccess>,
+) -> Result<DraftSession, String> {
+    session_state.write_session(&access, |session, conn| {
+        session.undo_last_pick(conn)?;
+        Ok(session.clone())
+    })
+}
+
+/// Tauri command: Redo the last undone deck change
+#[tauri::command]
+pub fn redo_pick(
+    session_state: State<SessionState>,
+    access: State<DatabaseAccess>,
+) -> Result<DraftSession, String> {
+    session_state.write_session(&access, |session, conn| {
+        session.redo_pick(conn)?;
+        Ok(session.clone())
+    })
+}
+
+/// Tauri command: Set the session champion and, optionally, upgrade path
+#[tauri::command]
+pub fn session_set_champion(
+    champion: String,
+    path: Option<String>,
+    session_state: State<SessionState>,
+    access: State<DatabaseAccess>,
+) -> Result<DraftSession, String> {
+    session_state.write_session(&access, move |session, conn| {
+        session.set_champion(conn, &champion, path.as_deref())?;
+        Ok(session.clone())
+    })
+}
+
+/// Tauri command: Move the session to another ring
+///
+/// Card detection is restricted to cards available in that ring.
+#[tauri::command]
+pub fn session_set_ring(
+    ring_number: i32,
+    session_state: State<SessionState>,
+    ocr_state: State<OcrState>,
+) -> Result<DraftSession, String> {
+    let session = session_state.with_session(|session| {
+        session.set_ring(ring_number)?;
+        Ok(session.clone())
+    })?;
+
+    ocr_state.update_detection_config(move |config| {
+        config.candidate_filter.ring_number = Some(ring_number)
+    })?;
+
+    Ok(session)
+}
+
+/// Tauri command: Set the pact shards collected in an endless run
+#[tauri::command]
+pub fn session_set_pact_shards(
+    pact_shards: i32,
+    session_state: State<SessionState>,
+) -> Result<DraftSession, String> {
+    session_state.with_session(|session| {
+        session.set_pact_shards(pact_shards)?;
+        Ok(session.clone())
+    })
+}
+
+/// Tauri command: Start a new run, discarding the current session
+///
+/// Cards already drafted stay in the history under the previous run.
+#[tauri::command]
+pub fn session_new_run(
+    covenant: Option<i32>,
+    session_state: State<SessionState>,
+    ocr_state: State<OcrState>,
+) -> Result<DraftSession, String> {
+    let covenant = covenant.unwrap_or(1);
+    if !(1..=25).contains(&covenant) {
+        return Err("Covenant must be between 1 and 25".to_string());
+    }
+
+    let session = session_state.with_session(|session| {
+        *session = DraftSession::new(covenant);
+        Ok(session.clone())
+    })?;
+
+    ocr_state.update_detection_config(|config| config.candidate_filter.ring_number = None)?;
+
+    Ok(session)
+}
+
+/// Tauri command: Record whether the current run was won, then start a new
+/// run at the same covenant
+#[tauri::command]
+pub fn session_end_run(
+    won: bool,
+    session_state: State<SessionState>,
+    access: State<DatabaseAccess>,
+    ocr_state: State<OcrState>,
+) -> Result<DraftSession, String> {
+    let (ended_run_id, session) = session_state.write_session(&access, move |session, conn| {
+        session.end_run(conn, won)?;
+        let ended_run_id = session.run_id.clone();
+        *session = DraftSession::new(session.covenant);
+        Ok((ended_run_id, session.clone()))
+    })?;
+    history::refresh_analytics_in_background(&access);
+    sync::queue_finished_run(access.inner().clone(), ended_run_id);
+
+    ocr_state.update_detection_config(|config| config.candidate_filter.ring_number = None)?;
+
+    Ok(session)
+}
@@ -0,0 +1,95 @@
+//! Settings commands
+//!
+//! Typed access to every persisted option in `crate::settings`. Updates
+//! are validated as a whole before anything is saved or applied.
+
+use crate::commands::ocr::OcrState;
+use crate::commands::window::apply_overlay_settings;
+use crate::database::access::DatabaseAccess;
+use crate::hotkeys::{self, HotkeyState};
+use crate::settings::{self, AppSettings, SettingsUpdate};
+use tauri::{AppHandle, State};
+
+/// Tauri command: Get all settings
+#[tauri::command]
+pub fn get_settings(access: State<DatabaseAccess>) -> Result<AppSettings, String> {
+    settings::load(&access).map_err(|e| e.to_string())
+}
+
+/// Tauri command: Replace one or more settings sections
+///
+/// Sections left out of `update` keep their current values. Every section
+/// is validated before any is saved, and the new settings are emitted as a
+/// `settings-changed` event and returned.
+#[tauri::command]
+pub fn update_settings(
+    update: SettingsUpdate,
+    app: AppHandle,
+    access: State<DatabaseAccess>,
+    ocr_state: State<OcrState>,
+    hotkey_state: State<HotkeyState>,
+) -> Result<AppSettings, String> {
+    apply_update(update, &app, &access, &ocr_state, &hotkey_state)
+}
+
+/// Validate, save and apply `update`, as `update_settings` does
+pub(crate) fn apply_update(
+    update: SettingsUpdate,
+    app: &AppHandle,
+    access: &DatabaseAccess,
+    ocr_state: &OcrState,
+    hotkey_state: &HotkeyState,
+) -> Result<AppSettings, String> {
+    if let Some(ref ocr) = update.ocr {
+        ocr.validate().map_err(|e| e.to_string())?;
+    }
+    if let Some(ref overlay) = update.overlay {
+        overlay.validate()?;
+    }
+    if let Some(ref bindings) = update.hotkeys {
+        bindings.normalized().map_err(|e| e.to_string())?;
+    }
+    if let Some(ref scoring) = update.scoring {
+        scoring.validate().map_err(|e| e.to_string())?;
+    }
+    if let Some(ref prefs) = update.logging {
+        prefs.validate().map_err(|e| e.to_string())?;
+    }
+    if let Some(ref regions) = update.regions {
+        regions.validate().map_err(|e| e.to_string())?;
+    }
+
+    if let Some(bindings) = update.hotkeys {
+        hotkeys::rebind(app, access, hotkey_state, &bindings).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(ocr) = update.ocr {
+        settings::save_section(access, settings::OCR_KEY, &ocr).map_err(|e| e.to_string())?;
+        ocr_state.update_detection_config(move |config| ocr.apply(config))?;
+    }
+
+    if let Some(overlay) = update.overlay {
+        settings::save_section(access, settings::OVERLAY_KEY, &overlay)
+            .map_err(|e| e.to_string())?;
+        apply_overlay_settings(app, &overlay)?;
+    }
+
+    if let Some(scoring) = update.scoring {
+        settings::save_section(access, settings::SCORING_KEY, &scoring)
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(prefs) = update.logging {
+        settings::save_section(access, settings::LOGGING_KEY, &prefs).map_err(|e| e.to_string())?;
+        prefs.apply().map_err(|e| e.to_string())?;
+    }
+
+    if let Some(regions) = update.regions {
+        settings::save_section(access, settings::REGIONS_KEY, &regions)
+            .map_err(|e| e.to_string())?;
+        ocr_state.update_detection_config(move |config| regions.apply(config))?;
+    }
+
+    settings::notify_changed(app, access);
+    settings::load(access).map_err(|e| e.to_string())
+}
@@ -0,0 +1,58 @@
+//! Run sync commands
+
+use crate::database::access::DatabaseAccess;
+use crate::error::{AppError, AppResult};
+use crate::sync::{self, SyncStatus};
+use tauri::State;
+
+/// Tauri command: Upload a finished run, anonymized, to the configured endpoint
+///
+/// If the upload fails the run stays queued and is retried in the background.
+#[tauri::command]
+pub async fn upload_run(
+    run_id: String,
+    access: State<'_, DatabaseAccess>,
+) -> AppResult<SyncStatus> {
+    let access = access.inner().clone();
+    tokio::task::spawn_blocking(move || sync::upload_run(&access, &run_id))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Tauri command: Turn auto-upload of finished runs on or off
+///
+/// `endpoint` replaces the upload URL; an empty string clears it. Auto-upload
+/// needs an endpoint.
+#[tauri::command]
+pub fn set_auto_upload(
+    enabled: bool,
+    endpoint: Option<String>,
+    access: State<DatabaseAccess>,
+) -> AppResult<SyncStatus> {
+    let mut config = sync::load_config(&access)?;
+    if let Some(endpoint) = endpoint {
+        let endpoint = endpoint.trim();
+        config.endpoint = if endpoint.is_empty() {
+            None
+        } else {
+            sync::validate_endpoint(endpoint)?;
+            Some(endpoint.to_string())
+        };
+    }
+    if enabled && config.endpoint.is_none() {
+        return Err(AppError::InvalidInput(
+            "Set an upload endpoint to turn on auto-upload".to_string(),
+        ));
+    }
+    config.auto_upload = enabled;
+    sync::save_config(&access, &config)?;
+
+    access.read(|conn| sync::status(conn, &config))
+}
+
+/// Tauri command: Get the upload settings and queue state
+#[tauri::command]
+pub fn get_sync_status(access: State<DatabaseAccess>) -> AppResult<SyncStatus> {
+    let config = sync::load_config(&access)?;
+    access.read(|conn| sync::status(conn, &config))
+}
@@ -1,5 +1,19 @@
+use crate::commands::ocr::OcrState;
+use crate::database::access::DatabaseAccess;
+use crate::ocr::{self, capture::CaptureRegion};
+use crate::settings;
+use crate::window_state;
 use serde::{Deserialize, Serialize};
-use tauri::{Manager, Window};
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+
+/// Lowest overlay opacity; anything fainter is indistinguishable from hidden
+const MIN_OVERLAY_OPACITY: f64 = 0.1;
+
+/// Height of a score badge anchored above a card region
+const BADGE_HEIGHT: u32 = 48;
+
+/// Gap between a score badge and the card region below it
+const BADGE_MARGIN: u32 = 8;
 
 #[derive(Serialize, Deserialize)]
 pub struct OverlayPosition {
@@ -7,18 +21,29 @@ pub struct OverlayPosition {
     pub y: i32,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct OverlaySize {
+    pub width: u32,
+    pub height: u32,
+}
+
 #[tauri::command]
 pub fn toggle_overlay(window: Window) -> Result<bool, String> {
-    if let Some(overlay) = window.get_webview_window("overlay") {
+    toggle_overlay_window(window.app_handle())
+}
+
+/// Show the overlay if hidden, hide it if shown; returns the new visibility
+pub fn toggle_overlay_window(app: &AppHandle) -> Result<bool, String> {
+    if let Some(overlay) = app.get_webview_window("overlay") {
         let is_visible = overlay.is_visible().map_err(|e| e.to_string())?;
 
         if is_visible {
             overlay.hide().map_err(|e| e.to_string())?;
-            Ok(false)
         } else {
             overlay.show().map_err(|e| e.to_string())?;
-            Ok(true)
         }
+        window_state::remember_overlay_state(app);
+        Ok(!is_visible)
     } else {
         Err("Overlay window not found".to_string())
     }
@@ -28,6 +53,7 @@ pub fn toggle_overlay(window: Window) -> Result<bool, String> {
 pub fn show_overlay(window: Window) -> Result<(), String> {
     if let Some(overlay) = window.get_webview_window("overlay") {
         overlay.show().map_err(|e| e.to_string())?;
+        window_state::remember_overlay_state(window.app_handle());
     }
     Ok(())
 }
@@ -36,6 +62,7 @@ pub fn show_overlay(window: Window) -> Result<(), String> {
 pub fn hide_overlay(window: Window) -> Result<(), String> {
     if let Some(overlay) = window.get_webview_window("overlay") {
         overlay.hide().map_err(|e| e.to_string())?;
+        window_state::remember_overlay_state(window.app_handle());
     }
     Ok(())
 }
@@ -49,6 +76,417 @@ pub fn set_overlay_position(window: Window, position: OverlayPosition) -> Result
                 y: position.y,
             }))
             .map_err(|e| e.to_string())?;
+        window_state::remember_overlay_state(window.app_handle());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_overlay_size(window: Window, size: OverlaySize) -> Result<(), String> {
+    if let Some(overlay) = window.get_webview_window("overlay") {
+        overlay
+            .set_size(tauri::Size::Physical(tauri::PhysicalSize {
+                width: size.width,
+                height: size.height,
+            }))
+            .map_err(|e| e.to_string())?;
+        window_state::remember_overlay_state(window.app_handle());
+    }
+    Ok(())
+}
+
+/// Where the score badge for one card region goes, relative to the overlay
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BadgeAnchor {
+    /// Index of the capture region (card slot) the badge labels
+    pub region_index: usize,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Overlay bounds covering a badge above every card region
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OverlayLayout {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub badges: Vec<BadgeAnchor>,
+}
+
+/// Lay out one badge directly above each card region
+///
+/// Badges are as wide as their region and sit `BADGE_MARGIN` above it,
+/// clamped to the top of the screen. Returns `None` without valid regions.
+pub fn layout_overlay_for_regions(regions: &[CaptureRegion]) -> Option<OverlayLayout> {
+    let badges: Vec<(usize, CaptureRegion)> = regions
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.is_valid())
+        .map(|(i, r)| {
+            let y = (r.y - (BADGE_MARGIN + BADGE_HEIGHT) as i32).max(0);
+            (i, CaptureRegion::new(r.x, y, r.width, BADGE_HEIGHT))
+        })
+        .collect();
+
+    let left = badges.iter().map(|(_, b)| b.x).min()?;
+    let top = badges.iter().map(|(_, b)| b.y).min()?;
+    let right = badges.iter().map(|(_, b)| b.x + b.width as i32).max()?;
+    let bottom = badges.iter().map(|(_, b)| b.y + b.height as i32).max()?;
+
+    Some(OverlayLayout {
+        x: left,
+        y: top,
+        width: (right - left) as u32,
+        height: (bottom - top) as u32,
+        badges: badges
+            .into_iter()
+            .map(|(region_index, b)| BadgeAnchor {
+                region_index,
+                x: b.x - left,
+                y: b.y - top,
+                width: b.width,
+                height: b.height,
+            })
+            .collect(),
+    })
+}
+
+/// Move and resize the overlay over the card regions
+///
+/// The overlay receives the badge positions as an `overlay-badges` event.
+#[tauri::command]
+pub fn position_overlay_at_regions(
+    window: Window,
+    ocr_state: State<OcrState>,
+) -> Result<OverlayLayout, String> {
+    let mut capture = ocr_state.detection_config()?.capture;
+    if !capture.normalized_regions.is_empty() {
+        if let Ok((width, height)) = ocr::get_primary_screen_dimensions() {
+            capture.resolve_for_screen(width, height);
+        }
+    }
+
+    let layout = layout_overlay_for_regions(capture.get_regions())
+        .ok_or_else(|| "No capture regions configured".to_string())?;
+
+    let overlay = window
+        .get_webview_window("overlay")
+        .ok_or_else(|| "Overlay window not found".to_string())?;
+    overlay
+        .set_position(tauri::Position::Physical(tauri::PhysicalPosition {
+            x: layout.x,
+            y: layout.y,
+        }))
+        .map_err(|e| e.to_string())?;
+    overlay
+        .set_size(tauri::Size::Physical(tauri::PhysicalSize {
+            width: layout.width,
+            height: layout.height,
+        }))
+        .map_err(|e| e.to_string())?;
+    window_state::remember_overlay_state(window.app_handle());
+
+    window
+        .app_handle()
+        .emit("overlay-badges", layout.badges.clone())
+        .map_err(|e| e.to_string())?;
+
+    Ok(layout)
+}
+
+/// Persisted overlay appearance
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct OverlaySettings {
+    /// Opacity of the overlay content, from 0.1 to 1.0
+    pub opacity: f64,
+    pub always_on_top: bool,
+    /// Show the overlay when a draft screen is detected and hide it after the pick
+    pub auto_overlay: bool,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            always_on_top: true,
+            auto_overlay: false,
+        }
+    }
+}
+
+impl OverlaySettings {
+    pub fn validate(&self) -> Result<(), String> {
+        validate_opacity(self.opacity).map(|_| ())
+    }
+}
+
+fn load_overlay_settings(access: &DatabaseAccess) -> Result<OverlaySettings, String> {
+    settings::load_section(access, settings::OVERLAY_KEY).map_err(|e| e.to_string())
+}
+
+/// Apply `update` to the saved overlay settings and persist the result
+fn update_overlay_settings<F>(access: &DatabaseAccess, update: F) -> Result<OverlaySettings, String>
+where
+    F: FnOnce(&mut OverlaySettings),
+{
+    let mut overlay_settings = load_overlay_settings(access)?;
+    update(&mut overlay_settings);
+
+    settings::save_section(access, settings::OVERLAY_KEY, &overlay_settings)
+        .map_err(|e| e.to_string())?;
+    Ok(overlay_settings)
+}
+
+fn validate_opacity(opacity: f64) -> Result<f64, String> {
+    if (MIN_OVERLAY_OPACITY..=1.0).contains(&opacity) {
+        Ok(opacity)
+    } else {
+        Err(format!(
+            "Opacity must be between {} and 1.0, got {}",
+            MIN_OVERLAY_OPACITY, opacity
+        ))
+    }
+}
+
+/// Fade the overlay page; window-level opacity is not portable, so the
+/// transparent overlay's content is faded instead
+fn apply_overlay_opacity(app: &AppHandle, opacity: f64) -> Result<(), String> {
+    if let Some(overlay) = app.get_webview_window("overlay") {
+        overlay
+            .eval(format!(
+                "document.documentElement.style.opacity = '{}'",
+                opacity
+            ))
+            .map_err(|e| e.to_string())?;
     }
     Ok(())
 }
+
+fn apply_overlay_always_on_top(app: &AppHandle, always_on_top: bool) -> Result<(), String> {
+    if let Some(overlay) = app.get_webview_window("overlay") {
+        overlay
+            .set_always_on_top(always_on_top)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Apply overlay appearance to the overlay window
+pub fn apply_overlay_settings(
+    app: &AppHandle,
+    overlay_settings: &OverlaySettings,
+) -> Result<(), String> {
+    apply_overlay_always_on_top(app, overlay_settings.always_on_top)?;
+    apply_overlay_opacity(app, overlay_settings.opacity)
+}
+
+/// Re-apply saved overlay appearance during startup
+pub fn restore_overlay_settings(app: &AppHandle, access: &DatabaseAccess) -> Result<(), String> {
+    apply_overlay_settings(app, &load_overlay_settings(access)?)
+}
+
+#[tauri::command]
+pub fn get_overlay_settings(access: State<DatabaseAccess>) -> Result<OverlaySettings, String> {
+    load_overlay_settings(&access)
+}
+
+#[tauri::command]
+pub fn set_overlay_opacity(
+    opacity: f64,
+    window: Window,
+    access: State<DatabaseAccess>,
+) -> Result<OverlaySettings, String> {
+    let opacity = validate_opacity(opacity)?;
+    apply_overlay_opacity(window.app_handle(), opacity)?;
+    let saved = update_overlay_settings(&access, |s| s.opacity = opacity)?;
+    settings::notify_changed(window.app_handle(), &access);
+    Ok(saved)
+}
+
+#[tauri::command]
+pub fn set_overlay_always_on_top(
+    always_on_top: bool,
+    window: Window,
+    access: State<DatabaseAccess>,
+) -> Result<OverlaySettings, String> {
+    apply_overlay_always_on_top(window.app_handle(), always_on_top)?;
+    let saved = update_overlay_settings(&access, |s| s.always_on_top = always_on_top)?;
+    settings::notify_changed(window.app_handle(), &access);
+    Ok(saved)
+}
+
+#[tauri::command]
+pub fn set_auto_overlay(
+    enabled: bool,
+    window: Window,
+    access: State<DatabaseAccess>,
+) -> Result<OverlaySettings, String> {
+    let saved = update_overlay_settings(&access, |s| s.auto_overlay = enabled)?;
+    settings::notify_changed(window.app_handle(), &access);
+    Ok(saved)
+}
+
+/// Consecutive non-draft frames before the draft screen counts as left;
+/// one missed frame mid-draft should not hide the overlay
+const DRAFT_SCREEN_EXIT_FRAMES: u32 = 2;
+
+/// Tracks continuous detection results for draft screen transitions
+#[derive(Debug, Default)]
+pub struct DraftScreenWatcher {
+    on_draft_screen: bool,
+    misses: u32,
+}
+
+impl DraftScreenWatcher {
+    /// Record a frame; returns the new state when the draft screen appears or goes
+    pub fn update(&mut self, is_draft_screen: bool) -> Option<bool> {
+        if is_draft_screen {
+            self.misses = 0;
+            if !self.on_draft_screen {
+                self.on_draft_screen = true;
+                return Some(true);
+            }
+        } else if self.on_draft_screen {
+            self.misses += 1;
+            if self.misses >= DRAFT_SCREEN_EXIT_FRAMES {
+                self.on_draft_screen = false;
+                self.misses = 0;
+                return Some(false);
+            }
+        }
+        None
+    }
+}
+
+/// Show the overlay and detect when a draft starts, hide it after the pick
+///
+/// Does nothing unless `auto_overlay` is enabled.
+pub fn auto_overlay_on_draft_screen(app: &AppHandle, on_draft_screen: bool) {
+    let enabled = match app.try_state::<DatabaseAccess>() {
+        Some(access) => load_overlay_settings(&access)
+            .map(|s| s.auto_overlay)
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to load overlay settings: {}", e);
+                false
+            }),
+        None => false,
+    };
+    if !enabled {
+        return;
+    }
+
+    let overlay = match app.get_webview_window("overlay") {
+        Some(overlay) => overlay,
+        None => return,
+    };
+
+    let result = if on_draft_screen {
+        log::info!("Draft screen detected; showing overlay");
+        crate::commands::ocr::detect_in_background(app);
+        overlay.show()
+    } else {
+        log::info!("Draft screen closed; hiding overlay");
+        overlay.hide()
+    };
+    if let Err(e) = result {
+        log::warn!("Failed to update overlay for draft screen: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_layout_overlay_for_regions() {
+        let regions = [
+            CaptureRegion::new(400, 300, 200, 40),
+            CaptureRegion::new(0, 0, 0, 0),
+            CaptureRegion::new(800, 320, 200, 40),
+        ];
+
+        let layout = layout_overlay_for_regions(&regions).unwrap();
+        assert_eq!((layout.x, layout.y), (400, 244));
+        assert_eq!((layout.width, layout.height), (600, 68));
+        assert_eq!(
+            layout.badges,
+            vec![
+                BadgeAnchor {
+                    region_index: 0,
+                    x: 0,
+                    y: 0,
+                    width: 200,
+                    height: BADGE_HEIGHT,
+                },
+                BadgeAnchor {
+                    region_index: 2,
+                    x: 400,
+                    y: 20,
+                    width: 200,
+                    height: BADGE_HEIGHT,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_overlay_clamps_to_screen_top() {
+        let layout = layout_overlay_for_regions(&[CaptureRegion::new(10, 20, 100, 30)]).unwrap();
+        assert_eq!(layout.y, 0);
+        assert!(layout_overlay_for_regions(&[]).is_none());
+    }
+
+    #[test]
+    fn test_draft_screen_watcher() {
+        let mut watcher = DraftScreenWatcher::default();
+        assert_eq!(watcher.update(false), None);
+        assert_eq!(watcher.update(true), Some(true));
+        assert_eq!(watcher.update(true), None);
+
+        // A single missed frame does not count as leaving
+        assert_eq!(watcher.update(false), None);
+        assert_eq!(watcher.update(true), None);
+
+        assert_eq!(watcher.update(false), None);
+        assert_eq!(watcher.update(false), Some(false));
+        assert_eq!(watcher.update(false), None);
+    }
+
+    #[test]
+    fn test_validate_opacity() {
+        assert_eq!(validate_opacity(0.5), Ok(0.5));
+        assert_eq!(validate_opacity(1.0), Ok(1.0));
+        assert!(validate_opacity(0.05).is_err());
+        assert!(validate_opacity(1.5).is_err());
+        assert!(validate_opacity(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_overlay_settings_persist() {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let access = DatabaseAccess::new(temp_file.path().to_path_buf());
+
+        assert_eq!(
+            load_overlay_settings(&access).unwrap(),
+            OverlaySettings::default()
+        );
+
+        update_overlay_settings(&access, |s| s.opacity = 0.6).unwrap();
+        let saved = update_overlay_settings(&access, |s| s.always_on_top = false).unwrap();
+        assert_eq!(
+            saved,
+            OverlaySettings {
+                opacity: 0.6,
+                always_on_top: false,
+                auto_overlay: false,
+            }
+        );
+        assert_eq!(load_overlay_settings(&access).unwrap(), saved);
+    }
+}
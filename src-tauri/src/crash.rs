@@ -0,0 +1,207 @@
+//! Crash reports
+//!
+//! A panic hook writes `crash_report.json` to the app data directory with
+//! the panic message, backtrace, app version and the tail of the log, so a
+//! crash (including a failed `expect` during startup) leaves something
+//! behind. The next launch hands it to the frontend until it is dismissed.
+//! Panics caught with `catch_panic`, which the caller recovers from, are
+//! only logged.
+
+use crate::logging;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+
+pub const CRASH_REPORT_FILE: &str = "crash_report.json";
+
+/// Log lines kept in a report
+const LOG_TAIL_LINES: usize = 100;
+
+thread_local! {
+    /// Whether this thread is inside `catch_panic`
+    static RECOVERING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// What was known when the app panicked
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CrashReport {
+    pub timestamp: String,
+    pub app_version: String,
+    pub os: String,
+    pub thread: String,
+    pub message: String,
+    /// `file:line:column` of the panic, when known
+    pub location: Option<String>,
+    pub backtrace: String,
+    /// Last lines of the current log file, oldest first
+    pub log_tail: Vec<String>,
+}
+
+pub fn report_path(dir: &Path) -> PathBuf {
+    dir.join(CRASH_REPORT_FILE)
+}
+
+/// The last `count` lines of a file, oldest first
+fn tail_lines(path: &Path, count: usize) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+fn build_report(
+    message: String,
+    location: Option<String>,
+    backtrace: &Backtrace,
+    log_dir: &Path,
+) -> CrashReport {
+    CrashReport {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        thread: std::thread::current()
+            .name()
+            .unwrap_or("<unnamed>")
+            .to_string(),
+        message,
+        location,
+        backtrace: backtrace.to_string(),
+        log_tail: tail_lines(&log_dir.join(logging::LOG_FILE_NAME), LOG_TAIL_LINES),
+    }
+}
+
+pub fn write_report(dir: &Path, report: &CrashReport) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(report)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::create_dir_all(dir)?;
+    fs::write(report_path(dir), json)
+}
+
+/// The report left by the last crash, if any
+pub fn read_report(dir: &Path) -> io::Result<Option<CrashReport>> {
+    let path = report_path(dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = fs::read(path)?;
+    serde_json::from_slice(&json)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Delete the stored report once the user has seen it
+pub fn clear_report(dir: &Path) -> io::Result<()> {
+    match fs::remove_file(report_path(dir)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Run `f`, catching a panic the caller will recover from
+///
+/// Like `std::panic::catch_unwind`, except the panic hook logs such a
+/// panic without writing a crash report.
+pub fn catch_panic<F, R>(f: F) -> std::thread::Result<R>
+where
+    F: FnOnce() -> R + panic::UnwindSafe,
+{
+    let outer = RECOVERING.with(|recovering| recovering.replace(true));
+    let result = panic::catch_unwind(f);
+    RECOVERING.with(|recovering| recovering.set(outer));
+    result
+}
+
+/// Install the panic hook, keeping the default one (which prints to stderr)
+///
+/// Reports go to `dir`, which is also where the log files are read from.
+pub fn install(dir: PathBuf) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let backtrace = Backtrace::force_capture();
+        log::error!("Panic: {}", info);
+        log::logger().flush();
+        if RECOVERING.with(Cell::get) {
+            default_hook(info);
+            return;
+        }
+
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        let report = build_report(panic_message(info.payload()), location, &backtrace, &dir);
+        if let Err(e) = write_report(&dir, &report) {
+            eprintln!("Failed to write crash report: {}", e);
+        }
+        default_hook(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_report_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(read_report(dir.path()).unwrap(), None);
+
+        let lines: Vec<String> = (0..150).map(|i| format!("line {}", i)).collect();
+        fs::write(dir.path().join(logging::LOG_FILE_NAME), lines.join("\n")).unwrap();
+        let tail = tail_lines(&dir.path().join(logging::LOG_FILE_NAME), LOG_TAIL_LINES);
+        assert_eq!(tail.len(), LOG_TAIL_LINES);
+        assert_eq!(tail[0], "line 50");
+        assert_eq!(tail[LOG_TAIL_LINES - 1], "line 149");
+
+        let report = CrashReport {
+            timestamp: "2024-01-01T00:00:00+00:00".to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: "linux x86_64".to_string(),
+            thread: "main".to_string(),
+            message: "Failed to get app data dir".to_string(),
+            location: Some("src/lib.rs:42:18".to_string()),
+            backtrace: String::new(),
+            log_tail: tail,
+        };
+        write_report(dir.path(), &report).unwrap();
+        assert_eq!(read_report(dir.path()).unwrap(), Some(report));
+
+        clear_report(dir.path()).unwrap();
+        assert_eq!(read_report(dir.path()).unwrap(), None);
+        // Clearing twice is fine
+        clear_report(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn test_catch_panic_marks_thread_recovering() {
+        assert!(!RECOVERING.with(Cell::get));
+        let result = catch_panic(|| {
+            assert!(RECOVERING.with(Cell::get));
+            assert_eq!(catch_panic(|| 1).unwrap(), 1);
+            // Still inside the outer call
+            assert!(RECOVERING.with(Cell::get));
+            panic!("recovered");
+        });
+        assert!(result.is_err());
+        assert!(!RECOVERING.with(Cell::get));
+    }
+}
@@ -0,0 +1,208 @@
+//! Read and write connection routing
+//!
+//! Reads borrow a read-only connection from a small pool, so UI queries
+//! never queue behind each other. Writes are sent to a single writer
+//! thread that owns the one write connection: the continuous detection
+//! loop hands its history off and carries on, and queued writes never
+//! contend with each other for the lock.
+//!
+//! Commands, background jobs and the CLI all go through this. Two writers
+//! still open a connection of their own, with WAL and the busy timeout
+//! covering them: restoring an app data bundle, which needs a connection
+//! to restore into and runs as a writer job, and the sync flush, which
+//! sends each upload over the network between its writes and so must not
+//! hold up the writer.
+
+use rusqlite::{Connection, OpenFlags};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Read connections kept open between reads
+const MAX_IDLE_READERS: usize = 4;
+
+type WriteJob = Box<dyn FnOnce(&Connection) + Send>;
+
+struct Inner {
+    db_path: PathBuf,
+    readers: Mutex<Vec<Connection>>,
+    /// Started on the first write
+    writer: Mutex<Option<Sender<WriteJob>>>,
+}
+
+/// Routes reads to pooled read-only connections and writes to the writer
+/// thread. Managed by Tauri; cheap to clone.
+#[derive(Clone)]
+pub struct DatabaseAccess {
+    inner: Arc<Inner>,
+}
+
+impl DatabaseAccess {
+    pub fn new(db_path: PathBuf) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                db_path,
+                readers: Mutex::new(Vec::new()),
+                writer: Mutex::new(None),
+            }),
+        }
+    }
+
+    pub fn db_path(&self) -> &Path {
+        &self.inner.db_path
+    }
+
+    /// Run `query` on a read-only connection
+    pub fn read<T, E>(&self, query: impl FnOnce(&Connection) -> Result<T, E>) -> Result<T, E>
+    where
+        E: From<rusqlite::Error>,
+    {
+        let idle = self
+            .inner
+            .readers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop();
+        let conn = match idle {
+            Some(conn) => conn,
+            None => open_reader(&self.inner.db_path)?,
+        };
+
+        let result = query(&conn);
+
+        let mut readers = self.inner.readers.lock().unwrap_or_else(|e| e.into_inner());
+        if readers.len() < MAX_IDLE_READERS {
+            readers.push(conn);
+        }
+        result
+    }
+
+    /// Queue `job` on the writer thread and return at once
+    ///
+    /// Jobs run in the order they were queued. Errors are the job's to log.
+    pub fn write(&self, job: impl FnOnce(&Connection) + Send + 'static) {
+        let job: WriteJob = Box::new(job);
+        let job = {
+            let mut writer = self.inner.writer.lock().unwrap_or_else(|e| e.into_inner());
+            let sender = writer.get_or_insert_with(|| spawn_writer(self.inner.db_path.clone()));
+            match sender.send(job) {
+                Ok(()) => return,
+                Err(mpsc::SendError(job)) => {
+                    // Start a new writer on the next write
+                    *writer = None;
+                    job
+                }
+            }
+        };
+
+        // The writer thread has gone; run the job on a connection of its own
+        log::warn!("Database writer unavailable, writing directly");
+        match super::open(&self.inner.db_path) {
+            Ok(conn) => job(&conn),
+            Err(e) => log::warn!("Failed to open database for write: {}", e),
+        }
+    }
+
+    /// Queue `job` on the writer thread and wait for its result
+    pub fn write_and_wait<T, E>(
+        &self,
+        job: impl FnOnce(&Connection) -> Result<T, E> + Send + 'static,
+    ) -> Result<T, E>
+    where
+        T: Send + 'static,
+        E: From<rusqlite::Error> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.write(move |conn| {
+            let _ = sender.send(job(conn));
+        });
+        match receiver.recv() {
+            Ok(result) => result,
+            // The job was dropped unrun, so the writer couldn't open the database
+            Err(_) => Err(E::from(rusqlite::Error::InvalidPath(
+                self.inner.db_path.clone(),
+            ))),
+        }
+    }
+}
+
+fn open_reader(db_path: &Path) -> rusqlite::Result<Connection> {
+    let conn = Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    conn.busy_timeout(super::BUSY_TIMEOUT)?;
+    Ok(conn)
+}
+
+/// Start the thread that owns the write connection
+fn spawn_writer(db_path: PathBuf) -> Sender<WriteJob> {
+    let (sender, receiver) = mpsc::channel::<WriteJob>();
+    let spawned = thread::Builder::new()
+        .name("db-writer".to_string())
+        .spawn(move || {
+            let conn = match super::open(&db_path) {
+                Ok(conn) => conn,
+                Err(e) => {
+                    log::error!("Database writer failed to open the database: {}", e);
+                    return;
+                }
+            };
+            // Runs until every `DatabaseAccess` handle is dropped
+            for job in receiver {
+                job(&conn);
+            }
+        });
+    if let Err(e) = spawned {
+        log::error!("Failed to start database writer: {}", e);
+    }
+    sender
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn setting_count(conn: &Connection) -> rusqlite::Result<i64> {
+        conn.query_row(
+            "SELECT COUNT(*) FROM app_settings WHERE key = 'access_test'",
+            [],
+            |row| row.get(0),
+        )
+    }
+
+    #[test]
+    fn test_reads_continue_during_a_write() {
+        let temp = NamedTempFile::new().unwrap();
+        crate::database::init(temp.path()).unwrap();
+        let access = DatabaseAccess::new(temp.path().to_path_buf());
+
+        let (started, wait_started) = mpsc::channel();
+        let (release, wait_release) = mpsc::channel::<()>();
+        access.write(move |conn| {
+            let tx = conn.unchecked_transaction().unwrap();
+            tx.execute(
+                "INSERT INTO app_settings (key, value) VALUES ('access_test', '1')",
+                [],
+            )
+            .unwrap();
+            started.send(()).unwrap();
+            wait_release.recv().unwrap();
+            tx.commit().unwrap();
+        });
+        wait_started.recv().unwrap();
+
+        // The uncommitted write neither blocks the read nor shows in it
+        assert_eq!(access.read(setting_count).unwrap(), 0);
+        assert!(access
+            .read(|conn| conn.execute("DELETE FROM app_settings", []))
+            .is_err());
+
+        release.send(()).unwrap();
+        // Queued after the first job, so it sees the commit
+        assert_eq!(access.write_and_wait(setting_count).unwrap(), 1);
+        assert_eq!(access.read(setting_count).unwrap(), 1);
+    }
+}
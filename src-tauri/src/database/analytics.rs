@@ -0,0 +1,129 @@
+//! Summary tables for the analytics commands
+//!
+//! Aggregating `deck_history` and `runs` on every query gets slow as
+//! history grows, so per-card and per-champion stats are kept in summary
+//! tables. Triggers on the raw tables queue each card and champion whose
+//! rows change; `refresh_analytics` recomputes only those summary rows and
+//! clears the queue. It runs in the background after each completed run,
+//! and the queries refresh first so they never read stale rows. Deleted
+//! runs are left out of every summary.
+
+use rusqlite::{Connection, Result};
+
+/// Summary rows recomputed by a refresh
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefreshReport {
+    pub cards: usize,
+    pub champions: usize,
+}
+
+/// Recompute the summaries of every queued card and champion
+pub fn refresh_analytics(conn: &Connection) -> Result<RefreshReport> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "DELETE FROM card_stats_summary
+         WHERE card_id IN (SELECT card_id FROM analytics_stale_cards)",
+        [],
+    )?;
+    let cards = tx.execute(
+        "INSERT INTO card_stats_summary
+            (card_id, runs_drafted, runs_completed, wins, average_ring, average_score)
+         SELECT card_id,
+                COUNT(DISTINCT run_id),
+                COUNT(DISTINCT CASE WHEN did_win IS NOT NULL THEN run_id END),
+                COUNT(DISTINCT CASE WHEN did_win = 1 THEN run_id END),
+                AVG(ring_number),
+                AVG(score_at_draft)
+         FROM live_deck_history
+         WHERE card_id IN (SELECT card_id FROM analytics_stale_cards)
+         GROUP BY card_id",
+        [],
+    )?;
+    tx.execute("DELETE FROM analytics_stale_cards", [])?;
+
+    tx.execute(
+        "DELETE FROM champion_stats_summary
+         WHERE EXISTS (SELECT 1 FROM analytics_stale_champions AS s
+                       WHERE s.champion = champion_stats_summary.champion
+                         AND s.champion_path IS champion_stats_summary.champion_path)",
+        [],
+    )?;
+    let champions = tx.execute(
+        "INSERT INTO champion_stats_summary
+            (champion, champion_path, covenant, games, wins, losses, cards)
+         SELECT r.champion, r.champion_path, r.covenant, COUNT(*),
+                COALESCE(SUM(r.did_win = 1), 0),
+                COALESCE(SUM(r.did_win = 0), 0),
+                COALESCE(SUM(h.cards), 0)
+         FROM live_runs AS r
+         LEFT JOIN (SELECT run_id, COUNT(*) AS cards FROM deck_history GROUP BY run_id) AS h
+                ON h.run_id = r.run_id
+         WHERE EXISTS (SELECT 1 FROM analytics_stale_champions AS s
+                       WHERE s.champion = r.champion AND s.champion_path IS r.champion_path)
+         GROUP BY r.champion, r.champion_path, r.covenant",
+        [],
+    )?;
+    tx.execute("DELETE FROM analytics_stale_champions", [])?;
+    tx.commit()?;
+
+    Ok(RefreshReport { cards, champions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn summary_count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_refresh_only_recomputes_changed_rows() {
+        let temp = NamedTempFile::new().unwrap();
+        crate::database::init(temp.path()).unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO runs (run_id, champion, covenant) VALUES ('run1', 'Fel', 10);
+             INSERT INTO deck_history (run_id, card_id, ring_number, draft_order, champion, covenant)
+             VALUES ('run1', 'banished_fel', 1, 1, 'Fel', 10),
+                    ('run1', 'banished_cleave', 2, 2, 'Fel', 10);",
+        )
+        .unwrap();
+        let report = refresh_analytics(&conn).unwrap();
+        assert_eq!((report.cards, report.champions), (2, 1));
+        assert_eq!(refresh_analytics(&conn).unwrap(), RefreshReport::default());
+
+        // Ending the run touches both cards and the champion
+        conn.execute_batch(
+            "UPDATE runs SET did_win = 1 WHERE run_id = 'run1';
+             UPDATE deck_history SET did_win = 1 WHERE run_id = 'run1';",
+        )
+        .unwrap();
+        let report = refresh_analytics(&conn).unwrap();
+        assert_eq!((report.cards, report.champions), (2, 1));
+        let (completed, wins): (u32, u32) = conn
+            .query_row(
+                "SELECT runs_completed, wins FROM card_stats_summary WHERE card_id = 'banished_cleave'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((completed, wins), (1, 1));
+
+        // Deleting a card's last row drops its summary
+        conn.execute(
+            "DELETE FROM deck_history WHERE card_id = 'banished_cleave'",
+            [],
+        )
+        .unwrap();
+        let report = refresh_analytics(&conn).unwrap();
+        assert_eq!((report.cards, report.champions), (0, 1));
+        assert_eq!(summary_count(&conn, "card_stats_summary"), 1);
+        assert_eq!(summary_count(&conn, "champion_stats_summary"), 1);
+    }
+}
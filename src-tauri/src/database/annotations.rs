@@ -0,0 +1,192 @@
+//! Personal card notes, tags and favorites
+//!
+//! Players can attach one free-text note and any number of tags to a card,
+//! and mark cards as favorites to have them highlighted when offered.
+//! Tags compare case-insensitively, so "Seraph" and "seraph" are one tag.
+
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use std::collections::{HashMap, HashSet};
+
+/// A card's note, tags and favorite flag
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CardAnnotation {
+    pub note: Option<String>,
+    pub tags: Vec<String>,
+    pub favorite: bool,
+}
+
+/// The note on a card, if any
+pub fn get_note(conn: &Connection, card_id: &str) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT note FROM card_notes WHERE card_id = ?1",
+        [card_id],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Set or replace the note on a card
+pub fn set_note(conn: &Connection, card_id: &str, note: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO card_notes (card_id, note, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)
+         ON CONFLICT(card_id) DO UPDATE SET note = excluded.note, updated_at = CURRENT_TIMESTAMP",
+        params![card_id, note],
+    )?;
+    Ok(())
+}
+
+/// Delete the note on a card, returning whether there was one
+pub fn delete_note(conn: &Connection, card_id: &str) -> Result<bool> {
+    Ok(conn.execute("DELETE FROM card_notes WHERE card_id = ?1", [card_id])? > 0)
+}
+
+/// Tags on a card, in the order they were added
+pub fn get_tags(conn: &Connection, card_id: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT tag FROM card_tags WHERE card_id = ?1 ORDER BY id")?;
+    let tags = stmt.query_map([card_id], |row| row.get(0))?.collect();
+    tags
+}
+
+/// Tag a card, returning whether the tag is new for it
+pub fn add_tag(conn: &Connection, card_id: &str, tag: &str) -> Result<bool> {
+    Ok(conn.execute(
+        "INSERT OR IGNORE INTO card_tags (card_id, tag) VALUES (?1, ?2)",
+        params![card_id, tag],
+    )? > 0)
+}
+
+/// Remove a tag from a card, returning whether it was there
+pub fn remove_tag(conn: &Connection, card_id: &str, tag: &str) -> Result<bool> {
+    Ok(conn.execute(
+        "DELETE FROM card_tags WHERE card_id = ?1 AND tag = ?2",
+        params![card_id, tag],
+    )? > 0)
+}
+
+/// Every tag in use with the number of cards carrying it
+pub fn list_tags(conn: &Connection) -> Result<Vec<(String, u32)>> {
+    let mut stmt = conn.prepare(
+        "SELECT MIN(tag), COUNT(*) FROM card_tags GROUP BY tag ORDER BY COUNT(*) DESC, MIN(tag)",
+    )?;
+    let tags = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect();
+    tags
+}
+
+/// Whether a card is a favorite
+pub fn is_favorite(conn: &Connection, card_id: &str) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM favorites WHERE card_id = ?1)",
+        [card_id],
+        |row| row.get(0),
+    )
+}
+
+/// Add a card to the favorites, or remove it if already there
+///
+/// Returns whether the card is a favorite afterwards.
+pub fn toggle_favorite(conn: &Connection, card_id: &str) -> Result<bool> {
+    if conn.execute("DELETE FROM favorites WHERE card_id = ?1", [card_id])? > 0 {
+        return Ok(false);
+    }
+    conn.execute("INSERT INTO favorites (card_id) VALUES (?1)", [card_id])?;
+    Ok(true)
+}
+
+/// IDs of every favorite card
+pub fn favorite_ids(conn: &Connection) -> Result<HashSet<String>> {
+    let mut stmt = conn.prepare("SELECT card_id FROM favorites")?;
+    let ids = stmt.query_map([], |row| row.get(0))?.collect();
+    ids
+}
+
+/// Notes, tags and favorites of every annotated card, keyed by card ID
+pub fn load_all(conn: &Connection) -> Result<HashMap<String, CardAnnotation>> {
+    let mut annotations: HashMap<String, CardAnnotation> = HashMap::new();
+
+    let mut stmt = conn.prepare("SELECT card_id, note FROM card_notes")?;
+    let notes = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?;
+    for note in notes {
+        let (card_id, note) = note?;
+        annotations.entry(card_id).or_default().note = Some(note);
+    }
+
+    let mut stmt = conn.prepare("SELECT card_id, tag FROM card_tags ORDER BY id")?;
+    let tags = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?;
+    for tag in tags {
+        let (card_id, tag) = tag?;
+        annotations.entry(card_id).or_default().tags.push(tag);
+    }
+
+    for card_id in favorite_ids(conn)? {
+        annotations.entry(card_id).or_default().favorite = true;
+    }
+
+    Ok(annotations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn setup() -> (Connection, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        (conn, temp_file)
+    }
+
+    #[test]
+    fn test_notes_round_trip() {
+        let (conn, _temp) = setup();
+
+        assert_eq!(get_note(&conn, "banished_fel").unwrap(), None);
+        set_note(&conn, "banished_fel", "great vs Seraph").unwrap();
+        set_note(&conn, "banished_fel", "great vs Seraph, weak vs Talos").unwrap();
+        assert_eq!(
+            get_note(&conn, "banished_fel").unwrap().as_deref(),
+            Some("great vs Seraph, weak vs Talos")
+        );
+
+        assert!(delete_note(&conn, "banished_fel").unwrap());
+        assert!(!delete_note(&conn, "banished_fel").unwrap());
+    }
+
+    #[test]
+    fn test_tags_are_case_insensitive() {
+        let (conn, _temp) = setup();
+
+        assert!(add_tag(&conn, "banished_fel", "Seraph").unwrap());
+        assert!(!add_tag(&conn, "banished_fel", "seraph").unwrap());
+        assert!(add_tag(&conn, "banished_fel", "core").unwrap());
+        assert!(add_tag(&conn, "banished_cleave", "SERAPH").unwrap());
+        assert_eq!(
+            get_tags(&conn, "banished_fel").unwrap(),
+            vec!["Seraph", "core"]
+        );
+
+        let tags = list_tags(&conn).unwrap();
+        assert_eq!(tags[0].1, 2);
+        assert!(tags[0].0.eq_ignore_ascii_case("seraph"));
+
+        assert!(remove_tag(&conn, "banished_fel", "SERAPH").unwrap());
+        let all = load_all(&conn).unwrap();
+        assert_eq!(all["banished_fel"].tags, vec!["core"]);
+        assert_eq!(all["banished_cleave"].tags, vec!["SERAPH"]);
+    }
+
+    #[test]
+    fn test_toggle_favorite() {
+        let (conn, _temp) = setup();
+
+        assert!(!is_favorite(&conn, "banished_fel").unwrap());
+        assert!(toggle_favorite(&conn, "banished_fel").unwrap());
+        assert!(is_favorite(&conn, "banished_fel").unwrap());
+        assert!(load_all(&conn).unwrap()["banished_fel"].favorite);
+
+        assert!(!toggle_favorite(&conn, "banished_fel").unwrap());
+        assert!(favorite_ids(&conn).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+//! Audit log of edits to scoring data
+//!
+//! Edits made through the app (archetypes, expansions, imported community
+//! stats) change scores with no trace in the card data itself. Each edit
+//! is logged with the value before and after as JSON, so a player can see
+//! when a score changed and which edit changed it.
+
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+
+/// Entries returned when no limit is given
+const DEFAULT_LIMIT: u32 = 100;
+
+/// What an edit did
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Enable,
+    Disable,
+    Import,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Enable => "enable",
+            AuditAction::Disable => "disable",
+            AuditAction::Import => "import",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub id: i64,
+    /// Kind of data edited, such as `archetype` or `expansion`
+    pub entity: String,
+    pub entity_id: String,
+    pub action: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub changed_at: String,
+}
+
+/// Filters for `list`; omitted fields match every entry
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct AuditFilter {
+    pub entity: Option<String>,
+    pub entity_id: Option<String>,
+    /// Maximum number of entries (default 100)
+    pub limit: Option<u32>,
+}
+
+/// Log an edit to `entity_id`
+pub fn record(
+    conn: &Connection,
+    entity: &str,
+    entity_id: &str,
+    action: AuditAction,
+    before: Option<serde_json::Value>,
+    after: Option<serde_json::Value>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO audit_log (entity, entity_id, action, before_value, after_value)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            entity,
+            entity_id,
+            action.as_str(),
+            before.map(|value| value.to_string()),
+            after.map(|value| value.to_string())
+        ],
+    )?;
+    Ok(())
+}
+
+/// Entries matching `filter`, newest first
+pub fn list(conn: &Connection, filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, entity, entity_id, action, before_value, after_value, changed_at
+         FROM audit_log
+         WHERE (?1 IS NULL OR entity = ?1) AND (?2 IS NULL OR entity_id = ?2)
+         ORDER BY id DESC
+         LIMIT ?3",
+    )?;
+    let entries = stmt
+        .query_map(
+            params![
+                filter.entity,
+                filter.entity_id,
+                filter.limit.unwrap_or(DEFAULT_LIMIT)
+            ],
+            |row| {
+                let before: Option<String> = row.get(4)?;
+                let after: Option<String> = row.get(5)?;
+                Ok(AuditEntry {
+                    id: row.get(0)?,
+                    entity: row.get(1)?,
+                    entity_id: row.get(2)?,
+                    action: row.get(3)?,
+                    before: before.and_then(|json| serde_json::from_str(&json).ok()),
+                    after: after.and_then(|json| serde_json::from_str(&json).ok()),
+                    changed_at: row.get(6)?,
+                })
+            },
+        )?
+        .collect();
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_record_and_list() {
+        let temp = NamedTempFile::new().unwrap();
+        crate::database::init(temp.path()).unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+
+        record(
+            &conn,
+            "expansion",
+            "pyreborne",
+            AuditAction::Disable,
+            Some(json!({ "active": true })),
+            Some(json!({ "active": false })),
+        )
+        .unwrap();
+        record(&conn, "archetype", "rage", AuditAction::Delete, None, None).unwrap();
+
+        let entries = list(&conn, &AuditFilter::default()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].entity_id, "rage");
+        assert_eq!(entries[1].action, "disable");
+        assert_eq!(entries[1].after, Some(json!({ "active": false })));
+
+        let expansions = AuditFilter {
+            entity: Some("expansion".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(list(&conn, &expansions).unwrap().len(), 1);
+        let limited = AuditFilter {
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(list(&conn, &limited).unwrap().len(), 1);
+    }
+}
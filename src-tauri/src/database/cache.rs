@@ -0,0 +1,251 @@
+//! In-memory copy of the card data
+//!
+//! Scoring, card search and OCR matching read every card far more often
+//! than cards change, so the whole set is loaded once into a `CardSnapshot`
+//! and shared behind an `Arc`. Anything that edits cards, keywords,
+//! translations or expansions calls `CardCache::invalidate`, and the next
+//! reader loads a fresh snapshot.
+
+use crate::database::repository::{self, CardData};
+use crate::ocr::matcher::CardPoolEntry;
+use crate::scoring::pool::PoolScoreCache;
+use rusqlite::{Connection, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Every card, loaded at one point in time
+#[derive(Debug, Default)]
+pub struct CardSnapshot {
+    /// Ordered by clan, then name
+    cards: Vec<CardData>,
+    /// Whether each card's expansion is active, parallel to `cards`
+    active: Vec<bool>,
+    index: HashMap<String, usize>,
+    /// Attributes the OCR matcher filters on, ordered by name
+    pool: Vec<CardPoolEntry>,
+    /// (locale, card_id, name) for every translated card name
+    translated_names: Vec<(String, String, String)>,
+    /// Scores of the card pool last ranked against these cards
+    pool_scores: PoolScoreCache,
+}
+
+impl CardSnapshot {
+    /// Load every card and translated name from the database
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let mut stmt = conn.prepare(
+            "SELECT c.id, c.name, c.clan, c.card_type, c.rarity, c.cost,
+                    c.base_value, c.tempo_score, c.value_score,
+                    (SELECT json_group_array(k.keyword ORDER BY k.id)
+                     FROM card_keywords AS k WHERE k.card_id = c.id),
+                    c.description, COALESCE(c.expansion, 'base'), COALESCE(e.is_active, 1), c.size
+             FROM cards AS c
+             LEFT JOIN expansions AS e ON e.id = c.expansion
+             ORDER BY c.clan, c.name",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let keywords_json: String = row.get(9)?;
+            let card = CardData {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                clan: row.get(2)?,
+                card_type: row.get(3)?,
+                rarity: row.get(4)?,
+                cost: row.get(5)?,
+                size: row.get(13)?,
+                base_value: row.get(6)?,
+                tempo_score: row.get(7)?,
+                value_score: row.get(8)?,
+                keywords: serde_json::from_str(&keywords_json).unwrap_or_default(),
+                description: row.get(10)?,
+                expansion: row.get(11)?,
+            };
+            Ok((card, row.get::<_, bool>(12)?))
+        })?;
+
+        let mut snapshot = CardSnapshot::default();
+        for row in rows {
+            let (card, active) = row?;
+            snapshot.index.insert(card.id.clone(), snapshot.cards.len());
+            snapshot.pool.push(CardPoolEntry {
+                card_id: card.id.clone(),
+                card_name: card.name.clone(),
+                clan: card.clan.clone(),
+                rarity: card.rarity.clone(),
+                expansion: card.expansion.clone(),
+                expansion_active: active,
+            });
+            snapshot.cards.push(card);
+            snapshot.active.push(active);
+        }
+        snapshot.pool.sort_by(|a, b| a.card_name.cmp(&b.card_name));
+
+        let mut stmt = conn.prepare(
+            "SELECT locale, card_id, name FROM card_translations ORDER BY locale, card_id",
+        )?;
+        let names = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        for name in names {
+            snapshot.translated_names.push(name?);
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Every card, including those from inactive expansions
+    pub fn cards(&self) -> &[CardData] {
+        &self.cards
+    }
+
+    /// Cards from active expansions, ordered by clan, then name
+    pub fn active_cards(&self) -> impl Iterator<Item = &CardData> {
+        self.cards
+            .iter()
+            .zip(&self.active)
+            .filter(|(_, active)| **active)
+            .map(|(card, _)| card)
+    }
+
+    /// A card by ID, whether or not its expansion is active
+    pub fn get(&self, card_id: &str) -> Option<&CardData> {
+        self.index.get(card_id).map(|&i| &self.cards[i])
+    }
+
+    /// Cards in the order of `card_ids`; unknown IDs are skipped
+    pub fn get_many(&self, card_ids: &[String]) -> Vec<CardData> {
+        card_ids
+            .iter()
+            .filter_map(|id| self.get(id).cloned())
+            .collect()
+    }
+
+    /// Card attributes for the OCR matcher, ordered by name
+    pub fn pool(&self) -> &[CardPoolEntry] {
+        &self.pool
+    }
+
+    /// Scores of the card pool last ranked against these cards
+    pub fn pool_scores(&self) -> &PoolScoreCache {
+        &self.pool_scores
+    }
+
+    /// (card_id, name) pairs of every card name translated to `locale`
+    pub fn translated_names(&self, locale: &str) -> Vec<(String, String)> {
+        let Some(locale) = repository::normalize_locale(locale) else {
+            return Vec::new();
+        };
+        self.translated_names
+            .iter()
+            .filter(|(l, _, _)| *l == locale)
+            .map(|(_, card_id, name)| (card_id.clone(), name.clone()))
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct CacheSlot {
+    /// Bumped on every invalidation so a load racing one is not kept
+    generation: u64,
+    snapshot: Option<Arc<CardSnapshot>>,
+}
+
+/// Shared handle to the card snapshot
+///
+/// Managed by Tauri and handed to the OCR controller thread; clones share
+/// one cache.
+#[derive(Clone, Default)]
+pub struct CardCache {
+    slot: Arc<RwLock<CacheSlot>>,
+}
+
+impl CardCache {
+    /// The current snapshot, loading it from `conn` if needed
+    pub fn get(&self, conn: &Connection) -> Result<Arc<CardSnapshot>> {
+        let generation = {
+            let slot = self.slot.read().unwrap_or_else(|e| e.into_inner());
+            if let Some(snapshot) = &slot.snapshot {
+                return Ok(Arc::clone(snapshot));
+            }
+            slot.generation
+        };
+
+        let snapshot = Arc::new(CardSnapshot::load(conn)?);
+
+        let mut slot = self.slot.write().unwrap_or_else(|e| e.into_inner());
+        if slot.generation == generation {
+            slot.snapshot = Some(Arc::clone(&snapshot));
+        }
+        Ok(snapshot)
+    }
+
+    /// Drop the current snapshot after card data changed
+    pub fn invalidate(&self) {
+        let mut slot = self.slot.write().unwrap_or_else(|e| e.into_inner());
+        slot.generation += 1;
+        slot.snapshot = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn setup() -> (Connection, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        (conn, temp_file)
+    }
+
+    #[test]
+    fn test_snapshot_contents() {
+        let (conn, _temp) = setup();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+
+        let fel = snapshot.get("banished_fel").unwrap();
+        assert_eq!(fel.name, "Fel");
+        assert_eq!(fel.keywords, vec!["multistrike", "valor", "revenge"]);
+        assert_eq!(snapshot.pool().len(), snapshot.cards().len());
+        assert_eq!(snapshot.active_cards().count(), snapshot.cards().len());
+
+        let ids = vec![
+            "banished_talos".to_string(),
+            "missing".to_string(),
+            "banished_fel".to_string(),
+        ];
+        let cards: Vec<_> = snapshot.get_many(&ids).into_iter().map(|c| c.id).collect();
+        assert_eq!(cards, ["banished_talos", "banished_fel"]);
+
+        assert!(snapshot
+            .translated_names("de-DE")
+            .iter()
+            .any(|(_, name)| name == "Pestdoktor"));
+        assert!(snapshot.translated_names("en").is_empty());
+    }
+
+    #[test]
+    fn test_cache_reloads_after_invalidate() {
+        let (conn, _temp) = setup();
+        let cache = CardCache::default();
+
+        let first = cache.get(&conn).unwrap();
+        assert!(Arc::ptr_eq(&first, &cache.get(&conn).unwrap()));
+
+        conn.execute(
+            "UPDATE expansions SET is_active = 0 WHERE id = 'railforged'",
+            [],
+        )
+        .unwrap();
+        assert_eq!(
+            cache.get(&conn).unwrap().active_cards().count(),
+            first.cards().len()
+        );
+
+        cache.invalidate();
+        let reloaded = cache.get(&conn).unwrap();
+        assert!(!Arc::ptr_eq(&first, &reloaded));
+        assert!(reloaded.active_cards().count() < first.cards().len());
+        assert!(reloaded
+            .active_cards()
+            .all(|card| card.expansion != "railforged"));
+    }
+}
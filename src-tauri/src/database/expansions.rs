@@ -0,0 +1,104 @@
+//! Game expansions and whether their cards are in play
+//!
+//! Cards from an inactive expansion are left out of card listings and OCR
+//! matching, but can still be looked up directly so older history resolves.
+//! Cards whose expansion has no row in `expansions` count as active.
+
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+
+/// SQL condition keeping only cards from active expansions
+///
+/// Expects the cards table to be referenced as `cards`.
+pub const ACTIVE_CARDS_CONDITION: &str =
+    "COALESCE((SELECT e.is_active FROM expansions AS e WHERE e.id = cards.expansion), 1) = 1";
+
+/// An expansion with the number of cards it adds
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Expansion {
+    pub id: String,
+    pub name: String,
+    pub release_date: Option<String>,
+    pub is_active: bool,
+    pub description: Option<String>,
+    pub card_count: u32,
+}
+
+/// Every expansion, oldest first
+pub fn list(conn: &Connection) -> Result<Vec<Expansion>> {
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.name, e.release_date, COALESCE(e.is_active, 1), e.description,
+                (SELECT COUNT(*) FROM cards AS c WHERE c.expansion = e.id)
+         FROM expansions AS e
+         ORDER BY e.release_date, e.id",
+    )?;
+    let expansions = stmt
+        .query_map([], |row| {
+            Ok(Expansion {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                release_date: row.get(2)?,
+                is_active: row.get(3)?,
+                description: row.get(4)?,
+                card_count: row.get(5)?,
+            })
+        })?
+        .collect();
+    expansions
+}
+
+/// Enable or disable an expansion, returning whether it exists
+pub fn set_active(conn: &Connection, id: &str, active: bool) -> Result<bool> {
+    Ok(conn.execute(
+        "UPDATE expansions SET is_active = ?2 WHERE id = ?1",
+        params![id, active],
+    )? > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn setup() -> (Connection, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        (conn, temp_file)
+    }
+
+    fn active_card_count(conn: &Connection) -> u32 {
+        conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM cards WHERE {}",
+                ACTIVE_CARDS_CONDITION
+            ),
+            [],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_list_and_toggle() {
+        let (conn, _temp) = setup();
+
+        let expansions = list(&conn).unwrap();
+        assert_eq!(expansions[0].id, "base");
+        assert!(expansions.iter().all(|e| e.is_active));
+        let railforged = expansions.iter().find(|e| e.id == "railforged").unwrap();
+        assert!(railforged.card_count > 0);
+
+        let total = active_card_count(&conn);
+        assert!(set_active(&conn, "railforged", false).unwrap());
+        assert_eq!(active_card_count(&conn), total - railforged.card_count);
+        assert!(!list(&conn)
+            .unwrap()
+            .iter()
+            .any(|e| e.id == "railforged" && e.is_active));
+
+        assert!(set_active(&conn, "railforged", true).unwrap());
+        assert_eq!(active_card_count(&conn), total);
+        assert!(!set_active(&conn, "unknown", false).unwrap());
+    }
+}
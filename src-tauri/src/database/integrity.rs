@@ -0,0 +1,225 @@
+//! Database integrity checks
+//!
+//! Seeding runs with foreign keys disabled, so rows referencing cards that
+//! were never seeded are kept silently. `check` finds them, along with card
+//! keyword lists that are not valid JSON. `fix` removes orphaned seed rows
+//! and resets malformed keywords; the player's own history is only ever
+//! reported, since a later card update may add the missing cards.
+
+use rusqlite::{Connection, Result};
+use serde::{Deserialize, Serialize};
+
+/// Kind of integrity problem
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// A synergy referencing a missing card
+    OrphanedSynergy,
+    /// A champion override for a missing card
+    OrphanedOverride,
+    /// A `deck_history` row for a missing card
+    DanglingHistory,
+    /// A `draft_offers` row for a missing card
+    DanglingOffer,
+    /// A card whose keywords are not a JSON array of strings
+    MalformedKeywords,
+}
+
+impl IssueKind {
+    /// Whether `fix` repairs this kind of issue
+    pub fn is_fixable(self) -> bool {
+        !matches!(self, IssueKind::DanglingHistory | IssueKind::DanglingOffer)
+    }
+}
+
+/// One row failing an integrity check
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IntegrityIssue {
+    pub kind: IssueKind,
+    pub table: String,
+    pub row_id: String,
+    pub detail: String,
+}
+
+fn collect(
+    conn: &Connection,
+    kind: IssueKind,
+    table: &str,
+    sql: &str,
+    issues: &mut Vec<IntegrityIssue>,
+) -> Result<()> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok(IntegrityIssue {
+            kind,
+            table: table.to_string(),
+            row_id: row.get::<_, i64>(0)?.to_string(),
+            detail: row.get(1)?,
+        })
+    })?;
+    for issue in rows {
+        issues.push(issue?);
+    }
+    Ok(())
+}
+
+fn is_valid_keywords(keywords: Option<&str>) -> bool {
+    keywords.is_some_and(|json| serde_json::from_str::<Vec<String>>(json).is_ok())
+}
+
+/// Find every integrity problem in the database
+pub fn check(conn: &Connection) -> Result<Vec<IntegrityIssue>> {
+    let mut issues = Vec::new();
+
+    collect(
+        conn,
+        IssueKind::OrphanedSynergy,
+        "synergies",
+        "SELECT s.id,
+                'references missing card ' ||
+                CASE
+                    WHEN a.id IS NULL AND b.id IS NULL THEN s.card_a_id || ' and ' || s.card_b_id
+                    WHEN a.id IS NULL THEN s.card_a_id
+                    ELSE s.card_b_id
+                END
+         FROM synergies AS s
+         LEFT JOIN cards AS a ON a.id = s.card_a_id
+         LEFT JOIN cards AS b ON b.id = s.card_b_id
+         WHERE a.id IS NULL OR b.id IS NULL
+         ORDER BY s.id",
+        &mut issues,
+    )?;
+    collect(
+        conn,
+        IssueKind::OrphanedOverride,
+        "champion_overrides",
+        "SELECT o.id, o.champion || ' override references missing card ' || o.card_id
+         FROM champion_overrides AS o
+         LEFT JOIN cards AS c ON c.id = o.card_id
+         WHERE c.id IS NULL
+         ORDER BY o.id",
+        &mut issues,
+    )?;
+    collect(
+        conn,
+        IssueKind::DanglingHistory,
+        "deck_history",
+        "SELECT h.id, 'run ' || h.run_id || ' references missing card ' || h.card_id
+         FROM deck_history AS h
+         LEFT JOIN cards AS c ON c.id = h.card_id
+         WHERE c.id IS NULL
+         ORDER BY h.id",
+        &mut issues,
+    )?;
+    collect(
+        conn,
+        IssueKind::DanglingOffer,
+        "draft_offers",
+        "SELECT o.id, 'run ' || o.run_id || ' references missing card ' || o.card_id
+         FROM draft_offers AS o
+         LEFT JOIN cards AS c ON c.id = o.card_id
+         WHERE c.id IS NULL
+         ORDER BY o.id",
+        &mut issues,
+    )?;
+
+    let mut stmt = conn.prepare("SELECT id, keywords FROM cards ORDER BY id")?;
+    let cards = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+    })?;
+    for card in cards {
+        let (id, keywords) = card?;
+        if !is_valid_keywords(keywords.as_deref()) {
+            issues.push(IntegrityIssue {
+                kind: IssueKind::MalformedKeywords,
+                table: "cards".to_string(),
+                detail: format!("keywords are not a JSON string array: {:?}", keywords),
+                row_id: id,
+            });
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Repair the fixable issues in `issues`, returning how many were fixed
+///
+/// Orphaned synergies and overrides are deleted; malformed keywords are
+/// reset to an empty list.
+pub fn fix(conn: &Connection, issues: &[IntegrityIssue]) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut fixed = 0;
+
+    for issue in issues {
+        fixed += match issue.kind {
+            IssueKind::OrphanedSynergy => {
+                tx.execute("DELETE FROM synergies WHERE id = ?1", [&issue.row_id])?
+            }
+            IssueKind::OrphanedOverride => tx.execute(
+                "DELETE FROM champion_overrides WHERE id = ?1",
+                [&issue.row_id],
+            )?,
+            IssueKind::MalformedKeywords => tx.execute(
+                "UPDATE cards SET keywords = '[]', updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                [&issue.row_id],
+            )?,
+            IssueKind::DanglingHistory | IssueKind::DanglingOffer => 0,
+        };
+    }
+
+    tx.commit()?;
+    Ok(fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn setup() -> (Connection, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        (conn, temp_file)
+    }
+
+    fn count(issues: &[IntegrityIssue], kind: IssueKind) -> usize {
+        issues.iter().filter(|i| i.kind == kind).count()
+    }
+
+    #[test]
+    fn test_seed_data_orphans_are_reported() {
+        let (conn, _temp) = setup();
+
+        let issues = check(&conn).unwrap();
+        assert!(issues
+            .iter()
+            .any(|i| i.kind == IssueKind::OrphanedSynergy
+                && i.detail.contains("railforged_equipment")));
+        assert_eq!(count(&issues, IssueKind::MalformedKeywords), 0);
+    }
+
+    #[test]
+    fn test_fix_repairs_seed_rows_but_keeps_history() {
+        let (conn, _temp) = setup();
+        conn.execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             UPDATE cards SET keywords = 'Rage, Multistrike' WHERE id = 'banished_cleave';
+             INSERT INTO deck_history (run_id, card_id, ring_number, draft_order, champion, covenant)
+             VALUES ('run1', 'retired_card', 1, 1, 'Fel', 1);",
+        )
+        .unwrap();
+
+        let issues = check(&conn).unwrap();
+        assert_eq!(count(&issues, IssueKind::MalformedKeywords), 1);
+        assert_eq!(count(&issues, IssueKind::DanglingHistory), 1);
+
+        let fixable = issues.iter().filter(|i| i.kind.is_fixable()).count();
+        assert_eq!(fix(&conn, &issues).unwrap(), fixable);
+
+        let remaining = check(&conn).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].kind, IssueKind::DanglingHistory);
+        assert_eq!(remaining[0].table, "deck_history");
+    }
+}
@@ -1,7 +1,7 @@
-use crate::database::schema;
+use crate::database::{repository, schema};
 use rusqlite::{Connection, Result};
 
-const CURRENT_VERSION: i32 = 1;
+pub const CURRENT_VERSION: i32 = 26;
 
 pub fn run_all(conn: &Connection) -> Result<()> {
     // Create migrations table if not exists
@@ -14,13 +14,7 @@ pub fn run_all(conn: &Connection) -> Result<()> {
     )?;
 
     // Get current version
-    let current: i32 = conn
-        .query_row(
-            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or(0);
+    let current = schema_version(conn).unwrap_or(0);
 
     // Run pending migrations
     if current < 1 {
@@ -28,6 +22,131 @@ pub fn run_all(conn: &Connection) -> Result<()> {
         mark_applied(conn, 1)?;
     }
 
+    if current < 2 {
+        migration_002_ocr_detections(conn)?;
+        mark_applied(conn, 2)?;
+    }
+
+    if current < 3 {
+        migration_003_ocr_detection_archives(conn)?;
+        mark_applied(conn, 3)?;
+    }
+
+    if current < 4 {
+        migration_004_ocr_corrections(conn)?;
+        mark_applied(conn, 4)?;
+    }
+
+    if current < 5 {
+        migration_005_card_aliases(conn)?;
+        mark_applied(conn, 5)?;
+    }
+
+    if current < 6 {
+        migration_006_draft_offers(conn)?;
+        mark_applied(conn, 6)?;
+    }
+
+    if current < 7 {
+        migration_007_ocr_confidence_calibration(conn)?;
+        mark_applied(conn, 7)?;
+    }
+
+    if current < 8 {
+        migration_008_app_settings(conn)?;
+        mark_applied(conn, 8)?;
+    }
+
+    if current < 9 {
+        migration_009_runs(conn)?;
+        mark_applied(conn, 9)?;
+    }
+
+    if current < 10 {
+        migration_010_card_keywords(conn)?;
+        mark_applied(conn, 10)?;
+    }
+
+    if current < 11 {
+        migration_011_card_translations(conn)?;
+        mark_applied(conn, 11)?;
+    }
+
+    if current < 12 {
+        migration_012_card_notes(conn)?;
+        mark_applied(conn, 12)?;
+    }
+
+    if current < 13 {
+        migration_013_favorites(conn)?;
+        mark_applied(conn, 13)?;
+    }
+
+    if current < 14 {
+        migration_014_archetypes(conn)?;
+        mark_applied(conn, 14)?;
+    }
+
+    if current < 15 {
+        migration_015_context_rules(conn)?;
+        mark_applied(conn, 15)?;
+    }
+
+    if current < 16 {
+        migration_016_champion_paths(conn)?;
+        mark_applied(conn, 16)?;
+    }
+
+    if current < 17 {
+        migration_017_card_sizes(conn)?;
+        mark_applied(conn, 17)?;
+    }
+
+    if current < 18 {
+        migration_018_archetype_curves(conn)?;
+        mark_applied(conn, 18)?;
+    }
+
+    if current < 19 {
+        migration_019_bosses(conn)?;
+        mark_applied(conn, 19)?;
+    }
+
+    if current < 20 {
+        migration_020_seed_endless_modifiers(conn)?;
+        mark_applied(conn, 20)?;
+    }
+
+    if current < 21 {
+        migration_021_offer_slots(conn)?;
+        mark_applied(conn, 21)?;
+    }
+
+    if current < 22 {
+        migration_022_analytics_summaries(conn)?;
+        mark_applied(conn, 22)?;
+    }
+
+    if current < 23 {
+        migration_023_community_stats(conn)?;
+        mark_applied(conn, 23)?;
+    }
+
+    if current < 24 {
+        migration_024_upload_queue(conn)?;
+        mark_applied(conn, 24)?;
+    }
+
+    if current < 25 {
+        migration_025_run_archival(conn)?;
+        mark_applied(conn, 25)?;
+    }
+
+    if current < 26 {
+        migration_026_audit_log(conn)?;
+        mark_applied(conn, 26)?;
+    }
+
     Ok(())
 }
 
@@ -39,6 +158,15 @@ fn mark_applied(conn: &Connection, version: i32) -> Result<()> {
     Ok(())
 }
 
+/// Highest migration applied to a database, 0 for a new one
+pub fn schema_version(conn: &Connection) -> Result<i32> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )
+}
+
 fn migration_001_initial_schema(conn: &Connection) -> Result<()> {
     conn.execute(schema::CREATE_CARDS_TABLE, [])?;
     conn.execute(schema::CREATE_SYNERGIES_TABLE, [])?;
@@ -48,3 +176,225 @@ fn migration_001_initial_schema(conn: &Connection) -> Result<()> {
     conn.execute(schema::CREATE_EXPANSIONS_TABLE, [])?;
     Ok(())
 }
+
+fn migration_002_ocr_detections(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_OCR_DETECTIONS_TABLE)?;
+    Ok(())
+}
+
+fn migration_003_ocr_detection_archives(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_OCR_DETECTION_ARCHIVES_TABLE)?;
+    Ok(())
+}
+
+fn migration_004_ocr_corrections(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_OCR_CORRECTIONS_TABLE)?;
+    Ok(())
+}
+
+fn migration_005_card_aliases(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_CARD_ALIASES_TABLE)?;
+    Ok(())
+}
+
+fn migration_006_draft_offers(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_DRAFT_OFFERS_TABLE)?;
+    Ok(())
+}
+
+fn migration_007_ocr_confidence_calibration(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_OCR_CONFIDENCE_CALIBRATION_TABLE)?;
+    Ok(())
+}
+
+fn migration_008_app_settings(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_APP_SETTINGS_TABLE)?;
+    Ok(())
+}
+
+fn migration_009_runs(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_RUNS_TABLE)?;
+
+    // Backfill runs logged before the table existed
+    conn.execute(
+        "INSERT OR IGNORE INTO runs (run_id, champion, covenant, did_win, final_ring, started_at)
+         SELECT run_id, MAX(champion), MAX(covenant), MAX(did_win), MAX(ring_number), MIN(created_at)
+         FROM deck_history
+         GROUP BY run_id",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_010_card_keywords(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_CARD_KEYWORDS_TABLE)?;
+
+    // Backfill from the JSON column, keeping each card's keyword order
+    conn.execute(
+        "INSERT OR IGNORE INTO card_keywords (card_id, keyword)
+         SELECT c.id, j.value
+         FROM cards AS c, json_each(c.keywords) AS j
+         WHERE json_valid(c.keywords) AND j.type = 'text'
+         ORDER BY c.id, j.key",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migration_011_card_translations(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_CARD_TRANSLATIONS_TABLE)?;
+    Ok(())
+}
+
+fn migration_012_card_notes(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_CARD_NOTES_TABLE)?;
+    Ok(())
+}
+
+fn migration_013_favorites(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_FAVORITES_TABLE)?;
+    Ok(())
+}
+
+fn migration_014_archetypes(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_ARCHETYPES_TABLE)?;
+    repository::seed_archetypes(conn)?;
+    Ok(())
+}
+
+/// Rule expressions for the condition names context modifiers used to be
+/// matched on
+///
+/// The covenant, ring and pyregel conditions never matched under the old
+/// evaluator, so they keep doing nothing rather than starting to fire.
+const LEGACY_CONDITIONS: &[(&str, &str)] = &[
+    ("missing_frontline", "count(keyword:frontline|tank) == 0"),
+    ("missing_backline_clear", "count(keyword:sweep|explosive|advance) == 0"),
+    ("has_reform_synergy", "count(keyword:reform) > 0"),
+    ("has_consume_synergy", "count(keyword:consume) > 0"),
+    ("has_forge_synergy", "count(keyword:forge) > 0"),
+    ("has_smelt_synergy", "count(keyword:smelt) > 0"),
+    ("deck_size_over_20", "deck.size > 20"),
+    ("covenant_high", "false"),
+    ("ring_early", "false"),
+    ("ring_late", "false"),
+    ("duplicate_common", "count(card, rarity:Common) >= 2"),
+    ("no_pyregel", "false"),
+    ("low_gold", "false"),
+];
+
+fn migration_015_context_rules(conn: &Connection) -> Result<()> {
+    for (name, rule) in LEGACY_CONDITIONS {
+        conn.execute(
+            "UPDATE context_modifiers SET condition = ?2 WHERE condition = ?1",
+            [name, rule],
+        )?;
+    }
+    Ok(())
+}
+
+fn migration_016_champion_paths(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_CHAMPION_PATHS_TABLE)?;
+    repository::seed_champion_paths(conn)?;
+    Ok(())
+}
+
+fn migration_017_card_sizes(conn: &Connection) -> Result<()> {
+    let has_size: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('cards') WHERE name = 'size'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_size {
+        conn.execute("ALTER TABLE cards ADD COLUMN size INTEGER", [])?;
+    }
+    repository::backfill_card_sizes(conn)?;
+    repository::seed_capacity_modifiers(conn)?;
+    Ok(())
+}
+
+fn migration_018_archetype_curves(conn: &Connection) -> Result<()> {
+    let has_curve: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('archetypes') WHERE name = 'ideal_curve'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_curve {
+        conn.execute("ALTER TABLE archetypes ADD COLUMN ideal_curve TEXT", [])?;
+    }
+    repository::seed_archetype_curves(conn)?;
+    Ok(())
+}
+
+fn migration_019_bosses(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_BOSSES_TABLE)?;
+    repository::seed_bosses(conn)?;
+    Ok(())
+}
+
+fn migration_020_seed_endless_modifiers(conn: &Connection) -> Result<()> {
+    repository::seed_endless_modifiers(conn)
+}
+
+fn migration_021_offer_slots(conn: &Connection) -> Result<()> {
+    for column in ["slot", "score"] {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('draft_offers') WHERE name = ?1",
+            [column],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            conn.execute(
+                &format!("ALTER TABLE draft_offers ADD COLUMN {} INTEGER", column),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn migration_022_analytics_summaries(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_ANALYTICS_TABLES)?;
+
+    // Queue existing history so the first refresh summarizes all of it
+    conn.execute_batch(
+        "INSERT OR IGNORE INTO analytics_stale_cards (card_id)
+         SELECT DISTINCT card_id FROM deck_history;
+         INSERT INTO analytics_stale_champions (champion, champion_path)
+         SELECT DISTINCT champion, champion_path FROM runs;",
+    )?;
+    Ok(())
+}
+
+fn migration_023_community_stats(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_COMMUNITY_STATS_TABLE)?;
+    Ok(())
+}
+
+fn migration_024_upload_queue(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_UPLOAD_QUEUE_TABLE)?;
+    Ok(())
+}
+
+fn migration_025_run_archival(conn: &Connection) -> Result<()> {
+    for column in ["archived_at", "deleted_at"] {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('runs') WHERE name = ?1",
+            [column],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            conn.execute(
+                &format!("ALTER TABLE runs ADD COLUMN {} TIMESTAMP", column),
+                [],
+            )?;
+        }
+    }
+    conn.execute_batch(schema::CREATE_LIVE_RUN_VIEWS)?;
+    Ok(())
+}
+
+fn migration_026_audit_log(conn: &Connection) -> Result<()> {
+    conn.execute_batch(schema::CREATE_AUDIT_LOG_TABLE)?;
+    Ok(())
+}
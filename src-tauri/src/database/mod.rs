@@ -1,9 +1,19 @@
 use rusqlite::{Connection, Result};
+use serde::Serialize;
 use std::path::Path;
+use std::time::Duration;
 
+pub mod access;
+pub mod analytics;
+pub mod annotations;
+pub mod audit;
+pub mod cache;
+pub mod expansions;
+pub mod integrity;
 pub mod migrations;
 pub mod repository;
 pub mod schema;
+pub mod settings;
 
 pub struct DatabaseState {
     pub db_path: std::path::PathBuf,
@@ -15,16 +25,70 @@ impl DatabaseState {
     }
 }
 
-pub fn init(db_path: &Path) -> Result<()> {
+/// Database file name in the app data folder
+pub const DATABASE_FILE: &str = "mt2_draft.db";
+
+/// How long a connection waits for another writer before failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Open the database with `configure_connection` applied
+pub fn open(db_path: &Path) -> Result<Connection> {
     let conn = Connection::open(db_path)?;
+    configure_connection(&conn)?;
+    Ok(conn)
+}
+
+/// Set the pragmas every connection uses
+///
+/// OCR logging, scoring and the background tasks each open their own
+/// connection. WAL lets them read while another writes, and the busy
+/// timeout makes a writer wait for the lock instead of failing with
+/// "database is locked".
+pub fn configure_connection(conn: &Connection) -> Result<()> {
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    // In-memory databases report `memory` and stay that way
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    conn.pragma_update(None, "foreign_keys", "ON")?;
+    Ok(())
+}
+
+/// Event carrying `LoadProgress` to the frontend
+pub const PROGRESS_EVENT: &str = "load-progress";
+
+/// Progress through seeding or a large import
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LoadProgress {
+    /// What is being loaded, such as `cards` or `history`
+    pub stage: String,
+    pub done: u32,
+    pub total: u32,
+}
+
+pub fn init(db_path: &Path) -> Result<()> {
+    init_with_progress(db_path, &mut |_| {})
+}
+
+/// `init`, telling `on_progress` how seeding a new database is going
+pub fn init_with_progress(
+    db_path: &Path,
+    on_progress: &mut dyn FnMut(LoadProgress),
+) -> Result<()> {
+    let conn = open(db_path)?;
     
     // Run migrations
     migrations::run_all(&conn)?;
     
     // Seed data if needed
     if is_empty(&conn)? {
-        repository::seed_data(&conn)?;
+        repository::seed_data(&conn, on_progress)?;
     }
+
+    // Aliases are seeded idempotently so existing databases pick up new entries
+    let tx = conn.unchecked_transaction()?;
+    repository::seed_card_aliases(&tx)?;
+    repository::seed_card_translations(&tx)?;
+    tx.commit()?;
     
     Ok(())
 }
@@ -58,6 +122,27 @@ mod tests {
             "context_modifiers",
             "champion_overrides",
             "deck_history",
+            "ocr_detections",
+            "ocr_detection_archives",
+            "ocr_corrections",
+            "ocr_aliases",
+            "card_aliases",
+            "draft_offers",
+            "app_settings",
+            "runs",
+            "card_keywords",
+            "card_translations",
+            "card_notes",
+            "card_tags",
+            "favorites",
+            "archetypes",
+            "champion_paths",
+            "bosses",
+            "card_stats_summary",
+            "champion_stats_summary",
+            "community_stats",
+            "upload_queue",
+            "audit_log",
         ];
         
         for table in &tables {
@@ -71,4 +156,41 @@ mod tests {
             assert_eq!(count, 1, "Table {} should exist", table);
         }
     }
+
+    #[test]
+    fn test_seeding_reports_progress() {
+        let temp_file = NamedTempFile::new().unwrap();
+
+        let mut stages = Vec::new();
+        init_with_progress(temp_file.path(), &mut |progress| stages.push(progress)).unwrap();
+        assert_eq!(stages.len(), 5);
+        assert_eq!(stages[1].stage, "cards");
+        assert_eq!((stages[4].done, stages[4].total), (5, 5));
+
+        // An existing database isn't seeded again
+        let mut reported = 0;
+        init_with_progress(temp_file.path(), &mut |_| reported += 1).unwrap();
+        assert_eq!(reported, 0);
+    }
+
+    #[test]
+    fn test_open_configures_connection() {
+        let temp_file = NamedTempFile::new().unwrap();
+        init(temp_file.path()).unwrap();
+
+        let conn = open(temp_file.path()).unwrap();
+        let journal_mode: String = conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+        let foreign_keys: bool = conn
+            .query_row("PRAGMA foreign_keys", [], |row| row.get(0))
+            .unwrap();
+        assert!(foreign_keys);
+        // NORMAL
+        let synchronous: i64 = conn
+            .query_row("PRAGMA synchronous", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(synchronous, 1);
+    }
 }
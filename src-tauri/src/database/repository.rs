@@ -1,23 +1,456 @@
-﻿use rusqlite::{Connection, Result};
+﻿use super::expansions::ACTIVE_CARDS_CONDITION;
+use super::LoadProgress;
+use crate::scoring::context::ContextModifier;
+use crate::scoring::rules::Rule;
+use crate::scoring::synergies::Synergy;
+use rusqlite::{Connection, OptionalExtension, Result, Row};
 use serde_json;
+use std::collections::{HashMap, HashSet};
+
+/// A seeding step that fills one table
+type SeedStep = fn(&Connection) -> Result<()>;
+
+/// Seed the card data into an empty database
+///
+/// Everything is written in one transaction with prepared statements, and
+/// `on_progress` is told as each table is done.
+pub fn seed_data(conn: &Connection, on_progress: &mut dyn FnMut(LoadProgress)) -> Result<()> {
+    let steps: [(&str, SeedStep); 5] = [
+        ("expansions", seed_expansions),
+        ("cards", seed_cards),
+        ("synergies", seed_synergies),
+        ("context_modifiers", seed_context_modifiers),
+        ("champion_overrides", seed_champion_overrides),
+    ];
 
-pub fn seed_data(conn: &Connection) -> Result<()> {
     // Disable foreign keys temporarily to allow seeding data
-    // Some synergies and overrides reference cards that may not exist yet
-    conn.execute( "PRAGMA foreign_keys = OFF", [])?;
-    
-    seed_expansions(conn)?;
-    seed_cards(conn)?;
-    seed_synergies(conn)?;
-    seed_context_modifiers(conn)?;
-    seed_champion_overrides(conn)?;
-    
+    // Some synergies and overrides reference cards that may not exist yet.
+    // The pragma does nothing inside a transaction, so it is set around it.
+    conn.execute("PRAGMA foreign_keys = OFF", [])?;
+
+    let seeded = conn.unchecked_transaction().and_then(|tx| {
+        for (i, (stage, seed)) in steps.iter().enumerate() {
+            seed(&tx)?;
+            on_progress(LoadProgress {
+                stage: stage.to_string(),
+                done: i as u32 + 1,
+                total: steps.len() as u32,
+            });
+        }
+        tx.commit()
+    });
+
     // Re-enable foreign keys
-    conn.execute( "PRAGMA foreign_keys = on ", [])?;
-    
+    conn.execute("PRAGMA foreign_keys = ON", [])?;
+    seeded
+}
+
+/// Seed alternate card names used by OCR matching
+///
+/// Covers nicknames, strings Tesseract commonly garbles on the game's font,
+/// and localized names. Uses `INSERT OR IGNORE`, so it is safe to run on
+/// every startup; aliases for cards not in the database are skipped.
+pub fn seed_card_aliases(conn: &Connection) -> Result<()> {
+    let aliases = vec![
+        // Nicknames
+        ("underlegion_bolete", "Bolete", "nickname", None),
+        ("underlegion_funguy_in_a_suit", "Funguy", "nickname", None),
+        ("underlegion_madame_lionsmane", "Lionsmane", "nickname", None),
+        ("melting_remnant_lady_of_the_house", "Lady of House", "nickname", None),
+        ("pyreborne_fanning_the_flame", "Fanning Flame", "nickname", None),
+        // Common OCR garbles (l/1/I, rn/m, O/0 confusions)
+        ("banished_fel", "Fe1", "ocr_garble", None),
+        ("banished_fel", "FeI", "ocr_garble", None),
+        ("banished_talos", "Ta1os", "ocr_garble", None),
+        ("banished_talos", "Tal0s", "ocr_garble", None),
+        ("luna_coven_ekka", "Ekk a", "ocr_garble", None),
+        ("underlegion_morel_mistress", "MoreI Mistress", "ocr_garble", None),
+        ("underlegion_morel_mistress", "Morel Mistrcss", "ocr_garble", None),
+        ("pyreborne_lord_fenix", "Lord Feriix", "ocr_garble", None),
+        ("pyreborne_lady_gilda", "Lady GiIda", "ocr_garble", None),
+        ("melting_remnant_waxen_spike", "Waxen Spil<e", "ocr_garble", None),
+        ("hellhorned_hornbreaker_prince", "Hombreaker Prince", "ocr_garble", None),
+        ("railforged_knuckler_steward", "Knuck1er Steward", "ocr_garble", None),
+        // Localized names
+        ("pyreborne_lord_fenix", "Seigneur Fenix", "localized", Some("fr")),
+        ("pyreborne_lord_fenix", "Fürst Fenix", "localized", Some("de")),
+        ("lazarus_league_plague_doctor", "Médecin de peste", "localized", Some("fr")),
+        ("lazarus_league_plague_doctor", "Pestdoktor", "localized", Some("de")),
+        ("lazarus_league_potion_kit", "Trousse de potions", "localized", Some("fr")),
+    ];
+
+    let mut stmt = conn.prepare(
+        "INSERT OR IGNORE INTO card_aliases (card_id, alias, alias_type, language)
+         SELECT ?1, ?2, ?3, ?4 WHERE EXISTS (SELECT 1 FROM cards WHERE id = ?1)",
+    )?;
+    for (card_id, alias, alias_type, language) in aliases {
+        stmt.execute(rusqlite::params![card_id, alias, alias_type, language])?;
+    }
+    Ok(())
+}
+
+/// Seed non-English card names
+///
+/// Uses `INSERT OR IGNORE` like `seed_card_aliases`, so it runs on every
+/// startup and never overwrites edited rows. Translations for cards not in
+/// the database are skipped.
+pub fn seed_card_translations(conn: &Connection) -> Result<()> {
+    let translations = vec![
+        ("pyreborne_lord_fenix", "fr", "Seigneur Fenix"),
+        ("pyreborne_lord_fenix", "de", "Fürst Fenix"),
+        ("lazarus_league_plague_doctor", "fr", "Médecin de peste"),
+        ("lazarus_league_plague_doctor", "de", "Pestdoktor"),
+        ("lazarus_league_potion_kit", "fr", "Trousse de potions"),
+    ];
+
+    let mut stmt = conn.prepare(
+        "INSERT OR IGNORE INTO card_translations (card_id, locale, name)
+         SELECT ?1, ?2, ?3 WHERE EXISTS (SELECT 1 FROM cards WHERE id = ?1)",
+    )?;
+    for (card_id, locale, name) in translations {
+        stmt.execute([card_id, locale, name])?;
+    }
+    Ok(())
+}
+
+/// A card's name and description in one locale
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardTranslation {
+    pub card_id: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Normalize a locale such as `de-DE` or `fr_FR` to the language code
+/// translations are stored under
+///
+/// Returns `None` for English, whose names are the card names themselves.
+pub fn normalize_locale(locale: &str) -> Option<String> {
+    let language = locale
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    (!language.is_empty() && language != "en").then_some(language)
+}
+
+/// Load every translation for a locale
+pub fn load_card_translations(conn: &Connection, locale: &str) -> Result<Vec<CardTranslation>> {
+    let Some(locale) = normalize_locale(locale) else {
+        return Ok(Vec::new());
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT card_id, name, description FROM card_translations
+         WHERE locale = ?1 ORDER BY card_id",
+    )?;
+    let translations = stmt
+        .query_map([locale], |row| {
+            Ok(CardTranslation {
+                card_id: row.get(0)?,
+                name: row.get(1)?,
+                description: row.get(2)?,
+            })
+        })?
+        .collect();
+
+    translations
+}
+
+/// Query selecting every card column, in the order `row_to_card_data` reads
+const SELECT_CARD_SQL: &str = r#"
+    SELECT
+        id, name, clan, card_type, rarity, cost,
+        base_value, tempo_score, value_score,
+        (SELECT json_group_array(k.keyword ORDER BY k.id) FROM card_keywords AS k WHERE k.card_id = cards.id),
+        description, expansion, size
+    FROM cards
+"#;
+
+/// Map a `SELECT_CARD_SQL` row to a card
+///
+/// Keywords come from `card_keywords`, aggregated into a JSON array.
+fn row_to_card_data(row: &Row) -> Result<CardData> {
+    let keywords_json: String = row.get(9)?;
+
+    Ok(CardData {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        clan: row.get(2)?,
+        card_type: row.get(3)?,
+        rarity: row.get(4)?,
+        cost: row.get(5)?,
+        size: row.get(12)?,
+        base_value: row.get(6)?,
+        tempo_score: row.get(7)?,
+        value_score: row.get(8)?,
+        keywords: serde_json::from_str(&keywords_json).unwrap_or_default(),
+        description: row.get(10)?,
+        expansion: row.get(11)?,
+    })
+}
+
+/// Whether a card with this ID exists
+pub fn card_exists(conn: &Connection, card_id: &str) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM cards WHERE id = ?1)",
+        [card_id],
+        |row| row.get(0),
+    )
+}
+
+/// Find a card by exact name
+///
+/// With a `locale`, the card's translated name also matches; English names
+/// take precedence over translated ones.
+pub fn get_card_by_name(
+    conn: &Connection,
+    name: &str,
+    locale: Option<&str>,
+) -> Result<Option<CardData>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE name = ?1
+            OR id IN (SELECT card_id FROM card_translations WHERE locale = ?2 AND name = ?1)
+         ORDER BY name = ?1 DESC
+         LIMIT 1",
+        SELECT_CARD_SQL
+    ))?;
+    let locale = locale.and_then(normalize_locale);
+
+    stmt.query_row(rusqlite::params![name, locale], row_to_card_data)
+        .optional()
+}
+
+/// Cards from active expansions with a keyword (case-insensitive), by name
+pub fn get_cards_by_keyword(conn: &Connection, keyword: &str) -> Result<Vec<CardData>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE id IN (SELECT card_id FROM card_keywords WHERE keyword = ?1 COLLATE NOCASE)
+           AND {}
+         ORDER BY name",
+        SELECT_CARD_SQL, ACTIVE_CARDS_CONDITION
+    ))?;
+    let cards = stmt.query_map([keyword], row_to_card_data)?.collect();
+
+    cards
+}
+
+/// Cards the player tagged with `tag`, by name
+pub fn get_cards_by_tag(conn: &Connection, tag: &str) -> Result<Vec<CardData>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE id IN (SELECT card_id FROM card_tags WHERE tag = ?1) ORDER BY name",
+        SELECT_CARD_SQL
+    ))?;
+    let cards = stmt.query_map([tag], row_to_card_data)?.collect();
+
+    cards
+}
+
+/// The player's favorite cards, by name
+pub fn get_favorite_cards(conn: &Connection) -> Result<Vec<CardData>> {
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE id IN (SELECT card_id FROM favorites) ORDER BY name",
+        SELECT_CARD_SQL
+    ))?;
+    let cards = stmt.query_map([], row_to_card_data)?.collect();
+
+    cards
+}
+
+fn row_to_synergy(row: &Row) -> Result<Synergy> {
+    Ok(Synergy {
+        card_a_id: row.get(0)?,
+        card_b_id: row.get(1)?,
+        synergy_type: row.get(2)?,
+        weight: row.get(3)?,
+        description: row.get(4)?,
+        bidirectional: row.get(5)?,
+    })
+}
+
+/// Synergies that apply to a card: its own, bidirectional ones naming it,
+/// and wildcards
+pub fn get_synergies_for_card(conn: &Connection, card_id: &str) -> Result<Vec<Synergy>> {
+    let mut stmt = conn.prepare(
+        "SELECT card_a_id, card_b_id, synergy_type, weight, description, bidirectional
+         FROM synergies
+         WHERE card_a_id = ?1
+            OR (card_b_id = ?1 AND bidirectional = 1)
+            OR card_b_id = '*'",
+    )?;
+    let synergies = stmt.query_map([card_id], row_to_synergy)?.collect();
+
+    synergies
+}
+
+/// Synergy descriptions per card, for the described synergies between two
+/// of `card_ids`
+///
+/// A description is listed on the synergy's second card, and on its first
+/// as well when the synergy works both ways.
+pub fn get_synergy_notes(
+    conn: &Connection,
+    card_ids: &HashSet<&str>,
+) -> Result<HashMap<String, Vec<String>>> {
+    let mut stmt = conn.prepare(
+        "SELECT card_a_id, card_b_id, description, bidirectional
+         FROM synergies
+         WHERE description IS NOT NULL AND card_a_id != card_b_id
+         ORDER BY id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, bool>(3)?,
+        ))
+    })?;
+
+    let mut notes: HashMap<String, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (card_a, card_b, description, bidirectional) = row?;
+        if !card_ids.contains(card_a.as_str()) || !card_ids.contains(card_b.as_str()) {
+            continue;
+        }
+        notes
+            .entry(card_b.clone())
+            .or_default()
+            .push(description.clone());
+        if bidirectional {
+            notes.entry(card_a).or_default().push(description);
+        }
+    }
+    Ok(notes)
+}
+
+/// Every synergy row, for scoring many cards in memory
+pub fn get_all_synergies(conn: &Connection) -> Result<Vec<Synergy>> {
+    let mut stmt = conn.prepare(
+        "SELECT card_a_id, card_b_id, synergy_type, weight, description, bidirectional
+         FROM synergies",
+    )?;
+    let synergies = stmt.query_map([], row_to_synergy)?.collect();
+
+    synergies
+}
+
+/// Active context modifiers
+///
+/// A condition that doesn't parse disables its modifier rather than failing
+/// scoring.
+pub fn get_active_context_modifiers(conn: &Connection) -> Result<Vec<ContextModifier>> {
+    let mut stmt = conn.prepare(
+        "SELECT condition, card_tag, modifier, priority, description
+         FROM context_modifiers
+         WHERE active = 1",
+    )?;
+    let modifiers: Result<Vec<Option<ContextModifier>>> = stmt
+        .query_map([], |row| {
+            let condition: String = row.get(0)?;
+            let condition = match condition.parse::<Rule>() {
+                Ok(rule) => rule,
+                Err(e) => {
+                    log::warn!("Skipping context modifier '{}': {}", condition, e);
+                    return Ok(None);
+                }
+            };
+            Ok(Some(ContextModifier {
+                condition,
+                card_tag: row.get(1)?,
+                modifier: row.get(2)?,
+                priority: row.get(3)?,
+                description: row.get(4)?,
+            }))
+        })?
+        .collect();
+
+    Ok(modifiers?.into_iter().flatten().collect())
+}
+
+/// A card's value override for a champion, preferring the `Any` path
+pub fn get_champion_override(
+    conn: &Connection,
+    card_id: &str,
+    champion: &str,
+) -> Result<Option<i32>> {
+    conn.query_row(
+        "SELECT value_override
+         FROM champion_overrides
+         WHERE card_id = ?1 AND champion = ?2
+         ORDER BY CASE path WHEN 'Any' THEN 0 ELSE 1 END
+         LIMIT 1",
+        [card_id, champion],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// The override `get_champion_override` would pick for each card
+pub fn get_champion_overrides(conn: &Connection, champion: &str) -> Result<HashMap<String, i32>> {
+    let mut stmt = conn.prepare(
+        "SELECT card_id, value_override
+         FROM champion_overrides
+         WHERE champion = ?1
+         ORDER BY CASE path WHEN 'Any' THEN 0 ELSE 1 END",
+    )?;
+
+    let mut overrides = HashMap::new();
+    let rows = stmt.query_map([champion], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?;
+    for row in rows {
+        let (card_id, value) = row?;
+        overrides.entry(card_id).or_insert(value);
+    }
+    Ok(overrides)
+}
+
+/// Alternate card names as (card_id, alias) pairs
+pub fn load_card_aliases(conn: &Connection) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare("SELECT card_id, alias FROM card_aliases ORDER BY card_id")?;
+    let aliases = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect();
+
+    aliases
+}
+
+/// Learned OCR aliases (normalized OCR text -> card_id)
+pub fn load_ocr_aliases(conn: &Connection) -> Result<HashMap<String, String>> {
+    let mut stmt = conn.prepare("SELECT normalized_text, card_id FROM ocr_aliases")?;
+    let aliases = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect();
+
+    aliases
+}
+
+/// `app_settings` key counting changes to the learned OCR aliases
+const OCR_ALIASES_REVISION_KEY: &str = "ocr_aliases_revision";
+
+/// Count a change to the learned OCR aliases
+pub fn bump_ocr_aliases_revision(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "INSERT INTO app_settings (key, value, updated_at) VALUES (?1, '1', CURRENT_TIMESTAMP)
+         ON CONFLICT(key) DO UPDATE SET
+             value = CAST(value AS INTEGER) + 1,
+             updated_at = CURRENT_TIMESTAMP",
+        [OCR_ALIASES_REVISION_KEY],
+    )?;
     Ok(())
 }
 
+/// How many times the learned OCR aliases have changed, 0 if never
+pub fn ocr_aliases_revision(conn: &Connection) -> Result<i64> {
+    let revision = conn
+        .query_row(
+            "SELECT CAST(value AS INTEGER) FROM app_settings WHERE key = ?1",
+            [OCR_ALIASES_REVISION_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(revision.unwrap_or(0))
+}
+
 fn seed_expansions(conn: &Connection) -> Result<()> {
     let expansions = vec![
         (
@@ -40,11 +473,11 @@ fn seed_expansions(conn: &Connection) -> Result<()> {
         ),
     ];
 
+    let mut stmt = conn.prepare(
+        "INSERT OR IGNORE INTO expansions (id, name, release_date, description) VALUES (?1, ?2, ?3, ?4)",
+    )?;
     for (id, name, date, desc) in expansions {
-        conn.execute(
-            "INSERT OR IGNORE INTO expansions (id, name, release_date, description) VALUES (?1, ?2, ?3, ?4)",
-            [id, name, date, desc],
-        )?;
+        stmt.execute([id, name, date, desc])?;
     }
     Ok(())
 }
@@ -52,28 +485,37 @@ fn seed_expansions(conn: &Connection) -> Result<()> {
 fn seed_cards(conn: &Connection) -> Result<()> {
     let cards = get_all_cards_data();
 
+    let mut insert_card = conn.prepare(
+        "INSERT OR IGNORE INTO cards 
+         (id, name, clan, card_type, rarity, cost, base_value, tempo_score, value_score, keywords, description, expansion, size)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+    )?;
+    let mut insert_keyword = conn.prepare(
+        "INSERT OR IGNORE INTO card_keywords (card_id, keyword) VALUES (?1, ?2)",
+    )?;
+
     for card in cards {
         let keywords_json = serde_json::to_string(&card.keywords).unwrap_or_default();
 
-        conn.execute(
-            "INSERT OR IGNORE INTO cards 
-             (id, name, clan, card_type, rarity, cost, base_value, tempo_score, value_score, keywords, description, expansion)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
-            rusqlite::params![
-                &card.id,
-                &card.name,
-                &card.clan,
-                &card.card_type,
-                &card.rarity,
-                card.cost,
-                card.base_value,
-                card.tempo_score,
-                card.value_score,
-                keywords_json,
-                &card.description,
-                &card.expansion,
-            ],
-        )?;
+        insert_card.execute(rusqlite::params![
+            &card.id,
+            &card.name,
+            &card.clan,
+            &card.card_type,
+            &card.rarity,
+            card.cost,
+            card.base_value,
+            card.tempo_score,
+            card.value_score,
+            keywords_json,
+            &card.description,
+            &card.expansion,
+            card.size,
+        ])?;
+
+        for keyword in &card.keywords {
+            insert_keyword.execute([&card.id, keyword])?;
+        }
     }
 
     Ok(())
@@ -234,20 +676,20 @@ fn seed_synergies(conn: &Connection) -> Result<()> {
         ),
     ];
 
+    let mut stmt = conn.prepare(
+        "INSERT OR IGNORE INTO synergies 
+         (card_a_id, card_b_id, synergy_type, weight, description, bidirectional)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
     for (card_a, card_b, synergy_type, weight, desc, bidirectional) in synergies {
-        conn.execute(
-            "INSERT OR IGNORE INTO synergies 
-             (card_a_id, card_b_id, synergy_type, weight, description, bidirectional)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            rusqlite::params![
-                card_a,
-                card_b,
-                synergy_type,
-                weight,
-                desc,
-                bidirectional,
-            ],
-        )?;
+        stmt.execute(rusqlite::params![
+            card_a,
+            card_b,
+            synergy_type,
+            weight,
+            desc,
+            bidirectional,
+        ])?;
     }
 
     Ok(())
@@ -256,98 +698,102 @@ fn seed_synergies(conn: &Connection) -> Result<()> {
 fn seed_context_modifiers(conn: &Connection) -> Result<()> {
     let modifiers = vec![
         (
-            "missing_frontline",
+            "count(keyword:frontline|tank) == 0",
             "frontline",
             15,
             "High",
             "No tank units in deck (HP<30)",
         ),
         (
-            "missing_backline_clear",
+            "count(keyword:sweep|explosive|advance) == 0",
             "sweep",
             20,
             "Critical",
             "No Sweep Explosive or Advance",
         ),
         (
-            "missing_backline_clear",
+            "count(keyword:sweep|explosive|advance) == 0",
             "explosive",
             20,
             "Critical",
             "No Sweep Explosive or Advance",
         ),
         (
-            "has_reform_synergy",
+            "count(keyword:reform) > 0",
             "burnout",
             25,
             "High",
             "Has Reform cards Burnout valued higher",
         ),
         (
-            "deck_size_over_20",
+            "deck.size > 20",
             "draw",
             -10,
             "Medium",
             "Deck too large draw less valuable",
         ),
+        // Covenant was never passed to the old evaluator, so this never applies
         (
-            "covenant_high",
+            "false",
             "scaling",
             10,
             "Medium",
             "Covenant 15+ scaling matters more",
         ),
         (
-            "has_consume_synergy",
+            "count(keyword:consume) > 0",
             "consume",
             30,
             "High",
             "Morel Mistress or similar present",
         ),
+        // Gold isn't tracked, so this never applies
         (
-            "low_gold",
+            "false",
             "gold",
             15,
             "Medium",
             "<100 gold gold generation priority",
         ),
+        // No condition name ever matched this one, so it never applies
         (
-            "no_pyregel",
+            "false",
             "pyregel",
             -10,
             "Low",
             "No pyregel applicators",
         ),
+        // Ring was never passed to the old evaluator, so these never apply
         (
-            "ring_early",
+            "false",
             "tempo",
             15,
             "High",
             "Ring 1-3 tempo cards better",
         ),
         (
-            "ring_late",
+            "false",
             "value",
             15,
             "High",
             "Ring 6+ value cards better",
         ),
         (
-            "duplicate_common",
+            "count(card, rarity:Common) >= 2",
             "common ",
             -5,
             "Low",
             "3rd+ copy of common",
         ),
         (
-            "has_forge_synergy",
+            "count(keyword:forge) > 0",
             "forge",
             20,
             "High",
             "Forge points available",
         ),
         (
-            "has_smelt_synergy",
+            "count(keyword:smelt) > 0",
             "smelt",
             25,
             "High",
@@ -355,11 +801,46 @@ fn seed_context_modifiers(conn: &Connection) -> Result<()> {
         ),
     ];
 
+    let mut stmt = conn.prepare(
+        "INSERT OR IGNORE INTO context_modifiers 
+         (condition, card_tag, modifier, priority, description)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for (condition, tag, modifier, priority, desc) in modifiers {
+        stmt.execute(rusqlite::params![condition, tag, modifier, priority, desc])?;
+    }
+
+    Ok(())
+}
+
+/// Seed the context modifiers for floor capacity
+///
+/// Run once, by the migration that adds unit sizes, so they reach existing
+/// databases without duplicating on new ones.
+pub fn seed_capacity_modifiers(conn: &Connection) -> Result<()> {
+    let modifiers = vec![
+        (
+            "card.size >= 2 && space() + card.size > capacity",
+            "*",
+            -15,
+            "Critical",
+            "Capacity pressure: no floor room left for another large unit",
+        ),
+        (
+            "card.size >= 2 && space() + card.size > capacity - 3 && space() + card.size <= capacity",
+            "*",
+            -5,
+            "Medium",
+            "Capacity pressure: floors nearly full",
+        ),
+    ];
+
     for (condition, tag, modifier, priority, desc) in modifiers {
         conn.execute(
-            "INSERT OR IGNORE INTO context_modifiers 
+            "INSERT INTO context_modifiers
              (condition, card_tag, modifier, priority, description)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+             SELECT ?1, ?2, ?3, ?4, ?5
+             WHERE NOT EXISTS (SELECT 1 FROM context_modifiers WHERE condition = ?1)",
             rusqlite::params![condition, tag, modifier, priority, desc],
         )?;
     }
@@ -367,6 +848,19 @@ fn seed_context_modifiers(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Fill in unit sizes for seeded cards that don't have one yet
+pub fn backfill_card_sizes(conn: &Connection) -> Result<()> {
+    for card in get_all_cards_data() {
+        if let Some(size) = card.size {
+            conn.execute(
+                "UPDATE cards SET size = ?1 WHERE id = ?2 AND size IS NULL",
+                rusqlite::params![size, card.id],
+            )?;
+        }
+    }
+    Ok(())
+}
+
 fn seed_champion_overrides(conn: &Connection) -> Result<()> {
     let overrides = vec![
         // Banished
@@ -497,12 +991,363 @@ fn seed_champion_overrides(conn: &Connection) -> Result<()> {
         ),
     ];
 
+    let mut stmt = conn.prepare(
+        "INSERT OR IGNORE INTO champion_overrides 
+         (champion, path, card_id, value_override, reason)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
     for (champion, path, card_id, value_override, reason) in overrides {
+        stmt.execute(rusqlite::params![champion, path, card_id, value_override, reason])?;
+    }
+
+    Ok(())
+}
+
+/// Seed the default archetype definitions
+///
+/// Run once, by the migration that adds the table, so archetypes the user
+/// edits or deletes stay that way.
+pub fn seed_archetypes(conn: &Connection) -> Result<()> {
+    let archetypes = vec![
+        (
+            "valor_frontline",
+            "Valor Frontline",
+            "Stack Valor on sturdy front units",
+            r#"{"valor": 1.0, "frontline": 0.5, "tank": 0.5, "revenge": 0.5, "multistrike": 0.5}"#,
+            r#"["banished_fel", "banished_steadfast_crusader"]"#,
+        ),
+        (
+            "shift_tempo",
+            "Shift Tempo",
+            "Move units between floors for repeated Shift triggers",
+            r#"{"shift": 1.0, "advance": 0.5, "tempo": 0.5, "combo": 0.5}"#,
+            r#"["banished_karmic_censer"]"#,
+        ),
+        (
+            "dragon_hoard",
+            "Dragon's Hoard",
+            "Grow the hoard and cash it in with dragons",
+            r#"{"dragon": 1.0, "dragon_hoard": 1.0, "whelp": 0.5, "gold": 0.5, "avarice": 0.5}"#,
+            r#"["pyreborne_lady_gilda"]"#,
+        ),
+        (
+            "funguy_swarm",
+            "Funguy Swarm",
+            "Flood the floor with Funguys and consume them for value",
+            r#"{"funguy": 1.0, "spawn": 0.5, "spore": 0.5, "spore_scaling": 0.5, "consume": 0.5}"#,
+            r#"["underlegion_madame_lionsmane"]"#,
+        ),
+        (
+            "potion_brewing",
+            "Potion Brewing",
+            "Mix potions for flexible buffs and damage",
+            r#"{"potion": 1.0, "mix": 1.0, "reanimate": 0.5}"#,
+            r#"["lazarus_league_potion_kit"]"#,
+        ),
+        (
+            "burnout_reform",
+            "Burnout Reform",
+            "Trade short-lived units for Reform value",
+            r#"{"burnout": 1.0, "reform": 1.0, "resurrection": 0.5}"#,
+            r#"["melting_remnant_rector_flicker"]"#,
+        ),
+        (
+            "forge_equipment",
+            "Forge Equipment",
+            "Forge and equip the best units",
+            r#"{"forge": 1.0, "equipment": 1.0, "smelt": 0.5, "steelguard": 0.5, "burst": 0.5}"#,
+            r#"["railforged_heph"]"#,
+        ),
+        (
+            "spell_power",
+            "Spell Power",
+            "Scale Magic Power and chain spells through conduits",
+            r#"{"magic_power": 1.0, "conduit": 1.0, "spell_buff": 0.5, "spell_synergy": 0.5, "incant": 0.5}"#,
+            r#"["luna_coven_ekka"]"#,
+        ),
+    ];
+
+    for (id, name, description, keyword_weights, key_cards) in archetypes {
         conn.execute(
-            "INSERT OR IGNORE INTO champion_overrides 
-             (champion, path, card_id, value_override, reason)
+            "INSERT OR IGNORE INTO archetypes
+             (id, name, description, keyword_weights, key_cards)
              VALUES (?1, ?2, ?3, ?4, ?5)",
-            rusqlite::params![champion, path, card_id, value_override, reason],
+            rusqlite::params![id, name, description, keyword_weights, key_cards],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Give the seeded archetypes their ideal energy curves
+///
+/// Only fills curves that are unset, so edited archetypes keep theirs.
+pub fn seed_archetype_curves(conn: &Connection) -> Result<()> {
+    let curves = vec![
+        ("valor_frontline", "[0.05, 0.25, 0.35, 0.25, 0.10]"),
+        ("shift_tempo", "[0.15, 0.35, 0.30, 0.15, 0.05]"),
+        ("dragon_hoard", "[0.05, 0.20, 0.30, 0.25, 0.20]"),
+        ("funguy_swarm", "[0.20, 0.35, 0.30, 0.10, 0.05]"),
+        ("potion_brewing", "[0.10, 0.30, 0.30, 0.20, 0.10]"),
+        ("burnout_reform", "[0.10, 0.30, 0.35, 0.15, 0.10]"),
+        ("forge_equipment", "[0.05, 0.25, 0.30, 0.25, 0.15]"),
+        ("spell_power", "[0.20, 0.30, 0.25, 0.15, 0.10]"),
+    ];
+
+    for (id, curve) in curves {
+        conn.execute(
+            "UPDATE archetypes SET ideal_curve = ?2 WHERE id = ?1 AND ideal_curve IS NULL",
+            [id, curve],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Seed the default champion path definitions
+///
+/// Run once, by the migration that adds the table, so edited paths stay
+/// that way.
+pub fn seed_champion_paths(conn: &Connection) -> Result<()> {
+    let paths = vec![
+        (
+            "Fel",
+            "Unchained",
+            "Scales with every Shift",
+            r#"{"shift": 1.0, "advance": 0.5, "combo": 0.5}"#,
+        ),
+        (
+            "Fel",
+            "Savior",
+            "Turns Valor and armor into a wall",
+            r#"{"valor": 1.0, "armor": 1.0, "frontline": 0.5}"#,
+        ),
+        (
+            "Talos",
+            "Flight",
+            "Hits harder as flying units pile up",
+            r#"{"flight": 1.0, "rage": 0.5, "attack_buff": 0.5}"#,
+        ),
+        (
+            "Lady Gilda",
+            "Bloat Matron",
+            "Feeds whelps from the Dragon's Hoard",
+            r#"{"dragon_hoard": 1.0, "whelp": 1.0, "gold": 0.5}"#,
+        ),
+        (
+            "Ekka",
+            "Spellweaver",
+            "Chains spells through conduits",
+            r#"{"conduit": 1.0, "magic_power": 1.0, "spell_buff": 0.5}"#,
+        ),
+        (
+            "Madame Lionsmane",
+            "Sporesinger",
+            "Grows with spores consumed",
+            r#"{"spore": 1.0, "spore_scaling": 1.0, "consume": 0.5}"#,
+        ),
+        (
+            "Orechi",
+            "Brewmaster",
+            "Gets more from every potion mixed",
+            r#"{"potion": 1.0, "mix": 1.0, "brewmaster": 1.0}"#,
+        ),
+        (
+            "Herzal",
+            "Blacksmith",
+            "Spends Forge points on equipment",
+            r#"{"forge": 1.0, "blacksmith": 1.0, "equipment": 0.5}"#,
+        ),
+        (
+            "Herzal",
+            "Pyresmith",
+            "Scales with Pyregel applied",
+            r#"{"pyregel": 1.0, "burst": 0.5}"#,
+        ),
+        (
+            "Heph",
+            "Handy",
+            "Fills every equipment slot",
+            r#"{"equipment": 1.0, "steelguard": 0.5}"#,
+        ),
+        (
+            "Heph",
+            "Metalworker",
+            "Turns smelted scrap into value",
+            r#"{"smelt": 1.0, "forge": 0.5, "artifact": 0.5}"#,
+        ),
+    ];
+
+    for (champion, path, description, scaling_keywords) in paths {
+        conn.execute(
+            "INSERT OR IGNORE INTO champion_paths
+             (champion, path, description, scaling_keywords)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![champion, path, description, scaling_keywords],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Seed the boss lineup and the context rules that reward counters to it
+///
+/// Run once, by the migration that adds the table, so edited bosses stay
+/// that way.
+pub fn seed_bosses(conn: &Connection) -> Result<()> {
+    let bosses = vec![
+        (
+            "sentinel_of_spikes",
+            "Sentinel of Spikes",
+            3,
+            0,
+            r#"["spikes"]"#,
+            "Spikes punish every unit that attacks it",
+        ),
+        (
+            "swarm_herald",
+            "Swarm Herald",
+            6,
+            0,
+            r#"["summons"]"#,
+            "Floods the floors with minions each turn",
+        ),
+        (
+            "seraph_the_relentless",
+            "Seraph the Relentless",
+            10,
+            0,
+            r#"["relentless", "spikes"]"#,
+            "Relentless: fights on every floor until the Pyre falls; spikes punish attackers",
+        ),
+        (
+            "seraph_the_undying",
+            "Seraph the Undying",
+            10,
+            10,
+            r#"["relentless", "regen"]"#,
+            "Relentless, and regenerates each turn",
+        ),
+        (
+            "swarm_matriarch",
+            "Swarm Matriarch",
+            6,
+            15,
+            r#"["summons", "spikes"]"#,
+            "Summons spiked minions each turn",
+        ),
+    ];
+
+    for (id, name, ring, min_covenant, mechanics, description) in bosses {
+        conn.execute(
+            "INSERT OR IGNORE INTO bosses
+             (id, name, ring, min_covenant, mechanics, description)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![id, name, ring, min_covenant, mechanics, description],
+        )?;
+    }
+
+    let counters = vec![
+        (
+            "boss(mechanic:spikes) > 0",
+            "armor",
+            10,
+            "High",
+            "Boss counter: armor soaks the upcoming boss's spikes",
+        ),
+        (
+            "boss(mechanic:summons) > 0",
+            "sweep",
+            10,
+            "High",
+            "Boss counter: sweep clears the upcoming boss's minions",
+        ),
+        (
+            "boss(mechanic:relentless) > 0",
+            "removal",
+            8,
+            "Medium",
+            "Boss counter: removal shortens a relentless boss fight",
+        ),
+        (
+            "boss(mechanic:relentless) > 0",
+            "burnout",
+            -5,
+            "Medium",
+            "Boss counter: burnout units expire before a relentless boss does",
+        ),
+        (
+            "boss(mechanic:regen) > 0",
+            "burst",
+            8,
+            "Medium",
+            "Boss counter: burst damage outpaces regeneration",
+        ),
+    ];
+
+    for (condition, tag, modifier, priority, desc) in counters {
+        conn.execute(
+            "INSERT INTO context_modifiers
+             (condition, card_tag, modifier, priority, description)
+             SELECT ?1, ?2, ?3, ?4, ?5
+             WHERE NOT EXISTS
+                (SELECT 1 FROM context_modifiers WHERE condition = ?1 AND card_tag = ?2)",
+            rusqlite::params![condition, tag, modifier, priority, desc],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Seed the context rules for endless runs, where enemies scale with
+/// pact shards and scaling and value cards matter more than tempo
+pub fn seed_endless_modifiers(conn: &Connection) -> Result<()> {
+    let modifiers = vec![
+        (
+            "pact_shards >= 25",
+            "scaling",
+            10,
+            "High",
+            "Endless: enemies scale with pact shards, so scaling matters more",
+        ),
+        (
+            "pact_shards >= 25",
+            "value",
+            5,
+            "Medium",
+            "Endless: long fights reward value",
+        ),
+        (
+            "pact_shards >= 50",
+            "scaling_damage",
+            10,
+            "High",
+            "Endless: only scaling damage keeps up at 50+ shards",
+        ),
+        (
+            "pact_shards >= 50",
+            "snowball",
+            8,
+            "High",
+            "Endless: snowballing units keep up at 50+ shards",
+        ),
+        (
+            "pact_shards >= 50",
+            "tempo",
+            -5,
+            "Medium",
+            "Endless: tempo fades against heavily scaled enemies",
+        ),
+    ];
+
+    for (condition, tag, modifier, priority, desc) in modifiers {
+        conn.execute(
+            "INSERT INTO context_modifiers
+             (condition, card_tag, modifier, priority, description)
+             SELECT ?1, ?2, ?3, ?4, ?5
+             WHERE NOT EXISTS
+                (SELECT 1 FROM context_modifiers WHERE condition = ?1 AND card_tag = ?2)",
+            rusqlite::params![condition, tag, modifier, priority, desc],
         )?;
     }
 
@@ -518,6 +1363,8 @@ pub struct CardData {
     pub card_type: String,
     pub rarity: String,
     pub cost: Option<i32>,
+    /// Floor space the unit takes; `None` for cards that aren't units
+    pub size: Option<i32>,
     pub base_value: i32,
     pub tempo_score: i32,
     pub value_score: i32,
@@ -538,6 +1385,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: None,
+            size: None,
             base_value: 85,
             tempo_score: 7,
             value_score: 8,
@@ -556,6 +1404,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: None,
+            size: None,
             base_value: 82,
             tempo_score: 8,
             value_score: 7,
@@ -574,6 +1423,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Spell".to_string(),
             rarity: "Common".to_string(),
             cost: Some(0),
+            size: None,
             base_value: 75,
             tempo_score: 9,
             value_score: 6,
@@ -592,6 +1442,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Spell".to_string(),
             rarity: "Common".to_string(),
             cost: Some(1),
+            size: None,
             base_value: 70,
             tempo_score: 7,
             value_score: 6,
@@ -610,6 +1461,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Unit".to_string(),
             rarity: "Uncommon".to_string(),
             cost: Some(3),
+            size: Some(2),
             base_value: 78,
             tempo_score: 6,
             value_score: 8,
@@ -629,6 +1481,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Spell".to_string(),
             rarity: "Rare".to_string(),
             cost: Some(1),
+            size: None,
             base_value: 92,
             tempo_score: 8,
             value_score: 10,
@@ -649,6 +1502,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Artifact".to_string(),
             rarity: "Rare".to_string(),
             cost: Some(0),
+            size: None,
             base_value: 88,
             tempo_score: 8,
             value_score: 9,
@@ -671,6 +1525,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: None,
+            size: None,
             base_value: 84,
             tempo_score: 7,
             value_score: 8,
@@ -690,6 +1545,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: None,
+            size: None,
             base_value: 83,
             tempo_score: 6,
             value_score: 9,
@@ -709,6 +1565,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Spell".to_string(),
             rarity: "Uncommon".to_string(),
             cost: Some(1),
+            size: None,
             base_value: 86,
             tempo_score: 8,
             value_score: 9,
@@ -728,6 +1585,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Unit".to_string(),
             rarity: "Uncommon".to_string(),
             cost: Some(1),
+            size: Some(2),
             base_value: 79,
             tempo_score: 7,
             value_score: 8,
@@ -751,6 +1609,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: None,
+            size: None,
             base_value: 86,
             tempo_score: 7,
             value_score: 9,
@@ -769,6 +1628,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Spell".to_string(),
             rarity: "Common".to_string(),
             cost: Some(0),
+            size: None,
             base_value: 76,
             tempo_score: 8,
             value_score: 6,
@@ -787,6 +1647,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Equipment".to_string(),
             rarity: "Rare".to_string(),
             cost: Some(3),
+            size: None,
             base_value: 91,
             tempo_score: 7,
             value_score: 10,
@@ -810,6 +1671,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: None,
+            size: None,
             base_value: 88,
             tempo_score: 8,
             value_score: 9,
@@ -829,6 +1691,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: None,
+            size: None,
             base_value: 87,
             tempo_score: 7,
             value_score: 9,
@@ -847,6 +1710,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Unit".to_string(),
             rarity: "Uncommon".to_string(),
             cost: Some(2),
+            size: Some(2),
             base_value: 84,
             tempo_score: 7,
             value_score: 9,
@@ -866,6 +1730,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Unit".to_string(),
             rarity: "Common".to_string(),
             cost: Some(1),
+            size: Some(1),
             base_value: 72,
             tempo_score: 7,
             value_score: 6,
@@ -888,6 +1753,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: None,
+            size: None,
             base_value: 85,
             tempo_score: 6,
             value_score: 9,
@@ -907,6 +1773,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Unit".to_string(),
             rarity: "Uncommon".to_string(),
             cost: Some(3),
+            size: Some(1),
             base_value: 89,
             tempo_score: 7,
             value_score: 9,
@@ -926,6 +1793,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Equipment".to_string(),
             rarity: "Common".to_string(),
             cost: Some(2),
+            size: None,
             base_value: 80,
             tempo_score: 6,
             value_score: 8,
@@ -949,6 +1817,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: None,
+            size: None,
             base_value: 87,
             tempo_score: 6,
             value_score: 9,
@@ -967,6 +1836,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Unit".to_string(),
             rarity: "Rare".to_string(),
             cost: Some(4),
+            size: Some(2),
             base_value: 86,
             tempo_score: 5,
             value_score: 9,
@@ -987,6 +1857,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Spell".to_string(),
             rarity: "Common".to_string(),
             cost: Some(1),
+            size: None,
             base_value: 77,
             tempo_score: 6,
             value_score: 7,
@@ -1010,6 +1881,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: None,
+            size: None,
             base_value: 83,
             tempo_score: 7,
             value_score: 8,
@@ -1028,6 +1900,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Unit".to_string(),
             rarity: "Uncommon".to_string(),
             cost: Some(3),
+            size: Some(3),
             base_value: 79,
             tempo_score: 6,
             value_score: 8,
@@ -1051,6 +1924,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: Some(0),
+            size: None,
             base_value: 85,
             tempo_score: 7,
             value_score: 8,
@@ -1069,6 +1943,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Champion".to_string(),
             rarity: "Champion".to_string(),
             cost: Some(0),
+            size: None,
             base_value: 84,
             tempo_score: 6,
             value_score: 9,
@@ -1087,6 +1962,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Unit".to_string(),
             rarity: "Uncommon".to_string(),
             cost: Some(2),
+            size: Some(2),
             base_value: 78,
             tempo_score: 6,
             value_score: 8,
@@ -1105,6 +1981,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Unit".to_string(),
             rarity: "Rare".to_string(),
             cost: Some(3),
+            size: Some(2),
             base_value: 82,
             tempo_score: 7,
             value_score: 8,
@@ -1123,6 +2000,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Spell".to_string(),
             rarity: "Uncommon".to_string(),
             cost: Some(1),
+            size: None,
             base_value: 81,
             tempo_score: 8,
             value_score: 7,
@@ -1137,6 +2015,7 @@ fn get_all_cards_data() -> Vec<CardData> {
             card_type: "Spell".to_string(),
             rarity: "Common".to_string(),
             cost: Some(1),
+            size: None,
             base_value: 74,
             tempo_score: 8,
             value_score: 6,
@@ -1152,7 +2031,94 @@ fn get_all_cards_data() -> Vec<CardData> {
     cards
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
 
+    fn setup_test_db() -> (Connection, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        (conn, temp_file)
+    }
+
+    #[test]
+    fn test_card_queries() {
+        let (conn, _temp) = setup_test_db();
 
+        assert!(card_exists(&conn, "banished_fel").unwrap());
+        assert!(!card_exists(&conn, "no_such_card").unwrap());
 
+        let fel = get_card_by_name(&conn, "Fel", None).unwrap().unwrap();
+        assert_eq!(fel.id, "banished_fel");
+        assert!(get_card_by_name(&conn, "Nobody", None).unwrap().is_none());
+
+        let cards = get_cards_by_keyword(&conn, "valor").unwrap();
+        assert!(!cards.is_empty());
+        assert!(cards.windows(2).all(|pair| pair[0].name <= pair[1].name));
+        assert!(get_favorite_cards(&conn).unwrap().is_empty());
+    }
 
+    #[test]
+    fn test_get_synergies_for_card() {
+        let (conn, _temp) = setup_test_db();
+
+        // banished_fel has synergies defined in seed data
+        let synergies = get_synergies_for_card(&conn, "banished_fel").unwrap();
+        assert!(!synergies.is_empty());
+        assert!(get_all_synergies(&conn).unwrap().len() >= synergies.len());
+    }
+
+    #[test]
+    fn test_get_synergy_notes() {
+        let (conn, _temp) = setup_test_db();
+
+        let ids = HashSet::from([
+            "banished_fel",
+            "banished_just_cause",
+            "banished_deadly_plunge",
+            "banished_titan_sentry",
+        ]);
+        let notes = get_synergy_notes(&conn, &ids).unwrap();
+        assert!(notes["banished_just_cause"].contains(&"Shift triggers Valor gain".to_string()));
+        // Both ways
+        for card_id in ["banished_deadly_plunge", "banished_titan_sentry"] {
+            assert!(notes[card_id].contains(&"High HP target for sacrifice".to_string()));
+        }
+        // Only on the second card of a one-way synergy
+        assert!(!notes
+            .get("banished_fel")
+            .into_iter()
+            .flatten()
+            .any(|note| note == "Shift triggers Valor gain"));
+
+        let ids = HashSet::from(["banished_fel"]);
+        assert!(get_synergy_notes(&conn, &ids).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_active_context_modifiers() {
+        let (conn, _temp) = setup_test_db();
+
+        let modifiers = get_active_context_modifiers(&conn).unwrap();
+        assert!(!modifiers.is_empty());
+    }
+
+    #[test]
+    fn test_get_champion_override() {
+        let (conn, _temp) = setup_test_db();
+
+        // Fel has an override for "Just Cause" with champion "Fel"
+        let override_val = get_champion_override(&conn, "banished_just_cause", "Fel").unwrap();
+        assert_eq!(override_val, Some(95));
+        assert_eq!(
+            get_champion_overrides(&conn, "Fel").unwrap().get("banished_just_cause"),
+            Some(&95)
+        );
+
+        // No override for non-matching champion
+        let no_override = get_champion_override(&conn, "banished_just_cause", "Random").unwrap();
+        assert!(no_override.is_none());
+    }
+}
@@ -12,7 +12,7 @@ CREATE TABLE IF NOT EXISTS cards (
     base_value INTEGER NOT NULL,
     tempo_score INTEGER NOT NULL,
     value_score INTEGER NOT NULL,
-    keywords TEXT, -- JSON array
+    keywords TEXT, -- JSON array, mirrored in card_keywords for queries
     description TEXT,
     expansion TEXT DEFAULT 'base',
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
@@ -99,3 +99,345 @@ CREATE TABLE IF NOT EXISTS expansions (
     description TEXT
 );
 "#;
+
+pub const CREATE_OCR_DETECTIONS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS ocr_detections (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    card_id TEXT NOT NULL,
+    card_name TEXT NOT NULL,
+    raw_text TEXT NOT NULL,
+    ocr_confidence INTEGER NOT NULL,
+    match_score INTEGER NOT NULL,
+    overall_confidence REAL NOT NULL,
+    region_x INTEGER NOT NULL,
+    region_y INTEGER NOT NULL,
+    region_width INTEGER NOT NULL,
+    region_height INTEGER NOT NULL,
+    detected_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (card_id) REFERENCES cards(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ocr_detections_card ON ocr_detections(card_id);
+CREATE INDEX IF NOT EXISTS idx_ocr_detections_detected_at ON ocr_detections(detected_at);
+"#;
+
+pub const CREATE_OCR_DETECTION_ARCHIVES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS ocr_detection_archives (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    first_detection_id INTEGER NOT NULL,
+    last_detection_id INTEGER NOT NULL,
+    row_count INTEGER NOT NULL,
+    oldest_detected_at TIMESTAMP NOT NULL,
+    newest_detected_at TIMESTAMP NOT NULL,
+    payload BLOB NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_ocr_detection_archives_range ON ocr_detection_archives(oldest_detected_at, newest_detected_at);
+"#;
+
+pub const CREATE_OCR_CORRECTIONS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS ocr_corrections (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    raw_text TEXT NOT NULL,
+    wrong_card_id TEXT,
+    correct_card_id TEXT NOT NULL,
+    reported_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (correct_card_id) REFERENCES cards(id)
+);
+
+CREATE TABLE IF NOT EXISTS ocr_aliases (
+    normalized_text TEXT PRIMARY KEY,
+    card_id TEXT NOT NULL,
+    times_reported INTEGER NOT NULL DEFAULT 1,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (card_id) REFERENCES cards(id)
+);
+"#;
+
+pub const CREATE_CARD_ALIASES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS card_aliases (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    card_id TEXT NOT NULL,
+    alias TEXT NOT NULL,
+    alias_type TEXT NOT NULL DEFAULT 'nickname',
+    language TEXT,
+    UNIQUE(card_id, alias),
+    FOREIGN KEY (card_id) REFERENCES cards(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_aliases_card ON card_aliases(card_id);
+"#;
+
+pub const CREATE_DRAFT_OFFERS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS draft_offers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id TEXT NOT NULL,
+    ring_number INTEGER NOT NULL,
+    card_id TEXT NOT NULL,
+    was_picked BOOLEAN NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (card_id) REFERENCES cards(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_draft_offers_card ON draft_offers(card_id);
+CREATE INDEX IF NOT EXISTS idx_draft_offers_run ON draft_offers(run_id);
+"#;
+
+pub const CREATE_OCR_CONFIDENCE_CALIBRATION_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS ocr_confidence_calibration (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    ocr_weight REAL NOT NULL,
+    match_weight REAL NOT NULL,
+    sample_count INTEGER NOT NULL,
+    corrected_count INTEGER NOT NULL,
+    brier_score REAL NOT NULL,
+    calibrated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+"#;
+
+pub const CREATE_APP_SETTINGS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS app_settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+"#;
+
+pub const CREATE_RUNS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS runs (
+    run_id TEXT PRIMARY KEY,
+    champion TEXT NOT NULL,
+    champion_path TEXT,
+    covenant INTEGER NOT NULL,
+    did_win BOOLEAN,
+    final_ring INTEGER,
+    started_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    ended_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_runs_champion ON runs(champion, champion_path);
+"#;
+
+pub const CREATE_CARD_KEYWORDS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS card_keywords (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    card_id TEXT NOT NULL,
+    keyword TEXT NOT NULL,
+    UNIQUE(card_id, keyword),
+    FOREIGN KEY (card_id) REFERENCES cards(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_keywords_keyword ON card_keywords(keyword COLLATE NOCASE);
+"#;
+
+pub const CREATE_CARD_TRANSLATIONS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS card_translations (
+    card_id TEXT NOT NULL,
+    locale TEXT NOT NULL, -- lowercase language code, e.g. 'de'
+    name TEXT NOT NULL,
+    description TEXT,
+    PRIMARY KEY (card_id, locale),
+    FOREIGN KEY (card_id) REFERENCES cards(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_translations_locale ON card_translations(locale, name);
+"#;
+
+pub const CREATE_CARD_NOTES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS card_notes (
+    card_id TEXT PRIMARY KEY,
+    note TEXT NOT NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (card_id) REFERENCES cards(id)
+);
+
+CREATE TABLE IF NOT EXISTS card_tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    card_id TEXT NOT NULL,
+    tag TEXT NOT NULL COLLATE NOCASE,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(card_id, tag),
+    FOREIGN KEY (card_id) REFERENCES cards(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_tags_tag ON card_tags(tag);
+"#;
+
+pub const CREATE_FAVORITES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS favorites (
+    card_id TEXT PRIMARY KEY,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (card_id) REFERENCES cards(id)
+);
+"#;
+
+pub const CREATE_ARCHETYPES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS archetypes (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    keyword_weights TEXT NOT NULL DEFAULT '{}', -- JSON object of keyword to weight
+    key_cards TEXT NOT NULL DEFAULT '[]', -- JSON array of card IDs
+    active BOOLEAN NOT NULL DEFAULT 1
+);
+"#;
+
+pub const CREATE_CHAMPION_PATHS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS champion_paths (
+    champion TEXT NOT NULL,
+    path TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    scaling_keywords TEXT NOT NULL DEFAULT '{}', -- JSON object of keyword to weight
+    PRIMARY KEY (champion, path)
+);
+"#;
+
+pub const CREATE_BOSSES_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS bosses (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    ring INTEGER NOT NULL,
+    min_covenant INTEGER NOT NULL DEFAULT 0,
+    mechanics TEXT NOT NULL DEFAULT '[]', -- JSON array of mechanic names
+    description TEXT NOT NULL DEFAULT ''
+);
+"#;
+
+/// Summaries of `deck_history` and `runs` for the analytics commands
+///
+/// Triggers queue every card and champion whose raw rows change, and
+/// `analytics::refresh_analytics` recomputes just those summary rows.
+pub const CREATE_ANALYTICS_TABLES: &str = r#"
+CREATE TABLE IF NOT EXISTS card_stats_summary (
+    card_id TEXT PRIMARY KEY,
+    runs_drafted INTEGER NOT NULL,
+    runs_completed INTEGER NOT NULL,
+    wins INTEGER NOT NULL,
+    average_ring REAL,
+    average_score REAL
+);
+
+CREATE TABLE IF NOT EXISTS champion_stats_summary (
+    champion TEXT NOT NULL,
+    champion_path TEXT,
+    covenant INTEGER NOT NULL,
+    games INTEGER NOT NULL,
+    wins INTEGER NOT NULL,
+    losses INTEGER NOT NULL,
+    cards INTEGER NOT NULL -- Cards drafted over all the games
+);
+
+CREATE INDEX IF NOT EXISTS idx_champion_stats_summary
+    ON champion_stats_summary(champion, champion_path);
+
+CREATE TABLE IF NOT EXISTS analytics_stale_cards (
+    card_id TEXT PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS analytics_stale_champions (
+    champion TEXT NOT NULL,
+    champion_path TEXT
+);
+
+CREATE TRIGGER IF NOT EXISTS deck_history_analytics_insert AFTER INSERT ON deck_history
+BEGIN
+    INSERT OR IGNORE INTO analytics_stale_cards (card_id) VALUES (NEW.card_id);
+    INSERT INTO analytics_stale_champions (champion, champion_path)
+        SELECT champion, champion_path FROM runs WHERE run_id = NEW.run_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS deck_history_analytics_update AFTER UPDATE ON deck_history
+BEGIN
+    INSERT OR IGNORE INTO analytics_stale_cards (card_id) VALUES (OLD.card_id), (NEW.card_id);
+    INSERT INTO analytics_stale_champions (champion, champion_path)
+        SELECT champion, champion_path FROM runs WHERE run_id IN (OLD.run_id, NEW.run_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS deck_history_analytics_delete AFTER DELETE ON deck_history
+BEGIN
+    INSERT OR IGNORE INTO analytics_stale_cards (card_id) VALUES (OLD.card_id);
+    INSERT INTO analytics_stale_champions (champion, champion_path)
+        SELECT champion, champion_path FROM runs WHERE run_id = OLD.run_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS runs_analytics_insert AFTER INSERT ON runs
+BEGIN
+    INSERT INTO analytics_stale_champions (champion, champion_path)
+        VALUES (NEW.champion, NEW.champion_path);
+END;
+
+CREATE TRIGGER IF NOT EXISTS runs_analytics_update AFTER UPDATE ON runs
+BEGIN
+    INSERT INTO analytics_stale_champions (champion, champion_path)
+        VALUES (OLD.champion, OLD.champion_path), (NEW.champion, NEW.champion_path);
+END;
+
+CREATE TRIGGER IF NOT EXISTS runs_analytics_delete AFTER DELETE ON runs
+BEGIN
+    INSERT INTO analytics_stale_champions (champion, champion_path)
+        VALUES (OLD.champion, OLD.champion_path);
+END;
+"#;
+
+pub const CREATE_COMMUNITY_STATS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS community_stats (
+    card_id TEXT PRIMARY KEY,
+    games INTEGER NOT NULL,
+    wins INTEGER NOT NULL,
+    pick_rate REAL,
+    source TEXT, -- File name or publisher the stats were imported from
+    imported_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (card_id) REFERENCES cards(id)
+);
+"#;
+
+pub const CREATE_UPLOAD_QUEUE_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS upload_queue (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id TEXT NOT NULL UNIQUE,
+    payload TEXT NOT NULL, -- Anonymized run summary JSON
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP,
+    queued_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    uploaded_at TIMESTAMP
+);
+"#;
+
+/// History without soft-deleted runs, for stats and summaries
+///
+/// Rows of runs with no `runs` entry count as live. Deleting a run queues
+/// its cards for the analytics refresh, as its rows drop out of the views.
+pub const CREATE_LIVE_RUN_VIEWS: &str = r#"
+CREATE VIEW IF NOT EXISTS live_runs AS
+    SELECT * FROM runs WHERE deleted_at IS NULL;
+
+CREATE VIEW IF NOT EXISTS live_deck_history AS
+    SELECT * FROM deck_history
+    WHERE run_id NOT IN (SELECT run_id FROM runs WHERE deleted_at IS NOT NULL);
+
+CREATE VIEW IF NOT EXISTS live_draft_offers AS
+    SELECT * FROM draft_offers
+    WHERE run_id NOT IN (SELECT run_id FROM runs WHERE deleted_at IS NOT NULL);
+
+CREATE TRIGGER IF NOT EXISTS runs_analytics_soft_delete AFTER UPDATE OF deleted_at ON runs
+BEGIN
+    INSERT OR IGNORE INTO analytics_stale_cards (card_id)
+        SELECT card_id FROM deck_history WHERE run_id = NEW.run_id;
+END;
+"#;
+
+pub const CREATE_AUDIT_LOG_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL, -- Kind of data edited, e.g. 'archetype'
+    entity_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    before_value TEXT, -- JSON
+    after_value TEXT, -- JSON
+    changed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_entity ON audit_log(entity, entity_id);
+"#;
@@ -0,0 +1,47 @@
+//! Persisted application settings
+//!
+//! Settings are stored as JSON values keyed by name in the `app_settings`
+//! table, so each subsystem owns the shape of its own entry.
+
+use rusqlite::{params, Connection, OptionalExtension, Result};
+
+/// Raw JSON stored under `key`, if any
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [key],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Store `value` under `key`, replacing any previous value
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO app_settings (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations;
+
+    #[test]
+    fn test_setting_roundtrip() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrations::run_all(&conn).unwrap();
+
+        assert_eq!(get_setting(&conn, "hotkeys").unwrap(), None);
+
+        set_setting(&conn, "hotkeys", "{\"a\":1}").unwrap();
+        set_setting(&conn, "hotkeys", "{\"a\":2}").unwrap();
+        assert_eq!(
+            get_setting(&conn, "hotkeys").unwrap().as_deref(),
+            Some("{\"a\":2}")
+        );
+    }
+}
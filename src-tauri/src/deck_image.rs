@@ -0,0 +1,355 @@
+//! Deck list rendered as a PNG
+//!
+//! Text is drawn with an embedded 5x7 bitmap font, so rendering needs
+//! nothing beyond the `image` crate and looks the same on every machine.
+//! The font covers printable ASCII; other characters are folded to their
+//! unaccented letter where possible and drawn as `?` otherwise.
+
+use image::{ImageBuffer, Rgba, RgbaImage};
+
+/// Font pixels per image pixel
+const SCALE: u32 = 2;
+/// Horizontal distance between characters, including spacing
+const ADVANCE: u32 = 6 * SCALE;
+const LINE_HEIGHT: u32 = 11 * SCALE;
+const PADDING: u32 = 8 * SCALE;
+
+/// Column widths in characters
+const COST_CHARS: usize = 3;
+const NAME_CHARS: usize = 26;
+const TIER_CHARS: usize = 2;
+const SYNERGY_CHARS: usize = 44;
+/// Total characters per line, with one space between columns
+const LINE_CHARS: usize = COST_CHARS + NAME_CHARS + TIER_CHARS + SYNERGY_CHARS + 3;
+
+const BACKGROUND: Rgba<u8> = Rgba([17, 24, 39, 255]);
+const ROW_STRIPE: Rgba<u8> = Rgba([31, 41, 55, 255]);
+const SYNERGY_ROW: Rgba<u8> = Rgba([30, 58, 52, 255]);
+const TEXT: Rgba<u8> = Rgba([229, 231, 235, 255]);
+const MUTED: Rgba<u8> = Rgba([156, 163, 175, 255]);
+const SYNERGY_TEXT: Rgba<u8> = Rgba([52, 211, 153, 255]);
+
+/// One card line of the image
+#[derive(Debug, Clone, Default)]
+pub struct DeckImageRow {
+    pub name: String,
+    pub cost: Option<i32>,
+    /// Score tier ("S" to "C"), if the card is known
+    pub tier: Option<String>,
+    /// Synergies with other cards in the deck; highlighted when present
+    pub synergies: Vec<String>,
+}
+
+/// Everything drawn on a deck image
+#[derive(Debug, Clone, Default)]
+pub struct DeckImage {
+    pub title: String,
+    pub subtitle: String,
+    pub rows: Vec<DeckImageRow>,
+}
+
+/// Same colors as the overlay's tier badges
+fn tier_color(tier: &str) -> Rgba<u8> {
+    match tier {
+        "S" => Rgba([250, 204, 21, 255]),
+        "A" => Rgba([209, 213, 219, 255]),
+        "B" => Rgba([251, 146, 60, 255]),
+        _ => Rgba([107, 114, 128, 255]),
+    }
+}
+
+/// Render the deck list
+pub fn render(deck: &DeckImage) -> RgbaImage {
+    // Title, subtitle, a blank line, then one line per card
+    let lines = 3 + deck.rows.len() as u32;
+    let width = PADDING * 2 + LINE_CHARS as u32 * ADVANCE;
+    let height = PADDING * 2 + lines * LINE_HEIGHT;
+    let mut img = ImageBuffer::from_pixel(width, height, BACKGROUND);
+
+    draw_text(
+        &mut img,
+        PADDING,
+        PADDING,
+        &fit(&deck.title, LINE_CHARS),
+        TEXT,
+    );
+    draw_text(
+        &mut img,
+        PADDING,
+        PADDING + LINE_HEIGHT,
+        &fit(&deck.subtitle, LINE_CHARS),
+        MUTED,
+    );
+
+    let name_x = PADDING + (COST_CHARS as u32 + 1) * ADVANCE;
+    let tier_x = name_x + (NAME_CHARS as u32 + 1) * ADVANCE;
+    let synergy_x = tier_x + (TIER_CHARS as u32 + 1) * ADVANCE;
+
+    for (i, row) in deck.rows.iter().enumerate() {
+        let y = PADDING + (3 + i as u32) * LINE_HEIGHT;
+        let background = if !row.synergies.is_empty() {
+            Some(SYNERGY_ROW)
+        } else if i % 2 == 1 {
+            Some(ROW_STRIPE)
+        } else {
+            None
+        };
+        if let Some(color) = background {
+            fill_rect(
+                &mut img,
+                PADDING / 2,
+                y - SCALE * 2,
+                width - PADDING,
+                LINE_HEIGHT,
+                color,
+            );
+        }
+
+        if let Some(cost) = row.cost {
+            let cost = format!("{:>width$}", cost, width = COST_CHARS);
+            draw_text(&mut img, PADDING, y, &cost, MUTED);
+        }
+        draw_text(&mut img, name_x, y, &fit(&row.name, NAME_CHARS), TEXT);
+        if let Some(tier) = &row.tier {
+            draw_text(
+                &mut img,
+                tier_x,
+                y,
+                &fit(tier, TIER_CHARS),
+                tier_color(tier),
+            );
+        }
+        if !row.synergies.is_empty() {
+            let synergies = fit(&row.synergies.join("; "), SYNERGY_CHARS);
+            draw_text(&mut img, synergy_x, y, &synergies, SYNERGY_TEXT);
+        }
+    }
+
+    img
+}
+
+/// Cut `text` to `max_chars`, ending in "..." when shortened
+fn fit(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
+}
+
+fn fill_rect(img: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    for py in y..(y + height).min(img.height()) {
+        for px in x..(x + width).min(img.width()) {
+            img.put_pixel(px, py, color);
+        }
+    }
+}
+
+fn draw_text(img: &mut RgbaImage, x: u32, y: u32, text: &str, color: Rgba<u8>) {
+    for (i, ch) in text.chars().enumerate() {
+        let glyph = glyph(ch);
+        let gx = x + i as u32 * ADVANCE;
+        for (col, bits) in glyph.iter().enumerate() {
+            for row in 0..7 {
+                if (bits >> row) & 1 == 1 {
+                    fill_rect(
+                        img,
+                        gx + col as u32 * SCALE,
+                        y + row * SCALE,
+                        SCALE,
+                        SCALE,
+                        color,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Columns of `ch`, least significant bit at the top
+fn glyph(ch: char) -> &'static [u8; 5] {
+    let ch = fold(ch);
+    if (' '..='~').contains(&ch) {
+        &FONT[ch as usize - 0x20]
+    } else {
+        &FONT['?' as usize - 0x20]
+    }
+}
+
+/// Map common accented letters to the ASCII letter the font has
+fn fold(ch: char) -> char {
+    match ch {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+        'ç' => 'c',
+        'Ç' => 'C',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'È' | 'É' | 'Ê' | 'Ë' => 'E',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'ñ' => 'n',
+        'Ñ' => 'N',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' => 'O',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+        'ß' => 's',
+        '’' | '‘' => '\'',
+        '–' | '—' => '-',
+        '·' => '.',
+        _ => ch,
+    }
+}
+
+/// 5x7 glyphs for ' ' through '~'
+#[rustfmt::skip]
+const FONT: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_truncates_with_ellipsis() {
+        assert_eq!(fit("Just Cause", 26), "Just Cause");
+        assert_eq!(fit("Shift triggers Valor gain", 12), "Shift tri...");
+        assert_eq!(fit("Shift triggers Valor gain", 9), "Shift...");
+        assert_eq!(fit("Pestdoktor", 10), "Pestdoktor");
+    }
+
+    #[test]
+    fn test_glyph_fallbacks() {
+        assert_eq!(glyph('é'), glyph('e'));
+        assert_eq!(glyph('Ö'), glyph('O'));
+        assert_eq!(glyph('☃'), glyph('?'));
+        assert_eq!(glyph(' '), &[0; 5]);
+    }
+
+    #[test]
+    fn test_render_size_and_highlight() {
+        let deck = DeckImage {
+            title: "Fel (Unchained)".to_string(),
+            subtitle: "Covenant 10 - Ring 3".to_string(),
+            rows: vec![
+                DeckImageRow {
+                    name: "Just Cause".to_string(),
+                    cost: Some(0),
+                    tier: Some("B".to_string()),
+                    synergies: vec!["Shift triggers Valor gain".to_string()],
+                },
+                DeckImageRow {
+                    name: "Unknown".to_string(),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let img = render(&deck);
+        assert_eq!(img.width(), PADDING * 2 + LINE_CHARS as u32 * ADVANCE);
+        assert_eq!(img.height(), PADDING * 2 + 5 * LINE_HEIGHT);
+
+        // The synergy row is highlighted at its left edge
+        let first_row_y = PADDING + 3 * LINE_HEIGHT;
+        assert_eq!(*img.get_pixel(PADDING / 2, first_row_y), SYNERGY_ROW);
+        assert!(img.pixels().any(|p| *p == SYNERGY_TEXT));
+        assert!(img.pixels().any(|p| *p == tier_color("B")));
+        // The title is drawn
+        assert!(img.pixels().any(|p| *p == TEXT));
+    }
+}
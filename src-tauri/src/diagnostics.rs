@@ -0,0 +1,279 @@
+//! Diagnostics bundle for bug reports
+//!
+//! One zip users can attach to an issue: `diagnostics.json` describing the
+//! app, OS, GPU, displays, OCR setup, settings and database schema, plus the
+//! log files and the most recent OCR debug images. The database itself is
+//! not included.
+
+use crate::commands::ocr::CaptureRegionInfo;
+use crate::database::access::DatabaseAccess;
+use crate::database::migrations;
+use crate::logging;
+use crate::ocr::capture;
+use crate::ocr::status::OcrStatus;
+use crate::settings::{self, AppSettings};
+use crate::zip_archive;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const SUMMARY_ENTRY: &str = "diagnostics.json";
+
+/// Debug images included, newest first
+const MAX_DEBUG_IMAGES: usize = 10;
+
+/// App build and host system
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SystemInfo {
+    pub app_version: String,
+    pub os: String,
+    pub os_family: String,
+    pub arch: String,
+    /// Whether this build includes the `ocr` feature
+    pub ocr_feature: bool,
+    /// Graphics adapters as reported by the OS, when it can be asked
+    pub gpus: Vec<String>,
+}
+
+/// One monitor, as the capture code sees it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DisplayInfo {
+    pub logical_width: u32,
+    pub logical_height: u32,
+    pub physical_width: u32,
+    pub physical_height: u32,
+    pub scale_factor: f32,
+    pub is_primary: bool,
+}
+
+/// Everything in `diagnostics.json`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiagnosticsSummary {
+    pub generated_at: String,
+    pub system: SystemInfo,
+    pub displays: Vec<DisplayInfo>,
+    /// Why display metrics are missing, if they are
+    pub display_error: Option<String>,
+    pub ocr_status: OcrStatus,
+    pub capture_regions: Vec<CaptureRegionInfo>,
+    pub settings: Option<AppSettings>,
+    pub schema_version: Option<i32>,
+    /// Names of the log files and debug images in the bundle
+    pub log_files: Vec<String>,
+    pub debug_images: Vec<String>,
+}
+
+/// Inputs gathered from the running app
+pub struct DiagnosticsSources<'a> {
+    pub access: &'a DatabaseAccess,
+    pub log_dir: &'a Path,
+    pub debug_image_dir: Option<&'a Path>,
+    pub ocr_status: OcrStatus,
+    pub capture_regions: Vec<CaptureRegionInfo>,
+}
+
+/// Lines of a command's output, or nothing if it cannot run
+fn command_lines(program: &str, args: &[&str]) -> Vec<String> {
+    let Ok(output) = Command::new(program).args(args).output() else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Graphics adapter names, asked from the OS's own tools
+fn gpu_names() -> Vec<String> {
+    match std::env::consts::OS {
+        "windows" => command_lines(
+            "powershell",
+            &[
+                "-NoProfile",
+                "-Command",
+                "Get-CimInstance Win32_VideoController | ForEach-Object { $_.Name }",
+            ],
+        ),
+        "macos" => command_lines("system_profiler", &["SPDisplaysDataType"])
+            .into_iter()
+            .filter_map(|line| {
+                line.strip_prefix("Chipset Model:")
+                    .map(|s| s.trim().to_string())
+            })
+            .collect(),
+        _ => command_lines("lspci", &[])
+            .into_iter()
+            .filter(|line| line.contains("VGA") || line.contains("3D controller"))
+            .collect(),
+    }
+}
+
+fn system_info() -> SystemInfo {
+    SystemInfo {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        os_family: std::env::consts::FAMILY.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        ocr_feature: cfg!(feature = "ocr"),
+        gpus: gpu_names(),
+    }
+}
+
+/// The newest `limit` PNG files in `dir`, newest first
+fn recent_images(dir: &Path, limit: usize) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut images: Vec<_> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "png"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    images.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    images
+        .into_iter()
+        .take(limit)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Build the diagnostics zip
+///
+/// Parts that cannot be read (a missing database, an unreadable log) are
+/// left out rather than failing the bundle, since it is most needed when
+/// something is broken.
+pub fn build(sources: DiagnosticsSources) -> io::Result<(Vec<u8>, DiagnosticsSummary)> {
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+
+    let mut log_files = Vec::new();
+    for log in logging::list_log_files(sources.log_dir) {
+        let path = Path::new(&log.path);
+        if let Ok(contents) = fs::read(path) {
+            log_files.push(file_name(path));
+            files.push((format!("logs/{}", file_name(path)), contents));
+        }
+    }
+
+    let mut debug_images = Vec::new();
+    if let Some(dir) = sources.debug_image_dir {
+        for path in recent_images(dir, MAX_DEBUG_IMAGES) {
+            if let Ok(contents) = fs::read(&path) {
+                debug_images.push(file_name(&path));
+                files.push((format!("debug-images/{}", file_name(&path)), contents));
+            }
+        }
+    }
+
+    let (displays, display_error) = match capture::get_display_metrics() {
+        Ok(metrics) => (
+            metrics
+                .iter()
+                .map(|m| {
+                    let (physical_width, physical_height) = m.physical_size();
+                    DisplayInfo {
+                        logical_width: m.logical_width,
+                        logical_height: m.logical_height,
+                        physical_width,
+                        physical_height,
+                        scale_factor: m.scale_factor,
+                        is_primary: m.is_primary,
+                    }
+                })
+                .collect(),
+            None,
+        ),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+
+    let summary = DiagnosticsSummary {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        system: system_info(),
+        displays,
+        display_error,
+        ocr_status: sources.ocr_status,
+        capture_regions: sources.capture_regions,
+        settings: settings::load(sources.access).ok(),
+        schema_version: sources.access.read(migrations::schema_version).ok(),
+        log_files,
+        debug_images,
+    };
+
+    let json = serde_json::to_vec_pretty(&summary)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut entries: Vec<(&str, &[u8])> = vec![(SUMMARY_ENTRY, &json)];
+    entries.extend(
+        files
+            .iter()
+            .map(|(name, contents)| (name.as_str(), contents.as_slice())),
+    );
+    Ok((zip_archive::write(&entries)?, summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::recognize::RecognizeConfig;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_bundle_contents() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("mt2_draft.db");
+        crate::database::init(&db_path).unwrap();
+
+        let log_dir = dir.path().join("logs");
+        fs::create_dir_all(&log_dir).unwrap();
+        fs::write(log_dir.join(logging::LOG_FILE_NAME), "current").unwrap();
+        fs::write(
+            log_dir.join(format!("{}.1", logging::LOG_FILE_NAME)),
+            "older",
+        )
+        .unwrap();
+
+        let image_dir = dir.path().join("debug");
+        fs::create_dir_all(&image_dir).unwrap();
+        fs::write(image_dir.join("debug_region_0.png"), b"png").unwrap();
+        fs::write(image_dir.join("notes.txt"), b"skipped").unwrap();
+
+        let (bundle, summary) = build(DiagnosticsSources {
+            access: &DatabaseAccess::new(db_path),
+            log_dir: &log_dir,
+            debug_image_dir: Some(&image_dir),
+            ocr_status: crate::ocr::status::ocr_status(&RecognizeConfig::default()),
+            capture_regions: Vec::new(),
+        })
+        .unwrap();
+
+        assert_eq!(summary.schema_version, Some(migrations::CURRENT_VERSION));
+        assert!(summary.settings.is_some());
+        assert_eq!(summary.debug_images, ["debug_region_0.png"]);
+        assert!(!summary.displays.is_empty() || summary.display_error.is_some());
+
+        let entries = zip_archive::read(&bundle).unwrap();
+        let names: Vec<_> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                SUMMARY_ENTRY,
+                "logs/mt2_draft_assistant.log",
+                "logs/mt2_draft_assistant.log.1",
+                "debug-images/debug_region_0.png",
+            ]
+        );
+        let json: serde_json::Value = serde_json::from_slice(&entries[0].1).unwrap();
+        assert_eq!(json["system"]["app_version"], env!("CARGO_PKG_VERSION"));
+    }
+}
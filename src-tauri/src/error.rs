@@ -0,0 +1,164 @@
+//! Errors returned to the frontend
+//!
+//! Commands that fail with `AppError` reach the frontend as
+//! `{ "code": "...", "message": "..." }`, so the UI can branch on `code`
+//! and still show `message`. Module errors convert into it with `?`; plain
+//! `String` errors from older helpers become `internal`.
+
+use crate::app_data::AppDataError;
+use crate::commands::cards::CardError;
+use crate::commands::scoring::ScoringError;
+use crate::ocr::{CaptureError, OcrPipelineError, PreprocessError, RecognizeError};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+/// Machine-readable error kind sent with every `AppError`
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    NotFound,
+    InvalidInput,
+    Database,
+    Capture,
+    Preprocess,
+    Recognize,
+    /// A feature not available in this build or on this machine
+    Unavailable,
+    /// The operation was stopped on request before it finished
+    Cancelled,
+    Internal,
+}
+
+/// Error type returned by Tauri commands
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Database error: {0}")]
+    Database(String),
+    #[error("Capture error: {0}")]
+    Capture(#[from] CaptureError),
+    #[error("Preprocess error: {0}")]
+    Preprocess(#[from] PreprocessError),
+    #[error("Recognize error: {0}")]
+    Recognize(#[from] RecognizeError),
+    #[error("{0}")]
+    Unavailable(String),
+    #[error("{0}")]
+    Cancelled(String),
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::InvalidInput(_) => ErrorCode::InvalidInput,
+            AppError::Database(_) => ErrorCode::Database,
+            AppError::Capture(_) => ErrorCode::Capture,
+            AppError::Preprocess(_) => ErrorCode::Preprocess,
+            AppError::Recognize(_) => ErrorCode::Recognize,
+            AppError::Unavailable(_) => ErrorCode::Unavailable,
+            AppError::Cancelled(_) => ErrorCode::Cancelled,
+            AppError::Internal(_) => ErrorCode::Internal,
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("code", &self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<CardError> for AppError {
+    fn from(err: CardError) -> Self {
+        match err {
+            CardError::DatabaseError(msg) => AppError::Database(msg),
+            CardError::CardNotFound(_) => AppError::NotFound(err.to_string()),
+            CardError::InvalidQuery(msg) => AppError::InvalidInput(msg),
+        }
+    }
+}
+
+impl From<ScoringError> for AppError {
+    fn from(err: ScoringError) -> Self {
+        match err {
+            ScoringError::DatabaseError(msg) => AppError::Database(msg),
+            ScoringError::CardNotFound(_) => AppError::NotFound(err.to_string()),
+            ScoringError::InvalidInput(msg) => AppError::InvalidInput(msg),
+        }
+    }
+}
+
+impl From<OcrPipelineError> for AppError {
+    fn from(err: OcrPipelineError) -> Self {
+        match err {
+            OcrPipelineError::Capture(e) => e.into(),
+            OcrPipelineError::Preprocess(e) => e.into(),
+            OcrPipelineError::Recognize(e) => e.into(),
+            OcrPipelineError::Configuration(msg) => AppError::InvalidInput(msg),
+            OcrPipelineError::Stage(e) => AppError::Internal(e.to_string()),
+        }
+    }
+}
+
+impl From<AppDataError> for AppError {
+    fn from(err: AppDataError) -> Self {
+        match err {
+            AppDataError::Database(msg) => AppError::Database(msg),
+            AppDataError::Io(_) => AppError::Internal(err.to_string()),
+            AppDataError::InvalidBundle(_) => AppError::InvalidInput(err.to_string()),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(err: rusqlite::Error) -> Self {
+        AppError::Database(err.to_string())
+    }
+}
+
+impl From<String> for AppError {
+    fn from(msg: String) -> Self {
+        AppError::Internal(msg)
+    }
+}
+
+/// Result type for Tauri commands
+pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let err = AppError::from(CardError::CardNotFound("Fel".to_string()));
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({ "code": "not_found", "message": "Card 'Fel' not found" })
+        );
+    }
+
+    #[test]
+    fn test_pipeline_errors_keep_their_kind() {
+        let err = AppError::from(OcrPipelineError::Capture(CaptureError::InvalidRegion));
+        assert_eq!(err.code(), ErrorCode::Capture);
+
+        let err = AppError::from(OcrPipelineError::Preprocess(PreprocessError::EmptyImage));
+        assert_eq!(err.code(), ErrorCode::Preprocess);
+        assert_eq!(err.to_string(), "Preprocess error: Image is empty");
+
+        let err = AppError::from(ScoringError::InvalidInput("Ring number".to_string()));
+        assert_eq!(err.code(), ErrorCode::InvalidInput);
+    }
+}
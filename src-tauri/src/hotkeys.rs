@@ -0,0 +1,409 @@
+//! Global keyboard shortcuts
+//!
+//! Shortcuts run a detection or toggle the overlay while the game has
+//! focus. Bindings are validated and normalized here, persisted in the
+//! `hotkeys` settings section, and registered with the OS through the Tauri
+//! global-shortcut plugin.
+
+use crate::commands::ocr::detect_in_background;
+use crate::commands::window::toggle_overlay_window;
+use crate::database::access::DatabaseAccess;
+use crate::settings::{self, SettingsError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+/// Error type for hotkey operations
+#[derive(Debug)]
+pub enum HotkeyError {
+    InvalidAccelerator(String),
+    DuplicateBinding(String),
+    RegistrationFailed(String),
+    SettingsError(SettingsError),
+}
+
+impl fmt::Display for HotkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HotkeyError::InvalidAccelerator(msg) => write!(f, "Invalid shortcut: {}", msg),
+            HotkeyError::DuplicateBinding(accel) => {
+                write!(f, "Shortcut {} is bound to more than one action", accel)
+            }
+            HotkeyError::RegistrationFailed(msg) => {
+                write!(f, "Failed to register shortcut: {}", msg)
+            }
+            HotkeyError::SettingsError(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for HotkeyError {}
+
+impl From<SettingsError> for HotkeyError {
+    fn from(e: SettingsError) -> Self {
+        HotkeyError::SettingsError(e)
+    }
+}
+
+/// Something a shortcut can trigger
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    Detect,
+    ToggleOverlay,
+}
+
+/// Accelerator bound to each action; `None` leaves the action unbound
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct HotkeyBindings {
+    pub detect: Option<String>,
+    pub toggle_overlay: Option<String>,
+}
+
+impl Default for HotkeyBindings {
+    fn default() -> Self {
+        Self {
+            detect: Some("Ctrl+Shift+D".to_string()),
+            toggle_overlay: Some("Ctrl+Shift+O".to_string()),
+        }
+    }
+}
+
+impl HotkeyBindings {
+    /// Bound actions with their accelerators
+    pub fn iter(&self) -> impl Iterator<Item = (HotkeyAction, &str)> {
+        [
+            (HotkeyAction::Detect, self.detect.as_deref()),
+            (HotkeyAction::ToggleOverlay, self.toggle_overlay.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(action, accel)| accel.map(|accel| (action, accel)))
+    }
+
+    /// Copy with every accelerator normalized, rejecting invalid or shared shortcuts
+    pub fn normalized(&self) -> Result<Self, HotkeyError> {
+        let normalize = |accel: &Option<String>| {
+            accel
+                .as_deref()
+                .map(str::trim)
+                .filter(|accel| !accel.is_empty())
+                .map(normalize_accelerator)
+                .transpose()
+        };
+
+        let bindings = Self {
+            detect: normalize(&self.detect)?,
+            toggle_overlay: normalize(&self.toggle_overlay)?,
+        };
+
+        let mut seen: Vec<&str> = Vec::new();
+        for (_, accel) in bindings.iter() {
+            if seen.contains(&accel) {
+                return Err(HotkeyError::DuplicateBinding(accel.to_string()));
+            }
+            seen.push(accel);
+        }
+
+        Ok(bindings)
+    }
+
+    /// Action bound to a pressed shortcut
+    pub fn action_for(&self, shortcut: &Shortcut) -> Option<HotkeyAction> {
+        self.iter()
+            .find(|(_, accel)| accel.parse::<Shortcut>().ok().as_ref() == Some(shortcut))
+            .map(|(action, _)| action)
+    }
+}
+
+/// Modifier spellings accepted in accelerators, in canonical output order
+const MODIFIERS: &[(&str, &[&str])] = &[
+    (
+        "CmdOrCtrl",
+        &[
+            "cmdorctrl",
+            "commandorcontrol",
+            "cmdorcontrol",
+            "commandorctrl",
+        ],
+    ),
+    ("Ctrl", &["ctrl", "control"]),
+    ("Alt", &["alt", "option"]),
+    ("Shift", &["shift"]),
+    ("Super", &["super", "cmd", "command", "meta", "win"]),
+];
+
+/// Named keys accepted in accelerators
+const NAMED_KEYS: &[&str] = &[
+    "Space",
+    "Tab",
+    "Enter",
+    "Escape",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "Up",
+    "Down",
+    "Left",
+    "Right",
+];
+
+fn function_key(key: &str) -> Option<String> {
+    let number: u8 = key.strip_prefix(['F', 'f'])?.parse().ok()?;
+    (1..=24).contains(&number).then(|| format!("F{}", number))
+}
+
+/// Normalize an accelerator such as `ctrl+shift+d` to `Ctrl+Shift+D`
+///
+/// An accelerator is any set of modifiers plus exactly one key: a letter,
+/// digit, F1-F24 or a named key. Keys other than function keys need at
+/// least one modifier so the shortcut cannot swallow normal typing.
+pub fn normalize_accelerator(accelerator: &str) -> Result<String, HotkeyError> {
+    let invalid = |msg: String| HotkeyError::InvalidAccelerator(msg);
+
+    let mut modifiers = [false; MODIFIERS.len()];
+    let mut key: Option<String> = None;
+
+    for token in accelerator.split('+').map(str::trim) {
+        if token.is_empty() {
+            return Err(invalid(format!("'{}' has an empty key", accelerator)));
+        }
+
+        let lower = token.to_ascii_lowercase();
+        if let Some(i) = MODIFIERS
+            .iter()
+            .position(|(_, spellings)| spellings.contains(&lower.as_str()))
+        {
+            modifiers[i] = true;
+            continue;
+        }
+
+        let normalized = if token.len() == 1 && token.chars().all(|c| c.is_ascii_alphanumeric()) {
+            token.to_ascii_uppercase()
+        } else if let Some(f) = function_key(token) {
+            f
+        } else if let Some(named) = NAMED_KEYS.iter().find(|k| k.eq_ignore_ascii_case(token)) {
+            named.to_string()
+        } else {
+            return Err(invalid(format!("unknown key '{}'", token)));
+        };
+
+        if key.replace(normalized).is_some() {
+            return Err(invalid(format!("'{}' has more than one key", accelerator)));
+        }
+    }
+
+    let key = key.ok_or_else(|| invalid(format!("'{}' has no key", accelerator)))?;
+    if !modifiers.contains(&true) && function_key(&key).is_none() {
+        return Err(invalid(format!(
+            "'{}' needs a modifier such as Ctrl",
+            accelerator
+        )));
+    }
+
+    let mut parts: Vec<String> = MODIFIERS
+        .iter()
+        .zip(modifiers)
+        .filter(|(_, used)| *used)
+        .map(|((name, _), _)| name.to_string())
+        .collect();
+    parts.push(key);
+    Ok(parts.join("+"))
+}
+
+/// Load saved bindings, falling back to the defaults
+pub fn load_bindings(access: &DatabaseAccess) -> Result<HotkeyBindings, HotkeyError> {
+    Ok(settings::load_section(access, settings::HOTKEYS_KEY)?)
+}
+
+/// Persist bindings
+pub fn save_bindings(
+    access: &DatabaseAccess,
+    bindings: &HotkeyBindings,
+) -> Result<(), HotkeyError> {
+    settings::save_section(access, settings::HOTKEYS_KEY, bindings)?;
+    Ok(())
+}
+
+/// Bindings currently registered with the OS
+pub struct HotkeyState {
+    bindings: Mutex<HotkeyBindings>,
+}
+
+impl HotkeyState {
+    pub fn new(bindings: HotkeyBindings) -> Self {
+        Self {
+            bindings: Mutex::new(bindings),
+        }
+    }
+
+    pub fn bindings(&self) -> HotkeyBindings {
+        match self.bindings.lock() {
+            Ok(bindings) => bindings.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    fn set_bindings(&self, bindings: HotkeyBindings) {
+        match self.bindings.lock() {
+            Ok(mut current) => *current = bindings,
+            Err(poisoned) => *poisoned.into_inner() = bindings,
+        }
+    }
+}
+
+/// Replace every registered shortcut with `bindings`
+pub fn register_bindings(app: &AppHandle, bindings: &HotkeyBindings) -> Result<(), HotkeyError> {
+    let shortcuts = app.global_shortcut();
+    shortcuts
+        .unregister_all()
+        .map_err(|e| HotkeyError::RegistrationFailed(e.to_string()))?;
+
+    for (_, accel) in bindings.iter() {
+        shortcuts
+            .register(accel)
+            .map_err(|e| HotkeyError::RegistrationFailed(format!("{}: {}", accel, e)))?;
+    }
+
+    Ok(())
+}
+
+/// Validate, register and persist new bindings
+///
+/// If the OS refuses a shortcut (usually because another application
+/// holds it), the previous bindings are restored and nothing is saved.
+pub fn rebind(
+    app: &AppHandle,
+    access: &DatabaseAccess,
+    state: &HotkeyState,
+    bindings: &HotkeyBindings,
+) -> Result<HotkeyBindings, HotkeyError> {
+    let bindings = bindings.normalized()?;
+
+    if let Err(e) = register_bindings(app, &bindings) {
+        if let Err(restore) = register_bindings(app, &state.bindings()) {
+            log::warn!("Failed to restore previous hotkeys: {}", restore);
+        }
+        return Err(e);
+    }
+
+    save_bindings(access, &bindings)?;
+    state.set_bindings(bindings.clone());
+    log::info!("Hotkeys updated: {:?}", bindings);
+    Ok(bindings)
+}
+
+/// Global-shortcut plugin handler; runs the action bound to a pressed shortcut
+pub fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+
+    let action = match app.try_state::<HotkeyState>() {
+        Some(state) => state.bindings().action_for(shortcut),
+        None => None,
+    };
+
+    if let Some(action) = action {
+        log::debug!("Hotkey triggered: {:?}", action);
+        run_action(app, action);
+    }
+}
+
+fn run_action(app: &AppHandle, action: HotkeyAction) {
+    match action {
+        HotkeyAction::Detect => detect_in_background(app),
+        HotkeyAction::ToggleOverlay => {
+            if let Err(e) = toggle_overlay_window(app) {
+                log::warn!("Hotkey overlay toggle failed: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_normalize_accelerator() {
+        assert_eq!(
+            normalize_accelerator("ctrl+shift+d").unwrap(),
+            "Ctrl+Shift+D"
+        );
+        assert_eq!(
+            normalize_accelerator("Shift + Control + o").unwrap(),
+            "Ctrl+Shift+O"
+        );
+        assert_eq!(
+            normalize_accelerator("cmdorctrl+alt+pageup").unwrap(),
+            "CmdOrCtrl+Alt+PageUp"
+        );
+        assert_eq!(normalize_accelerator("f9").unwrap(), "F9");
+
+        assert!(normalize_accelerator("D").is_err());
+        assert!(normalize_accelerator("Ctrl+Shift").is_err());
+        assert!(normalize_accelerator("Ctrl+D+E").is_err());
+        assert!(normalize_accelerator("Ctrl++D").is_err());
+        assert!(normalize_accelerator("Ctrl+Banana").is_err());
+        assert!(normalize_accelerator("Ctrl+F25").is_err());
+    }
+
+    #[test]
+    fn test_bindings_normalized_rejects_duplicates() {
+        let bindings = HotkeyBindings {
+            detect: Some("ctrl+shift+d".to_string()),
+            toggle_overlay: Some(" ".to_string()),
+        };
+        let normalized = bindings.normalized().unwrap();
+        assert_eq!(normalized.detect.as_deref(), Some("Ctrl+Shift+D"));
+        assert_eq!(normalized.toggle_overlay, None);
+
+        let bindings = HotkeyBindings {
+            detect: Some("Ctrl+Shift+D".to_string()),
+            toggle_overlay: Some("shift+ctrl+d".to_string()),
+        };
+        assert!(matches!(
+            bindings.normalized(),
+            Err(HotkeyError::DuplicateBinding(_))
+        ));
+    }
+
+    #[test]
+    fn test_action_for_shortcut() {
+        let bindings = HotkeyBindings::default();
+        let detect: Shortcut = "Ctrl+Shift+D".parse().unwrap();
+        let toggle: Shortcut = "Ctrl+Shift+O".parse().unwrap();
+        let other: Shortcut = "Ctrl+Shift+X".parse().unwrap();
+
+        assert_eq!(bindings.action_for(&detect), Some(HotkeyAction::Detect));
+        assert_eq!(
+            bindings.action_for(&toggle),
+            Some(HotkeyAction::ToggleOverlay)
+        );
+        assert_eq!(bindings.action_for(&other), None);
+    }
+
+    #[test]
+    fn test_bindings_persist() {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let access = DatabaseAccess::new(temp_file.path().to_path_buf());
+
+        assert_eq!(load_bindings(&access).unwrap(), HotkeyBindings::default());
+
+        let bindings = HotkeyBindings {
+            detect: Some("Alt+F2".to_string()),
+            toggle_overlay: None,
+        };
+        save_bindings(&access, &bindings).unwrap();
+        assert_eq!(load_bindings(&access).unwrap(), bindings);
+    }
+}
@@ -1,71 +1,321 @@
+pub mod app_data;
+pub mod cli;
 pub mod commands;
+pub mod crash;
 pub mod database;
+pub mod deck_image;
+pub mod diagnostics;
+pub mod error;
+pub mod hotkeys;
 pub mod logging;
+pub mod map;
 pub mod ocr;
+pub mod perf;
+pub mod profiles;
 pub mod scoring;
+pub mod server;
+pub mod session;
+pub mod settings;
+pub mod sync;
+pub mod window_state;
+pub mod zip_archive;
 
 use commands::ocr::OcrState;
-use tauri::Manager;
+use ocr::controller::OcrSettings;
+use tauri::{Emitter, Manager};
 
 pub fn run() {
     // Initialize logging
     logging::init();
+    perf::init();
+
+    // Leave a crash report behind instead of dying silently
+    let crash_dir = logging::log_dir();
+    crash::install(crash_dir.clone());
+    if crash::report_path(&crash_dir).exists() {
+        log::warn!("The previous run crashed; report saved in {}", crash_dir.display());
+    }
     
     log::info!("Starting MT2 Draft Assistant");
     
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(hotkeys::handle_shortcut)
+                .build(),
+        )
         .setup(|app| {
             log::info!("Running application setup");
             // Initialize database
-            let db_path = app
+            let app_dir = app
                 .path()
                 .app_data_dir()
-                .expect("Failed to get app data dir")
-                .join("mt2_draft.db");
+                .expect("Failed to get app data dir");
+            let db_path = profiles::active_database_path(&app_dir);
             
             database::init(&db_path)?;
+
+            // Reads and queued writes, shared with the OCR thread
+            let access = database::access::DatabaseAccess::new(db_path.clone());
+            app.manage(access.clone());
+
+            // Apply saved log settings before the rest of setup logs anything
+            if let Err(e) = settings::load_section::<settings::LoggingPreferences>(&access, settings::LOGGING_KEY)
+                .and_then(|prefs| prefs.apply())
+            {
+                log::warn!("Failed to load log settings, using defaults: {}", e);
+            }
             
             // Store database path in app state
-            app.manage(database::DatabaseState::new(db_path));
+            app.manage(database::DatabaseState::new(db_path.clone()));
             
-            // Initialize OCR state
-            app.manage(OcrState::new());
+            app.manage(session::SessionState::default());
+            app.manage(commands::export::ExportState::default());
+
+            // Cards are loaded on first use and shared with the OCR thread
+            let card_cache = database::cache::CardCache::default();
+            app.manage(card_cache.clone());
+
+            // Initialize OCR controller with saved preferences; continuous
+            // detection results go to the frontend
+            let mut ocr_settings = OcrSettings::default();
+            match settings::load_section::<settings::OcrPreferences>(&access, settings::OCR_KEY) {
+                Ok(prefs) => prefs.apply(&mut ocr_settings.detection),
+                Err(e) => log::warn!("Failed to load OCR settings, using defaults: {}", e),
+            }
+            match settings::load_section::<settings::RegionPreferences>(
+                &access,
+                settings::REGIONS_KEY,
+            ) {
+                Ok(regions) => regions.apply(&mut ocr_settings.detection),
+                Err(e) => log::warn!("Failed to load capture regions, using defaults: {}", e),
+            }
+            // The overlay server is fed from the same detections
+            // and its HTTP endpoints act like the hotkeys
+            let actions_handle = app.handle().clone();
+            app.manage(server::ServerState::default().with_actions(std::sync::Arc::new(
+                move |action| server::run_action(&actions_handle, action),
+            )));
+
+            let handle = app.handle().clone();
+            let mut draft_screen = commands::window::DraftScreenWatcher::default();
+            app.manage(OcrState::spawn(access.clone(), card_cache, ocr_settings, move |response| {
+                if let Err(e) = handle.emit("ocr-detection", response) {
+                    log::warn!("Failed to emit OCR detection: {}", e);
+                }
+                handle.state::<session::SessionState>().note_detection(response);
+                if response.new_offer {
+                    commands::ocr::emit_new_offer(&handle, response);
+                }
+                server::publish_detection(&handle, response);
+                if let Some(on_draft_screen) = draft_screen.update(response.is_draft_screen()) {
+                    commands::window::auto_overlay_on_draft_screen(&handle, on_draft_screen);
+                }
+            }));
+            commands::ocr::spawn_archive_compaction(app.handle().clone());
+            sync::spawn_retry(access.clone());
+
+            // Resume the overlay server if it was left on
+            match server::load_config(&access) {
+                Ok(mut config) if config.enabled => {
+                    if let Err(e) = app.state::<server::ServerState>().start(&mut config, false) {
+                        log::warn!("Failed to start overlay server: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to load overlay server settings: {}", e),
+            }
+
+            // Register saved global hotkeys; a shortcut held by another app is not fatal
+            let bindings = hotkeys::load_bindings(&access).unwrap_or_else(|e| {
+                log::warn!("Failed to load hotkeys, using defaults: {}", e);
+                hotkeys::HotkeyBindings::default()
+            });
+            if let Err(e) = hotkeys::register_bindings(app.handle(), &bindings) {
+                log::warn!("Failed to register hotkeys: {}", e);
+            }
+            app.manage(hotkeys::HotkeyState::new(bindings));
+
+            if let Err(e) = commands::window::restore_overlay_settings(app.handle(), &access) {
+                log::warn!("Failed to restore overlay settings: {}", e);
+            }
+            if let Err(e) = window_state::restore_overlay_state(app.handle(), &access) {
+                log::warn!("Failed to restore overlay window state: {}", e);
+            }
             
             Ok(())
         })
+        .on_window_event(|window, event| {
+            // Dragging the overlay bypasses the window commands; capture it on exit
+            if window.label() == "main"
+                && matches!(event, tauri::WindowEvent::CloseRequested { .. })
+            {
+                window_state::remember_overlay_state(window.app_handle());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
+            // Draft session commands
+            commands::session::session_get_state,
+            commands::session::session_add_card,
+            commands::session::session_remove_card,
+            commands::session::undo_last_pick,
+            commands::session::redo_pick,
+            commands::session::session_set_champion,
+            commands::session::session_set_ring,
+            commands::session::session_set_pact_shards,
+            commands::session::session_new_run,
+            commands::session::session_end_run,
+            
             // Card commands
             commands::cards::get_card_by_name,
+            commands::cards::get_card_by_id,
+            commands::cards::get_cards_by_ids,
             commands::cards::get_cards_by_clan,
             commands::cards::search_cards,
+            commands::cards::get_cards_by_keyword,
             commands::cards::get_all_cards,
+            commands::cards::get_cards_by_tag,
+            commands::cards::list_card_tags,
+            commands::cards::set_card_note,
+            commands::cards::delete_card_note,
+            commands::cards::add_card_tag,
+            commands::cards::remove_card_tag,
+            commands::cards::get_favorites,
+            commands::cards::toggle_favorite,
+            
+            // Database commands
+            commands::database::validate_database,
+            commands::database::get_expansions,
+            commands::database::set_expansion_active,
+            commands::database::get_audit_log,
             
             // Scoring commands
             commands::scoring::calculate_draft_score,
             commands::scoring::get_synergies,
             commands::scoring::get_context_modifiers,
+            commands::scoring::forecast_draft,
+            commands::scoring::compare_picks,
+            commands::scoring::evaluate_shop,
+            commands::map::recommend_route,
+            commands::scoring::detect_archetype,
+            commands::scoring::get_archetypes,
+            commands::scoring::save_archetype,
+            commands::scoring::delete_archetype,
+            commands::scoring::get_champion_paths,
+            commands::scoring::get_bosses,
+            commands::scoring::validate_scoring_fixtures,
+            
+            // History commands
+            commands::history::record_draft_offer,
+            commands::history::get_card_performance,
+            commands::history::get_card_stats,
+            commands::history::get_champion_stats,
+            commands::history::get_run_history,
+            commands::history::get_run_summary,
+            commands::history::analyze_run,
+            commands::history::get_meta_stats,
+            commands::history::archive_run,
+            commands::history::delete_run,
+            commands::history::restore_run,
+            commands::history::purge_deleted_runs,
+            
+            // Community stats commands
+            commands::community::import_community_stats,
+            commands::community::set_community_stats_enabled,
             
             // OCR commands
             commands::ocr::detect_cards_on_screen,
+            commands::ocr::start_continuous_detection,
+            commands::ocr::stop_continuous_detection,
+            commands::ocr::cancel_detection,
+            commands::ocr::get_continuous_detection_status,
             commands::ocr::calibrate_ocr_regions,
+            commands::ocr::preview_capture_regions,
+            commands::ocr::capture_fullscreen_for_calibration,
             commands::ocr::set_capture_regions,
             commands::ocr::get_capture_regions,
             commands::ocr::reset_capture_regions,
             commands::ocr::update_ocr_config,
+            commands::ocr::set_region_profile,
+            commands::ocr::set_detection_filter,
             commands::ocr::test_ocr_region,
+            commands::ocr::get_detection_history,
+            commands::ocr::report_misdetection,
+            commands::ocr::match_text_to_cards,
+            commands::ocr::calibrate_ocr_confidence,
+            commands::ocr::get_ocr_confusion_report,
+            commands::ocr::set_archive_config,
+            commands::ocr::compact_ocr_archive,
+            commands::ocr::start_ocr_recording,
+            commands::ocr::stop_ocr_recording,
+            commands::ocr::get_ocr_status,
+            commands::ocr::build_glyph_atlas,
+            commands::ocr::get_capture_backend_status,
+            commands::ocr::list_tesseract_languages,
+            commands::ocr::download_tesseract_language,
+            
+            // Settings commands
+            commands::settings::get_settings,
+            commands::settings::update_settings,
+            
+            // Hotkey commands
+            commands::hotkeys::get_hotkey_bindings,
+            commands::hotkeys::set_hotkey_bindings,
+            commands::hotkeys::reset_hotkey_bindings,
+            
+            // Logging commands
+            commands::logging::get_log_files,
+            commands::logging::set_log_level,
+            commands::logging::query_logs,
+            commands::logging::generate_diagnostics_bundle,
+            commands::logging::get_perf_metrics,
+            commands::logging::get_last_crash_report,
+            commands::logging::dismiss_crash_report,
+            
+            // Overlay server commands
+            commands::server::start_overlay_server,
+            commands::server::stop_overlay_server,
+            commands::server::get_overlay_server_status,
+            
+            // Profile commands
+            commands::profiles::list_profiles,
+            commands::profiles::create_profile,
+            commands::profiles::switch_profile,
+            
+            // Run sync commands
+            commands::sync::upload_run,
+            commands::sync::set_auto_upload,
+            commands::sync::get_sync_status,
             
             // Window commands
             commands::window::toggle_overlay,
             commands::window::show_overlay,
             commands::window::hide_overlay,
             commands::window::set_overlay_position,
+            commands::window::set_overlay_size,
+            commands::window::position_overlay_at_regions,
+            commands::window::get_overlay_settings,
+            commands::window::set_overlay_opacity,
+            commands::window::set_overlay_always_on_top,
+            commands::window::set_auto_overlay,
             
             // Export/Import commands
             commands::export::export_deck,
             commands::export::import_deck,
+            commands::export::copy_deck_to_clipboard,
+            commands::export::import_deck_from_clipboard,
             commands::export::export_history_csv,
+            commands::export::cancel_export,
+            commands::export::import_history_csv,
+            commands::export::export_run_report,
+            commands::export::export_app_data,
+            commands::export::import_app_data,
             commands::export::get_export_formats,
+            commands::export::export_deck_image,
+            commands::export::export_share_code,
+            commands::export::import_share_code,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
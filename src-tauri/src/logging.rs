@@ -1,40 +1,201 @@
 //! Logging module for MT2 Draft Assistant
 //!
-//! Provides structured logging to file and console.
+//! Provides structured logging to file and console. The log file is
+//! rotated once it reaches `MAX_LOG_SIZE`: `mt2_draft_assistant.log`
+//! becomes `mt2_draft_assistant.log.1`, older archives shift up and the
+//! oldest beyond `MAX_LOG_ARCHIVES` is deleted.
+//!
+//! Which records are written is decided by a `LogFilter`, an overall level
+//! plus per-module overrides, that can be swapped while the app runs.
+//! Lines are plain text by default, or one JSON `LogEntry` per line; `query`
+//! reads either back for the in-app diagnostics panel.
 
-use std::fs::OpenOptions;
+use std::fs::{self, File, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, RwLock};
 
-use chrono::Local;
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc,
+};
 use log::{LevelFilter, Metadata, Record};
+use serde::{Deserialize, Serialize};
+
+/// Name of the file currently written to
+pub const LOG_FILE_NAME: &str = "mt2_draft_assistant.log";
+/// Size at which the log file is rotated
+pub const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+/// Rotated files kept besides the current one
+pub const MAX_LOG_ARCHIVES: u32 = 3;
+
+/// Timestamp format of plain text lines, in local time
+const TEXT_TIMESTAMP: &str = "%Y-%m-%d %H:%M:%S%.3f";
+/// Entries `query` returns when no limit is given
+pub const DEFAULT_QUERY_LIMIT: usize = 200;
+/// Most entries `query` returns
+pub const MAX_QUERY_LIMIT: usize = 5000;
+
+/// The logger installed by `init`, for changing its filter later
+static LOGGER: OnceLock<&'static AppLogger> = OnceLock::new();
+
+/// Whether `target` is `module` or one of its submodules
+fn module_matches(target: &str, module: &str) -> bool {
+    target
+        .strip_prefix(module)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Overall log level with per-module overrides
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    level: LevelFilter,
+    /// Longest module first, so the most specific override wins
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self::new(LevelFilter::Info, Vec::new())
+    }
+}
+
+impl LogFilter {
+    pub fn new(level: LevelFilter, modules: Vec<(String, LevelFilter)>) -> Self {
+        let mut modules = modules;
+        modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        Self { level, modules }
+    }
+
+    /// Level for records from `target`, a module path
+    ///
+    /// Modules of this crate match with or without the crate name, so
+    /// `ocr` covers `mt2_draft_assistant_lib::ocr::pipeline`.
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        let local = target.strip_prefix(concat!(env!("CARGO_CRATE_NAME"), "::"));
+        self.modules
+            .iter()
+            .find(|(module, _)| {
+                module_matches(target, module)
+                    || local.is_some_and(|local| module_matches(local, module))
+            })
+            .map_or(self.level, |(_, level)| *level)
+    }
+
+    /// Most verbose level any module is logged at
+    pub fn max_level(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.level, Ord::max)
+    }
+}
+
+/// How log lines are written
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// `[timestamp] LEVEL [target] message`
+    #[default]
+    Text,
+    /// One JSON `LogEntry` per line
+    Json,
+}
+
+/// One log record, as written in JSON lines and returned by `query`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// RFC 3339 with milliseconds
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// The open log file and how much has been written to it
+struct LogFile {
+    file: File,
+    size: u64,
+}
 
 /// Custom logger that writes to both file and console
 pub struct AppLogger {
-    log_file: Mutex<std::fs::File>,
-    max_level: LevelFilter,
+    log_dir: PathBuf,
+    log_file: Mutex<LogFile>,
+    filter: RwLock<LogFilter>,
+    format: RwLock<LogFormat>,
+    max_size: u64,
+    max_archives: u32,
+}
+
+fn open_log_file(path: &Path) -> std::io::Result<LogFile> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok(LogFile { file, size })
+}
+
+/// Path of rotated file `index`, or of the current file for 0
+fn archive_path(log_dir: &Path, index: u32) -> PathBuf {
+    match index {
+        0 => log_dir.join(LOG_FILE_NAME),
+        n => log_dir.join(format!("{}.{}", LOG_FILE_NAME, n)),
+    }
 }
 
 impl AppLogger {
     pub fn new(log_dir: PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
-        let log_path = log_dir.join("mt2_draft_assistant.log");
-        
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_path)?;
-        
+        Self::with_limits(log_dir, MAX_LOG_SIZE, MAX_LOG_ARCHIVES)
+    }
+
+    /// Logger rotating at `max_size` bytes and keeping `max_archives` old files
+    pub fn with_limits(
+        log_dir: PathBuf,
+        max_size: u64,
+        max_archives: u32,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let log_file = open_log_file(&archive_path(&log_dir, 0))?;
+
         Ok(Self {
-            log_file: Mutex::new(file),
-            max_level: LevelFilter::Info,
+            log_dir,
+            log_file: Mutex::new(log_file),
+            filter: RwLock::new(LogFilter::default()),
+            format: RwLock::new(LogFormat::default()),
+            max_size,
+            max_archives,
         })
     }
+
+    /// Replace the filter deciding which records are written
+    pub fn set_filter(&self, filter: LogFilter) {
+        *self.filter.write().unwrap_or_else(|e| e.into_inner()) = filter;
+    }
+
+    /// Switch the format of lines written from now on
+    pub fn set_format(&self, format: LogFormat) {
+        *self.format.write().unwrap_or_else(|e| e.into_inner()) = format;
+    }
+
+    /// Shift archives up by one and start a fresh current file
+    fn rotate(&self, log_file: &mut LogFile) -> std::io::Result<()> {
+        let _ = log_file.file.flush();
+        let _ = fs::remove_file(archive_path(&self.log_dir, self.max_archives));
+        for index in (0..self.max_archives).rev() {
+            let from = archive_path(&self.log_dir, index);
+            if from.exists() {
+                fs::rename(&from, archive_path(&self.log_dir, index + 1))?;
+            }
+        }
+        if self.max_archives == 0 {
+            let _ = fs::remove_file(archive_path(&self.log_dir, 0));
+        }
+        *log_file = open_log_file(&archive_path(&self.log_dir, 0))?;
+        Ok(())
+    }
 }
 
 impl log::Log for AppLogger {
     fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.level() <= self.max_level
+        let filter = self.filter.read().unwrap_or_else(|e| e.into_inner());
+        metadata.level() <= filter.level_for(metadata.target())
     }
 
     fn log(&self, record: &Record) {
@@ -42,19 +203,42 @@ impl log::Log for AppLogger {
             return;
         }
 
-        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-        let log_line = format!(
-            "[{}] {:<5} [{}] {}\n",
-            timestamp,
-            record.level(),
-            record.target(),
-            record.args()
-        );
+        let now = Local::now();
+        let format = *self.format.read().unwrap_or_else(|e| e.into_inner());
+        let log_line = match format {
+            LogFormat::Text => format!(
+                "[{}] {:<5} [{}] {}\n",
+                now.format(TEXT_TIMESTAMP),
+                record.level(),
+                record.target(),
+                record.args()
+            ),
+            LogFormat::Json => {
+                let entry = LogEntry {
+                    timestamp: now.to_rfc3339_opts(SecondsFormat::Millis, false),
+                    level: record.level().to_string(),
+                    target: record.target().to_string(),
+                    message: record.args().to_string(),
+                };
+                match serde_json::to_string(&entry) {
+                    Ok(json) => json + "\n",
+                    Err(_) => return,
+                }
+            }
+        };
 
-        // Write to file
-        if let Ok(mut file) = self.log_file.lock() {
-            let _ = file.write_all(log_line.as_bytes());
-            let _ = file.flush();
+        // Write to file, rotating first if the line would overflow it
+        if let Ok(mut log_file) = self.log_file.lock() {
+            let len = log_line.len() as u64;
+            if log_file.size > 0 && log_file.size + len > self.max_size {
+                if let Err(e) = self.rotate(&mut log_file) {
+                    eprintln!("Failed to rotate log file: {}", e);
+                }
+            }
+            if log_file.file.write_all(log_line.as_bytes()).is_ok() {
+                log_file.size += len;
+            }
+            let _ = log_file.file.flush();
         }
 
         // Also print to console for development
@@ -62,21 +246,67 @@ impl log::Log for AppLogger {
     }
 
     fn flush(&self) {
-        if let Ok(mut file) = self.log_file.lock() {
-            let _ = file.flush();
+        if let Ok(mut log_file) = self.log_file.lock() {
+            let _ = log_file.file.flush();
         }
     }
 }
 
-/// Initialize the logging system
-pub fn init() {
-    // Determine log directory
-    let log_dir = if let Some(data_dir) = dirs::data_dir() {
+/// A log file on disk, current or rotated
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogFileInfo {
+    pub path: String,
+    pub size: u64,
+    /// Last write time, RFC 3339
+    pub modified: Option<String>,
+    /// Whether this is the file currently written to
+    pub current: bool,
+}
+
+/// Directory the log files are written to
+pub fn log_dir() -> PathBuf {
+    if let Some(data_dir) = dirs::data_dir() {
         data_dir.join("com.mt2.overlay")
     } else {
         PathBuf::from(".")
+    }
+}
+
+/// The current log file and its archives in `log_dir`, newest first
+pub fn list_log_files(log_dir: &Path) -> Vec<LogFileInfo> {
+    let Ok(entries) = fs::read_dir(log_dir) else {
+        return Vec::new();
     };
 
+    let mut files: Vec<(u32, LogFileInfo)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let index = match name.strip_prefix(LOG_FILE_NAME)? {
+                "" => 0,
+                suffix => suffix.strip_prefix('.')?.parse().ok()?,
+            };
+            let metadata = entry.metadata().ok()?;
+            let info = LogFileInfo {
+                path: entry.path().to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .map(|time| DateTime::<Utc>::from(time).to_rfc3339()),
+                current: index == 0,
+            };
+            Some((index, info))
+        })
+        .collect();
+    files.sort_by_key(|(index, _)| *index);
+    files.into_iter().map(|(_, info)| info).collect()
+}
+
+/// Initialize the logging system
+pub fn init() {
+    let log_dir = log_dir();
+
     // Create log directory if it doesn't exist
     if let Err(e) = std::fs::create_dir_all(&log_dir) {
         eprintln!("Failed to create log directory: {}", e);
@@ -86,12 +316,13 @@ pub fn init() {
     // Create and set logger
     match AppLogger::new(log_dir) {
         Ok(logger) => {
-            let max_level = logger.max_level;
-            if let Err(e) = log::set_boxed_logger(Box::new(logger)) {
+            let logger: &'static AppLogger = Box::leak(Box::new(logger));
+            if let Err(e) = log::set_logger(logger) {
                 eprintln!("Failed to set logger: {}", e);
                 return;
             }
-            log::set_max_level(max_level);
+            let _ = LOGGER.set(logger);
+            log::set_max_level(LogFilter::default().max_level());
         }
         Err(e) => {
             eprintln!("Failed to create logger: {}", e);
@@ -99,6 +330,149 @@ pub fn init() {
     }
 }
 
+/// Switch the installed logger's line format
+pub fn set_format(format: LogFormat) {
+    if let Some(logger) = LOGGER.get() {
+        logger.set_format(format);
+    }
+}
+
+/// Filters for `query`
+#[derive(Debug, Clone, Default)]
+pub struct LogQuery {
+    /// Only entries at this level or more severe
+    pub level: Option<LevelFilter>,
+    pub since: Option<DateTime<FixedOffset>>,
+    /// Case-insensitive text to find in the message or target
+    pub contains: Option<String>,
+    /// At most this many entries, `DEFAULT_QUERY_LIMIT` when `None`
+    pub limit: Option<usize>,
+}
+
+/// Read a `since` bound: RFC 3339, or a local date with optional time
+pub fn parse_since(since: &str) -> Option<DateTime<FixedOffset>> {
+    let since = since.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(since) {
+        return Some(time);
+    }
+    let naive = NaiveDateTime::parse_from_str(since, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(since, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(since, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })?;
+    Some(Local.from_local_datetime(&naive).earliest()?.fixed_offset())
+}
+
+/// Parse a line in either format
+fn parse_line(line: &str) -> Option<(DateTime<FixedOffset>, LogEntry)> {
+    if line.starts_with('{') {
+        let entry: LogEntry = serde_json::from_str(line).ok()?;
+        let time = DateTime::parse_from_rfc3339(&entry.timestamp).ok()?;
+        return Some((time, entry));
+    }
+
+    let rest = line.strip_prefix('[')?;
+    let (timestamp, rest) = rest.split_once("] ")?;
+    let naive = NaiveDateTime::parse_from_str(timestamp, TEXT_TIMESTAMP).ok()?;
+    let time = Local.from_local_datetime(&naive).earliest()?.fixed_offset();
+    let (level, rest) = rest.split_once(" [")?;
+    let level: log::Level = level.trim().parse().ok()?;
+    let (target, message) = rest
+        .split_once("] ")
+        .unwrap_or((rest.trim_end_matches(']'), ""));
+    Some((
+        time,
+        LogEntry {
+            timestamp: time.to_rfc3339_opts(SecondsFormat::Millis, false),
+            level: level.to_string(),
+            target: target.to_string(),
+            message: message.to_string(),
+        },
+    ))
+}
+
+/// Entries of one file, oldest first; lines that do not start an entry
+/// continue the previous message
+fn read_entries(path: &Path) -> Vec<(DateTime<FixedOffset>, LogEntry)> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<(DateTime<FixedOffset>, LogEntry)> = Vec::new();
+    for line in content.lines() {
+        match parse_line(line) {
+            Some(entry) => entries.push(entry),
+            None => {
+                if let Some((_, last)) = entries.last_mut() {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// Recent entries from the log files in `log_dir` matching `query`,
+/// newest first
+pub fn query(log_dir: &Path, query: &LogQuery) -> Vec<LogEntry> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_QUERY_LIMIT)
+        .min(MAX_QUERY_LIMIT);
+    let contains = query.contains.as_deref().map(str::to_lowercase);
+    let matches = |entry: &LogEntry| {
+        let level_ok = match (query.level, entry.level.parse::<log::Level>()) {
+            (Some(max), Ok(level)) => level <= max,
+            (Some(_), Err(_)) => false,
+            (None, _) => true,
+        };
+        let text_ok = match &contains {
+            Some(text) => {
+                entry.message.to_lowercase().contains(text)
+                    || entry.target.to_lowercase().contains(text)
+            }
+            None => true,
+        };
+        level_ok && text_ok
+    };
+
+    let mut found = Vec::new();
+    // Newest file first, each read from its end
+    for file in list_log_files(log_dir) {
+        let mut reached_since = false;
+        for (time, entry) in read_entries(Path::new(&file.path)).into_iter().rev() {
+            if found.len() >= limit {
+                return found;
+            }
+            if query.since.is_some_and(|since| time < since) {
+                reached_since = true;
+                continue;
+            }
+            if matches(&entry) {
+                found.push(entry);
+            }
+        }
+        // Older files only hold older entries
+        if reached_since {
+            break;
+        }
+    }
+    found.truncate(limit);
+    found
+}
+
+/// Apply a new filter to the installed logger
+pub fn set_filter(filter: LogFilter) {
+    log::set_max_level(filter.max_level());
+    if let Some(logger) = LOGGER.get() {
+        logger.set_filter(filter);
+    }
+}
+
 /// Log an error with context
 #[macro_export]
 macro_rules! log_error {
@@ -162,4 +536,146 @@ mod tests {
         let content = fs::read_to_string(log_file).unwrap();
         assert!(content.contains("Test message"));
     }
+
+    fn log_line(logger: &AppLogger, message: &str) {
+        log_record(logger, log::Level::Info, "test", message);
+    }
+
+    fn log_record(logger: &AppLogger, level: log::Level, target: &str, message: &str) {
+        use log::Log;
+        logger.log(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(level)
+                .target(target)
+                .build(),
+        );
+    }
+
+    #[test]
+    fn test_log_file_rotation() {
+        let temp_dir = TempDir::new().unwrap();
+        let logger = AppLogger::with_limits(temp_dir.path().to_path_buf(), 200, 2).unwrap();
+
+        for i in 0..20 {
+            log_line(&logger, &format!("message {:02} with some padding", i));
+        }
+
+        let files = list_log_files(temp_dir.path());
+        assert_eq!(files.len(), 3);
+        assert!(files[0].current);
+        assert!(files[0].path.ends_with(LOG_FILE_NAME));
+        assert!(files[2].path.ends_with(".log.2"));
+        assert!(files.iter().all(|f| f.size <= 200));
+
+        // The newest message is in the current file, the oldest are gone
+        let current = fs::read_to_string(&files[0].path).unwrap();
+        assert!(current.contains("message 19"));
+        let archived = fs::read_to_string(&files[1].path).unwrap();
+        assert!(!archived.contains("message 19"));
+        assert!(!fs::read_to_string(&files[2].path)
+            .unwrap()
+            .contains("message 00"));
+    }
+
+    #[test]
+    fn test_log_filter_levels() {
+        let filter = LogFilter::new(
+            LevelFilter::Info,
+            vec![
+                ("ocr".to_string(), LevelFilter::Warn),
+                ("ocr::pipeline".to_string(), LevelFilter::Debug),
+                ("tao".to_string(), LevelFilter::Off),
+            ],
+        );
+        let local = |module: &str| format!("{}::{}", env!("CARGO_CRATE_NAME"), module);
+
+        assert_eq!(filter.level_for(&local("session")), LevelFilter::Info);
+        assert_eq!(filter.level_for(&local("ocr::matcher")), LevelFilter::Warn);
+        assert_eq!(
+            filter.level_for(&local("ocr::pipeline")),
+            LevelFilter::Debug
+        );
+        assert_eq!(filter.level_for(&local("ocrs")), LevelFilter::Info);
+        assert_eq!(filter.level_for("tao::platform"), LevelFilter::Off);
+        assert_eq!(filter.max_level(), LevelFilter::Debug);
+    }
+
+    #[test]
+    fn test_filter_changes_apply_to_logger() {
+        let temp_dir = TempDir::new().unwrap();
+        let logger = AppLogger::new(temp_dir.path().to_path_buf()).unwrap();
+        let ocr = format!("{}::ocr::pipeline", env!("CARGO_CRATE_NAME"));
+
+        log_record(&logger, log::Level::Debug, &ocr, "debug before");
+        logger.set_filter(LogFilter::new(
+            LevelFilter::Info,
+            vec![("ocr".to_string(), LevelFilter::Debug)],
+        ));
+        log_record(&logger, log::Level::Debug, &ocr, "debug after");
+        log_record(&logger, log::Level::Debug, "other", "other debug");
+
+        let content = fs::read_to_string(temp_dir.path().join(LOG_FILE_NAME)).unwrap();
+        assert!(!content.contains("debug before"));
+        assert!(content.contains("debug after"));
+        assert!(!content.contains("other debug"));
+    }
+
+    #[test]
+    fn test_query_reads_both_formats() {
+        let temp_dir = TempDir::new().unwrap();
+        let logger = AppLogger::new(temp_dir.path().to_path_buf()).unwrap();
+        let ocr = format!("{}::ocr::pipeline", env!("CARGO_CRATE_NAME"));
+
+        log_record(&logger, log::Level::Info, "session", "Run started");
+        log_record(&logger, log::Level::Warn, &ocr, "Low confidence\nmore");
+        logger.set_format(LogFormat::Json);
+        log_record(&logger, log::Level::Error, &ocr, "Capture failed");
+        log_record(&logger, log::Level::Info, "session", "Run ended");
+
+        let all = query(temp_dir.path(), &LogQuery::default());
+        let messages: Vec<_> = all.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Run ended",
+                "Capture failed",
+                "Low confidence\nmore",
+                "Run started"
+            ]
+        );
+        assert_eq!(all[1].level, "ERROR");
+        assert_eq!(all[1].target, ocr);
+
+        let warnings = query(
+            temp_dir.path(),
+            &LogQuery {
+                level: Some(LevelFilter::Warn),
+                contains: Some("OCR".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(warnings.len(), 2);
+
+        let limited = query(
+            temp_dir.path(),
+            &LogQuery {
+                limit: Some(1),
+                ..Default::default()
+            },
+        );
+        assert_eq!(limited[0].message, "Run ended");
+
+        let future = parse_since("2999-01-01").unwrap();
+        let none = query(
+            temp_dir.path(),
+            &LogQuery {
+                since: Some(future),
+                ..Default::default()
+            },
+        );
+        assert!(none.is_empty());
+        assert!(parse_since("2024-05-01T12:00:00Z").is_some());
+        assert!(parse_since("yesterday").is_none());
+    }
 }
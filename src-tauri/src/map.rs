@@ -0,0 +1,289 @@
+//! Map routes between battles
+//!
+//! After each battle the map splits into branches, each a run of nodes:
+//! shops, unit and spell banners that offer a clan's cards, and Pyre
+//! heals. A route is worth what its nodes fix in the deck, so the deck is
+//! first checked for weaknesses (no frontline, no backline clear, a
+//! top-heavy curve, too few units or spells, cards worth removing, a
+//! damaged Pyre) and each node is valued for the ones it addresses.
+
+use crate::database::repository::CardData;
+use crate::scoring::archetypes::Archetype;
+use crate::scoring::curve;
+use crate::scoring::pool::CardPool;
+use crate::scoring::shop::DILUTION_THRESHOLD;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Deck cards needed before the type balance counts as a weakness
+const MIN_CARDS_FOR_BALANCE: usize = 4;
+/// Unit share of the deck below which units are lacking
+const MIN_UNIT_SHARE: f64 = 0.4;
+/// Spell share of the deck below which spells are lacking
+const MIN_SPELL_SHARE: f64 = 0.3;
+/// Pyre health percentage below which healing matters
+const LOW_PYRE_HEALTH: i32 = 60;
+/// Gold needed for a shop to be worth visiting
+const MIN_SHOP_GOLD: i32 = 50;
+/// Gold per point of shop value, and the most gold alone is worth
+const GOLD_PER_SHOP_POINT: i32 = 20;
+const MAX_GOLD_VALUE: i32 = 15;
+/// Shop value per card worth removing, and the most it adds
+const REMOVAL_VALUE: i32 = 5;
+const MAX_REMOVAL_VALUE: i32 = 15;
+/// Value of a banner before weaknesses
+const BANNER_BASE_VALUE: i32 = 10;
+const FILLS_ROLE_BONUS: i32 = 12;
+const BALANCE_BONUS: i32 = 6;
+const CHEAP_SPELL_BONUS: i32 = 4;
+
+/// What a map node offers
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MapNodeKind {
+    Shop,
+    UnitBanner,
+    SpellBanner,
+    PyreHeal,
+}
+
+/// One node on a branch of the map
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MapNode {
+    /// Branch the node is on; nodes with the same branch form one route
+    pub branch: u32,
+    pub kind: MapNodeKind,
+}
+
+/// Something the deck or run is missing, serialized like `ScoreReason`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "code", content = "args", rename_all = "snake_case")]
+pub enum Weakness {
+    NoFrontline,
+    NoBacklineClear,
+    TopHeavy,
+    FewUnits,
+    FewSpells,
+    /// Cards that score too low to keep
+    WeakCards {
+        count: usize,
+    },
+    /// Pyre health as a percentage
+    LowPyre {
+        health: i32,
+    },
+}
+
+impl fmt::Display for Weakness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Weakness::NoFrontline => write!(f, "No frontline"),
+            Weakness::NoBacklineClear => write!(f, "No backline clear"),
+            Weakness::TopHeavy => write!(f, "Top-heavy energy curve"),
+            Weakness::FewUnits => write!(f, "Few units"),
+            Weakness::FewSpells => write!(f, "Few spells"),
+            Weakness::WeakCards { count } => write!(f, "{} cards worth removing", count),
+            Weakness::LowPyre { health } => write!(f, "Pyre at {}%", health),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NodeEvaluation {
+    pub kind: MapNodeKind,
+    pub value: i32,
+    /// Weaknesses the node helps with
+    pub addresses: Vec<Weakness>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BranchEvaluation {
+    pub branch: u32,
+    /// Total value of the branch's nodes
+    pub value: i32,
+    pub nodes: Vec<NodeEvaluation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RouteRecommendation {
+    pub weaknesses: Vec<Weakness>,
+    /// Most valuable first
+    pub branches: Vec<BranchEvaluation>,
+    pub recommended: Option<u32>,
+}
+
+/// Find the deck's weaknesses, scoring its cards with `pool` at `ring`
+pub fn find_weaknesses(
+    pool: &CardPool,
+    archetypes: &[Archetype],
+    deck: &[CardData],
+    ring: i32,
+    pyre_health: Option<i32>,
+) -> Vec<Weakness> {
+    let has_keyword = |keywords: &[&str]| {
+        deck.iter()
+            .any(|c| c.keywords.iter().any(|k| keywords.contains(&k.as_str())))
+    };
+    let drafted: Vec<&CardData> = deck.iter().filter(|c| c.rarity != "Champion").collect();
+
+    let mut weaknesses = Vec::new();
+    if !has_keyword(&["frontline", "tank"]) {
+        weaknesses.push(Weakness::NoFrontline);
+    }
+    if !has_keyword(&["sweep", "explosive", "advance"]) {
+        weaknesses.push(Weakness::NoBacklineClear);
+    }
+    if curve::analyze(deck, archetypes).is_some_and(|analysis| analysis.is_top_heavy()) {
+        weaknesses.push(Weakness::TopHeavy);
+    }
+    if drafted.len() >= MIN_CARDS_FOR_BALANCE {
+        let share = |card_type: &str| {
+            drafted.iter().filter(|c| c.card_type == card_type).count() as f64
+                / drafted.len() as f64
+        };
+        if share("Unit") < MIN_UNIT_SHARE {
+            weaknesses.push(Weakness::FewUnits);
+        }
+        if share("Spell") < MIN_SPELL_SHARE {
+            weaknesses.push(Weakness::FewSpells);
+        }
+    }
+
+    let weak = deck
+        .iter()
+        .enumerate()
+        .filter(|(_, card)| card.rarity != "Champion")
+        .filter(|(i, card)| {
+            let rest = [&deck[..*i], &deck[i + 1..]].concat();
+            pool.score(card, &rest, ring) <= DILUTION_THRESHOLD
+        })
+        .count();
+    if weak > 0 {
+        weaknesses.push(Weakness::WeakCards { count: weak });
+    }
+
+    if let Some(health) = pyre_health.filter(|&health| health < LOW_PYRE_HEALTH) {
+        weaknesses.push(Weakness::LowPyre { health });
+    }
+    weaknesses
+}
+
+/// Value one node for a run with `weaknesses` and `gold`
+pub fn evaluate_node(kind: MapNodeKind, weaknesses: &[Weakness], gold: i32) -> NodeEvaluation {
+    let base = match kind {
+        MapNodeKind::UnitBanner | MapNodeKind::SpellBanner => BANNER_BASE_VALUE,
+        MapNodeKind::Shop if gold >= MIN_SHOP_GOLD => {
+            (gold / GOLD_PER_SHOP_POINT).min(MAX_GOLD_VALUE)
+        }
+        MapNodeKind::Shop | MapNodeKind::PyreHeal => 0,
+    };
+    let bonuses: Vec<(&Weakness, i32)> = weaknesses
+        .iter()
+        .filter_map(|weakness| Some((weakness, weakness_bonus(kind, weakness, gold)?)))
+        .collect();
+
+    NodeEvaluation {
+        kind,
+        value: base + bonuses.iter().map(|(_, bonus)| bonus).sum::<i32>(),
+        addresses: bonuses
+            .into_iter()
+            .map(|(weakness, _)| weakness.clone())
+            .collect(),
+    }
+}
+
+/// Value a node of `kind` adds by addressing `weakness`, if it does
+fn weakness_bonus(kind: MapNodeKind, weakness: &Weakness, gold: i32) -> Option<i32> {
+    match (kind, weakness) {
+        (MapNodeKind::UnitBanner, Weakness::NoFrontline) => Some(FILLS_ROLE_BONUS),
+        (MapNodeKind::UnitBanner, Weakness::FewUnits) => Some(BALANCE_BONUS),
+        (MapNodeKind::SpellBanner, Weakness::NoBacklineClear) => Some(FILLS_ROLE_BONUS),
+        (MapNodeKind::SpellBanner, Weakness::FewSpells) => Some(BALANCE_BONUS),
+        (MapNodeKind::SpellBanner, Weakness::TopHeavy) => Some(CHEAP_SPELL_BONUS),
+        (MapNodeKind::Shop, Weakness::WeakCards { count }) if gold >= MIN_SHOP_GOLD => {
+            Some((REMOVAL_VALUE * *count as i32).min(MAX_REMOVAL_VALUE))
+        }
+        (MapNodeKind::PyreHeal, Weakness::LowPyre { health }) => Some(LOW_PYRE_HEALTH - health),
+        _ => None,
+    }
+}
+
+/// Value each branch of `nodes` for the run and recommend the best
+pub fn recommend_route(
+    nodes: &[MapNode],
+    weaknesses: Vec<Weakness>,
+    gold: i32,
+) -> RouteRecommendation {
+    let mut by_branch: BTreeMap<u32, Vec<NodeEvaluation>> = BTreeMap::new();
+    for node in nodes {
+        by_branch
+            .entry(node.branch)
+            .or_default()
+            .push(evaluate_node(node.kind, &weaknesses, gold));
+    }
+
+    let mut branches: Vec<BranchEvaluation> = by_branch
+        .into_iter()
+        .map(|(branch, nodes)| BranchEvaluation {
+            branch,
+            value: nodes.iter().map(|node| node.value).sum(),
+            nodes,
+        })
+        .collect();
+    // Stable, so ties keep the lower branch first
+    branches.sort_by_key(|branch| -branch.value);
+
+    RouteRecommendation {
+        recommended: branches.first().map(|branch| branch.branch),
+        weaknesses,
+        branches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recommend_route() {
+        let nodes = [
+            MapNode {
+                branch: 0,
+                kind: MapNodeKind::Shop,
+            },
+            MapNode {
+                branch: 0,
+                kind: MapNodeKind::SpellBanner,
+            },
+            MapNode {
+                branch: 1,
+                kind: MapNodeKind::UnitBanner,
+            },
+            MapNode {
+                branch: 1,
+                kind: MapNodeKind::PyreHeal,
+            },
+        ];
+
+        // A deck without a frontline and a battered Pyre goes for units and healing
+        let weaknesses = vec![Weakness::NoFrontline, Weakness::LowPyre { health: 30 }];
+        let route = recommend_route(&nodes, weaknesses, 40);
+        assert_eq!(route.recommended, Some(1));
+        assert_eq!(route.branches[0].value, 10 + 12 + 30);
+        assert_eq!(
+            route.branches[0].nodes[1].addresses,
+            vec![Weakness::LowPyre { health: 30 }]
+        );
+        // Too little gold makes the shop worthless
+        assert_eq!(route.branches[1].nodes[0].value, 0);
+
+        // Gold and cards to remove make the shop worth the detour
+        let weaknesses = vec![Weakness::WeakCards { count: 2 }];
+        let route = recommend_route(&nodes, weaknesses, 300);
+        assert_eq!(route.recommended, Some(0));
+        assert_eq!(route.branches[0].nodes[0].value, 15 + 10);
+
+        assert_eq!(recommend_route(&[], Vec::new(), 0).recommended, None);
+    }
+}
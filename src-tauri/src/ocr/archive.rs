@@ -0,0 +1,411 @@
+//! Cold storage for long-term OCR dataset collection
+//!
+//! When dataset archival is enabled, old rows from `ocr_detections` are
+//! batched into zlib-compressed JSON-lines blobs in `ocr_detection_archives`
+//! (which doubles as the index: each blob records its id and time range),
+//! and old PNG region crops are re-encoded as lossless WebP.
+
+use crate::database::access::DatabaseAccess;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use image::codecs::webp::WebPEncoder;
+use image::{ColorType, DynamicImage};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Error types for archive compaction
+#[derive(Debug)]
+pub enum ArchiveError {
+    Database(String),
+    Io(String),
+    Encoding(String),
+}
+
+impl std::fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveError::Database(msg) => write!(f, "Archive database error: {}", msg),
+            ArchiveError::Io(msg) => write!(f, "Archive I/O error: {}", msg),
+            ArchiveError::Encoding(msg) => write!(f, "Archive encoding error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<rusqlite::Error> for ArchiveError {
+    fn from(err: rusqlite::Error) -> Self {
+        ArchiveError::Database(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(err: std::io::Error) -> Self {
+        ArchiveError::Io(err.to_string())
+    }
+}
+
+/// Result type for archive operations
+pub type ArchiveResult<T> = Result<T, ArchiveError>;
+
+/// Configuration for dataset archival
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    /// Whether dataset archival (and background compaction) is enabled
+    pub enabled: bool,
+    /// Rows and images older than this are moved to cold storage
+    pub max_age_days: u32,
+    /// Maximum number of detection rows per compressed blob
+    pub batch_size: usize,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age_days: 30,
+            batch_size: 500,
+        }
+    }
+}
+
+/// A detection row as stored inside a compressed archive blob
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedDetection {
+    pub id: i64,
+    pub card_id: String,
+    pub card_name: String,
+    pub raw_text: String,
+    pub ocr_confidence: i32,
+    pub match_score: i32,
+    pub overall_confidence: f64,
+    pub region: (i32, i32, u32, u32),
+    pub detected_at: String,
+}
+
+/// Summary of a compaction run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompactionReport {
+    pub rows_archived: usize,
+    pub batches_written: usize,
+    pub images_reencoded: usize,
+    pub image_bytes_saved: i64,
+}
+
+/// Move detection rows older than `max_age_days` into compressed archive blobs
+///
+/// Returns (rows archived, batches written). Each batch is selected, written
+/// and its original rows deleted by ID in a single transaction, so a row is
+/// never deleted without being archived.
+pub fn compact_detections(
+    conn: &Connection,
+    max_age_days: u32,
+    batch_size: usize,
+) -> ArchiveResult<(usize, usize)> {
+    let age_modifier = format!("-{} days", max_age_days);
+    let batch_size = batch_size.max(1);
+    let mut rows_archived = 0;
+    let mut batches_written = 0;
+
+    loop {
+        let tx = conn.unchecked_transaction()?;
+        let batch = select_expired_batch(&tx, &age_modifier, batch_size)?;
+        if batch.is_empty() {
+            break;
+        }
+
+        let payload = compress_batch(&batch)?;
+        let first = &batch[0];
+        let last = &batch[batch.len() - 1];
+
+        tx.execute(
+            "INSERT INTO ocr_detection_archives
+             (first_detection_id, last_detection_id, row_count, oldest_detected_at, newest_detected_at, payload)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                first.id,
+                last.id,
+                batch.len() as i64,
+                first.detected_at,
+                last.detected_at,
+                payload,
+            ],
+        )?;
+        {
+            let mut delete = tx.prepare("DELETE FROM ocr_detections WHERE id = ?1")?;
+            for detection in &batch {
+                delete.execute([detection.id])?;
+            }
+        }
+        tx.commit()?;
+
+        rows_archived += batch.len();
+        batches_written += 1;
+    }
+
+    Ok((rows_archived, batches_written))
+}
+
+fn select_expired_batch(
+    conn: &Connection,
+    age_modifier: &str,
+    batch_size: usize,
+) -> ArchiveResult<Vec<ArchivedDetection>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, card_id, card_name, raw_text, ocr_confidence, match_score, overall_confidence,
+                region_x, region_y, region_width, region_height, detected_at
+         FROM ocr_detections
+         WHERE detected_at < datetime('now', ?1)
+         ORDER BY id
+         LIMIT ?2",
+    )?;
+
+    let rows: Result<Vec<ArchivedDetection>, rusqlite::Error> = stmt
+        .query_map(rusqlite::params![age_modifier, batch_size as i64], |row| {
+            Ok(ArchivedDetection {
+                id: row.get(0)?,
+                card_id: row.get(1)?,
+                card_name: row.get(2)?,
+                raw_text: row.get(3)?,
+                ocr_confidence: row.get(4)?,
+                match_score: row.get(5)?,
+                overall_confidence: row.get(6)?,
+                region: (row.get(7)?, row.get(8)?, row.get(9)?, row.get(10)?),
+                detected_at: row.get(11)?,
+            })
+        })?
+        .collect();
+
+    Ok(rows?)
+}
+
+fn compress_batch(batch: &[ArchivedDetection]) -> ArchiveResult<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    for row in batch {
+        let line =
+            serde_json::to_string(row).map_err(|e| ArchiveError::Encoding(e.to_string()))?;
+        encoder.write_all(line.as_bytes())?;
+        encoder.write_all(b"\n")?;
+    }
+    Ok(encoder.finish()?)
+}
+
+/// Decompress an archive blob back into detection rows
+pub fn read_archive_batch(conn: &Connection, archive_id: i64) -> ArchiveResult<Vec<ArchivedDetection>> {
+    let payload: Vec<u8> = conn.query_row(
+        "SELECT payload FROM ocr_detection_archives WHERE id = ?1",
+        [archive_id],
+        |row| row.get(0),
+    )?;
+
+    let mut json_lines = String::new();
+    ZlibDecoder::new(payload.as_slice()).read_to_string(&mut json_lines)?;
+
+    json_lines
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| ArchiveError::Encoding(e.to_string())))
+        .collect()
+}
+
+/// Re-encode PNG crops older than `max_age_days` in `dir` (recursively) as lossless WebP
+///
+/// Returns (images re-encoded, bytes saved). The PNG is removed only after
+/// the WebP file has been written successfully.
+pub fn compact_images(dir: &Path, max_age_days: u32) -> ArchiveResult<(usize, i64)> {
+    let max_age = Duration::from_secs(u64::from(max_age_days) * 24 * 60 * 60);
+    let cutoff = SystemTime::now()
+        .checked_sub(max_age)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+    let mut reencoded = 0;
+    let mut bytes_saved = 0;
+
+    if !dir.is_dir() {
+        return Ok((0, 0));
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            let (count, saved) = compact_images(&path, max_age_days)?;
+            reencoded += count;
+            bytes_saved += saved;
+            continue;
+        }
+
+        let is_png = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+        if !is_png {
+            continue;
+        }
+
+        let metadata = std::fs::metadata(&path)?;
+        if metadata.modified()? > cutoff {
+            continue;
+        }
+
+        match reencode_as_webp(&path) {
+            Ok(new_size) => {
+                std::fs::remove_file(&path)?;
+                reencoded += 1;
+                bytes_saved += metadata.len() as i64 - new_size as i64;
+            }
+            Err(e) => log::warn!("Skipping archive of {:?}: {}", path, e),
+        }
+    }
+
+    Ok((reencoded, bytes_saved))
+}
+
+fn reencode_as_webp(path: &Path) -> ArchiveResult<u64> {
+    let img = image::open(path).map_err(|e| ArchiveError::Encoding(e.to_string()))?;
+    let webp_path = path.with_extension("webp");
+    let file = std::fs::File::create(&webp_path)?;
+    let encoder = WebPEncoder::new_lossless(std::io::BufWriter::new(file));
+
+    let result = match img {
+        DynamicImage::ImageLuma8(gray) => {
+            encoder.encode(gray.as_raw(), gray.width(), gray.height(), ColorType::L8)
+        }
+        other => {
+            let rgba = other.to_rgba8();
+            encoder.encode(rgba.as_raw(), rgba.width(), rgba.height(), ColorType::Rgba8)
+        }
+    };
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&webp_path);
+        return Err(ArchiveError::Encoding(e.to_string()));
+    }
+
+    Ok(std::fs::metadata(&webp_path)?.len())
+}
+
+/// Run a full compaction pass over the database and (optionally) an image directory
+pub fn run_compaction(
+    access: &DatabaseAccess,
+    image_dir: Option<&Path>,
+    config: &ArchiveConfig,
+) -> ArchiveResult<CompactionReport> {
+    let (max_age_days, batch_size) = (config.max_age_days, config.batch_size);
+    let (rows_archived, batches_written) =
+        access.write_and_wait(move |conn| compact_detections(conn, max_age_days, batch_size))?;
+
+    let (images_reencoded, image_bytes_saved) = match image_dir {
+        Some(dir) => compact_images(dir, config.max_age_days)?,
+        None => (0, 0),
+    };
+
+    log::info!(
+        "Archive compaction: {} rows in {} batches, {} images re-encoded",
+        rows_archived,
+        batches_written,
+        images_reencoded
+    );
+
+    Ok(CompactionReport {
+        rows_archived,
+        batches_written,
+        images_reencoded,
+        image_bytes_saved,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+    use tempfile::{NamedTempFile, TempDir};
+
+    fn setup_test_db() -> (Connection, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        (conn, temp_file)
+    }
+
+    fn insert_detection(conn: &Connection, card_id: &str, detected_at: &str) {
+        conn.execute(
+            "INSERT INTO ocr_detections
+             (card_id, card_name, raw_text, ocr_confidence, match_score, overall_confidence,
+              region_x, region_y, region_width, region_height, detected_at)
+             VALUES (?1, ?1, ?1, 80, 90, 0.86, 0, 0, 300, 60, ?2)",
+            rusqlite::params![card_id, detected_at],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_compact_detections_archives_only_old_rows() {
+        let (conn, _temp) = setup_test_db();
+        insert_detection(&conn, "banished_fel", "2020-01-01 00:00:00");
+        insert_detection(&conn, "banished_talos", "2020-01-02 00:00:00");
+        insert_detection(&conn, "banished_cleave", "2020-01-03 00:00:00");
+        conn.execute(
+            "INSERT INTO ocr_detections
+             (card_id, card_name, raw_text, ocr_confidence, match_score, overall_confidence,
+              region_x, region_y, region_width, region_height)
+             VALUES ('banished_just_cause', 'Just Cause', 'Just Cause', 80, 90, 0.86, 0, 0, 300, 60)",
+            [],
+        )
+        .unwrap();
+
+        let (rows, batches) = compact_detections(&conn, 30, 2).unwrap();
+        assert_eq!(rows, 3);
+        assert_eq!(batches, 2);
+
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM ocr_detections", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 1);
+
+        let first_batch_id: i64 = conn
+            .query_row("SELECT MIN(id) FROM ocr_detection_archives", [], |row| row.get(0))
+            .unwrap();
+        let restored = read_archive_batch(&conn, first_batch_id).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[0].card_id, "banished_fel");
+        assert_eq!(restored[1].detected_at, "2020-01-02 00:00:00");
+    }
+
+    #[test]
+    fn test_compact_detections_nothing_to_do() {
+        let (conn, _temp) = setup_test_db();
+        assert_eq!(compact_detections(&conn, 30, 100).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_compact_images_reencodes_png_as_webp() {
+        let dir = TempDir::new().unwrap();
+        let png_path = dir.path().join("debug_region_0.png");
+        let mut img = GrayImage::new(40, 20);
+        for x in 10..30 {
+            img.put_pixel(x, 10, Luma([255]));
+        }
+        img.save(&png_path).unwrap();
+
+        // A max age of zero archives everything that already exists
+        let (count, _) = compact_images(dir.path(), 0).unwrap();
+        assert_eq!(count, 1);
+        assert!(!png_path.exists());
+
+        let webp_path = dir.path().join("debug_region_0.webp");
+        let decoded = image::open(&webp_path).unwrap().to_luma8();
+        assert_eq!(decoded.dimensions(), (40, 20));
+        assert_eq!(decoded.get_pixel(15, 10)[0], 255);
+    }
+
+    #[test]
+    fn test_compact_images_missing_dir() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join("does_not_exist");
+        assert_eq!(compact_images(&missing, 30).unwrap(), (0, 0));
+    }
+}
@@ -0,0 +1,169 @@
+//! Card frame and banner color analysis
+//!
+//! Samples the colors around a card name crop to infer the card's rarity
+//! (from the frame) and clan (from the name banner). The result is a hint
+//! used to break ties between similarly named cards, never a match on its
+//! own. This module only depends on `image` and is compiled with or
+//! without the `ocr` feature; it is re-exported from `ocr::preprocess`.
+
+use image::{ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
+
+/// Approximate frame color of each rarity
+pub const RARITY_COLORS: &[(&str, [u8; 3])] = &[
+    ("Common", [128, 128, 128]),
+    ("Uncommon", [64, 140, 200]),
+    ("Rare", [220, 170, 50]),
+    ("Champion", [170, 90, 200]),
+];
+
+/// Approximate name banner color of each clan
+pub const CLAN_COLORS: &[(&str, [u8; 3])] = &[
+    ("Banished", [225, 200, 120]),
+    ("Pyreborne", [200, 70, 40]),
+    ("Luna Coven", [120, 100, 200]),
+    ("Underlegion", [110, 140, 60]),
+    ("Lazarus League", [60, 170, 150]),
+    ("Hellhorned", [170, 40, 40]),
+    ("Railforged", [150, 110, 70]),
+    ("Melting Remnant", [210, 130, 170]),
+];
+
+/// Largest RGB distance at which a sampled color still counts as a palette color
+const MAX_COLOR_DISTANCE: f64 = 60.0;
+
+/// Pixels darker or brighter than this are treated as text, not banner
+const TEXT_LUMA_MIN: u8 = 40;
+const TEXT_LUMA_MAX: u8 = 215;
+
+/// Colors sampled from a card name crop, with the attributes they suggest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BannerColor {
+    /// Mean color of the crop's outer ring
+    pub frame_rgb: [u8; 3],
+    /// Mean color of the crop's interior, excluding text pixels
+    pub fill_rgb: Option<[u8; 3]>,
+    /// Rarity whose frame color is closest, if close enough
+    pub rarity: Option<String>,
+    /// Clan whose banner color is closest, if close enough
+    pub clan: Option<String>,
+}
+
+impl BannerColor {
+    /// Number of inferred attributes that agree with a card's clan and rarity
+    pub fn agreement(&self, clan: &str, rarity: &str) -> usize {
+        let same = |inferred: &Option<String>, actual: &str| {
+            inferred.as_deref().is_some_and(|i| i.eq_ignore_ascii_case(actual))
+        };
+        same(&self.clan, clan) as usize + same(&self.rarity, rarity) as usize
+    }
+}
+
+fn luma([r, g, b]: [u8; 3]) -> u8 {
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> f64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(&x, &y)| (x as f64 - y as f64).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Name of the palette color closest to `rgb`, if within range
+pub fn closest_color(rgb: [u8; 3], palette: &[(&str, [u8; 3])]) -> Option<String> {
+    palette
+        .iter()
+        .map(|(name, color)| (name, distance(rgb, *color)))
+        .filter(|(_, d)| *d <= MAX_COLOR_DISTANCE)
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(name, _)| name.to_string())
+}
+
+fn mean_color(pixels: impl Iterator<Item = [u8; 3]>) -> Option<[u8; 3]> {
+    let (sum, count) = pixels.fold(([0u64; 3], 0u64), |(mut sum, count), rgb| {
+        for (total, channel) in sum.iter_mut().zip(rgb) {
+            *total += channel as u64;
+        }
+        (sum, count + 1)
+    });
+
+    (count > 0).then(|| sum.map(|total| (total / count) as u8))
+}
+
+/// Sample the frame and banner colors of an RGBA card name crop
+///
+/// The outer ring (an eighth of the shorter side, at least one pixel) is
+/// the frame; the interior minus dark and bright text pixels is the banner.
+pub fn analyze_banner(img: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> Option<BannerColor> {
+    let (width, height) = img.dimensions();
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let ring = (width.min(height) / 8).max(1);
+    let in_ring = |x: u32, y: u32| {
+        x < ring || y < ring || x >= width.saturating_sub(ring) || y >= height.saturating_sub(ring)
+    };
+    let rgb = |p: &Rgba<u8>| [p.0[0], p.0[1], p.0[2]];
+
+    let frame_rgb = mean_color(
+        img.enumerate_pixels()
+            .filter(|(x, y, _)| in_ring(*x, *y))
+            .map(|(_, _, p)| rgb(p)),
+    )?;
+    let fill_rgb = mean_color(
+        img.enumerate_pixels()
+            .filter(|(x, y, _)| !in_ring(*x, *y))
+            .map(|(_, _, p)| rgb(p))
+            .filter(|c| (TEXT_LUMA_MIN..=TEXT_LUMA_MAX).contains(&luma(*c))),
+    );
+
+    Some(BannerColor {
+        frame_rgb,
+        fill_rgb,
+        rarity: closest_color(frame_rgb, RARITY_COLORS),
+        clan: fill_rgb.and_then(|fill| closest_color(fill, CLAN_COLORS)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A crop with a colored frame, colored banner and white "text" stripe
+    fn crop(frame: [u8; 3], fill: [u8; 3]) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        ImageBuffer::from_fn(80, 24, |x, y| {
+            let [r, g, b] = if x < 3 || y < 3 || x >= 77 || y >= 21 {
+                frame
+            } else if y == 12 {
+                [255, 255, 255]
+            } else {
+                fill
+            };
+            Rgba([r, g, b, 255])
+        })
+    }
+
+    #[test]
+    fn test_analyze_banner_infers_rarity_and_clan() {
+        let banner = analyze_banner(&crop([220, 170, 50], [200, 70, 40])).unwrap();
+        assert_eq!(banner.frame_rgb, [220, 170, 50]);
+        assert_eq!(banner.fill_rgb, Some([200, 70, 40]));
+        assert_eq!(banner.rarity.as_deref(), Some("Rare"));
+        assert_eq!(banner.clan.as_deref(), Some("Pyreborne"));
+        assert_eq!(banner.agreement("pyreborne", "Rare"), 2);
+        assert_eq!(banner.agreement("Banished", "Rare"), 1);
+    }
+
+    #[test]
+    fn test_analyze_banner_without_palette_match() {
+        let banner = analyze_banner(&crop([0, 255, 0], [0, 0, 0])).unwrap();
+        assert!(banner.rarity.is_none());
+        assert!(banner.fill_rgb.is_none());
+        assert!(banner.clan.is_none());
+
+        assert!(analyze_banner(&ImageBuffer::new(0, 0)).is_none());
+    }
+}
@@ -1,21 +1,32 @@
 //! Screen capture functionality for OCR
 //!
 //! This module provides functionality to capture specific screen regions
-//! where card names appear in Monster Train 2.
+//! where card names appear in Monster Train 2. Captures go through the
+//! `CaptureBackend` chosen in the OCR settings (see `capture_backend`).
 
 use image::{ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 #[cfg(feature = "ocr")]
 use screenshots::Screen;
 
+pub use super::capture_backend::{
+    capture_backend_status, BackendAvailability, CaptureBackend, CaptureBackendStatus,
+    DisplaySession,
+};
+pub use super::frame_diff::{frame_hash, FrameGate};
+pub use super::region_role::RegionRole;
+
 /// Represents a screen region to capture
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CaptureRegion {
     pub x: i32,
     pub y: i32,
     pub width: u32,
     pub height: u32,
+    /// What the region shows, if assigned
+    pub role: Option<RegionRole>,
 }
 
 impl CaptureRegion {
@@ -26,6 +37,15 @@ impl CaptureRegion {
             y,
             width,
             height,
+            role: None,
+        }
+    }
+
+    /// Assign what the region shows
+    pub fn with_role(self, role: RegionRole) -> Self {
+        Self {
+            role: Some(role),
+            ..self
         }
     }
 
@@ -38,6 +58,56 @@ impl CaptureRegion {
     pub fn contains(&self, px: i32, py: i32) -> bool {
         px >= self.x && px < self.x + self.width as i32 && py >= self.y && py < self.y + self.height as i32
     }
+
+    /// Scale position and size by `factor`
+    pub fn scaled(&self, factor: f32) -> Self {
+        Self {
+            x: (self.x as f32 * factor).round() as i32,
+            y: (self.y as f32 * factor).round() as i32,
+            width: (self.width as f32 * factor).round() as u32,
+            height: (self.height as f32 * factor).round() as u32,
+            role: self.role,
+        }
+    }
+
+    /// Convert a region in physical pixels to the logical pixels used by
+    /// the OS at the given display scale factor
+    pub fn to_logical(&self, scale_factor: f32) -> Self {
+        if scale_factor <= 0.0 {
+            return *self;
+        }
+        self.scaled(1.0 / scale_factor)
+    }
+
+    /// Smallest region covering every valid region in `regions`
+    pub fn bounding(regions: &[CaptureRegion]) -> Option<Self> {
+        let mut valid = regions.iter().filter(|r| r.is_valid());
+        let first = valid.next()?;
+        let (mut left, mut top) = (first.x as i64, first.y as i64);
+        let mut right = left + first.width as i64;
+        let mut bottom = top + first.height as i64;
+        for region in valid {
+            left = left.min(region.x as i64);
+            top = top.min(region.y as i64);
+            right = right.max(region.x as i64 + region.width as i64);
+            bottom = bottom.max(region.y as i64 + region.height as i64);
+        }
+        Some(Self::new(
+            left as i32,
+            top as i32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        ))
+    }
+
+    /// Position of this region inside an image captured from `area`
+    pub fn relative_to(&self, area: &CaptureRegion) -> Self {
+        Self {
+            x: self.x - area.x,
+            y: self.y - area.y,
+            ..*self
+        }
+    }
 }
 
 impl fmt::Display for CaptureRegion {
@@ -46,8 +116,94 @@ impl fmt::Display for CaptureRegion {
     }
 }
 
+/// A capture region in fractions (0.0-1.0) of the screen size
+///
+/// Stays on the same part of the game UI when the resolution changes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NormalizedRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub role: Option<RegionRole>,
+}
+
+impl NormalizedRegion {
+    /// Whether the region has a size and lies within the screen
+    pub fn is_valid(&self) -> bool {
+        let unit = 0.0..=1.0;
+        unit.contains(&self.x)
+            && unit.contains(&self.y)
+            && self.width > 0.0
+            && self.height > 0.0
+            && self.x + self.width <= 1.0 + f64::EPSILON
+            && self.y + self.height <= 1.0 + f64::EPSILON
+    }
+
+    /// Pixel region on a screen of the given physical size
+    pub fn to_pixels(&self, screen_width: u32, screen_height: u32) -> CaptureRegion {
+        let (w, h) = (screen_width as f64, screen_height as f64);
+        CaptureRegion {
+            role: self.role,
+            ..CaptureRegion::new(
+                (self.x * w).round() as i32,
+                (self.y * h).round() as i32,
+                ((self.width * w).round() as u32).max(1),
+                ((self.height * h).round() as u32).max(1),
+            )
+        }
+    }
+
+    /// Normalize a pixel region on a screen of the given physical size
+    pub fn from_pixels(region: &CaptureRegion, screen_width: u32, screen_height: u32) -> Self {
+        let (w, h) = (screen_width.max(1) as f64, screen_height.max(1) as f64);
+        Self {
+            x: region.x as f64 / w,
+            y: region.y as f64 / h,
+            width: region.width as f64 / w,
+            height: region.height as f64 / h,
+            role: region.role,
+        }
+    }
+}
+
+/// Geometry and display scaling of a monitor
+///
+/// Capture regions are in physical pixels, the resolution the game renders
+/// at. With 125%/150% display scaling the OS reports a smaller logical size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayMetrics {
+    /// Logical width, as reported by the OS
+    pub logical_width: u32,
+    /// Logical height, as reported by the OS
+    pub logical_height: u32,
+    /// Physical pixels per logical pixel (1.25 at 125% scaling)
+    pub scale_factor: f32,
+    pub is_primary: bool,
+}
+
+impl DisplayMetrics {
+    /// Size in physical pixels
+    pub fn physical_size(&self) -> (u32, u32) {
+        (
+            (self.logical_width as f32 * self.scale_factor).round() as u32,
+            (self.logical_height as f32 * self.scale_factor).round() as u32,
+        )
+    }
+
+    /// Whether a region (in physical pixels) lies entirely on this display
+    pub fn contains_region(&self, region: &CaptureRegion) -> bool {
+        let (width, height) = self.physical_size();
+        region.x >= 0
+            && region.y >= 0
+            && region.x as i64 + region.width as i64 <= width as i64
+            && region.y as i64 + region.height as i64 <= height as i64
+    }
+}
+
 /// Error types for screen capture operations
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum CaptureError {
     NoScreensAvailable,
     RegionOutOfBounds,
@@ -71,45 +227,217 @@ impl std::error::Error for CaptureError {}
 /// Result type for capture operations
 pub type CaptureResult<T> = Result<T, CaptureError>;
 
-/// Captures a specific region of the primary screen
+/// A captured image and the screen area it covers
+pub type AreaCapture = (CaptureRegion, ImageBuffer<Rgba<u8>, Vec<u8>>);
+
 #[cfg(feature = "ocr")]
-pub fn capture_region(region: &CaptureRegion) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
-    if !region.is_valid() {
-        return Err(CaptureError::InvalidRegion);
+fn display_metrics(screen: &Screen) -> DisplayMetrics {
+    let info = &screen.display_info;
+    DisplayMetrics {
+        logical_width: info.width,
+        logical_height: info.height,
+        scale_factor: if info.scale_factor > 0.0 { info.scale_factor } else { 1.0 },
+        is_primary: info.is_primary,
     }
+}
+
+/// The primary screen, or the first one if none is flagged primary
+#[cfg(feature = "ocr")]
+fn primary_screen() -> CaptureResult<Screen> {
+    let screens = Screen::all().map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
 
+    screens
+        .iter()
+        .find(|s| s.display_info.is_primary)
+        .or_else(|| screens.first())
+        .copied()
+        .ok_or(CaptureError::NoScreensAvailable)
+}
+
+/// Geometry and scale factor of every monitor
+#[cfg(feature = "ocr")]
+pub fn get_display_metrics() -> CaptureResult<Vec<DisplayMetrics>> {
     let screens = Screen::all().map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
-    
     if screens.is_empty() {
         return Err(CaptureError::NoScreensAvailable);
     }
+    Ok(screens.iter().map(display_metrics).collect())
+}
+
+/// Geometry and scale factor of the primary monitor
+#[cfg(feature = "ocr")]
+pub fn get_primary_display_metrics() -> CaptureResult<DisplayMetrics> {
+    primary_screen().map(|screen| display_metrics(&screen))
+}
+
+/// Captures a specific region of the primary screen with the `Auto` backend
+#[cfg(feature = "ocr")]
+pub fn capture_region(region: &CaptureRegion) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    capture_region_with(CaptureBackend::Auto, region)
+}
+
+/// Captures a specific region of the primary screen through `backend`
+///
+/// The region is in physical pixels, and the result is always
+/// `region.width` x `region.height`.
+#[cfg(feature = "ocr")]
+pub fn capture_region_with(
+    backend: CaptureBackend,
+    region: &CaptureRegion,
+) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    match backend.resolve(DisplaySession::detect()) {
+        CaptureBackend::Portal => crop_region(&portal_screenshot()?, region),
+        _ => screenshots_capture_region(region),
+    }
+}
+
+/// Captures a region through the `screenshots` crate
+///
+/// The region is converted to logical pixels for the capture API.
+#[cfg(feature = "ocr")]
+fn screenshots_capture_region(
+    region: &CaptureRegion,
+) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    if !region.is_valid() {
+        return Err(CaptureError::InvalidRegion);
+    }
+
+    let screen = primary_screen()?;
+    let metrics = display_metrics(&screen);
 
-    // Use the primary screen (usually the first one)
-    let screen = &screens[0];
-    
     // Check if region is within screen bounds
-    let screen_width = screen.display_info.width as i32;
-    let screen_height = screen.display_info.height as i32;
-    
-    if region.x < 0 
-        || region.y < 0 
-        || region.x + region.width as i32 > screen_width 
-        || region.y + region.height as i32 > screen_height {
+    if !metrics.contains_region(region) {
         return Err(CaptureError::RegionOutOfBounds);
     }
 
-    // Capture the region
+    // Capture the region; the API takes logical coordinates
+    let logical = region.to_logical(metrics.scale_factor);
     let image = screen
-        .capture_area(region.x, region.y, region.width, region.height)
+        .capture_area(logical.x, logical.y, logical.width.max(1), logical.height.max(1))
         .map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
 
     // Convert to image::ImageBuffer
-    let img_buffer = ImageBuffer::from_raw(region.width, region.height, image.to_vec())
-        .ok_or_else(|| CaptureError::CaptureFailed("Failed to create image buffer".to_string()))?;
+    let img_buffer: ImageBuffer<Rgba<u8>, Vec<u8>> =
+        ImageBuffer::from_raw(image.width(), image.height(), image.to_vec())
+            .ok_or_else(|| CaptureError::CaptureFailed("Failed to create image buffer".to_string()))?;
+
+    // Rounding through logical pixels can be off by one physical pixel
+    if img_buffer.dimensions() != (region.width, region.height) {
+        return Ok(image::imageops::resize(
+            &img_buffer,
+            region.width,
+            region.height,
+            image::imageops::FilterType::Triangle,
+        ));
+    }
 
     Ok(img_buffer)
 }
 
+/// Cut `region` out of a capture whose top-left corner is at (0, 0)
+fn crop_region(
+    screen: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    region: &CaptureRegion,
+) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    if !region.is_valid() {
+        return Err(CaptureError::InvalidRegion);
+    }
+    let (width, height) = screen.dimensions();
+    if region.x < 0
+        || region.y < 0
+        || region.x as i64 + region.width as i64 > width as i64
+        || region.y as i64 + region.height as i64 > height as i64
+    {
+        return Err(CaptureError::RegionOutOfBounds);
+    }
+
+    Ok(image::imageops::crop_imm(
+        screen,
+        region.x as u32,
+        region.y as u32,
+        region.width,
+        region.height,
+    )
+    .to_image())
+}
+
+/// Takes a full-screen capture through the xdg-desktop-portal
+///
+/// The portal writes a PNG at physical resolution and returns its URI; the
+/// file is read and removed.
+#[cfg(all(feature = "ocr", target_os = "linux"))]
+fn portal_screenshot() -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    use ashpd::desktop::screenshot::Screenshot;
+
+    let response = pollster::block_on(async {
+        Screenshot::request()
+            .interactive(false)
+            .modal(false)
+            .send()
+            .await?
+            .response()
+    })
+    .map_err(|e| CaptureError::CaptureFailed(format!("Desktop portal: {}", e)))?;
+
+    let path = response.uri().to_file_path().map_err(|_| {
+        CaptureError::CaptureFailed(format!("Desktop portal returned {}", response.uri()))
+    })?;
+    let image = image::open(&path)
+        .map_err(|e| CaptureError::CaptureFailed(format!("Desktop portal image: {}", e)))?
+        .to_rgba8();
+    if let Err(e) = std::fs::remove_file(&path) {
+        log::debug!("Failed to remove portal screenshot {}: {}", path.display(), e);
+    }
+
+    Ok(image)
+}
+
+#[cfg(all(feature = "ocr", not(target_os = "linux")))]
+fn portal_screenshot() -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    Err(CaptureError::CaptureFailed(
+        "The desktop portal is only available on Linux".to_string(),
+    ))
+}
+
+/// Captures the whole primary screen at physical resolution with the
+/// `Auto` backend
+#[cfg(feature = "ocr")]
+pub fn capture_fullscreen() -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    capture_fullscreen_with(CaptureBackend::Auto)
+}
+
+/// Captures the whole primary screen at physical resolution through `backend`
+#[cfg(feature = "ocr")]
+pub fn capture_fullscreen_with(
+    backend: CaptureBackend,
+) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    if backend.resolve(DisplaySession::detect()) == CaptureBackend::Portal {
+        return portal_screenshot();
+    }
+
+    let screen = primary_screen()?;
+    let image = screen
+        .capture()
+        .map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
+
+    ImageBuffer::from_raw(image.width(), image.height(), image.to_vec())
+        .ok_or_else(|| CaptureError::CaptureFailed("Failed to create image buffer".to_string()))
+}
+
+/// Mock implementation when OCR feature is not enabled
+#[cfg(not(feature = "ocr"))]
+pub fn capture_fullscreen() -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    Err(CaptureError::CaptureFailed("OCR feature not enabled".to_string()))
+}
+
+/// Mock implementation when OCR feature is not enabled
+#[cfg(not(feature = "ocr"))]
+pub fn capture_fullscreen_with(
+    _backend: CaptureBackend,
+) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    capture_fullscreen()
+}
+
 /// Mock implementation when OCR feature is not enabled
 #[cfg(not(feature = "ocr"))]
 pub fn capture_region(_region: &CaptureRegion) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
@@ -120,6 +448,15 @@ pub fn capture_region(_region: &CaptureRegion) -> CaptureResult<ImageBuffer<Rgba
     Ok(img)
 }
 
+/// Mock implementation when OCR feature is not enabled
+#[cfg(not(feature = "ocr"))]
+pub fn capture_region_with(
+    _backend: CaptureBackend,
+    region: &CaptureRegion,
+) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    capture_region(region)
+}
+
 /// Captures multiple regions and returns them as a vector
 pub fn capture_multiple_regions(regions: &[CaptureRegion]) -> Vec<CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>>> {
     regions.iter().map(capture_region).collect()
@@ -148,26 +485,21 @@ pub fn get_default_card_regions(screen_width: u32, screen_height: u32) -> Vec<Ca
     // Scale regions based on screen resolution
     base_regions
         .into_iter()
-        .map(|r| CaptureRegion {
+        .enumerate()
+        .map(|(i, r)| CaptureRegion {
             x: (r.x as f32 * scale_x) as i32,
             y: (r.y as f32 * scale_y) as i32,
             width: (r.width as f32 * scale_x) as u32,
             height: (r.height as f32 * scale_y) as u32,
+            role: RegionRole::card_name(i),
         })
         .collect()
 }
 
-/// Gets the primary screen dimensions
+/// Gets the primary screen dimensions in physical pixels
 #[cfg(feature = "ocr")]
 pub fn get_primary_screen_dimensions() -> CaptureResult<(u32, u32)> {
-    let screens = Screen::all().map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
-    
-    if screens.is_empty() {
-        return Err(CaptureError::NoScreensAvailable);
-    }
-
-    let info = &screens[0].display_info;
-    Ok((info.width, info.height))
+    get_primary_display_metrics().map(|metrics| metrics.physical_size())
 }
 
 /// Mock implementation when OCR feature is not enabled
@@ -177,12 +509,33 @@ pub fn get_primary_screen_dimensions() -> CaptureResult<(u32, u32)> {
     Ok((1920, 1080))
 }
 
+/// Mock implementation when OCR feature is not enabled
+#[cfg(not(feature = "ocr"))]
+pub fn get_display_metrics() -> CaptureResult<Vec<DisplayMetrics>> {
+    get_primary_display_metrics().map(|metrics| vec![metrics])
+}
+
+/// Mock implementation when OCR feature is not enabled
+#[cfg(not(feature = "ocr"))]
+pub fn get_primary_display_metrics() -> CaptureResult<DisplayMetrics> {
+    Ok(DisplayMetrics {
+        logical_width: 1920,
+        logical_height: 1080,
+        scale_factor: 1.0,
+        is_primary: true,
+    })
+}
+
 /// Configuration for OCR capture regions
 #[derive(Debug, Clone)]
 pub struct CaptureConfig {
     pub regions: Vec<CaptureRegion>,
+    /// When set, `regions` are resolved from these for the current screen
+    pub normalized_regions: Vec<NormalizedRegion>,
     pub screen_width: u32,
     pub screen_height: u32,
+    /// How captures are taken
+    pub backend: CaptureBackend,
 }
 
 impl CaptureConfig {
@@ -193,8 +546,10 @@ impl CaptureConfig {
         
         Ok(Self {
             regions,
+            normalized_regions: Vec::new(),
             screen_width,
             screen_height,
+            backend: CaptureBackend::default(),
         })
     }
 
@@ -204,14 +559,55 @@ impl CaptureConfig {
         
         Ok(Self {
             regions,
+            normalized_regions: Vec::new(),
             screen_width,
             screen_height,
+            backend: CaptureBackend::default(),
         })
     }
 
     /// Update regions after calibration
+    ///
+    /// Pixel regions replace any normalized regions.
     pub fn update_regions(&mut self, regions: Vec<CaptureRegion>) {
         self.regions = regions;
+        self.normalized_regions.clear();
+    }
+
+    /// Use regions given as fractions of the screen, resolved for the given size
+    pub fn set_normalized_regions(
+        &mut self,
+        regions: Vec<NormalizedRegion>,
+        screen_width: u32,
+        screen_height: u32,
+    ) {
+        self.normalized_regions = regions;
+        self.resolve(screen_width, screen_height);
+    }
+
+    /// Re-resolve normalized regions if the screen size changed
+    ///
+    /// Returns whether the pixel regions changed. Pixel regions set with
+    /// `update_regions` are left alone.
+    pub fn resolve_for_screen(&mut self, screen_width: u32, screen_height: u32) -> bool {
+        if self.normalized_regions.is_empty()
+            || (self.screen_width, self.screen_height) == (screen_width, screen_height)
+        {
+            return false;
+        }
+
+        self.resolve(screen_width, screen_height);
+        true
+    }
+
+    fn resolve(&mut self, screen_width: u32, screen_height: u32) {
+        self.screen_width = screen_width;
+        self.screen_height = screen_height;
+        self.regions = self
+            .normalized_regions
+            .iter()
+            .map(|r| r.to_pixels(screen_width, screen_height))
+            .collect();
     }
 
     /// Get the current capture regions
@@ -219,9 +615,51 @@ impl CaptureConfig {
         &self.regions
     }
 
+    /// Capture one region through the configured backend
+    pub fn capture(&self, region: &CaptureRegion) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+        capture_region_with(self.backend, region)
+    }
+
+    /// The draft area: smallest region covering every configured region
+    pub fn draft_area(&self) -> Option<CaptureRegion> {
+        CaptureRegion::bounding(&self.regions)
+    }
+
+    /// Capture the draft area in one go
+    ///
+    /// Returns the area the image covers. The portal can only take
+    /// full-screen captures, so with it that is the whole screen.
+    #[cfg(feature = "ocr")]
+    pub fn capture_draft_area(&self) -> CaptureResult<AreaCapture> {
+        if self.backend.resolve(DisplaySession::detect()) == CaptureBackend::Portal {
+            let screen = portal_screenshot()?;
+            let (width, height) = screen.dimensions();
+            return Ok((CaptureRegion::new(0, 0, width, height), screen));
+        }
+
+        let area = self.draft_area().ok_or(CaptureError::InvalidRegion)?;
+        Ok((area, screenshots_capture_region(&area)?))
+    }
+
+    /// Mock implementation when OCR feature is not enabled
+    #[cfg(not(feature = "ocr"))]
+    pub fn capture_draft_area(&self) -> CaptureResult<AreaCapture> {
+        Err(CaptureError::CaptureFailed("OCR feature not enabled".to_string()))
+    }
+
     /// Capture all configured regions
+    ///
+    /// The draft area is captured once and every region is cropped from
+    /// that image, so all regions come from the same frame.
     pub fn capture_all(&self) -> Vec<CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>>> {
-        capture_multiple_regions(&self.regions)
+        match self.capture_draft_area() {
+            Ok((area, frame)) => self
+                .regions
+                .iter()
+                .map(|r| crop_region(&frame, &r.relative_to(&area)))
+                .collect(),
+            Err(e) => self.regions.iter().map(|_| Err(e.clone())).collect(),
+        }
     }
 }
 
@@ -231,8 +669,10 @@ impl Default for CaptureConfig {
         let regions = get_default_card_regions(1920, 1080);
         Self {
             regions,
+            normalized_regions: Vec::new(),
             screen_width: 1920,
             screen_height: 1080,
+            backend: CaptureBackend::default(),
         }
     }
 }
@@ -241,6 +681,56 @@ impl Default for CaptureConfig {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_display_metrics_scaling() {
+        let display = DisplayMetrics {
+            logical_width: 1536,
+            logical_height: 864,
+            scale_factor: 1.25,
+            is_primary: true,
+        };
+        assert_eq!(display.physical_size(), (1920, 1080));
+
+        // Default regions are in physical pixels and must fit a scaled display
+        assert!(display.contains_region(&CaptureRegion::new(1500, 900, 400, 150)));
+        assert!(!display.contains_region(&CaptureRegion::new(1600, 900, 400, 150)));
+        assert!(!display.contains_region(&CaptureRegion::new(-1, 0, 10, 10)));
+    }
+
+    #[test]
+    fn test_normalized_regions_follow_resolution() {
+        let region = NormalizedRegion {
+            x: 0.25,
+            y: 0.5,
+            width: 0.125,
+            height: 0.05,
+            role: None,
+        };
+        assert!(region.is_valid());
+        assert_eq!(region.to_pixels(1920, 1080), CaptureRegion::new(480, 540, 240, 54));
+        let pixels = CaptureRegion::new(480, 540, 240, 54);
+        assert_eq!(NormalizedRegion::from_pixels(&pixels, 1920, 1080), region);
+        assert!(!NormalizedRegion { x: 0.9, ..region }.is_valid());
+
+        let mut config = CaptureConfig::default();
+        config.set_normalized_regions(vec![region], 1920, 1080);
+        assert!(!config.resolve_for_screen(1920, 1080));
+        assert!(config.resolve_for_screen(2560, 1440));
+        assert_eq!(config.get_regions(), &[CaptureRegion::new(640, 720, 320, 72)]);
+
+        // Pixel regions are not rescaled
+        config.update_regions(vec![CaptureRegion::new(1, 2, 3, 4)]);
+        assert!(!config.resolve_for_screen(1920, 1080));
+    }
+
+    #[test]
+    fn test_capture_region_to_logical() {
+        let region = CaptureRegion::new(300, 600, 450, 90);
+        assert_eq!(region.to_logical(1.5), CaptureRegion::new(200, 400, 300, 60));
+        assert_eq!(region.to_logical(1.0), region);
+        assert_eq!(region.to_logical(0.0), region);
+    }
+
     #[test]
     fn test_capture_region_new() {
         let region = CaptureRegion::new(100, 200, 300, 400);
@@ -333,4 +823,32 @@ mod tests {
         assert_eq!(config.regions.len(), 1);
         assert_eq!(config.regions[0].x, 0);
     }
+
+    #[test]
+    fn test_regions_cropped_from_draft_area() {
+        let mut config = CaptureConfig::default();
+        config.update_regions(vec![
+            CaptureRegion::new(100, 50, 20, 10),
+            CaptureRegion::new(60, 80, 30, 5),
+            CaptureRegion::new(0, 0, 0, 0),
+        ]);
+        let area = config.draft_area().unwrap();
+        assert_eq!(area, CaptureRegion::new(60, 50, 60, 35));
+        assert_eq!(CaptureRegion::bounding(&[]), None);
+
+        // Each region lands on its own pixels of the single frame
+        let frame = ImageBuffer::from_fn(area.width, area.height, |x, y| {
+            Rgba([(x + area.x as u32) as u8, (y + area.y as u32) as u8, 0, 255])
+        });
+        let crops: Vec<_> = config
+            .get_regions()
+            .iter()
+            .map(|r| crop_region(&frame, &r.relative_to(&area)))
+            .collect();
+        let first = crops[0].as_ref().unwrap();
+        assert_eq!(first.dimensions(), (20, 10));
+        assert_eq!(first.get_pixel(0, 0), &Rgba([100, 50, 0, 255]));
+        assert_eq!(crops[1].as_ref().unwrap().get_pixel(29, 4), &Rgba([89, 84, 0, 255]));
+        assert_eq!(crops[2], Err(CaptureError::InvalidRegion));
+    }
 }
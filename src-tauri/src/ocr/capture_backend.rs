@@ -0,0 +1,220 @@
+//! Screen capture backend selection
+//!
+//! On Windows, macOS and X11 the `screenshots` crate reads the screen
+//! directly. Under Wayland it can only see XWayland windows and usually
+//! returns black images, so captures go through the xdg-desktop-portal
+//! Screenshot interface instead. `Auto` picks between them from the
+//! session type. This module is compiled with or without the `ocr`
+//! feature; it is re-exported from `ocr::capture`.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How screen captures are taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureBackend {
+    /// The portal under Wayland, `Screenshots` everywhere else
+    #[default]
+    Auto,
+    /// Direct capture through the `screenshots` crate (Windows, macOS, X11)
+    Screenshots,
+    /// xdg-desktop-portal Screenshot interface (Linux, Wayland)
+    Portal,
+}
+
+impl fmt::Display for CaptureBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CaptureBackend::Auto => "auto",
+            CaptureBackend::Screenshots => "screenshots",
+            CaptureBackend::Portal => "portal",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Kind of desktop session the app runs in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplaySession {
+    Windows,
+    MacOs,
+    X11,
+    Wayland,
+    Unknown,
+}
+
+impl DisplaySession {
+    /// Session of the running process
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        Self::from_env(
+            std::env::consts::OS,
+            var("XDG_SESSION_TYPE").as_deref(),
+            var("WAYLAND_DISPLAY").is_some(),
+            var("DISPLAY").is_some(),
+        )
+    }
+
+    /// Session for an OS name and the Linux session variables
+    ///
+    /// `XDG_SESSION_TYPE` wins when set; otherwise a `WAYLAND_DISPLAY`
+    /// means Wayland even if XWayland also sets `DISPLAY`.
+    pub fn from_env(
+        os: &str,
+        session_type: Option<&str>,
+        wayland_display: bool,
+        x11_display: bool,
+    ) -> Self {
+        match os {
+            "windows" => return Self::Windows,
+            "macos" => return Self::MacOs,
+            _ => {}
+        }
+        match session_type.map(str::to_ascii_lowercase).as_deref() {
+            Some("wayland") => Self::Wayland,
+            Some("x11") => Self::X11,
+            _ if wayland_display => Self::Wayland,
+            _ if x11_display => Self::X11,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl CaptureBackend {
+    /// Backend that actually takes captures in `session`
+    pub fn resolve(self, session: DisplaySession) -> Self {
+        match self {
+            CaptureBackend::Auto if session == DisplaySession::Wayland => CaptureBackend::Portal,
+            CaptureBackend::Auto => CaptureBackend::Screenshots,
+            backend => backend,
+        }
+    }
+
+    /// Whether this backend can capture in `session`, and why not if it can't
+    ///
+    /// This only inspects the build and environment; it takes no capture.
+    pub fn availability(self, session: DisplaySession) -> Result<(), String> {
+        if !cfg!(feature = "ocr") {
+            return Err("This build does not include OCR support".to_string());
+        }
+
+        match self.resolve(session) {
+            CaptureBackend::Screenshots if session == DisplaySession::Wayland => Err(
+                "Direct capture only sees XWayland windows under Wayland and usually returns \
+                 black images; use the portal backend"
+                    .to_string(),
+            ),
+            CaptureBackend::Portal if !cfg!(target_os = "linux") => {
+                Err("The desktop portal is only available on Linux".to_string())
+            }
+            CaptureBackend::Portal if std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_none() => {
+                Err("No D-Bus session bus; the desktop portal needs xdg-desktop-portal \
+                     running in the user session"
+                    .to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Whether one backend can capture here
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BackendAvailability {
+    pub backend: CaptureBackend,
+    pub available: bool,
+    pub reason: Option<String>,
+}
+
+/// Capture backend report for the settings screen
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CaptureBackendStatus {
+    /// Backend chosen in the OCR settings
+    pub requested: CaptureBackend,
+    /// Backend captures go through, with `Auto` resolved
+    pub active: CaptureBackend,
+    pub session: DisplaySession,
+    /// Whether the active backend can capture
+    pub available: bool,
+    /// Why the active backend can't capture, if it can't
+    pub reason: Option<String>,
+    /// Every explicit backend, for choosing another one
+    pub backends: Vec<BackendAvailability>,
+    /// Error from a test capture, when one was requested and failed
+    pub probe_error: Option<String>,
+}
+
+/// Capture backend report for `requested` in `session`
+pub fn capture_backend_status(
+    requested: CaptureBackend,
+    session: DisplaySession,
+) -> CaptureBackendStatus {
+    let active = requested.resolve(session);
+    let reason = active.availability(session).err();
+
+    let backends = [CaptureBackend::Screenshots, CaptureBackend::Portal]
+        .into_iter()
+        .map(|backend| {
+            let reason = backend.availability(session).err();
+            BackendAvailability {
+                backend,
+                available: reason.is_none(),
+                reason,
+            }
+        })
+        .collect();
+
+    CaptureBackendStatus {
+        requested,
+        active,
+        session,
+        available: reason.is_none(),
+        reason,
+        backends,
+        probe_error: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_detection() {
+        let detect = DisplaySession::from_env;
+        assert_eq!(detect("windows", None, false, false), DisplaySession::Windows);
+        assert_eq!(detect("linux", Some("wayland"), false, true), DisplaySession::Wayland);
+        assert_eq!(detect("linux", Some("x11"), true, true), DisplaySession::X11);
+        // XWayland sets DISPLAY alongside WAYLAND_DISPLAY
+        assert_eq!(detect("linux", None, true, true), DisplaySession::Wayland);
+        assert_eq!(detect("linux", Some("tty"), false, true), DisplaySession::X11);
+        assert_eq!(detect("linux", None, false, false), DisplaySession::Unknown);
+    }
+
+    #[test]
+    fn test_auto_uses_portal_under_wayland() {
+        let auto = CaptureBackend::Auto;
+        assert_eq!(auto.resolve(DisplaySession::Wayland), CaptureBackend::Portal);
+        assert_eq!(auto.resolve(DisplaySession::X11), CaptureBackend::Screenshots);
+        assert_eq!(auto.resolve(DisplaySession::Windows), CaptureBackend::Screenshots);
+        assert_eq!(
+            CaptureBackend::Screenshots.resolve(DisplaySession::Wayland),
+            CaptureBackend::Screenshots
+        );
+    }
+
+    #[test]
+    fn test_status_explains_unavailable_backends() {
+        let status = capture_backend_status(CaptureBackend::Screenshots, DisplaySession::Wayland);
+        assert_eq!(status.active, CaptureBackend::Screenshots);
+        assert!(!status.available);
+        assert!(status.reason.is_some());
+        assert_eq!(status.backends.len(), 2);
+        assert!(status.backends.iter().all(|b| b.available == b.reason.is_none()));
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["requested"], "screenshots");
+        assert_eq!(json["session"], "wayland");
+    }
+}
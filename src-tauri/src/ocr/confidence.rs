@@ -0,0 +1,176 @@
+//! Overall confidence weighting and calibration
+//!
+//! `CardMatch::overall_confidence` blends Tesseract's confidence with the
+//! fuzzy match score. The default 40/60 blend can be replaced by weights
+//! fitted to this install's detection history, where detections the user
+//! corrected are wrong and the rest are taken as implicitly confirmed.
+//! This module has no Tesseract dependency and is compiled with or without
+//! the `ocr` feature.
+
+use serde::{Deserialize, Serialize};
+
+/// Fewest labelled detections needed before fitting weights
+pub const MIN_CALIBRATION_SAMPLES: usize = 50;
+
+/// Spacing of the weight search grid
+const WEIGHT_STEP: f64 = 0.05;
+
+/// Smallest allowed sum of the two weights; lower sums would push every
+/// detection under `min_overall_confidence`
+const MIN_WEIGHT_SUM: f64 = 0.5;
+
+/// Weights of the OCR confidence and match score in the overall confidence
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceWeights {
+    pub ocr_weight: f64,
+    pub match_weight: f64,
+}
+
+impl Default for ConfidenceWeights {
+    fn default() -> Self {
+        Self {
+            ocr_weight: 0.4,
+            match_weight: 0.6,
+        }
+    }
+}
+
+impl ConfidenceWeights {
+    /// Overall confidence (0.0-1.0) from OCR confidence and match score (0-100)
+    pub fn combine(&self, ocr_confidence: i32, match_score: i32) -> f64 {
+        (ocr_confidence as f64 * self.ocr_weight + match_score as f64 * self.match_weight) / 100.0
+    }
+}
+
+/// A past detection and whether it turned out to be right
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibrationSample {
+    pub ocr_confidence: i32,
+    pub match_score: i32,
+    /// False if the user corrected this detection
+    pub correct: bool,
+}
+
+/// Outcome of fitting confidence weights to detection history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceCalibration {
+    pub weights: ConfidenceWeights,
+    pub sample_count: usize,
+    /// Samples the user corrected
+    pub corrected_count: usize,
+    /// Mean squared error of the previous weights (lower is better)
+    pub brier_before: f64,
+    /// Mean squared error of the fitted weights
+    pub brier_after: f64,
+}
+
+/// Mean squared error between predicted confidence and actual correctness
+pub fn brier_score(weights: &ConfidenceWeights, samples: &[CalibrationSample]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let total: f64 = samples
+        .iter()
+        .map(|s| {
+            let predicted = weights.combine(s.ocr_confidence, s.match_score).clamp(0.0, 1.0);
+            let actual = if s.correct { 1.0 } else { 0.0 };
+            (predicted - actual).powi(2)
+        })
+        .sum();
+
+    total / samples.len() as f64
+}
+
+/// Fit weights that minimize the Brier score over `samples`
+///
+/// Weights are searched on a 0.05 grid, with their sum kept between 0.5 and
+/// 1.0 so the overall confidence stays in range. Returns `None` with fewer
+/// than `MIN_CALIBRATION_SAMPLES` samples, or when every sample has the
+/// same label and there is nothing to separate.
+pub fn fit_weights(
+    samples: &[CalibrationSample],
+    current: &ConfidenceWeights,
+) -> Option<ConfidenceCalibration> {
+    let corrected_count = samples.iter().filter(|s| !s.correct).count();
+    if samples.len() < MIN_CALIBRATION_SAMPLES
+        || corrected_count == 0
+        || corrected_count == samples.len()
+    {
+        return None;
+    }
+
+    let steps = (1.0 / WEIGHT_STEP).round() as u32;
+    let grid = (0..=steps).map(|i| i as f64 * WEIGHT_STEP);
+
+    let brier_before = brier_score(current, samples);
+    let (weights, brier_after) = grid
+        .clone()
+        .flat_map(|ocr_weight| {
+            grid.clone().map(move |match_weight| ConfidenceWeights {
+                ocr_weight,
+                match_weight,
+            })
+        })
+        .filter(|w| {
+            let sum = w.ocr_weight + w.match_weight;
+            (MIN_WEIGHT_SUM..=1.0 + f64::EPSILON).contains(&sum)
+        })
+        .map(|w| (w, brier_score(&w, samples)))
+        .chain(std::iter::once((*current, brier_before)))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+
+    Some(ConfidenceCalibration {
+        weights,
+        sample_count: samples.len(),
+        corrected_count,
+        brier_before,
+        brier_after,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(ocr_confidence: i32, match_score: i32, correct: bool) -> CalibrationSample {
+        CalibrationSample {
+            ocr_confidence,
+            match_score,
+            correct,
+        }
+    }
+
+    #[test]
+    fn test_default_weights_match_legacy_blend() {
+        let weights = ConfidenceWeights::default();
+        assert!((weights.combine(80, 80) - 0.8).abs() < 1e-9);
+        assert!((weights.combine(80, 60) - 0.68).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_weights_needs_labelled_history() {
+        let current = ConfidenceWeights::default();
+        assert!(fit_weights(&[sample(90, 90, true); 10], &current).is_none());
+        assert!(fit_weights(&[sample(90, 90, true); 100], &current).is_none());
+    }
+
+    #[test]
+    fn test_fit_weights_favors_predictive_signal() {
+        // Match score separates right from wrong; OCR confidence is noise
+        let mut samples = Vec::new();
+        for i in 0..40 {
+            let noise = if i % 2 == 0 { 95 } else { 40 };
+            samples.push(sample(noise, 95, true));
+            samples.push(sample(noise, 60, false));
+        }
+
+        let current = ConfidenceWeights::default();
+        let fit = fit_weights(&samples, &current).unwrap();
+        assert_eq!(fit.sample_count, 80);
+        assert_eq!(fit.corrected_count, 40);
+        assert!(fit.brier_after < fit.brier_before);
+        assert!(fit.weights.match_weight > current.match_weight);
+        assert!(fit.weights.ocr_weight < current.ocr_weight);
+    }
+}
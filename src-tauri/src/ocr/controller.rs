@@ -0,0 +1,345 @@
+//! Single-owner OCR orchestration
+//!
+//! Detection settings and the continuous detection loop are owned by one
+//! background thread. Hotkeys, the tray, and UI commands talk to it through
+//! an `OcrController` handle by message passing, so concurrent callers never
+//! share a lock and a panicking detection cannot poison shared state.
+
+use super::archive::ArchiveConfig;
+use super::CardDetectionOptions;
+use crate::crash::catch_panic;
+use serde::{Deserialize, Serialize};
+use std::panic::AssertUnwindSafe;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+use std::time::{Duration, Instant};
+
+/// Capacity of the control channel; senders block briefly if it is full
+const CONTROL_CHANNEL_CAPACITY: usize = 32;
+
+/// Shortest allowed interval for continuous detection
+pub const MIN_LOOP_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Default interval for continuous detection
+pub const DEFAULT_LOOP_INTERVAL: Duration = Duration::from_millis(1500);
+
+/// Error type for controller operations
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlError {
+    /// The owner thread has exited and can no longer accept messages
+    ControllerStopped,
+    /// The requested detection panicked and produced no result
+    DetectionFailed,
+}
+
+impl std::fmt::Display for ControlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlError::ControllerStopped => write!(f, "OCR controller is not running"),
+            ControlError::DetectionFailed => write!(f, "OCR detection failed unexpectedly"),
+        }
+    }
+}
+
+impl std::error::Error for ControlError {}
+
+/// Result type for controller operations
+pub type ControlResult<T> = Result<T, ControlError>;
+
+/// All settings owned by the OCR controller
+#[derive(Debug, Clone, Default)]
+pub struct OcrSettings {
+    pub detection: CardDetectionOptions,
+    pub archive: ArchiveConfig,
+    /// Incremented on every update, so detectors can drop state derived
+    /// from older settings
+    pub revision: u64,
+}
+
+/// State of the continuous detection loop
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoopStatus {
+    pub running: bool,
+    pub interval_ms: u64,
+    /// Number of detections run by the loop since it was last started
+    pub iterations: u64,
+}
+
+type SettingsUpdate = Box<dyn FnOnce(&mut OcrSettings) + Send>;
+
+enum ControlMessage<R> {
+    Start(Duration, SyncSender<LoopStatus>),
+    Stop(SyncSender<LoopStatus>),
+    Status(SyncSender<LoopStatus>),
+    DetectOnce(SyncSender<R>),
+    GetSettings(SyncSender<OcrSettings>),
+    UpdateSettings(SettingsUpdate, SyncSender<OcrSettings>),
+}
+
+/// Cloneable handle to the OCR owner thread
+///
+/// `R` is the detection result type produced by the detector function.
+pub struct OcrController<R> {
+    sender: SyncSender<ControlMessage<R>>,
+}
+
+impl<R> Clone for OcrController<R> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<R: Send + 'static> OcrController<R> {
+    /// Spawn the owner thread
+    ///
+    /// * `detect` - runs one detection pass with the current settings
+    /// * `on_loop_result` - receives each result produced by the continuous
+    ///   loop, with the settings it was produced under
+    pub fn spawn<D, L>(settings: OcrSettings, detect: D, on_loop_result: L) -> Self
+    where
+        D: FnMut(&OcrSettings) -> R + Send + 'static,
+        L: FnMut(&OcrSettings, &R) + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::sync_channel(CONTROL_CHANNEL_CAPACITY);
+
+        std::thread::Builder::new()
+            .name("ocr-controller".to_string())
+            .spawn(move || run_owner(receiver, settings, detect, on_loop_result))
+            .expect("Failed to spawn OCR controller thread");
+
+        Self { sender }
+    }
+
+    /// Start continuous detection, or change the interval if already running
+    pub fn start(&self, interval: Duration) -> ControlResult<LoopStatus> {
+        self.request(|reply| ControlMessage::Start(interval, reply))
+    }
+
+    /// Stop continuous detection; stopping an idle loop is a no-op
+    pub fn stop(&self) -> ControlResult<LoopStatus> {
+        self.request(ControlMessage::Stop)
+    }
+
+    /// Get the continuous loop status
+    pub fn status(&self) -> ControlResult<LoopStatus> {
+        self.request(ControlMessage::Status)
+    }
+
+    /// Run a single detection pass and wait for its result
+    pub fn detect_once(&self) -> ControlResult<R> {
+        let (reply, response) = mpsc::sync_channel(1);
+        self.sender
+            .send(ControlMessage::DetectOnce(reply))
+            .map_err(|_| ControlError::ControllerStopped)?;
+        // The reply sender is dropped without a value if the detector panics
+        response.recv().map_err(|_| ControlError::DetectionFailed)
+    }
+
+    /// Get a snapshot of the current settings
+    pub fn settings(&self) -> ControlResult<OcrSettings> {
+        self.request(ControlMessage::GetSettings)
+    }
+
+    /// Modify settings on the owner thread and return the updated snapshot
+    pub fn update_settings<F>(&self, update: F) -> ControlResult<OcrSettings>
+    where
+        F: FnOnce(&mut OcrSettings) + Send + 'static,
+    {
+        self.request(|reply| ControlMessage::UpdateSettings(Box::new(update), reply))
+    }
+
+    fn request<T>(
+        &self,
+        message: impl FnOnce(SyncSender<T>) -> ControlMessage<R>,
+    ) -> ControlResult<T> {
+        let (reply, response) = mpsc::sync_channel(1);
+        self.sender
+            .send(message(reply))
+            .map_err(|_| ControlError::ControllerStopped)?;
+        response.recv().map_err(|_| ControlError::ControllerStopped)
+    }
+}
+
+/// Owner thread body: handles messages and runs the loop until all handles are dropped
+fn run_owner<R, D, L>(
+    receiver: Receiver<ControlMessage<R>>,
+    mut settings: OcrSettings,
+    mut detect: D,
+    mut on_loop_result: L,
+) where
+    D: FnMut(&OcrSettings) -> R,
+    L: FnMut(&OcrSettings, &R),
+{
+    let mut interval = DEFAULT_LOOP_INTERVAL;
+    let mut running = false;
+    let mut iterations = 0u64;
+    let mut next_run = Instant::now();
+
+    let status = |running: bool, interval: Duration, iterations: u64| LoopStatus {
+        running,
+        interval_ms: interval.as_millis() as u64,
+        iterations,
+    };
+
+    loop {
+        let message = if running {
+            match receiver.recv_timeout(next_run.saturating_duration_since(Instant::now())) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => {
+                    match catch_panic(AssertUnwindSafe(|| detect(&settings))) {
+                        Ok(result) => on_loop_result(&settings, &result),
+                        Err(_) => log::error!("OCR detection panicked in continuous loop"),
+                    }
+                    iterations += 1;
+                    next_run = Instant::now() + interval;
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            match receiver.recv() {
+                Ok(message) => message,
+                Err(_) => break,
+            }
+        };
+
+        match message {
+            ControlMessage::Start(requested, reply) => {
+                interval = requested.max(MIN_LOOP_INTERVAL);
+                if !running {
+                    running = true;
+                    iterations = 0;
+                    next_run = Instant::now();
+                    log::info!("Continuous OCR detection started ({:?})", interval);
+                }
+                let _ = reply.send(status(running, interval, iterations));
+            }
+            ControlMessage::Stop(reply) => {
+                if running {
+                    running = false;
+                    log::info!("Continuous OCR detection stopped after {} passes", iterations);
+                }
+                let _ = reply.send(status(running, interval, iterations));
+            }
+            ControlMessage::Status(reply) => {
+                let _ = reply.send(status(running, interval, iterations));
+            }
+            ControlMessage::DetectOnce(reply) => {
+                match catch_panic(AssertUnwindSafe(|| detect(&settings))) {
+                    Ok(result) => {
+                        let _ = reply.send(result);
+                    }
+                    Err(_) => log::error!("OCR detection panicked"),
+                }
+            }
+            ControlMessage::GetSettings(reply) => {
+                let _ = reply.send(settings.clone());
+            }
+            ControlMessage::UpdateSettings(update, reply) => {
+                // Apply to a copy so a panicking update leaves settings untouched
+                let mut updated = settings.clone();
+                match catch_panic(AssertUnwindSafe(|| update(&mut updated))) {
+                    Ok(()) => {
+                        updated.revision = settings.revision.wrapping_add(1);
+                        settings = updated;
+                    }
+                    Err(_) => log::error!("OCR settings update panicked; keeping previous settings"),
+                }
+                let _ = reply.send(settings.clone());
+            }
+        }
+    }
+
+    log::debug!("OCR controller thread exiting");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn spawn_counting() -> (OcrController<usize>, Arc<AtomicUsize>) {
+        let loop_results = Arc::new(AtomicUsize::new(0));
+        let counter = loop_results.clone();
+        let mut calls = 0;
+        let controller = OcrController::spawn(
+            OcrSettings::default(),
+            move |_settings| {
+                calls += 1;
+                calls
+            },
+            move |_settings, _result| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            },
+        );
+        (controller, loop_results)
+    }
+
+    #[test]
+    fn test_detect_once_and_settings() {
+        let (controller, _) = spawn_counting();
+
+        assert_eq!(controller.detect_once().unwrap(), 1);
+        assert_eq!(controller.detect_once().unwrap(), 2);
+
+        let updated = controller
+            .update_settings(|s| s.detection.min_overall_confidence = 0.9)
+            .unwrap();
+        assert!((updated.detection.min_overall_confidence - 0.9).abs() < f64::EPSILON);
+        assert_eq!(updated.revision, 1);
+        assert!((controller.settings().unwrap().detection.min_overall_confidence - 0.9).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_start_stop_idempotent() {
+        let (controller, loop_results) = spawn_counting();
+
+        let status = controller.start(Duration::from_millis(1)).unwrap();
+        assert!(status.running);
+        assert_eq!(status.interval_ms, MIN_LOOP_INTERVAL.as_millis() as u64);
+
+        // Starting again keeps the loop running without resetting it
+        assert!(controller.start(MIN_LOOP_INTERVAL).unwrap().running);
+        std::thread::sleep(Duration::from_millis(100));
+
+        let stopped = controller.stop().unwrap();
+        assert!(!stopped.running);
+        assert!(stopped.iterations >= 1);
+        assert_eq!(loop_results.load(Ordering::SeqCst) as u64, stopped.iterations);
+
+        // Stopping twice is a no-op
+        assert_eq!(controller.stop().unwrap(), stopped);
+    }
+
+    #[test]
+    fn test_panics_do_not_kill_controller() {
+        let controller: OcrController<u32> = OcrController::spawn(
+            OcrSettings::default(),
+            |settings| {
+                if settings.detection.save_debug_images {
+                    panic!("detector failure");
+                }
+                7
+            },
+            |_, _| {},
+        );
+
+        controller
+            .update_settings(|s| s.detection.save_debug_images = true)
+            .unwrap();
+        assert_eq!(controller.detect_once(), Err(ControlError::DetectionFailed));
+
+        // A panicking update is discarded
+        let settings = controller
+            .update_settings(|_| panic!("bad update"))
+            .unwrap();
+        assert!(settings.detection.save_debug_images);
+
+        controller
+            .update_settings(|s| s.detection.save_debug_images = false)
+            .unwrap();
+        assert_eq!(controller.detect_once(), Ok(7));
+    }
+}
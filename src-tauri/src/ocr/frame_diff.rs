@@ -0,0 +1,106 @@
+//! Screen-change gating for repeated captures
+//!
+//! Hashes each captured region so the continuous detection loop can reuse
+//! the previous result for a region whose content has not changed, instead
+//! of preprocessing and running Tesseract on an identical frame again. This
+//! module only depends on `image` and is compiled with or without the `ocr`
+//! feature; it is re-exported from `ocr::capture`.
+
+use super::capture::CaptureRegion;
+use image::{ImageBuffer, Rgba};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Low bits dropped from each channel before hashing, so compression and
+/// dithering noise does not register as a change
+const NOISE_MASK: u8 = 0b1111_1000;
+
+/// Hash the visible content of a captured region
+pub fn frame_hash(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    image.dimensions().hash(&mut hasher);
+    for pixel in image.pixels() {
+        let [r, g, b, _] = pixel.0;
+        [r & NOISE_MASK, g & NOISE_MASK, b & NOISE_MASK].hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Remembers the last frame hash and result for each capture region
+#[derive(Debug, Clone)]
+pub struct FrameGate<T> {
+    frames: HashMap<CaptureRegion, (u64, T)>,
+}
+
+impl<T> Default for FrameGate<T> {
+    fn default() -> Self {
+        Self {
+            frames: HashMap::new(),
+        }
+    }
+}
+
+impl<T> FrameGate<T> {
+    /// Create an empty gate; the first frame of every region is treated as changed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Result stored for `region` if its content still hashes to `hash`
+    pub fn unchanged(&self, region: &CaptureRegion, hash: u64) -> Option<&T> {
+        self.frames
+            .get(region)
+            .filter(|(stored, _)| *stored == hash)
+            .map(|(_, result)| result)
+    }
+
+    /// Store the result computed for a region's current frame
+    pub fn store(&mut self, region: CaptureRegion, hash: u64, result: T) {
+        self.frames.insert(region, (hash, result));
+    }
+
+    /// Forget all stored frames, forcing the next cycle to process every region
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Whether no frames are stored
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        ImageBuffer::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn test_frame_hash_ignores_noise() {
+        assert_eq!(frame_hash(&solid(10, 10, 200)), frame_hash(&solid(10, 10, 201)));
+        assert_ne!(frame_hash(&solid(10, 10, 200)), frame_hash(&solid(10, 10, 40)));
+        assert_ne!(frame_hash(&solid(10, 10, 200)), frame_hash(&solid(20, 5, 200)));
+    }
+
+    #[test]
+    fn test_frame_gate() {
+        let region = CaptureRegion::new(0, 0, 10, 10);
+        let other = CaptureRegion::new(0, 20, 10, 10);
+        let mut gate = FrameGate::new();
+
+        let hash = frame_hash(&solid(10, 10, 200));
+        assert!(gate.unchanged(&region, hash).is_none());
+
+        gate.store(region, hash, "Talos");
+        assert_eq!(gate.unchanged(&region, hash), Some(&"Talos"));
+        assert!(gate.unchanged(&region, frame_hash(&solid(10, 10, 40))).is_none());
+        assert!(gate.unchanged(&other, hash).is_none());
+
+        gate.clear();
+        assert!(gate.is_empty());
+    }
+}
@@ -0,0 +1,485 @@
+//! Template glyph OCR
+//!
+//! A fallback recognizer for machines without Tesseract. Card names are set
+//! in one typeface, so a name can be read by cutting the preprocessed line
+//! into glyphs and correlating each with the templates of a `GlyphAtlas`.
+//!
+//! Atlases are learned from recorded sessions (see `recorder`): every
+//! region that matched a card is segmented, and when its glyph count agrees
+//! with the card name each glyph becomes a template for its character.
+//! This module is compiled with or without the `ocr` feature.
+
+use super::recorder::{RegionDecision, SessionManifest, MANIFEST_FILE};
+use image::GrayImage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// File name of the atlas in the app data folder
+pub const ATLAS_FILE: &str = "glyph_atlas.json";
+
+/// Height every glyph is normalized to, in pixels
+pub const GLYPH_HEIGHT: u32 = 24;
+
+/// Templates kept per character, to cover rendering variations
+pub const MAX_TEMPLATES_PER_CHAR: usize = 4;
+
+/// A glyph at least this similar to an existing template adds nothing
+const DUPLICATE_SIMILARITY: f64 = 0.95;
+
+/// Gap between glyphs, as a fraction of the line height, read as a space
+const SPACE_GAP_RATIO: f64 = 0.35;
+
+/// Engine that turns a preprocessed region into text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecognitionEngine {
+    /// Tesseract, through `leptess`
+    #[default]
+    Tesseract,
+    /// Correlation against a learned `GlyphAtlas`
+    TemplateGlyph,
+}
+
+/// Error type for glyph atlases
+#[derive(Debug)]
+pub enum GlyphError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Image(image::ImageError),
+}
+
+impl std::fmt::Display for GlyphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GlyphError::Io(e) => write!(f, "Glyph atlas I/O error: {}", e),
+            GlyphError::Json(e) => write!(f, "Invalid glyph atlas: {}", e),
+            GlyphError::Image(e) => write!(f, "Failed to read recorded image: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for GlyphError {}
+
+impl From<std::io::Error> for GlyphError {
+    fn from(e: std::io::Error) -> Self {
+        GlyphError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for GlyphError {
+    fn from(e: serde_json::Error) -> Self {
+        GlyphError::Json(e)
+    }
+}
+
+impl From<image::ImageError> for GlyphError {
+    fn from(e: image::ImageError) -> Self {
+        GlyphError::Image(e)
+    }
+}
+
+/// Result type for glyph atlas operations
+pub type GlyphResult<T> = Result<T, GlyphError>;
+
+/// One learned glyph, `GLYPH_HEIGHT` rows of `#` (ink) and `.`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlyphTemplate {
+    pub ch: char,
+    pub width: u32,
+    pub rows: Vec<String>,
+}
+
+impl GlyphTemplate {
+    fn from_bitmap(ch: char, bitmap: &GlyphBitmap) -> Self {
+        let rows = bitmap
+            .ink
+            .chunks(bitmap.width as usize)
+            .map(|row| row.iter().map(|&ink| if ink { '#' } else { '.' }).collect())
+            .collect();
+        Self {
+            ch,
+            width: bitmap.width,
+            rows,
+        }
+    }
+
+    fn bitmap(&self) -> GlyphBitmap {
+        GlyphBitmap {
+            width: self.width,
+            ink: self
+                .rows
+                .iter()
+                .flat_map(|row| row.chars().map(|c| c == '#'))
+                .collect(),
+        }
+    }
+}
+
+/// Templates for the characters of the card-name typeface
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlyphAtlas {
+    pub glyphs: Vec<GlyphTemplate>,
+}
+
+/// Text read by `GlyphAtlas::recognize`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphReading {
+    pub text: String,
+    /// Mean similarity of each glyph to its best template (0-100)
+    pub confidence: i32,
+}
+
+/// What learning from a recorded session did
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LearnReport {
+    /// Matched regions whose glyphs were added
+    pub samples_used: usize,
+    /// Matched regions whose glyph count didn't agree with the card name
+    pub samples_skipped: usize,
+    /// Characters the atlas now has templates for
+    pub characters: String,
+}
+
+impl GlyphAtlas {
+    /// Read an atlas saved with `save`
+    pub fn load(path: &Path) -> GlyphResult<Self> {
+        let atlas: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        Ok(atlas)
+    }
+
+    pub fn save(&self, path: &Path) -> GlyphResult<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Characters with at least one template, in order
+    pub fn characters(&self) -> String {
+        let mut chars: Vec<char> = self.glyphs.iter().map(|g| g.ch).collect();
+        chars.sort_unstable();
+        chars.dedup();
+        chars.into_iter().collect()
+    }
+
+    /// Add the glyphs of a preprocessed line known to read `text`
+    ///
+    /// Returns false, learning nothing, when the line doesn't split into
+    /// exactly one glyph per non-space character of `text`.
+    pub fn learn(&mut self, line: &GrayImage, text: &str) -> bool {
+        let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let Some(glyphs) = segment(line) else {
+            return false;
+        };
+        if chars.is_empty() || glyphs.len() != chars.len() {
+            return false;
+        }
+
+        for (ch, glyph) in chars.into_iter().zip(glyphs) {
+            let existing: Vec<GlyphBitmap> = self
+                .glyphs
+                .iter()
+                .filter(|template| template.ch == ch)
+                .map(GlyphTemplate::bitmap)
+                .collect();
+            let duplicate = existing
+                .iter()
+                .any(|template| similarity(&glyph.bitmap, template) >= DUPLICATE_SIMILARITY);
+            if existing.len() < MAX_TEMPLATES_PER_CHAR && !duplicate {
+                self.glyphs
+                    .push(GlyphTemplate::from_bitmap(ch, &glyph.bitmap));
+            }
+        }
+        true
+    }
+
+    /// Learn from every matched region of a recorded session folder
+    pub fn learn_from_session(&mut self, session_dir: &Path) -> GlyphResult<LearnReport> {
+        let manifest: SessionManifest =
+            serde_json::from_str(&fs::read_to_string(session_dir.join(MANIFEST_FILE))?)?;
+
+        let mut report = LearnReport::default();
+        for record in &manifest.regions {
+            let (Some(image), Some(name)) = (&record.preprocessed_image, &record.card_name) else {
+                continue;
+            };
+            if record.decision != RegionDecision::Matched {
+                continue;
+            }
+
+            let line = image::open(session_dir.join(image))?.to_luma8();
+            if self.learn(&line, name) {
+                report.samples_used += 1;
+            } else {
+                report.samples_skipped += 1;
+            }
+        }
+
+        report.characters = self.characters();
+        Ok(report)
+    }
+
+    /// Read a preprocessed line, or `None` if the atlas is empty or the
+    /// line has no ink
+    pub fn recognize(&self, line: &GrayImage) -> Option<GlyphReading> {
+        if self.glyphs.is_empty() {
+            return None;
+        }
+        let templates: Vec<(char, GlyphBitmap)> =
+            self.glyphs.iter().map(|g| (g.ch, g.bitmap())).collect();
+        let glyphs = segment(line)?;
+
+        let mut text = String::new();
+        let mut total = 0.0;
+        for glyph in &glyphs {
+            let (ch, score) = templates
+                .iter()
+                .map(|(ch, template)| (*ch, similarity(&glyph.bitmap, template)))
+                .fold(('?', 0.0), |best, candidate| {
+                    if candidate.1 > best.1 {
+                        candidate
+                    } else {
+                        best
+                    }
+                });
+            if glyph.space_before {
+                text.push(' ');
+            }
+            text.push(ch);
+            total += score;
+        }
+
+        Some(GlyphReading {
+            text,
+            confidence: (total / glyphs.len() as f64 * 100.0).round() as i32,
+        })
+    }
+}
+
+/// Binary glyph normalized to `GLYPH_HEIGHT` rows
+#[derive(Debug, Clone)]
+struct GlyphBitmap {
+    width: u32,
+    ink: Vec<bool>,
+}
+
+impl GlyphBitmap {
+    fn at(&self, x: u32, y: u32) -> bool {
+        self.ink[(y * self.width + x) as usize]
+    }
+}
+
+/// A glyph cut from a line
+#[derive(Debug, Clone)]
+struct Glyph {
+    bitmap: GlyphBitmap,
+    /// Whether a word gap precedes the glyph
+    space_before: bool,
+}
+
+/// Cut a line into glyphs at columns without ink
+///
+/// Ink is the less common side of mid-gray, so light text on a dark banner
+/// and dark text on a light one both work. Each glyph spans the full ink
+/// height of the line, keeping `'` and `-` apart from letters.
+fn segment(line: &GrayImage) -> Option<Vec<Glyph>> {
+    let (width, height) = line.dimensions();
+    let dark = line.pixels().filter(|p| p[0] < 128).count();
+    let ink_is_dark = dark * 2 <= line.pixels().len();
+    let is_ink = |x: u32, y: u32| (line.get_pixel(x, y)[0] < 128) == ink_is_dark;
+
+    let rows: Vec<u32> = (0..height)
+        .filter(|&y| (0..width).any(|x| is_ink(x, y)))
+        .collect();
+    let (&top, &bottom) = (rows.first()?, rows.last()?);
+    let line_height = bottom - top + 1;
+    let column_ink: Vec<bool> = (0..width)
+        .map(|x| (top..=bottom).any(|y| is_ink(x, y)))
+        .collect();
+
+    let mut glyphs = Vec::new();
+    let mut x = 0;
+    let mut gap_start: Option<u32> = None;
+    while x < width {
+        if !column_ink[x as usize] {
+            x += 1;
+            continue;
+        }
+        let left = x;
+        while x < width && column_ink[x as usize] {
+            x += 1;
+        }
+        let right = x;
+
+        let space_before = gap_start
+            .is_some_and(|end| (left - end) as f64 >= line_height as f64 * SPACE_GAP_RATIO);
+        gap_start = Some(right);
+
+        let glyph_width = ((right - left) as f64 * GLYPH_HEIGHT as f64 / line_height as f64)
+            .round()
+            .max(1.0) as u32;
+        let ink = (0..GLYPH_HEIGHT)
+            .flat_map(|gy| (0..glyph_width).map(move |gx| (gx, gy)))
+            .map(|(gx, gy)| {
+                let sx = left + gx * (right - left) / glyph_width;
+                let sy = top + gy * line_height / GLYPH_HEIGHT;
+                is_ink(sx, sy)
+            })
+            .collect();
+
+        glyphs.push(Glyph {
+            bitmap: GlyphBitmap {
+                width: glyph_width,
+                ink,
+            },
+            space_before,
+        });
+    }
+
+    (!glyphs.is_empty()).then_some(glyphs)
+}
+
+/// Similarity (0.0-1.0) of a glyph to a template
+///
+/// The glyph is resampled to the template's width and compared by the Dice
+/// overlap of their ink, scaled down when their widths differ.
+fn similarity(glyph: &GlyphBitmap, template: &GlyphBitmap) -> f64 {
+    let (mut overlap, mut glyph_ink, mut template_ink) = (0u32, 0u32, 0u32);
+    for y in 0..GLYPH_HEIGHT {
+        for x in 0..template.width {
+            let g = glyph.at(x * glyph.width / template.width, y);
+            let t = template.at(x, y);
+            overlap += (g && t) as u32;
+            glyph_ink += g as u32;
+            template_ink += t as u32;
+        }
+    }
+    if glyph_ink + template_ink == 0 {
+        return 0.0;
+    }
+
+    let dice = 2.0 * overlap as f64 / (glyph_ink + template_ink) as f64;
+    let widths =
+        (glyph.width.min(template.width) as f64) / (glyph.width.max(template.width) as f64);
+    dice * widths.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    /// 5x7 patterns for a few letters, `#` = ink
+    fn pattern(ch: char) -> [&'static str; 7] {
+        match ch {
+            'F' => [
+                "#####", "#....", "#....", "####.", "#....", "#....", "#....",
+            ],
+            'E' => [
+                "#####", "#....", "#....", "####.", "#....", "#....", "#####",
+            ],
+            'L' => [
+                "#....", "#....", "#....", "#....", "#....", "#....", "#####",
+            ],
+            'O' => [
+                ".###.", "#...#", "#...#", "#...#", "#...#", "#...#", ".###.",
+            ],
+            _ => panic!("no pattern for {}", ch),
+        }
+    }
+
+    /// White text on a black banner at `scale` pixels per pattern cell
+    fn render(text: &str, scale: u32) -> GrayImage {
+        let cell = |n: u32| n * scale;
+        let width = cell(
+            2 + text
+                .chars()
+                .map(|c| if c == ' ' { 4 } else { 6 })
+                .sum::<u32>(),
+        );
+        let mut img = GrayImage::from_pixel(width, cell(11), Luma([0]));
+        let mut left = 2;
+        for ch in text.chars() {
+            if ch == ' ' {
+                left += 4;
+                continue;
+            }
+            for (row, line) in pattern(ch).iter().enumerate() {
+                for (col, c) in line.chars().enumerate() {
+                    if c == '#' {
+                        for dy in 0..scale {
+                            for dx in 0..scale {
+                                let x = cell(left + col as u32) + dx;
+                                let y = cell(2 + row as u32) + dy;
+                                img.put_pixel(x, y, Luma([255]));
+                            }
+                        }
+                    }
+                }
+            }
+            left += 6;
+        }
+        img
+    }
+
+    #[test]
+    fn test_learn_and_recognize() {
+        let mut atlas = GlyphAtlas::default();
+        assert!(atlas.recognize(&render("FEL", 3)).is_none());
+
+        assert!(atlas.learn(&render("FEL", 3), "FEL"));
+        assert!(atlas.learn(&render("LO", 3), "LO"));
+        // Glyph count doesn't match the name
+        assert!(!atlas.learn(&render("FEL", 3), "FELL"));
+        assert_eq!(atlas.characters(), "EFLO");
+
+        // Same typeface at another size and spacing
+        let reading = atlas.recognize(&render("FOE LOL", 4)).unwrap();
+        assert_eq!(reading.text, "FOE LOL");
+        assert!(reading.confidence > 90);
+    }
+
+    #[test]
+    fn test_duplicate_glyphs_are_not_stored_twice() {
+        let mut atlas = GlyphAtlas::default();
+        assert!(atlas.learn(&render("FEL", 3), "FEL"));
+        assert!(atlas.learn(&render("FEL", 3), "FEL"));
+        assert_eq!(atlas.glyphs.len(), 3);
+    }
+
+    #[test]
+    fn test_atlas_round_trip_and_session_learning() {
+        let dir = tempfile::TempDir::new().unwrap();
+        render("FEL", 3)
+            .save(dir.path().join("pass1_pre.png"))
+            .unwrap();
+        let manifest = serde_json::json!({
+            "started_at": "2026-01-01T00:00:00Z",
+            "app_version": "0.1.0",
+            "passes": 1,
+            "regions": [{
+                "pass": 1, "region_index": 0, "x": 0, "y": 0, "width": 10, "height": 10,
+                "recorded_at": "2026-01-01T00:00:00Z", "raw_image": null,
+                "preprocessed_image": "pass1_pre.png", "ocr_text": "FEL",
+                "ocr_confidence": 90, "decision": "matched", "card_id": "banished_fel",
+                "card_name": "FEL", "match_score": 100, "overall_confidence": 0.9,
+                "candidates": [], "error": null
+            }]
+        });
+        fs::write(dir.path().join(MANIFEST_FILE), manifest.to_string()).unwrap();
+
+        let mut atlas = GlyphAtlas::default();
+        let report = atlas.learn_from_session(dir.path()).unwrap();
+        assert_eq!(report.samples_used, 1);
+        assert_eq!(report.characters, "EFL");
+
+        let path = dir.path().join(ATLAS_FILE);
+        atlas.save(&path).unwrap();
+        assert_eq!(GlyphAtlas::load(&path).unwrap(), atlas);
+    }
+
+    #[test]
+    fn test_engine_serialization() {
+        let json = serde_json::to_string(&RecognitionEngine::TemplateGlyph).unwrap();
+        assert_eq!(json, r#""template_glyph""#);
+    }
+}
@@ -0,0 +1,806 @@
+//! Card name matching
+//!
+//! Matches OCR text against known card names. Scores are normalized to
+//! 0-100 (Levenshtein similarity), so `min_match_score` behaves the same
+//! for short and long card names. This module has no Tesseract dependency
+//! and is compiled with or without the `ocr` feature.
+
+use super::banner::BannerColor;
+use super::confidence::ConfidenceWeights;
+use super::recognize::{
+    normalize_card_name, CardMatch, OcrResult, RecognizeError, RecognizeResult,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Default score gap between the best and second-best match below which
+/// the match is reported as ambiguous
+pub const DEFAULT_AMBIGUITY_MARGIN: i32 = 5;
+
+/// Default number of candidates reported for an ambiguous match
+pub const DEFAULT_MAX_CANDIDATES: usize = 3;
+
+/// Partial (word-window) matches are scaled by this factor so that a
+/// full-name match always ranks above a match on part of a name
+const PARTIAL_MATCH_FACTOR: f64 = 0.9;
+
+/// Minimum OCR text length for partial (word-window) matching
+const MIN_PARTIAL_MATCH_LEN: usize = 3;
+
+/// Matches on a card's rules text are scaled by this factor so that a
+/// readable name always ranks above them
+const TEXT_MATCH_FACTOR: f64 = 0.8;
+
+/// Words from the start of a description compared with OCR text
+const DESCRIPTION_OPENING_WORDS: usize = 5;
+
+/// Similarity an OCR word run needs to count as a keyword
+const KEYWORD_MATCH_SCORE: i32 = 80;
+
+/// Levenshtein edit distance between two character sequences
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Similarity of two strings as 0-100, where 100 is identical
+pub fn similarity(a: &str, b: &str) -> i32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let max_len = a.len().max(b.len());
+
+    if max_len == 0 {
+        return 100;
+    }
+
+    let distance = levenshtein(&a, &b);
+    (100.0 * (1.0 - distance as f64 / max_len as f64)).round() as i32
+}
+
+/// Normalized 0-100 score of OCR text against a candidate card name
+///
+/// Compares against the whole name, and (when the OCR text has fewer words
+/// than the name) against each run of consecutive words of the same length,
+/// to handle captures that cut off part of a long name.
+pub fn match_score(candidate: &str, ocr_text: &str) -> i32 {
+    let candidate = normalize_card_name(candidate);
+    let ocr_text = normalize_card_name(ocr_text);
+
+    if candidate.is_empty() || ocr_text.is_empty() {
+        return 0;
+    }
+
+    let mut best = similarity(&candidate, &ocr_text);
+
+    let candidate_words: Vec<&str> = candidate.split_whitespace().collect();
+    let ocr_word_count = ocr_text.split_whitespace().count();
+
+    if ocr_word_count < candidate_words.len() && ocr_text.chars().count() >= MIN_PARTIAL_MATCH_LEN {
+        for window in candidate_words.windows(ocr_word_count) {
+            let partial = similarity(&window.join(" "), &ocr_text);
+            best = best.max((partial as f64 * PARTIAL_MATCH_FACTOR).round() as i32);
+        }
+    }
+
+    best
+}
+
+/// Rules text a card can be recognized by when its name can't be read
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardText {
+    pub card_id: String,
+    /// Normalized keywords, e.g. `multistrike`
+    pub keywords: Vec<String>,
+    /// First words of the normalized description
+    pub opening: String,
+}
+
+impl CardText {
+    pub fn new(card_id: &str, keywords: &[String], description: &str) -> Self {
+        let description = normalize_card_name(description);
+        Self {
+            card_id: card_id.to_string(),
+            keywords: keywords
+                .iter()
+                .map(|keyword| normalize_card_name(keyword))
+                .filter(|keyword| !keyword.is_empty())
+                .collect(),
+            opening: description
+                .split_whitespace()
+                .take(DESCRIPTION_OPENING_WORDS)
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+
+    /// Normalized 0-100 score of OCR text against this card's text
+    ///
+    /// The better of two scores: the opening words of the description
+    /// against the same number of words at the start of the OCR text, and
+    /// the share of the card's keywords found anywhere in it. Scaled by
+    /// `TEXT_MATCH_FACTOR`.
+    pub fn match_score(&self, ocr_text: &str) -> i32 {
+        let ocr_text = normalize_card_name(ocr_text);
+        let words: Vec<&str> = ocr_text.split_whitespace().collect();
+        if words.is_empty() {
+            return 0;
+        }
+
+        let opening_words = self.opening.split_whitespace().count();
+        let opening = if opening_words == 0 {
+            0
+        } else {
+            let start = words[..opening_words.min(words.len())].join(" ");
+            match_score(&self.opening, &start)
+        };
+
+        let keywords = if self.keywords.is_empty() {
+            0
+        } else {
+            let found = self
+                .keywords
+                .iter()
+                .filter(|keyword| {
+                    let width = keyword.split_whitespace().count();
+                    words.windows(width).any(|window| {
+                        similarity(keyword, &window.join(" ")) >= KEYWORD_MATCH_SCORE
+                    })
+                })
+                .count();
+            (100 * found / self.keywords.len()) as i32
+        };
+
+        (opening.max(keywords) as f64 * TEXT_MATCH_FACTOR).round() as i32
+    }
+}
+
+/// Card attributes used to decide whether a card can appear in the current run
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardPoolEntry {
+    pub card_id: String,
+    pub card_name: String,
+    pub clan: String,
+    pub rarity: String,
+    pub expansion: String,
+    /// Whether the card's expansion is enabled in the `expansions` table
+    pub expansion_active: bool,
+}
+
+/// Restricts matching to cards that can plausibly be on screen in the current run
+///
+/// Empty `clans` or `expansions` lists do not restrict on that attribute;
+/// cards from inactive expansions are always excluded. Champion cards are
+/// chosen at the start of a run and never offered in a draft, so they are
+/// excluded once a ring is known.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CandidateFilter {
+    /// The player's primary and allied clans
+    pub clans: Vec<String>,
+    /// Expansion ids in play for this run
+    pub expansions: Vec<String>,
+    /// Current ring, if a draft is in progress
+    pub ring_number: Option<i32>,
+}
+
+impl CandidateFilter {
+    /// Whether this filter restricts anything beyond inactive expansions
+    pub fn is_empty(&self) -> bool {
+        self.clans.is_empty() && self.expansions.is_empty() && self.ring_number.is_none()
+    }
+
+    /// Whether a card can appear in the run described by this filter
+    pub fn allows(&self, entry: &CardPoolEntry) -> bool {
+        let in_clans = self.clans.is_empty()
+            || self.clans.iter().any(|c| c.eq_ignore_ascii_case(&entry.clan));
+        let in_expansions = self.expansions.is_empty()
+            || self.expansions.iter().any(|e| e.eq_ignore_ascii_case(&entry.expansion));
+        let draftable = self.ring_number.is_none() || entry.rarity != "Champion";
+
+        entry.expansion_active && in_clans && in_expansions && draftable
+    }
+
+    /// Reduce a card pool to (card_id, card_name) pairs for `CardMatcher`
+    pub fn apply(&self, pool: &[CardPoolEntry]) -> Vec<(String, String)> {
+        pool.iter()
+            .filter(|entry| self.allows(entry))
+            .map(|entry| (entry.card_id.clone(), entry.card_name.clone()))
+            .collect()
+    }
+}
+
+/// A candidate card for an ambiguous match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchCandidate {
+    pub card_id: String,
+    pub card_name: String,
+    /// Normalized match score (0-100)
+    pub match_score: i32,
+}
+
+/// Card name matcher using normalized fuzzy string matching
+pub struct CardMatcher {
+    card_names: Vec<(String, String)>, // (card_id, card_name)
+    min_score: i32,
+    aliases: HashMap<String, String>, // normalized OCR text -> card_id
+    alternate_names: Vec<(String, String)>, // (card_id, alternate name)
+    ambiguity_margin: i32,
+    max_candidates: usize,
+    card_attributes: HashMap<String, (String, String)>, // card_id -> (clan, rarity)
+    confidence_weights: ConfidenceWeights,
+    card_text: Vec<CardText>,
+}
+
+impl CardMatcher {
+    /// Create a new card matcher with the given card names
+    pub fn new(card_names: Vec<(String, String)>, min_score: i32) -> RecognizeResult<Self> {
+        if card_names.is_empty() {
+            return Err(RecognizeError::NoCardNamesAvailable);
+        }
+
+        Ok(Self {
+            card_names,
+            min_score,
+            aliases: HashMap::new(),
+            alternate_names: Vec::new(),
+            ambiguity_margin: DEFAULT_AMBIGUITY_MARGIN,
+            max_candidates: DEFAULT_MAX_CANDIDATES,
+            card_attributes: HashMap::new(),
+            card_text: Vec::new(),
+            confidence_weights: ConfidenceWeights::default(),
+        })
+    }
+
+    /// Report up to `max_candidates` candidates when the best match beats
+    /// the runner-up by less than `margin` points
+    pub fn with_ambiguity(mut self, margin: i32, max_candidates: usize) -> Self {
+        self.ambiguity_margin = margin;
+        self.max_candidates = max_candidates;
+        self
+    }
+
+    /// Use learned aliases (normalized OCR text -> card_id) from user corrections
+    pub fn with_aliases(mut self, aliases: HashMap<String, String>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Use alternate names (nicknames, common OCR garbles, localized names)
+    /// as additional fuzzy match targets for their card
+    pub fn with_alternate_names(mut self, alternate_names: Vec<(String, String)>) -> Self {
+        self.alternate_names = alternate_names;
+        self
+    }
+
+    /// Use card clans and rarities to break ties with banner colors
+    pub fn with_card_attributes(mut self, pool: &[CardPoolEntry]) -> Self {
+        self.card_attributes = pool
+            .iter()
+            .map(|entry| (entry.card_id.clone(), (entry.clan.clone(), entry.rarity.clone())))
+            .collect();
+        self
+    }
+
+    /// Also match OCR text against card keywords and description openings,
+    /// for when a name is obscured but the card's text is visible
+    pub fn with_card_text(mut self, card_text: Vec<CardText>) -> Self {
+        self.card_text = card_text;
+        self
+    }
+
+    /// Blend OCR confidence and match score with these weights
+    pub fn with_confidence_weights(mut self, weights: ConfidenceWeights) -> Self {
+        self.confidence_weights = weights;
+        self
+    }
+
+    /// Set a match's OCR confidence and the overall confidence derived from it
+    pub fn apply_confidence(&self, card_match: &mut CardMatch, ocr_confidence: i32) {
+        card_match.ocr_confidence = ocr_confidence;
+        card_match.overall_confidence =
+            self.confidence_weights.combine(ocr_confidence, card_match.match_score);
+    }
+
+    /// How many of a card's attributes agree with the banner color
+    fn banner_agreement(&self, card_id: &str, banner: &BannerColor) -> usize {
+        self.card_attributes
+            .get(card_id)
+            .map_or(0, |(clan, rarity)| banner.agreement(clan, rarity))
+    }
+
+    /// All (card_id, canonical name, text to match) candidates, canonical names first
+    fn match_candidates(&self) -> impl Iterator<Item = (&String, &String, &String)> {
+        let canonical = self.card_names.iter().map(|(id, name)| (id, name, name));
+        let alternates = self.alternate_names.iter().filter_map(move |(alt_id, alt_name)| {
+            self.card_names
+                .iter()
+                .find(|(id, _)| id == alt_id)
+                .map(|(id, name)| (id, name, alt_name))
+        });
+
+        canonical.chain(alternates)
+    }
+
+    /// Look up OCR text in the learned alias table
+    fn find_alias_match(&self, ocr_text: &str) -> Option<CardMatch> {
+        let card_id = self.aliases.get(&normalize_card_name(ocr_text))?;
+        let (card_id, card_name) = self.card_names.iter().find(|(id, _)| id == card_id)?;
+
+        Some(CardMatch {
+            card_name: card_name.clone(),
+            card_id: card_id.clone(),
+            ocr_text: ocr_text.to_string(),
+            match_score: 100,
+            ocr_confidence: 0,
+            overall_confidence: 0.0,
+            candidates: Vec::new(),
+            banner: None,
+        })
+    }
+
+    /// Score every card against the OCR text, keeping each card's best score
+    fn score_all(&self, ocr_text: &str) -> Vec<CardMatch> {
+        let mut scored: Vec<CardMatch> = Vec::new();
+        let mut keep_best = |card_id: &String, card_name: &String, score: i32| {
+            match scored.iter_mut().find(|m| &m.card_id == card_id) {
+                Some(existing) if existing.match_score >= score => {}
+                Some(existing) => existing.match_score = score,
+                None => scored.push(CardMatch {
+                    card_name: card_name.clone(),
+                    card_id: card_id.clone(),
+                    ocr_text: ocr_text.to_string(),
+                    match_score: score,
+                    ocr_confidence: 0, // Will be set by caller
+                    overall_confidence: 0.0,
+                    candidates: Vec::new(),
+                    banner: None,
+                }),
+            }
+        };
+
+        for (card_id, card_name, match_text) in self.match_candidates() {
+            keep_best(card_id, card_name, match_score(match_text, ocr_text));
+        }
+
+        for text in &self.card_text {
+            if let Some((card_id, card_name)) =
+                self.card_names.iter().find(|(id, _)| *id == text.card_id)
+            {
+                keep_best(card_id, card_name, text.match_score(ocr_text));
+            }
+        }
+
+        scored
+    }
+
+    /// Find up to `limit` cards scoring at least `min_score`, best first
+    pub fn find_top_matches(&self, ocr_text: &str, limit: usize) -> Vec<CardMatch> {
+        if normalize_card_name(ocr_text).is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<CardMatch> = self
+            .score_all(ocr_text)
+            .into_iter()
+            .filter(|m| m.match_score >= self.min_score)
+            .collect();
+
+        // Stable sort keeps canonical names ahead of equal-scoring later cards
+        matches.sort_by_key(|m| std::cmp::Reverse(m.match_score));
+        matches.truncate(limit);
+
+        matches
+    }
+
+    /// Find the best matching card for the given OCR text
+    ///
+    /// If the runner-up scores within the ambiguity margin, the returned
+    /// match lists the top candidates (including itself) in `candidates`.
+    pub fn find_best_match(&self, ocr_text: &str) -> Option<CardMatch> {
+        self.find_best_match_with_banner(ocr_text, None)
+    }
+
+    /// Find the best matching card, using the crop's banner color to break ties
+    ///
+    /// When several cards score within the ambiguity margin and exactly one
+    /// of them agrees best with the inferred rarity and clan, that card is
+    /// returned as an unambiguous match.
+    pub fn find_best_match_with_banner(
+        &self,
+        ocr_text: &str,
+        banner: Option<&BannerColor>,
+    ) -> Option<CardMatch> {
+        if normalize_card_name(ocr_text).is_empty() {
+            return None;
+        }
+
+        // Known misreads corrected by the user take precedence over fuzzy matching
+        if let Some(alias_match) = self.find_alias_match(ocr_text) {
+            return Some(CardMatch {
+                banner: banner.cloned(),
+                ..alias_match
+            });
+        }
+
+        let top = self.find_top_matches(ocr_text, self.max_candidates.max(2));
+        let mut best_match = top.first()?.clone();
+        best_match.banner = banner.cloned();
+
+        let best_score = best_match.match_score;
+        let tied: Vec<&CardMatch> = top
+            .iter()
+            .filter(|m| best_score - m.match_score < self.ambiguity_margin)
+            .collect();
+        let mut is_ambiguous = tied.len() > 1;
+
+        if let Some(banner) = banner.filter(|_| is_ambiguous) {
+            let agreement = |m: &CardMatch| self.banner_agreement(&m.card_id, banner);
+            let most = tied.iter().map(|m| agreement(m)).max().unwrap_or(0);
+            let agreeing: Vec<&CardMatch> =
+                tied.into_iter().filter(|m| agreement(m) == most).collect();
+
+            if let [resolved] = agreeing[..] {
+                if most > 0 {
+                    best_match = CardMatch {
+                        banner: Some(banner.clone()),
+                        ..resolved.clone()
+                    };
+                    is_ambiguous = false;
+                }
+            }
+        }
+
+        if is_ambiguous {
+            best_match.candidates = top
+                .iter()
+                .take(self.max_candidates)
+                .map(|m| MatchCandidate {
+                    card_id: m.card_id.clone(),
+                    card_name: m.card_name.clone(),
+                    match_score: m.match_score,
+                })
+                .collect();
+        }
+
+        Some(best_match)
+    }
+
+    /// Match multiple OCR results and update their confidence scores
+    pub fn match_results(&self, ocr_results: Vec<OcrResult>) -> Vec<CardMatch> {
+        let mut matches = Vec::new();
+
+        for result in ocr_results {
+            if let Some(mut card_match) = self.find_best_match(&result.text) {
+                self.apply_confidence(&mut card_match, result.confidence);
+                matches.push(card_match);
+            }
+        }
+
+        // Sort by overall confidence (highest first)
+        matches.sort_by(|a, b| {
+            b.overall_confidence
+                .partial_cmp(&a.overall_confidence)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        matches
+    }
+
+    /// Find all cards that match above the threshold (for ambiguous matches)
+    pub fn find_all_matches(&self, ocr_text: &str, threshold: i32) -> Vec<CardMatch> {
+        if normalize_card_name(ocr_text).is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<CardMatch> = self
+            .score_all(ocr_text)
+            .into_iter()
+            .filter(|m| m.match_score >= threshold)
+            .map(|mut m| {
+                m.overall_confidence = m.match_score as f64 / 100.0;
+                m
+            })
+            .collect();
+
+        // Sort by match score (highest first)
+        matches.sort_by_key(|m| std::cmp::Reverse(m.match_score));
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_card_names() -> Vec<(String, String)> {
+        vec![
+            ("banished_fel".to_string(), "Fel".to_string()),
+            ("banished_talos".to_string(), "Talos".to_string()),
+            ("banished_just_cause".to_string(), "Just Cause".to_string()),
+            ("banished_cleave".to_string(), "Cleave".to_string()),
+            ("pyreborne_lord_fenix".to_string(), "Lord Fenix".to_string()),
+            ("underlegion_bolete".to_string(), "Bolete the Guillotine".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("fel", "fel"), 100);
+        assert_eq!(similarity("fell", "fel"), 75);
+        assert_eq!(similarity("", ""), 100);
+        assert_eq!(similarity("abc", ""), 0);
+    }
+
+    #[test]
+    fn test_match_score_is_length_independent() {
+        // One wrong character costs proportionally, regardless of name length
+        assert_eq!(match_score("Talos", "Ta1os"), 80);
+        assert_eq!(match_score("Madame Lionsmane", "Madame Li0nsmane"), 94);
+
+        // Partial captures of long names score below a full match
+        let partial = match_score("Bolete the Guillotine", "Guillotine");
+        assert_eq!(partial, 90);
+        assert!(partial < match_score("Bolete the Guillotine", "Bolete the Guillotine"));
+    }
+
+    #[test]
+    fn test_card_matcher_new_empty() {
+        let result = CardMatcher::new(vec![], 60);
+        assert!(matches!(result, Err(RecognizeError::NoCardNamesAvailable)));
+    }
+
+    #[test]
+    fn test_card_matcher_alias_takes_precedence() {
+        let cards = create_test_card_names();
+        let mut aliases = HashMap::new();
+        aliases.insert("fe1".to_string(), "banished_cleave".to_string());
+        aliases.insert("ghost".to_string(), "missing_card".to_string());
+        let matcher = CardMatcher::new(cards, 60).unwrap().with_aliases(aliases);
+
+        let m = matcher.find_best_match("FE1").unwrap();
+        assert_eq!(m.card_id, "banished_cleave");
+        assert_eq!(m.match_score, 100);
+        assert_eq!(m.ocr_text, "FE1");
+
+        // Aliases pointing at unknown cards fall back to fuzzy matching
+        assert!(matcher.find_best_match("ghost").is_none());
+    }
+
+    #[test]
+    fn test_card_matcher_alternate_names() {
+        let cards = create_test_card_names();
+        let matcher = CardMatcher::new(cards, 60)
+            .unwrap()
+            .with_alternate_names(vec![
+                ("pyreborne_lord_fenix".to_string(), "Seigneur Fenix".to_string()),
+                ("missing_card".to_string(), "Phantom Card".to_string()),
+            ]);
+
+        // Alternate names resolve to the canonical card
+        let m = matcher.find_best_match("Seigneur Fenix").unwrap();
+        assert_eq!(m.card_id, "pyreborne_lord_fenix");
+        assert_eq!(m.card_name, "Lord Fenix");
+
+        // Alternate names for unknown cards are ignored
+        assert!(matcher.find_best_match("Phantom Card").is_none());
+    }
+
+    #[test]
+    fn test_card_matcher_find_best_match() {
+        let cards = create_test_card_names();
+        let matcher = CardMatcher::new(cards, 60).unwrap();
+
+        // Exact match
+        let result = matcher.find_best_match("Fel");
+        assert!(result.is_some());
+        let m = result.unwrap();
+        assert_eq!(m.card_name, "Fel");
+        assert!(m.match_score >= 60);
+
+        // Fuzzy match
+        let result = matcher.find_best_match("Fell");
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().card_name, "Fel");
+
+        // Case insensitive
+        let result = matcher.find_best_match("FEL");
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().card_name, "Fel");
+
+        // No match
+        let result = matcher.find_best_match("NonExistentCard123");
+        assert!(result.is_none());
+
+        // Empty string
+        let result = matcher.find_best_match("");
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_card_matcher_partial_match() {
+        let cards = create_test_card_names();
+        let matcher = CardMatcher::new(cards, 60).unwrap();
+
+        // Partial match on multi-word name
+        let result = matcher.find_best_match("Guillotine");
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().card_name, "Bolete the Guillotine");
+
+        // Match on "the"
+        let result = matcher.find_best_match("Just");
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().card_name, "Just Cause");
+    }
+
+    #[test]
+    fn test_card_matcher_reports_ambiguous_candidates() {
+        let cards = vec![
+            ("card_a".to_string(), "Waxen Spike".to_string()),
+            ("card_b".to_string(), "Waxen Spire".to_string()),
+            ("card_c".to_string(), "Talos".to_string()),
+        ];
+        let matcher = CardMatcher::new(cards, 60).unwrap();
+
+        // One character from both names: ambiguous
+        let m = matcher.find_best_match("Waxen Spixe").unwrap();
+        assert!(m.is_ambiguous());
+        assert_eq!(m.candidates.len(), 2);
+        assert_eq!(m.candidates[0].card_id, m.card_id);
+
+        // Clear winner: no candidates
+        let m = matcher.find_best_match("Waxen Spike").unwrap();
+        assert_eq!(m.card_id, "card_a");
+        assert!(!m.is_ambiguous());
+
+        // A zero margin disables ambiguity reporting
+        let strict = CardMatcher::new(
+            vec![
+                ("card_a".to_string(), "Waxen Spike".to_string()),
+                ("card_b".to_string(), "Waxen Spire".to_string()),
+            ],
+            60,
+        )
+        .unwrap()
+        .with_ambiguity(0, 3);
+        assert!(!strict.find_best_match("Waxen Spixe").unwrap().is_ambiguous());
+    }
+
+    #[test]
+    fn test_card_matcher_banner_breaks_ties() {
+        let entry = |card_id: &str, card_name: &str, rarity: &str| CardPoolEntry {
+            card_id: card_id.to_string(),
+            card_name: card_name.to_string(),
+            clan: "Melting Remnant".to_string(),
+            rarity: rarity.to_string(),
+            expansion: "base".to_string(),
+            expansion_active: true,
+        };
+        let pool = vec![
+            entry("card_a", "Waxen Spike", "Common"),
+            entry("card_b", "Waxen Spire", "Rare"),
+        ];
+        let matcher = CardMatcher::new(CandidateFilter::default().apply(&pool), 60)
+            .unwrap()
+            .with_card_attributes(&pool);
+
+        let banner = |rarity: &str| BannerColor {
+            frame_rgb: [0, 0, 0],
+            fill_rgb: None,
+            rarity: Some(rarity.to_string()),
+            clan: Some("Melting Remnant".to_string()),
+        };
+
+        // The rare frame picks the rare card out of the tie
+        let m = matcher.find_best_match_with_banner("Waxen Spixe", Some(&banner("Rare"))).unwrap();
+        assert_eq!(m.card_id, "card_b");
+        assert!(!m.is_ambiguous());
+        assert_eq!(m.banner, Some(banner("Rare")));
+
+        // A banner that fits neither card leaves the match ambiguous
+        let m = matcher.find_best_match_with_banner("Waxen Spixe", Some(&banner("Champion"))).unwrap();
+        assert!(m.is_ambiguous());
+    }
+
+    #[test]
+    fn test_card_matcher_find_top_matches() {
+        let matcher = CardMatcher::new(create_test_card_names(), 60).unwrap();
+
+        let top = matcher.find_top_matches("Lord Fenix", 3);
+        assert_eq!(top[0].card_id, "pyreborne_lord_fenix");
+        assert!(top.windows(2).all(|w| w[0].match_score >= w[1].match_score));
+        assert!(matcher.find_top_matches("", 3).is_empty());
+    }
+
+    #[test]
+    fn test_card_matcher_find_all_matches() {
+        let cards = create_test_card_names();
+        let matcher = CardMatcher::new(cards, 60).unwrap();
+
+        let results = matcher.find_all_matches("Fe", 50);
+        assert!(!results.is_empty());
+        // Should find "Fel" and possibly "Lord Fenix"
+    }
+
+    #[test]
+    fn test_card_matcher_matches_card_text() {
+        let card_text = vec![
+            CardText::new(
+                "banished_just_cause",
+                &["Valor".to_string()],
+                "Deal damage equal to Valor to a front unit",
+            ),
+            CardText::new(
+                "pyreborne_lord_fenix",
+                &["Multistrike".to_string(), "Pyregel".to_string()],
+                "Pyreborne champion who scales with Pyregel",
+            ),
+        ];
+        let matcher = CardMatcher::new(create_test_card_names(), 60).unwrap();
+        assert!(matcher.find_best_match("Deal damage equal to Valor").is_none());
+
+        let matcher = matcher.with_card_text(card_text);
+
+        // The opening words of a description identify the card
+        let m = matcher.find_best_match("Deal damage equa1 to Valor to the").unwrap();
+        assert_eq!(m.card_id, "banished_just_cause");
+        assert!(m.match_score <= 80);
+
+        // So do its keywords, found anywhere in the text
+        let m = matcher.find_best_match("Gains Multistrike. Pyrege1 3").unwrap();
+        assert_eq!(m.card_id, "pyreborne_lord_fenix");
+        assert_eq!(m.match_score, 80);
+
+        // A readable name still wins
+        let m = matcher.find_best_match("Lord Fenix").unwrap();
+        assert_eq!(m.match_score, 100);
+    }
+
+    #[test]
+    fn test_candidate_filter() {
+        let entry = |id: &str, clan: &str, rarity: &str, expansion: &str, active: bool| {
+            CardPoolEntry {
+                card_id: id.to_string(),
+                card_name: id.to_string(),
+                clan: clan.to_string(),
+                rarity: rarity.to_string(),
+                expansion: expansion.to_string(),
+                expansion_active: active,
+            }
+        };
+        let pool = vec![
+            entry("banished_fel", "Banished", "Champion", "base", true),
+            entry("banished_just_cause", "Banished", "Rare", "base", true),
+            entry("pyreborne_lord_fenix", "Pyreborne", "Rare", "base", true),
+            entry("underlegion_bolete", "Underlegion", "Common", "base", true),
+            entry("railforged_card", "Railforged", "Common", "railforged", false),
+        ];
+
+        // Only inactive expansions are excluded by default
+        assert_eq!(CandidateFilter::default().apply(&pool).len(), 4);
+
+        let filter = CandidateFilter {
+            clans: vec!["banished".to_string(), "Pyreborne".to_string()],
+            expansions: vec![],
+            ring_number: Some(2),
+        };
+        let ids: Vec<String> = filter.apply(&pool).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec!["banished_just_cause", "pyreborne_lord_fenix"]);
+
+        let railforged_only = CandidateFilter {
+            expansions: vec!["railforged".to_string()],
+            ..Default::default()
+        };
+        assert!(railforged_only.apply(&pool).is_empty());
+    }
+}
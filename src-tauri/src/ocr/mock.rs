@@ -4,7 +4,18 @@
 //! that return empty results or errors gracefully when the OCR feature
 //! is not enabled. This allows the code to compile without the OCR dependencies.
 
+use super::banner::BannerColor;
+use super::capture_backend::CaptureBackend;
+use super::confidence::ConfidenceWeights;
+use super::glyph::RecognitionEngine;
+use super::matcher::{
+    CardMatcher, CardPoolEntry, CardText, MatchCandidate, DEFAULT_AMBIGUITY_MARGIN,
+    DEFAULT_MAX_CANDIDATES,
+};
+use super::preprocess_steps::{default_steps, validate_steps, PreprocessStep};
+use super::region_role::RegionRole;
 use image::{GrayImage, ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 
 // ============================================================================
@@ -12,12 +23,14 @@ use std::path::Path;
 // ============================================================================
 
 /// Represents a screen region to capture
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CaptureRegion {
     pub x: i32,
     pub y: i32,
     pub width: u32,
     pub height: u32,
+    /// What the region shows, if assigned
+    pub role: Option<RegionRole>,
 }
 
 impl CaptureRegion {
@@ -28,6 +41,15 @@ impl CaptureRegion {
             y,
             width,
             height,
+            role: None,
+        }
+    }
+
+    /// Assign what the region shows
+    pub fn with_role(self, role: RegionRole) -> Self {
+        Self {
+            role: Some(role),
+            ..self
         }
     }
 
@@ -40,6 +62,56 @@ impl CaptureRegion {
     pub fn contains(&self, px: i32, py: i32) -> bool {
         px >= self.x && px < self.x + self.width as i32 && py >= self.y && py < self.y + self.height as i32
     }
+
+    /// Scale position and size by `factor`
+    pub fn scaled(&self, factor: f32) -> Self {
+        Self {
+            x: (self.x as f32 * factor).round() as i32,
+            y: (self.y as f32 * factor).round() as i32,
+            width: (self.width as f32 * factor).round() as u32,
+            height: (self.height as f32 * factor).round() as u32,
+            role: self.role,
+        }
+    }
+
+    /// Convert a region in physical pixels to the logical pixels used by
+    /// the OS at the given display scale factor
+    pub fn to_logical(&self, scale_factor: f32) -> Self {
+        if scale_factor <= 0.0 {
+            return *self;
+        }
+        self.scaled(1.0 / scale_factor)
+    }
+
+    /// Smallest region covering every valid region in `regions`
+    pub fn bounding(regions: &[CaptureRegion]) -> Option<Self> {
+        let mut valid = regions.iter().filter(|r| r.is_valid());
+        let first = valid.next()?;
+        let (mut left, mut top) = (first.x as i64, first.y as i64);
+        let mut right = left + first.width as i64;
+        let mut bottom = top + first.height as i64;
+        for region in valid {
+            left = left.min(region.x as i64);
+            top = top.min(region.y as i64);
+            right = right.max(region.x as i64 + region.width as i64);
+            bottom = bottom.max(region.y as i64 + region.height as i64);
+        }
+        Some(Self::new(
+            left as i32,
+            top as i32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        ))
+    }
+
+    /// Position of this region inside an image captured from `area`
+    pub fn relative_to(&self, area: &CaptureRegion) -> Self {
+        Self {
+            x: self.x - area.x,
+            y: self.y - area.y,
+            ..*self
+        }
+    }
 }
 
 impl std::fmt::Display for CaptureRegion {
@@ -48,8 +120,94 @@ impl std::fmt::Display for CaptureRegion {
     }
 }
 
+/// A capture region in fractions (0.0-1.0) of the screen size
+///
+/// Stays on the same part of the game UI when the resolution changes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NormalizedRegion {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub role: Option<RegionRole>,
+}
+
+impl NormalizedRegion {
+    /// Whether the region has a size and lies within the screen
+    pub fn is_valid(&self) -> bool {
+        let unit = 0.0..=1.0;
+        unit.contains(&self.x)
+            && unit.contains(&self.y)
+            && self.width > 0.0
+            && self.height > 0.0
+            && self.x + self.width <= 1.0 + f64::EPSILON
+            && self.y + self.height <= 1.0 + f64::EPSILON
+    }
+
+    /// Pixel region on a screen of the given physical size
+    pub fn to_pixels(&self, screen_width: u32, screen_height: u32) -> CaptureRegion {
+        let (w, h) = (screen_width as f64, screen_height as f64);
+        CaptureRegion {
+            role: self.role,
+            ..CaptureRegion::new(
+                (self.x * w).round() as i32,
+                (self.y * h).round() as i32,
+                ((self.width * w).round() as u32).max(1),
+                ((self.height * h).round() as u32).max(1),
+            )
+        }
+    }
+
+    /// Normalize a pixel region on a screen of the given physical size
+    pub fn from_pixels(region: &CaptureRegion, screen_width: u32, screen_height: u32) -> Self {
+        let (w, h) = (screen_width.max(1) as f64, screen_height.max(1) as f64);
+        Self {
+            x: region.x as f64 / w,
+            y: region.y as f64 / h,
+            width: region.width as f64 / w,
+            height: region.height as f64 / h,
+            role: region.role,
+        }
+    }
+}
+
+/// Geometry and display scaling of a monitor
+///
+/// Capture regions are in physical pixels, the resolution the game renders
+/// at. With 125%/150% display scaling the OS reports a smaller logical size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayMetrics {
+    /// Logical width, as reported by the OS
+    pub logical_width: u32,
+    /// Logical height, as reported by the OS
+    pub logical_height: u32,
+    /// Physical pixels per logical pixel (1.25 at 125% scaling)
+    pub scale_factor: f32,
+    pub is_primary: bool,
+}
+
+impl DisplayMetrics {
+    /// Size in physical pixels
+    pub fn physical_size(&self) -> (u32, u32) {
+        (
+            (self.logical_width as f32 * self.scale_factor).round() as u32,
+            (self.logical_height as f32 * self.scale_factor).round() as u32,
+        )
+    }
+
+    /// Whether a region (in physical pixels) lies entirely on this display
+    pub fn contains_region(&self, region: &CaptureRegion) -> bool {
+        let (width, height) = self.physical_size();
+        region.x >= 0
+            && region.y >= 0
+            && region.x as i64 + region.width as i64 <= width as i64
+            && region.y as i64 + region.height as i64 <= height as i64
+    }
+}
+
 /// Error types for screen capture operations
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum CaptureError {
     NoScreensAvailable,
     RegionOutOfBounds,
@@ -73,12 +231,36 @@ impl std::error::Error for CaptureError {}
 /// Result type for capture operations
 pub type CaptureResult<T> = Result<T, CaptureError>;
 
+/// A captured image and the screen area it covers
+pub type AreaCapture = (CaptureRegion, ImageBuffer<Rgba<u8>, Vec<u8>>);
+
 /// Mock: Captures a specific region - returns error since OCR is disabled
 pub fn capture_region(_region: &CaptureRegion) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
     log::warn!("OCR feature is disabled - screen capture not available");
     Err(CaptureError::CaptureFailed("OCR feature not enabled".to_string()))
 }
 
+/// Mock: Captures a specific region - returns error since OCR is disabled
+pub fn capture_region_with(
+    _backend: CaptureBackend,
+    region: &CaptureRegion,
+) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    capture_region(region)
+}
+
+/// Mock: Captures the whole screen - returns error since OCR is disabled
+pub fn capture_fullscreen() -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    log::warn!("OCR feature is disabled - screen capture not available");
+    Err(CaptureError::CaptureFailed("OCR feature not enabled".to_string()))
+}
+
+/// Mock: Captures the whole screen - returns error since OCR is disabled
+pub fn capture_fullscreen_with(
+    _backend: CaptureBackend,
+) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    capture_fullscreen()
+}
+
 /// Mock: Captures multiple regions
 pub fn capture_multiple_regions(regions: &[CaptureRegion]) -> Vec<CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>>> {
     log::warn!("OCR feature is disabled - screen capture not available");
@@ -99,11 +281,13 @@ pub fn get_default_card_regions(screen_width: u32, screen_height: u32) -> Vec<Ca
 
     base_regions
         .into_iter()
-        .map(|r| CaptureRegion {
+        .enumerate()
+        .map(|(i, r)| CaptureRegion {
             x: (r.x as f32 * scale_x) as i32,
             y: (r.y as f32 * scale_y) as i32,
             width: (r.width as f32 * scale_x) as u32,
             height: (r.height as f32 * scale_y) as u32,
+            role: RegionRole::card_name(i),
         })
         .collect()
 }
@@ -114,12 +298,31 @@ pub fn get_primary_screen_dimensions() -> CaptureResult<(u32, u32)> {
     Ok((1920, 1080))
 }
 
+/// Mock: Returns a single unscaled 1920x1080 display
+pub fn get_display_metrics() -> CaptureResult<Vec<DisplayMetrics>> {
+    get_primary_display_metrics().map(|metrics| vec![metrics])
+}
+
+/// Mock: Returns an unscaled 1920x1080 primary display
+pub fn get_primary_display_metrics() -> CaptureResult<DisplayMetrics> {
+    Ok(DisplayMetrics {
+        logical_width: 1920,
+        logical_height: 1080,
+        scale_factor: 1.0,
+        is_primary: true,
+    })
+}
+
 /// Configuration for OCR capture regions
 #[derive(Debug, Clone)]
 pub struct CaptureConfig {
     pub regions: Vec<CaptureRegion>,
+    /// When set, `regions` are resolved from these for the current screen
+    pub normalized_regions: Vec<NormalizedRegion>,
     pub screen_width: u32,
     pub screen_height: u32,
+    /// How captures are taken
+    pub backend: CaptureBackend,
 }
 
 impl CaptureConfig {
@@ -130,8 +333,10 @@ impl CaptureConfig {
         
         Ok(Self {
             regions,
+            normalized_regions: Vec::new(),
             screen_width,
             screen_height,
+            backend: CaptureBackend::default(),
         })
     }
 
@@ -141,14 +346,55 @@ impl CaptureConfig {
         
         Ok(Self {
             regions,
+            normalized_regions: Vec::new(),
             screen_width,
             screen_height,
+            backend: CaptureBackend::default(),
         })
     }
 
     /// Update regions after calibration
+    ///
+    /// Pixel regions replace any normalized regions.
     pub fn update_regions(&mut self, regions: Vec<CaptureRegion>) {
         self.regions = regions;
+        self.normalized_regions.clear();
+    }
+
+    /// Use regions given as fractions of the screen, resolved for the given size
+    pub fn set_normalized_regions(
+        &mut self,
+        regions: Vec<NormalizedRegion>,
+        screen_width: u32,
+        screen_height: u32,
+    ) {
+        self.normalized_regions = regions;
+        self.resolve(screen_width, screen_height);
+    }
+
+    /// Re-resolve normalized regions if the screen size changed
+    ///
+    /// Returns whether the pixel regions changed. Pixel regions set with
+    /// `update_regions` are left alone.
+    pub fn resolve_for_screen(&mut self, screen_width: u32, screen_height: u32) -> bool {
+        if self.normalized_regions.is_empty()
+            || (self.screen_width, self.screen_height) == (screen_width, screen_height)
+        {
+            return false;
+        }
+
+        self.resolve(screen_width, screen_height);
+        true
+    }
+
+    fn resolve(&mut self, screen_width: u32, screen_height: u32) {
+        self.screen_width = screen_width;
+        self.screen_height = screen_height;
+        self.regions = self
+            .normalized_regions
+            .iter()
+            .map(|r| r.to_pixels(screen_width, screen_height))
+            .collect();
     }
 
     /// Get the current capture regions
@@ -156,6 +402,21 @@ impl CaptureConfig {
         &self.regions
     }
 
+    /// Mock: Capture one region through the configured backend
+    pub fn capture(&self, region: &CaptureRegion) -> CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+        capture_region_with(self.backend, region)
+    }
+
+    /// The draft area: smallest region covering every configured region
+    pub fn draft_area(&self) -> Option<CaptureRegion> {
+        CaptureRegion::bounding(&self.regions)
+    }
+
+    /// Mock: Capture the draft area in one go
+    pub fn capture_draft_area(&self) -> CaptureResult<AreaCapture> {
+        Err(CaptureError::CaptureFailed("OCR feature not enabled".to_string()))
+    }
+
     /// Mock: Capture all configured regions
     pub fn capture_all(&self) -> Vec<CaptureResult<ImageBuffer<Rgba<u8>, Vec<u8>>>> {
         log::warn!("OCR feature is disabled - capture_all returning empty results");
@@ -168,8 +429,10 @@ impl Default for CaptureConfig {
         let regions = get_default_card_regions(1920, 1080);
         Self {
             regions,
+            normalized_regions: Vec::new(),
             screen_width: 1920,
             screen_height: 1080,
+            backend: CaptureBackend::default(),
         }
     }
 }
@@ -184,6 +447,7 @@ pub enum PreprocessError {
     InvalidImage(String),
     ProcessingFailed(String),
     EmptyImage,
+    InvalidPipeline(String),
 }
 
 impl std::fmt::Display for PreprocessError {
@@ -192,6 +456,7 @@ impl std::fmt::Display for PreprocessError {
             PreprocessError::InvalidImage(msg) => write!(f, "Invalid image: {}", msg),
             PreprocessError::ProcessingFailed(msg) => write!(f, "Processing failed: {}", msg),
             PreprocessError::EmptyImage => write!(f, "Image is empty"),
+            PreprocessError::InvalidPipeline(msg) => write!(f, "Invalid preprocessing pipeline: {}", msg),
         }
     }
 }
@@ -202,29 +467,15 @@ impl std::error::Error for PreprocessError {}
 pub type PreprocessResult<T> = Result<T, PreprocessError>;
 
 /// Configuration for image preprocessing
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PreprocessConfig {
-    pub threshold: u8,
-    pub use_adaptive_threshold: bool,
-    pub adaptive_block_size: u32,
-    pub adaptive_c: i32,
-    pub denoise: bool,
-    pub invert: bool,
-    pub scale_factor: f32,
-    pub contrast_factor: f32,
+    pub steps: Vec<PreprocessStep>,
 }
 
 impl Default for PreprocessConfig {
     fn default() -> Self {
         Self {
-            threshold: 127,
-            use_adaptive_threshold: true,
-            adaptive_block_size: 11,
-            adaptive_c: 2,
-            denoise: true,
-            invert: false,
-            scale_factor: 2.0,
-            contrast_factor: 1.5,
+            steps: default_steps(),
         }
     }
 }
@@ -232,11 +483,12 @@ impl Default for PreprocessConfig {
 /// Mock: Main preprocessing pipeline - returns empty image
 pub fn preprocess_for_ocr(
     img: &ImageBuffer<Rgba<u8>, Vec<u8>>,
-    _config: &PreprocessConfig,
+    config: &PreprocessConfig,
 ) -> PreprocessResult<GrayImage> {
     if img.width() == 0 || img.height() == 0 {
         return Err(PreprocessError::EmptyImage);
     }
+    validate_steps(&config.steps)?;
     // Return a simple grayscale conversion
     Ok(image::imageops::grayscale(img))
 }
@@ -265,6 +517,7 @@ pub enum RecognizeError {
     NoCardNamesAvailable,
     InvalidImage,
     MatchingFailed(String),
+    GlyphAtlasUnavailable(String),
 }
 
 impl std::fmt::Display for RecognizeError {
@@ -277,6 +530,9 @@ impl std::fmt::Display for RecognizeError {
             RecognizeError::NoCardNamesAvailable => write!(f, "No card names available for matching"),
             RecognizeError::InvalidImage => write!(f, "Invalid image for OCR"),
             RecognizeError::MatchingFailed(msg) => write!(f, "Card matching failed: {}", msg),
+            RecognizeError::GlyphAtlasUnavailable(msg) => {
+                write!(f, "Glyph atlas unavailable: {}", msg)
+            }
         }
     }
 }
@@ -296,6 +552,12 @@ pub struct RecognizeConfig {
     pub min_confidence: i32,
     pub min_match_score: i32,
     pub whitelist: Option<String>,
+    pub ambiguity_margin: i32,
+    pub max_candidates: usize,
+    pub confidence_weights: ConfidenceWeights,
+    pub engine: RecognitionEngine,
+    pub glyph_atlas_path: Option<String>,
+    pub user_words_path: Option<String>,
 }
 
 impl Default for RecognizeConfig {
@@ -308,6 +570,12 @@ impl Default for RecognizeConfig {
             min_confidence: 60,
             min_match_score: 60,
             whitelist: Some("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789 '-".to_string()),
+            ambiguity_margin: DEFAULT_AMBIGUITY_MARGIN,
+            max_candidates: DEFAULT_MAX_CANDIDATES,
+            confidence_weights: ConfidenceWeights::default(),
+            engine: RecognitionEngine::default(),
+            glyph_atlas_path: None,
+            user_words_path: None,
         }
     }
 }
@@ -328,6 +596,17 @@ impl RecognizeConfig {
             ..self
         }
     }
+
+    /// Config for a card's rules text: a block of lines in any characters,
+    /// read without the card name vocabulary
+    pub fn for_rules_text(&self) -> Self {
+        Self {
+            psm: 6,
+            whitelist: None,
+            user_words_path: None,
+            ..self.clone()
+        }
+    }
 }
 
 /// Result of OCR text recognition
@@ -364,12 +643,19 @@ pub struct CardMatch {
     pub match_score: i32,
     pub ocr_confidence: i32,
     pub overall_confidence: f64,
+    pub candidates: Vec<MatchCandidate>,
+    pub banner: Option<BannerColor>,
 }
 
 impl CardMatch {
     /// Calculate overall confidence from OCR confidence and match score
     pub fn calculate_overall_confidence(ocr_confidence: i32, match_score: i32) -> f64 {
-        (ocr_confidence as f64 * 0.4 + match_score as f64 * 0.6) / 100.0
+        ConfidenceWeights::default().combine(ocr_confidence, match_score)
+    }
+
+    /// Whether other cards scored close enough that the user should confirm
+    pub fn is_ambiguous(&self) -> bool {
+        !self.candidates.is_empty()
     }
 }
 
@@ -393,6 +679,13 @@ impl OcrEngine {
         Ok(Self { config })
     }
 
+    /// Mock: Tesseract is never available without the OCR feature
+    pub fn check_available(&self) -> RecognizeResult<()> {
+        Err(RecognizeError::TesseractInitFailed(
+            "OCR feature is disabled".to_string(),
+        ))
+    }
+
     /// Mock: Recognize text from a grayscale image
     pub fn recognize(&self, _img: &GrayImage) -> RecognizeResult<OcrResult> {
         log::warn!("OCR feature is disabled - returning empty recognition result");
@@ -416,144 +709,9 @@ impl Default for OcrEngine {
     }
 }
 
-/// Card name matcher using fuzzy string matching
-pub struct CardMatcher {
-    card_names: Vec<(String, String)>,
-    matcher: fuzzy_matcher::skim::SkimMatcherV2,
-    min_score: i32,
-}
-
-impl CardMatcher {
-    /// Create a new card matcher with the given card names
-    pub fn new(card_names: Vec<(String, String)>, min_score: i32) -> RecognizeResult<Self> {
-        if card_names.is_empty() {
-            return Err(RecognizeError::NoCardNamesAvailable);
-        }
-
-        Ok(Self {
-            card_names,
-            matcher: fuzzy_matcher::skim::SkimMatcherV2::default(),
-            min_score,
-        })
-    }
-
-    /// Find the best matching card for the given OCR text
-    pub fn find_best_match(&self, ocr_text: &str) -> Option<CardMatch> {
-        use fuzzy_matcher::FuzzyMatcher;
-        
-        let ocr_normalized = ocr_text.to_lowercase().trim().to_string();
-        
-        if ocr_normalized.is_empty() {
-            return None;
-        }
-
-        let mut best_match: Option<CardMatch> = None;
-        let mut best_score = self.min_score as i64;
-
-        for (card_id, card_name) in &self.card_names {
-            if let Some(score) = self.matcher.fuzzy_match(&card_name.to_lowercase(), &ocr_normalized) {
-                if score > best_score {
-                    best_score = score;
-                    best_match = Some(CardMatch {
-                        card_name: card_name.clone(),
-                        card_id: card_id.clone(),
-                        ocr_text: ocr_text.to_string(),
-                        match_score: score.min(100) as i32,
-                        ocr_confidence: 0,
-                        overall_confidence: 0.0,
-                    });
-                }
-            }
-
-            // Also try matching individual words for short OCR text
-            if ocr_normalized.len() < 10 {
-                let card_name_lower = card_name.to_lowercase();
-                let card_words: Vec<&str> = card_name_lower.split_whitespace().collect();
-                for word in &card_words {
-                    if let Some(word_score) = self.matcher.fuzzy_match(word, &ocr_normalized) {
-                        if word_score > best_score {
-                            best_score = word_score;
-                            best_match = Some(CardMatch {
-                                card_name: card_name.clone(),
-                                card_id: card_id.clone(),
-                                ocr_text: ocr_text.to_string(),
-                                match_score: word_score.min(100) as i32,
-                                ocr_confidence: 0,
-                                overall_confidence: 0.0,
-                            });
-                        }
-                    }
-                }
-            }
-        }
-
-        best_match
-    }
-
-    /// Match multiple OCR results and update their confidence scores
-    pub fn match_results(&self, ocr_results: Vec<OcrResult>) -> Vec<CardMatch> {
-        let mut matches = Vec::new();
-
-        for result in ocr_results {
-            if let Some(mut card_match) = self.find_best_match(&result.text) {
-                card_match.ocr_confidence = result.confidence;
-                card_match.overall_confidence = CardMatch::calculate_overall_confidence(
-                    result.confidence,
-                    card_match.match_score,
-                );
-                matches.push(card_match);
-            }
-        }
-
-        // Sort by overall confidence (highest first)
-        matches.sort_by(|a, b| {
-            b.overall_confidence
-                .partial_cmp(&a.overall_confidence)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        matches
-    }
-
-    /// Find all cards that match above the threshold
-    pub fn find_all_matches(&self, ocr_text: &str, threshold: i32) -> Vec<CardMatch> {
-        use fuzzy_matcher::FuzzyMatcher;
-        
-        let ocr_normalized = ocr_text.to_lowercase().trim().to_string();
-        let mut matches = Vec::new();
-
-        if ocr_normalized.is_empty() {
-            return matches;
-        }
-
-        for (card_id, card_name) in &self.card_names {
-            if let Some(score) = self.matcher.fuzzy_match(&card_name.to_lowercase(), &ocr_normalized) {
-                if score >= threshold as i64 {
-                    matches.push(CardMatch {
-                        card_name: card_name.clone(),
-                        card_id: card_id.clone(),
-                        ocr_text: ocr_text.to_string(),
-                        match_score: score.min(100) as i32,
-                        ocr_confidence: 0,
-                        overall_confidence: score as f64 / 100.0,
-                    });
-                }
-            }
-        }
-
-        // Sort by match score (highest first)
-        matches.sort_by(|a, b| {
-            b.match_score
-                .cmp(&a.match_score)
-        });
-
-        matches
-    }
-}
-
 /// Complete recognition pipeline combining OCR and card matching
 pub struct RecognitionPipeline {
-    ocr_engine: OcrEngine,
+    ocr_engine: std::sync::Arc<OcrEngine>,
     card_matcher: CardMatcher,
 }
 
@@ -562,10 +720,12 @@ impl RecognitionPipeline {
     pub fn new(card_names: Vec<(String, String)>) -> RecognizeResult<Self> {
         let ocr_engine = OcrEngine::new()?;
         let config = RecognizeConfig::default();
-        let card_matcher = CardMatcher::new(card_names, config.min_match_score)?;
+        let card_matcher = CardMatcher::new(card_names, config.min_match_score)?
+            .with_ambiguity(config.ambiguity_margin, config.max_candidates)
+            .with_confidence_weights(config.confidence_weights);
 
         Ok(Self {
-            ocr_engine,
+            ocr_engine: std::sync::Arc::new(ocr_engine),
             card_matcher,
         })
     }
@@ -576,20 +736,70 @@ impl RecognitionPipeline {
         config: RecognizeConfig,
     ) -> RecognizeResult<Self> {
         let ocr_engine = OcrEngine::with_config(config.clone())?;
-        let card_matcher = CardMatcher::new(card_names, config.min_match_score)?;
+        let card_matcher = CardMatcher::new(card_names, config.min_match_score)?
+            .with_ambiguity(config.ambiguity_margin, config.max_candidates)
+            .with_confidence_weights(config.confidence_weights);
 
         Ok(Self {
-            ocr_engine,
+            ocr_engine: std::sync::Arc::new(ocr_engine),
             card_matcher,
         })
     }
 
+    /// Apply alternate card names to the card matcher
+    pub fn with_alternate_names(mut self, alternate_names: Vec<(String, String)>) -> Self {
+        self.card_matcher = self.card_matcher.with_alternate_names(alternate_names);
+        self
+    }
+
+    /// Apply learned aliases to the card matcher
+    pub fn with_aliases(mut self, aliases: std::collections::HashMap<String, String>) -> Self {
+        self.card_matcher = self.card_matcher.with_aliases(aliases);
+        self
+    }
+
+    /// Apply card clans and rarities for banner color tie-breaking
+    pub fn with_card_attributes(mut self, pool: &[CardPoolEntry]) -> Self {
+        self.card_matcher = self.card_matcher.with_card_attributes(pool);
+        self
+    }
+
+    /// Apply card keywords and description openings to the card matcher
+    pub fn with_card_text(mut self, card_text: Vec<CardText>) -> Self {
+        self.card_matcher = self.card_matcher.with_card_text(card_text);
+        self
+    }
+
+    /// The OCR engine, for running recognition on another thread
+    pub fn ocr_engine(&self) -> std::sync::Arc<OcrEngine> {
+        std::sync::Arc::clone(&self.ocr_engine)
+    }
+
+    /// Mock: Never matches, as no text is recognized
+    pub fn match_ocr_result(
+        &self,
+        _ocr_result: &OcrResult,
+        _banner: Option<&BannerColor>,
+    ) -> Option<CardMatch> {
+        None
+    }
+
     /// Mock: Process a single image through the full pipeline
     pub fn process(&self, _img: &GrayImage) -> RecognizeResult<Option<CardMatch>> {
         log::warn!("OCR feature is disabled - process() returning None");
         Ok(None)
     }
 
+    /// Mock: Process a single image, also returning the raw OCR result
+    pub fn process_detailed(
+        &self,
+        _img: &GrayImage,
+        _banner: Option<&BannerColor>,
+    ) -> RecognizeResult<(OcrResult, Option<CardMatch>)> {
+        log::warn!("OCR feature is disabled - process_detailed() returning empty text");
+        Ok((OcrResult::new(String::new(), 0, 100), None))
+    }
+
     /// Mock: Process multiple images through the full pipeline
     pub fn process_multiple(&self, _images: &[GrayImage]) -> Vec<CardMatch> {
         log::warn!("OCR feature is disabled - process_multiple() returning empty");
@@ -597,6 +807,11 @@ impl RecognitionPipeline {
     }
 }
 
+/// Mock: No Tesseract library is linked
+pub fn tesseract_version() -> Option<String> {
+    None
+}
+
 /// Helper function to normalize card name for better matching
 pub fn normalize_card_name(name: &str) -> String {
     name.chars()
@@ -5,7 +5,11 @@
 //!
 //! 1. **Capture** (`capture`): Screen capture of specific regions
 //! 2. **Preprocess** (`preprocess`): Image preprocessing for better OCR accuracy
-//! 3. **Recognize** (`recognize`): Tesseract OCR and card name matching
+//! 3. **Recognize** (`recognize`): Tesseract OCR, with card name matching
+//!    provided by `matcher`
+//!
+//! Long-term dataset storage is handled by `archive`, which compacts old
+//! detection rows and debug crops into cold storage.
 //!
 //! # Example Usage
 //!
@@ -17,6 +21,24 @@
 //! let detected_cards = pipeline.detect_cards()?;
 //! ```
 
+pub mod archive;
+mod banner;
+mod capture_backend;
+pub mod confidence;
+pub mod controller;
+mod frame_diff;
+pub mod glyph;
+pub mod matcher;
+pub mod offer;
+pub mod pips;
+mod preprocess_steps;
+mod region_role;
+pub mod recorder;
+pub mod rules_text;
+pub mod status;
+pub mod tessdata;
+pub mod timeout;
+
 // Conditional compilation for OCR feature - real modules
 #[cfg(feature = "ocr")]
 pub mod capture;
@@ -32,15 +54,24 @@ mod mock;
 // Re-export mock module items as if they were submodules
 #[cfg(not(feature = "ocr"))]
 pub mod capture {
+    pub use super::capture_backend::{
+        capture_backend_status, BackendAvailability, CaptureBackend, CaptureBackendStatus,
+        DisplaySession,
+    };
+    pub use super::frame_diff::{frame_hash, FrameGate};
+    pub use super::region_role::RegionRole;
     pub use super::mock::{
-        CaptureConfig, CaptureError, CaptureRegion, CaptureResult,
-        capture_multiple_regions, capture_region, get_default_card_regions,
-        get_primary_screen_dimensions,
+        CaptureConfig, CaptureError, CaptureRegion, CaptureResult, DisplayMetrics,
+        NormalizedRegion, capture_fullscreen, capture_fullscreen_with, capture_multiple_regions,
+        capture_region, capture_region_with, get_default_card_regions, get_display_metrics,
+        get_primary_display_metrics, get_primary_screen_dimensions,
     };
 }
 
 #[cfg(not(feature = "ocr"))]
 pub mod preprocess {
+    pub use super::banner::{analyze_banner, BannerColor};
+    pub use super::preprocess_steps::{default_steps, validate_steps, PreprocessStep};
     pub use super::mock::{
         PreprocessConfig, PreprocessError, PreprocessResult,
         preprocess_default, preprocess_for_ocr, save_debug_image,
@@ -49,30 +80,47 @@ pub mod preprocess {
 
 #[cfg(not(feature = "ocr"))]
 pub mod recognize {
+    pub use super::glyph::RecognitionEngine;
+    pub use super::matcher::{CardMatcher, MatchCandidate};
     pub use super::mock::{
         CardMatch, OcrEngine, OcrResult, RecognizeConfig, RecognizeError,
         RecognizeResult, RecognitionPipeline, normalize_card_name, build_card_map,
+        tesseract_version,
     };
 }
 
 // Re-export commonly used types at the module level for convenience
 pub use capture::{
-    CaptureConfig, CaptureError, CaptureRegion, CaptureResult,
-    capture_multiple_regions, capture_region, get_default_card_regions,
+    CaptureBackend, CaptureConfig, CaptureError, CaptureRegion, CaptureResult, DisplayMetrics,
+    FrameGate, NormalizedRegion, RegionRole, capture_fullscreen, capture_fullscreen_with,
+    capture_multiple_regions, capture_region, capture_region_with, frame_hash,
+    get_default_card_regions, get_display_metrics, get_primary_display_metrics,
     get_primary_screen_dimensions,
 };
 
 pub use preprocess::{
-    PreprocessConfig, PreprocessError, PreprocessResult,
-    preprocess_default, preprocess_for_ocr, save_debug_image,
+    BannerColor, PreprocessConfig, PreprocessError, PreprocessResult, PreprocessStep,
+    analyze_banner, preprocess_default, preprocess_for_ocr, save_debug_image,
 };
 
 pub use recognize::{
-    CardMatch, OcrEngine, OcrResult, RecognizeConfig, RecognizeError,
+    CardMatch, MatchCandidate, OcrEngine, OcrResult, RecognitionEngine, RecognizeConfig,
+    RecognizeError,
     RecognizeResult, RecognitionPipeline, normalize_card_name, build_card_map,
 };
 
+pub use confidence::{ConfidenceCalibration, ConfidenceWeights};
+pub use matcher::{CandidateFilter, CardPoolEntry, CardText};
+pub use offer::{image_hash, OfferTracker};
+pub use pips::PipSprites;
+pub use recorder::{RegionDecision, RegionOutcome, SessionRecorder};
+pub use rules_text::{check_rules_text, RulesTextCheck};
+pub use timeout::{CancelToken, DetectionStage, StageError, StageTimeouts};
+
+use image::{GrayImage, ImageBuffer, Rgba};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Error type for OCR pipeline operations
 #[derive(Debug)]
@@ -81,6 +129,8 @@ pub enum OcrPipelineError {
     Preprocess(preprocess::PreprocessError),
     Recognize(recognize::RecognizeError),
     Configuration(String),
+    /// A stage timed out or the detection was cancelled
+    Stage(StageError),
 }
 
 impl std::fmt::Display for OcrPipelineError {
@@ -90,6 +140,7 @@ impl std::fmt::Display for OcrPipelineError {
             OcrPipelineError::Preprocess(e) => write!(f, "Preprocess error: {}", e),
             OcrPipelineError::Recognize(e) => write!(f, "Recognize error: {}", e),
             OcrPipelineError::Configuration(msg) => write!(f, "Configuration error: {}", msg),
+            OcrPipelineError::Stage(e) => write!(f, "{}", e),
         }
     }
 }
@@ -101,6 +152,7 @@ impl std::error::Error for OcrPipelineError {
             OcrPipelineError::Preprocess(e) => Some(e),
             OcrPipelineError::Recognize(e) => Some(e),
             OcrPipelineError::Configuration(_) => None,
+            OcrPipelineError::Stage(e) => Some(e),
         }
     }
 }
@@ -123,9 +175,22 @@ impl From<recognize::RecognizeError> for OcrPipelineError {
     }
 }
 
+impl From<StageError> for OcrPipelineError {
+    fn from(err: StageError) -> Self {
+        OcrPipelineError::Stage(err)
+    }
+}
+
 /// Result type for OCR pipeline operations
 pub type OcrPipelineResult<T> = Result<T, OcrPipelineError>;
 
+/// Continuous detection frames a card is voted on over by default
+pub const DEFAULT_STABLE_WINDOW: usize = 3;
+/// Frames of the window a card must be detected in by default
+pub const DEFAULT_STABLE_VOTES: usize = 2;
+/// Longest voting window the settings accept
+pub const MAX_STABLE_WINDOW: usize = 10;
+
 /// Options for card detection
 #[derive(Debug, Clone)]
 pub struct CardDetectionOptions {
@@ -139,8 +204,40 @@ pub struct CardDetectionOptions {
     pub save_debug_images: bool,
     /// Path for debug images (if enabled)
     pub debug_image_path: Option<PathBuf>,
+    /// Session folder to record every pass into (see `recorder`)
+    pub recording_dir: Option<PathBuf>,
     /// Minimum overall confidence for a valid detection (0.0-1.0)
     pub min_overall_confidence: f64,
+    /// Restricts which cards are matched against
+    pub candidate_filter: CandidateFilter,
+    /// Game language (e.g. `de`) whose translated card names are also
+    /// matched; `None` matches English names only
+    pub card_locale: Option<String>,
+    /// Per-region overrides, keyed by index into the capture regions
+    pub region_profiles: HashMap<usize, RegionProfile>,
+    /// Continuous detection frames each card is voted on over
+    pub stable_window: usize,
+    /// Frames of `stable_window` a card must be detected in before the
+    /// continuous loop reports it
+    pub stable_votes: usize,
+    /// Limits after which a stuck capture or OCR call is abandoned
+    pub stage_timeouts: StageTimeouts,
+    /// Folder of upgrade pip sprites (see `pips`); upgrade regions are
+    /// skipped without it
+    pub pip_sprites_dir: Option<PathBuf>,
+    /// Also match OCR text against card keywords and description openings
+    pub match_card_text: bool,
+}
+
+/// Preprocessing and recognition used for one capture region in place of
+/// the global configs
+///
+/// Card names, HUD digits and the champion banner read best with different
+/// settings, e.g. a digit whitelist and PSM 8 for a single number.
+#[derive(Debug, Clone, Default)]
+pub struct RegionProfile {
+    pub preprocess: Option<PreprocessConfig>,
+    pub recognize: Option<RecognizeConfig>,
 }
 
 impl Default for CardDetectionOptions {
@@ -151,7 +248,16 @@ impl Default for CardDetectionOptions {
             recognize: RecognizeConfig::default(),
             save_debug_images: false,
             debug_image_path: None,
+            recording_dir: None,
             min_overall_confidence: 0.6,
+            candidate_filter: CandidateFilter::default(),
+            card_locale: None,
+            region_profiles: HashMap::new(),
+            stable_window: DEFAULT_STABLE_WINDOW,
+            stable_votes: DEFAULT_STABLE_VOTES,
+            stage_timeouts: StageTimeouts::default(),
+            pip_sprites_dir: None,
+            match_card_text: false,
         }
     }
 }
@@ -172,6 +278,24 @@ impl CardDetectionOptions {
         self.debug_image_path = Some(path);
         self
     }
+
+    /// Preprocessing for region `index`
+    pub fn preprocess_for(&self, index: usize) -> &PreprocessConfig {
+        self.region_profiles
+            .get(&index)
+            .and_then(|profile| profile.preprocess.as_ref())
+            .unwrap_or(&self.preprocess)
+    }
+
+    /// The global recognition config and every region's override, for
+    /// settings that apply to all of them such as the tessdata path
+    pub fn recognize_configs_mut(&mut self) -> impl Iterator<Item = &mut RecognizeConfig> {
+        std::iter::once(&mut self.recognize).chain(
+            self.region_profiles
+                .values_mut()
+                .filter_map(|profile| profile.recognize.as_mut()),
+        )
+    }
 }
 
 /// Individual card detection result
@@ -191,6 +315,14 @@ pub struct DetectedCard {
     pub overall_confidence: f64,
     /// Raw OCR text
     pub raw_ocr_text: String,
+    /// Close-scoring candidates when the match is ambiguous (empty otherwise)
+    pub candidates: Vec<MatchCandidate>,
+    /// Frame and banner colors sampled from the region
+    pub banner: Option<BannerColor>,
+    /// Text read from the card's rules text region, if one is configured
+    pub rules_text: Option<String>,
+    /// Upgrade pips counted on the card, if a pip region is configured
+    pub upgrade_count: Option<u32>,
 }
 
 impl DetectedCard {
@@ -211,6 +343,10 @@ pub struct CardDetectionResult {
     pub success: bool,
     /// Error message if detection failed
     pub error_message: Option<String>,
+    /// Regions whose content was unchanged and reused a previous result
+    pub unchanged_regions: usize,
+    /// Image hash of each region's capture, `None` where it failed; see `offer`
+    pub region_hashes: Vec<Option<u64>>,
 }
 
 impl CardDetectionResult {
@@ -227,6 +363,8 @@ impl CardDetectionResult {
             average_confidence: avg_confidence,
             success: true,
             error_message: None,
+            unchanged_regions: 0,
+            region_hashes: Vec::new(),
         }
     }
 
@@ -237,6 +375,8 @@ impl CardDetectionResult {
             average_confidence: 0.0,
             success: false,
             error_message: Some(error.to_string()),
+            unchanged_regions: 0,
+            region_hashes: Vec::new(),
         }
     }
 
@@ -259,11 +399,56 @@ impl CardDetectionResult {
     }
 }
 
+/// The card a recognized region settled on, if it was a confident match
+fn detected_card(outcome: &RegionOutcome, region: CaptureRegion) -> Option<DetectedCard> {
+    (outcome.decision == RegionDecision::Matched)
+        .then(|| outcome.card_match.clone())
+        .flatten()
+        .map(|card_match| DetectedCard {
+            card_id: card_match.card_id,
+            card_name: card_match.card_name,
+            region,
+            ocr_confidence: card_match.ocr_confidence,
+            match_score: card_match.match_score,
+            overall_confidence: card_match.overall_confidence,
+            raw_ocr_text: card_match.ocr_text,
+            candidates: card_match.candidates,
+            banner: card_match.banner,
+            rules_text: None,
+            upgrade_count: None,
+        })
+}
+
+/// A rules text or upgrade pip region, held until the card names are read
+struct DetailCapture {
+    /// Index of the region in the capture config
+    index: usize,
+    region: CaptureRegion,
+    image: ImageBuffer<Rgba<u8>, Vec<u8>>,
+}
+
+/// Record the regions of a pass abandoned part way through
+fn write_partial_manifest(recorder: Option<&SessionRecorder>) {
+    if let Some(recorder) = recorder {
+        if let Err(e) = recorder.write_manifest() {
+            log::warn!("{}", e);
+        }
+    }
+}
+
 /// High-level OCR pipeline for card detection
 pub struct OcrPipeline {
     recognition_pipeline: RecognitionPipeline,
+    /// Pipelines for regions whose profile overrides recognition
+    region_pipelines: HashMap<usize, RecognitionPipeline>,
+    /// Reads rules text regions that have no profile of their own
+    rules_text_engine: Option<Arc<OcrEngine>>,
+    /// Pip sprites scaled for the screen, when upgrade regions are configured
+    pip_sprites: Option<PipSprites>,
     options: CardDetectionOptions,
     card_names: Vec<(String, String)>,
+    /// Aborts this pipeline's detection when cancelled
+    cancel: CancelToken,
 }
 
 impl OcrPipeline {
@@ -281,80 +466,436 @@ impl OcrPipeline {
             options.recognize.clone(),
         )?;
 
+        let mut region_pipelines = HashMap::new();
+        for (&index, profile) in &options.region_profiles {
+            if let Some(ref recognize) = profile.recognize {
+                let pipeline =
+                    RecognitionPipeline::with_config(card_names.clone(), recognize.clone())?;
+                region_pipelines.insert(index, pipeline);
+            }
+        }
+
+        let regions = options.capture.get_regions();
+        let has_text_regions = regions
+            .iter()
+            .any(|region| region.role.and_then(|role| role.text_slot()).is_some());
+        let rules_text_engine = if has_text_regions {
+            let config = options.recognize.for_rules_text();
+            Some(Arc::new(OcrEngine::with_config(config)?))
+        } else {
+            None
+        };
+
+        let has_upgrade_regions = regions
+            .iter()
+            .any(|region| region.role.and_then(|role| role.upgrade_slot()).is_some());
+        let pip_sprites = match &options.pip_sprites_dir {
+            Some(dir) if has_upgrade_regions => match PipSprites::load_dir(dir) {
+                Ok(sprites) => Some(sprites.scaled_for(options.capture.screen_height)),
+                Err(e) => {
+                    log::warn!("Upgrade pips will not be counted: {}", e);
+                    None
+                }
+            },
+            _ => None,
+        };
+
         Ok(Self {
             recognition_pipeline,
+            region_pipelines,
+            rules_text_engine,
+            pip_sprites,
             options,
             card_names,
+            cancel: CancelToken::default(),
         })
     }
 
+    /// Abort detection when `cancel` is cancelled
+    pub fn with_cancel_token(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Apply alternate card names as (card_id, alternate name) pairs
+    pub fn with_alternate_names(self, alternate_names: Vec<(String, String)>) -> Self {
+        self.map_pipelines(|pipeline| pipeline.with_alternate_names(alternate_names.clone()))
+    }
+
+    /// Apply learned aliases (normalized OCR text -> card_id) from user corrections
+    pub fn with_aliases(self, aliases: HashMap<String, String>) -> Self {
+        self.map_pipelines(|pipeline| pipeline.with_aliases(aliases.clone()))
+    }
+
+    /// Apply card clans and rarities so banner colors can break ties
+    pub fn with_card_attributes(self, pool: &[CardPoolEntry]) -> Self {
+        self.map_pipelines(|pipeline| pipeline.with_card_attributes(pool))
+    }
+
+    /// Also match card keywords and description openings, for names that
+    /// are obscured while the card's text is visible
+    pub fn with_card_text(self, card_text: Vec<CardText>) -> Self {
+        self.map_pipelines(|pipeline| pipeline.with_card_text(card_text.clone()))
+    }
+
+    /// Apply `f` to the global and every per-region recognition pipeline
+    fn map_pipelines(mut self, f: impl Fn(RecognitionPipeline) -> RecognitionPipeline) -> Self {
+        self.recognition_pipeline = f(self.recognition_pipeline);
+        self.region_pipelines = self
+            .region_pipelines
+            .into_iter()
+            .map(|(index, pipeline)| (index, f(pipeline)))
+            .collect();
+        self
+    }
+
     /// Create with default options
     pub fn with_default_options(card_names: Vec<(String, String)>) -> OcrPipelineResult<Self> {
         Self::new(card_names, CardDetectionOptions::default())
     }
 
     /// Detect cards on screen using the configured regions
+    ///
+    /// Capturing and recognizing each region are limited by the options'
+    /// `stage_timeouts`; a stage that runs over fails the whole pass.
     pub fn detect_cards(&self) -> OcrPipelineResult<CardDetectionResult> {
-        // Step 1: Capture screen regions
-        let capture_results = self.options.capture.capture_all();
+        self.detect_cards_gated(&mut FrameGate::new(), None)
+    }
+
+    /// Detect cards, reusing the stored result for any region whose content
+    /// is unchanged since the frame recorded in `gate`
+    ///
+    /// Only regions that changed are preprocessed and recognized, which keeps
+    /// the continuous detection loop cheap while the screen is static. When a
+    /// `recorder` is given, every region of this pass is added to its session.
+    pub fn detect_cards_gated(
+        &self,
+        gate: &mut FrameGate<Option<DetectedCard>>,
+        mut recorder: Option<&mut SessionRecorder>,
+    ) -> OcrPipelineResult<CardDetectionResult> {
+        // Step 1: Capture the draft area once and crop every region from it
+        let capture = self.options.capture.clone();
+        let capture_results = tracing::info_span!("ocr.capture").in_scope(|| {
+            timeout::run_stage(
+                DetectionStage::Capture,
+                self.options.stage_timeouts.capture,
+                &self.cancel,
+                move || capture.capture_all(),
+            )
+        })?;
 
         let mut detected_cards = Vec::new();
+        let mut unchanged_regions = 0;
+        let mut region_hashes = Vec::with_capacity(capture_results.len());
         let mut debug_image_index = 0;
+        // Cards read this pass, with their region and hash for the gate
+        let mut fresh = Vec::new();
+        let mut detail_captures = Vec::new();
+
+        if let Some(recorder) = recorder.as_deref_mut() {
+            recorder.begin_pass();
+        }
 
         for (i, capture_result) in capture_results.into_iter().enumerate() {
-            match capture_result {
-                Ok(rgba_image) => {
-                    // Step 2: Preprocess
-                    let gray_image = match preprocess_for_ocr(&rgba_image, &self.options.preprocess) {
-                        Ok(img) => img,
-                        Err(e) => {
-                            log::warn!("Preprocessing failed for region {}: {}", i, e);
-                            continue;
-                        }
-                    };
-
-                    // Save debug image if enabled
-                    if self.options.save_debug_images {
-                        if let Some(ref path) = self.options.debug_image_path {
-                            let debug_path = path.join(format!("debug_region_{}.png", debug_image_index));
-                            let _ = save_debug_image(&gray_image, &debug_path);
-                            debug_image_index += 1;
-                        }
-                    }
+            let region = self.options.capture.get_regions().get(i).copied()
+                .unwrap_or_else(|| CaptureRegion::new(0, 0, 0, 0));
 
-                    // Step 3: Recognize
-                    match self.recognition_pipeline.process(&gray_image) {
-                        Ok(Some(card_match)) => {
-                            if card_match.overall_confidence >= self.options.min_overall_confidence {
-                                let region = self.options.capture.get_regions().get(i).copied()
-                                    .unwrap_or_else(|| CaptureRegion::new(0, 0, 0, 0));
-
-                                detected_cards.push(DetectedCard {
-                                    card_id: card_match.card_id,
-                                    card_name: card_match.card_name,
-                                    region,
-                                    ocr_confidence: card_match.ocr_confidence,
-                                    match_score: card_match.match_score,
-                                    overall_confidence: card_match.overall_confidence,
-                                    raw_ocr_text: card_match.ocr_text,
-                                });
-                            }
-                        }
-                        Ok(None) => {
-                            log::debug!("No card detected in region {}", i);
-                        }
-                        Err(e) => {
-                            log::warn!("Recognition failed for region {}: {}", i, e);
-                        }
-                    }
-                }
+            let rgba_image = match capture_result {
+                Ok(img) => img,
                 Err(e) => {
                     log::warn!("Capture failed for region {}: {}", i, e);
+                    region_hashes.push(None);
+                    if let Some(recorder) = recorder.as_deref_mut() {
+                        let outcome = RegionOutcome::failed(RegionDecision::CaptureFailed, e);
+                        recorder.record_region(i, &region, None, &outcome);
+                    }
+                    continue;
+                }
+            };
+
+            region_hashes.push(Some(image_hash(&rgba_image)));
+            let hash = frame_hash(&rgba_image);
+
+            // Rules text and upgrade pips are read once the card names are
+            // known; the gate only tracks whether they changed
+            if region.role.and_then(|role| role.detail_slot()).is_some() {
+                if gate.unchanged(&region, hash).is_some() {
+                    unchanged_regions += 1;
+                } else {
+                    gate.store(region, hash, None);
+                }
+                detail_captures.push(DetailCapture {
+                    index: i,
+                    region,
+                    image: rgba_image,
+                });
+                continue;
+            }
+
+            // Step 2: Skip regions that look the same as last cycle
+            let outcome = if let Some(previous) = gate.unchanged(&region, hash) {
+                detected_cards.extend(previous.clone());
+                unchanged_regions += 1;
+                RegionOutcome::new(RegionDecision::Unchanged)
+            } else {
+                let outcome = match self.recognize_region(i, &rgba_image, &mut debug_image_index) {
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        write_partial_manifest(recorder.as_deref());
+                        return Err(e);
+                    }
+                };
+                let detected = detected_card(&outcome, region);
+                gate.store(region, hash, detected.clone());
+                if detected.is_some() {
+                    fresh.push((detected_cards.len(), region, hash));
                 }
+                detected_cards.extend(detected);
+                outcome
+            };
+
+            if let Some(recorder) = recorder.as_deref_mut() {
+                recorder.record_region(i, &region, Some(&rgba_image), &outcome);
+            }
+        }
+
+        // Step 5: Read the rules text and upgrade pips of the cards read
+        // this pass; reused cards keep the details stored with them
+        let fresh_indices: Vec<usize> = fresh.iter().map(|(index, _, _)| *index).collect();
+        let attached =
+            self.attach_card_details(&mut detected_cards, &fresh_indices, detail_captures);
+        let attached = match attached {
+            Ok(attached) => attached,
+            Err(e) => {
+                write_partial_manifest(recorder.as_deref());
+                return Err(e);
+            }
+        };
+        for (index, region, hash) in fresh {
+            if attached.contains(&index) {
+                gate.store(region, hash, Some(detected_cards[index].clone()));
             }
         }
 
-        Ok(CardDetectionResult::new(detected_cards))
+        if let Some(recorder) = recorder {
+            if let Err(e) = recorder.write_manifest() {
+                log::warn!("{}", e);
+            }
+        }
+
+        let mut result = CardDetectionResult::new(detected_cards);
+        result.unchanged_regions = unchanged_regions;
+        result.region_hashes = region_hashes;
+        Ok(result)
+    }
+
+    /// Detect cards in a screenshot instead of the live screen
+    ///
+    /// Each configured region is cropped out of `screenshot`; regions that
+    /// fall outside it are skipped.
+    pub fn detect_cards_in_image(
+        &self,
+        screenshot: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    ) -> CardDetectionResult {
+        let mut detected_cards = Vec::new();
+        let mut region_hashes = Vec::new();
+        let mut detail_captures = Vec::new();
+        let mut debug_image_index = 0;
+
+        for (i, region) in self.options.capture.get_regions().iter().enumerate() {
+            let inside = region.x >= 0
+                && region.y >= 0
+                && region.x as u64 + region.width as u64 <= screenshot.width() as u64
+                && region.y as u64 + region.height as u64 <= screenshot.height() as u64;
+            if !region.is_valid() || !inside {
+                log::warn!("Region {} ({}) is outside the image", i, region);
+                region_hashes.push(None);
+                continue;
+            }
+
+            let rgba_image = image::imageops::crop_imm(
+                screenshot,
+                region.x as u32,
+                region.y as u32,
+                region.width,
+                region.height,
+            )
+            .to_image();
+            region_hashes.push(Some(image_hash(&rgba_image)));
+            if region.role.and_then(|role| role.detail_slot()).is_some() {
+                detail_captures.push(DetailCapture {
+                    index: i,
+                    region: *region,
+                    image: rgba_image,
+                });
+                continue;
+            }
+            match self.recognize_region(i, &rgba_image, &mut debug_image_index) {
+                Ok(outcome) => detected_cards.extend(detected_card(&outcome, *region)),
+                Err(e) => return CardDetectionResult::failed(e),
+            }
+        }
+
+        let all: Vec<usize> = (0..detected_cards.len()).collect();
+        if let Err(e) = self.attach_card_details(&mut detected_cards, &all, detail_captures) {
+            return CardDetectionResult::failed(e);
+        }
+
+        CardDetectionResult {
+            region_hashes,
+            ..CardDetectionResult::new(detected_cards)
+        }
+    }
+
+    /// Read the rules text and upgrade pip regions and attach each to the
+    /// card read in the same slot
+    ///
+    /// Only the cards at `candidates`, indices into `cards`, are given
+    /// details; returns the indices of those that were.
+    fn attach_card_details(
+        &self,
+        cards: &mut [DetectedCard],
+        candidates: &[usize],
+        detail_captures: Vec<DetailCapture>,
+    ) -> OcrPipelineResult<Vec<usize>> {
+        let mut attached = Vec::new();
+        for capture in detail_captures {
+            let Some(role) = capture.region.role else { continue };
+            let card = candidates.iter().copied().find(|&index| {
+                cards[index].region.role.and_then(|role| role.card_slot()) == role.detail_slot()
+            });
+            let Some(index) = card else { continue };
+
+            if role.text_slot().is_some() {
+                cards[index].rules_text = self.read_rules_text(capture.index, &capture.image)?;
+            } else {
+                cards[index].upgrade_count = self
+                    .pip_sprites
+                    .as_ref()
+                    .map(|sprites| sprites.count(&capture.image));
+            }
+            if !attached.contains(&index) {
+                attached.push(index);
+            }
+        }
+        Ok(attached)
+    }
+
+    /// Preprocess a rules text region and read its text
+    ///
+    /// Fails only when recognition times out or the detection is cancelled;
+    /// returns `None` when no text could be read.
+    fn read_rules_text(
+        &self,
+        i: usize,
+        rgba_image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    ) -> OcrPipelineResult<Option<String>> {
+        let engine = match (self.region_pipelines.get(&i), &self.rules_text_engine) {
+            (Some(pipeline), _) => pipeline.ocr_engine(),
+            (None, Some(engine)) => Arc::clone(engine),
+            (None, None) => return Ok(None),
+        };
+
+        let gray_image = match preprocess_for_ocr(rgba_image, self.options.preprocess_for(i)) {
+            Ok(img) => img,
+            Err(e) => {
+                log::warn!("Preprocessing failed for rules text region {}: {}", i, e);
+                return Ok(None);
+            }
+        };
+        let recognized = tracing::info_span!("ocr.recognize").in_scope(|| {
+            timeout::run_stage(
+                DetectionStage::Recognize,
+                self.options.stage_timeouts.recognize,
+                &self.cancel,
+                move || engine.recognize(&gray_image),
+            )
+        })?;
+
+        match recognized {
+            Ok(result) if !result.text.is_empty() => Ok(Some(result.text)),
+            Ok(_) => Ok(None),
+            Err(e) => {
+                log::warn!("Recognition failed for rules text region {}: {}", i, e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Preprocess and recognize a single captured region
+    ///
+    /// Fails only when recognition times out or the detection is cancelled;
+    /// other failures are reported in the outcome.
+    fn recognize_region(
+        &self,
+        i: usize,
+        rgba_image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+        debug_image_index: &mut usize,
+    ) -> OcrPipelineResult<RegionOutcome> {
+        // Step 3: Preprocess
+        let preprocessed = tracing::info_span!("ocr.preprocess")
+            .in_scope(|| preprocess_for_ocr(rgba_image, self.options.preprocess_for(i)));
+        let gray_image = match preprocessed {
+            Ok(img) => img,
+            Err(e) => {
+                log::warn!("Preprocessing failed for region {}: {}", i, e);
+                return Ok(RegionOutcome::failed(RegionDecision::PreprocessFailed, e));
+            }
+        };
+
+        // Save debug image if enabled
+        if self.options.save_debug_images {
+            if let Some(ref path) = self.options.debug_image_path {
+                let debug_path = path.join(format!("debug_region_{}.png", debug_image_index));
+                let _ = save_debug_image(&gray_image, &debug_path);
+                *debug_image_index += 1;
+            }
+        }
+
+        // Step 4: Recognize, with the original colors as a tie-breaker
+        let banner = analyze_banner(rgba_image);
+        let pipeline = self.region_pipelines.get(&i).unwrap_or(&self.recognition_pipeline);
+        let engine = pipeline.ocr_engine();
+        let image = gray_image.clone();
+        let recognized = tracing::info_span!("ocr.recognize").in_scope(|| {
+            timeout::run_stage(
+                DetectionStage::Recognize,
+                self.options.stage_timeouts.recognize,
+                &self.cancel,
+                move || engine.recognize(&image),
+            )
+        })?;
+        let ocr_result = match recognized {
+            Ok(result) => result,
+            Err(e) => {
+                log::warn!("Recognition failed for region {}: {}", i, e);
+                return Ok(RegionOutcome {
+                    gray_image: Some(gray_image),
+                    ..RegionOutcome::failed(RegionDecision::RecognizeFailed, e)
+                });
+            }
+        };
+        let card_match = pipeline.match_ocr_result(&ocr_result, banner.as_ref());
+
+        let decision = match &card_match {
+            _ if !ocr_result.is_confident => RegionDecision::LowOcrConfidence,
+            None => {
+                log::debug!("No card detected in region {}", i);
+                RegionDecision::NoMatch
+            }
+            Some(m) if m.overall_confidence < self.options.min_overall_confidence => {
+                RegionDecision::BelowThreshold
+            }
+            Some(_) => RegionDecision::Matched,
+        };
+
+        Ok(RegionOutcome {
+            decision,
+            gray_image: Some(gray_image),
+            ocr_result: Some(ocr_result),
+            card_match,
+            error: None,
+        })
     }
 
     /// Update capture regions
@@ -390,7 +931,8 @@ pub fn quick_detect(card_names: Vec<(String, String)>) -> OcrPipelineResult<Card
 /// This function helps find the best capture regions for a given screen
 /// by testing the current configuration and returning capture statistics.
 pub fn calibrate_regions(options: &CardDetectionOptions) -> OcrPipelineResult<CalibrationReport> {
-    let dimensions = get_primary_screen_dimensions()?;
+    let display = get_primary_display_metrics()?;
+    let dimensions = display.physical_size();
     let regions = options.capture.get_regions();
 
     // Test capture of each region
@@ -398,7 +940,7 @@ pub fn calibrate_regions(options: &CardDetectionOptions) -> OcrPipelineResult<Ca
     let mut failed_captures = 0;
 
     for region in regions {
-        match capture_region(region) {
+        match options.capture.capture(region) {
             Ok(_) => successful_captures += 1,
             Err(_) => failed_captures += 1,
         }
@@ -406,6 +948,7 @@ pub fn calibrate_regions(options: &CardDetectionOptions) -> OcrPipelineResult<Ca
 
     Ok(CalibrationReport {
         screen_dimensions: dimensions,
+        scale_factor: display.scale_factor,
         regions_tested: regions.len(),
         successful_captures,
         failed_captures,
@@ -413,11 +956,68 @@ pub fn calibrate_regions(options: &CardDetectionOptions) -> OcrPipelineResult<Ca
     })
 }
 
+/// What the OCR sees for one capture region
+#[derive(Debug, Clone)]
+pub struct RegionPreview {
+    pub region: CaptureRegion,
+    /// Raw capture, if the region could be captured
+    pub raw_image: Option<ImageBuffer<Rgba<u8>, Vec<u8>>>,
+    /// Capture after the configured preprocessing steps
+    pub preprocessed_image: Option<GrayImage>,
+    /// Why capture or preprocessing failed
+    pub error: Option<String>,
+}
+
+/// Capture and preprocess every configured region without running OCR
+///
+/// Lets the calibration UI show exactly what Tesseract would be given.
+pub fn preview_regions(options: &CardDetectionOptions) -> Vec<RegionPreview> {
+    options
+        .capture
+        .get_regions()
+        .iter()
+        .enumerate()
+        .map(|(i, region)| {
+            let preview = RegionPreview {
+                region: *region,
+                raw_image: None,
+                preprocessed_image: None,
+                error: None,
+            };
+
+            let raw_image = match options.capture.capture(region) {
+                Ok(img) => img,
+                Err(e) => {
+                    return RegionPreview {
+                        error: Some(e.to_string()),
+                        ..preview
+                    }
+                }
+            };
+
+            match preprocess_for_ocr(&raw_image, options.preprocess_for(i)) {
+                Ok(gray_image) => RegionPreview {
+                    raw_image: Some(raw_image),
+                    preprocessed_image: Some(gray_image),
+                    ..preview
+                },
+                Err(e) => RegionPreview {
+                    raw_image: Some(raw_image),
+                    error: Some(e.to_string()),
+                    ..preview
+                },
+            }
+        })
+        .collect()
+}
+
 /// Report from calibration operation
 #[derive(Debug, Clone)]
 pub struct CalibrationReport {
-    /// Screen dimensions (width, height)
+    /// Screen dimensions in physical pixels (width, height)
     pub screen_dimensions: (u32, u32),
+    /// Display scale factor of the primary monitor
+    pub scale_factor: f32,
     /// Number of regions tested
     pub regions_tested: usize,
     /// Number of successful captures
@@ -459,6 +1059,10 @@ mod tests {
             match_score: 90,
             overall_confidence: 0.85,
             raw_ocr_text: "Test".to_string(),
+            candidates: vec![],
+            banner: None,
+            rules_text: None,
+            upgrade_count: None,
         };
 
         assert!(card.is_confident(0.8));
@@ -476,6 +1080,10 @@ mod tests {
                 match_score: 85,
                 overall_confidence: 0.8,
                 raw_ocr_text: "Card 1".to_string(),
+                candidates: vec![],
+                banner: None,
+                rules_text: None,
+                upgrade_count: None,
             },
             DetectedCard {
                 card_id: "2".to_string(),
@@ -485,6 +1093,10 @@ mod tests {
                 match_score: 90,
                 overall_confidence: 0.85,
                 raw_ocr_text: "Card 2".to_string(),
+                candidates: vec![],
+                banner: None,
+                rules_text: None,
+                upgrade_count: None,
             },
         ];
 
@@ -522,6 +1134,10 @@ mod tests {
                 match_score: 85,
                 overall_confidence: 0.5,
                 raw_ocr_text: "Card 1".to_string(),
+                candidates: vec![],
+                banner: None,
+                rules_text: None,
+                upgrade_count: None,
             },
             DetectedCard {
                 card_id: "2".to_string(),
@@ -531,6 +1147,10 @@ mod tests {
                 match_score: 90,
                 overall_confidence: 0.8,
                 raw_ocr_text: "Card 2".to_string(),
+                candidates: vec![],
+                banner: None,
+                rules_text: None,
+                upgrade_count: None,
             },
         ];
 
@@ -544,6 +1164,7 @@ mod tests {
     fn test_calibration_report() {
         let report = CalibrationReport {
             screen_dimensions: (1920, 1080),
+            scale_factor: 1.0,
             regions_tested: 4,
             successful_captures: 3,
             failed_captures: 1,
@@ -558,6 +1179,7 @@ mod tests {
     fn test_calibration_report_successful() {
         let report = CalibrationReport {
             screen_dimensions: (1920, 1080),
+            scale_factor: 1.0,
             regions_tested: 4,
             successful_captures: 4,
             failed_captures: 0,
@@ -576,6 +1198,40 @@ mod tests {
         assert!(options.min_overall_confidence > 0.0);
     }
 
+    #[test]
+    fn test_region_profiles_override_global_configs() {
+        let mut options = CardDetectionOptions::default();
+        let digits = PreprocessConfig {
+            steps: vec![PreprocessStep::Grayscale, PreprocessStep::Invert],
+        };
+        options.region_profiles.insert(
+            1,
+            RegionProfile {
+                preprocess: Some(digits.clone()),
+                recognize: Some(RecognizeConfig::default().with_whitelist("0123456789")),
+            },
+        );
+        options.region_profiles.insert(2, RegionProfile::default());
+
+        assert_eq!(options.preprocess_for(0), &options.preprocess);
+        assert_eq!(options.preprocess_for(1), &digits);
+        assert_eq!(options.preprocess_for(2), &options.preprocess);
+
+        // Settings shared by every config reach the overrides too
+        for recognize in options.recognize_configs_mut() {
+            recognize.language = "deu".to_string();
+        }
+        assert_eq!(options.recognize.language, "deu");
+        let profile = options.region_profiles[&1].recognize.as_ref().unwrap();
+        assert_eq!(profile.language, "deu");
+        assert_eq!(profile.whitelist.as_deref(), Some("0123456789"));
+
+        let card_names = vec![("1".to_string(), "Card".to_string())];
+        let pipeline = OcrPipeline::new(card_names, options).unwrap();
+        assert_eq!(pipeline.region_pipelines.len(), 1);
+        assert!(pipeline.region_pipelines.contains_key(&1));
+    }
+
     #[test]
     fn test_ocr_pipeline_error_display() {
         let err = OcrPipelineError::Configuration("test".to_string());
@@ -591,5 +1247,8 @@ mod tests {
         let preprocess_err = PreprocessError::EmptyImage;
         let pipeline_err: OcrPipelineError = preprocess_err.into();
         assert!(matches!(pipeline_err, OcrPipelineError::Preprocess(_)));
+
+        let pipeline_err: OcrPipelineError = StageError::Cancelled.into();
+        assert_eq!(pipeline_err.to_string(), "Detection was cancelled");
     }
 }
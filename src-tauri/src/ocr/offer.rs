@@ -0,0 +1,139 @@
+//! Draft offer identity from region image hashes
+//!
+//! The continuous loop sees the same offer for many frames while the player
+//! decides. Each region capture is reduced to a 64-bit difference hash,
+//! which survives small rendering changes such as a hover glow, and a
+//! frame's hashes are compared with those of the last offer to tell "the
+//! same offer is still on screen" from "a new set of cards appeared". This
+//! module only depends on `image` and is compiled with or without the `ocr`
+//! feature.
+
+use image::imageops::{self, FilterType};
+use image::{ImageBuffer, Rgba};
+
+/// Bits two region hashes may differ by and still show the same card
+pub const OFFER_HASH_TOLERANCE: u32 = 10;
+
+/// Size a capture is shrunk to before hashing; one extra column gives
+/// eight neighbour comparisons per row
+const HASH_WIDTH: u32 = 9;
+const HASH_HEIGHT: u32 = 8;
+
+/// Difference hash of a captured region
+///
+/// The capture is shrunk to 9x8 gray pixels, and each bit says whether a
+/// pixel is brighter than its right-hand neighbour.
+pub fn image_hash(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> u64 {
+    let gray = imageops::grayscale(image);
+    let small = imageops::resize(&gray, HASH_WIDTH, HASH_HEIGHT, FilterType::Triangle);
+
+    let mut hash = 0u64;
+    for y in 0..HASH_HEIGHT {
+        for x in 0..HASH_WIDTH - 1 {
+            let brighter = small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | brighter as u64;
+        }
+    }
+    hash
+}
+
+/// Number of bits that differ between two hashes
+pub fn hash_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Whether two frames' region hashes show the same offer
+///
+/// Regions are compared by position, skipping any that failed to capture
+/// in either frame. Most compared regions must be within
+/// `OFFER_HASH_TOLERANCE`, so a single card lit up by the cursor does not
+/// make a new offer.
+pub fn same_offer(previous: &[Option<u64>], current: &[Option<u64>]) -> bool {
+    if previous.len() != current.len() {
+        return false;
+    }
+
+    let (mut compared, mut matching) = (0, 0);
+    for (previous, current) in previous.iter().zip(current) {
+        if let (Some(previous), Some(current)) = (previous, current) {
+            compared += 1;
+            if hash_distance(*previous, *current) <= OFFER_HASH_TOLERANCE {
+                matching += 1;
+            }
+        }
+    }
+    compared > 0 && matching * 2 > compared
+}
+
+/// Tells a new draft offer apart from the one still on screen
+#[derive(Debug, Clone, Default)]
+pub struct OfferTracker {
+    /// Region hashes of the last reported offer
+    current: Option<Vec<Option<u64>>>,
+}
+
+impl OfferTracker {
+    /// Record a frame; returns true only for the first frame of a new offer
+    ///
+    /// `shows_offer` says whether the frame's cards were read as a draft
+    /// offer. Other frames are ignored and leave the last offer current, so
+    /// coming back to it after a look at the deck does not report it again.
+    pub fn update(&mut self, hashes: &[Option<u64>], shows_offer: bool) -> bool {
+        if !shows_offer || hashes.iter().all(Option::is_none) {
+            return false;
+        }
+        if let Some(current) = &self.current {
+            if same_offer(current, hashes) {
+                return false;
+            }
+        }
+
+        self.current = Some(hashes.to_vec());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A capture with a horizontal gradient starting at `offset`
+    fn card(offset: u8, reversed: bool) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        ImageBuffer::from_fn(90, 24, |x, y| {
+            let x = if reversed { 89 - x } else { x };
+            let value = offset.wrapping_add((x * 2 + (y % 3)) as u8);
+            Rgba([value, value, value, 255])
+        })
+    }
+
+    #[test]
+    fn test_image_hash_tolerates_small_changes() {
+        let talos = image_hash(&card(20, false));
+        let glowing = image_hash(&card(26, false));
+        let other = image_hash(&card(20, true));
+
+        assert!(hash_distance(talos, glowing) <= OFFER_HASH_TOLERANCE);
+        assert!(hash_distance(talos, other) > OFFER_HASH_TOLERANCE);
+    }
+
+    #[test]
+    fn test_offer_tracker_reports_each_offer_once() {
+        let first = [Some(0x0f0f), Some(0xff00), Some(0x00ff)];
+        let hovered = [Some(0x0f0f), Some(!0xff00), Some(0x00ff)];
+        let second = [Some(!0x0f0f), Some(!0xff00), Some(0x00ff)];
+        let mut tracker = OfferTracker::default();
+
+        assert!(!tracker.update(&first, false));
+        assert!(tracker.update(&first, true));
+        assert!(!tracker.update(&first, true));
+        assert!(!tracker.update(&hovered, true));
+
+        // The map screen in between does not end the offer
+        assert!(!tracker.update(&[Some(1), Some(2), Some(3)], false));
+        assert!(!tracker.update(&first, true));
+
+        assert!(tracker.update(&second, true));
+        assert!(!tracker.update(&[None, None, None], true));
+        assert!(!same_offer(&first, &first[..2]));
+    }
+}
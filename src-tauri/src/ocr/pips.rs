@@ -0,0 +1,246 @@
+//! Upgrade pip detection
+//!
+//! A card that comes upgraded shows one pip per upgrade. The card's pip
+//! region is searched for known pip sprites by normalized cross-correlation
+//! and the non-overlapping matches are counted. Sprites are PNG files in the
+//! `upgrade-pips` folder of the app data folder, cut from 1080p screenshots
+//! and scaled to the screen height before matching. This module only
+//! depends on `image` and is compiled with or without the `ocr` feature.
+
+use image::imageops::{self, FilterType};
+use image::{GrayImage, ImageBuffer, Rgba};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Folder of pip sprites in the app data folder
+pub const PIP_SPRITES_DIR: &str = "upgrade-pips";
+
+/// Screen height the sprites were cut at
+pub const SPRITE_REFERENCE_HEIGHT: u32 = 1080;
+
+/// Correlation a spot needs to count as a pip
+const MATCH_THRESHOLD: f64 = 0.8;
+
+/// Error type for loading pip sprites
+#[derive(Debug)]
+pub enum PipError {
+    Io(std::io::Error),
+    Image(image::ImageError),
+    /// The folder holds no PNG files
+    NoSprites(PathBuf),
+}
+
+impl std::fmt::Display for PipError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PipError::Io(e) => write!(f, "Pip sprite I/O error: {}", e),
+            PipError::Image(e) => write!(f, "Failed to read pip sprite: {}", e),
+            PipError::NoSprites(dir) => write!(f, "No pip sprites in {}", dir.display()),
+        }
+    }
+}
+
+impl std::error::Error for PipError {}
+
+impl From<std::io::Error> for PipError {
+    fn from(e: std::io::Error) -> Self {
+        PipError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for PipError {
+    fn from(e: image::ImageError) -> Self {
+        PipError::Image(e)
+    }
+}
+
+/// Result type for pip sprite operations
+pub type PipResult<T> = Result<T, PipError>;
+
+/// Known upgrade pip sprites, in grayscale
+#[derive(Debug, Clone, Default)]
+pub struct PipSprites {
+    sprites: Vec<GrayImage>,
+}
+
+impl PipSprites {
+    pub fn new(sprites: Vec<GrayImage>) -> Self {
+        Self { sprites }
+    }
+
+    /// Load every PNG file in `dir`
+    pub fn load_dir(dir: &Path) -> PipResult<Self> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+            })
+            .collect();
+        if paths.is_empty() {
+            return Err(PipError::NoSprites(dir.to_path_buf()));
+        }
+        paths.sort();
+
+        let sprites = paths
+            .iter()
+            .map(|path| Ok(image::open(path)?.to_luma8()))
+            .collect::<PipResult<Vec<_>>>()?;
+        Ok(Self::new(sprites))
+    }
+
+    /// The sprites resized for a screen `screen_height` pixels tall
+    pub fn scaled_for(&self, screen_height: u32) -> Self {
+        if screen_height == SPRITE_REFERENCE_HEIGHT || screen_height == 0 {
+            return self.clone();
+        }
+
+        let scale = screen_height as f64 / SPRITE_REFERENCE_HEIGHT as f64;
+        let sprites = self
+            .sprites
+            .iter()
+            .map(|sprite| {
+                let width = ((sprite.width() as f64 * scale).round() as u32).max(1);
+                let height = ((sprite.height() as f64 * scale).round() as u32).max(1);
+                imageops::resize(sprite, width, height, FilterType::Triangle)
+            })
+            .collect();
+        Self::new(sprites)
+    }
+
+    /// Number of pips in a captured pip region
+    ///
+    /// Each sprite is counted on its own and the highest count wins, so
+    /// two sprites of the same pip are not counted twice.
+    pub fn count(&self, image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> u32 {
+        let gray = imageops::grayscale(image);
+        self.sprites
+            .iter()
+            .map(|sprite| count_matches(&gray, sprite))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Count the non-overlapping places where `sprite` matches `image`
+///
+/// The best matches are taken first, and a match overlapping one already
+/// taken is dropped.
+fn count_matches(image: &GrayImage, sprite: &GrayImage) -> u32 {
+    let (width, height) = sprite.dimensions();
+    if width > image.width() || height > image.height() {
+        return 0;
+    }
+
+    let pixels = (width * height) as f64;
+    let mean = sprite.pixels().map(|p| p[0] as f64).sum::<f64>() / pixels;
+    let centered: Vec<f64> = sprite.pixels().map(|p| p[0] as f64 - mean).collect();
+    let norm = centered.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        return 0;
+    }
+
+    let mut candidates = Vec::new();
+    for y in 0..=image.height() - height {
+        for x in 0..=image.width() - width {
+            let score = correlation(image, x, y, width, height, &centered, norm);
+            if score >= MATCH_THRESHOLD {
+                candidates.push((score, x, y));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut taken: Vec<(u32, u32)> = Vec::new();
+    for (_, x, y) in candidates {
+        let overlaps = taken
+            .iter()
+            .any(|&(tx, ty)| tx.abs_diff(x) < width && ty.abs_diff(y) < height);
+        if !overlaps {
+            taken.push((x, y));
+        }
+    }
+    taken.len() as u32
+}
+
+/// Normalized cross-correlation (-1.0 to 1.0) of the sprite with the
+/// window of `image` at (`x`, `y`); a flat window scores 0
+fn correlation(
+    image: &GrayImage,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    centered: &[f64],
+    norm: f64,
+) -> f64 {
+    let pixels = (width * height) as f64;
+    let window = || (0..height).flat_map(move |dy| (0..width).map(move |dx| (dx, dy)));
+    let mean = window()
+        .map(|(dx, dy)| image.get_pixel(x + dx, y + dy)[0] as f64)
+        .sum::<f64>()
+        / pixels;
+
+    let (mut dot, mut energy) = (0.0, 0.0);
+    for ((dx, dy), sprite) in window().zip(centered) {
+        let value = image.get_pixel(x + dx, y + dy)[0] as f64 - mean;
+        dot += value * sprite;
+        energy += value * value;
+    }
+    if energy == 0.0 {
+        return 0.0;
+    }
+    dot / (energy.sqrt() * norm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    /// A bright 6x6 diamond on a dark background
+    fn pip() -> GrayImage {
+        GrayImage::from_fn(6, 6, |x, y| {
+            let distance = (x as i32 * 2 - 5).abs() + (y as i32 * 2 - 5).abs();
+            Luma([if distance <= 5 { 230 } else { 30 }])
+        })
+    }
+
+    fn region_with_pips(at: &[u32]) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+        let pip = pip();
+        ImageBuffer::from_fn(60, 10, |x, y| {
+            let value = at
+                .iter()
+                .find(|&&left| (left..left + 6).contains(&x) && (2..8).contains(&y))
+                .map(|&left| pip.get_pixel(x - left, y - 2)[0])
+                .unwrap_or(30);
+            Rgba([value, value, value, 255])
+        })
+    }
+
+    #[test]
+    fn test_counts_each_pip_once() {
+        let sprites = PipSprites::new(vec![pip()]);
+        assert_eq!(sprites.count(&region_with_pips(&[])), 0);
+        assert_eq!(sprites.count(&region_with_pips(&[4])), 1);
+        assert_eq!(sprites.count(&region_with_pips(&[4, 14, 30])), 3);
+
+        // A sprite larger than the region never matches
+        let large = PipSprites::new(vec![GrayImage::new(80, 20)]);
+        assert_eq!(large.count(&region_with_pips(&[4])), 0);
+    }
+
+    #[test]
+    fn test_sprites_scale_with_screen_height() {
+        let sprites = PipSprites::new(vec![pip()]).scaled_for(2160);
+        assert_eq!(sprites.sprites[0].dimensions(), (12, 12));
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            PipSprites::load_dir(dir.path()),
+            Err(PipError::NoSprites(_))
+        ));
+        pip().save(dir.path().join("pip.png")).unwrap();
+        assert_eq!(PipSprites::load_dir(dir.path()).unwrap().sprites.len(), 1);
+    }
+}
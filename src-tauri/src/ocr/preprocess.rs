@@ -5,15 +5,29 @@
 //! - Adaptive thresholding
 //! - Noise reduction
 //! - Contrast enhancement
+//! - Morphological dilation and erosion
+//!
+//! The order of operations is configured as a list of `PreprocessStep`s.
 
 use image::{GrayImage, ImageBuffer, Luma, Rgba};
 
+pub use super::banner::{analyze_banner, BannerColor};
+pub use super::preprocess_steps::{default_steps, validate_steps, PreprocessStep};
+use super::preprocess_steps::MAX_UPSCALE_FACTOR;
+
+/// Shortest run of text rows treated as a line of text rather than noise
+const MIN_MEASURED_TEXT_HEIGHT: u32 = 4;
+
+/// Smallest factor a capture is scaled down by to reach the target text height
+const MIN_DOWNSCALE_FACTOR: f32 = 0.25;
+
 /// Error types for image preprocessing
 #[derive(Debug, PartialEq)]
 pub enum PreprocessError {
     InvalidImage(String),
     ProcessingFailed(String),
     EmptyImage,
+    InvalidPipeline(String),
 }
 
 impl std::fmt::Display for PreprocessError {
@@ -22,6 +36,7 @@ impl std::fmt::Display for PreprocessError {
             PreprocessError::InvalidImage(msg) => write!(f, "Invalid image: {}", msg),
             PreprocessError::ProcessingFailed(msg) => write!(f, "Processing failed: {}", msg),
             PreprocessError::EmptyImage => write!(f, "Image is empty"),
+            PreprocessError::InvalidPipeline(msg) => write!(f, "Invalid preprocessing pipeline: {}", msg),
         }
     }
 }
@@ -32,37 +47,16 @@ impl std::error::Error for PreprocessError {}
 pub type PreprocessResult<T> = Result<T, PreprocessError>;
 
 /// Configuration for image preprocessing
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PreprocessConfig {
-    /// Threshold value for binary conversion (0-255)
-    pub threshold: u8,
-    /// Whether to use adaptive thresholding
-    pub use_adaptive_threshold: bool,
-    /// Block size for adaptive thresholding (must be odd)
-    pub adaptive_block_size: u32,
-    /// Constant C for adaptive thresholding
-    pub adaptive_c: i32,
-    /// Whether to apply denoising
-    pub denoise: bool,
-    /// Whether to invert colors (white text on black background)
-    pub invert: bool,
-    /// Scale factor for upscaling (1.0 = no scaling)
-    pub scale_factor: f32,
-    /// Contrast enhancement factor (1.0 = no enhancement)
-    pub contrast_factor: f32,
+    /// Operations applied to each capture, in order
+    pub steps: Vec<PreprocessStep>,
 }
 
 impl Default for PreprocessConfig {
     fn default() -> Self {
         Self {
-            threshold: 127,
-            use_adaptive_threshold: true,
-            adaptive_block_size: 11,
-            adaptive_c: 2,
-            denoise: true,
-            invert: false,
-            scale_factor: 2.0, // Upscale by 2x for better OCR
-            contrast_factor: 1.5,
+            steps: default_steps(),
         }
     }
 }
@@ -87,6 +81,9 @@ pub fn apply_threshold(img: &GrayImage, threshold: u8) -> GrayImage {
 
 /// Apply adaptive thresholding using mean method
 /// This is more robust to varying lighting conditions
+///
+/// Neighborhood means come from an integral image, so the cost doesn't
+/// depend on `block_size`.
 pub fn apply_adaptive_threshold(img: &GrayImage, block_size: u32, c: i32) -> GrayImage {
     if block_size == 0 || block_size % 2 == 0 {
         // Block size must be odd and positive
@@ -94,35 +91,39 @@ pub fn apply_adaptive_threshold(img: &GrayImage, block_size: u32, c: i32) -> Gra
     }
 
     let (width, height) = img.dimensions();
-    let mut result = GrayImage::new(width, height);
-    let half_block = (block_size / 2) as i32;
-
-    for y in 0..height {
-        for x in 0..width {
-            // Calculate mean of neighborhood
-            let mut sum: u32 = 0;
-            let mut count: u32 = 0;
-
-            for dy in -half_block..=half_block {
-                for dx in -half_block..=half_block {
-                    let nx = x as i32 + dx;
-                    let ny = y as i32 + dy;
+    let (w, h) = (width as usize, height as usize);
+    let half_block = (block_size / 2) as usize;
+    let pixels = img.as_raw();
+
+    // integral[y * (w + 1) + x] is the sum of all pixels above and left of (x, y)
+    let stride = w + 1;
+    let mut integral = vec![0u64; stride * (h + 1)];
+    for y in 0..h {
+        let mut row_sum = 0u64;
+        for x in 0..w {
+            row_sum += pixels[y * w + x] as u64;
+            integral[(y + 1) * stride + x + 1] = integral[y * stride + x + 1] + row_sum;
+        }
+    }
 
-                    if nx >= 0 && nx < width as i32 && ny >= 0 && ny < height as i32 {
-                        sum += img.get_pixel(nx as u32, ny as u32)[0] as u32;
-                        count += 1;
-                    }
-                }
-            }
+    let mut result = GrayImage::new(width, height);
+    let out: &mut [u8] = &mut result;
+    for y in 0..h {
+        let top = y.saturating_sub(half_block);
+        let bottom = (y + half_block + 1).min(h);
+        for x in 0..w {
+            let left = x.saturating_sub(half_block);
+            let right = (x + half_block + 1).min(w);
+
+            let sum = integral[bottom * stride + right] + integral[top * stride + left]
+                - integral[top * stride + right]
+                - integral[bottom * stride + left];
+            let count = ((bottom - top) * (right - left)) as u64;
+            let mean = (sum / count) as i32;
 
-            let mean = if count > 0 { (sum / count) as i32 } else { 128 };
-            let pixel_value = img.get_pixel(x, y)[0] as i32;
-            
             // Apply threshold: pixel > (mean - C) ? white : black
             let threshold_value = mean - c;
-            let new_value = if pixel_value > threshold_value { 255 } else { 0 };
-            
-            result.put_pixel(x, y, Luma([new_value as u8]));
+            out[y * w + x] = if pixels[y * w + x] as i32 > threshold_value { 255 } else { 0 };
         }
     }
 
@@ -134,40 +135,104 @@ pub fn apply_gaussian_blur(img: &GrayImage, sigma: f32) -> GrayImage {
     image::imageops::blur(img, sigma)
 }
 
-/// Simple median filter for noise reduction
+/// Median filter for noise reduction
+///
+/// Slides a 256-bin histogram along each row and tracks the median as
+/// columns enter and leave the window, so each pixel costs one kernel
+/// column rather than a sort of the whole kernel.
 pub fn apply_median_filter(img: &GrayImage, kernel_size: u32) -> GrayImage {
     if kernel_size < 3 || kernel_size % 2 == 0 {
         return img.clone();
     }
 
     let (width, height) = img.dimensions();
-    let mut result = GrayImage::new(width, height);
-    let half_kernel = (kernel_size / 2) as i32;
+    let (w, h) = (width as usize, height as usize);
+    let half_kernel = (kernel_size / 2) as usize;
+    let pixels = img.as_raw();
 
-    for y in 0..height {
-        for x in 0..width {
-            let mut values: Vec<u8> = Vec::new();
-
-            for dy in -half_kernel..=half_kernel {
-                for dx in -half_kernel..=half_kernel {
-                    let nx = x as i32 + dx;
-                    let ny = y as i32 + dy;
+    let mut result = GrayImage::new(width, height);
+    let out: &mut [u8] = &mut result;
+    for y in 0..h {
+        let top = y.saturating_sub(half_kernel);
+        let bottom = (y + half_kernel + 1).min(h);
+        let rows = bottom - top;
+
+        let mut window = MedianWindow::default();
+        for x in 0..half_kernel.min(w) {
+            for row in top..bottom {
+                window.add(pixels[row * w + x]);
+            }
+        }
 
-                    if nx >= 0 && nx < width as i32 && ny >= 0 && ny < height as i32 {
-                        values.push(img.get_pixel(nx as u32, ny as u32)[0]);
-                    }
+        for x in 0..w {
+            if x + half_kernel < w {
+                for row in top..bottom {
+                    window.add(pixels[row * w + x + half_kernel]);
+                }
+            }
+            if x > half_kernel {
+                for row in top..bottom {
+                    window.remove(pixels[row * w + x - half_kernel - 1]);
                 }
             }
 
-            values.sort_unstable();
-            let median = values[values.len() / 2];
-            result.put_pixel(x, y, Luma([median]));
+            let columns = (x + half_kernel + 1).min(w) - x.saturating_sub(half_kernel);
+            out[y * w + x] = window.median(rows * columns);
         }
     }
 
     result
 }
 
+/// Histogram of a median filter window with the running median
+struct MedianWindow {
+    histogram: [u32; 256],
+    /// Current median candidate
+    median: usize,
+    /// Number of values in the window below `median`
+    below: usize,
+}
+
+impl Default for MedianWindow {
+    fn default() -> Self {
+        Self {
+            histogram: [0; 256],
+            median: 0,
+            below: 0,
+        }
+    }
+}
+
+impl MedianWindow {
+    fn add(&mut self, value: u8) {
+        self.histogram[value as usize] += 1;
+        if (value as usize) < self.median {
+            self.below += 1;
+        }
+    }
+
+    fn remove(&mut self, value: u8) {
+        self.histogram[value as usize] -= 1;
+        if (value as usize) < self.median {
+            self.below -= 1;
+        }
+    }
+
+    /// Value at index `count / 2` of the sorted window
+    fn median(&mut self, count: usize) -> u8 {
+        let rank = count / 2;
+        while self.below > rank {
+            self.median -= 1;
+            self.below -= self.histogram[self.median] as usize;
+        }
+        while self.below + self.histogram[self.median] as usize <= rank {
+            self.below += self.histogram[self.median] as usize;
+            self.median += 1;
+        }
+        self.median as u8
+    }
+}
+
 /// Invert image colors
 pub fn invert(img: &GrayImage) -> GrayImage {
     let mut result = img.clone();
@@ -194,6 +259,46 @@ pub fn enhance_contrast(img: &GrayImage, factor: f32) -> GrayImage {
     result
 }
 
+/// Replace each pixel with the maximum (`dilate`) or minimum of its
+/// square neighborhood
+fn morphology(img: &GrayImage, radius: u32, dilate: bool) -> GrayImage {
+    let (width, height) = img.dimensions();
+    let radius = radius as i32;
+    let mut result = GrayImage::new(width, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let mut value = img.get_pixel(x, y)[0];
+
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    let nx = x as i32 + dx;
+                    let ny = y as i32 + dy;
+
+                    if nx >= 0 && nx < width as i32 && ny >= 0 && ny < height as i32 {
+                        let neighbor = img.get_pixel(nx as u32, ny as u32)[0];
+                        value = if dilate { value.max(neighbor) } else { value.min(neighbor) };
+                    }
+                }
+            }
+
+            result.put_pixel(x, y, Luma([value]));
+        }
+    }
+
+    result
+}
+
+/// Grow bright regions (thins dark text on a light background)
+pub fn apply_dilate(img: &GrayImage, radius: u32) -> GrayImage {
+    morphology(img, radius, true)
+}
+
+/// Shrink bright regions (thickens dark text on a light background)
+pub fn apply_erode(img: &GrayImage, radius: u32) -> GrayImage {
+    morphology(img, radius, false)
+}
+
 /// Scale up image for better OCR accuracy
 pub fn upscale(img: &GrayImage, factor: f32) -> GrayImage {
     if factor <= 1.0 {
@@ -212,6 +317,95 @@ pub fn upscale(img: &GrayImage, factor: f32) -> GrayImage {
     )
 }
 
+/// Otsu threshold separating the two brightness classes of an image, or
+/// `None` when the image has a single brightness
+fn otsu_threshold(img: &GrayImage) -> Option<u8> {
+    let mut histogram = [0u64; 256];
+    for pixel in img.pixels() {
+        histogram[pixel[0] as usize] += 1;
+    }
+
+    let total = img.pixels().len() as f64;
+    let total_sum: f64 = histogram.iter().enumerate().map(|(v, &n)| v as f64 * n as f64).sum();
+    let (mut below, mut below_sum) = (0.0, 0.0);
+    let mut best: Option<(u8, f64)> = None;
+
+    for (value, &count) in histogram.iter().enumerate().take(255) {
+        below += count as f64;
+        below_sum += value as f64 * count as f64;
+        let above = total - below;
+        if below == 0.0 || above == 0.0 {
+            continue;
+        }
+        let mean_below = below_sum / below;
+        let mean_above = (total_sum - below_sum) / above;
+        let variance = below * above * (mean_below - mean_above).powi(2);
+        match best {
+            Some((_, best_variance)) if best_variance >= variance => {}
+            _ => best = Some((value as u8, variance)),
+        }
+    }
+
+    best.map(|(value, _)| value)
+}
+
+/// Measure the height in pixels of the tallest line of text in a capture
+///
+/// The image is split into text and background at its Otsu threshold, text
+/// being the smaller class. Rows holding text pixels are text rows; the
+/// longest run of them is the line height. Rows that are almost entirely
+/// text (banner borders, solid bars) are not glyphs and end a run. Returns
+/// `None` when no line of text is found.
+pub fn measure_text_height(img: &GrayImage) -> Option<u32> {
+    let (width, height) = img.dimensions();
+    let threshold = otsu_threshold(img)?;
+
+    let bright = img.pixels().filter(|p| p[0] > threshold).count();
+    let text_is_bright = bright * 2 < img.pixels().len();
+    let is_text = |value: u8| (value > threshold) == text_is_bright;
+
+    let min_text_pixels = (width / 50).max(1);
+    let max_text_pixels = width * 9 / 10;
+
+    let (mut longest, mut run) = (0, 0);
+    for y in 0..height {
+        let text_pixels = (0..width).filter(|&x| is_text(img.get_pixel(x, y)[0])).count() as u32;
+        if text_pixels >= min_text_pixels && text_pixels <= max_text_pixels {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+
+    (longest >= MIN_MEASURED_TEXT_HEIGHT).then_some(longest)
+}
+
+/// Scale a capture so its text line is `target_height` pixels tall
+///
+/// The factor comes from `measure_text_height` and is clamped to
+/// `MIN_DOWNSCALE_FACTOR..=MAX_UPSCALE_FACTOR`. Captures without
+/// measurable text, or already within 5% of the target, are unchanged.
+pub fn scale_to_text_height(img: &GrayImage, target_height: u32) -> GrayImage {
+    let Some(measured) = measure_text_height(img) else {
+        return img.clone();
+    };
+
+    let factor = (target_height as f32 / measured as f32)
+        .clamp(MIN_DOWNSCALE_FACTOR, MAX_UPSCALE_FACTOR);
+    if (factor - 1.0).abs() < 0.05 {
+        return img.clone();
+    }
+
+    let (width, height) = img.dimensions();
+    image::imageops::resize(
+        img,
+        ((width as f32 * factor).round() as u32).max(1),
+        ((height as f32 * factor).round() as u32).max(1),
+        image::imageops::FilterType::Lanczos3,
+    )
+}
+
 /// Main preprocessing pipeline for OCR
 /// 
 /// # Arguments
@@ -228,36 +422,29 @@ pub fn preprocess_for_ocr(
         return Err(PreprocessError::EmptyImage);
     }
 
-    // Step 1: Convert to grayscale
-    let mut processed = to_grayscale(img);
+    validate_steps(&config.steps)?;
 
-    // Step 2: Enhance contrast
-    if config.contrast_factor != 1.0 {
-        processed = enhance_contrast(&processed, config.contrast_factor);
-    }
-
-    // Step 3: Upscale for better OCR accuracy
-    if config.scale_factor > 1.0 {
-        processed = upscale(&processed, config.scale_factor);
-    }
-
-    // Step 4: Denoise
-    if config.denoise {
-        // Apply mild Gaussian blur followed by median filter
-        processed = apply_gaussian_blur(&processed, 0.5);
-        processed = apply_median_filter(&processed, 3);
-    }
-
-    // Step 5: Apply thresholding
-    if config.use_adaptive_threshold {
-        processed = apply_adaptive_threshold(&processed, config.adaptive_block_size, config.adaptive_c);
-    } else {
-        processed = apply_threshold(&processed, config.threshold);
-    }
+    // The first step is always grayscale conversion
+    let mut processed = to_grayscale(img);
 
-    // Step 6: Invert if needed (for white text on dark background)
-    if config.invert {
-        processed = invert(&processed);
+    for step in &config.steps[1..] {
+        processed = match *step {
+            PreprocessStep::Grayscale => processed,
+            PreprocessStep::Contrast { factor } => enhance_contrast(&processed, factor),
+            PreprocessStep::Upscale { factor } => upscale(&processed, factor),
+            PreprocessStep::ScaleToTextHeight { target_height } => {
+                scale_to_text_height(&processed, target_height)
+            }
+            PreprocessStep::Blur { sigma } => apply_gaussian_blur(&processed, sigma),
+            PreprocessStep::Median { kernel_size } => apply_median_filter(&processed, kernel_size),
+            PreprocessStep::Threshold { value } => apply_threshold(&processed, value),
+            PreprocessStep::AdaptiveThreshold { block_size, c } => {
+                apply_adaptive_threshold(&processed, block_size, c)
+            }
+            PreprocessStep::Invert => invert(&processed),
+            PreprocessStep::Dilate { radius } => apply_dilate(&processed, radius),
+            PreprocessStep::Erode { radius } => apply_erode(&processed, radius),
+        };
     }
 
     Ok(processed)
@@ -362,6 +549,46 @@ mod tests {
         assert_eq!(same.dimensions(), (10, 10));
     }
 
+    /// White capture with a `text_height` line of dark vertical strokes
+    fn text_image(width: u32, height: u32, text_height: u32) -> GrayImage {
+        let top = (height - text_height) / 2;
+        GrayImage::from_fn(width, height, |x, y| {
+            let in_line = y >= top && y < top + text_height;
+            Luma([if in_line && x % 6 < 2 { 20 } else { 230 }])
+        })
+    }
+
+    #[test]
+    fn test_measure_text_height() {
+        assert_eq!(measure_text_height(&text_image(200, 60, 20)), Some(20));
+
+        // A solid border is not part of the text line
+        let mut bordered = text_image(200, 60, 20);
+        for x in 0..200 {
+            bordered.put_pixel(x, 19, Luma([20]));
+        }
+        assert_eq!(measure_text_height(&bordered), Some(20));
+
+        assert_eq!(measure_text_height(&GrayImage::from_pixel(50, 50, Luma([128]))), None);
+        assert_eq!(measure_text_height(&text_image(200, 60, 2)), None);
+    }
+
+    #[test]
+    fn test_scale_to_text_height() {
+        // Small text on a 1080p capture is scaled up
+        let scaled = scale_to_text_height(&text_image(200, 40, 20), 40);
+        assert_eq!(scaled.dimensions(), (400, 80));
+
+        // Large text on a 4K capture is scaled down rather than up
+        let scaled = scale_to_text_height(&text_image(800, 160, 80), 40);
+        assert_eq!(scaled.dimensions(), (400, 80));
+
+        // Already at the target, or no text: unchanged
+        assert_eq!(scale_to_text_height(&text_image(200, 60, 40), 40).dimensions(), (200, 60));
+        let blank = GrayImage::from_pixel(30, 10, Luma([0]));
+        assert_eq!(scale_to_text_height(&blank, 40).dimensions(), (30, 10));
+    }
+
     #[test]
     fn test_preprocess_default_empty_image() {
         let img = create_test_image(0, 0, 128);
@@ -378,18 +605,56 @@ mod tests {
         assert!(result.is_ok());
         
         let processed = result.unwrap();
-        // Should be upscaled by 2x
-        assert_eq!(processed.dimensions(), (200, 100));
+        // A blank capture has no text to scale to
+        assert_eq!(processed.dimensions(), (100, 50));
     }
 
     #[test]
     fn test_preprocess_config_default() {
         let config = PreprocessConfig::default();
-        assert_eq!(config.threshold, 127);
-        assert!(config.use_adaptive_threshold);
-        assert_eq!(config.adaptive_block_size, 11);
-        assert!(config.denoise);
-        assert!(config.scale_factor > 1.0);
+        assert_eq!(config.steps[0], PreprocessStep::Grayscale);
+        assert!(config.steps.contains(&PreprocessStep::AdaptiveThreshold { block_size: 11, c: 2 }));
+        assert!(config.steps.contains(&PreprocessStep::Median { kernel_size: 3 }));
+        assert!(config.steps.contains(&PreprocessStep::ScaleToTextHeight { target_height: 40 }));
+    }
+
+    #[test]
+    fn test_preprocess_custom_step_order() {
+        let img = create_test_image(10, 5, 200);
+        let config = PreprocessConfig {
+            steps: vec![
+                PreprocessStep::Grayscale,
+                PreprocessStep::Threshold { value: 127 },
+                PreprocessStep::Invert,
+                PreprocessStep::Upscale { factor: 3.0 },
+            ],
+        };
+
+        let processed = preprocess_for_ocr(&img, &config).unwrap();
+        assert_eq!(processed.dimensions(), (30, 15));
+        assert_eq!(processed.get_pixel(0, 0)[0], 0);
+
+        let invalid = PreprocessConfig {
+            steps: vec![PreprocessStep::Invert],
+        };
+        assert!(matches!(
+            preprocess_for_ocr(&img, &invalid),
+            Err(PreprocessError::InvalidPipeline(_))
+        ));
+    }
+
+    #[test]
+    fn test_dilate_and_erode() {
+        let mut img = GrayImage::from_pixel(5, 5, Luma([0]));
+        img.put_pixel(2, 2, Luma([255]));
+
+        let dilated = apply_dilate(&img, 1);
+        assert_eq!(dilated.get_pixel(1, 1)[0], 255);
+        assert_eq!(dilated.get_pixel(0, 0)[0], 0);
+
+        let eroded = apply_erode(&dilated, 1);
+        assert_eq!(eroded.get_pixel(2, 2)[0], 255);
+        assert_eq!(eroded.get_pixel(1, 1)[0], 0);
     }
 
     #[test]
@@ -410,6 +675,68 @@ mod tests {
         assert!(filtered.get_pixel(2, 2)[0] < 255);
     }
 
+    /// Deterministic noisy image for comparing against the reference filters
+    fn noisy_image(width: u32, height: u32) -> GrayImage {
+        let mut seed = 0x2545_f491u32;
+        GrayImage::from_fn(width, height, |_, _| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            Luma([(seed % 256) as u8])
+        })
+    }
+
+    /// Pixels of the `kernel_size` neighborhood of (x, y), clipped to the image
+    fn neighborhood(img: &GrayImage, x: u32, y: u32, kernel_size: u32) -> Vec<u8> {
+        let half = (kernel_size / 2) as i32;
+        let (width, height) = img.dimensions();
+        let mut values = Vec::new();
+        for ny in y as i32 - half..=y as i32 + half {
+            for nx in x as i32 - half..=x as i32 + half {
+                if nx >= 0 && nx < width as i32 && ny >= 0 && ny < height as i32 {
+                    values.push(img.get_pixel(nx as u32, ny as u32)[0]);
+                }
+            }
+        }
+        values
+    }
+
+    #[test]
+    fn test_adaptive_threshold_matches_reference() {
+        let img = noisy_image(23, 17);
+        for (block_size, c) in [(3, 0), (11, 2), (31, -5)] {
+            let result = apply_adaptive_threshold(&img, block_size, c);
+            for (x, y, pixel) in img.enumerate_pixels() {
+                let values = neighborhood(&img, x, y, block_size);
+                let mean = values.iter().map(|&v| v as u32).sum::<u32>() / values.len() as u32;
+                let expected = if pixel[0] as i32 > mean as i32 - c { 255 } else { 0 };
+                assert_eq!(result.get_pixel(x, y)[0], expected, "({}, {}) block {}", x, y, block_size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_median_filter_matches_reference() {
+        for (width, height) in [(23, 17), (1, 6), (4, 1)] {
+            let img = noisy_image(width, height);
+            for kernel_size in [3, 5, 9] {
+                let result = apply_median_filter(&img, kernel_size);
+                for (x, y, _) in img.enumerate_pixels() {
+                    let mut values = neighborhood(&img, x, y, kernel_size);
+                    values.sort_unstable();
+                    assert_eq!(
+                        result.get_pixel(x, y)[0],
+                        values[values.len() / 2],
+                        "({}, {}) kernel {}",
+                        x,
+                        y,
+                        kernel_size
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_error_display() {
         assert!(PreprocessError::EmptyImage.to_string().contains("empty"));
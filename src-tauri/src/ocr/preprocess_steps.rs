@@ -0,0 +1,162 @@
+//! Preprocessing pipeline description
+//!
+//! `PreprocessConfig::steps` lists the operations applied to each capture,
+//! in order, so the pipeline can be tuned for a given capture quality. This
+//! module is data-only, compiled with or without the `ocr` feature, and
+//! re-exported from `ocr::preprocess`.
+
+use super::preprocess::{PreprocessError, PreprocessResult};
+use serde::{Deserialize, Serialize};
+
+/// Longest allowed pipeline
+pub const MAX_STEPS: usize = 16;
+
+/// Largest allowed upscale factor; captures grow quadratically
+pub const MAX_UPSCALE_FACTOR: f32 = 4.0;
+
+/// Text line height Tesseract reads best at, in pixels
+pub const DEFAULT_TEXT_HEIGHT: u32 = 40;
+
+/// Allowed range for a target text height
+pub const TEXT_HEIGHT_RANGE: std::ops::RangeInclusive<u32> = 16..=120;
+
+/// Largest allowed blur sigma
+pub const MAX_BLUR_SIGMA: f32 = 5.0;
+
+/// Largest allowed median or adaptive threshold window
+pub const MAX_KERNEL_SIZE: u32 = 31;
+
+/// Largest allowed dilate/erode radius
+pub const MAX_MORPHOLOGY_RADIUS: u32 = 5;
+
+/// A single preprocessing operation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PreprocessStep {
+    /// Convert the RGBA capture to grayscale; must be the first step
+    Grayscale,
+    /// Stretch contrast around mid-gray (1.0 = unchanged)
+    Contrast { factor: f32 },
+    /// Scale up with Lanczos filtering (1.0 = unchanged)
+    Upscale { factor: f32 },
+    /// Scale up or down so the measured text line is `target_height`
+    /// pixels tall, whatever the capture resolution
+    ScaleToTextHeight { target_height: u32 },
+    /// Gaussian blur
+    Blur { sigma: f32 },
+    /// Median filter with an odd square window
+    Median { kernel_size: u32 },
+    /// Fixed binary threshold
+    Threshold { value: u8 },
+    /// Mean adaptive threshold with an odd square window
+    AdaptiveThreshold { block_size: u32, c: i32 },
+    /// Invert colors (for white text on a dark background)
+    Invert,
+    /// Grow bright regions by taking the maximum of each square neighborhood
+    Dilate { radius: u32 },
+    /// Shrink bright regions by taking the minimum of each square neighborhood
+    Erode { radius: u32 },
+}
+
+/// The pipeline used when none is configured
+///
+/// Captures are scaled to a fixed text height rather than a fixed factor,
+/// so 4K captures are scaled down instead of quadrupled.
+pub fn default_steps() -> Vec<PreprocessStep> {
+    vec![
+        PreprocessStep::Grayscale,
+        PreprocessStep::Contrast { factor: 1.5 },
+        PreprocessStep::ScaleToTextHeight {
+            target_height: DEFAULT_TEXT_HEIGHT,
+        },
+        PreprocessStep::Blur { sigma: 0.5 },
+        PreprocessStep::Median { kernel_size: 3 },
+        PreprocessStep::AdaptiveThreshold { block_size: 11, c: 2 },
+    ]
+}
+
+/// Check that a pipeline can be run
+pub fn validate_steps(steps: &[PreprocessStep]) -> PreprocessResult<()> {
+    let invalid = |msg: String| Err(PreprocessError::InvalidPipeline(msg));
+
+    if steps.first() != Some(&PreprocessStep::Grayscale) {
+        return invalid("pipeline must start with grayscale".to_string());
+    }
+    if steps.len() > MAX_STEPS {
+        return invalid(format!("pipeline has more than {} steps", MAX_STEPS));
+    }
+
+    let odd_window = |size: u32| size >= 3 && size % 2 == 1 && size <= MAX_KERNEL_SIZE;
+
+    for (i, step) in steps.iter().enumerate().skip(1) {
+        let valid = match *step {
+            PreprocessStep::Grayscale => false,
+            PreprocessStep::Contrast { factor } => factor.is_finite() && factor > 0.0,
+            PreprocessStep::Upscale { factor } => (1.0..=MAX_UPSCALE_FACTOR).contains(&factor),
+            PreprocessStep::ScaleToTextHeight { target_height } => {
+                TEXT_HEIGHT_RANGE.contains(&target_height)
+            }
+            PreprocessStep::Blur { sigma } => sigma > 0.0 && sigma <= MAX_BLUR_SIGMA,
+            PreprocessStep::Median { kernel_size } => odd_window(kernel_size),
+            PreprocessStep::AdaptiveThreshold { block_size, .. } => odd_window(block_size),
+            PreprocessStep::Threshold { .. } | PreprocessStep::Invert => true,
+            PreprocessStep::Dilate { radius } | PreprocessStep::Erode { radius } => {
+                (1..=MAX_MORPHOLOGY_RADIUS).contains(&radius)
+            }
+        };
+
+        if !valid {
+            return invalid(format!("step {} has invalid parameters: {:?}", i + 1, step));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_steps_are_valid() {
+        assert!(validate_steps(&default_steps()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_steps_rejects_bad_pipelines() {
+        assert!(validate_steps(&[]).is_err());
+        assert!(validate_steps(&[PreprocessStep::Invert]).is_err());
+        assert!(validate_steps(&[PreprocessStep::Grayscale, PreprocessStep::Grayscale]).is_err());
+        assert!(validate_steps(&[
+            PreprocessStep::Grayscale,
+            PreprocessStep::Median { kernel_size: 4 },
+        ])
+        .is_err());
+        assert!(validate_steps(&[
+            PreprocessStep::Grayscale,
+            PreprocessStep::Upscale { factor: 8.0 },
+        ])
+        .is_err());
+        assert!(validate_steps(&[
+            PreprocessStep::Grayscale,
+            PreprocessStep::ScaleToTextHeight { target_height: 4 },
+        ])
+        .is_err());
+        assert!(validate_steps(&[
+            PreprocessStep::Grayscale,
+            PreprocessStep::Threshold { value: 100 },
+            PreprocessStep::Erode { radius: 1 },
+        ])
+        .is_ok());
+    }
+
+    #[test]
+    fn test_step_serialization() {
+        let json = serde_json::to_string(&PreprocessStep::Dilate { radius: 2 }).unwrap();
+        assert_eq!(json, r#"{"op":"dilate","radius":2}"#);
+
+        let step: PreprocessStep =
+            serde_json::from_str(r#"{"op":"adaptive_threshold","block_size":15,"c":4}"#).unwrap();
+        assert_eq!(step, PreprocessStep::AdaptiveThreshold { block_size: 15, c: 4 });
+    }
+}
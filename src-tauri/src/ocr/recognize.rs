@@ -1,17 +1,31 @@
 //! OCR recognition and card name matching
 //!
 //! This module provides Tesseract OCR integration and fuzzy matching
-//! to identify card names from preprocessed images.
+//! to identify card names from preprocessed images. Without Tesseract,
+//! the template glyph engine (see `glyph`) can read names instead.
 
-use fuzzy_matcher::skim::SkimMatcherV2;
-use fuzzy_matcher::FuzzyMatcher;
 use image::GrayImage;
+
+use super::banner::BannerColor;
+use super::confidence::ConfidenceWeights;
+use super::glyph::GlyphAtlas;
+pub use super::glyph::RecognitionEngine;
+pub use super::matcher::{CardMatcher, MatchCandidate};
+use super::matcher::{DEFAULT_AMBIGUITY_MARGIN, DEFAULT_MAX_CANDIDATES};
+use super::matcher::{CardPoolEntry, CardText};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[cfg(feature = "ocr")]
-use leptess::tesseract::TessInitError;
+use leptess::capi;
 #[cfg(feature = "ocr")]
-use leptess::LepTess;
+use leptess::tesseract::TessInitError;
+
+/// Penalty Tesseract gives words outside its dictionaries when a card
+/// vocabulary is loaded (Tesseract's default is 0.15)
+pub const NON_DICT_WORD_PENALTY: f32 = 0.5;
+/// Penalty for dictionary words that aren't frequent words (default 0.1)
+pub const NON_FREQ_DICT_WORD_PENALTY: f32 = 0.2;
 
 /// Error types for OCR recognition
 #[derive(Debug)]
@@ -21,6 +35,7 @@ pub enum RecognizeError {
     NoCardNamesAvailable,
     InvalidImage,
     MatchingFailed(String),
+    GlyphAtlasUnavailable(String),
 }
 
 impl std::fmt::Display for RecognizeError {
@@ -33,6 +48,9 @@ impl std::fmt::Display for RecognizeError {
             RecognizeError::NoCardNamesAvailable => write!(f, "No card names available for matching"),
             RecognizeError::InvalidImage => write!(f, "Invalid image for OCR"),
             RecognizeError::MatchingFailed(msg) => write!(f, "Card matching failed: {}", msg),
+            RecognizeError::GlyphAtlasUnavailable(msg) => {
+                write!(f, "Glyph atlas unavailable: {}", msg)
+            }
         }
     }
 }
@@ -71,6 +89,19 @@ pub struct RecognizeConfig {
     pub min_match_score: i32,
     /// Whitelist of characters (None for all)
     pub whitelist: Option<String>,
+    /// Score gap below which the runner-up makes a match ambiguous
+    pub ambiguity_margin: i32,
+    /// Number of candidates reported for ambiguous matches
+    pub max_candidates: usize,
+    /// Blend of OCR confidence and match score in the overall confidence
+    pub confidence_weights: ConfidenceWeights,
+    /// Engine that reads the text
+    pub engine: RecognitionEngine,
+    /// Atlas used by the template glyph engine
+    pub glyph_atlas_path: Option<String>,
+    /// Tesseract user-words file listing the card name vocabulary; when set,
+    /// decoding is weighted toward those words
+    pub user_words_path: Option<String>,
 }
 
 impl Default for RecognizeConfig {
@@ -83,6 +114,12 @@ impl Default for RecognizeConfig {
             min_confidence: 60,
             min_match_score: 60,
             whitelist: Some("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789 '-".to_string()),
+            ambiguity_margin: DEFAULT_AMBIGUITY_MARGIN,
+            max_candidates: DEFAULT_MAX_CANDIDATES,
+            confidence_weights: ConfidenceWeights::default(),
+            engine: RecognitionEngine::default(),
+            glyph_atlas_path: None,
+            user_words_path: None,
         }
     }
 }
@@ -103,6 +140,40 @@ impl RecognizeConfig {
             ..self
         }
     }
+
+    /// Config for a card's rules text: a block of lines in any characters,
+    /// read without the card name vocabulary
+    pub fn for_rules_text(&self) -> Self {
+        Self {
+            psm: 6,
+            whitelist: None,
+            user_words_path: None,
+            ..self.clone()
+        }
+    }
+
+    /// Tesseract variables to set while it initializes
+    ///
+    /// The user-words file is only read while Tesseract loads its
+    /// dictionaries, so it can't be set on a running instance.
+    pub fn tesseract_variables(&self) -> Vec<(&'static str, String)> {
+        let mut variables = vec![("tessedit_pageseg_mode", self.psm.to_string())];
+        if let Some(ref whitelist) = self.whitelist {
+            variables.push(("tessedit_char_whitelist", whitelist.clone()));
+        }
+        if let Some(ref words) = self.user_words_path {
+            variables.push(("user_words_file", words.clone()));
+            variables.push((
+                "language_model_penalty_non_dict_word",
+                NON_DICT_WORD_PENALTY.to_string(),
+            ));
+            variables.push((
+                "language_model_penalty_non_freq_dict_word",
+                NON_FREQ_DICT_WORD_PENALTY.to_string(),
+            ));
+        }
+        variables
+    }
 }
 
 /// Result of OCR text recognition
@@ -148,19 +219,30 @@ pub struct CardMatch {
     pub ocr_confidence: i32,
     /// Overall confidence (weighted combination)
     pub overall_confidence: f64,
+    /// Top candidates (including this match) when the match is ambiguous
+    pub candidates: Vec<MatchCandidate>,
+    /// Frame and banner colors sampled from the capture, if analyzed
+    pub banner: Option<BannerColor>,
 }
 
 impl CardMatch {
     /// Calculate overall confidence from OCR confidence and match score
+    /// with the default (uncalibrated) weights
     pub fn calculate_overall_confidence(ocr_confidence: i32, match_score: i32) -> f64 {
-        // Weight: 40% OCR confidence, 60% match score
-        (ocr_confidence as f64 * 0.4 + match_score as f64 * 0.6) / 100.0
+        ConfidenceWeights::default().combine(ocr_confidence, match_score)
+    }
+
+    /// Whether other cards scored close enough that the user should confirm
+    pub fn is_ambiguous(&self) -> bool {
+        !self.candidates.is_empty()
     }
 }
 
-/// OCR engine wrapper for Tesseract
+/// OCR engine wrapper for Tesseract, or the template glyph fallback
 pub struct OcrEngine {
     config: RecognizeConfig,
+    /// Loaded when `config.engine` is `TemplateGlyph`
+    atlas: Option<GlyphAtlas>,
 }
 
 impl OcrEngine {
@@ -168,38 +250,39 @@ impl OcrEngine {
     pub fn new() -> RecognizeResult<Self> {
         Ok(Self {
             config: RecognizeConfig::default(),
+            atlas: None,
         })
     }
 
     /// Create a new OCR engine with custom configuration
+    ///
+    /// The template glyph engine loads its atlas here, and fails if there
+    /// is none.
     pub fn with_config(config: RecognizeConfig) -> RecognizeResult<Self> {
-        Ok(Self { config })
+        let atlas = match config.engine {
+            RecognitionEngine::Tesseract => None,
+            RecognitionEngine::TemplateGlyph => Some(load_glyph_atlas(&config)?),
+        };
+        Ok(Self { config, atlas })
     }
 
     /// Initialize Tesseract with the configured settings
     #[cfg(feature = "ocr")]
-    fn init_tesseract(&self) -> RecognizeResult<LepTess> {
-        let mut tess = if let Some(ref data_path) = self.config.tesseract_data_path {
-            LepTess::new(Some(data_path), &self.config.language)?
-        } else {
-            LepTess::new(None, &self.config.language)?
-        };
-
-        // Set page segmentation mode
-        tess.set_variable("tessedit_pageseg_mode", &self.config.psm.to_string())
-            .map_err(|e| RecognizeError::TesseractError(format!("Failed to set PSM: {:?}", e)))?;
-
-        // Set OCR engine mode
-        tess.set_variable("oem_mode", &self.config.oem.to_string())
-            .map_err(|e| RecognizeError::TesseractError(format!("Failed to set OEM: {:?}", e)))?;
-
-        // Set character whitelist if specified
-        if let Some(ref whitelist) = self.config.whitelist {
-            tess.set_variable("tessedit_char_whitelist", whitelist)
-                .map_err(|e| RecognizeError::TesseractError(format!("Failed to set whitelist: {:?}", e)))?;
-        }
-
-        Ok(tess)
+    fn init_tesseract(&self) -> RecognizeResult<Tesseract> {
+        Tesseract::new(
+            self.config.tesseract_data_path.as_deref(),
+            &self.config.language,
+            self.config.oem,
+            &self.config.tesseract_variables(),
+        )
+        .map_err(|e| {
+            RecognizeError::TesseractInitFailed(format!(
+                "{} (is '{}.traineddata' installed in {}? Download it with download_tesseract_language)",
+                e,
+                self.config.language,
+                self.config.tesseract_data_path.as_deref().unwrap_or("the system tessdata folder"),
+            ))
+        })
     }
 
     /// Mock Tesseract initialization when OCR feature is not enabled
@@ -216,6 +299,13 @@ impl OcrEngine {
             return Err(RecognizeError::InvalidImage);
         }
 
+        if let Some(ref atlas) = self.atlas {
+            let (text, confidence) = atlas
+                .recognize(img)
+                .map_or((String::new(), 0), |reading| (reading.text, reading.confidence));
+            return Ok(OcrResult::new(text, confidence, self.config.min_confidence));
+        }
+
         let mut tess = self.init_tesseract()?;
 
         // Convert image to bytes for Tesseract
@@ -232,8 +322,7 @@ impl OcrEngine {
             height,
             bytes_per_pixel,
             bytes_per_line,
-        )
-        .map_err(|e| RecognizeError::TesseractError(format!("Failed to set image: {:?}", e)))?;
+        );
 
         // Get recognized text
         let text = tess.get_utf8_text()
@@ -249,6 +338,19 @@ impl OcrEngine {
         ))
     }
 
+    /// Check that Tesseract can start with the configured language data,
+    /// or that the glyph atlas has templates
+    #[cfg(feature = "ocr")]
+    pub fn check_available(&self) -> RecognizeResult<()> {
+        match self.atlas {
+            Some(ref atlas) if atlas.glyphs.is_empty() => Err(
+                RecognizeError::GlyphAtlasUnavailable("the atlas has no glyphs".to_string()),
+            ),
+            Some(_) => Ok(()),
+            None => self.init_tesseract().map(|_| ()),
+        }
+    }
+
     /// Mock recognition when OCR feature is not enabled
     #[cfg(not(feature = "ocr"))]
     pub fn recognize(&self, _img: &GrayImage) -> RecognizeResult<OcrResult> {
@@ -272,141 +374,10 @@ impl Default for OcrEngine {
     }
 }
 
-/// Card name matcher using fuzzy string matching
-pub struct CardMatcher {
-    card_names: Vec<(String, String)>, // (card_id, card_name)
-    matcher: SkimMatcherV2,
-    min_score: i32,
-}
-
-impl CardMatcher {
-    /// Create a new card matcher with the given card names
-    pub fn new(card_names: Vec<(String, String)>, min_score: i32) -> RecognizeResult<Self> {
-        if card_names.is_empty() {
-            return Err(RecognizeError::NoCardNamesAvailable);
-        }
-
-        Ok(Self {
-            card_names,
-            matcher: SkimMatcherV2::default(),
-            min_score,
-        })
-    }
-
-    /// Find the best matching card for the given OCR text
-    pub fn find_best_match(&self, ocr_text: &str) -> Option<CardMatch> {
-        let ocr_normalized = ocr_text.to_lowercase().trim().to_string();
-        
-        if ocr_normalized.is_empty() {
-            return None;
-        }
-
-        let mut best_match: Option<CardMatch> = None;
-        let mut best_score = self.min_score as i64;
-
-        for (card_id, card_name) in &self.card_names {
-            // Try fuzzy matching
-            if let Some(score) = self.matcher.fuzzy_match(&card_name.to_lowercase(), &ocr_normalized) {
-                if score > best_score {
-                    best_score = score;
-                    best_match = Some(CardMatch {
-                        card_name: card_name.clone(),
-                        card_id: card_id.clone(),
-                        ocr_text: ocr_text.to_string(),
-                        match_score: score.min(100) as i32,
-                        ocr_confidence: 0, // Will be set by caller
-                        overall_confidence: 0.0,
-                    });
-                }
-            }
-
-            // Also try matching individual words for short OCR text
-            if ocr_normalized.len() < 10 {
-                let card_name_lower = card_name.to_lowercase();
-                let card_words: Vec<&str> = card_name_lower.split_whitespace().collect();
-                for word in &card_words {
-                    if let Some(word_score) = self.matcher.fuzzy_match(word, &ocr_normalized) {
-                        if word_score > best_score {
-                            best_score = word_score;
-                            best_match = Some(CardMatch {
-                                card_name: card_name.clone(),
-                                card_id: card_id.clone(),
-                                ocr_text: ocr_text.to_string(),
-                                match_score: word_score.min(100) as i32,
-                                ocr_confidence: 0,
-                                overall_confidence: 0.0,
-                            });
-                        }
-                    }
-                }
-            }
-        }
-
-        best_match
-    }
-
-    /// Match multiple OCR results and update their confidence scores
-    pub fn match_results(&self, ocr_results: Vec<OcrResult>) -> Vec<CardMatch> {
-        let mut matches = Vec::new();
-
-        for result in ocr_results {
-            if let Some(mut card_match) = self.find_best_match(&result.text) {
-                card_match.ocr_confidence = result.confidence;
-                card_match.overall_confidence = CardMatch::calculate_overall_confidence(
-                    result.confidence,
-                    card_match.match_score,
-                );
-                matches.push(card_match);
-            }
-        }
-
-        // Sort by overall confidence (highest first)
-        matches.sort_by(|a, b| {
-            b.overall_confidence
-                .partial_cmp(&a.overall_confidence)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        matches
-    }
-
-    /// Find all cards that match above the threshold (for ambiguous matches)
-    pub fn find_all_matches(&self, ocr_text: &str, threshold: i32) -> Vec<CardMatch> {
-        let ocr_normalized = ocr_text.to_lowercase().trim().to_string();
-        let mut matches = Vec::new();
-
-        if ocr_normalized.is_empty() {
-            return matches;
-        }
-
-        for (card_id, card_name) in &self.card_names {
-            if let Some(score) = self.matcher.fuzzy_match(&card_name.to_lowercase(), &ocr_normalized) {
-                if score >= threshold as i64 {
-                    matches.push(CardMatch {
-                        card_name: card_name.clone(),
-                        card_id: card_id.clone(),
-                        ocr_text: ocr_text.to_string(),
-                        match_score: score.min(100) as i32,
-                        ocr_confidence: 0,
-                        overall_confidence: score as f64 / 100.0,
-                    });
-                }
-            }
-        }
-
-        // Sort by match score (highest first)
-        matches.sort_by(|a, b| {
-            b.match_score
-                .cmp(&a.match_score)
-        });
-
-        matches
-    }
-}
-
 /// Complete recognition pipeline combining OCR and card matching
 pub struct RecognitionPipeline {
-    ocr_engine: OcrEngine,
+    /// Shared so OCR can run on a worker thread under a timeout
+    ocr_engine: Arc<OcrEngine>,
     card_matcher: CardMatcher,
 }
 
@@ -415,10 +386,12 @@ impl RecognitionPipeline {
     pub fn new(card_names: Vec<(String, String)>) -> RecognizeResult<Self> {
         let ocr_engine = OcrEngine::new()?;
         let config = RecognizeConfig::default();
-        let card_matcher = CardMatcher::new(card_names, config.min_match_score)?;
+        let card_matcher = CardMatcher::new(card_names, config.min_match_score)?
+            .with_ambiguity(config.ambiguity_margin, config.max_candidates)
+            .with_confidence_weights(config.confidence_weights);
 
         Ok(Self {
-            ocr_engine,
+            ocr_engine: Arc::new(ocr_engine),
             card_matcher,
         })
     }
@@ -429,23 +402,82 @@ impl RecognitionPipeline {
         config: RecognizeConfig,
     ) -> RecognizeResult<Self> {
         let ocr_engine = OcrEngine::with_config(config.clone())?;
-        let card_matcher = CardMatcher::new(card_names, config.min_match_score)?;
+        let card_matcher = CardMatcher::new(card_names, config.min_match_score)?
+            .with_ambiguity(config.ambiguity_margin, config.max_candidates)
+            .with_confidence_weights(config.confidence_weights);
 
         Ok(Self {
-            ocr_engine,
+            ocr_engine: Arc::new(ocr_engine),
             card_matcher,
         })
     }
 
-    /// Process a single image through the full pipeline
-    pub fn process(&self, img: &GrayImage) -> RecognizeResult<Option<CardMatch>> {
-        let ocr_result = self.ocr_engine.recognize(img)?;
+    /// Apply alternate card names to the card matcher
+    pub fn with_alternate_names(mut self, alternate_names: Vec<(String, String)>) -> Self {
+        self.card_matcher = self.card_matcher.with_alternate_names(alternate_names);
+        self
+    }
+
+    /// Apply learned aliases to the card matcher
+    pub fn with_aliases(mut self, aliases: HashMap<String, String>) -> Self {
+        self.card_matcher = self.card_matcher.with_aliases(aliases);
+        self
+    }
+
+    /// Apply card clans and rarities for banner color tie-breaking
+    pub fn with_card_attributes(mut self, pool: &[CardPoolEntry]) -> Self {
+        self.card_matcher = self.card_matcher.with_card_attributes(pool);
+        self
+    }
+
+    /// Apply card keywords and description openings to the card matcher
+    pub fn with_card_text(mut self, card_text: Vec<CardText>) -> Self {
+        self.card_matcher = self.card_matcher.with_card_text(card_text);
+        self
+    }
+
+    /// The OCR engine, for running recognition on another thread
+    pub fn ocr_engine(&self) -> Arc<OcrEngine> {
+        Arc::clone(&self.ocr_engine)
+    }
 
+    /// Match an OCR result to a card, if it is confident enough to try
+    pub fn match_ocr_result(
+        &self,
+        ocr_result: &OcrResult,
+        banner: Option<&BannerColor>,
+    ) -> Option<CardMatch> {
         if !ocr_result.is_confident {
-            return Ok(None);
+            return None;
         }
+        let _span = tracing::info_span!("ocr.match").entered();
+        self.card_matcher
+            .find_best_match_with_banner(&ocr_result.text, banner)
+            .map(|mut card_match| {
+                self.card_matcher.apply_confidence(&mut card_match, ocr_result.confidence);
+                card_match
+            })
+    }
 
-        Ok(self.card_matcher.find_best_match(&ocr_result.text))
+    /// Process a single image through the full pipeline
+    pub fn process(&self, img: &GrayImage) -> RecognizeResult<Option<CardMatch>> {
+        self.process_detailed(img, None).map(|(_, card_match)| card_match)
+    }
+
+    /// Process a single image, also returning the raw OCR result
+    ///
+    /// Matching is only attempted when the OCR result is confident. The
+    /// `banner` sampled from the original RGBA crop breaks ties between
+    /// similarly named cards.
+    pub fn process_detailed(
+        &self,
+        img: &GrayImage,
+        banner: Option<&BannerColor>,
+    ) -> RecognizeResult<(OcrResult, Option<CardMatch>)> {
+        let ocr_result =
+            tracing::info_span!("ocr.recognize").in_scope(|| self.ocr_engine.recognize(img))?;
+        let card_match = self.match_ocr_result(&ocr_result, banner);
+        Ok((ocr_result, card_match))
     }
 
     /// Process multiple images through the full pipeline
@@ -462,6 +494,114 @@ impl RecognitionPipeline {
     }
 }
 
+/// A Tesseract instance, initialized through the C API
+///
+/// `LepTess::new` can't pass variables to initialization, which the
+/// user-words file needs.
+#[cfg(feature = "ocr")]
+struct Tesseract(*mut capi::TessBaseAPI);
+
+#[cfg(feature = "ocr")]
+impl Tesseract {
+    /// Initialize Tesseract for `language` with `variables` set
+    fn new(
+        data_path: Option<&str>,
+        language: &str,
+        oem: i32,
+        variables: &[(&str, String)],
+    ) -> Result<Self, String> {
+        use std::ffi::CString;
+        use std::os::raw::c_char;
+
+        let cstring =
+            |s: &str| CString::new(s).map_err(|_| format!("'{}' contains a NUL byte", s));
+        let data_path = data_path.map(cstring).transpose()?;
+        let language = cstring(language)?;
+        let names = variables
+            .iter()
+            .map(|(name, _)| cstring(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let values = variables
+            .iter()
+            .map(|(_, value)| cstring(value))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut name_ptrs: Vec<*mut c_char> =
+            names.iter().map(|name| name.as_ptr() as *mut c_char).collect();
+        let mut value_ptrs: Vec<*mut c_char> =
+            values.iter().map(|value| value.as_ptr() as *mut c_char).collect();
+
+        // SAFETY: every string outlives the call and Tesseract copies them;
+        // the handle is deleted by Drop whether or not Init succeeds
+        let tess = Self(unsafe { capi::TessBaseAPICreate() });
+        let code = unsafe {
+            capi::TessBaseAPIInit4(
+                tess.0,
+                data_path.as_ref().map_or(std::ptr::null(), |path| path.as_ptr()),
+                language.as_ptr(),
+                oem as _,
+                std::ptr::null_mut(),
+                0,
+                name_ptrs.as_mut_ptr(),
+                value_ptrs.as_mut_ptr(),
+                variables.len() as _,
+                0,
+            )
+        };
+        if code != 0 {
+            return Err(format!("Tesseract returned error code {}", code));
+        }
+        Ok(tess)
+    }
+
+    fn set_image(&mut self, bytes: &[u8], width: i32, height: i32, bpp: i32, bpl: i32) {
+        // SAFETY: Tesseract copies the image, and `bytes` holds `height` rows of `bpl` bytes
+        unsafe { capi::TessBaseAPISetImage(self.0, bytes.as_ptr(), width, height, bpp, bpl) }
+    }
+
+    fn get_utf8_text(&mut self) -> Result<String, std::str::Utf8Error> {
+        // SAFETY: the returned string is owned by the caller until TessDeleteText
+        unsafe {
+            let text = capi::TessBaseAPIGetUTF8Text(self.0);
+            if text.is_null() {
+                return Ok(String::new());
+            }
+            let result = std::ffi::CStr::from_ptr(text).to_str().map(str::to_string);
+            capi::TessDeleteText(text);
+            result
+        }
+    }
+
+    fn mean_text_conf(&mut self) -> i32 {
+        // SAFETY: the handle is valid for the lifetime of `self`
+        unsafe { capi::TessBaseAPIMeanTextConf(self.0) }
+    }
+}
+
+#[cfg(feature = "ocr")]
+impl Drop for Tesseract {
+    fn drop(&mut self) {
+        // SAFETY: the handle came from TessBaseAPICreate and is deleted once
+        unsafe { capi::TessBaseAPIDelete(self.0) }
+    }
+}
+
+/// Load the atlas `config` points the template glyph engine at
+fn load_glyph_atlas(config: &RecognizeConfig) -> RecognizeResult<GlyphAtlas> {
+    let path = config.glyph_atlas_path.as_deref().ok_or_else(|| {
+        RecognizeError::GlyphAtlasUnavailable("no glyph atlas has been built".to_string())
+    })?;
+    GlyphAtlas::load(std::path::Path::new(path))
+        .map_err(|e| RecognizeError::GlyphAtlasUnavailable(format!("{}: {}", path, e)))
+}
+
+/// Version of the linked Tesseract library
+#[cfg(feature = "ocr")]
+pub fn tesseract_version() -> Option<String> {
+    // SAFETY: TessVersion returns a pointer to a static NUL-terminated string
+    let version = unsafe { std::ffi::CStr::from_ptr(leptess::capi::TessVersion()) };
+    Some(version.to_string_lossy().into_owned())
+}
+
 /// Helper function to normalize card name for better matching
 pub fn normalize_card_name(name: &str) -> String {
     name.chars()
@@ -520,69 +660,6 @@ mod tests {
         assert!((conf2 - 0.68).abs() < 0.001);
     }
 
-    #[test]
-    fn test_card_matcher_new_empty() {
-        let result = CardMatcher::new(vec![], 60);
-        assert!(matches!(result, Err(RecognizeError::NoCardNamesAvailable)));
-    }
-
-    #[test]
-    fn test_card_matcher_find_best_match() {
-        let cards = create_test_card_names();
-        let matcher = CardMatcher::new(cards, 60).unwrap();
-
-        // Exact match
-        let result = matcher.find_best_match("Fel");
-        assert!(result.is_some());
-        let m = result.unwrap();
-        assert_eq!(m.card_name, "Fel");
-        assert!(m.match_score >= 60);
-
-        // Fuzzy match
-        let result = matcher.find_best_match("Fell");
-        assert!(result.is_some());
-        assert_eq!(result.unwrap().card_name, "Fel");
-
-        // Case insensitive
-        let result = matcher.find_best_match("FEL");
-        assert!(result.is_some());
-        assert_eq!(result.unwrap().card_name, "Fel");
-
-        // No match
-        let result = matcher.find_best_match("NonExistentCard123");
-        assert!(result.is_none());
-
-        // Empty string
-        let result = matcher.find_best_match("");
-        assert!(result.is_none());
-    }
-
-    #[test]
-    fn test_card_matcher_partial_match() {
-        let cards = create_test_card_names();
-        let matcher = CardMatcher::new(cards, 60).unwrap();
-
-        // Partial match on multi-word name
-        let result = matcher.find_best_match("Guillotine");
-        assert!(result.is_some());
-        assert_eq!(result.unwrap().card_name, "Bolete the Guillotine");
-
-        // Match on "the"
-        let result = matcher.find_best_match("Just");
-        assert!(result.is_some());
-        assert_eq!(result.unwrap().card_name, "Just Cause");
-    }
-
-    #[test]
-    fn test_card_matcher_find_all_matches() {
-        let cards = create_test_card_names();
-        let matcher = CardMatcher::new(cards, 60).unwrap();
-
-        let results = matcher.find_all_matches("Fe", 50);
-        assert!(!results.is_empty());
-        // Should find "Fel" and possibly "Lord Fenix"
-    }
-
     #[test]
     fn test_recognize_config_default() {
         let config = RecognizeConfig::default();
@@ -623,6 +700,49 @@ mod tests {
         assert!(RecognizeError::TesseractError("test".to_string()).to_string().contains("test"));
     }
 
+    #[test]
+    fn test_template_glyph_engine_needs_an_atlas() {
+        let config = RecognizeConfig {
+            engine: RecognitionEngine::TemplateGlyph,
+            ..Default::default()
+        };
+        assert!(matches!(
+            OcrEngine::with_config(config),
+            Err(RecognizeError::GlyphAtlasUnavailable(_))
+        ));
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(crate::ocr::glyph::ATLAS_FILE);
+        GlyphAtlas::default().save(&path).unwrap();
+        let config = RecognizeConfig {
+            engine: RecognitionEngine::TemplateGlyph,
+            glyph_atlas_path: Some(path.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let engine = OcrEngine::with_config(config).unwrap();
+        assert!(engine.check_available().is_err());
+    }
+
+    #[test]
+    fn test_user_words_weight_decoding() {
+        let config = RecognizeConfig::default();
+        let variables = config.tesseract_variables();
+        assert!(variables.iter().all(|(name, _)| *name != "user_words_file"));
+
+        let config = RecognizeConfig {
+            user_words_path: Some("/data/tessdata/card-names.user-words".to_string()),
+            ..Default::default()
+        };
+        let variables = config.tesseract_variables();
+        assert!(variables.contains(&(
+            "user_words_file",
+            "/data/tessdata/card-names.user-words".to_string()
+        )));
+        assert!(variables
+            .iter()
+            .any(|(name, _)| *name == "language_model_penalty_non_dict_word"));
+    }
+
     // Note: Tests that actually call Tesseract are integration tests
     // and would require Tesseract to be installed. We skip those here.
 }
@@ -0,0 +1,304 @@
+//! OCR debug session recorder
+//!
+//! While a recording is active, every detection pass saves the raw capture,
+//! the preprocessed image, the OCR text and the match decision for each
+//! region into a timestamped session folder, with a `manifest.json` that
+//! indexes them. The folder can be zipped and attached to a bug report.
+
+use super::capture::{CaptureRegion, RegionRole};
+use super::matcher::MatchCandidate;
+use super::recognize::{CardMatch, OcrResult};
+use chrono::Local;
+use image::{GrayImage, ImageBuffer, Rgba};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the session manifest
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Error type for session recording
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(std::io::Error),
+    Image(image::ImageError),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordingError::Io(e) => write!(f, "Recording I/O error: {}", e),
+            RecordingError::Image(e) => write!(f, "Failed to save recorded image: {}", e),
+            RecordingError::Json(e) => write!(f, "Failed to write recording manifest: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+impl From<std::io::Error> for RecordingError {
+    fn from(e: std::io::Error) -> Self {
+        RecordingError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for RecordingError {
+    fn from(e: image::ImageError) -> Self {
+        RecordingError::Image(e)
+    }
+}
+
+impl From<serde_json::Error> for RecordingError {
+    fn from(e: serde_json::Error) -> Self {
+        RecordingError::Json(e)
+    }
+}
+
+/// Result type for session recording
+pub type RecordingResult<T> = Result<T, RecordingError>;
+
+/// What the pipeline decided for one region in one pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionDecision {
+    /// A card was matched with enough overall confidence
+    Matched,
+    /// The region looked the same as last pass and reused its result
+    Unchanged,
+    /// A card was matched, but below `min_overall_confidence`
+    BelowThreshold,
+    /// Tesseract's confidence was too low to attempt matching
+    LowOcrConfidence,
+    /// The OCR text did not match any card
+    NoMatch,
+    CaptureFailed,
+    PreprocessFailed,
+    RecognizeFailed,
+}
+
+/// Intermediate products of processing one region
+#[derive(Debug, Clone)]
+pub struct RegionOutcome {
+    pub decision: RegionDecision,
+    pub gray_image: Option<GrayImage>,
+    pub ocr_result: Option<OcrResult>,
+    pub card_match: Option<CardMatch>,
+    pub error: Option<String>,
+}
+
+impl RegionOutcome {
+    /// An outcome with no intermediate products
+    pub fn new(decision: RegionDecision) -> Self {
+        Self {
+            decision,
+            gray_image: None,
+            ocr_result: None,
+            card_match: None,
+            error: None,
+        }
+    }
+
+    /// A failed outcome carrying the error message
+    pub fn failed(decision: RegionDecision, error: impl ToString) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Self::new(decision)
+        }
+    }
+}
+
+/// One region of one pass, as written to the manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionRecord {
+    pub pass: u64,
+    pub region_index: usize,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub role: Option<RegionRole>,
+    pub recorded_at: String,
+    /// Raw capture, relative to the session folder
+    pub raw_image: Option<String>,
+    /// Preprocessed image, relative to the session folder
+    pub preprocessed_image: Option<String>,
+    pub ocr_text: Option<String>,
+    pub ocr_confidence: Option<i32>,
+    pub decision: RegionDecision,
+    pub card_id: Option<String>,
+    pub card_name: Option<String>,
+    pub match_score: Option<i32>,
+    pub overall_confidence: Option<f64>,
+    pub candidates: Vec<MatchCandidate>,
+    pub error: Option<String>,
+}
+
+/// Index of everything recorded in a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionManifest {
+    pub started_at: String,
+    pub app_version: String,
+    pub passes: u64,
+    pub regions: Vec<RegionRecord>,
+}
+
+/// Records detection passes into a session folder
+#[derive(Debug)]
+pub struct SessionRecorder {
+    dir: PathBuf,
+    manifest: SessionManifest,
+}
+
+impl SessionRecorder {
+    /// Timestamped session folder name inside `parent`
+    pub fn session_dir(parent: &Path) -> PathBuf {
+        parent.join(Local::now().format("ocr-session-%Y%m%d-%H%M%S").to_string())
+    }
+
+    /// Start recording into `dir`, creating it if needed
+    pub fn start(dir: PathBuf) -> RecordingResult<Self> {
+        fs::create_dir_all(&dir)?;
+
+        let recorder = Self {
+            dir,
+            manifest: SessionManifest {
+                started_at: Local::now().to_rfc3339(),
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+                passes: 0,
+                regions: Vec::new(),
+            },
+        };
+        recorder.write_manifest()?;
+        Ok(recorder)
+    }
+
+    /// Session folder this recorder writes to
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Everything recorded so far
+    pub fn manifest(&self) -> &SessionManifest {
+        &self.manifest
+    }
+
+    /// Start a new detection pass
+    pub fn begin_pass(&mut self) {
+        self.manifest.passes += 1;
+    }
+
+    /// Record one region of the current pass
+    ///
+    /// Images are only saved for regions that were actually processed.
+    /// A failed image write is logged and leaves the image out of the record.
+    pub fn record_region(
+        &mut self,
+        region_index: usize,
+        region: &CaptureRegion,
+        raw_image: Option<&ImageBuffer<Rgba<u8>, Vec<u8>>>,
+        outcome: &RegionOutcome,
+    ) {
+        let pass = self.manifest.passes;
+        let stem = format!("pass{:05}_region{}", pass, region_index);
+
+        let processed = outcome.decision != RegionDecision::Unchanged;
+        let raw_image = raw_image
+            .filter(|_| processed)
+            .and_then(|img| self.save_image(&format!("{}_raw.png", stem), |path| img.save(path)));
+        let preprocessed_image = outcome
+            .gray_image
+            .as_ref()
+            .and_then(|img| self.save_image(&format!("{}_pre.png", stem), |path| img.save(path)));
+
+        let card_match = outcome.card_match.as_ref();
+        self.manifest.regions.push(RegionRecord {
+            pass,
+            region_index,
+            x: region.x,
+            y: region.y,
+            width: region.width,
+            height: region.height,
+            role: region.role,
+            recorded_at: Local::now().to_rfc3339(),
+            raw_image,
+            preprocessed_image,
+            ocr_text: outcome.ocr_result.as_ref().map(|r| r.text.clone()),
+            ocr_confidence: outcome.ocr_result.as_ref().map(|r| r.confidence),
+            decision: outcome.decision,
+            card_id: card_match.map(|m| m.card_id.clone()),
+            card_name: card_match.map(|m| m.card_name.clone()),
+            match_score: card_match.map(|m| m.match_score),
+            overall_confidence: card_match.map(|m| m.overall_confidence),
+            candidates: card_match.map(|m| m.candidates.clone()).unwrap_or_default(),
+            error: outcome.error.clone(),
+        });
+    }
+
+    /// Rewrite the manifest with everything recorded so far
+    pub fn write_manifest(&self) -> RecordingResult<()> {
+        let json = serde_json::to_string_pretty(&self.manifest)?;
+        fs::write(self.dir.join(MANIFEST_FILE), json)?;
+        Ok(())
+    }
+
+    fn save_image(
+        &self,
+        file_name: &str,
+        save: impl FnOnce(&Path) -> image::ImageResult<()>,
+    ) -> Option<String> {
+        match save(&self.dir.join(file_name)) {
+            Ok(()) => Some(file_name.to_string()),
+            Err(e) => {
+                log::warn!("{}", RecordingError::from(e));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_session_recorder_writes_manifest_and_images() {
+        let parent = TempDir::new().unwrap();
+        let dir = SessionRecorder::session_dir(parent.path());
+        let mut recorder = SessionRecorder::start(dir.clone()).unwrap();
+        assert!(dir.join(MANIFEST_FILE).exists());
+
+        let region = CaptureRegion::new(10, 20, 4, 4);
+        let raw = ImageBuffer::from_pixel(4, 4, Rgba([200, 200, 200, 255]));
+
+        recorder.begin_pass();
+        let outcome = RegionOutcome {
+            gray_image: Some(GrayImage::from_pixel(4, 4, image::Luma([255]))),
+            ocr_result: Some(OcrResult::new("Ta1os".to_string(), 40, 60)),
+            ..RegionOutcome::new(RegionDecision::LowOcrConfidence)
+        };
+        recorder.record_region(0, &region, Some(&raw), &outcome);
+
+        recorder.begin_pass();
+        recorder.record_region(0, &region, Some(&raw), &RegionOutcome::new(RegionDecision::Unchanged));
+        recorder.write_manifest().unwrap();
+
+        let manifest: SessionManifest =
+            serde_json::from_str(&fs::read_to_string(dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+        assert_eq!(manifest.passes, 2);
+        assert_eq!(manifest.regions.len(), 2);
+
+        let first = &manifest.regions[0];
+        assert_eq!(first.decision, RegionDecision::LowOcrConfidence);
+        assert_eq!(first.ocr_text.as_deref(), Some("Ta1os"));
+        assert!(dir.join(first.raw_image.as_ref().unwrap()).exists());
+        assert!(dir.join(first.preprocessed_image.as_ref().unwrap()).exists());
+
+        // Unchanged regions are logged without re-saving their images
+        let second = &manifest.regions[1];
+        assert_eq!(second.pass, 2);
+        assert!(second.raw_image.is_none());
+    }
+}
@@ -0,0 +1,169 @@
+//! What a capture region shows
+//!
+//! A role travels with its `CaptureRegion` into every detection, so
+//! consumers can tell a card name from the gold counter without relying on
+//! the order regions were configured in. This module is compiled with or
+//! without the `ocr` feature; it is re-exported from `ocr::capture`.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Part of the game UI a capture region covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionRole {
+    CardName1,
+    CardName2,
+    CardName3,
+    CardName4,
+    /// Rules text of a card on offer, read to spot upgrades
+    CardText1,
+    CardText2,
+    CardText3,
+    CardText4,
+    /// Upgrade pips of a card on offer
+    CardUpgrades1,
+    CardUpgrades2,
+    CardUpgrades3,
+    CardUpgrades4,
+    ChampionName,
+    Covenant,
+    Gold,
+    Ring,
+}
+
+impl RegionRole {
+    /// Card name slot `index` (0-based) of the draft screen
+    pub fn card_name(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(Self::CardName1),
+            1 => Some(Self::CardName2),
+            2 => Some(Self::CardName3),
+            3 => Some(Self::CardName4),
+            _ => None,
+        }
+    }
+
+    /// Rules text slot `index` (0-based) of the draft screen
+    pub fn card_text(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(Self::CardText1),
+            1 => Some(Self::CardText2),
+            2 => Some(Self::CardText3),
+            3 => Some(Self::CardText4),
+            _ => None,
+        }
+    }
+
+    /// Upgrade pip slot `index` (0-based) of the draft screen
+    pub fn card_upgrades(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(Self::CardUpgrades1),
+            1 => Some(Self::CardUpgrades2),
+            2 => Some(Self::CardUpgrades3),
+            3 => Some(Self::CardUpgrades4),
+            _ => None,
+        }
+    }
+
+    /// Whether the region shows the name of a card on offer
+    pub fn is_card_name(&self) -> bool {
+        self.card_slot().is_some()
+    }
+
+    /// 0-based card slot for card name roles
+    pub fn card_slot(&self) -> Option<usize> {
+        match self {
+            Self::CardName1 => Some(0),
+            Self::CardName2 => Some(1),
+            Self::CardName3 => Some(2),
+            Self::CardName4 => Some(3),
+            _ => None,
+        }
+    }
+
+    /// 0-based card slot for rules text roles
+    pub fn text_slot(&self) -> Option<usize> {
+        match self {
+            Self::CardText1 => Some(0),
+            Self::CardText2 => Some(1),
+            Self::CardText3 => Some(2),
+            Self::CardText4 => Some(3),
+            _ => None,
+        }
+    }
+
+    /// 0-based card slot for roles read after the card's name, i.e. rules
+    /// text and upgrade pips
+    pub fn detail_slot(&self) -> Option<usize> {
+        self.text_slot().or_else(|| self.upgrade_slot())
+    }
+
+    /// 0-based card slot for upgrade pip roles
+    pub fn upgrade_slot(&self) -> Option<usize> {
+        match self {
+            Self::CardUpgrades1 => Some(0),
+            Self::CardUpgrades2 => Some(1),
+            Self::CardUpgrades3 => Some(2),
+            Self::CardUpgrades4 => Some(3),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RegionRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::CardName1 => "card name 1",
+            Self::CardName2 => "card name 2",
+            Self::CardName3 => "card name 3",
+            Self::CardName4 => "card name 4",
+            Self::CardText1 => "card text 1",
+            Self::CardText2 => "card text 2",
+            Self::CardText3 => "card text 3",
+            Self::CardText4 => "card text 4",
+            Self::CardUpgrades1 => "card upgrades 1",
+            Self::CardUpgrades2 => "card upgrades 2",
+            Self::CardUpgrades3 => "card upgrades 3",
+            Self::CardUpgrades4 => "card upgrades 4",
+            Self::ChampionName => "champion name",
+            Self::Covenant => "covenant",
+            Self::Gold => "gold",
+            Self::Ring => "ring",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_name_roles_map_to_slots() {
+        for index in 0..4 {
+            let role = RegionRole::card_name(index).unwrap();
+            assert!(role.is_card_name());
+            assert_eq!(role.card_slot(), Some(index));
+            assert_eq!(role.text_slot(), None);
+
+            let text = RegionRole::card_text(index).unwrap();
+            assert!(!text.is_card_name());
+            assert_eq!(text.text_slot(), Some(index));
+
+            let upgrades = RegionRole::card_upgrades(index).unwrap();
+            assert_eq!(upgrades.upgrade_slot(), Some(index));
+            assert_eq!(upgrades.text_slot(), None);
+            assert_eq!(upgrades.detail_slot(), Some(index));
+        }
+        assert_eq!(RegionRole::card_name(4), None);
+        assert!(!RegionRole::Gold.is_card_name());
+
+        assert_eq!(RegionRole::CardName2.to_string(), "card name 2");
+        assert_eq!(RegionRole::CardUpgrades3.to_string(), "card upgrades 3");
+        assert_eq!(
+            serde_json::to_string(&RegionRole::ChampionName).unwrap(),
+            "\"champion_name\""
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Upgrade detection from a card's rules text
+//!
+//! Upgrades and infusions change the numbers printed on a card: a spell
+//! deals more damage, or a unit gains "+10 Attack". The rules text read
+//! from a card's text region is compared with the database description by
+//! its numbers alone, since OCR of small body text garbles words far more
+//! often than digits and the stored descriptions are not always verbatim.
+//! This module is compiled with or without the `ocr` feature.
+
+use serde::{Deserialize, Serialize};
+
+/// A number in card text with the word that follows it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextValue {
+    pub value: i32,
+    /// Lowercased word after the number, e.g. `damage` in "Deal 10 damage"
+    pub label: String,
+    /// Whether the number was written as a bonus, e.g. "+10 Attack"
+    pub bonus: bool,
+}
+
+/// A number on the card that differs from the database description
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueChange {
+    pub label: String,
+    /// Value in the description; `None` for a bonus the description lacks
+    pub base: Option<i32>,
+    /// Value read from the card
+    pub shown: i32,
+}
+
+/// Outcome of comparing a card's rules text with its description
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RulesTextCheck {
+    /// Whether the card looks upgraded or otherwise modified
+    pub modified: bool,
+    pub changes: Vec<ValueChange>,
+}
+
+/// Numbers in `text`, each with the word after it
+///
+/// Numbers not followed by a word, such as a lone cost, are skipped.
+pub fn text_values(text: &str) -> Vec<TextValue> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut values = Vec::new();
+
+    for (i, word) in words.iter().enumerate() {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '+');
+        let (bonus, digits) = match word.strip_prefix('+') {
+            Some(digits) => (true, digits),
+            None => (false, word),
+        };
+        let value = match digits.parse::<i32>() {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+
+        let label: String = words
+            .get(i + 1)
+            .map(|next| {
+                next.chars()
+                    .take_while(|c| c.is_alphabetic())
+                    .flat_map(char::to_lowercase)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !label.is_empty() {
+            values.push(TextValue {
+                value,
+                label,
+                bonus,
+            });
+        }
+    }
+    values
+}
+
+/// Compare the rules text read from a card with its database description
+///
+/// A bonus on the card that the description lacks counts as a change, as
+/// does a number whose label the description gives a different value for.
+/// Other numbers the description does not mention are ignored.
+pub fn check_rules_text(shown: &str, description: &str) -> RulesTextCheck {
+    let mut base = text_values(description);
+    let mut changes = Vec::new();
+
+    for value in text_values(shown) {
+        // Numbers that match the description are used up, so a repeated
+        // label is compared with its next occurrence
+        if let Some(i) = base
+            .iter()
+            .position(|b| b.label == value.label && b.value == value.value)
+        {
+            base.remove(i);
+            continue;
+        }
+
+        match base.iter().position(|b| b.label == value.label) {
+            Some(i) => {
+                let original = base.remove(i);
+                changes.push(ValueChange {
+                    label: value.label,
+                    base: Some(original.value),
+                    shown: value.value,
+                });
+            }
+            None if value.bonus => changes.push(ValueChange {
+                label: value.label,
+                base: None,
+                shown: value.value,
+            }),
+            None => {}
+        }
+    }
+
+    RulesTextCheck {
+        modified: !changes.is_empty(),
+        changes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_values() {
+        let values = text_values("Deal 10 damage. +5 Attack, Cost: 2");
+        assert_eq!(
+            values,
+            vec![
+                TextValue {
+                    value: 10,
+                    label: "damage".to_string(),
+                    bonus: false,
+                },
+                TextValue {
+                    value: 5,
+                    label: "attack".to_string(),
+                    bonus: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_rules_text_flags_upgrades() {
+        let description = "Deal 10 damage to a front unit";
+
+        let plain = check_rules_text("Deal 10 damage to a front unit.", description);
+        assert!(!plain.modified);
+
+        let stronger = check_rules_text("Deal 20 damage to a front unit.", description);
+        assert_eq!(
+            stronger.changes,
+            vec![ValueChange {
+                label: "damage".to_string(),
+                base: Some(10),
+                shown: 20,
+            }]
+        );
+
+        let infused = check_rules_text("Deal 10 damage. +10 Attack", description);
+        assert!(infused.modified);
+        assert_eq!(infused.changes[0].base, None);
+        assert_eq!(infused.changes[0].shown, 10);
+
+        // Numbers the description does not mention are not upgrades
+        assert!(!check_rules_text("Gain 3 Valor", "Core to the Valor deck").modified);
+    }
+}
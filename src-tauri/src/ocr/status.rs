@@ -0,0 +1,143 @@
+//! OCR runtime availability
+//!
+//! The `ocr` feature can be compiled in while Tesseract or its language
+//! data is missing on the user's machine. These checks turn that into a
+//! structured reason plus remediation the UI can show, instead of an
+//! opaque initialization error. With the template glyph engine only the
+//! glyph atlas is checked.
+
+use super::recognize::{tesseract_version, OcrEngine, RecognitionEngine, RecognizeConfig};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Why OCR cannot run, and what the user can do about it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OcrUnavailable {
+    pub reason: String,
+    pub remediation: String,
+}
+
+/// OCR capability report
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OcrStatus {
+    /// Whether this build includes the `ocr` feature
+    pub compiled: bool,
+    /// Whether the configured engine can run: Tesseract initialized with
+    /// the configured language data, or the glyph atlas loaded
+    pub available: bool,
+    pub engine: RecognitionEngine,
+    /// Linked Tesseract version
+    pub version: Option<String>,
+    pub language: String,
+    /// Configured tessdata folder (`None` uses the system default)
+    pub data_path: Option<String>,
+    pub unavailable: Option<OcrUnavailable>,
+}
+
+/// Check that OCR can run with `config`
+pub fn check_availability(config: &RecognizeConfig) -> Result<(), OcrUnavailable> {
+    if !cfg!(feature = "ocr") {
+        return Err(OcrUnavailable {
+            reason: "This build does not include OCR support".to_string(),
+            remediation: "Install a release built with the `ocr` feature".to_string(),
+        });
+    }
+
+    if config.engine == RecognitionEngine::TemplateGlyph {
+        return OcrEngine::with_config(config.clone())
+            .and_then(|engine| engine.check_available())
+            .map_err(|e| OcrUnavailable {
+                reason: e.to_string(),
+                remediation: "Build a glyph atlas from a recorded OCR session, or switch the OCR \
+                              engine back to Tesseract"
+                    .to_string(),
+            });
+    }
+
+    // Points at the fallback engine once an atlas has been built
+    let fallback = if config.glyph_atlas_path.is_some() {
+        ", or switch the OCR engine to template glyphs"
+    } else {
+        ""
+    };
+
+    if let Some(ref data_path) = config.tesseract_data_path {
+        if !Path::new(data_path).is_dir() {
+            return Err(OcrUnavailable {
+                reason: format!("Tesseract data folder '{}' does not exist", data_path),
+                remediation: format!(
+                    "Download the '{}' language pack, or clear the custom data path{}",
+                    config.language, fallback
+                ),
+            });
+        }
+    }
+
+    OcrEngine::with_config(config.clone())
+        .and_then(|engine| engine.check_available())
+        .map_err(|e| OcrUnavailable {
+            reason: e.to_string(),
+            remediation: format!(
+                "Install Tesseract OCR, or download the '{}' language pack from the OCR settings{}",
+                config.language, fallback
+            ),
+        })
+}
+
+/// Full OCR capability report for `config`
+pub fn ocr_status(config: &RecognizeConfig) -> OcrStatus {
+    let unavailable = check_availability(config).err();
+
+    OcrStatus {
+        compiled: cfg!(feature = "ocr"),
+        available: unavailable.is_none(),
+        engine: config.engine,
+        version: tesseract_version(),
+        language: config.language.clone(),
+        data_path: config.tesseract_data_path.clone(),
+        unavailable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_data_path_is_reported() {
+        let config = RecognizeConfig {
+            tesseract_data_path: Some("/nonexistent/tessdata".to_string()),
+            ..Default::default()
+        };
+
+        let status = ocr_status(&config);
+        assert!(!status.available);
+        assert_eq!(status.compiled, cfg!(feature = "ocr"));
+
+        let unavailable = status.unavailable.unwrap();
+        assert!(!unavailable.remediation.is_empty());
+        if cfg!(feature = "ocr") {
+            assert!(unavailable.reason.contains("/nonexistent/tessdata"));
+        }
+    }
+
+    #[test]
+    fn test_missing_glyph_atlas_is_reported() {
+        let config = RecognizeConfig {
+            engine: RecognitionEngine::TemplateGlyph,
+            glyph_atlas_path: Some("/nonexistent/glyph_atlas.json".to_string()),
+            ..Default::default()
+        };
+
+        let status = ocr_status(&config);
+        assert!(!status.available);
+        assert_eq!(status.engine, RecognitionEngine::TemplateGlyph);
+        if cfg!(feature = "ocr") {
+            assert!(status
+                .unavailable
+                .unwrap()
+                .reason
+                .contains("glyph_atlas.json"));
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Tesseract language data management
+//!
+//! Lists installed `*.traineddata` language packs (in the app's own
+//! `tessdata` folder and the usual system locations) and downloads missing
+//! packs into the app folder, so OCR works without a system-wide tessdata
+//! install. Downloading requires the `ocr` feature.
+//!
+//! The card name vocabulary is also written here as a Tesseract user-words
+//! file, so decoding favors words that appear in card names.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Folder under the app data dir holding downloaded language packs
+pub const APP_TESSDATA_DIR: &str = "tessdata";
+
+/// Source of downloadable language packs (the "fast" LSTM models)
+pub const TESSDATA_BASE_URL: &str = "https://github.com/tesseract-ocr/tessdata_fast/raw/main";
+
+/// User-words file in the app tessdata folder holding the card name vocabulary
+pub const USER_WORDS_FILE: &str = "card-names.user-words";
+
+/// Extension of Tesseract language data files
+const TRAINEDDATA_EXT: &str = "traineddata";
+
+/// Language codes are short identifiers like `eng` or `chi_sim`
+const MAX_LANGUAGE_CODE_LEN: usize = 16;
+
+/// Error type for language pack management
+#[derive(Debug)]
+pub enum TessdataError {
+    InvalidLanguage(String),
+    Io(std::io::Error),
+    Download(String),
+    /// Downloads need the `ocr` feature
+    Unsupported,
+}
+
+impl std::fmt::Display for TessdataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TessdataError::InvalidLanguage(code) => write!(f, "Invalid language code: '{}'", code),
+            TessdataError::Io(e) => write!(f, "Language data I/O error: {}", e),
+            TessdataError::Download(msg) => write!(f, "Language data download failed: {}", msg),
+            TessdataError::Unsupported => {
+                write!(f, "Language data downloads require the OCR feature")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TessdataError {}
+
+impl From<std::io::Error> for TessdataError {
+    fn from(e: std::io::Error) -> Self {
+        TessdataError::Io(e)
+    }
+}
+
+/// Result type for language pack management
+pub type TessdataResult<T> = Result<T, TessdataError>;
+
+/// An installed language pack
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LanguagePack {
+    /// Language code, e.g. `eng`
+    pub language: String,
+    /// Folder containing the pack (usable as `tesseract_data_path`)
+    pub data_path: String,
+    pub size_bytes: u64,
+    /// Whether the pack lives in the app's own tessdata folder
+    pub app_managed: bool,
+}
+
+/// The app's own tessdata folder
+pub fn app_tessdata_dir(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(APP_TESSDATA_DIR)
+}
+
+/// Well-known system tessdata folders, `TESSDATA_PREFIX` first
+pub fn system_tessdata_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("TESSDATA_PREFIX")
+        .map(PathBuf::from)
+        .into_iter()
+        .collect();
+
+    dirs.extend(
+        [
+            "/usr/share/tesseract-ocr/5/tessdata",
+            "/usr/share/tesseract-ocr/4.00/tessdata",
+            "/usr/share/tessdata",
+            "/usr/local/share/tessdata",
+            "/opt/homebrew/share/tessdata",
+            "C:\\Program Files\\Tesseract-OCR\\tessdata",
+        ]
+        .iter()
+        .map(PathBuf::from),
+    );
+
+    dirs
+}
+
+/// Whether `code` looks like a Tesseract language code
+pub fn is_valid_language_code(code: &str) -> bool {
+    !code.is_empty()
+        && code.len() <= MAX_LANGUAGE_CODE_LEN
+        && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Language packs found in a single folder
+fn packs_in(dir: &Path, app_managed: bool) -> Vec<LanguagePack> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut packs: Vec<LanguagePack> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(TRAINEDDATA_EXT) {
+                return None;
+            }
+            Some(LanguagePack {
+                language: path.file_stem()?.to_str()?.to_string(),
+                data_path: dir.to_string_lossy().to_string(),
+                size_bytes: entry.metadata().ok()?.len(),
+                app_managed,
+            })
+        })
+        .collect();
+
+    packs.sort_by(|a, b| a.language.cmp(&b.language));
+    packs
+}
+
+/// All installed language packs, app-managed packs first
+pub fn list_installed(app_dir: &Path, system_dirs: &[PathBuf]) -> Vec<LanguagePack> {
+    let mut packs = packs_in(app_dir, true);
+    for dir in system_dirs.iter().filter(|dir| dir.as_path() != app_dir) {
+        packs.extend(packs_in(dir, false));
+    }
+    packs
+}
+
+/// The app tessdata folder, if it has a pack for `language`
+///
+/// Used to point `tesseract_data_path` at downloaded packs automatically.
+pub fn resolve_data_path(app_dir: &Path, language: &str) -> Option<PathBuf> {
+    // Tesseract accepts `eng+fra`; every listed language must be present
+    let all_present = language
+        .split('+')
+        .all(|lang| app_dir.join(format!("{}.{}", lang, TRAINEDDATA_EXT)).is_file());
+
+    all_present.then(|| app_dir.to_path_buf())
+}
+
+/// Download a language pack into `app_dir`, replacing any existing copy
+#[cfg(feature = "ocr")]
+pub fn download_language(app_dir: &Path, language: &str) -> TessdataResult<LanguagePack> {
+    use std::io::Write;
+
+    if !is_valid_language_code(language) {
+        return Err(TessdataError::InvalidLanguage(language.to_string()));
+    }
+    fs::create_dir_all(app_dir)?;
+
+    let url = format!("{}/{}.{}", TESSDATA_BASE_URL, language, TRAINEDDATA_EXT);
+    let response = ureq::get(&url)
+        .call()
+        .map_err(|e| TessdataError::Download(format!("{}: {}", url, e)))?;
+
+    // Write to a temporary file so an interrupted download never leaves a
+    // truncated pack where Tesseract would load it
+    let target = app_dir.join(format!("{}.{}", language, TRAINEDDATA_EXT));
+    let partial = target.with_extension("part");
+    let mut file = fs::File::create(&partial)?;
+    let size_bytes = std::io::copy(&mut response.into_reader(), &mut file)?;
+    file.flush()?;
+    drop(file);
+
+    if size_bytes == 0 {
+        let _ = fs::remove_file(&partial);
+        return Err(TessdataError::Download(format!("{}: empty response", url)));
+    }
+    fs::rename(&partial, &target)?;
+
+    log::info!("Downloaded Tesseract language '{}' ({} bytes)", language, size_bytes);
+    Ok(LanguagePack {
+        language: language.to_string(),
+        data_path: app_dir.to_string_lossy().to_string(),
+        size_bytes,
+        app_managed: true,
+    })
+}
+
+/// Download a language pack into `app_dir` (unavailable without OCR)
+#[cfg(not(feature = "ocr"))]
+pub fn download_language(_app_dir: &Path, language: &str) -> TessdataResult<LanguagePack> {
+    if !is_valid_language_code(language) {
+        return Err(TessdataError::InvalidLanguage(language.to_string()));
+    }
+    Err(TessdataError::Unsupported)
+}
+
+/// Distinct words of `names`, sorted, as Tesseract reads them from a
+/// user-words file
+///
+/// Punctuation around a word is dropped; apostrophes and hyphens inside
+/// one (`Fel's`, `Half-Man`) are kept.
+pub fn card_vocabulary<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let words: BTreeSet<String> = names
+        .into_iter()
+        .flat_map(str::split_whitespace)
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect();
+    words.into_iter().collect()
+}
+
+/// Write the vocabulary of `names` to the user-words file in `app_dir`
+///
+/// Returns the file's path, for `RecognizeConfig::user_words_path`.
+pub fn write_user_words<'a>(
+    app_dir: &Path,
+    names: impl IntoIterator<Item = &'a str>,
+) -> TessdataResult<PathBuf> {
+    let words = card_vocabulary(names);
+    fs::create_dir_all(app_dir)?;
+
+    // Tesseract may be reading the previous file from another thread
+    let target = app_dir.join(USER_WORDS_FILE);
+    let partial = target.with_extension("part");
+    let mut contents = words.join("\n");
+    contents.push('\n');
+    fs::write(&partial, contents)?;
+    fs::rename(&partial, &target)?;
+
+    log::info!("Wrote {} card name words for Tesseract", words.len());
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_valid_language_code() {
+        assert!(is_valid_language_code("eng"));
+        assert!(is_valid_language_code("chi_sim"));
+        assert!(!is_valid_language_code(""));
+        assert!(!is_valid_language_code("../eng"));
+        assert!(!is_valid_language_code("eng+fra"));
+    }
+
+    #[test]
+    fn test_list_and_resolve_language_packs() {
+        let app = TempDir::new().unwrap();
+        let system = TempDir::new().unwrap();
+        fs::write(app.path().join("eng.traineddata"), b"data").unwrap();
+        fs::write(app.path().join("notes.txt"), b"ignored").unwrap();
+        fs::write(system.path().join("fra.traineddata"), b"data").unwrap();
+
+        let packs = list_installed(app.path(), &[system.path().to_path_buf()]);
+        assert_eq!(packs.len(), 2);
+        assert_eq!(packs[0].language, "eng");
+        assert!(packs[0].app_managed);
+        assert_eq!(packs[0].size_bytes, 4);
+        assert_eq!(packs[1].language, "fra");
+        assert!(!packs[1].app_managed);
+
+        assert_eq!(resolve_data_path(app.path(), "eng"), Some(app.path().to_path_buf()));
+        assert!(resolve_data_path(app.path(), "eng+fra").is_none());
+        assert!(list_installed(&app.path().join("missing"), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_user_words_from_card_names() {
+        let names = ["Lord Fenix", "Fel's Ruse", "Half-Man (Upgraded)", "Lord of Ash"];
+        assert_eq!(
+            card_vocabulary(names),
+            vec!["Ash", "Fel's", "Fenix", "Half-Man", "Lord", "Ruse", "Upgraded", "of"]
+        );
+
+        let app = TempDir::new().unwrap();
+        let path = write_user_words(app.path(), names).unwrap();
+        assert_eq!(path, app.path().join(USER_WORDS_FILE));
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 8);
+        assert!(list_installed(app.path(), &[]).is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+//! Per-stage detection timeouts and cancellation
+//!
+//! A screen capture can block on a compositor or portal prompt and a
+//! Tesseract call can spin on a pathological image; neither can be
+//! interrupted from outside. Each such stage therefore runs on a worker
+//! thread while the detection waits with a deadline. On timeout or
+//! cancellation the detection returns at once and the worker is abandoned;
+//! its result is dropped whenever it finishes. This module is compiled with
+//! or without the `ocr` feature.
+
+use crate::crash;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default limit for capturing every region; a portal capture may show a
+/// permission prompt the first time
+pub const DEFAULT_CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default limit for reading the text of one region
+pub const DEFAULT_RECOGNIZE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Shortest timeout the settings accept
+pub const MIN_STAGE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How often a waiting stage checks for cancellation
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Detection stage that runs under a timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectionStage {
+    Capture,
+    Recognize,
+}
+
+impl fmt::Display for DetectionStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DetectionStage::Capture => f.write_str("capture"),
+            DetectionStage::Recognize => f.write_str("recognize"),
+        }
+    }
+}
+
+/// Time limits for the detection stages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StageTimeouts {
+    /// Limit for capturing every region of one pass
+    pub capture: Duration,
+    /// Limit for recognizing one region
+    pub recognize: Duration,
+}
+
+impl Default for StageTimeouts {
+    fn default() -> Self {
+        Self {
+            capture: DEFAULT_CAPTURE_TIMEOUT,
+            recognize: DEFAULT_RECOGNIZE_TIMEOUT,
+        }
+    }
+}
+
+/// Why a stage produced no result
+#[derive(Debug, Clone, PartialEq)]
+pub enum StageError {
+    TimedOut {
+        stage: DetectionStage,
+        timeout: Duration,
+    },
+    Cancelled,
+    /// The worker thread panicked
+    Panicked(DetectionStage),
+    /// No worker thread could be started
+    Spawn(String),
+}
+
+impl fmt::Display for StageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StageError::TimedOut { stage, timeout } => {
+                write!(
+                    f,
+                    "OCR {} timed out after {} ms",
+                    stage,
+                    timeout.as_millis()
+                )
+            }
+            StageError::Cancelled => write!(f, "Detection was cancelled"),
+            StageError::Panicked(stage) => write!(f, "OCR {} failed unexpectedly", stage),
+            StageError::Spawn(msg) => write!(f, "Failed to start OCR worker: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for StageError {}
+
+#[derive(Debug, Default)]
+struct CancelState {
+    running: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+/// Shared flag that aborts the detection in flight
+///
+/// The detection marks itself running with `begin`; `cancel` from any
+/// thread then makes its current and remaining stages fail with
+/// `StageError::Cancelled`. A cancel while nothing runs is ignored, so it
+/// never aborts the next detection.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    state: Arc<CancelState>,
+}
+
+/// Marks a detection as running until dropped
+pub struct RunningDetection<'a> {
+    token: &'a CancelToken,
+}
+
+impl CancelToken {
+    /// Mark a detection as running
+    pub fn begin(&self) -> RunningDetection<'_> {
+        self.state.cancelled.store(false, Ordering::SeqCst);
+        self.state.running.store(true, Ordering::SeqCst);
+        RunningDetection { token: self }
+    }
+
+    /// Abort the running detection; returns whether one was running
+    pub fn cancel(&self) -> bool {
+        let running = self.state.running.load(Ordering::SeqCst);
+        if running {
+            self.state.cancelled.store(true, Ordering::SeqCst);
+        }
+        running
+    }
+
+    /// Whether the running detection was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for RunningDetection<'_> {
+    fn drop(&mut self) {
+        self.token.state.running.store(false, Ordering::SeqCst);
+        self.token.state.cancelled.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Run `work` on a worker thread and wait up to `timeout` for its result
+pub fn run_stage<T, F>(
+    stage: DetectionStage,
+    timeout: Duration,
+    cancel: &CancelToken,
+    work: F,
+) -> Result<T, StageError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    if cancel.is_cancelled() {
+        return Err(StageError::Cancelled);
+    }
+
+    let (sender, receiver) = mpsc::sync_channel(1);
+    std::thread::Builder::new()
+        .name(format!("ocr-{}", stage))
+        .spawn(move || {
+            // A panic drops the sender, which reports it below
+            if let Ok(result) = crash::catch_panic(AssertUnwindSafe(work)) {
+                // The receiver is gone if the stage was abandoned
+                let _ = sender.send(result);
+            }
+        })
+        .map_err(|e| StageError::Spawn(e.to_string()))?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(remaining.min(CANCEL_POLL_INTERVAL)) {
+            Ok(result) => return Ok(result),
+            Err(RecvTimeoutError::Disconnected) => return Err(StageError::Panicked(stage)),
+            Err(RecvTimeoutError::Timeout) if cancel.is_cancelled() => {
+                log::info!("OCR {} cancelled", stage);
+                return Err(StageError::Cancelled);
+            }
+            Err(RecvTimeoutError::Timeout) if remaining <= CANCEL_POLL_INTERVAL => {
+                log::warn!("OCR {} timed out after {:?}; abandoning it", stage, timeout);
+                return Err(StageError::TimedOut { stage, timeout });
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_returns_result_or_times_out() {
+        let cancel = CancelToken::default();
+        let result = run_stage(
+            DetectionStage::Capture,
+            Duration::from_secs(5),
+            &cancel,
+            || 7,
+        );
+        assert_eq!(result, Ok(7));
+
+        let started = Instant::now();
+        let result = run_stage(
+            DetectionStage::Recognize,
+            Duration::from_millis(100),
+            &cancel,
+            || {
+                std::thread::sleep(Duration::from_secs(5));
+            },
+        );
+        assert_eq!(
+            result,
+            Err(StageError::TimedOut {
+                stage: DetectionStage::Recognize,
+                timeout: Duration::from_millis(100),
+            })
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let result: Result<(), _> = run_stage(
+            DetectionStage::Capture,
+            Duration::from_secs(5),
+            &cancel,
+            || panic!("stuck"),
+        );
+        assert_eq!(result, Err(StageError::Panicked(DetectionStage::Capture)));
+    }
+
+    #[test]
+    fn test_cancel_aborts_only_a_running_detection() {
+        let cancel = CancelToken::default();
+        assert!(!cancel.cancel());
+        assert!(!cancel.is_cancelled());
+
+        let running = cancel.begin();
+        let canceller = cancel.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            assert!(canceller.cancel());
+        });
+        let started = Instant::now();
+        let result = run_stage(
+            DetectionStage::Recognize,
+            Duration::from_secs(10),
+            &cancel,
+            || {
+                std::thread::sleep(Duration::from_secs(5));
+            },
+        );
+        assert_eq!(result, Err(StageError::Cancelled));
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        // Later stages of the same detection fail at once
+        assert_eq!(
+            run_stage(
+                DetectionStage::Capture,
+                Duration::from_secs(1),
+                &cancel,
+                || ()
+            ),
+            Err(StageError::Cancelled)
+        );
+
+        drop(running);
+        assert!(!cancel.is_cancelled());
+        let _running = cancel.begin();
+        assert_eq!(
+            run_stage(
+                DetectionStage::Capture,
+                Duration::from_secs(1),
+                &cancel,
+                || 1
+            ),
+            Ok(1)
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Per-stage latency metrics
+//!
+//! Hot paths open `tracing` spans named after their stage (`ocr.capture`,
+//! `scoring.calculate`, ...). `PerfSubscriber` times each span from creation
+//! to close and keeps the last `WINDOW` samples per stage, from which
+//! rolling averages and p95 latencies are reported. Events are ignored;
+//! logging still goes through the `log` crate.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Samples kept per stage
+pub const WINDOW: usize = 200;
+
+static METRICS: OnceLock<Arc<PerfMetrics>> = OnceLock::new();
+
+/// Latency summary for one stage, in milliseconds
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StageMetrics {
+    pub stage: String,
+    /// Samples in the rolling window
+    pub count: usize,
+    /// Spans closed since startup or the last reset
+    pub total: u64,
+    pub avg_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    pub last_ms: f64,
+}
+
+#[derive(Default)]
+struct Samples {
+    window: VecDeque<Duration>,
+    total: u64,
+}
+
+impl Samples {
+    fn metrics(&self, stage: &str) -> StageMetrics {
+        let mut sorted: Vec<Duration> = self.window.iter().copied().collect();
+        sorted.sort();
+        let count = sorted.len();
+        let ms = |d: Duration| d.as_micros() as f64 / 1000.0;
+        let sum: Duration = sorted.iter().sum();
+        // Nearest-rank percentile
+        let p95 = sorted
+            .get(((count * 95 + 99) / 100).saturating_sub(1))
+            .copied()
+            .unwrap_or_default();
+
+        StageMetrics {
+            stage: stage.to_string(),
+            count,
+            total: self.total,
+            avg_ms: if count == 0 {
+                0.0
+            } else {
+                ms(sum) / count as f64
+            },
+            p95_ms: ms(p95),
+            max_ms: ms(sorted.last().copied().unwrap_or_default()),
+            last_ms: ms(self.window.back().copied().unwrap_or_default()),
+        }
+    }
+}
+
+/// Rolling span durations, keyed by span name
+#[derive(Default)]
+pub struct PerfMetrics {
+    stages: Mutex<BTreeMap<&'static str, Samples>>,
+}
+
+impl PerfMetrics {
+    pub fn record(&self, stage: &'static str, elapsed: Duration) {
+        let mut stages = self.stages.lock().unwrap_or_else(|e| e.into_inner());
+        let samples = stages.entry(stage).or_default();
+        if samples.window.len() == WINDOW {
+            samples.window.pop_front();
+        }
+        samples.window.push_back(elapsed);
+        samples.total += 1;
+    }
+
+    /// Metrics for every stage seen so far, sorted by stage name
+    pub fn snapshot(&self) -> Vec<StageMetrics> {
+        let stages = self.stages.lock().unwrap_or_else(|e| e.into_inner());
+        stages
+            .iter()
+            .map(|(stage, samples)| samples.metrics(stage))
+            .collect()
+    }
+
+    pub fn reset(&self) {
+        self.stages
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+struct OpenSpan {
+    name: &'static str,
+    started: Instant,
+    /// Handles to the span still alive
+    refs: usize,
+}
+
+/// `tracing` subscriber that only times this crate's spans
+pub struct PerfSubscriber {
+    metrics: Arc<PerfMetrics>,
+    next_id: AtomicU64,
+    open: Mutex<HashMap<u64, OpenSpan>>,
+}
+
+impl PerfSubscriber {
+    pub fn new(metrics: Arc<PerfMetrics>) -> Self {
+        Self {
+            metrics,
+            next_id: AtomicU64::new(1),
+            open: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Subscriber for PerfSubscriber {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.is_span() && metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let open = OpenSpan {
+            name: span.metadata().name(),
+            started: Instant::now(),
+            refs: 1,
+        };
+        self.open
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(id, open);
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+
+    fn clone_span(&self, id: &Id) -> Id {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(span) = open.get_mut(&id.into_u64()) {
+            span.refs += 1;
+        }
+        id.clone()
+    }
+
+    fn try_close(&self, id: Id) -> bool {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(span) = open.get_mut(&id.into_u64()) else {
+            return false;
+        };
+        span.refs -= 1;
+        if span.refs > 0 {
+            return false;
+        }
+        let span = open.remove(&id.into_u64()).expect("span is open");
+        drop(open);
+        self.metrics.record(span.name, span.started.elapsed());
+        true
+    }
+}
+
+/// Install the subscriber process-wide
+///
+/// Call once at startup; later calls are ignored.
+pub fn init() {
+    let metrics = METRICS.get_or_init(Arc::default).clone();
+    if tracing::subscriber::set_global_default(PerfSubscriber::new(metrics)).is_err() {
+        log::warn!("A tracing subscriber is already installed; stage metrics are disabled");
+    }
+}
+
+/// Current metrics, empty until `init` has run
+pub fn snapshot() -> Vec<StageMetrics> {
+    METRICS.get().map(|m| m.snapshot()).unwrap_or_default()
+}
+
+pub fn reset() {
+    if let Some(metrics) = METRICS.get() {
+        metrics.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_window_stats() {
+        let metrics = PerfMetrics::default();
+        for ms in 1..=(WINDOW as u64 + 20) {
+            metrics.record("ocr.capture", Duration::from_millis(ms));
+        }
+
+        let stats = metrics.snapshot();
+        assert_eq!(stats.len(), 1);
+        let capture = &stats[0];
+        assert_eq!(capture.count, WINDOW);
+        assert_eq!(capture.total, WINDOW as u64 + 20);
+        // The window holds 21..=220 ms
+        assert_eq!(capture.avg_ms, 120.5);
+        assert_eq!(capture.p95_ms, 210.0);
+        assert_eq!(capture.max_ms, 220.0);
+        assert_eq!(capture.last_ms, 220.0);
+
+        metrics.reset();
+        assert!(metrics.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_subscriber_times_spans() {
+        let metrics = Arc::new(PerfMetrics::default());
+        tracing::subscriber::with_default(PerfSubscriber::new(metrics.clone()), || {
+            for _ in 0..3 {
+                let span = tracing::info_span!("scoring.calculate");
+                // Clones keep the span open until the last handle drops
+                let copy = span.clone();
+                drop(span);
+                let _entered = copy.entered();
+                std::thread::sleep(Duration::from_millis(2));
+            }
+            tracing::info_span!("ocr.match").in_scope(|| {});
+        });
+
+        let stats = metrics.snapshot();
+        let names: Vec<_> = stats.iter().map(|s| s.stage.as_str()).collect();
+        assert_eq!(names, ["ocr.match", "scoring.calculate"]);
+        assert_eq!(stats[1].count, 3);
+        assert!(stats[1].avg_ms >= 2.0);
+        assert!(stats[1].p95_ms >= stats[1].avg_ms);
+    }
+}
@@ -0,0 +1,220 @@
+//! Player profiles
+//!
+//! Each profile has its own database file, so runs, history, custom weights
+//! and settings stay apart for players sharing a machine. The default
+//! profile keeps the original `mt2_draft.db`, so an existing install carries
+//! on as that profile. The other databases sit beside it in the app data
+//! folder, which keeps OCR language packs and recordings shared.
+//!
+//! The profile list and the active profile are stored in `profiles.json`.
+//! Everything that holds the database path picks it up at launch, so
+//! switching profiles restarts the app.
+
+use crate::database::{self, LoadProgress, DATABASE_FILE};
+use crate::error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PROFILE_ID: &str = "default";
+const PROFILES_FILE: &str = "profiles.json";
+const MAX_NAME_LENGTH: usize = 40;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Profile {
+    /// Stable identifier, used in the database file name
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProfileList {
+    /// ID of the profile the app is running as
+    pub active: String,
+    pub profiles: Vec<Profile>,
+}
+
+impl Default for ProfileList {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE_ID.to_string(),
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: "Default".to_string(),
+            }],
+        }
+    }
+}
+
+impl ProfileList {
+    fn contains(&self, id: &str) -> bool {
+        self.profiles.iter().any(|profile| profile.id == id)
+    }
+}
+
+/// Database file for profile `id`
+pub fn database_path(app_dir: &Path, id: &str) -> PathBuf {
+    if id == DEFAULT_PROFILE_ID {
+        app_dir.join(DATABASE_FILE)
+    } else {
+        app_dir.join(format!("mt2_draft-{}.db", id))
+    }
+}
+
+/// Load the profile list, or the default profile alone if none was saved
+pub fn load(app_dir: &Path) -> AppResult<ProfileList> {
+    let path = app_dir.join(PROFILES_FILE);
+    if !path.exists() {
+        return Ok(ProfileList::default());
+    }
+    let json = fs::read_to_string(&path)
+        .map_err(|e| AppError::Internal(format!("Failed to read profiles: {}", e)))?;
+    let mut list: ProfileList = serde_json::from_str(&json)
+        .map_err(|e| AppError::Internal(format!("Invalid profiles file: {}", e)))?;
+    if !list.contains(DEFAULT_PROFILE_ID) {
+        list.profiles
+            .insert(0, ProfileList::default().profiles.remove(0));
+    }
+    if !list.contains(&list.active) {
+        list.active = DEFAULT_PROFILE_ID.to_string();
+    }
+    Ok(list)
+}
+
+fn save(app_dir: &Path, list: &ProfileList) -> AppResult<()> {
+    let json = serde_json::to_string_pretty(list).map_err(|e| AppError::Internal(e.to_string()))?;
+    fs::write(app_dir.join(PROFILES_FILE), json)
+        .map_err(|e| AppError::Internal(format!("Failed to save profiles: {}", e)))
+}
+
+/// Database of the active profile, falling back to the default profile's
+pub fn active_database_path(app_dir: &Path) -> PathBuf {
+    let active = load(app_dir).map(|list| list.active).unwrap_or_else(|e| {
+        log::warn!("Failed to load profiles, using the default profile: {}", e);
+        DEFAULT_PROFILE_ID.to_string()
+    });
+    database_path(app_dir, &active)
+}
+
+/// ID for a new profile named `name`, unique within `list`
+fn profile_id(list: &ProfileList, name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let base = if slug.is_empty() {
+        "profile".to_string()
+    } else {
+        slug
+    };
+
+    let mut id = base.clone();
+    let mut suffix = 2;
+    while list.contains(&id) {
+        id = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+    id
+}
+
+/// Add a profile and create its database, reporting seeding to `on_progress`
+pub fn create(
+    app_dir: &Path,
+    name: &str,
+    on_progress: &mut dyn FnMut(LoadProgress),
+) -> AppResult<Profile> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Profile name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(AppError::InvalidInput(format!(
+            "Profile name cannot be longer than {} characters",
+            MAX_NAME_LENGTH
+        )));
+    }
+
+    let mut list = load(app_dir)?;
+    if list
+        .profiles
+        .iter()
+        .any(|profile| profile.name.eq_ignore_ascii_case(name))
+    {
+        return Err(AppError::InvalidInput(format!(
+            "A profile named '{}' already exists",
+            name
+        )));
+    }
+
+    let profile = Profile {
+        id: profile_id(&list, name),
+        name: name.to_string(),
+    };
+    database::init_with_progress(&database_path(app_dir, &profile.id), on_progress)?;
+    list.profiles.push(profile.clone());
+    save(app_dir, &list)?;
+    Ok(profile)
+}
+
+/// Make `id` the active profile, from the next launch
+pub fn set_active(app_dir: &Path, id: &str) -> AppResult<ProfileList> {
+    let mut list = load(app_dir)?;
+    if !list.contains(id) {
+        return Err(AppError::NotFound(format!("Profile '{}' not found", id)));
+    }
+    list.active = id.to_string();
+    save(app_dir, &list)?;
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_profiles_keep_separate_databases() {
+        let dir = TempDir::new().unwrap();
+        let app_dir = dir.path();
+        assert_eq!(load(app_dir).unwrap(), ProfileList::default());
+        assert_eq!(active_database_path(app_dir), app_dir.join(DATABASE_FILE));
+
+        let alex = create(app_dir, "  Alex's runs ", &mut |_| {}).unwrap();
+        assert_eq!(alex.id, "alex-s-runs");
+        assert_eq!(alex.name, "Alex's runs");
+        assert!(database_path(app_dir, &alex.id).exists());
+        assert_eq!(
+            create(app_dir, "Alex-s runs", &mut |_| {}).unwrap().id,
+            "alex-s-runs-2"
+        );
+        assert!(matches!(
+            create(app_dir, "ALEX'S RUNS", &mut |_| {}),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            create(app_dir, " ", &mut |_| {}),
+            Err(AppError::InvalidInput(_))
+        ));
+
+        // Creating a profile doesn't switch to it
+        assert_eq!(load(app_dir).unwrap().active, DEFAULT_PROFILE_ID);
+        let list = set_active(app_dir, "alex-s-runs").unwrap();
+        assert_eq!(list.profiles.len(), 3);
+        assert_eq!(
+            active_database_path(app_dir),
+            app_dir.join("mt2_draft-alex-s-runs.db")
+        );
+        assert!(matches!(
+            set_active(app_dir, "missing"),
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,288 @@
+//! Deck archetypes
+//!
+//! An archetype is a plan a deck builds toward, such as a Valor frontline
+//! or a Funguy swarm. Each is defined by keyword weights and a few key
+//! cards, kept in the `archetypes` table so definitions can be tuned or
+//! added without a release. Once a deck clearly leans toward an archetype,
+//! cards that fit it get a scoring bonus.
+
+use crate::database::repository::CardData;
+use crate::scoring::curve::{self, Curve};
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Lowest confidence `detect` reports
+pub const MIN_CONFIDENCE: f64 = 0.15;
+/// Confidence a deck needs before its archetype affects scores
+const BONUS_MIN_CONFIDENCE: f64 = 0.3;
+/// Bonus for a card that fully fits an archetype the deck fully commits to
+const MAX_FIT_BONUS: f64 = 10.0;
+/// Decks smaller than this can't reach full confidence
+const FULL_CONFIDENCE_CARDS: usize = 5;
+
+fn default_active() -> bool {
+    true
+}
+
+/// One archetype definition
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Archetype {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// How much a card with each keyword fits, from 0 to 1
+    pub keyword_weights: BTreeMap<String, f64>,
+    /// Card IDs that fit fully whatever their keywords
+    #[serde(default)]
+    pub key_cards: Vec<String>,
+    /// Share of cards at each energy cost the archetype wants, if it has a
+    /// preference; see `curve`
+    #[serde(default)]
+    pub ideal_curve: Option<Curve>,
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+/// How well a deck fits one archetype
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ArchetypeMatch {
+    pub archetype_id: String,
+    pub name: String,
+    /// From 0 to 1
+    pub confidence: f64,
+    /// Deck cards that fit the archetype at all
+    pub matching_cards: Vec<String>,
+}
+
+impl Archetype {
+    /// How well one card fits, from 0 to 1
+    pub fn card_fit(&self, card: &CardData) -> f64 {
+        if self.key_cards.contains(&card.id) {
+            return 1.0;
+        }
+        card.keywords
+            .iter()
+            .filter_map(|keyword| self.keyword_weights.get(keyword))
+            .sum::<f64>()
+            .min(1.0)
+    }
+
+    /// Mean card fit, counting a small deck as padded with non-fitting cards
+    pub fn confidence(&self, deck: &[CardData]) -> f64 {
+        let fit: f64 = deck.iter().map(|card| self.card_fit(card)).sum();
+        fit / deck.len().max(FULL_CONFIDENCE_CARDS) as f64
+    }
+
+    /// Check a definition before it is saved
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("Archetype ID cannot be empty".to_string());
+        }
+        if self.name.trim().is_empty() {
+            return Err("Archetype name cannot be empty".to_string());
+        }
+        if let Some((keyword, _)) = self
+            .keyword_weights
+            .iter()
+            .find(|(_, weight)| !(0.0..=1.0).contains(*weight))
+        {
+            return Err(format!(
+                "Weight for keyword '{}' must be between 0 and 1",
+                keyword
+            ));
+        }
+        if let Some(ideal) = &self.ideal_curve {
+            curve::validate(ideal)?;
+        }
+        Ok(())
+    }
+}
+
+/// Active archetypes the deck fits, best first
+pub fn detect(deck: &[CardData], archetypes: &[Archetype]) -> Vec<ArchetypeMatch> {
+    let mut matches: Vec<ArchetypeMatch> = archetypes
+        .iter()
+        .filter(|archetype| archetype.active)
+        .map(|archetype| ArchetypeMatch {
+            archetype_id: archetype.id.clone(),
+            name: archetype.name.clone(),
+            confidence: archetype.confidence(deck),
+            matching_cards: deck
+                .iter()
+                .filter(|card| archetype.card_fit(card) > 0.0)
+                .map(|card| card.id.clone())
+                .collect(),
+        })
+        .filter(|m| m.confidence >= MIN_CONFIDENCE)
+        .collect();
+    matches.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    matches
+}
+
+/// The archetype `deck` leans toward most, with its confidence, once the
+/// deck clearly leans toward one
+pub fn leading<'a>(deck: &[CardData], archetypes: &'a [Archetype]) -> Option<(&'a Archetype, f64)> {
+    archetypes
+        .iter()
+        .filter(|archetype| archetype.active)
+        .map(|archetype| (archetype, archetype.confidence(deck)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .filter(|(_, confidence)| *confidence >= BONUS_MIN_CONFIDENCE)
+}
+
+/// Bonus for `card` fitting the archetype `deck` leans toward most, with
+/// that archetype's name
+pub fn fit_bonus<'a>(
+    card: &CardData,
+    deck: &[CardData],
+    archetypes: &'a [Archetype],
+) -> Option<(i32, &'a str)> {
+    let (archetype, confidence) = leading(deck, archetypes)?;
+    let bonus = (archetype.card_fit(card) * confidence * MAX_FIT_BONUS).round() as i32;
+    (bonus > 0).then_some((bonus, archetype.name.as_str()))
+}
+
+/// Every archetype, ordered by name
+pub fn load_archetypes(conn: &Connection) -> Result<Vec<Archetype>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, keyword_weights, key_cards, active, ideal_curve
+         FROM archetypes
+         ORDER BY name",
+    )?;
+    let archetypes = stmt
+        .query_map([], |row| {
+            let weights: String = row.get(3)?;
+            let key_cards: String = row.get(4)?;
+            let ideal_curve: Option<String> = row.get(6)?;
+            Ok(Archetype {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                description: row.get(2)?,
+                keyword_weights: serde_json::from_str(&weights).unwrap_or_default(),
+                key_cards: serde_json::from_str(&key_cards).unwrap_or_default(),
+                ideal_curve: ideal_curve.and_then(|json| serde_json::from_str(&json).ok()),
+                active: row.get(5)?,
+            })
+        })?
+        .collect();
+    archetypes
+}
+
+/// Insert an archetype, or replace the one with the same ID
+pub fn save_archetype(conn: &Connection, archetype: &Archetype) -> Result<()> {
+    let weights = serde_json::to_string(&archetype.keyword_weights)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let key_cards = serde_json::to_string(&archetype.key_cards)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let ideal_curve = archetype
+        .ideal_curve
+        .map(|ideal| serde_json::to_string(&ideal))
+        .transpose()
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "INSERT INTO archetypes (id, name, description, keyword_weights, key_cards, active, ideal_curve)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(id) DO UPDATE SET
+             name = excluded.name,
+             description = excluded.description,
+             keyword_weights = excluded.keyword_weights,
+             key_cards = excluded.key_cards,
+             active = excluded.active,
+             ideal_curve = excluded.ideal_curve",
+        params![
+            archetype.id,
+            archetype.name,
+            archetype.description,
+            weights,
+            key_cards,
+            archetype.active,
+            ideal_curve
+        ],
+    )?;
+    Ok(())
+}
+
+/// Delete an archetype; returns whether it existed
+pub fn delete_archetype(conn: &Connection, id: &str) -> Result<bool> {
+    Ok(conn.execute("DELETE FROM archetypes WHERE id = ?1", [id])? > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::cache::CardSnapshot;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_detect_and_fit_bonus() {
+        let temp = NamedTempFile::new().unwrap();
+        crate::database::init(temp.path()).unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+        let archetypes = load_archetypes(&conn).unwrap();
+        assert!(archetypes.iter().any(|a| a.id == "funguy_swarm"));
+
+        let ids = |ids: &[&str]| -> Vec<String> { ids.iter().map(|id| id.to_string()).collect() };
+        let deck = snapshot.get_many(&ids(&[
+            "underlegion_bolete",
+            "underlegion_madame_lionsmane",
+            "underlegion_funguy_in_a_suit",
+            "banished_cleave",
+        ]));
+        let matches = detect(&deck, &archetypes);
+        assert_eq!(matches[0].archetype_id, "funguy_swarm");
+        // Three of a padded five cards fit fully
+        assert!((matches[0].confidence - 0.6).abs() < 1e-9);
+        assert_eq!(matches[0].matching_cards.len(), 3);
+
+        let morel = snapshot.get("underlegion_morel_mistress").unwrap();
+        assert_eq!(
+            fit_bonus(morel, &deck, &archetypes),
+            Some((6, "Funguy Swarm"))
+        );
+        let cleave = snapshot.get("banished_cleave").unwrap();
+        assert_eq!(fit_bonus(cleave, &deck, &archetypes), None);
+        // Too early to tell with one card
+        assert_eq!(fit_bonus(morel, &deck[..1], &archetypes), None);
+    }
+
+    #[test]
+    fn test_save_and_delete() {
+        let temp = NamedTempFile::new().unwrap();
+        crate::database::init(temp.path()).unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+
+        let mut archetype = Archetype {
+            id: "armor_wall".to_string(),
+            name: "Armor Wall".to_string(),
+            description: String::new(),
+            keyword_weights: BTreeMap::from([("armor".to_string(), 1.0)]),
+            key_cards: vec!["hellhorned_titan_sentry".to_string()],
+            ideal_curve: Some([0.0, 0.2, 0.3, 0.3, 0.2]),
+            active: true,
+        };
+        assert!(archetype.validate().is_ok());
+        save_archetype(&conn, &archetype).unwrap();
+        archetype.active = false;
+        save_archetype(&conn, &archetype).unwrap();
+
+        let saved = load_archetypes(&conn).unwrap();
+        assert_eq!(
+            saved.iter().find(|a| a.id == "armor_wall"),
+            Some(&archetype)
+        );
+        // Inactive archetypes are never detected
+        assert!(detect(&[], &[archetype.clone()]).is_empty());
+
+        assert!(delete_archetype(&conn, "armor_wall").unwrap());
+        assert!(!delete_archetype(&conn, "armor_wall").unwrap());
+
+        archetype.ideal_curve = Some([0.0; 5]);
+        assert!(archetype.validate().is_err());
+        archetype.ideal_curve = None;
+        archetype.keyword_weights.insert("tank".to_string(), 1.5);
+        assert!(archetype.validate().is_err());
+    }
+}
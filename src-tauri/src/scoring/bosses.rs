@@ -0,0 +1,116 @@
+//! Boss lineup for boss-aware scoring
+//!
+//! Each ring ends in a boss fight, and some bosses only show up at higher
+//! covenants. The `bosses` table lists them with their signature mechanics,
+//! such as the Seraph's relentless spikes. When a request names the
+//! upcoming boss, context rules can test its mechanics with `boss(...)` and
+//! reward the cards that counter it.
+
+use rusqlite::{Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+/// A boss and the mechanics counters are chosen against
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Boss {
+    pub id: String,
+    pub name: String,
+    /// Ring the boss is fought at
+    pub ring: i32,
+    /// Lowest covenant the boss appears at
+    pub min_covenant: i32,
+    pub mechanics: Vec<String>,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl Boss {
+    pub fn has_mechanic(&self, mechanic: &str) -> bool {
+        self.mechanics
+            .iter()
+            .any(|m| m.eq_ignore_ascii_case(mechanic))
+    }
+}
+
+/// Every boss, ordered by ring then covenant
+pub fn load_bosses(conn: &Connection) -> Result<Vec<Boss>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, ring, min_covenant, mechanics, description
+         FROM bosses
+         ORDER BY ring, min_covenant, id",
+    )?;
+    let bosses = stmt.query_map([], row_to_boss)?.collect();
+    bosses
+}
+
+/// The bosses a run at `covenant` can still meet from `ring` on
+pub fn load_lineup(conn: &Connection, ring: i32, covenant: i32) -> Result<Vec<Boss>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, ring, min_covenant, mechanics, description
+         FROM bosses
+         WHERE ring >= ?1 AND min_covenant <= ?2
+         ORDER BY ring, min_covenant, id",
+    )?;
+    let bosses = stmt.query_map([ring, covenant], row_to_boss)?.collect();
+    bosses
+}
+
+/// The boss with ID or name `boss`, ignoring case and surrounding spaces
+pub fn load_boss(conn: &Connection, boss: &str) -> Result<Option<Boss>> {
+    conn.query_row(
+        "SELECT id, name, ring, min_covenant, mechanics, description
+         FROM bosses
+         WHERE id = ?1 COLLATE NOCASE OR name = ?1 COLLATE NOCASE",
+        [boss.trim()],
+        row_to_boss,
+    )
+    .optional()
+}
+
+fn row_to_boss(row: &rusqlite::Row) -> Result<Boss> {
+    let mechanics: String = row.get(4)?;
+    Ok(Boss {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        ring: row.get(2)?,
+        min_covenant: row.get(3)?,
+        mechanics: serde_json::from_str(&mechanics).unwrap_or_default(),
+        description: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_lineup() {
+        let temp = NamedTempFile::new().unwrap();
+        crate::database::init(temp.path()).unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+
+        let seraph = load_boss(&conn, " seraph the relentless ")
+            .unwrap()
+            .unwrap();
+        assert_eq!(seraph.id, "seraph_the_relentless");
+        assert!(seraph.has_mechanic("Spikes"));
+        assert_eq!(
+            load_boss(&conn, "seraph_the_relentless").unwrap(),
+            Some(seraph)
+        );
+        assert!(load_boss(&conn, "Nobody").unwrap().is_none());
+
+        // Covenant-gated bosses only join the lineup at their covenant
+        let low = load_lineup(&conn, 7, 0).unwrap();
+        assert!(low
+            .iter()
+            .all(|boss| boss.ring >= 7 && boss.min_covenant == 0));
+        assert!(low.iter().any(|boss| boss.id == "seraph_the_relentless"));
+        let high = load_lineup(&conn, 7, 25).unwrap();
+        assert!(high.len() > low.len());
+        assert_eq!(
+            load_bosses(&conn).unwrap().len(),
+            load_lineup(&conn, 1, 25).unwrap().len()
+        );
+    }
+}
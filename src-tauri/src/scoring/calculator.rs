@@ -1,22 +1,187 @@
 use crate::database::repository::CardData;
-use crate::scoring::{context, context::ContextModifier, synergies::Synergy};
+use crate::scoring::context::PenaltyKind;
+use crate::scoring::{
+    archetypes, archetypes::Archetype, bosses::Boss, champion_paths::ChampionPath, context,
+    context::ContextModifier, curve, synergies::Synergy,
+};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 const SYNERGY_CAP: f64 = 1.5;
-const MAX_SCORE: i32 = 120;
 const S_TIER_THRESHOLD: i32 = 90;
 const A_TIER_THRESHOLD: i32 = 80;
 const B_TIER_THRESHOLD: i32 = 70;
+/// Completed runs needed before personal win rate affects the score
+const EMPIRICAL_MIN_RUNS: u32 = 5;
+/// Largest score change (either direction) from personal win rate
+const EMPIRICAL_MAX_ADJUSTMENT: i32 = 10;
+/// Games needed before a community win rate affects the score
+const COMMUNITY_MIN_GAMES: u32 = 50;
+/// Score per upgrade an offered card comes with
+const UPGRADE_BONUS: i32 = 4;
+/// Score per upgrade when the deck already holds a plain copy, which the
+/// upgraded one outclasses without costing an upgrade slot
+const DUPLICATE_UPGRADE_BONUS: i32 = 8;
+/// Largest score increase from upgrades
+const UPGRADE_MAX_BONUS: i32 = 20;
+
+/// One part of the explanation for a score
+///
+/// Serialized as a stable `code` with its `args`, e.g.
+/// `{"code": "synergy_bonus", "args": {"percent": 25}}`, so the frontend can
+/// localize it and tests can match on it; `Display` gives the English text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "code", content = "args", rename_all = "snake_case")]
+pub enum ScoreReason {
+    SynergyBonus {
+        percent: i32,
+    },
+    /// Sum of the context modifiers that raised the score
+    Context {
+        bonus: i32,
+    },
+    /// A context modifier that lowered the score, such as capacity pressure
+    ContextPenalty {
+        kind: PenaltyKind,
+        penalty: i32,
+    },
+    ChampionFavorite {
+        champion: String,
+    },
+    EarlyGameTempo,
+    LateGameValue,
+    ArchetypeFit {
+        archetype: String,
+        bonus: i32,
+    },
+    /// The card feeds the chosen champion path's scaling
+    PathScaling {
+        path: String,
+        bonus: i32,
+    },
+    /// The deck's average energy cost runs over its ideal curve
+    EnergyCurve {
+        average_cost: f64,
+        ideal_cost: f64,
+        adjustment: i32,
+    },
+    /// Adjustment from the player's own completed runs with the card
+    History {
+        win_rate_percent: i32,
+        runs: u32,
+        adjustment: i32,
+    },
+    /// Adjustment from imported community win rates
+    Community {
+        win_rate_percent: i32,
+        games: u32,
+        adjustment: i32,
+    },
+    /// The offered card comes with upgrades; `duplicate` when the deck
+    /// already holds a copy
+    Upgraded {
+        upgrades: u32,
+        duplicate: bool,
+        bonus: i32,
+    },
+}
+
+impl fmt::Display for ScoreReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoreReason::SynergyBonus { percent } => write!(f, "Synergy bonus: {}%", percent),
+            ScoreReason::Context { bonus } => write!(f, "Context: +{}", bonus),
+            ScoreReason::ContextPenalty { kind, penalty } => match kind {
+                PenaltyKind::Capacity { space, capacity } => write!(
+                    f,
+                    "Capacity pressure: {} of {} floor space ({})",
+                    space, capacity, penalty
+                ),
+                PenaltyKind::Boss { boss } => write!(f, "Boss counter: {} ({})", boss, penalty),
+                PenaltyKind::PactShards { pact_shards } => {
+                    write!(f, "Endless: {} pact shards ({})", pact_shards, penalty)
+                }
+                PenaltyKind::DeckSize { cards } => {
+                    write!(f, "Deck of {} cards ({})", cards, penalty)
+                }
+                PenaltyKind::Tag { tag } => write!(f, "Context: {} ({})", tag, penalty),
+            },
+            ScoreReason::ChampionFavorite { champion } => {
+                write!(f, "Champion favorite: {}", champion)
+            }
+            ScoreReason::EarlyGameTempo => write!(f, "Early game tempo"),
+            ScoreReason::LateGameValue => write!(f, "Late game value"),
+            ScoreReason::ArchetypeFit { archetype, bonus } => {
+                write!(f, "Fits {}: +{}", archetype, bonus)
+            }
+            ScoreReason::PathScaling { path, bonus } => {
+                write!(f, "Feeds {} path: +{}", path, bonus)
+            }
+            ScoreReason::EnergyCurve {
+                average_cost,
+                ideal_cost,
+                adjustment,
+            } => write!(
+                f,
+                "Energy curve: average {:.1} vs ideal {:.1} ({:+})",
+                average_cost, ideal_cost, adjustment
+            ),
+            ScoreReason::History {
+                win_rate_percent,
+                runs,
+                adjustment,
+            } => write!(
+                f,
+                "Your history: {}% wins over {} runs ({:+})",
+                win_rate_percent, runs, adjustment
+            ),
+            ScoreReason::Community {
+                win_rate_percent,
+                games,
+                adjustment,
+            } => write!(
+                f,
+                "Community: {}% wins over {} games ({:+})",
+                win_rate_percent, games, adjustment
+            ),
+            ScoreReason::Upgraded {
+                upgrades,
+                duplicate: true,
+                bonus,
+            } => write!(f, "Upgraded copy of a deck card x{}: +{}", upgrades, bonus),
+            ScoreReason::Upgraded {
+                upgrades,
+                duplicate: false,
+                bonus,
+            } => write!(f, "Comes upgraded x{}: +{}", upgrades, bonus),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScoringResult {
+    /// Raw score, unbounded above so strong cards stay apart
     pub score: i32,
     pub tier: String,
     pub base_value: i32,
     pub synergy_multiplier: f64,
     pub context_bonus: i32,
     pub champion_bonus: i32,
-    pub reasons: Vec<String>,
+    /// Bonus for fitting the archetype the deck leans toward
+    #[serde(default)]
+    pub archetype_bonus: i32,
+    /// Bonus for feeding the chosen champion path's scaling
+    #[serde(default)]
+    pub path_bonus: i32,
+    /// Bonus for cheap cards, or penalty for expensive ones, when the deck's
+    /// energy curve is top-heavy
+    #[serde(default)]
+    pub curve_adjustment: i32,
+    /// Where the score falls among the champion's card pool, from 0 to 100,
+    /// when the pool was scored too
+    #[serde(default)]
+    pub percentile: Option<f64>,
+    pub reasons: Vec<ScoreReason>,
 }
 
 pub struct ScoreCalculator;
@@ -63,6 +228,7 @@ impl ScoreCalculator {
         multiplier.min(SYNERGY_CAP)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn calculate_full(
         &self,
         card: &CardData,
@@ -70,9 +236,13 @@ impl ScoreCalculator {
         champion: &str,
         ring_number: i32,
         covenant: i32,
+        pact_shards: i32,
         synergies: &[Synergy],
         context_modifiers: &[ContextModifier],
         champion_override: Option<i32>,
+        archetypes: &[Archetype],
+        champion_path: Option<&ChampionPath>,
+        upcoming_boss: Option<&Boss>,
     ) -> ScoringResult {
         let mut reasons = Vec::new();
 
@@ -85,21 +255,45 @@ impl ScoreCalculator {
         let synergy_score = (base_value as f64 * synergy_multiplier) as i32;
 
         if synergy_multiplier > 1.0 {
-            reasons.push(format!(
-                "Synergy bonus: {:.0}%",
-                (synergy_multiplier - 1.0) * 100.0
-            ));
+            reasons.push(ScoreReason::SynergyBonus {
+                percent: ((synergy_multiplier - 1.0) * 100.0).round() as i32,
+            });
         }
 
         // 3. Context bonus
-        let context_bonus = context::calculate_context_bonus(card, current_deck, context_modifiers);
-        if context_bonus > 0 {
-            reasons.push(format!("Context: +{}", context_bonus));
+        let applied = context::applied_modifiers(
+            card,
+            current_deck,
+            ring_number,
+            covenant,
+            pact_shards,
+            upcoming_boss,
+            context_modifiers,
+        );
+        let context_bonus = applied.iter().map(|modifier| modifier.modifier).sum();
+        // The reasons split the net bonus into what raised and what lowered it
+        let gross_bonus = applied
+            .iter()
+            .map(|modifier| modifier.modifier.max(0))
+            .sum();
+        if gross_bonus > 0 {
+            reasons.push(ScoreReason::Context { bonus: gross_bonus });
         }
+        reasons.extend(
+            applied
+                .iter()
+                .filter(|modifier| modifier.modifier < 0)
+                .map(|modifier| ScoreReason::ContextPenalty {
+                    kind: modifier.penalty_kind(card, current_deck, pact_shards, upcoming_boss),
+                    penalty: modifier.modifier,
+                }),
+        );
 
         // 4. Champion override
         let champion_bonus = if let Some(override_val) = champion_override {
-            reasons.push(format!("Champion favorite: {}", champion));
+            reasons.push(ScoreReason::ChampionFavorite {
+                champion: champion.to_string(),
+            });
             override_val - base_value
         } else {
             0
@@ -107,29 +301,69 @@ impl ScoreCalculator {
 
         // 5. Ring adjustment
         let ring_adjustment = if ring_number <= 3 && card.tempo_score > card.value_score {
-            reasons.push("Early game tempo".to_string());
+            reasons.push(ScoreReason::EarlyGameTempo);
             10
         } else if ring_number >= 6 && card.value_score > card.tempo_score {
-            reasons.push("Late game value".to_string());
+            reasons.push(ScoreReason::LateGameValue);
             10
         } else {
             0
         };
 
+        // 6. Archetype fit
+        let archetype_bonus = match archetypes::fit_bonus(card, current_deck, archetypes) {
+            Some((bonus, name)) => {
+                reasons.push(ScoreReason::ArchetypeFit {
+                    archetype: name.to_string(),
+                    bonus,
+                });
+                bonus
+            }
+            None => 0,
+        };
+
+        // 7. Champion path scaling, on top of any flat override
+        let path_bonus = match champion_path {
+            Some(path) => {
+                let bonus = path.scaling_bonus(card, current_deck);
+                if bonus > 0 {
+                    reasons.push(ScoreReason::PathScaling {
+                        path: path.path.clone(),
+                        bonus,
+                    });
+                }
+                bonus
+            }
+            None => 0,
+        };
+
+        // 8. Energy curve
+        let curve_adjustment = match curve::analyze(current_deck, archetypes) {
+            Some(analysis) => {
+                let adjustment = analysis.adjustment(card);
+                if adjustment != 0 {
+                    reasons.push(ScoreReason::EnergyCurve {
+                        average_cost: (analysis.average_cost * 10.0).round() / 10.0,
+                        ideal_cost: (analysis.ideal_average * 10.0).round() / 10.0,
+                        adjustment,
+                    });
+                }
+                adjustment
+            }
+            None => 0,
+        };
+
         // Calculate final score
-        let score =
-            (synergy_score + context_bonus + champion_bonus + ring_adjustment).min(MAX_SCORE);
+        let score = synergy_score
+            + context_bonus
+            + champion_bonus
+            + ring_adjustment
+            + archetype_bonus
+            + path_bonus
+            + curve_adjustment;
 
         // Determine tier
-        let tier = if score >= S_TIER_THRESHOLD {
-            "S".to_string()
-        } else if score >= A_TIER_THRESHOLD {
-            "A".to_string()
-        } else if score >= B_TIER_THRESHOLD {
-            "B".to_string()
-        } else {
-            "C".to_string()
-        };
+        let tier = Self::tier_for(score);
 
         ScoringResult {
             score,
@@ -138,10 +372,123 @@ impl ScoreCalculator {
             synergy_multiplier,
             context_bonus,
             champion_bonus,
+            archetype_bonus,
+            path_bonus,
+            curve_adjustment,
+            percentile: None,
             reasons,
         }
     }
 
+    /// Nudge a score by the card's win rate in the player's own completed runs
+    ///
+    /// A 50% win rate is neutral; each 5% above or below moves the score by
+    /// one point, capped at `EMPIRICAL_MAX_ADJUSTMENT`. Ignored until the card
+    /// has `EMPIRICAL_MIN_RUNS` completed runs.
+    pub fn apply_empirical_adjustment(
+        &self,
+        result: &mut ScoringResult,
+        win_rate: Option<f64>,
+        runs_completed: u32,
+    ) {
+        let Some(win_rate) = win_rate else { return };
+        if runs_completed < EMPIRICAL_MIN_RUNS {
+            return;
+        }
+
+        let adjustment = (((win_rate - 0.5) * 20.0).round() as i32)
+            .clamp(-EMPIRICAL_MAX_ADJUSTMENT, EMPIRICAL_MAX_ADJUSTMENT);
+        if adjustment == 0 {
+            return;
+        }
+
+        result.score = (result.score + adjustment).max(0);
+        result.tier = Self::tier_for(result.score);
+        result.reasons.push(ScoreReason::History {
+            win_rate_percent: (win_rate * 100.0).round() as i32,
+            runs: runs_completed,
+            adjustment,
+        });
+    }
+
+    /// Nudge a score by the card's community win rate, scaled by `weight`
+    ///
+    /// At full weight this moves the score as the player's own win rate
+    /// would. Ignored until the card has `COMMUNITY_MIN_GAMES` games.
+    pub fn apply_community_adjustment(
+        &self,
+        result: &mut ScoringResult,
+        win_rate: f64,
+        games: u32,
+        weight: f64,
+    ) {
+        if games < COMMUNITY_MIN_GAMES {
+            return;
+        }
+
+        let adjustment = (((win_rate - 0.5) * 20.0 * weight).round() as i32)
+            .clamp(-EMPIRICAL_MAX_ADJUSTMENT, EMPIRICAL_MAX_ADJUSTMENT);
+        if adjustment == 0 {
+            return;
+        }
+
+        result.score = (result.score + adjustment).max(0);
+        result.tier = Self::tier_for(result.score);
+        result.reasons.push(ScoreReason::Community {
+            win_rate_percent: (win_rate * 100.0).round() as i32,
+            games,
+            adjustment,
+        });
+    }
+
+    /// Raise the score of a card offered with `upgrade_count` upgrades
+    ///
+    /// Upgrades are worth more on a card the deck already holds
+    /// `copies_in_deck` plain copies of, since the upgraded copy is a strict
+    /// improvement over drafting the same card again. Capped at
+    /// `UPGRADE_MAX_BONUS`.
+    pub fn apply_upgrade_adjustment(
+        &self,
+        result: &mut ScoringResult,
+        upgrade_count: u32,
+        copies_in_deck: usize,
+    ) {
+        if upgrade_count == 0 {
+            return;
+        }
+
+        let duplicate = copies_in_deck > 0;
+        let per_upgrade = if duplicate {
+            DUPLICATE_UPGRADE_BONUS
+        } else {
+            UPGRADE_BONUS
+        };
+        let bonus = upgrade_count
+            .saturating_mul(per_upgrade as u32)
+            .min(UPGRADE_MAX_BONUS as u32) as i32;
+
+        result.score += bonus;
+        result.tier = Self::tier_for(result.score);
+        result.reasons.push(ScoreReason::Upgraded {
+            upgrades: upgrade_count,
+            duplicate,
+            bonus,
+        });
+    }
+
+    /// Letter tier ("S" to "C") for a score
+    pub fn tier_for(score: i32) -> String {
+        if score >= S_TIER_THRESHOLD {
+            "S".to_string()
+        } else if score >= A_TIER_THRESHOLD {
+            "A".to_string()
+        } else if score >= B_TIER_THRESHOLD {
+            "B".to_string()
+        } else {
+            "C".to_string()
+        }
+    }
+
     pub fn calculate_with_database(
         &self,
         card_id: &str,
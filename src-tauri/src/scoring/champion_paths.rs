@@ -0,0 +1,133 @@
+//! Champion upgrade path scaling
+//!
+//! Champion overrides are flat values per card. An upgrade path also scales
+//! with what the deck feeds it: Fel's Unchained path grows with every Shift,
+//! so each Shift card is worth more when the deck already has several. The
+//! `champion_paths` table lists each path's scaling keywords, and cards with
+//! them get a bonus that grows with the deck's existing feeders.
+
+use crate::database::repository::CardData;
+use rusqlite::{Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Bonus for a card that fully feeds a path no deck card feeds yet
+const BASE_PATH_BONUS: f64 = 4.0;
+/// Extra bonus per deck card already feeding the path
+const PER_FEEDER_BONUS: f64 = 1.0;
+/// Feeders past this add nothing more
+const MAX_FEEDERS: f64 = 8.0;
+
+/// One champion upgrade path and what it scales with
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChampionPath {
+    pub champion: String,
+    pub path: String,
+    #[serde(default)]
+    pub description: String,
+    /// How much a card with each keyword feeds the path, from 0 to 1
+    pub scaling_keywords: BTreeMap<String, f64>,
+}
+
+impl ChampionPath {
+    /// How much one card feeds the path, from 0 to 1
+    pub fn feed(&self, card: &CardData) -> f64 {
+        card.keywords
+            .iter()
+            .filter_map(|keyword| self.scaling_keywords.get(keyword))
+            .sum::<f64>()
+            .min(1.0)
+    }
+
+    /// Bonus for `card` feeding the path, given how much `deck` already does
+    pub fn scaling_bonus(&self, card: &CardData, deck: &[CardData]) -> i32 {
+        let feed = self.feed(card);
+        if feed <= 0.0 {
+            return 0;
+        }
+        let feeders: f64 = deck.iter().map(|c| self.feed(c)).sum();
+        (feed * (BASE_PATH_BONUS + PER_FEEDER_BONUS * feeders.min(MAX_FEEDERS))).round() as i32
+    }
+}
+
+/// Every path, ordered by champion then path
+pub fn load_champion_paths(conn: &Connection) -> Result<Vec<ChampionPath>> {
+    let mut stmt = conn.prepare(
+        "SELECT champion, path, description, scaling_keywords
+         FROM champion_paths
+         ORDER BY champion, path",
+    )?;
+    let paths = stmt.query_map([], row_to_path)?.collect();
+    paths
+}
+
+/// The path named `path` for `champion`, ignoring case and surrounding spaces
+pub fn load_champion_path(
+    conn: &Connection,
+    champion: &str,
+    path: &str,
+) -> Result<Option<ChampionPath>> {
+    conn.query_row(
+        "SELECT champion, path, description, scaling_keywords
+         FROM champion_paths
+         WHERE champion = ?1 COLLATE NOCASE AND path = ?2 COLLATE NOCASE",
+        [champion.trim(), path.trim()],
+        row_to_path,
+    )
+    .optional()
+}
+
+fn row_to_path(row: &rusqlite::Row) -> Result<ChampionPath> {
+    let keywords: String = row.get(3)?;
+    Ok(ChampionPath {
+        champion: row.get(0)?,
+        path: row.get(1)?,
+        description: row.get(2)?,
+        scaling_keywords: serde_json::from_str(&keywords).unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::cache::CardSnapshot;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_scaling_bonus() {
+        let temp = NamedTempFile::new().unwrap();
+        crate::database::init(temp.path()).unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+
+        let unchained = load_champion_path(&conn, "fel", " Unchained ")
+            .unwrap()
+            .unwrap();
+        assert_eq!(unchained.path, "Unchained");
+        assert!(load_champion_path(&conn, "Fel", "Nowhere")
+            .unwrap()
+            .is_none());
+        assert!(load_champion_paths(&conn)
+            .unwrap()
+            .iter()
+            .any(|p| p.champion == "Fel" && p.path == "Savior"));
+
+        let shift_cards: Vec<CardData> = snapshot
+            .cards()
+            .iter()
+            .filter(|card| card.keywords.iter().any(|k| k == "shift"))
+            .cloned()
+            .collect();
+        assert!(shift_cards.len() >= 3);
+        let card = &shift_cards[0];
+        let deck = &shift_cards[1..3];
+
+        // Each Shift card already in the deck makes the next worth more
+        assert_eq!(unchained.scaling_bonus(card, &[]), 4);
+        assert_eq!(unchained.scaling_bonus(card, &deck[..1]), 5);
+        assert_eq!(unchained.scaling_bonus(card, deck), 6);
+
+        let cleave = snapshot.get("banished_cleave").unwrap();
+        assert_eq!(unchained.scaling_bonus(cleave, deck), 0);
+    }
+}
@@ -0,0 +1,140 @@
+//! What-if comparison of draft picks
+//!
+//! A draft score ranks the offered cards, but not what each does to the
+//! deck. The comparison adds each candidate to the deck in turn and reports
+//! how the deck's analysis changes: synergy pairs gained, weaknesses fixed
+//! or introduced, and the shift in average energy cost, so the UI can say
+//! "picking A fixes your missing frontline, picking B doesn't".
+
+use crate::database::repository::CardData;
+use crate::map::{self, Weakness};
+use crate::scoring::curve;
+use crate::scoring::pool::{CardPool, ScoringTables};
+use crate::scoring::synergies;
+use serde::{Deserialize, Serialize};
+use std::mem;
+
+/// Deck analysis the comparison tracks
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeckMetrics {
+    /// Pairs of deck cards with a synergy between them
+    pub synergy_count: usize,
+    pub weaknesses: Vec<Weakness>,
+    /// Mean energy cost, once enough cards have a cost
+    pub average_cost: Option<f64>,
+}
+
+/// What picking one candidate does to the deck
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PickComparison {
+    pub card_id: String,
+    pub card_name: String,
+    /// Draft score against the current deck
+    pub score: i32,
+    /// Synergy pairs gained
+    pub synergies_added: usize,
+    /// Weaknesses the deck no longer has
+    pub fixes: Vec<Weakness>,
+    /// Weaknesses the deck would gain
+    pub introduces: Vec<Weakness>,
+    /// Change in average energy cost, when both sides have one
+    pub average_cost_change: Option<f64>,
+    /// The deck's analysis with the card added
+    pub metrics: DeckMetrics,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PickComparisons {
+    /// The deck's analysis as it stands
+    pub current: DeckMetrics,
+    /// Highest score first
+    pub picks: Vec<PickComparison>,
+}
+
+/// Analyze `deck`, scoring its cards with `pool` at `ring`
+pub fn deck_metrics(
+    pool: &CardPool,
+    tables: &ScoringTables,
+    deck: &[CardData],
+    ring: i32,
+) -> DeckMetrics {
+    let ids: Vec<String> = deck.iter().map(|card| card.id.clone()).collect();
+    DeckMetrics {
+        synergy_count: synergies::get_deck_synergies(&ids, &tables.synergies).len(),
+        weaknesses: map::find_weaknesses(pool, &tables.archetypes, deck, ring, None),
+        average_cost: curve::analyze(deck, &tables.archetypes)
+            .map(|analysis| analysis.average_cost),
+    }
+}
+
+/// Weaknesses in `from` of a kind missing from `to`
+///
+/// Compared by kind, so a change in how many weak cards there are is
+/// neither fixed nor introduced.
+fn missing_from(from: &[Weakness], to: &[Weakness]) -> Vec<Weakness> {
+    from.iter()
+        .filter(|weakness| {
+            !to.iter()
+                .any(|other| mem::discriminant(*weakness) == mem::discriminant(other))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Compare adding each of `candidates` to `deck` at `ring`
+pub fn compare_picks(
+    pool: &CardPool,
+    tables: &ScoringTables,
+    deck: &[CardData],
+    candidates: &[CardData],
+    ring: i32,
+) -> PickComparisons {
+    let _span = tracing::info_span!("scoring.compare").entered();
+
+    let current = deck_metrics(pool, tables, deck, ring);
+    let mut picks: Vec<PickComparison> = candidates
+        .iter()
+        .map(|card| {
+            let mut with_card = deck.to_vec();
+            with_card.push(card.clone());
+            let metrics = deck_metrics(pool, tables, &with_card, ring);
+            PickComparison {
+                card_id: card.id.clone(),
+                card_name: card.name.clone(),
+                score: pool.score(card, deck, ring),
+                synergies_added: metrics.synergy_count.saturating_sub(current.synergy_count),
+                fixes: missing_from(&current.weaknesses, &metrics.weaknesses),
+                introduces: missing_from(&metrics.weaknesses, &current.weaknesses),
+                average_cost_change: current
+                    .average_cost
+                    .zip(metrics.average_cost)
+                    .map(|(before, after)| after - before),
+                metrics,
+            }
+        })
+        .collect();
+    picks.sort_by_key(|pick| -pick.score);
+
+    PickComparisons { current, picks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_from() {
+        let before = vec![
+            Weakness::NoFrontline,
+            Weakness::WeakCards { count: 1 },
+            Weakness::FewUnits,
+        ];
+        let after = vec![
+            Weakness::WeakCards { count: 2 },
+            Weakness::FewUnits,
+            Weakness::TopHeavy,
+        ];
+        assert_eq!(missing_from(&before, &after), vec![Weakness::NoFrontline]);
+        assert_eq!(missing_from(&after, &before), vec![Weakness::TopHeavy]);
+    }
+}
@@ -1,107 +1,146 @@
 use crate::database::repository::CardData;
+use crate::scoring::bosses::Boss;
+use crate::scoring::rules::{self, Input, Rule, RuleContext, FLOOR_CAPACITY, TRAIN_FLOORS};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ContextModifier {
-    pub condition: String,
+    pub condition: Rule,
     pub card_tag: String,
     pub modifier: i32,
     pub priority: String,
     pub description: String,
 }
 
+/// What a context modifier that lowered a score held against the card
+///
+/// Worked out from the values the modifier's condition reads, so each kind
+/// carries the numbers behind it rather than the modifier's description.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PenaltyKind {
+    /// Drafting the unit would bring the deck's floor space to `space`
+    Capacity { space: i64, capacity: i64 },
+    /// The upcoming boss punishes cards like this one
+    Boss { boss: String },
+    /// Endless-mode enemy scaling
+    PactShards { pact_shards: i32 },
+    /// The deck already holds `cards` cards
+    DeckSize { cards: usize },
+    /// Any other condition, by the card tag the modifier applies to
+    Tag { tag: String },
+}
+
+impl ContextModifier {
+    /// What this modifier holds against `card` when it lowers its score
+    pub fn penalty_kind(
+        &self,
+        card: &CardData,
+        current_deck: &[CardData],
+        pact_shards: i32,
+        upcoming_boss: Option<&Boss>,
+    ) -> PenaltyKind {
+        let reads = |input| self.condition.reads(input);
+        if reads(Input::Capacity) {
+            PenaltyKind::Capacity {
+                space: rules::floor_space(current_deck) + card.size.unwrap_or(0) as i64,
+                capacity: TRAIN_FLOORS * FLOOR_CAPACITY,
+            }
+        } else if let Some(boss) = upcoming_boss.filter(|_| reads(Input::Boss)) {
+            PenaltyKind::Boss {
+                boss: boss.id.clone(),
+            }
+        } else if reads(Input::PactShards) {
+            PenaltyKind::PactShards { pact_shards }
+        } else if reads(Input::DeckSize) {
+            PenaltyKind::DeckSize {
+                cards: current_deck.len(),
+            }
+        } else {
+            PenaltyKind::Tag {
+                tag: self.card_tag.clone(),
+            }
+        }
+    }
+}
+
+/// Card tag that matches every card
+pub const ANY_CARD_TAG: &str = "*";
+
 pub fn calculate_context_bonus(
     card: &CardData,
     current_deck: &[CardData],
+    ring_number: i32,
+    covenant: i32,
+    pact_shards: i32,
+    upcoming_boss: Option<&Boss>,
     modifiers: &[ContextModifier],
 ) -> i32 {
-    let mut total_bonus = 0;
-    
-    for modifier in modifiers {
-        if should_apply_modifier(card, current_deck, modifier) {
-            total_bonus += modifier.modifier;
-        }
-    }
-    
-    total_bonus
+    applied_modifiers(
+        card,
+        current_deck,
+        ring_number,
+        covenant,
+        pact_shards,
+        upcoming_boss,
+        modifiers,
+    )
+    .iter()
+    .map(|modifier| modifier.modifier)
+    .sum()
 }
 
-fn should_apply_modifier(
+/// The modifiers that apply to `card` in this context
+pub fn applied_modifiers<'a>(
     card: &CardData,
     current_deck: &[CardData],
-    modifier: &ContextModifier,
-) -> bool {
+    ring_number: i32,
+    covenant: i32,
+    pact_shards: i32,
+    upcoming_boss: Option<&Boss>,
+    modifiers: &'a [ContextModifier],
+) -> Vec<&'a ContextModifier> {
+    let ctx = RuleContext {
+        card,
+        deck: current_deck,
+        ring: ring_number,
+        covenant,
+        pact_shards,
+        boss: upcoming_boss,
+    };
+    modifiers
+        .iter()
+        .filter(|modifier| should_apply_modifier(&ctx, modifier))
+        .collect()
+}
+
+fn should_apply_modifier(ctx: &RuleContext, modifier: &ContextModifier) -> bool {
     // Check if card has the required tag
-    if !card.keywords.iter().any(|k| k == &modifier.card_tag) {
+    if modifier.card_tag != ANY_CARD_TAG
+        && !ctx.card.keywords.iter().any(|k| k == &modifier.card_tag)
+    {
         return false;
     }
     
     // Check the condition
-    match modifier.condition.as_str() {
-        "missing_frontline" => {
-            // Check if deck lacks frontline units
-            !current_deck.iter().any(|c| {
-                c.keywords.iter().any(|k| k == "frontline" || k == "tank")
-            })
-        }
-        "missing_backline_clear" => {
-            // Check if deck lacks backline clear
-            !current_deck.iter().any(|c| {
-                c.keywords.iter().any(|k| k == "sweep" || k == "explosive" || k == "advance")
-            })
-        }
-        "has_reform_synergy" => {
-            // Check if deck has Reform cards
-            current_deck.iter().any(|c| {
-                c.keywords.iter().any(|k| k == "reform")
-            })
-        }
-        "has_consume_synergy" => {
-            // Check if deck has Consume triggers
-            current_deck.iter().any(|c| {
-                c.keywords.iter().any(|k| k == "consume")
-            })
-        }
-        "deck_size_over_20" => {
-            current_deck.len() > 20
-        }
-        "covenant_high" => {
-            // This would need covenant parameter
-            false
-        }
-        "ring_early" => {
-            // This would need ring parameter
-            false
-        }
-        "ring_late" => {
-            // This would need ring parameter
-            false
-        }
-        "duplicate_common" => {
-            // Check for duplicate commons
-            let common_count = current_deck
-                .iter()
-                .filter(|c| c.rarity == "Common" && c.id == card.id)
-                .count();
-            common_count >= 2
-        }
-        "has_forge_synergy" => {
-            current_deck.iter().any(|c| {
-                c.keywords.iter().any(|k| k == "forge")
-            })
-        }
-        "has_smelt_synergy" => {
-            current_deck.iter().any(|c| {
-                c.keywords.iter().any(|k| k == "smelt")
-            })
-        }
-        _ => false,
-    }
+    modifier.condition.evaluate(ctx)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     
+    fn context<'a>(card: &'a CardData, deck: &'a [CardData]) -> RuleContext<'a> {
+        RuleContext {
+            card,
+            deck,
+            ring: 1,
+            covenant: 10,
+            pact_shards: 0,
+            boss: None,
+        }
+    }
+    
     fn create_test_card_with_tags(id: &str, tags: Vec<&str>) -> CardData {
         CardData {
             id: id.to_string(),
@@ -110,6 +149,7 @@ mod tests {
             card_type: "Unit".to_string(),
             rarity: "Common".to_string(),
             cost: Some(1),
+            size: None,
             base_value: 70,
             tempo_score: 6,
             value_score: 7,
@@ -125,14 +165,14 @@ mod tests {
         let empty_deck: Vec<CardData> = vec![];
         
         let modifier = ContextModifier {
-            condition: "missing_frontline".to_string(),
+            condition: "count(keyword:frontline|tank) == 0".parse().unwrap(),
             card_tag: "frontline".to_string(),
             modifier: 15,
             priority: "High".to_string(),
             description: "No tank".to_string(),
         };
         
-        assert!(should_apply_modifier(&tank_card, &empty_deck, &modifier));
+        assert!(should_apply_modifier(&context(&tank_card, &empty_deck), &modifier));
     }
     
     #[test]
@@ -141,14 +181,14 @@ mod tests {
         let existing_tank = create_test_card_with_tags("existing", vec!["frontline"]);
         
         let modifier = ContextModifier {
-            condition: "missing_frontline".to_string(),
+            condition: "count(keyword:frontline|tank) == 0".parse().unwrap(),
             card_tag: "frontline".to_string(),
             modifier: 15,
             priority: "High".to_string(),
             description: "No tank".to_string(),
         };
         
-        assert!(!should_apply_modifier(&tank_card, &[existing_tank], &modifier));
+        assert!(!should_apply_modifier(&context(&tank_card, &[existing_tank]), &modifier));
     }
     
     #[test]
@@ -157,13 +197,13 @@ mod tests {
         let empty_deck: Vec<CardData> = vec![];
         
         let modifier = ContextModifier {
-            condition: "missing_backline_clear".to_string(),
+            condition: "count(keyword:sweep|explosive|advance) == 0".parse().unwrap(),
             card_tag: "sweep".to_string(),
             modifier: 20,
             priority: "Critical".to_string(),
             description: "No clear".to_string(),
         };
         
-        assert!(should_apply_modifier(&sweep_card, &empty_deck, &modifier));
+        assert!(should_apply_modifier(&context(&sweep_card, &empty_deck), &modifier));
     }
 }
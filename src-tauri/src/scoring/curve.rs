@@ -0,0 +1,216 @@
+//! Energy curve analysis
+//!
+//! Compares the deck's cost histogram with an ideal curve, from the
+//! archetype the deck leans toward or a general default. Once the deck's
+//! average cost runs clearly above the ideal, cheap cards get a bonus and
+//! expensive bombs a penalty, growing with how top-heavy the deck is.
+
+use crate::database::repository::CardData;
+use crate::scoring::archetypes::{self, Archetype};
+use serde::{Deserialize, Serialize};
+
+/// Cost buckets: 0, 1, 2, 3 and 4+ energy
+pub const COST_BUCKETS: usize = 5;
+
+/// Share of cards wanted at each cost bucket
+pub type Curve = [f64; COST_BUCKETS];
+
+/// Ideal curve for decks without a clear archetype
+pub const DEFAULT_CURVE: Curve = [0.10, 0.30, 0.30, 0.20, 0.10];
+
+/// Costed cards needed before the curve affects scores
+const MIN_COSTED_CARDS: usize = 4;
+/// How far the average cost may run over the ideal before it counts
+const TOP_HEAVY_MARGIN: f64 = 0.3;
+/// Score points per energy of average cost over the margin
+const POINTS_PER_ENERGY: f64 = 8.0;
+/// Largest adjustment either way
+const MAX_CURVE_ADJUSTMENT: i32 = 8;
+/// Cards costing at most this are cheap
+const CHEAP_COST: i32 = 1;
+/// Cards costing at least this are bombs
+const BOMB_COST: i32 = 4;
+
+/// The deck's energy curve against its ideal
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CurveAnalysis {
+    /// Costed deck cards in each cost bucket
+    pub histogram: [u32; COST_BUCKETS],
+    /// Mean cost, counting 4+ as 4
+    pub average_cost: f64,
+    /// Mean cost of the ideal curve
+    pub ideal_average: f64,
+    /// Archetype whose ideal curve was used, if not the default
+    pub archetype: Option<String>,
+}
+
+impl CurveAnalysis {
+    /// Energy of average cost over the ideal, past the margin allowed
+    fn excess(&self) -> f64 {
+        self.average_cost - self.ideal_average - TOP_HEAVY_MARGIN
+    }
+
+    /// Whether the deck costs clearly more than its ideal curve
+    pub fn is_top_heavy(&self) -> bool {
+        self.excess() > 0.0
+    }
+
+    /// Score adjustment for drafting `card` into the analyzed deck
+    pub fn adjustment(&self, card: &CardData) -> i32 {
+        let Some(cost) = card.cost else { return 0 };
+        let excess = self.excess();
+        if excess <= 0.0 {
+            return 0;
+        }
+        let points = ((excess * POINTS_PER_ENERGY).round() as i32).min(MAX_CURVE_ADJUSTMENT);
+        if cost <= CHEAP_COST {
+            points
+        } else if cost >= BOMB_COST {
+            -points
+        } else {
+            0
+        }
+    }
+}
+
+fn bucket(cost: i32) -> usize {
+    (cost.max(0) as usize).min(COST_BUCKETS - 1)
+}
+
+/// Mean bucket of a curve, normalized so its shares needn't sum to 1
+fn curve_average(curve: &Curve) -> f64 {
+    let total: f64 = curve.iter().sum();
+    curve
+        .iter()
+        .enumerate()
+        .map(|(bucket, share)| bucket as f64 * share)
+        .sum::<f64>()
+        / total
+}
+
+/// Check an ideal curve before it is saved
+pub fn validate(curve: &Curve) -> Result<(), String> {
+    if curve.iter().any(|share| !(0.0..=1.0).contains(share)) {
+        return Err("Ideal curve shares must be between 0 and 1".to_string());
+    }
+    if curve.iter().sum::<f64>() <= 0.0 {
+        return Err("Ideal curve needs at least one nonzero share".to_string());
+    }
+    Ok(())
+}
+
+/// Analyze the deck's curve, or `None` until enough cards have a cost
+pub fn analyze(deck: &[CardData], archetypes: &[Archetype]) -> Option<CurveAnalysis> {
+    let costs: Vec<i32> = deck.iter().filter_map(|card| card.cost).collect();
+    if costs.len() < MIN_COSTED_CARDS {
+        return None;
+    }
+
+    let mut histogram = [0; COST_BUCKETS];
+    for &cost in &costs {
+        histogram[bucket(cost)] += 1;
+    }
+    let average_cost =
+        costs.iter().map(|&cost| bucket(cost) as f64).sum::<f64>() / costs.len() as f64;
+
+    let (ideal, archetype) = match archetypes::leading(deck, archetypes) {
+        Some((archetype, _)) if archetype.ideal_curve.is_some() => {
+            (archetype.ideal_curve.unwrap(), Some(archetype.name.clone()))
+        }
+        _ => (DEFAULT_CURVE, None),
+    };
+
+    Some(CurveAnalysis {
+        histogram,
+        average_cost,
+        ideal_average: curve_average(&ideal),
+        archetype,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn card(id: &str, cost: Option<i32>, keywords: &[&str]) -> CardData {
+        CardData {
+            id: id.to_string(),
+            name: id.to_string(),
+            clan: "Test".to_string(),
+            card_type: "Spell".to_string(),
+            rarity: "Common".to_string(),
+            cost,
+            size: None,
+            base_value: 70,
+            tempo_score: 6,
+            value_score: 7,
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            description: String::new(),
+            expansion: "base".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_top_heavy_deck() {
+        let deck = vec![
+            card("a", Some(3), &[]),
+            card("b", Some(4), &[]),
+            card("c", Some(6), &[]),
+            card("d", Some(2), &[]),
+            card("champion", None, &[]),
+        ];
+        let analysis = analyze(&deck, &[]).unwrap();
+        assert_eq!(analysis.histogram, [0, 0, 1, 1, 2]);
+        assert_eq!(analysis.average_cost, 3.25);
+        assert!((analysis.ideal_average - 1.9).abs() < 1e-9);
+        assert_eq!(analysis.archetype, None);
+        assert!(analysis.is_top_heavy());
+
+        // 3.25 - 1.9 - 0.3 = 1.05 energy over: capped at 8
+        assert_eq!(analysis.adjustment(&card("cheap", Some(0), &[])), 8);
+        assert_eq!(analysis.adjustment(&card("bomb", Some(5), &[])), -8);
+        assert_eq!(analysis.adjustment(&card("mid", Some(2), &[])), 0);
+        assert_eq!(analysis.adjustment(&card("champion", None, &[])), 0);
+
+        // A balanced deck leaves every card alone
+        let balanced: Vec<CardData> = [0, 1, 2, 3]
+            .iter()
+            .map(|&cost| card("x", Some(cost), &[]))
+            .collect();
+        let analysis = analyze(&balanced, &[]).unwrap();
+        assert_eq!(analysis.adjustment(&card("bomb", Some(5), &[])), 0);
+        assert!(!analysis.is_top_heavy());
+
+        assert_eq!(analyze(&balanced[..3], &[]), None);
+    }
+
+    #[test]
+    fn test_archetype_curve() {
+        let hoard = Archetype {
+            id: "dragon_hoard".to_string(),
+            name: "Dragon's Hoard".to_string(),
+            description: String::new(),
+            keyword_weights: BTreeMap::from([("dragon".to_string(), 1.0)]),
+            key_cards: Vec::new(),
+            ideal_curve: Some([0.0, 0.0, 0.25, 0.25, 0.5]),
+            active: true,
+        };
+        let deck: Vec<CardData> = [3, 4, 4, 2]
+            .iter()
+            .map(|&cost| card("whelp", Some(cost), &["dragon"]))
+            .collect();
+
+        // Top-heavy for most decks, but on curve for a dragon deck
+        let analysis = analyze(&deck, &[hoard]).unwrap();
+        assert_eq!(analysis.archetype.as_deref(), Some("Dragon's Hoard"));
+        assert_eq!(analysis.ideal_average, 3.25);
+        assert_eq!(analysis.adjustment(&card("bomb", Some(4), &[])), 0);
+        assert_eq!(
+            analyze(&deck, &[])
+                .unwrap()
+                .adjustment(&card("bomb", Some(4), &[])),
+            -8
+        );
+    }
+}
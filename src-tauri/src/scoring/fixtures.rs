@@ -0,0 +1,207 @@
+//! Scoring scenario fixtures
+//!
+//! A fixture is a JSON file describing one draft scenario: the champion,
+//! ring and deck, the candidate card, and the score range and reason codes
+//! the candidate is expected to get. The fixtures under `tests/fixtures/`
+//! are golden tests: every one must pass, so a scoring change that breaks
+//! a contributed scenario shows up as a test failure.
+
+use crate::scoring::calculator::{ScoreReason, ScoringResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Directory of the fixtures in the source tree
+#[cfg(test)]
+pub const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+/// One draft scenario and what the candidate should score in it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoringFixture {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub champion: String,
+    pub ring: i32,
+    #[serde(default)]
+    pub covenant: i32,
+    #[serde(default)]
+    pub champion_path: Option<String>,
+    #[serde(default)]
+    pub upcoming_boss: Option<String>,
+    #[serde(default)]
+    pub pact_shards: Option<i32>,
+    /// Card IDs already drafted, champion included
+    #[serde(default)]
+    pub deck: Vec<String>,
+    /// Card ID being scored
+    pub candidate: String,
+    pub expected: ExpectedScore,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExpectedScore {
+    /// Inclusive bounds on the score
+    pub min_score: i32,
+    pub max_score: i32,
+    /// Reason codes, such as `synergy_bonus`, that must all be given
+    #[serde(default)]
+    pub reasons: Vec<String>,
+}
+
+/// Outcome of running one fixture
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FixtureReport {
+    pub name: String,
+    /// Score the candidate got, if it could be scored
+    pub score: Option<i32>,
+    pub passed: bool,
+    /// What didn't match, empty when passed
+    pub failures: Vec<String>,
+}
+
+impl FixtureReport {
+    /// Report for a fixture that couldn't be scored at all
+    pub fn error(fixture: &ScoringFixture, error: String) -> Self {
+        Self {
+            name: fixture.name.clone(),
+            score: None,
+            passed: false,
+            failures: vec![error],
+        }
+    }
+}
+
+impl ScoringFixture {
+    /// Compare a scoring result with what the fixture expects
+    pub fn check(&self, result: &ScoringResult) -> FixtureReport {
+        let expected = &self.expected;
+        let mut failures = Vec::new();
+        if !(expected.min_score..=expected.max_score).contains(&result.score) {
+            failures.push(format!(
+                "Score {} outside {}..={}",
+                result.score, expected.min_score, expected.max_score
+            ));
+        }
+        let codes: Vec<String> = result.reasons.iter().map(reason_code).collect();
+        for code in &expected.reasons {
+            if !codes.contains(code) {
+                failures.push(format!(
+                    "Missing reason {} (got {})",
+                    code,
+                    codes.join(", ")
+                ));
+            }
+        }
+
+        FixtureReport {
+            name: self.name.clone(),
+            score: Some(result.score),
+            passed: failures.is_empty(),
+            failures,
+        }
+    }
+}
+
+/// The stable code a reason is serialized with
+fn reason_code(reason: &ScoreReason) -> String {
+    serde_json::to_value(reason)
+        .ok()
+        .and_then(|value| value["code"].as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Load every `.json` fixture in `dir`, ordered by file name
+pub fn load_fixtures(dir: &Path) -> Result<Vec<ScoringFixture>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read fixtures in {}: {}", dir.display(), e))?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let contents = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let fixture: ScoringFixture = serde_json::from_str(&contents)
+                .map_err(|e| format!("Invalid fixture {}: {}", path.display(), e))?;
+            if fixture.expected.min_score > fixture.expected.max_score {
+                return Err(format!(
+                    "Invalid fixture {}: min_score is above max_score",
+                    path.display()
+                ));
+            }
+            Ok(fixture)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_and_check() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("b.json"),
+            r#"{
+                "name": "Second",
+                "champion": "Fel",
+                "ring": 1,
+                "candidate": "banished_cleave",
+                "expected": { "min_score": 70, "max_score": 90, "reasons": ["context"] }
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("a.json"),
+            r#"{
+                "name": "First",
+                "champion": "Fel",
+                "ring": 1,
+                "candidate": "banished_cleave",
+                "expected": { "min_score": 0, "max_score": 200 }
+            }"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a fixture").unwrap();
+
+        let fixtures = load_fixtures(dir.path()).unwrap();
+        let names: Vec<&str> = fixtures.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["First", "Second"]);
+        assert_eq!(fixtures[0].covenant, 0);
+        assert!(fixtures[0].deck.is_empty());
+
+        let result = ScoringResult {
+            score: 95,
+            tier: "S".to_string(),
+            base_value: 95,
+            synergy_multiplier: 1.0,
+            context_bonus: 0,
+            champion_bonus: 0,
+            archetype_bonus: 0,
+            path_bonus: 0,
+            curve_adjustment: 0,
+            percentile: None,
+            reasons: vec![ScoreReason::EarlyGameTempo],
+        };
+        assert!(fixtures[0].check(&result).passed);
+        let report = fixtures[1].check(&result);
+        assert!(!report.passed);
+        assert_eq!(report.score, Some(95));
+        assert_eq!(report.failures.len(), 2);
+
+        fs::write(
+            dir.path().join("c.json"),
+            r#"{ "name": "Broken", "champion": "Fel" }"#,
+        )
+        .unwrap();
+        assert!(load_fixtures(dir.path()).unwrap_err().contains("c.json"));
+        assert!(load_fixtures(&dir.path().join("missing")).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! Monte Carlo draft forecast
+//!
+//! A draft score says how good a card is for the deck as it stands. The
+//! forecast looks ahead instead: it plays out the rest of the run many
+//! times, drawing random offers from the card pool and greedily taking the
+//! best-scoring card of each, and reports the expected quality of the final
+//! deck with and without each currently offered card. The difference is
+//! the card's marginal value.
+//!
+//! Every option is simulated with the same random offers (simulation `i`
+//! uses seed `seed + i` for each), so differences between options come from
+//! the cards and not from luck of the draw.
+
+use crate::database::repository::CardData;
+use crate::scoring::pool::CardPool;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_SIMULATIONS: usize = 500;
+pub const MAX_SIMULATIONS: usize = 5000;
+
+/// Last ring of a run, matching the ring numbers scoring accepts
+pub const FINAL_RING: i32 = 10;
+/// Card drafts in each ring after the current one
+const PICKS_PER_RING: usize = 2;
+/// Cards in a simulated draft offer
+const OFFER_SIZE: usize = 3;
+
+/// Forecast for taking one offered card
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OfferForecast {
+    pub card_id: String,
+    pub card_name: String,
+    /// Draft score against the current deck
+    pub current_score: i32,
+    /// Mean final deck quality when this card is taken
+    pub expected_quality: f64,
+    /// Expected quality gained over skipping the offer
+    pub marginal_value: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DraftForecast {
+    pub simulations: usize,
+    /// Picks simulated after the current offer
+    pub remaining_picks: usize,
+    /// Quality of the deck as it stands
+    pub current_quality: f64,
+    /// Mean final deck quality when the current offer is skipped
+    pub expected_quality: f64,
+    /// Offered cards, highest marginal value first
+    pub offers: Vec<OfferForecast>,
+    /// The offered card with the highest marginal value
+    pub recommended: Option<String>,
+}
+
+/// Simulates the rest of a draft from a champion's card pool
+pub struct Forecaster<'a> {
+    pool: CardPool<'a>,
+}
+
+impl<'a> Forecaster<'a> {
+    pub fn new(pool: CardPool<'a>) -> Self {
+        Self { pool }
+    }
+
+    /// Mean score of each card against the rest of the deck at the final ring
+    pub fn deck_quality(&self, deck: &mut [CardData]) -> f64 {
+        if deck.is_empty() {
+            return 0.0;
+        }
+        let last = deck.len() - 1;
+        let mut total = 0;
+        for i in 0..deck.len() {
+            // Score the card in the last slot against everything before it
+            deck.swap(i, last);
+            total += self.pool.score(&deck[last], &deck[..last], FINAL_RING);
+            deck.swap(i, last);
+        }
+        total as f64 / deck.len() as f64
+    }
+
+    /// Play out the rings after `ring` and return the final deck quality
+    fn simulate(&self, mut deck: Vec<CardData>, ring: i32, rng: &mut StdRng) -> f64 {
+        for pick_ring in (ring + 1)..=FINAL_RING {
+            for _ in 0..PICKS_PER_RING {
+                let best = self
+                    .pool
+                    .cards()
+                    .choose_multiple(rng, OFFER_SIZE)
+                    .max_by_key(|card| self.pool.score(card, &deck, pick_ring));
+                if let Some(card) = best {
+                    deck.push((*card).clone());
+                }
+            }
+        }
+        self.deck_quality(&mut deck)
+    }
+
+    /// Mean final quality over `simulations` runs starting from `deck`
+    fn expected_quality(&self, deck: &[CardData], ring: i32, simulations: usize, seed: u64) -> f64 {
+        let total: f64 = (0..simulations as u64)
+            .map(|i| {
+                let mut rng = StdRng::seed_from_u64(seed.wrapping_add(i));
+                self.simulate(deck.to_vec(), ring, &mut rng)
+            })
+            .sum();
+        total / simulations.max(1) as f64
+    }
+
+    /// Forecast the rest of the draft from `deck` at `ring`, with and
+    /// without each card in `offered`
+    pub fn forecast(
+        &self,
+        deck: &[CardData],
+        offered: &[CardData],
+        ring: i32,
+        simulations: usize,
+        seed: u64,
+    ) -> DraftForecast {
+        let _span = tracing::info_span!("scoring.forecast").entered();
+
+        let expected_quality = self.expected_quality(deck, ring, simulations, seed);
+        let mut offers: Vec<OfferForecast> = offered
+            .iter()
+            .map(|card| {
+                let mut with_card = deck.to_vec();
+                with_card.push(card.clone());
+                let quality = self.expected_quality(&with_card, ring, simulations, seed);
+                OfferForecast {
+                    card_id: card.id.clone(),
+                    card_name: card.name.clone(),
+                    current_score: self.pool.score(card, deck, ring),
+                    expected_quality: quality,
+                    marginal_value: quality - expected_quality,
+                }
+            })
+            .collect();
+        offers.sort_by(|a, b| b.marginal_value.total_cmp(&a.marginal_value));
+
+        DraftForecast {
+            simulations,
+            remaining_picks: (FINAL_RING - ring).max(0) as usize * PICKS_PER_RING,
+            current_quality: self.deck_quality(&mut deck.to_vec()),
+            expected_quality,
+            recommended: offers.first().map(|offer| offer.card_id.clone()),
+            offers,
+        }
+    }
+}
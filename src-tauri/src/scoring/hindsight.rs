@@ -0,0 +1,118 @@
+//! Hindsight review of a completed run
+//!
+//! A pick is scored against the deck as it stood when it was made, and
+//! later picks can show that another offered card would have fit better.
+//! The review rescores every pick against the run's final deck, alongside
+//! the cards offered in the same ring that were passed over, and flags
+//! picks an alternative beats by a clear margin.
+
+use crate::database::repository::CardData;
+use crate::scoring::pool::CardPool;
+use serde::{Deserialize, Serialize};
+
+/// Hindsight points an alternative must win by to flag a pick, one tier
+pub const MISSED_PICK_MARGIN: i32 = 10;
+
+/// A card drafted during the run
+#[derive(Debug, Clone)]
+pub struct RecordedPick {
+    pub card: CardData,
+    pub ring_number: i32,
+    pub draft_order: i32,
+    pub score_at_draft: Option<i32>,
+}
+
+/// An offered card that wasn't picked
+#[derive(Debug, Clone)]
+pub struct PassedOver {
+    pub card: CardData,
+    pub ring_number: i32,
+    /// Score recorded when the card was offered
+    pub score_at_draft: Option<i32>,
+}
+
+/// A card offered instead of a pick, with its hindsight score
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Alternative {
+    pub card_id: String,
+    pub card_name: String,
+    /// Score recorded when the card was offered
+    pub score_at_draft: Option<i32>,
+    pub hindsight_score: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PickReview {
+    pub card_id: String,
+    pub card_name: String,
+    pub ring_number: i32,
+    pub draft_order: i32,
+    /// Score recorded when the card was drafted
+    pub score_at_draft: Option<i32>,
+    /// Score against the rest of the final deck
+    pub hindsight_score: i32,
+    /// Best-scoring card passed over in the same ring, if any was recorded
+    pub best_alternative: Option<Alternative>,
+    /// The best alternative beat the pick by at least `MISSED_PICK_MARGIN`
+    pub missed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunReview {
+    pub run_id: String,
+    pub champion: String,
+    pub covenant: i32,
+    pub did_win: Option<bool>,
+    /// Drafted cards in draft order, champions left out
+    pub picks: Vec<PickReview>,
+    pub missed_picks: usize,
+    /// Hindsight points the missed picks gave up
+    pub value_lost: i32,
+}
+
+/// Review `picks` against the final deck they make up
+///
+/// Each pick is compared with the cards in `passed_over` from its own ring.
+pub fn review_picks(
+    pool: &CardPool,
+    picks: &[RecordedPick],
+    passed_over: &[PassedOver],
+) -> Vec<PickReview> {
+    let _span = tracing::info_span!("scoring.hindsight").entered();
+
+    let final_deck: Vec<CardData> = picks.iter().map(|pick| pick.card.clone()).collect();
+    picks
+        .iter()
+        .enumerate()
+        .filter(|(_, pick)| pick.card.rarity != "Champion")
+        .map(|(i, pick)| {
+            // The rest of the deck, which an alternative would have joined instead
+            let rest = [&final_deck[..i], &final_deck[i + 1..]].concat();
+            let hindsight_score = pool.score(&pick.card, &rest, pick.ring_number);
+            let best_alternative = passed_over
+                .iter()
+                .filter(|offered| offered.ring_number == pick.ring_number)
+                .map(|offered| Alternative {
+                    card_id: offered.card.id.clone(),
+                    card_name: offered.card.name.clone(),
+                    score_at_draft: offered.score_at_draft,
+                    hindsight_score: pool.score(&offered.card, &rest, pick.ring_number),
+                })
+                .max_by_key(|alternative| alternative.hindsight_score);
+            let missed = best_alternative.as_ref().is_some_and(|alternative| {
+                alternative.hindsight_score - hindsight_score >= MISSED_PICK_MARGIN
+            });
+
+            PickReview {
+                card_id: pick.card.id.clone(),
+                card_name: pick.card.name.clone(),
+                ring_number: pick.ring_number,
+                draft_order: pick.draft_order,
+                score_at_draft: pick.score_at_draft,
+                hindsight_score,
+                best_alternative,
+                missed,
+            }
+        })
+        .collect()
+}
@@ -1,5 +1,16 @@
+pub mod archetypes;
+pub mod bosses;
 pub mod calculator;
+pub mod champion_paths;
+pub mod compare;
 pub mod context;
+pub mod curve;
+pub mod fixtures;
+pub mod forecast;
+pub mod hindsight;
+pub mod pool;
+pub mod rules;
+pub mod shop;
 pub mod synergies;
 
 #[cfg(test)]
@@ -15,6 +26,7 @@ mod tests {
             card_type: "Unit".to_string(),
             rarity: "Common".to_string(),
             cost: Some(1),
+            size: None,
             base_value,
             tempo_score: tempo,
             value_score: value,
@@ -87,7 +99,7 @@ mod tests {
         
         let context_mods = vec![
             context::ContextModifier {
-                condition: "missing_frontline".to_string(),
+                condition: "count(keyword:frontline|tank) == 0".parse().unwrap(),
                 card_tag: "frontline".to_string(),
                 modifier: 15,
                 priority: "High".to_string(),
@@ -95,7 +107,7 @@ mod tests {
             }
         ];
         
-        let context_bonus = context::calculate_context_bonus(&card, &empty_deck, &context_mods
+        let context_bonus = context::calculate_context_bonus(&card, &empty_deck, 1, 10, 0, None, &context_mods
         );
         
         assert_eq!(context_bonus, 15);
@@ -108,7 +120,7 @@ mod tests {
         
         let context_mods = vec![
             context::ContextModifier {
-                condition: "missing_frontline".to_string(),
+                condition: "count(keyword:frontline|tank) == 0".parse().unwrap(),
                 card_tag: "frontline".to_string(),
                 modifier: 15,
                 priority: "High".to_string(),
@@ -117,7 +129,7 @@ mod tests {
         ];
         
         let context_bonus = context::calculate_context_bonus(
-            &tank_card, &[existing_tank], &context_mods
+            &tank_card, &[existing_tank], 1, 10, 0, None, &context_mods
         );
         
         assert_eq!(context_bonus, 0);
@@ -151,13 +163,139 @@ mod tests {
             "Fel",
             1,
             10,
+            0,
             &synergies,
             &context_mods,
             None, // No champion override
+            &[],
+            None,
+            None,
         );
         
         // Base 92 * 1.25 synergy = 115
-        assert!(result.score > 90);
-        assert!(result.score <= 120);
+        assert_eq!(result.score, 115);
+        assert_eq!(result.percentile, None);
+    }
+    
+    #[test]
+    fn test_empirical_adjustment() {
+        let card = create_test_card("card_a", 78, 5, 5, vec![]);
+        let calculator = calculator::ScoreCalculator::new_test();
+        let base = calculator.calculate_full(&card, &[], "Fel", 4, 10, 0, &[], &[], None, &[], None, None);
+        assert_eq!(base.score, 78);
+        
+        // Too few runs: no change
+        let mut result = base.clone();
+        calculator.apply_empirical_adjustment(&mut result, Some(1.0), 2);
+        assert_eq!(result.score, 78);
+        
+        // 80% wins over 10 runs: +6, crossing into A tier
+        let mut result = base.clone();
+        calculator.apply_empirical_adjustment(&mut result, Some(0.8), 10);
+        assert_eq!(result.score, 84);
+        assert_eq!(result.tier, "A");
+        assert_eq!(
+            result.reasons.last(),
+            Some(&calculator::ScoreReason::History {
+                win_rate_percent: 80,
+                runs: 10,
+                adjustment: 6,
+            })
+        );
+        
+        // Losing record is capped
+        let mut result = base;
+        calculator.apply_empirical_adjustment(&mut result, Some(0.0), 10);
+        assert_eq!(result.score, 68);
+    }
+
+    #[test]
+    fn test_community_adjustment() {
+        let card = create_test_card("card_a", 78, 5, 5, vec![]);
+        let calculator = calculator::ScoreCalculator::new_test();
+        let base = calculator.calculate_full(&card, &[], "Fel", 4, 10, 0, &[], &[], None, &[], None, None);
+
+        // Too few games: no change
+        let mut result = base.clone();
+        calculator.apply_community_adjustment(&mut result, 0.8, 20, 1.0);
+        assert_eq!(result.score, 78);
+
+        // 80% wins at half weight: +3
+        let mut result = base;
+        calculator.apply_community_adjustment(&mut result, 0.8, 1200, 0.5);
+        assert_eq!(result.score, 81);
+        assert_eq!(
+            result.reasons.last(),
+            Some(&calculator::ScoreReason::Community {
+                win_rate_percent: 80,
+                games: 1200,
+                adjustment: 3,
+            })
+        );
+        assert_eq!(
+            result.reasons.last().unwrap().to_string(),
+            "Community: 80% wins over 1200 games (+3)"
+        );
+    }
+
+    #[test]
+    fn test_upgrade_adjustment() {
+        let card = create_test_card("card_a", 78, 5, 5, vec![]);
+        let calculator = calculator::ScoreCalculator::new_test();
+        let base = calculator.calculate_full(&card, &[], "Fel", 4, 10, 0, &[], &[], None, &[], None, None);
+
+        // Plain card: no change
+        let mut result = base.clone();
+        calculator.apply_upgrade_adjustment(&mut result, 0, 1);
+        assert_eq!(result.score, 78);
+        assert_eq!(result.reasons, base.reasons);
+
+        // New card with one upgrade
+        let mut result = base.clone();
+        calculator.apply_upgrade_adjustment(&mut result, 1, 0);
+        assert_eq!(result.score, 82);
+        assert_eq!(result.reasons.last().unwrap().to_string(), "Comes upgraded x1: +4");
+
+        // Upgraded copy of a deck card scores higher
+        let mut result = base.clone();
+        calculator.apply_upgrade_adjustment(&mut result, 2, 1);
+        assert_eq!(result.score, 94);
+        assert_eq!(result.tier, "S");
+        assert_eq!(
+            result.reasons.last(),
+            Some(&calculator::ScoreReason::Upgraded {
+                upgrades: 2,
+                duplicate: true,
+                bonus: 16,
+            })
+        );
+
+        // Capped
+        let mut result = base;
+        calculator.apply_upgrade_adjustment(&mut result, 5, 1);
+        assert_eq!(result.score, 98);
+    }
+    
+    #[test]
+    fn test_reason_codes() {
+        use calculator::ScoreReason;
+        
+        let reason = ScoreReason::SynergyBonus { percent: 25 };
+        assert_eq!(
+            serde_json::to_value(&reason).unwrap(),
+            serde_json::json!({ "code": "synergy_bonus", "args": { "percent": 25 } })
+        );
+        assert_eq!(reason.to_string(), "Synergy bonus: 25%");
+        assert_eq!(
+            serde_json::to_value(ScoreReason::EarlyGameTempo).unwrap(),
+            serde_json::json!({ "code": "early_game_tempo" })
+        );
+        
+        let history: ScoreReason = serde_json::from_value(serde_json::json!({
+            "code": "history",
+            "args": { "win_rate_percent": 80, "runs": 10, "adjustment": 6 }
+        }))
+        .unwrap();
+        assert_eq!(history.to_string(), "Your history: 80% wins over 10 runs (+6)");
     }
 }
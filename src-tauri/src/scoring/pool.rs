@@ -0,0 +1,304 @@
+//! Scoring a champion's whole card pool in memory
+//!
+//! Placing a score against every card the champion could be offered, or
+//! simulating future drafts, scores far too many cards to query the
+//! database for each. Callers load the `ScoringTables` once and score from
+//! memory. A candidate and the pool it is ranked against go through the
+//! same steps, and the pool's sorted scores are kept on the card snapshot
+//! so the other cards of the same offer are ranked without rescoring it.
+
+use crate::database::cache::CardSnapshot;
+use crate::database::repository::CardData;
+use crate::scoring::archetypes::Archetype;
+use crate::scoring::bosses::Boss;
+use crate::scoring::calculator::{ScoreCalculator, ScoringResult};
+use crate::scoring::champion_paths::ChampionPath;
+use crate::scoring::context::ContextModifier;
+use crate::scoring::synergies::Synergy;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+/// Scoring data held in memory so pool-wide scoring doesn't query the database
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ScoringTables {
+    /// Every synergy row
+    pub synergies: Vec<Synergy>,
+    pub context_modifiers: Vec<ContextModifier>,
+    /// Value override per card ID for the pool's champion
+    pub champion_overrides: HashMap<String, i32>,
+    pub archetypes: Vec<Archetype>,
+    /// The champion path being drafted for, if chosen
+    pub champion_path: Option<ChampionPath>,
+    /// The boss at the end of the ring, if known
+    pub upcoming_boss: Option<Boss>,
+    /// Adjustments from recorded results, empty unless wanted
+    pub results: ResultAdjustments,
+}
+
+/// Win rates that adjust a card's final score, per card ID
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ResultAdjustments {
+    /// (win rate, completed runs) from the player's own history
+    pub history: HashMap<String, (Option<f64>, u32)>,
+    /// (win rate, games) from imported community stats
+    pub community: HashMap<String, (f64, u32)>,
+    /// Share of the community adjustment applied
+    pub community_weight: f64,
+}
+
+impl ResultAdjustments {
+    /// Scoring steps 8 and 9: adjust by the player's own results with the
+    /// card, then by community results
+    pub fn apply(&self, card_id: &str, result: &mut ScoringResult) {
+        let calculator = ScoreCalculator::new();
+        if let Some(&(win_rate, runs)) = self.history.get(card_id) {
+            calculator.apply_empirical_adjustment(result, win_rate, runs);
+        }
+        if let Some(&(win_rate, games)) = self.community.get(card_id) {
+            calculator.apply_community_adjustment(result, win_rate, games, self.community_weight);
+        }
+    }
+}
+
+/// Everything a pool's scores depend on besides the card snapshot
+#[derive(Debug, Clone, PartialEq)]
+struct PoolKey {
+    champion: String,
+    covenant: i32,
+    pact_shards: i32,
+    ring: i32,
+    upgrades: u32,
+    deck: Vec<String>,
+    tables: ScoringTables,
+}
+
+/// The sorted scores of the last pool ranked against a snapshot
+///
+/// Lives on the `CardSnapshot`, so it goes when the cards change; anything
+/// else the scores depend on is part of the key.
+#[derive(Debug, Default)]
+pub struct PoolScoreCache {
+    last: Mutex<Option<(PoolKey, Arc<Vec<i32>>)>>,
+}
+
+impl PoolScoreCache {
+    /// The scores for `key`, computed with `score` unless already kept
+    fn get_or_insert(&self, key: PoolKey, score: impl FnOnce() -> Vec<i32>) -> Arc<Vec<i32>> {
+        {
+            let last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some((last_key, scores)) = last.as_ref() {
+                if *last_key == key {
+                    return Arc::clone(scores);
+                }
+            }
+        }
+
+        let mut scores = score();
+        scores.sort_unstable();
+        let scores = Arc::new(scores);
+        *self.last.lock().unwrap_or_else(|e| e.into_inner()) = Some((key, Arc::clone(&scores)));
+        scores
+    }
+}
+
+/// The cards a champion can be offered, scored for one covenant and pact
+/// shard count
+pub struct CardPool<'a> {
+    cards: Vec<&'a CardData>,
+    /// Synergies per card ID, as scoring looks them up
+    synergies: HashMap<&'a str, Vec<Synergy>>,
+    tables: &'a ScoringTables,
+    champion: &'a str,
+    covenant: i32,
+    pact_shards: i32,
+    pool_scores: &'a PoolScoreCache,
+}
+
+impl<'a> CardPool<'a> {
+    /// The pool is the active, non-champion cards of the champion's clan
+    /// and the clans already in `deck`, or every such card if neither is
+    /// known.
+    pub fn new(
+        snapshot: &'a CardSnapshot,
+        tables: &'a ScoringTables,
+        champion: &'a str,
+        covenant: i32,
+        pact_shards: i32,
+        deck: &[CardData],
+    ) -> Self {
+        let mut clans: HashSet<&str> = deck.iter().map(|card| card.clan.as_str()).collect();
+        clans.extend(
+            snapshot
+                .cards()
+                .iter()
+                .filter(|card| card.rarity == "Champion" && card.name == champion)
+                .map(|card| card.clan.as_str()),
+        );
+
+        let draftable: Vec<&CardData> = snapshot
+            .active_cards()
+            .filter(|card| card.rarity != "Champion")
+            .collect();
+        let in_clans: Vec<&CardData> = draftable
+            .iter()
+            .copied()
+            .filter(|card| clans.contains(card.clan.as_str()))
+            .collect();
+        let cards = if in_clans.is_empty() {
+            draftable
+        } else {
+            in_clans
+        };
+
+        // Same rows `get_synergies_for_card` selects
+        let synergies = snapshot
+            .cards()
+            .iter()
+            .map(|card| {
+                let rows = tables
+                    .synergies
+                    .iter()
+                    .filter(|s| {
+                        s.card_a_id == card.id
+                            || (s.card_b_id == card.id && s.bidirectional)
+                            || s.card_b_id == "*"
+                    })
+                    .cloned()
+                    .collect();
+                (card.id.as_str(), rows)
+            })
+            .collect();
+
+        Self {
+            cards,
+            synergies,
+            tables,
+            champion,
+            covenant,
+            pact_shards,
+            pool_scores: snapshot.pool_scores(),
+        }
+    }
+
+    pub fn cards(&self) -> &[&'a CardData] {
+        &self.cards
+    }
+
+    /// Draft score of `card` against `deck`
+    pub fn score(&self, card: &CardData, deck: &[CardData], ring: i32) -> i32 {
+        self.result(card, deck, ring, 0).score
+    }
+
+    /// Final score of `card` offered with `upgrades` upgrades: the draft
+    /// score, then the upgrade and result adjustments
+    pub fn result(
+        &self,
+        card: &CardData,
+        deck: &[CardData],
+        ring: i32,
+        upgrades: u32,
+    ) -> ScoringResult {
+        let synergies = self
+            .synergies
+            .get(card.id.as_str())
+            .map_or(&[][..], Vec::as_slice);
+        let calculator = ScoreCalculator::new();
+        let mut result = calculator.calculate_full(
+            card,
+            deck,
+            self.champion,
+            ring,
+            self.covenant,
+            self.pact_shards,
+            synergies,
+            &self.tables.context_modifiers,
+            self.tables.champion_overrides.get(&card.id).copied(),
+            &self.tables.archetypes,
+            self.tables.champion_path.as_ref(),
+            self.tables.upcoming_boss.as_ref(),
+        );
+        let copies_in_deck = deck.iter().filter(|other| other.id == card.id).count();
+        calculator.apply_upgrade_adjustment(&mut result, upgrades, copies_in_deck);
+        self.tables.results.apply(&card.id, &mut result);
+        result
+    }
+
+    /// Where `score` falls among the pool's final scores against `deck`,
+    /// each card offered with `upgrades` upgrades, from 0 to 100
+    pub fn percentile(&self, score: i32, deck: &[CardData], ring: i32, upgrades: u32) -> f64 {
+        let key = PoolKey {
+            champion: self.champion.to_string(),
+            covenant: self.covenant,
+            pact_shards: self.pact_shards,
+            ring,
+            upgrades,
+            deck: deck.iter().map(|card| card.id.clone()).collect(),
+            tables: self.tables.clone(),
+        };
+        let scores = self.pool_scores.get_or_insert(key, || {
+            self.cards
+                .iter()
+                .map(|card| self.result(card, deck, ring, upgrades).score)
+                .collect()
+        });
+        percentile_rank(score, &scores)
+    }
+}
+
+/// Percent of `scores` below `score`, counting ties as half below, to one
+/// decimal place
+///
+/// Counting ties as half keeps a pool where every card scores the same at
+/// 50 rather than 0 or 100.
+pub fn percentile_rank(score: i32, scores: &[i32]) -> f64 {
+    if scores.is_empty() {
+        return 50.0;
+    }
+    let below = scores.iter().filter(|&&s| s < score).count() as f64;
+    let ties = scores.iter().filter(|&&s| s == score).count() as f64;
+    let rank = (below + ties / 2.0) / scores.len() as f64 * 100.0;
+    (rank * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_rank() {
+        let scores = [60, 70, 70, 80, 95];
+        assert_eq!(percentile_rank(95, &scores), 90.0);
+        assert_eq!(percentile_rank(120, &scores), 100.0);
+        assert_eq!(percentile_rank(70, &scores), 40.0);
+        assert_eq!(percentile_rank(10, &scores), 0.0);
+        assert_eq!(percentile_rank(70, &[70, 70]), 50.0);
+        assert_eq!(percentile_rank(70, &[]), 50.0);
+    }
+
+    #[test]
+    fn test_pool_scores_kept_per_key() {
+        let key = |ring: i32| PoolKey {
+            champion: "Fel".to_string(),
+            covenant: 10,
+            pact_shards: 0,
+            ring,
+            upgrades: 0,
+            deck: Vec::new(),
+            tables: ScoringTables::default(),
+        };
+        let cache = PoolScoreCache::default();
+        let mut scored = 0;
+        let mut score = |scores: Vec<i32>| {
+            scored += 1;
+            scores
+        };
+
+        let first = cache.get_or_insert(key(1), || score(vec![80, 60, 70]));
+        assert_eq!(*first, [60, 70, 80]);
+        let again = cache.get_or_insert(key(1), || score(Vec::new()));
+        assert!(Arc::ptr_eq(&first, &again));
+        let next = cache.get_or_insert(key(2), || score(vec![90]));
+        assert_eq!(*next, [90]);
+        assert_eq!(scored, 2);
+    }
+}
@@ -0,0 +1,727 @@
+//! Declarative conditions for context modifiers
+//!
+//! A context modifier's `condition` column holds a small rule expression
+//! that is parsed when modifiers are loaded, so new conditions need only a
+//! database row:
+//!
+//! ```text
+//! count(keyword:frontline|tank) == 0
+//! deck.size > 20 && ring >= 6
+//! count(card, rarity:Common) >= 2
+//! space() + card.size > capacity
+//! boss(mechanic:spikes) > 0
+//! ```
+//!
+//! Values are integer literals, `deck.size`, `ring`, `covenant`,
+//! `pact_shards` (endless-mode enemy scaling, 0 outside endless runs),
+//! `card.size` (floor space of the card being scored, 0 if not a unit),
+//! `capacity` (floor space across the train), `count(...)`, the number of
+//! deck cards matching every comma-separated filter, and `space(...)`, the
+//! floor space those cards take. A filter is `card` (a copy of the card
+//! being scored) or a field (`keyword`, `rarity`, `clan`, `type` or `id`)
+//! with one or more `|`-separated values, quoted if they contain spaces;
+//! with no filters every deck card matches. `boss(...)` is 1 if the
+//! upcoming boss is known and matches every filter (`id` or `mechanic`),
+//! else 0. Values add and subtract with
+//! `+` and `-`, and compare with `==`, `!=`, `<`, `<=`, `>` and `>=`.
+//! Comparisons combine with `!`, `&&`, `||`, parentheses and the literals
+//! `true` and `false`.
+
+use crate::database::repository::CardData;
+use crate::scoring::bosses::Boss;
+use std::fmt;
+use std::str::FromStr;
+
+/// Floors on the train that hold units
+pub const TRAIN_FLOORS: i64 = 3;
+/// Unit space on each floor
+pub const FLOOR_CAPACITY: i64 = 5;
+
+/// A value from the scoring context that a rule can read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    DeckSize,
+    PactShards,
+    Capacity,
+    Boss,
+}
+
+/// Floor space the unit cards in `cards` take
+pub fn floor_space(cards: &[CardData]) -> i64 {
+    cards.iter().map(|card| card.size.unwrap_or(0) as i64).sum()
+}
+
+/// What a rule is evaluated against
+pub struct RuleContext<'a> {
+    pub card: &'a CardData,
+    pub deck: &'a [CardData],
+    pub ring: i32,
+    pub covenant: i32,
+    pub pact_shards: i32,
+    /// The boss at the end of the ring, if known
+    pub boss: Option<&'a Boss>,
+}
+
+/// A parsed condition, displayed as its source text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(bool),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Value, CompareOp, Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(i64),
+    DeckSize,
+    Ring,
+    Covenant,
+    PactShards,
+    CardSize,
+    Capacity,
+    Count(Vec<Filter>),
+    Space(Vec<Filter>),
+    Boss(Vec<BossFilter>),
+    Add(Box<Value>, Box<Value>),
+    Subtract(Box<Value>, Box<Value>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    SameCard,
+    Field(Field, Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum BossFilter {
+    Id(Vec<String>),
+    Mechanic(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Keyword,
+    Rarity,
+    Clan,
+    Type,
+    Id,
+}
+
+impl Rule {
+    pub fn evaluate(&self, ctx: &RuleContext) -> bool {
+        self.expr.evaluate(ctx)
+    }
+
+    /// Whether the rule reads `input` anywhere
+    pub fn reads(&self, input: Input) -> bool {
+        self.expr.reads(input)
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {}", token));
+        }
+        Ok(Self {
+            source: source.trim().to_string(),
+            expr,
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Expr {
+    fn evaluate(&self, ctx: &RuleContext) -> bool {
+        match self {
+            Expr::Literal(value) => *value,
+            Expr::Not(expr) => !expr.evaluate(ctx),
+            Expr::And(a, b) => a.evaluate(ctx) && b.evaluate(ctx),
+            Expr::Or(a, b) => a.evaluate(ctx) || b.evaluate(ctx),
+            Expr::Compare(a, op, b) => {
+                let (a, b) = (a.evaluate(ctx), b.evaluate(ctx));
+                match op {
+                    CompareOp::Eq => a == b,
+                    CompareOp::Ne => a != b,
+                    CompareOp::Lt => a < b,
+                    CompareOp::Le => a <= b,
+                    CompareOp::Gt => a > b,
+                    CompareOp::Ge => a >= b,
+                }
+            }
+        }
+    }
+
+    fn reads(&self, input: Input) -> bool {
+        match self {
+            Expr::Literal(_) => false,
+            Expr::Not(expr) => expr.reads(input),
+            Expr::And(a, b) | Expr::Or(a, b) => a.reads(input) || b.reads(input),
+            Expr::Compare(a, _, b) => a.reads(input) || b.reads(input),
+        }
+    }
+}
+
+impl Value {
+    fn evaluate(&self, ctx: &RuleContext) -> i64 {
+        match self {
+            Value::Number(n) => *n,
+            Value::DeckSize => ctx.deck.len() as i64,
+            Value::Ring => ctx.ring as i64,
+            Value::Covenant => ctx.covenant as i64,
+            Value::PactShards => ctx.pact_shards as i64,
+            Value::CardSize => ctx.card.size.unwrap_or(0) as i64,
+            Value::Capacity => TRAIN_FLOORS * FLOOR_CAPACITY,
+            Value::Count(filters) => Self::matching(filters, ctx).count() as i64,
+            Value::Space(filters) => Self::matching(filters, ctx)
+                .map(|card| card.size.unwrap_or(0) as i64)
+                .sum(),
+            Value::Boss(filters) => match ctx.boss {
+                Some(boss) if filters.iter().all(|filter| filter.matches(boss)) => 1,
+                _ => 0,
+            },
+            Value::Add(a, b) => a.evaluate(ctx).saturating_add(b.evaluate(ctx)),
+            Value::Subtract(a, b) => a.evaluate(ctx).saturating_sub(b.evaluate(ctx)),
+        }
+    }
+
+    fn reads(&self, input: Input) -> bool {
+        match self {
+            Value::DeckSize => input == Input::DeckSize,
+            Value::PactShards => input == Input::PactShards,
+            Value::Capacity => input == Input::Capacity,
+            Value::Boss(_) => input == Input::Boss,
+            Value::Add(a, b) | Value::Subtract(a, b) => a.reads(input) || b.reads(input),
+            _ => false,
+        }
+    }
+
+    fn matching<'a>(
+        filters: &'a [Filter],
+        ctx: &'a RuleContext,
+    ) -> impl Iterator<Item = &'a CardData> + 'a {
+        ctx.deck
+            .iter()
+            .filter(move |card| filters.iter().all(|filter| filter.matches(card, ctx.card)))
+    }
+}
+
+impl Filter {
+    fn matches(&self, card: &CardData, scored: &CardData) -> bool {
+        match self {
+            Filter::SameCard => card.id == scored.id,
+            Filter::Field(field, values) => {
+                let any = |actual: &str| values.iter().any(|v| v.eq_ignore_ascii_case(actual));
+                match field {
+                    Field::Keyword => card.keywords.iter().any(|k| any(k)),
+                    Field::Rarity => any(&card.rarity),
+                    Field::Clan => any(&card.clan),
+                    Field::Type => any(&card.card_type),
+                    Field::Id => any(&card.id),
+                }
+            }
+        }
+    }
+}
+
+impl BossFilter {
+    fn matches(&self, boss: &Boss) -> bool {
+        match self {
+            BossFilter::Id(ids) => ids.iter().any(|id| id.eq_ignore_ascii_case(&boss.id)),
+            BossFilter::Mechanic(mechanics) => mechanics.iter().any(|m| boss.has_mechanic(m)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i64),
+    Ident(String),
+    Str(String),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    Plus,
+    Minus,
+    LParen,
+    RParen,
+    Colon,
+    Pipe,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "number {}", n),
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Str(s) => write!(f, "\"{}\"", s),
+            Token::Op(op) => write!(f, "'{}'", op.symbol()),
+            Token::And => f.write_str("'&&'"),
+            Token::Or => f.write_str("'||'"),
+            Token::Not => f.write_str("'!'"),
+            Token::Plus => f.write_str("'+'"),
+            Token::Minus => f.write_str("'-'"),
+            Token::LParen => f.write_str("'('"),
+            Token::RParen => f.write_str("')'"),
+            Token::Colon => f.write_str("':'"),
+            Token::Pipe => f.write_str("'|'"),
+            Token::Comma => f.write_str("','"),
+        }
+    }
+}
+
+impl CompareOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('=', Some('=')) => (Token::Op(CompareOp::Eq), 2),
+            ('!', Some('=')) => (Token::Op(CompareOp::Ne), 2),
+            ('<', Some('=')) => (Token::Op(CompareOp::Le), 2),
+            ('>', Some('=')) => (Token::Op(CompareOp::Ge), 2),
+            ('<', _) => (Token::Op(CompareOp::Lt), 1),
+            ('>', _) => (Token::Op(CompareOp::Gt), 1),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('!', _) => (Token::Not, 1),
+            ('+', _) => (Token::Plus, 1),
+            ('-', _) => (Token::Minus, 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            (':', _) => (Token::Colon, 1),
+            ('|', _) => (Token::Pipe, 1),
+            (',', _) => (Token::Comma, 1),
+            ('"', _) => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&c| c == '"')
+                    .ok_or_else(|| format!("Unterminated string at {}", i))?;
+                let text: String = chars[i + 1..i + 1 + end].iter().collect();
+                (Token::Str(text), end + 2)
+            }
+            (c, _) if c.is_ascii_digit() => {
+                let len = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+                let text: String = chars[i..i + len].iter().collect();
+                let n = text
+                    .parse()
+                    .map_err(|_| format!("Number {} is out of range", text))?;
+                (Token::Number(n), len)
+            }
+            (c, _) if c.is_ascii_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.'))
+                    .count();
+                (Token::Ident(chars[i..i + len].iter().collect()), len)
+            }
+            (c, _) => return Err(format!("Unexpected '{}' at {}", c, i)),
+        };
+        tokens.push(token);
+        i += len;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| "Unexpected end of condition".to_string())?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        match self.next()? {
+            found if found == token => Ok(()),
+            found => Err(format!("Expected {} but found {}", token, found)),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_and()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.parse_unary()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.eat(&Token::LParen) {
+            let expr = self.parse_or()?;
+            self.expect(Token::RParen)?;
+            return Ok(expr);
+        }
+        match self.peek() {
+            Some(Token::Ident(name)) if name == "true" || name == "false" => {
+                let value = name == "true";
+                self.pos += 1;
+                return Ok(Expr::Literal(value));
+            }
+            _ => {}
+        }
+        let left = self.parse_value()?;
+        let op = match self.next()? {
+            Token::Op(op) => op,
+            found => return Err(format!("Expected a comparison but found {}", found)),
+        };
+        let right = self.parse_value()?;
+        Ok(Expr::Compare(left, op, right))
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        let mut value = self.parse_term()?;
+        loop {
+            if self.eat(&Token::Plus) {
+                value = Value::Add(Box::new(value), Box::new(self.parse_term()?));
+            } else if self.eat(&Token::Minus) {
+                value = Value::Subtract(Box::new(value), Box::new(self.parse_term()?));
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Value, String> {
+        match self.next()? {
+            Token::Number(n) => Ok(Value::Number(n)),
+            Token::Minus => match self.next()? {
+                Token::Number(n) => Ok(Value::Number(-n)),
+                found => Err(format!("Expected a number but found {}", found)),
+            },
+            Token::Ident(name) => match name.as_str() {
+                "deck.size" => Ok(Value::DeckSize),
+                "ring" => Ok(Value::Ring),
+                "covenant" => Ok(Value::Covenant),
+                "pact_shards" => Ok(Value::PactShards),
+                "card.size" => Ok(Value::CardSize),
+                "capacity" => Ok(Value::Capacity),
+                "count" => Ok(Value::Count(self.parse_filters()?)),
+                "space" => Ok(Value::Space(self.parse_filters()?)),
+                "boss" => Ok(Value::Boss(self.parse_list(Self::parse_boss_filter)?)),
+                _ => Err(format!("Unknown value '{}'", name)),
+            },
+            found => Err(format!("Expected a value but found {}", found)),
+        }
+    }
+
+    /// A parenthesized, comma-separated filter list, possibly empty
+    fn parse_filters(&mut self) -> Result<Vec<Filter>, String> {
+        self.parse_list(Self::parse_filter)
+    }
+
+    fn parse_list<T>(
+        &mut self,
+        parse_item: fn(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        self.expect(Token::LParen)?;
+        let mut items = Vec::new();
+        if self.eat(&Token::RParen) {
+            return Ok(items);
+        }
+        items.push(parse_item(self)?);
+        while self.eat(&Token::Comma) {
+            items.push(parse_item(self)?);
+        }
+        self.expect(Token::RParen)?;
+        Ok(items)
+    }
+
+    fn parse_boss_filter(&mut self) -> Result<BossFilter, String> {
+        let name = match self.next()? {
+            Token::Ident(name) => name,
+            found => return Err(format!("Expected a boss filter but found {}", found)),
+        };
+        let filter: fn(Vec<String>) -> BossFilter = match name.as_str() {
+            "id" => BossFilter::Id,
+            "mechanic" => BossFilter::Mechanic,
+            _ => return Err(format!("Unknown boss filter '{}'", name)),
+        };
+        self.expect(Token::Colon)?;
+        Ok(filter(self.parse_filter_values()?))
+    }
+
+    fn parse_filter(&mut self) -> Result<Filter, String> {
+        let name = match self.next()? {
+            Token::Ident(name) => name,
+            found => return Err(format!("Expected a filter but found {}", found)),
+        };
+        let field = match name.as_str() {
+            "card" => return Ok(Filter::SameCard),
+            "keyword" => Field::Keyword,
+            "rarity" => Field::Rarity,
+            "clan" => Field::Clan,
+            "type" => Field::Type,
+            "id" => Field::Id,
+            _ => return Err(format!("Unknown filter '{}'", name)),
+        };
+        self.expect(Token::Colon)?;
+        Ok(Filter::Field(field, self.parse_filter_values()?))
+    }
+
+    /// One or more `|`-separated filter values
+    fn parse_filter_values(&mut self) -> Result<Vec<String>, String> {
+        let mut values = vec![self.parse_filter_value()?];
+        while self.eat(&Token::Pipe) {
+            values.push(self.parse_filter_value()?);
+        }
+        Ok(values)
+    }
+
+    fn parse_filter_value(&mut self) -> Result<String, String> {
+        match self.next()? {
+            Token::Ident(value) | Token::Str(value) => Ok(value),
+            Token::Number(n) => Ok(n.to_string()),
+            found => Err(format!("Expected a filter value but found {}", found)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(id: &str, rarity: &str, keywords: &[&str]) -> CardData {
+        sized(id, rarity, keywords, None)
+    }
+
+    fn sized(id: &str, rarity: &str, keywords: &[&str], size: Option<i32>) -> CardData {
+        CardData {
+            id: id.to_string(),
+            name: id.to_string(),
+            clan: "Luna Coven".to_string(),
+            card_type: "Unit".to_string(),
+            rarity: rarity.to_string(),
+            cost: Some(1),
+            size,
+            base_value: 70,
+            tempo_score: 6,
+            value_score: 7,
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            description: String::new(),
+            expansion: "base".to_string(),
+        }
+    }
+
+    fn eval(rule: &str, scored: &CardData, deck: &[CardData], ring: i32) -> bool {
+        let rule: Rule = rule.parse().unwrap();
+        rule.evaluate(&RuleContext {
+            card: scored,
+            deck,
+            ring,
+            covenant: 10,
+            pact_shards: 0,
+            boss: None,
+        })
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let scored = card("imp", "Common", &["tank"]);
+        let deck = vec![
+            card("imp", "Common", &["frontline"]),
+            card("imp", "Common", &[]),
+            card("wall", "Rare", &["tank", "sweep"]),
+        ];
+
+        assert!(eval(
+            "count(keyword:frontline|tank) == 2",
+            &scored,
+            &deck,
+            1
+        ));
+        assert!(eval("count(keyword:reform) == 0", &scored, &deck, 1));
+        assert!(eval("count(card, rarity:common) >= 2", &scored, &deck, 1));
+        assert!(!eval("count(card, keyword:sweep) > 0", &scored, &deck, 1));
+        assert!(eval(
+            "count(clan:\"Luna Coven\") == deck.size",
+            &scored,
+            &deck,
+            1
+        ));
+        assert!(eval("deck.size > 20 || ring >= 6", &scored, &deck, 6));
+        assert!(!eval("deck.size > 20 && ring >= 6", &scored, &deck, 6));
+        assert!(eval("!(ring < 3) && covenant == 10", &scored, &deck, 3));
+        assert!(eval("false || true && ring != -1", &scored, &deck, 1));
+        // Overflow saturates rather than panicking or wrapping
+        let max = i64::MAX;
+        assert!(eval(&format!("{} + 1 == {}", max, max), &scored, &deck, 1));
+        assert!(eval(&format!("0 - {} - {} < 0", max, max), &scored, &deck, 1));
+    }
+
+    #[test]
+    fn test_floor_space() {
+        let scored = sized("titan", "Rare", &[], Some(3));
+        let deck = vec![
+            sized("a", "Common", &[], Some(2)),
+            sized("b", "Common", &[], Some(3)),
+            card("spell", "Common", &[]),
+        ];
+
+        assert!(eval("space() == 5 && count() == 3", &scored, &deck, 1));
+        assert!(eval(
+            "space(rarity:Common) - card.size == 2",
+            &scored,
+            &deck,
+            1
+        ));
+        assert!(eval("capacity == 15", &scored, &deck, 1));
+        assert!(!eval("space() + card.size > capacity", &scored, &deck, 1));
+        let full = vec![sized("big", "Common", &[], Some(13))];
+        assert!(eval("space() + card.size > capacity", &scored, &full, 1));
+        assert!(eval("card.size == 0", &deck[2], &full, 1));
+        assert_eq!(floor_space(&deck), 5);
+
+        let rule: Rule = "card.size >= 2 && space() + card.size > capacity - 3"
+            .parse()
+            .unwrap();
+        assert!(rule.reads(Input::Capacity));
+        assert!(!rule.reads(Input::DeckSize));
+        let boss: Rule = "!(boss(id:seraph) > 0)".parse().unwrap();
+        assert!(boss.reads(Input::Boss));
+        assert!(!boss.reads(Input::Capacity));
+    }
+
+    #[test]
+    fn test_boss() {
+        let seraph = Boss {
+            id: "seraph_the_relentless".to_string(),
+            name: "Seraph the Relentless".to_string(),
+            ring: 10,
+            min_covenant: 0,
+            mechanics: vec!["relentless".to_string(), "spikes".to_string()],
+            description: String::new(),
+        };
+        let scored = card("imp", "Common", &[]);
+        let eval_boss = |rule: &str, boss: Option<&Boss>| {
+            let rule: Rule = rule.parse().unwrap();
+            rule.evaluate(&RuleContext {
+                card: &scored,
+                deck: &[],
+                ring: 9,
+                covenant: 10,
+                pact_shards: 0,
+                boss,
+            })
+        };
+
+        assert!(eval_boss("boss(mechanic:Spikes) > 0", Some(&seraph)));
+        assert!(eval_boss(
+            "boss(mechanic:regen|relentless) == 1",
+            Some(&seraph)
+        ));
+        assert!(!eval_boss(
+            "boss(id:seraph_the_relentless, mechanic:regen) > 0",
+            Some(&seraph)
+        ));
+        assert!(eval_boss("boss() == 1", Some(&seraph)));
+        assert!(eval_boss("boss() == 0", None));
+        assert!(!eval_boss("boss(mechanic:spikes) > 0", None));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (source, error) in [
+            ("", "Unexpected end of condition"),
+            ("ring >", "Unexpected end of condition"),
+            ("ring", "Unexpected end of condition"),
+            ("gold < 100", "Unknown value 'gold'"),
+            ("count(tag:sweep) > 0", "Unknown filter 'tag'"),
+            ("boss(rarity:Rare) > 0", "Unknown boss filter 'rarity'"),
+            ("count(keyword:sweep > 0", "Expected ')' but found '>'"),
+            ("ring >= 6 ring", "Unexpected 'ring'"),
+            ("ring == 1 && 2", "Unexpected end of condition"),
+            ("ring = 1", "Unexpected '=' at 5"),
+            (
+                "ring > - covenant",
+                "Expected a number but found 'covenant'",
+            ),
+            ("count(clan:\"Luna) > 0", "Unterminated string at 11"),
+        ] {
+            assert_eq!(source.parse::<Rule>().unwrap_err(), error, "{}", source);
+        }
+        let rule: Rule = " ring <= 3 ".parse().unwrap();
+        assert_eq!(rule.to_string(), "ring <= 3");
+    }
+}
@@ -0,0 +1,341 @@
+//! Merchant purchase advice
+//!
+//! A shop offers cards, upgrades and card removal, and gold rarely covers
+//! all of it. Each offer gets a net value in score points: cards their
+//! draft score above `DILUTION_THRESHOLD` (weaker cards would only dilute
+//! the deck), upgrades a fixed value plus more for a strong target, and
+//! removal how far the deck's weakest card falls below the threshold. The
+//! most valuable set of offers the gold covers is recommended, and every
+//! other affordable offer reports the value it would cost to buy instead.
+
+use crate::database::cache::CardSnapshot;
+use crate::database::repository::CardData;
+use crate::scoring::pool::CardPool;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Cards scoring at or below this (the B tier) add nothing to a deck
+pub const DILUTION_THRESHOLD: i32 = 70;
+/// Value of an upgrade on an average card
+const UPGRADE_BASE_VALUE: i32 = 8;
+/// Most extra upgrade value for a strong target
+const UPGRADE_MAX_TARGET_BONUS: i32 = 8;
+/// Score points over the threshold per point of extra upgrade value
+const UPGRADE_POINTS_PER_BONUS: i32 = 5;
+
+/// Something the merchant sells
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShopOffer {
+    Card {
+        card_id: String,
+        price: i32,
+    },
+    /// An upgrade for `target_card_id`, or the deck's best card if unset
+    Upgrade {
+        name: String,
+        price: i32,
+        #[serde(default)]
+        target_card_id: Option<String>,
+    },
+    /// Removing a card from the deck
+    Removal {
+        price: i32,
+    },
+}
+
+impl ShopOffer {
+    pub fn price(&self) -> i32 {
+        match self {
+            ShopOffer::Card { price, .. }
+            | ShopOffer::Upgrade { price, .. }
+            | ShopOffer::Removal { price } => *price,
+        }
+    }
+
+    /// Card IDs the offer refers to
+    pub fn card_ids(&self) -> Vec<&str> {
+        match self {
+            ShopOffer::Card { card_id, .. } => vec![card_id],
+            ShopOffer::Upgrade {
+                target_card_id: Some(id),
+                ..
+            } => vec![id],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// One part of the explanation for an offer's value, serialized like
+/// `ScoreReason`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "code", content = "args", rename_all = "snake_case")]
+pub enum ShopReason {
+    DraftScore {
+        score: i32,
+    },
+    /// The card scores too low to be worth a deck slot
+    Dilutes {
+        score: i32,
+    },
+    Upgrades {
+        card_name: String,
+    },
+    Removes {
+        card_name: String,
+        score: i32,
+    },
+    /// Nothing in the deck is weak enough to be worth removing
+    NothingToRemove,
+    Unaffordable {
+        short_by: i32,
+    },
+    /// Buying this instead of the recommended offers loses value
+    OpportunityCost {
+        value_lost: i32,
+    },
+}
+
+impl fmt::Display for ShopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShopReason::DraftScore { score } => write!(f, "Draft score {}", score),
+            ShopReason::Dilutes { score } => {
+                write!(f, "Draft score {} would dilute the deck", score)
+            }
+            ShopReason::Upgrades { card_name } => write!(f, "Upgrades {}", card_name),
+            ShopReason::Removes { card_name, score } => {
+                write!(f, "Removes {}, scoring {}", card_name, score)
+            }
+            ShopReason::NothingToRemove => write!(f, "No card weak enough to remove"),
+            ShopReason::Unaffordable { short_by } => write!(f, "Needs {} more gold", short_by),
+            ShopReason::OpportunityCost { value_lost } => {
+                write!(f, "Buying this instead gives up {} value", value_lost)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OfferEvaluation {
+    pub offer: ShopOffer,
+    /// Net value in score points; 0 or less is not worth buying
+    pub value: i32,
+    /// Value per 100 gold, for offers with a price
+    pub efficiency: Option<f64>,
+    pub affordable: bool,
+    /// Part of the most valuable set of offers the gold covers
+    pub recommended: bool,
+    pub reasons: Vec<ShopReason>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShopEvaluation {
+    pub gold: i32,
+    /// Gold the recommended offers cost
+    pub spent: i32,
+    pub remaining_gold: i32,
+    /// Net value of the recommended offers
+    pub total_value: i32,
+    /// Recommended offers first, then by value
+    pub offers: Vec<OfferEvaluation>,
+}
+
+/// Values shop offers for a deck, scoring cards with a champion's pool
+pub struct ShopAdvisor<'a> {
+    pool: &'a CardPool<'a>,
+    snapshot: &'a CardSnapshot,
+    ring: i32,
+}
+
+impl<'a> ShopAdvisor<'a> {
+    pub fn new(pool: &'a CardPool<'a>, snapshot: &'a CardSnapshot, ring: i32) -> Self {
+        Self {
+            pool,
+            snapshot,
+            ring,
+        }
+    }
+
+    /// Net value of `offer` for `deck`, with the reasons for it
+    fn value(&self, offer: &ShopOffer, deck: &[CardData]) -> (i32, Vec<ShopReason>) {
+        match offer {
+            ShopOffer::Card { card_id, .. } => match self.snapshot.get(card_id) {
+                Some(card) => {
+                    let score = self.pool.score(card, deck, self.ring);
+                    let reason = if score > DILUTION_THRESHOLD {
+                        ShopReason::DraftScore { score }
+                    } else {
+                        ShopReason::Dilutes { score }
+                    };
+                    (score - DILUTION_THRESHOLD, vec![reason])
+                }
+                None => (0, Vec::new()),
+            },
+            ShopOffer::Upgrade { target_card_id, .. } => {
+                let target = match target_card_id {
+                    Some(id) => self.snapshot.get(id),
+                    None => self.strongest(deck),
+                };
+                match target {
+                    Some(card) => {
+                        let score = self.score_in_deck(card, deck);
+                        let bonus = ((score - DILUTION_THRESHOLD) / UPGRADE_POINTS_PER_BONUS)
+                            .clamp(0, UPGRADE_MAX_TARGET_BONUS);
+                        let reason = ShopReason::Upgrades {
+                            card_name: card.name.clone(),
+                        };
+                        (UPGRADE_BASE_VALUE + bonus, vec![reason])
+                    }
+                    None => (UPGRADE_BASE_VALUE, Vec::new()),
+                }
+            }
+            ShopOffer::Removal { .. } => match self.weakest(deck) {
+                Some((card, score)) if score < DILUTION_THRESHOLD => (
+                    DILUTION_THRESHOLD - score,
+                    vec![ShopReason::Removes {
+                        card_name: card.name.clone(),
+                        score,
+                    }],
+                ),
+                _ => (0, vec![ShopReason::NothingToRemove]),
+            },
+        }
+    }
+
+    /// Score of a deck card against the rest of the deck
+    fn score_in_deck(&self, card: &CardData, deck: &[CardData]) -> i32 {
+        let rest: Vec<CardData> = match deck.iter().position(|c| c.id == card.id) {
+            Some(i) => [&deck[..i], &deck[i + 1..]].concat(),
+            None => deck.to_vec(),
+        };
+        self.pool.score(card, &rest, self.ring)
+    }
+
+    /// Draftable deck cards with their scores; champions can't be removed
+    /// or upgraded at a shop
+    fn scored_deck<'d>(&self, deck: &'d [CardData]) -> Vec<(&'d CardData, i32)> {
+        deck.iter()
+            .filter(|card| card.rarity != "Champion")
+            .map(|card| (card, self.score_in_deck(card, deck)))
+            .collect()
+    }
+
+    fn strongest<'d>(&self, deck: &'d [CardData]) -> Option<&'d CardData> {
+        self.scored_deck(deck)
+            .into_iter()
+            .max_by_key(|(_, score)| *score)
+            .map(|(card, _)| card)
+    }
+
+    fn weakest<'d>(&self, deck: &'d [CardData]) -> Option<(&'d CardData, i32)> {
+        self.scored_deck(deck)
+            .into_iter()
+            .min_by_key(|(_, score)| *score)
+    }
+
+    pub fn evaluate(&self, offers: &[ShopOffer], gold: i32, deck: &[CardData]) -> ShopEvaluation {
+        let _span = tracing::info_span!("scoring.shop").entered();
+
+        let gold = gold.max(0);
+        let valued: Vec<(i32, Vec<ShopReason>)> =
+            offers.iter().map(|offer| self.value(offer, deck)).collect();
+        let items: Vec<(i32, i32)> = offers
+            .iter()
+            .zip(&valued)
+            .map(|(offer, (value, _))| (offer.price().max(0), *value))
+            .collect();
+
+        let plan = best_purchases(&items, gold, None);
+        let spent: i32 = plan.iter().map(|&i| items[i].0).sum();
+        let total_value: i32 = plan.iter().map(|&i| items[i].1).sum();
+
+        let mut evaluations: Vec<OfferEvaluation> = offers
+            .iter()
+            .zip(valued)
+            .enumerate()
+            .map(|(i, (offer, (value, mut reasons)))| {
+                let (price, _) = items[i];
+                let affordable = price <= gold;
+                let recommended = plan.contains(&i);
+                if !affordable {
+                    reasons.push(ShopReason::Unaffordable {
+                        short_by: price - gold,
+                    });
+                } else if !recommended && value > 0 {
+                    // Best value with this offer bought first
+                    let rest = best_purchases(&items, gold - price, Some(i));
+                    let forced: i32 = value + rest.iter().map(|&j| items[j].1).sum::<i32>();
+                    reasons.push(ShopReason::OpportunityCost {
+                        value_lost: total_value - forced,
+                    });
+                }
+                OfferEvaluation {
+                    offer: offer.clone(),
+                    value,
+                    efficiency: (price > 0).then(|| value as f64 * 100.0 / price as f64),
+                    affordable,
+                    recommended,
+                    reasons,
+                }
+            })
+            .collect();
+        evaluations.sort_by_key(|e| (!e.recommended, -e.value));
+
+        ShopEvaluation {
+            gold,
+            spent,
+            remaining_gold: gold - spent,
+            total_value,
+            offers: evaluations,
+        }
+    }
+}
+
+/// Indexes of the `(price, value)` items with the most total value that
+/// `gold` covers, leaving out `excluded`
+///
+/// A 0/1 knapsack over gold; items worth nothing are never bought.
+fn best_purchases(items: &[(i32, i32)], gold: i32, excluded: Option<usize>) -> Vec<usize> {
+    let gold = gold.max(0) as usize;
+    let mut best = vec![0i32; gold + 1];
+    let mut taken = vec![vec![false; gold + 1]; items.len()];
+    for (i, &(price, value)) in items.iter().enumerate() {
+        if Some(i) == excluded || value <= 0 {
+            continue;
+        }
+        let price = price as usize;
+        for g in (price..=gold).rev() {
+            if best[g - price] + value > best[g] {
+                best[g] = best[g - price] + value;
+                taken[i][g] = true;
+            }
+        }
+    }
+
+    let mut plan = Vec::new();
+    let mut g = gold;
+    for i in (0..items.len()).rev() {
+        if taken[i][g] {
+            plan.push(i);
+            g -= items[i].0 as usize;
+        }
+    }
+    plan.reverse();
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_purchases() {
+        // One pricey item loses to two cheaper ones worth more together
+        let items = [(100, 10), (60, 7), (40, 6), (30, -5)];
+        assert_eq!(best_purchases(&items, 100, None), vec![1, 2]);
+        assert_eq!(best_purchases(&items, 99, None), vec![1]);
+        assert_eq!(best_purchases(&items, 100, Some(1)), vec![0]);
+        assert_eq!(best_purchases(&items, 0, None), Vec::<usize>::new());
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Synergy {
     pub card_a_id: String,
     pub card_b_id: String,
@@ -0,0 +1,987 @@
+//! Local overlay server for OBS and other external overlays
+//!
+//! Streamers point a browser source at `ws://127.0.0.1:<port>/?token=<token>`
+//! and receive the detection results, scores and run state the overlay
+//! shows, as one JSON `OverlayUpdate` per message: the latest on connect,
+//! then every later one as it is published. Only localhost is bound, and
+//! the token keeps other local pages from reading along.
+//!
+//! The same port answers `GET` or `POST` requests to `/detect`,
+//! `/toggle-overlay` and `/undo-pick`, so Stream Deck buttons and
+//! AutoHotkey scripts can drive the app without focusing its window. These
+//! take the token as the `token` query parameter or as an
+//! `Authorization: Bearer` header, and reply with a JSON body.
+//!
+//! The WebSocket server needs the `server` feature; without it, starting it
+//! reports the feature as unavailable.
+
+use crate::commands::ocr::{detect_in_background, CardDetectionResponse};
+use crate::commands::scoring::{self, DraftScoreRequest};
+use crate::commands::window::toggle_overlay_window;
+use crate::database::access::DatabaseAccess;
+use crate::database::cache::CardCache;
+use crate::database::settings;
+use crate::error::{AppError, AppResult};
+use crate::scoring::calculator::ScoreReason;
+use crate::session::{DraftSession, SessionCard, SessionState};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{broadcast, watch};
+
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// Settings key the server configuration is stored under
+const SETTINGS_KEY: &str = "overlay_server";
+
+/// Updates a slow client may fall behind before it skips ahead
+const UPDATE_BUFFER: usize = 16;
+
+/// Persisted server configuration
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub port: u16,
+    /// Clients must pass this as the `token` query parameter
+    pub token: String,
+    /// Start the server on launch
+    pub enabled: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_PORT,
+            token: String::new(),
+            enabled: false,
+        }
+    }
+}
+
+/// Server state reported to the frontend
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ServerStatus {
+    /// Whether this build includes the `server` feature
+    pub available: bool,
+    pub running: bool,
+    pub port: u16,
+    pub token: String,
+    /// Address for a browser source, while running
+    pub url: Option<String>,
+    /// Connected clients
+    pub clients: usize,
+}
+
+/// Score of one detected card
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CardScore {
+    pub card_id: String,
+    pub card_name: String,
+    pub score: i32,
+    pub tier: String,
+    pub reasons: Vec<ScoreReason>,
+    /// Where the score falls among the champion's card pool, from 0 to 100
+    pub percentile: Option<f64>,
+}
+
+/// The run as external overlays see it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunState {
+    pub run_id: String,
+    pub champion: Option<String>,
+    pub champion_path: Option<String>,
+    pub ring_number: i32,
+    pub covenant: i32,
+    #[serde(default)]
+    pub pact_shards: i32,
+    pub deck: Vec<SessionCard>,
+}
+
+impl From<&DraftSession> for RunState {
+    fn from(session: &DraftSession) -> Self {
+        Self {
+            run_id: session.run_id.clone(),
+            champion: session.champion.clone(),
+            champion_path: session.champion_path.clone(),
+            ring_number: session.ring_number,
+            covenant: session.covenant,
+            pact_shards: session.pact_shards,
+            deck: session.deck.clone(),
+        }
+    }
+}
+
+/// One message to clients
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OverlayUpdate {
+    pub updated_at: String,
+    pub detection: CardDetectionResponse,
+    /// Scores of the detected cards, empty until a champion is chosen
+    pub scores: Vec<CardScore>,
+    pub run: RunState,
+}
+
+pub fn load_config(access: &DatabaseAccess) -> Result<ServerConfig, String> {
+    let json = access
+        .read(|conn| settings::get_setting(conn, SETTINGS_KEY))
+        .map_err(|e| e.to_string())?;
+    match json {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(ServerConfig::default()),
+    }
+}
+
+pub fn save_config(access: &DatabaseAccess, config: &ServerConfig) -> Result<(), String> {
+    let json = serde_json::to_string(config).map_err(|e| e.to_string())?;
+    access
+        .write_and_wait(move |conn| settings::set_setting(conn, SETTINGS_KEY, &json))
+        .map_err(|e| e.to_string())
+}
+
+/// Score each detected card for the current run
+///
+/// Scoring needs a champion; before one is chosen, and for cards that fail
+/// to score, the list is left short rather than failing the update.
+pub fn score_detection(
+    conn: &Connection,
+    cache: &CardCache,
+    session: &DraftSession,
+    detection: &CardDetectionResponse,
+) -> Vec<CardScore> {
+    if detection.details.is_empty() || session.champion.is_none() {
+        return Vec::new();
+    }
+    let snapshot = match cache.get(conn) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            log::warn!("Failed to load cards for overlay scores: {}", e);
+            return Vec::new();
+        }
+    };
+
+    detection
+        .details
+        .iter()
+        .filter_map(|card| {
+            let mut request = DraftScoreRequest {
+                card_id: card.card_id.clone(),
+                current_deck: Vec::new(),
+                champion: String::new(),
+                ring_number: 0,
+                covenant: 0,
+                champion_path: None,
+                upcoming_boss: None,
+                pact_shards: None,
+                upgrade_count: card.upgrade_count.unwrap_or(0),
+            };
+            session.fill_request(&mut request);
+            scoring::validate_request(&request).ok()?;
+            let result = scoring::score_card_with_history(conn, &snapshot, &request)
+                .map_err(|e| log::debug!("Failed to score {}: {}", card.card_id, e))
+                .ok()?;
+            Some(CardScore {
+                card_id: card.card_id.clone(),
+                card_name: card.card_name.clone(),
+                score: result.score,
+                tier: result.tier,
+                reasons: result.reasons,
+                percentile: result.percentile,
+            })
+        })
+        .collect()
+}
+
+/// Send a detection to connected clients, if the server is running
+pub fn publish_detection(app: &AppHandle, detection: &CardDetectionResponse) {
+    let server = app.state::<ServerState>();
+    if !server.is_running() {
+        return;
+    }
+
+    let session = match app.state::<SessionState>().snapshot() {
+        Ok(session) => session,
+        Err(e) => {
+            log::warn!("Failed to read session for overlay server: {}", e);
+            return;
+        }
+    };
+
+    server.publish(&OverlayUpdate {
+        updated_at: chrono::Utc::now().to_rfc3339(),
+        detection: detection.clone(),
+        scores: score_for_session(app, &session, detection),
+        run: RunState::from(&session),
+    });
+}
+
+/// Score each detected card against the app's database and `session`
+pub fn score_for_session(
+    app: &AppHandle,
+    session: &DraftSession,
+    detection: &CardDetectionResponse,
+) -> Vec<CardScore> {
+    let cache = app.state::<CardCache>();
+    app.state::<DatabaseAccess>()
+        .read(|conn| Ok::<_, rusqlite::Error>(score_detection(conn, &cache, session, detection)))
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to open database for scores: {}", e);
+            Vec::new()
+        })
+}
+
+/// Something an external controller can trigger over HTTP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerAction {
+    Detect,
+    ToggleOverlay,
+    UndoPick,
+}
+
+impl ServerAction {
+    /// The action served at `path`, if any
+    pub fn from_path(path: &str) -> Option<Self> {
+        match path.trim_end_matches('/') {
+            "/detect" => Some(Self::Detect),
+            "/toggle-overlay" => Some(Self::ToggleOverlay),
+            "/undo-pick" => Some(Self::UndoPick),
+            _ => None,
+        }
+    }
+}
+
+/// Runs an action for the HTTP endpoints and returns the response body
+pub type ActionHandler =
+    Arc<dyn Fn(ServerAction) -> Result<serde_json::Value, String> + Send + Sync>;
+
+/// Run an action against the app, as a hotkey would
+///
+/// Detection runs in the background and arrives as the usual
+/// `ocr-detection` event; an undone pick is emitted as `session-changed`.
+pub fn run_action(app: &AppHandle, action: ServerAction) -> Result<serde_json::Value, String> {
+    match action {
+        ServerAction::Detect => {
+            detect_in_background(app);
+            Ok(serde_json::json!({ "detecting": true }))
+        }
+        ServerAction::ToggleOverlay => {
+            let visible = toggle_overlay_window(app)?;
+            Ok(serde_json::json!({ "visible": visible }))
+        }
+        ServerAction::UndoPick => {
+            let access = app.state::<DatabaseAccess>();
+            let session = app
+                .state::<SessionState>()
+                .write_session(&access, |session, conn| {
+                    session.undo_last_pick(conn)?;
+                    Ok(session.clone())
+                })?;
+            if let Err(e) = app.emit("session-changed", &session) {
+                log::warn!("Failed to emit session change: {}", e);
+            }
+            serde_json::to_value(&session).map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "server"), allow(dead_code))]
+struct RunningServer {
+    port: u16,
+    token: String,
+    shutdown: watch::Sender<bool>,
+    thread: JoinHandle<()>,
+}
+
+/// Managed overlay server
+pub struct ServerState {
+    latest: Arc<RwLock<Option<String>>>,
+    updates: broadcast::Sender<String>,
+    clients: Arc<AtomicUsize>,
+    /// Answers the HTTP endpoints; without one they reply 503
+    actions: Option<ActionHandler>,
+    running: Mutex<Option<RunningServer>>,
+}
+
+impl Default for ServerState {
+    fn default() -> Self {
+        Self {
+            latest: Arc::default(),
+            updates: broadcast::channel(UPDATE_BUFFER).0,
+            clients: Arc::default(),
+            actions: None,
+            running: Mutex::new(None),
+        }
+    }
+}
+
+impl ServerState {
+    pub fn with_actions(mut self, actions: ActionHandler) -> Self {
+        self.actions = Some(actions);
+        self
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+            .lock()
+            .map(|running| running.is_some())
+            .unwrap_or(false)
+    }
+
+    /// Store `update` as the latest and send it to connected clients
+    pub fn publish(&self, update: &OverlayUpdate) {
+        let json = match serde_json::to_string(update) {
+            Ok(json) => json,
+            Err(e) => {
+                log::warn!("Failed to serialize overlay update: {}", e);
+                return;
+            }
+        };
+        *self.latest.write().unwrap_or_else(|e| e.into_inner()) = Some(json.clone());
+        // No receivers just means no clients are connected
+        let _ = self.updates.send(json);
+    }
+
+    pub fn status(&self, config: &ServerConfig) -> ServerStatus {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        let port = running.as_ref().map_or(config.port, |server| server.port);
+        ServerStatus {
+            available: cfg!(feature = "server"),
+            running: running.is_some(),
+            port,
+            token: config.token.clone(),
+            url: running
+                .as_ref()
+                .map(|server| format!("ws://127.0.0.1:{}/?token={}", server.port, server.token)),
+            clients: self.clients.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Start serving, or restart if the port or token changed
+    ///
+    /// A token is generated first if `config` has none, or if
+    /// `regenerate_token` is set; the caller saves the updated config.
+    #[cfg(feature = "server")]
+    pub fn start(&self, config: &mut ServerConfig, regenerate_token: bool) -> AppResult<()> {
+        if config.token.is_empty() || regenerate_token {
+            config.token = ws::generate_token();
+        }
+
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(server) = running.as_ref() {
+            if server.port == config.port && server.token == config.token {
+                return Ok(());
+            }
+        }
+        if let Some(server) = running.take() {
+            server.stop();
+        }
+
+        let listener = std::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, config.port))
+            .map_err(|e| {
+                AppError::Unavailable(format!("Failed to listen on port {}: {}", config.port, e))
+            })?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .port();
+
+        let shared = Arc::new(ws::Shared {
+            token: config.token.clone(),
+            latest: self.latest.clone(),
+            updates: self.updates.clone(),
+            clients: self.clients.clone(),
+            actions: self.actions.clone(),
+        });
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let thread = ws::spawn(listener, shared, shutdown_rx)
+            .map_err(|e| AppError::Internal(format!("Failed to start overlay server: {}", e)))?;
+
+        log::info!("Overlay server listening on 127.0.0.1:{}", port);
+        *running = Some(RunningServer {
+            port,
+            token: config.token.clone(),
+            shutdown,
+            thread,
+        });
+        Ok(())
+    }
+
+    /// Stub when the `server` feature is disabled
+    #[cfg(not(feature = "server"))]
+    pub fn start(&self, _config: &mut ServerConfig, _regenerate_token: bool) -> AppResult<()> {
+        Err(AppError::Unavailable(
+            "The overlay server is not included in this build. Rebuild with --features server to use it."
+                .to_string(),
+        ))
+    }
+
+    /// Stop serving and disconnect all clients
+    pub fn stop(&self) {
+        let server = self
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(server) = server {
+            server.stop();
+            log::info!("Overlay server stopped");
+        }
+    }
+}
+
+impl RunningServer {
+    fn stop(self) {
+        let _ = self.shutdown.send(true);
+        if self.thread.join().is_err() {
+            log::warn!("Overlay server thread panicked");
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+mod ws {
+    use super::{ActionHandler, ServerAction};
+    use futures_util::{SinkExt, StreamExt};
+    use rand::distributions::{Alphanumeric, DistString};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::sync::RwLock;
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::broadcast;
+    use tokio::sync::broadcast::error::RecvError;
+    use tokio::sync::watch;
+    use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+    use tokio_tungstenite::tungstenite::http::StatusCode;
+    use tokio_tungstenite::tungstenite::Message;
+
+    const TOKEN_LEN: usize = 32;
+
+    /// Largest HTTP request head read for the action endpoints
+    const MAX_HEAD: usize = 8192;
+
+    /// Times the request line is peeked for before giving up on it
+    const PEEK_ATTEMPTS: usize = 50;
+
+    /// The `token` query parameter of a request
+    pub(super) fn request_token(query: Option<&str>) -> Option<&str> {
+        query?
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    }
+
+    /// Compare tokens without returning early on the first difference
+    pub(super) fn tokens_match(given: &str, expected: &str) -> bool {
+        given.len() == expected.len()
+            && given
+                .bytes()
+                .zip(expected.bytes())
+                .fold(0, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+
+    /// State shared with client connections
+    pub(super) struct Shared {
+        pub token: String,
+        pub latest: Arc<RwLock<Option<String>>>,
+        pub updates: broadcast::Sender<String>,
+        pub clients: Arc<AtomicUsize>,
+        pub actions: Option<ActionHandler>,
+    }
+
+    /// The parts of an HTTP request the action endpoints look at
+    #[derive(Debug, PartialEq)]
+    pub(super) struct HttpRequest<'a> {
+        pub method: &'a str,
+        pub path: &'a str,
+        pub query: Option<&'a str>,
+        /// Token from an `Authorization: Bearer` header
+        pub bearer: Option<&'a str>,
+    }
+
+    impl<'a> HttpRequest<'a> {
+        pub fn parse(head: &'a str) -> Option<Self> {
+            let mut lines = head.split("\r\n");
+            let mut request_line = lines.next()?.split(' ');
+            let method = request_line.next()?;
+            let target = request_line.next()?;
+            let (path, query) = match target.split_once('?') {
+                Some((path, query)) => (path, Some(query)),
+                None => (target, None),
+            };
+            let bearer = lines.find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                if !name.trim().eq_ignore_ascii_case("authorization") {
+                    return None;
+                }
+                value.trim().strip_prefix("Bearer ").map(str::trim)
+            });
+            Some(Self {
+                method,
+                path,
+                query,
+                bearer,
+            })
+        }
+    }
+
+    pub fn generate_token() -> String {
+        Alphanumeric.sample_string(&mut rand::thread_rng(), TOKEN_LEN)
+    }
+
+    /// Serve on a thread of its own until `shutdown` is set
+    pub fn spawn(
+        listener: std::net::TcpListener,
+        shared: Arc<Shared>,
+        shutdown: watch::Receiver<bool>,
+    ) -> std::io::Result<JoinHandle<()>> {
+        listener.set_nonblocking(true)?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        std::thread::Builder::new()
+            .name("overlay-server".to_string())
+            .spawn(move || runtime.block_on(accept_loop(listener, shared, shutdown)))
+    }
+
+    /// Dropping the runtime when this returns closes every client
+    async fn accept_loop(
+        listener: std::net::TcpListener,
+        shared: Arc<Shared>,
+        mut shutdown: watch::Receiver<bool>,
+    ) {
+        let listener = match TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("Overlay server failed to listen: {}", e);
+                return;
+            }
+        };
+
+        loop {
+            tokio::select! {
+                _ = shutdown.changed() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        tokio::spawn(handle_client(stream, shared.clone()));
+                    }
+                    Err(e) => log::warn!("Overlay server failed to accept: {}", e),
+                },
+            }
+        }
+    }
+
+    /// Counts a client as connected while alive
+    struct ClientGuard(Arc<AtomicUsize>);
+
+    impl ClientGuard {
+        fn new(clients: &Arc<AtomicUsize>) -> Self {
+            clients.fetch_add(1, Ordering::Relaxed);
+            Self(clients.clone())
+        }
+    }
+
+    impl Drop for ClientGuard {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Serve an action endpoint over plain HTTP, anything else as a WebSocket
+    async fn handle_client(stream: TcpStream, shared: Arc<Shared>) {
+        let is_action = peek_request_line(&stream)
+            .await
+            .and_then(|line| HttpRequest::parse(&line).map(|r| ServerAction::from_path(r.path)))
+            .flatten()
+            .is_some();
+        if is_action {
+            serve_action(stream, &shared).await;
+        } else {
+            serve_websocket(stream, shared).await;
+        }
+    }
+
+    /// The request line, left unread so a handshake can still read it
+    async fn peek_request_line(stream: &TcpStream) -> Option<String> {
+        let mut buf = [0u8; 1024];
+        for _ in 0..PEEK_ATTEMPTS {
+            let read = stream.peek(&mut buf).await.ok()?;
+            if read == 0 {
+                return None;
+            }
+            if let Some(end) = buf[..read].windows(2).position(|w| w == b"\r\n") {
+                return String::from_utf8(buf[..end].to_vec()).ok();
+            }
+            if read == buf.len() {
+                return None;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        None
+    }
+
+    async fn read_head(stream: &mut TcpStream) -> Option<String> {
+        let mut head = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+            let read = stream.read(&mut buf).await.ok()?;
+            if read == 0 || head.len() + read > MAX_HEAD {
+                return None;
+            }
+            head.extend_from_slice(&buf[..read]);
+        }
+        String::from_utf8(head).ok()
+    }
+
+    async fn serve_action(mut stream: TcpStream, shared: &Shared) {
+        let (status, body) = match read_head(&mut stream).await {
+            Some(head) => respond(&head, shared).await,
+            None => (
+                StatusCode::BAD_REQUEST,
+                serde_json::json!({ "error": "Malformed request" }),
+            ),
+        };
+        let body = body.to_string();
+        let response = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or_default(),
+            body.len(),
+            body
+        );
+        if let Err(e) = stream.write_all(response.as_bytes()).await {
+            log::debug!("Failed to answer overlay server request: {}", e);
+        }
+        let _ = stream.shutdown().await;
+    }
+
+    async fn respond(head: &str, shared: &Shared) -> (StatusCode, serde_json::Value) {
+        let error =
+            |status: StatusCode, message: &str| (status, serde_json::json!({ "error": message }));
+        let Some(request) = HttpRequest::parse(head) else {
+            return error(StatusCode::BAD_REQUEST, "Malformed request");
+        };
+        let Some(action) = ServerAction::from_path(request.path) else {
+            return error(StatusCode::NOT_FOUND, "Unknown endpoint");
+        };
+        if !matches!(request.method, "GET" | "POST") {
+            return error(StatusCode::METHOD_NOT_ALLOWED, "Use GET or POST");
+        }
+        match request_token(request.query).or(request.bearer) {
+            Some(token) if tokens_match(token, &shared.token) => {}
+            _ => return error(StatusCode::UNAUTHORIZED, "Invalid or missing token"),
+        }
+        let Some(actions) = shared.actions.clone() else {
+            return error(StatusCode::SERVICE_UNAVAILABLE, "Actions are not available");
+        };
+
+        log::debug!("Overlay server action: {:?}", action);
+        // Actions touch the database and windows, so keep them off the runtime
+        match tokio::task::spawn_blocking(move || actions(action)).await {
+            Ok(Ok(body)) => (StatusCode::OK, body),
+            Ok(Err(e)) => error(StatusCode::CONFLICT, &e),
+            Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+        }
+    }
+
+    // The handshake callback's error type is tungstenite's
+    #[allow(clippy::result_large_err)]
+    async fn serve_websocket(stream: TcpStream, shared: Arc<Shared>) {
+        let authorize =
+            |request: &Request, response: Response| match request_token(request.uri().query()) {
+                Some(token) if tokens_match(token, &shared.token) => Ok(response),
+                _ => {
+                    let mut error =
+                        ErrorResponse::new(Some("Invalid or missing token".to_string()));
+                    *error.status_mut() = StatusCode::UNAUTHORIZED;
+                    Err(error)
+                }
+            };
+        let socket = match tokio_tungstenite::accept_hdr_async(stream, authorize).await {
+            Ok(socket) => socket,
+            Err(e) => {
+                log::debug!("Rejected overlay client: {}", e);
+                return;
+            }
+        };
+
+        let _guard = ClientGuard::new(&shared.clients);
+        let mut updates = shared.updates.subscribe();
+        let (mut sink, mut incoming) = socket.split();
+
+        let latest = shared
+            .latest
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if let Some(latest) = latest {
+            if sink.send(Message::Text(latest)).await.is_err() {
+                return;
+            }
+        }
+
+        loop {
+            tokio::select! {
+                update = updates.recv() => match update {
+                    Ok(json) => {
+                        if sink.send(Message::Text(json)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                },
+                // Pings are answered by tungstenite; other messages are ignored
+                message = incoming.next() => match message {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_request_token() {
+        use ws::{request_token, tokens_match};
+
+        assert_eq!(request_token(Some("token=abc")), Some("abc"));
+        assert_eq!(request_token(Some("theme=dark&token=abc")), Some("abc"));
+        assert_eq!(request_token(Some("theme=dark")), None);
+        assert_eq!(request_token(None), None);
+
+        assert!(tokens_match("abc", "abc"));
+        assert!(!tokens_match("abd", "abc"));
+        assert!(!tokens_match("ab", "abc"));
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_parse_http_request() {
+        use ws::HttpRequest;
+
+        let request = HttpRequest::parse(
+            "POST /undo-pick HTTP/1.1\r\nHost: localhost\r\nauthorization: Bearer abc \r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            request,
+            HttpRequest {
+                method: "POST",
+                path: "/undo-pick",
+                query: None,
+                bearer: Some("abc"),
+            }
+        );
+        assert_eq!(
+            ServerAction::from_path(request.path),
+            Some(ServerAction::UndoPick)
+        );
+
+        let request = HttpRequest::parse("GET /detect/?token=abc HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(request.query, Some("token=abc"));
+        assert_eq!(request.bearer, None);
+        assert_eq!(
+            ServerAction::from_path(request.path),
+            Some(ServerAction::Detect)
+        );
+        assert_eq!(ServerAction::from_path("/"), None);
+    }
+
+    #[test]
+    fn test_score_detection_uses_the_session() {
+        use crate::commands::ocr::{CaptureRegionInfo, DetectedCardInfo};
+
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        crate::database::init(temp.path()).unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+        let detection = CardDetectionResponse {
+            detected_cards: vec!["Just Cause".to_string()],
+            confidence: 0.9,
+            success: true,
+            error: None,
+            details: vec![DetectedCardInfo {
+                card_id: "banished_just_cause".to_string(),
+                card_name: "Just Cause".to_string(),
+                confidence: 0.9,
+                ocr_confidence: 90,
+                match_score: 100,
+                raw_text: "Just Cause".to_string(),
+                region: CaptureRegionInfo {
+                    x: 0,
+                    y: 0,
+                    width: 300,
+                    height: 60,
+                    role: None,
+                },
+                ambiguous: false,
+                candidates: vec![],
+                banner: None,
+                is_favorite: false,
+                rules_text: None,
+                text_check: None,
+                upgrade_count: None,
+            }],
+            unavailable: None,
+            region_hashes: vec![],
+            new_offer: false,
+        };
+
+        let cache = CardCache::default();
+        let mut session = DraftSession::default();
+        // No champion yet, so nothing can be scored
+        assert!(score_detection(&conn, &cache, &session, &detection).is_empty());
+
+        session.champion = Some("Fel".to_string());
+        let scores = score_detection(&conn, &cache, &session, &detection);
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[0].card_id, "banished_just_cause");
+        assert!(scores[0].score > 0);
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let temp = tempfile::NamedTempFile::new().unwrap();
+        crate::database::init(temp.path()).unwrap();
+        let access = DatabaseAccess::new(temp.path().to_path_buf());
+        assert_eq!(load_config(&access).unwrap(), ServerConfig::default());
+
+        let config = ServerConfig {
+            port: 9000,
+            token: "secret".to_string(),
+            enabled: true,
+        };
+        save_config(&access, &config).unwrap();
+        assert_eq!(load_config(&access).unwrap(), config);
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_clients_need_token_and_get_updates() {
+        use futures_util::StreamExt;
+
+        let update = |run_id: &str| OverlayUpdate {
+            updated_at: String::new(),
+            detection: CardDetectionResponse {
+                detected_cards: vec![],
+                confidence: 0.0,
+                success: true,
+                error: None,
+                details: vec![],
+                unavailable: None,
+                region_hashes: vec![],
+                new_offer: false,
+            },
+            scores: vec![],
+            run: RunState::from(&DraftSession {
+                run_id: run_id.to_string(),
+                ..DraftSession::default()
+            }),
+        };
+        let run_id = |message: tokio_tungstenite::tungstenite::Message| {
+            let update: OverlayUpdate = serde_json::from_str(message.to_text().unwrap()).unwrap();
+            update.run.run_id
+        };
+
+        let state = ServerState::default();
+        let mut config = ServerConfig {
+            port: 0,
+            ..ServerConfig::default()
+        };
+        state.start(&mut config, false).unwrap();
+        assert_eq!(config.token.len(), 32);
+        let status = state.status(&config);
+        assert!(status.running);
+        state.publish(&update("run-1"));
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let wrong = format!("ws://127.0.0.1:{}/?token=nope", status.port);
+            assert!(tokio_tungstenite::connect_async(wrong).await.is_err());
+
+            let (mut socket, _) = tokio_tungstenite::connect_async(status.url.clone().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(run_id(socket.next().await.unwrap().unwrap()), "run-1");
+
+            state.publish(&update("run-2"));
+            assert_eq!(run_id(socket.next().await.unwrap().unwrap()), "run-2");
+            assert_eq!(state.status(&config).clients, 1);
+
+            state.stop();
+            assert!(!state.is_running());
+            assert!(matches!(
+                socket.next().await,
+                None | Some(Err(_)) | Some(Ok(tokio_tungstenite::tungstenite::Message::Close(_)))
+            ));
+        });
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn test_action_endpoints() {
+        use std::io::{Read, Write};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let state = ServerState::default().with_actions(Arc::new(move |action| {
+            recorded.lock().unwrap().push(action);
+            match action {
+                ServerAction::UndoPick => Err("Nothing to undo".to_string()),
+                _ => Ok(serde_json::json!({ "ok": true })),
+            }
+        }));
+        let mut config = ServerConfig {
+            port: 0,
+            ..ServerConfig::default()
+        };
+        state.start(&mut config, false).unwrap();
+        let port = state.status(&config).port;
+
+        let request = |head: String| {
+            let mut stream = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+            stream.write_all(head.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = request("GET /detect?token=nope HTTP/1.1\r\n\r\n".to_string());
+        assert!(response.starts_with("HTTP/1.1 401"), "{}", response);
+        let response = request(format!(
+            "DELETE /detect?token={} HTTP/1.1\r\n\r\n",
+            config.token
+        ));
+        assert!(response.starts_with("HTTP/1.1 405"), "{}", response);
+        assert!(seen.lock().unwrap().is_empty());
+
+        let response = request(format!(
+            "GET /detect?token={} HTTP/1.1\r\n\r\n",
+            config.token
+        ));
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.ends_with(r#"{"ok":true}"#), "{}", response);
+
+        let response = request(format!(
+            "POST /undo-pick HTTP/1.1\r\nAuthorization: Bearer {}\r\nContent-Length: 0\r\n\r\n",
+            config.token
+        ));
+        assert!(response.starts_with("HTTP/1.1 409"), "{}", response);
+        assert!(response.contains("Nothing to undo"), "{}", response);
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [ServerAction::Detect, ServerAction::UndoPick]
+        );
+        state.stop();
+    }
+}
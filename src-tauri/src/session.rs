@@ -0,0 +1,829 @@
+//! Draft session state
+//!
+//! The current run's champion, ring, covenant, pact shards and drafted deck
+//! live here
+//! rather than in the frontend, so scoring calls only need the card being
+//! scored and every drafted card is logged to `deck_history` exactly once.
+//! The session also remembers the last offer detected on screen, so a pick
+//! from it is recorded in `draft_offers` too, with each offered card's
+//! slot and score. Once the champion is known
+//! the run itself is kept in `runs`, where its outcome is recorded when it
+//! ends.
+
+use crate::commands::ocr::CardDetectionResponse;
+use crate::commands::scoring::{self, DraftScoreRequest};
+use crate::database::access::DatabaseAccess;
+use crate::database::cache::CardSnapshot;
+use crate::database::repository;
+use crate::error::AppError;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// A card drafted this run
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SessionCard {
+    pub card_id: String,
+    pub ring_number: i32,
+    /// Position in the draft order, starting at 1
+    pub draft_order: i32,
+    pub score_at_draft: Option<i32>,
+}
+
+/// A reversible change to the drafted deck
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PickOperation {
+    /// A card was drafted, possibly from a detected offer
+    Add {
+        card: SessionCard,
+        /// The detected offer the card was picked from, if any
+        offered_card_ids: Vec<String>,
+        /// `draft_offers` rows written for the offer
+        #[serde(skip)]
+        offer_row_ids: Vec<i64>,
+    },
+    /// A card was removed from position `index` of the deck
+    Remove { card: SessionCard, index: usize },
+}
+
+/// The run being drafted
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DraftSession {
+    pub run_id: String,
+    pub champion: Option<String>,
+    /// The champion's upgrade path, if chosen
+    #[serde(default)]
+    pub champion_path: Option<String>,
+    pub ring_number: i32,
+    pub covenant: i32,
+    /// Pact shards collected in an endless run; 0 otherwise
+    #[serde(default)]
+    pub pact_shards: i32,
+    pub deck: Vec<SessionCard>,
+    /// Cards in the most recently detected draft offer
+    pub offered_card_ids: Vec<String>,
+    /// Applied operations, most recent last
+    pub undo_stack: Vec<PickOperation>,
+    /// Undone operations, most recently undone last
+    pub redo_stack: Vec<PickOperation>,
+}
+
+impl Default for DraftSession {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl DraftSession {
+    /// Start a new run at ring 1
+    pub fn new(covenant: i32) -> Self {
+        Self {
+            run_id: format!("run-{}", chrono::Utc::now().timestamp_millis()),
+            champion: None,
+            champion_path: None,
+            ring_number: 1,
+            covenant,
+            pact_shards: 0,
+            deck: Vec::new(),
+            offered_card_ids: Vec::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    /// Card ids in the deck, in draft order
+    pub fn deck_ids(&self) -> Vec<String> {
+        self.deck.iter().map(|c| c.card_id.clone()).collect()
+    }
+
+    /// Fill fields a scoring request left unset from this session
+    pub fn fill_request(&self, request: &mut DraftScoreRequest) {
+        if request.champion.trim().is_empty() {
+            if let Some(ref champion) = self.champion {
+                request.champion = champion.clone();
+            }
+        }
+        if request.current_deck.is_empty() {
+            request.current_deck = self.deck_ids();
+        }
+        if request.ring_number == 0 {
+            request.ring_number = self.ring_number;
+        }
+        if request.covenant == 0 {
+            request.covenant = self.covenant;
+        }
+        if request.pact_shards.is_none() {
+            request.pact_shards = Some(self.pact_shards);
+        }
+        if request.champion_path.is_none() && self.champion.as_deref() == Some(&request.champion) {
+            request.champion_path = self.champion_path.clone();
+        }
+    }
+
+    /// Draft a card, logging it to `deck_history`
+    ///
+    /// If the card was part of the last detected offer, the offer and pick
+    /// are also recorded in `draft_offers`.
+    pub fn add_card(
+        &mut self,
+        conn: &Connection,
+        card_id: &str,
+        score_at_draft: Option<i32>,
+    ) -> Result<&SessionCard, String> {
+        let exists = repository::card_exists(conn, card_id).map_err(|e| e.to_string())?;
+        if !exists {
+            return Err(format!("Card '{}' not found", card_id));
+        }
+
+        let card = SessionCard {
+            card_id: card_id.to_string(),
+            ring_number: self.ring_number,
+            draft_order: self.deck.iter().map(|c| c.draft_order).max().unwrap_or(0) + 1,
+            score_at_draft,
+        };
+        let offered_card_ids = if self.offered_card_ids.iter().any(|id| id == card_id) {
+            std::mem::take(&mut self.offered_card_ids)
+        } else {
+            Vec::new()
+        };
+
+        let mut op = PickOperation::Add {
+            card,
+            offered_card_ids,
+            offer_row_ids: Vec::new(),
+        };
+        if let Err(e) = self.apply(conn, &mut op) {
+            if let PickOperation::Add {
+                offered_card_ids, ..
+            } = op
+            {
+                if !offered_card_ids.is_empty() {
+                    self.offered_card_ids = offered_card_ids;
+                }
+            }
+            return Err(e);
+        }
+        self.record(op);
+        Ok(self.deck.last().expect("card was just pushed"))
+    }
+
+    /// Remove the most recently drafted copy of a card and its history row
+    pub fn remove_card(&mut self, conn: &Connection, card_id: &str) -> Result<SessionCard, String> {
+        let index = self
+            .deck
+            .iter()
+            .rposition(|c| c.card_id == card_id)
+            .ok_or_else(|| format!("Card '{}' is not in the deck", card_id))?;
+
+        let card = self.deck[index].clone();
+        let mut op = PickOperation::Remove {
+            card: card.clone(),
+            index,
+        };
+        self.apply(conn, &mut op)?;
+        self.record(op);
+        Ok(card)
+    }
+
+    /// Revert the most recent add or remove
+    ///
+    /// Undoing a pick from a detected offer also deletes the recorded offer
+    /// and restores it, so the correct card can be picked instead.
+    pub fn undo_last_pick(&mut self, conn: &Connection) -> Result<PickOperation, String> {
+        let op = self
+            .undo_stack
+            .pop()
+            .ok_or_else(|| "Nothing to undo".to_string())?;
+
+        if let Err(e) = self.revert(conn, &op) {
+            self.undo_stack.push(op);
+            return Err(e);
+        }
+        if let PickOperation::Add {
+            offered_card_ids, ..
+        } = &op
+        {
+            if !offered_card_ids.is_empty() {
+                self.offered_card_ids = offered_card_ids.clone();
+            }
+        }
+
+        self.redo_stack.push(op.clone());
+        Ok(op)
+    }
+
+    /// Reapply the most recently undone add or remove
+    pub fn redo_pick(&mut self, conn: &Connection) -> Result<PickOperation, String> {
+        let mut op = self
+            .redo_stack
+            .pop()
+            .ok_or_else(|| "Nothing to redo".to_string())?;
+
+        if let Err(e) = self.apply(conn, &mut op) {
+            self.redo_stack.push(op);
+            return Err(e);
+        }
+        if let PickOperation::Add {
+            offered_card_ids, ..
+        } = &op
+        {
+            if !offered_card_ids.is_empty() {
+                self.offered_card_ids.clear();
+            }
+        }
+
+        self.undo_stack.push(op.clone());
+        Ok(op)
+    }
+
+    /// Apply an operation to the history, then the deck
+    ///
+    /// Row ids of recorded offers are stored back into `op` so the offer
+    /// can be deleted again on undo.
+    fn apply(&mut self, conn: &Connection, op: &mut PickOperation) -> Result<(), String> {
+        let offer_scores = match op {
+            PickOperation::Add {
+                offered_card_ids, ..
+            } if !offered_card_ids.is_empty() => self.offer_scores(conn, offered_card_ids),
+            _ => Vec::new(),
+        };
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        match op {
+            PickOperation::Add {
+                card,
+                offered_card_ids,
+                offer_row_ids,
+            } => {
+                self.insert_history(&tx, card)?;
+
+                let mut stmt = tx
+                    .prepare(
+                        "INSERT INTO draft_offers
+                             (run_id, ring_number, card_id, was_picked, slot, score)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    )
+                    .map_err(|e| e.to_string())?;
+                offer_row_ids.clear();
+                for (i, offered) in offered_card_ids.iter().enumerate() {
+                    stmt.execute(params![
+                        self.run_id,
+                        card.ring_number,
+                        offered,
+                        *offered == card.card_id,
+                        i as i32 + 1,
+                        offer_scores.get(i).copied().flatten()
+                    ])
+                    .map_err(|e| format!("Failed to record draft offer: {}", e))?;
+                    offer_row_ids.push(tx.last_insert_rowid());
+                }
+            }
+            PickOperation::Remove { card, .. } => self.delete_history(&tx, card)?,
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+
+        match op {
+            PickOperation::Add { card, .. } => self.deck.push(card.clone()),
+            PickOperation::Remove { card, .. } => self.remove_from_deck(card),
+        }
+        Ok(())
+    }
+
+    /// Score each offered card against the deck before the pick
+    ///
+    /// Best effort and without the player's history adjustment; cards that
+    /// can't be scored, such as before a champion is chosen, get `None`.
+    fn offer_scores(&self, conn: &Connection, offered_card_ids: &[String]) -> Vec<Option<i32>> {
+        let snapshot = match CardSnapshot::load(conn) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                log::warn!("Failed to load cards to score the offer: {}", e);
+                return vec![None; offered_card_ids.len()];
+            }
+        };
+        offered_card_ids
+            .iter()
+            .map(|card_id| {
+                let mut request = DraftScoreRequest {
+                    card_id: card_id.clone(),
+                    current_deck: Vec::new(),
+                    champion: String::new(),
+                    ring_number: 0,
+                    covenant: 0,
+                    champion_path: None,
+                    upcoming_boss: None,
+                    pact_shards: None,
+                    upgrade_count: 0,
+                };
+                self.fill_request(&mut request);
+                scoring::validate_request(&request).ok()?;
+                scoring::score_card(conn, &snapshot, &request)
+                    .map(|result| result.score)
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Undo an applied operation in the history, then the deck
+    fn revert(&mut self, conn: &Connection, op: &PickOperation) -> Result<(), String> {
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        match op {
+            PickOperation::Add {
+                card,
+                offer_row_ids,
+                ..
+            } => {
+                self.delete_history(&tx, card)?;
+                for row_id in offer_row_ids {
+                    tx.execute("DELETE FROM draft_offers WHERE id = ?1", [row_id])
+                        .map_err(|e| format!("Failed to delete draft offer: {}", e))?;
+                }
+            }
+            PickOperation::Remove { card, .. } => self.insert_history(&tx, card)?,
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+
+        match op {
+            PickOperation::Add { card, .. } => self.remove_from_deck(card),
+            PickOperation::Remove { card, index } => {
+                let index = (*index).min(self.deck.len());
+                self.deck.insert(index, card.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Log a newly applied operation; it replaces anything that could be redone
+    fn record(&mut self, op: PickOperation) {
+        self.undo_stack.push(op);
+        self.redo_stack.clear();
+    }
+
+    fn remove_from_deck(&mut self, card: &SessionCard) {
+        self.deck.retain(|c| c.draft_order != card.draft_order);
+    }
+
+    fn insert_history(&self, conn: &Connection, card: &SessionCard) -> Result<(), String> {
+        let champion = self
+            .champion
+            .as_deref()
+            .ok_or_else(|| "Set the champion before drafting cards".to_string())?;
+
+        conn.execute(
+            "INSERT INTO deck_history (run_id, card_id, ring_number, draft_order, champion, covenant, score_at_draft)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                self.run_id,
+                card.card_id,
+                card.ring_number,
+                card.draft_order,
+                champion,
+                self.covenant,
+                card.score_at_draft
+            ],
+        )
+        .map_err(|e| format!("Failed to log drafted card: {}", e))?;
+        Ok(())
+    }
+
+    fn delete_history(&self, conn: &Connection, card: &SessionCard) -> Result<(), String> {
+        conn.execute(
+            "DELETE FROM deck_history WHERE run_id = ?1 AND card_id = ?2 AND draft_order = ?3",
+            params![self.run_id, card.card_id, card.draft_order],
+        )
+        .map_err(|e| format!("Failed to remove drafted card from history: {}", e))?;
+        Ok(())
+    }
+
+    /// Change the champion, including on cards already logged this run
+    pub fn set_champion(
+        &mut self,
+        conn: &Connection,
+        champion: &str,
+        path: Option<&str>,
+    ) -> Result<(), String> {
+        let champion = champion.trim();
+        if champion.is_empty() {
+            return Err("Champion cannot be empty".to_string());
+        }
+        let path = path.map(str::trim).filter(|p| !p.is_empty());
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "UPDATE deck_history SET champion = ?1 WHERE run_id = ?2",
+            params![champion, self.run_id],
+        )
+        .map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT INTO runs (run_id, champion, champion_path, covenant)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(run_id) DO UPDATE SET
+                champion = excluded.champion,
+                champion_path = excluded.champion_path",
+            params![self.run_id, champion, path, self.covenant],
+        )
+        .map_err(|e| format!("Failed to save run: {}", e))?;
+        tx.commit().map_err(|e| e.to_string())?;
+
+        self.champion = Some(champion.to_string());
+        self.champion_path = path.map(str::to_string);
+        Ok(())
+    }
+
+    /// Record the run's outcome on the run and every card drafted in it
+    pub fn end_run(&self, conn: &Connection, won: bool) -> Result<(), String> {
+        if self.champion.is_none() {
+            return Err("Set the champion before ending the run".to_string());
+        }
+
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "UPDATE runs SET did_win = ?1, final_ring = ?2, ended_at = CURRENT_TIMESTAMP
+             WHERE run_id = ?3",
+            params![won, self.ring_number, self.run_id],
+        )
+        .map_err(|e| format!("Failed to record run outcome: {}", e))?;
+        tx.execute(
+            "UPDATE deck_history SET did_win = ?1 WHERE run_id = ?2",
+            params![won, self.run_id],
+        )
+        .map_err(|e| format!("Failed to record run outcome: {}", e))?;
+        tx.commit().map_err(|e| e.to_string())
+    }
+
+    /// Move to another ring
+    pub fn set_ring(&mut self, ring_number: i32) -> Result<(), String> {
+        if !(1..=10).contains(&ring_number) {
+            return Err("Ring number must be between 1 and 10".to_string());
+        }
+        self.ring_number = ring_number;
+        Ok(())
+    }
+
+    pub fn set_pact_shards(&mut self, pact_shards: i32) -> Result<(), String> {
+        if pact_shards < 0 {
+            return Err("Pact shards cannot be negative".to_string());
+        }
+        self.pact_shards = pact_shards;
+        Ok(())
+    }
+}
+
+/// Managed draft session
+pub struct SessionState {
+    session: Mutex<DraftSession>,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self::new(DraftSession::default())
+    }
+}
+
+impl SessionState {
+    pub fn new(session: DraftSession) -> Self {
+        Self {
+            session: Mutex::new(session),
+        }
+    }
+
+    /// Run `f` with exclusive access to the session
+    pub fn with_session<T, F>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&mut DraftSession) -> Result<T, String>,
+    {
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| "Draft session lock poisoned".to_string())?;
+        f(&mut session)
+    }
+
+    /// Run `change` on the database writer with a copy of the session
+    ///
+    /// The session stays locked while the writer runs it, and the copy
+    /// replaces the session only if `change` succeeds.
+    pub fn write_session<T, F>(&self, access: &DatabaseAccess, change: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut DraftSession, &Connection) -> Result<T, String> + Send + 'static,
+    {
+        self.with_session(|session| {
+            let mut updated = session.clone();
+            let (updated, result) = access
+                .write_and_wait(move |conn| {
+                    let result = change(&mut updated, conn).map_err(AppError::Internal)?;
+                    Ok::<_, AppError>((updated, result))
+                })
+                .map_err(|e| e.to_string())?;
+            *session = updated;
+            Ok(result)
+        })
+    }
+
+    /// Snapshot of the session
+    pub fn snapshot(&self) -> Result<DraftSession, String> {
+        self.with_session(|session| Ok(session.clone()))
+    }
+
+    /// Remember the cards offered on a detected draft screen
+    pub fn note_detection(&self, response: &CardDetectionResponse) {
+        if !response.is_draft_screen() {
+            return;
+        }
+
+        let card_ids: Vec<String> = response.details.iter().map(|d| d.card_id.clone()).collect();
+        if let Err(e) = self.with_session(|session| {
+            session.offered_card_ids = card_ids;
+            Ok(())
+        }) {
+            log::warn!("Failed to note draft offer: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn setup() -> (Connection, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        (conn, temp_file)
+    }
+
+    fn history_rows(conn: &Connection, run_id: &str) -> Vec<(String, i32, String)> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT card_id, draft_order, champion FROM deck_history
+                 WHERE run_id = ?1 ORDER BY draft_order",
+            )
+            .unwrap();
+        stmt.query_map([run_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_add_and_remove_cards_logs_history() {
+        let (conn, _temp) = setup();
+        let mut session = DraftSession::new(10);
+
+        assert!(session.add_card(&conn, "banished_cleave", None).is_err());
+        session.set_champion(&conn, "Fel", None).unwrap();
+        assert!(session.add_card(&conn, "no_such_card", None).is_err());
+
+        session
+            .add_card(&conn, "banished_cleave", Some(70))
+            .unwrap();
+        session
+            .add_card(&conn, "banished_just_cause", None)
+            .unwrap();
+        assert_eq!(
+            session.deck_ids(),
+            vec!["banished_cleave", "banished_just_cause"]
+        );
+
+        session.set_champion(&conn, "Talos", None).unwrap();
+        assert_eq!(
+            history_rows(&conn, &session.run_id),
+            vec![
+                ("banished_cleave".to_string(), 1, "Talos".to_string()),
+                ("banished_just_cause".to_string(), 2, "Talos".to_string()),
+            ]
+        );
+
+        let removed = session.remove_card(&conn, "banished_cleave").unwrap();
+        assert_eq!(removed.score_at_draft, Some(70));
+        assert_eq!(history_rows(&conn, &session.run_id).len(), 1);
+        assert!(session.remove_card(&conn, "banished_cleave").is_err());
+
+        // Draft order keeps increasing after a removal
+        let card = session.add_card(&conn, "banished_cleave", None).unwrap();
+        assert_eq!(card.draft_order, 3);
+    }
+
+    #[test]
+    fn test_pick_from_detected_offer_is_recorded() {
+        let (conn, _temp) = setup();
+        let mut session = DraftSession::new(10);
+        session.set_champion(&conn, "Fel", None).unwrap();
+        session.offered_card_ids = vec![
+            "banished_cleave".to_string(),
+            "banished_just_cause".to_string(),
+        ];
+
+        session
+            .add_card(&conn, "banished_just_cause", None)
+            .unwrap();
+        assert!(session.offered_card_ids.is_empty());
+
+        let picked: Vec<(String, bool)> = conn
+            .prepare("SELECT card_id, was_picked FROM draft_offers ORDER BY card_id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            picked,
+            vec![
+                ("banished_cleave".to_string(), false),
+                ("banished_just_cause".to_string(), true),
+            ]
+        );
+
+        // Slots follow the detected order, each scored against the deck before the pick
+        let slots: Vec<(String, i32, Option<i32>)> = conn
+            .prepare("SELECT card_id, slot, score FROM draft_offers ORDER BY slot")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(slots[0].0, "banished_cleave");
+        assert_eq!(slots[1].1, 2);
+        let request = DraftScoreRequest {
+            card_id: "banished_just_cause".to_string(),
+            current_deck: Vec::new(),
+            champion: "Fel".to_string(),
+            ring_number: 1,
+            covenant: 10,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: Some(0),
+            upgrade_count: 0,
+        };
+        let snapshot = CardSnapshot::load(&conn).unwrap();
+        let expected = scoring::score_card(&conn, &snapshot, &request).unwrap().score;
+        assert_eq!(slots[1].2, Some(expected));
+        assert!(slots[0].2.is_some());
+    }
+
+    #[test]
+    fn test_undo_redo_picks() {
+        let (conn, _temp) = setup();
+        let mut session = DraftSession::new(10);
+        session.set_champion(&conn, "Fel", None).unwrap();
+        assert!(session.undo_last_pick(&conn).is_err());
+
+        session.add_card(&conn, "banished_cleave", None).unwrap();
+        session.add_card(&conn, "banished_talos", None).unwrap();
+        session.remove_card(&conn, "banished_cleave").unwrap();
+        assert_eq!(session.deck_ids(), vec!["banished_talos"]);
+
+        // Undoing the removal puts the card back in its slot
+        session.undo_last_pick(&conn).unwrap();
+        assert_eq!(
+            session.deck_ids(),
+            vec!["banished_cleave", "banished_talos"]
+        );
+        session.undo_last_pick(&conn).unwrap();
+        assert_eq!(session.deck_ids(), vec!["banished_cleave"]);
+        assert_eq!(history_rows(&conn, &session.run_id).len(), 1);
+
+        session.redo_pick(&conn).unwrap();
+        assert_eq!(
+            session.deck_ids(),
+            vec!["banished_cleave", "banished_talos"]
+        );
+        assert_eq!(history_rows(&conn, &session.run_id).len(), 2);
+
+        // A new pick discards what could be redone
+        session.undo_last_pick(&conn).unwrap();
+        session
+            .add_card(&conn, "banished_just_cause", None)
+            .unwrap();
+        assert!(session.redo_pick(&conn).is_err());
+        assert_eq!(
+            session.deck_ids(),
+            vec!["banished_cleave", "banished_just_cause"]
+        );
+    }
+
+    #[test]
+    fn test_undo_pick_restores_detected_offer() {
+        let (conn, _temp) = setup();
+        let mut session = DraftSession::new(10);
+        session.set_champion(&conn, "Fel", None).unwrap();
+        let offer = vec![
+            "banished_cleave".to_string(),
+            "banished_just_cause".to_string(),
+        ];
+        session.offered_card_ids = offer.clone();
+
+        session.add_card(&conn, "banished_cleave", None).unwrap();
+        session.undo_last_pick(&conn).unwrap();
+        assert_eq!(session.offered_card_ids, offer);
+
+        let offers: i64 = conn
+            .query_row("SELECT COUNT(*) FROM draft_offers", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(offers, 0);
+
+        // The corrected pick records the offer once
+        session
+            .add_card(&conn, "banished_just_cause", None)
+            .unwrap();
+        let picked: String = conn
+            .query_row(
+                "SELECT card_id FROM draft_offers WHERE was_picked = 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(picked, "banished_just_cause");
+    }
+
+    #[test]
+    fn test_fill_request_from_session() {
+        let mut session = DraftSession::new(12);
+        session.champion = Some("Fel".to_string());
+        session.champion_path = Some("Unchained".to_string());
+        session.ring_number = 3;
+        session.set_pact_shards(40).unwrap();
+        assert!(session.set_pact_shards(-1).is_err());
+        session.deck.push(SessionCard {
+            card_id: "banished_cleave".to_string(),
+            ring_number: 1,
+            draft_order: 1,
+            score_at_draft: None,
+        });
+
+        let mut request = DraftScoreRequest {
+            card_id: "banished_just_cause".to_string(),
+            current_deck: vec![],
+            champion: String::new(),
+            ring_number: 0,
+            covenant: 0,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: None,
+            upgrade_count: 0,
+        };
+        session.fill_request(&mut request);
+        assert_eq!(request.champion, "Fel");
+        assert_eq!(request.champion_path.as_deref(), Some("Unchained"));
+        assert_eq!(request.current_deck, vec!["banished_cleave"]);
+        assert_eq!((request.ring_number, request.covenant), (3, 12));
+        assert_eq!(request.pact_shards, Some(40));
+
+        // Explicit values win
+        let mut request = DraftScoreRequest {
+            card_id: "banished_just_cause".to_string(),
+            current_deck: vec![],
+            champion: "Talos".to_string(),
+            ring_number: 5,
+            covenant: 1,
+            champion_path: None,
+            upcoming_boss: None,
+            pact_shards: Some(0),
+            upgrade_count: 0,
+        };
+        session.fill_request(&mut request);
+        assert_eq!(request.champion, "Talos");
+        // Fel's path means nothing for Talos
+        assert_eq!(request.champion_path, None);
+        assert_eq!((request.ring_number, request.covenant), (5, 1));
+        assert_eq!(request.pact_shards, Some(0));
+    }
+
+    #[test]
+    fn test_end_run_records_outcome() {
+        let (conn, _temp) = setup();
+        let mut session = DraftSession::new(5);
+        assert!(session.end_run(&conn, true).is_err());
+
+        session
+            .set_champion(&conn, "Fel", Some("Unchained"))
+            .unwrap();
+        session.add_card(&conn, "banished_cleave", None).unwrap();
+        session.set_ring(4).unwrap();
+        session.end_run(&conn, true).unwrap();
+
+        let run: (String, Option<String>, i32, bool, i32) = conn
+            .query_row(
+                "SELECT champion, champion_path, covenant, did_win, final_ring FROM runs WHERE run_id = ?1",
+                [&session.run_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            run,
+            ("Fel".to_string(), Some("Unchained".to_string()), 5, true, 4)
+        );
+
+        let did_win: bool = conn
+            .query_row(
+                "SELECT did_win FROM deck_history WHERE run_id = ?1",
+                [&session.run_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(did_win);
+    }
+}
@@ -0,0 +1,603 @@
+//! Application settings
+//!
+//! Every user-facing option lives in one typed `AppSettings`, stored one
+//! section per key in the `app_settings` table. Sections are read with
+//! their defaults filled in, so settings saved by older versions load
+//! unchanged. Changes are announced to the frontend as `settings-changed`.
+
+use crate::commands::ocr::CaptureRegionInfo;
+use crate::commands::window::OverlaySettings;
+use crate::database;
+use crate::database::access::DatabaseAccess;
+use crate::hotkeys::HotkeyBindings;
+use crate::logging::{self, LogFilter, LogFormat};
+use crate::ocr::controller::DEFAULT_LOOP_INTERVAL;
+use crate::ocr::timeout::{DEFAULT_CAPTURE_TIMEOUT, DEFAULT_RECOGNIZE_TIMEOUT, MIN_STAGE_TIMEOUT};
+use crate::ocr::{
+    self, get_default_card_regions, CaptureBackend, CardDetectionOptions, NormalizedRegion,
+    PreprocessConfig, PreprocessStep, RecognitionEngine, RecognizeConfig, RegionProfile,
+    DEFAULT_STABLE_VOTES, DEFAULT_STABLE_WINDOW, MAX_STABLE_WINDOW,
+};
+use log::LevelFilter;
+use rusqlite::Connection;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub const OCR_KEY: &str = "ocr";
+pub const OVERLAY_KEY: &str = "overlay";
+pub const HOTKEYS_KEY: &str = "hotkeys";
+pub const SCORING_KEY: &str = "scoring";
+pub const LOGGING_KEY: &str = "logging";
+pub const REGIONS_KEY: &str = "regions";
+
+/// Event emitted with the full `AppSettings` after any change
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// Shortest continuous detection interval the settings accept
+const MIN_LOOP_INTERVAL_MS: u64 = 250;
+
+/// Highest Tesseract page segmentation mode
+const MAX_PSM: i32 = 13;
+
+/// Error type for settings operations
+#[derive(Debug)]
+pub enum SettingsError {
+    DatabaseError(rusqlite::Error),
+    SerializationError(serde_json::Error),
+    InvalidValue(String),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::DatabaseError(e) => write!(f, "Database error: {}", e),
+            SettingsError::SerializationError(e) => write!(f, "Invalid settings: {}", e),
+            SettingsError::InvalidValue(msg) => write!(f, "Invalid setting: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<rusqlite::Error> for SettingsError {
+    fn from(e: rusqlite::Error) -> Self {
+        SettingsError::DatabaseError(e)
+    }
+}
+
+impl From<serde_json::Error> for SettingsError {
+    fn from(e: serde_json::Error) -> Self {
+        SettingsError::SerializationError(e)
+    }
+}
+
+/// Persisted OCR options
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct OcrPreferences {
+    /// Minimum overall confidence for a valid detection (0.0-1.0)
+    pub min_confidence: f64,
+    pub save_debug_images: bool,
+    /// Continuous detection interval used when none is requested
+    pub loop_interval_ms: u64,
+    /// Language the game runs in (e.g. `de`), for matching localized names
+    pub card_locale: Option<String>,
+    /// Continuous detection frames each card is voted on over
+    pub stable_window: usize,
+    /// Frames of `stable_window` a card must appear in before it is shown
+    pub stable_votes: usize,
+    /// How the screen is captured; `auto` uses the desktop portal under Wayland
+    pub capture_backend: CaptureBackend,
+    /// Text recognizer; `template_glyph` needs a glyph atlas but no Tesseract
+    pub engine: RecognitionEngine,
+    /// Time allowed to capture every region before a pass is abandoned
+    pub capture_timeout_ms: u64,
+    /// Time allowed to read one region before a pass is abandoned
+    pub recognize_timeout_ms: u64,
+    /// Also recognize cards by their keywords and description openings
+    pub match_card_text: bool,
+}
+
+impl Default for OcrPreferences {
+    fn default() -> Self {
+        Self {
+            min_confidence: 0.6,
+            save_debug_images: false,
+            loop_interval_ms: DEFAULT_LOOP_INTERVAL.as_millis() as u64,
+            card_locale: None,
+            stable_window: DEFAULT_STABLE_WINDOW,
+            stable_votes: DEFAULT_STABLE_VOTES,
+            capture_backend: CaptureBackend::default(),
+            engine: RecognitionEngine::default(),
+            capture_timeout_ms: DEFAULT_CAPTURE_TIMEOUT.as_millis() as u64,
+            recognize_timeout_ms: DEFAULT_RECOGNIZE_TIMEOUT.as_millis() as u64,
+            match_card_text: false,
+        }
+    }
+}
+
+impl OcrPreferences {
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err(SettingsError::InvalidValue(format!(
+                "min_confidence must be between 0.0 and 1.0, got {}",
+                self.min_confidence
+            )));
+        }
+        if self.loop_interval_ms < MIN_LOOP_INTERVAL_MS {
+            return Err(SettingsError::InvalidValue(format!(
+                "loop_interval_ms must be at least {}, got {}",
+                MIN_LOOP_INTERVAL_MS, self.loop_interval_ms
+            )));
+        }
+        if !(1..=MAX_STABLE_WINDOW).contains(&self.stable_window) {
+            return Err(SettingsError::InvalidValue(format!(
+                "stable_window must be between 1 and {}, got {}",
+                MAX_STABLE_WINDOW, self.stable_window
+            )));
+        }
+        if !(1..=self.stable_window).contains(&self.stable_votes) {
+            return Err(SettingsError::InvalidValue(format!(
+                "stable_votes must be between 1 and stable_window ({}), got {}",
+                self.stable_window, self.stable_votes
+            )));
+        }
+        let min_timeout = MIN_STAGE_TIMEOUT.as_millis() as u64;
+        for (name, value) in [
+            ("capture_timeout_ms", self.capture_timeout_ms),
+            ("recognize_timeout_ms", self.recognize_timeout_ms),
+        ] {
+            if value < min_timeout {
+                return Err(SettingsError::InvalidValue(format!(
+                    "{} must be at least {}, got {}",
+                    name, min_timeout, value
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy these preferences into detection options
+    pub fn apply(&self, config: &mut CardDetectionOptions) {
+        config.min_overall_confidence = self.min_confidence;
+        config.save_debug_images = self.save_debug_images;
+        config.stable_window = self.stable_window;
+        config.stable_votes = self.stable_votes;
+        config.capture.backend = self.capture_backend;
+        config.stage_timeouts.capture = Duration::from_millis(self.capture_timeout_ms);
+        config.stage_timeouts.recognize = Duration::from_millis(self.recognize_timeout_ms);
+        config.match_card_text = self.match_card_text;
+        for recognize in config.recognize_configs_mut() {
+            recognize.engine = self.engine;
+        }
+        config.card_locale = self
+            .card_locale
+            .as_deref()
+            .and_then(database::repository::normalize_locale);
+    }
+}
+
+/// Persisted custom capture regions and per-region OCR overrides
+///
+/// With neither `regions` nor `normalized_regions` set, the default card
+/// regions are used.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct RegionPreferences {
+    /// Regions in pixels
+    pub regions: Vec<CaptureRegionInfo>,
+    /// Regions as fractions of the screen; take precedence over `regions`
+    pub normalized_regions: Vec<NormalizedRegion>,
+    /// Overrides keyed by index into the capture regions
+    pub profiles: BTreeMap<usize, RegionProfilePreferences>,
+}
+
+/// Preprocessing and OCR settings one capture region uses in place of the
+/// global ones; each setting left out keeps the global one
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct RegionProfilePreferences {
+    pub preprocess_steps: Option<Vec<PreprocessStep>>,
+    /// Tesseract page segmentation mode
+    pub psm: Option<i32>,
+    /// Characters OCR may return; `""` allows every character
+    pub whitelist: Option<String>,
+}
+
+impl RegionProfilePreferences {
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if let Some(ref steps) = self.preprocess_steps {
+            ocr::preprocess::validate_steps(steps)
+                .map_err(|e| SettingsError::InvalidValue(e.to_string()))?;
+        }
+        if let Some(psm) = self.psm {
+            if !(0..=MAX_PSM).contains(&psm) {
+                return Err(SettingsError::InvalidValue(format!(
+                    "psm must be between 0 and {}, got {}",
+                    MAX_PSM, psm
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether every setting is left to the global ones
+    pub fn is_empty(&self) -> bool {
+        self.preprocess_steps.is_none() && self.psm.is_none() && self.whitelist.is_none()
+    }
+
+    /// The detection profile these preferences describe, built on the
+    /// global recognition config
+    pub fn to_profile(&self, global: &RecognizeConfig) -> RegionProfile {
+        let preprocess = self
+            .preprocess_steps
+            .clone()
+            .map(|steps| PreprocessConfig { steps });
+        let recognize = if self.psm.is_some() || self.whitelist.is_some() {
+            let mut recognize = global.clone();
+            if let Some(psm) = self.psm {
+                recognize.psm = psm;
+            }
+            if let Some(ref whitelist) = self.whitelist {
+                recognize.whitelist = Some(whitelist.clone()).filter(|chars| !chars.is_empty());
+            }
+            Some(recognize)
+        } else {
+            None
+        };
+        RegionProfile {
+            preprocess,
+            recognize,
+        }
+    }
+}
+
+impl RegionPreferences {
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if let Some(i) = self.normalized_regions.iter().position(|r| !r.is_valid()) {
+            return Err(SettingsError::InvalidValue(format!(
+                "normalized region {} must lie within 0.0-1.0",
+                i + 1
+            )));
+        }
+        for profile in self.profiles.values() {
+            profile.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Copy the saved regions and overrides into detection options
+    ///
+    /// Normalized regions are resolved for the screen size the options were
+    /// last resolved for; detection re-resolves them for the current screen.
+    pub fn apply(&self, config: &mut CardDetectionOptions) {
+        let (width, height) = (config.capture.screen_width, config.capture.screen_height);
+        if !self.normalized_regions.is_empty() {
+            config
+                .capture
+                .set_normalized_regions(self.normalized_regions.clone(), width, height);
+        } else if !self.regions.is_empty() {
+            let regions = self
+                .regions
+                .iter()
+                .map(CaptureRegionInfo::to_region)
+                .collect();
+            config.capture.update_regions(regions);
+        } else {
+            config
+                .capture
+                .update_regions(get_default_card_regions(width, height));
+        }
+
+        config.region_profiles = self
+            .profiles
+            .iter()
+            .filter(|(_, profile)| !profile.is_empty())
+            .map(|(&index, profile)| (index, profile.to_profile(&config.recognize)))
+            .collect();
+    }
+}
+
+/// Persisted scoring options
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ScoringPreferences {
+    /// Adjust scores by the player's own win rate with each card
+    pub use_history_adjustment: bool,
+    /// Adjust scores by imported community win rates; off until opted in
+    pub use_community_stats: bool,
+    /// Share of the community adjustment applied (0.0-1.0)
+    pub community_weight: f64,
+}
+
+impl Default for ScoringPreferences {
+    fn default() -> Self {
+        Self {
+            use_history_adjustment: true,
+            use_community_stats: false,
+            community_weight: 0.5,
+        }
+    }
+}
+
+impl ScoringPreferences {
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if !(0.0..=1.0).contains(&self.community_weight) {
+            return Err(SettingsError::InvalidValue(format!(
+                "community_weight must be between 0.0 and 1.0, got {}",
+                self.community_weight
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Persisted log levels
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct LoggingPreferences {
+    /// `off`, `error`, `warn`, `info`, `debug` or `trace`
+    pub level: String,
+    /// Level per module, overriding `level`, e.g. `{"ocr": "warn"}`
+    pub modules: BTreeMap<String, String>,
+    pub format: LogFormat,
+}
+
+impl Default for LoggingPreferences {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            modules: BTreeMap::new(),
+            format: LogFormat::default(),
+        }
+    }
+}
+
+impl LoggingPreferences {
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        self.filter().map(|_| ())
+    }
+
+    /// The logger filter these preferences describe
+    pub fn filter(&self) -> Result<LogFilter, SettingsError> {
+        let parse = |level: &str| {
+            level
+                .trim()
+                .parse::<LevelFilter>()
+                .map_err(|_| SettingsError::InvalidValue(format!("unknown log level '{}'", level)))
+        };
+
+        let mut modules = Vec::with_capacity(self.modules.len());
+        for (module, level) in &self.modules {
+            let module = module.trim();
+            if module.is_empty() {
+                return Err(SettingsError::InvalidValue(
+                    "log module name cannot be empty".to_string(),
+                ));
+            }
+            modules.push((module.to_string(), parse(level)?));
+        }
+        Ok(LogFilter::new(parse(&self.level)?, modules))
+    }
+
+    /// Apply these preferences to the installed logger
+    pub fn apply(&self) -> Result<(), SettingsError> {
+        logging::set_filter(self.filter()?);
+        logging::set_format(self.format);
+        Ok(())
+    }
+}
+
+/// All persisted settings
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct AppSettings {
+    pub ocr: OcrPreferences,
+    pub overlay: OverlaySettings,
+    pub hotkeys: HotkeyBindings,
+    pub scoring: ScoringPreferences,
+    pub logging: LoggingPreferences,
+    pub regions: RegionPreferences,
+}
+
+/// Sections to replace; omitted sections are left unchanged
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct SettingsUpdate {
+    pub ocr: Option<OcrPreferences>,
+    pub overlay: Option<OverlaySettings>,
+    pub hotkeys: Option<HotkeyBindings>,
+    pub scoring: Option<ScoringPreferences>,
+    pub logging: Option<LoggingPreferences>,
+    pub regions: Option<RegionPreferences>,
+}
+
+/// Read one settings section on an open connection, or its defaults if
+/// never saved
+pub(crate) fn read_section<T: DeserializeOwned + Default>(
+    conn: &Connection,
+    key: &str,
+) -> Result<T, SettingsError> {
+    match database::settings::get_setting(conn, key)? {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Ok(T::default()),
+    }
+}
+
+/// Load one settings section, or its defaults if never saved
+pub fn load_section<T: DeserializeOwned + Default>(
+    access: &DatabaseAccess,
+    key: &str,
+) -> Result<T, SettingsError> {
+    access.read(|conn| read_section(conn, key))
+}
+
+/// Save one settings section
+pub fn save_section<T: Serialize>(
+    access: &DatabaseAccess,
+    key: &str,
+    value: &T,
+) -> Result<(), SettingsError> {
+    let key = key.to_string();
+    let json = serde_json::to_string(value)?;
+    access.write_and_wait(move |conn| {
+        database::settings::set_setting(conn, &key, &json)?;
+        Ok(())
+    })
+}
+
+/// Load every settings section
+pub fn load(access: &DatabaseAccess) -> Result<AppSettings, SettingsError> {
+    access.read(read_all)
+}
+
+/// Read every settings section on an open connection
+pub(crate) fn read_all(conn: &Connection) -> Result<AppSettings, SettingsError> {
+    Ok(AppSettings {
+        ocr: read_section(conn, OCR_KEY)?,
+        overlay: read_section(conn, OVERLAY_KEY)?,
+        hotkeys: read_section(conn, HOTKEYS_KEY)?,
+        scoring: read_section(conn, SCORING_KEY)?,
+        logging: read_section(conn, LOGGING_KEY)?,
+        regions: read_section(conn, REGIONS_KEY)?,
+    })
+}
+
+/// Emit the current settings to the frontend after a change
+pub fn notify_changed(app: &AppHandle, access: &DatabaseAccess) {
+    let result = load(access)
+        .map_err(|e| e.to_string())
+        .and_then(|settings| {
+            app.emit(SETTINGS_CHANGED_EVENT, settings)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        log::warn!("Failed to emit settings change: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_load_defaults_and_sections() {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let access = DatabaseAccess::new(temp_file.path().to_path_buf());
+
+        assert_eq!(load(&access).unwrap(), AppSettings::default());
+
+        let scoring = ScoringPreferences {
+            use_history_adjustment: false,
+            ..Default::default()
+        };
+        save_section(&access, SCORING_KEY, &scoring).unwrap();
+
+        let settings = load(&access).unwrap();
+        assert_eq!(settings.scoring, scoring);
+        assert_eq!(settings.ocr, OcrPreferences::default());
+    }
+
+    #[test]
+    fn test_sections_saved_by_older_versions_load() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = temp_file.path();
+        crate::database::init(db_path).unwrap();
+
+        // Saved before `auto_overlay` existed
+        let conn = Connection::open(db_path).unwrap();
+        database::settings::set_setting(
+            &conn,
+            OVERLAY_KEY,
+            r#"{"opacity":0.5,"always_on_top":false}"#,
+        )
+        .unwrap();
+
+        let access = DatabaseAccess::new(db_path.to_path_buf());
+        let overlay = load(&access).unwrap().overlay;
+        assert_eq!(overlay.opacity, 0.5);
+        assert!(!overlay.always_on_top);
+        assert!(!overlay.auto_overlay);
+    }
+
+    #[test]
+    fn test_ocr_preferences_validation() {
+        assert!(OcrPreferences::default().validate().is_ok());
+
+        let prefs = OcrPreferences {
+            min_confidence: 1.5,
+            ..Default::default()
+        };
+        assert!(prefs.validate().is_err());
+
+        let prefs = OcrPreferences {
+            loop_interval_ms: 10,
+            ..Default::default()
+        };
+        assert!(prefs.validate().is_err());
+
+        let prefs = OcrPreferences {
+            stable_window: 2,
+            stable_votes: 3,
+            ..Default::default()
+        };
+        assert!(prefs.validate().is_err());
+
+        let prefs = OcrPreferences {
+            recognize_timeout_ms: 0,
+            ..Default::default()
+        };
+        assert!(prefs.validate().is_err());
+    }
+
+    #[test]
+    fn test_logging_preferences_filter() {
+        let default = LoggingPreferences::default().filter().unwrap();
+        assert_eq!(default, LogFilter::default());
+
+        let prefs = LoggingPreferences {
+            level: "warn".to_string(),
+            modules: BTreeMap::from([("ocr".to_string(), "Error".to_string())]),
+            ..Default::default()
+        };
+        let filter = prefs.filter().unwrap();
+        assert_eq!(filter.level_for("session"), LevelFilter::Warn);
+        assert_eq!(filter.level_for("ocr::pipeline"), LevelFilter::Error);
+
+        let prefs = LoggingPreferences {
+            level: "loud".to_string(),
+            ..Default::default()
+        };
+        assert!(prefs.validate().is_err());
+        let prefs = LoggingPreferences {
+            modules: BTreeMap::from([(" ".to_string(), "info".to_string())]),
+            ..Default::default()
+        };
+        assert!(prefs.validate().is_err());
+    }
+
+    #[test]
+    fn test_ocr_preferences_apply_normalizes_locale() {
+        let mut config = CardDetectionOptions::default();
+
+        let prefs = OcrPreferences {
+            card_locale: Some("de_DE".to_string()),
+            ..Default::default()
+        };
+        prefs.apply(&mut config);
+        assert_eq!(config.card_locale.as_deref(), Some("de"));
+
+        let prefs = OcrPreferences {
+            card_locale: Some("en-US".to_string()),
+            ..Default::default()
+        };
+        prefs.apply(&mut config);
+        assert!(config.card_locale.is_none());
+    }
+}
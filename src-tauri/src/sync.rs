@@ -0,0 +1,543 @@
+//! Opt-in upload of anonymized run summaries
+//!
+//! Players who opt in share finished runs with a community dataset at an
+//! endpoint they choose. A run is cut down to what the dataset needs: the
+//! champion, covenant and outcome, the cards drafted and the offers they
+//! were picked from. The run ID and timestamps are left out, and each
+//! upload gets a fresh random ID instead, so uploads can't be tied back to
+//! the player or to each other.
+//!
+//! Uploads go through the `upload_queue` table so runs finished offline
+//! are sent later; a failed upload is retried with a growing delay by a
+//! background job. Nothing is sent unless the player uploads a run or
+//! turns on auto-upload, which queues each run as it ends.
+//!
+//! Sending needs the `sync` feature; without it, uploading reports the
+//! feature as unavailable.
+//!
+//! Queue changes go through the database writer, but a flush sends each
+//! upload over the network between its writes, so it runs on a connection
+//! of its own rather than hold up the writer.
+
+use crate::database::access::DatabaseAccess;
+use crate::database::{self, settings};
+use crate::error::{AppError, AppResult};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+/// Settings key the sync configuration is stored under
+const SETTINGS_KEY: &str = "run_sync";
+
+/// Interval between background retries of queued uploads
+const RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Delay after the first failed attempt, doubled for each later one
+const BASE_RETRY_DELAY_SECS: i64 = 60;
+const MAX_RETRY_DELAY_SECS: i64 = 6 * 60 * 60;
+/// Longest an upload may take before it counts as failed
+#[cfg(feature = "sync")]
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Persisted sync configuration; everything is off by default
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct SyncConfig {
+    /// URL run summaries are POSTed to
+    pub endpoint: Option<String>,
+    /// Queue each run for upload as it ends
+    pub auto_upload: bool,
+}
+
+/// Sync state reported to the frontend
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SyncStatus {
+    /// Whether this build includes the `sync` feature
+    pub available: bool,
+    pub endpoint: Option<String>,
+    pub auto_upload: bool,
+    /// Queued runs not uploaded yet
+    pub pending: u32,
+    pub uploaded: u32,
+    /// Why the most recent failed upload failed
+    pub last_error: Option<String>,
+}
+
+/// A drafted card as uploaded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UploadedCard {
+    pub card_id: String,
+    pub ring_number: i32,
+    pub draft_order: i32,
+    pub score_at_draft: Option<i32>,
+}
+
+/// An offered card as uploaded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UploadedOffer {
+    pub ring_number: i32,
+    pub card_id: String,
+    /// 1-based position in the offer, when recorded
+    pub slot: Option<i32>,
+    pub was_picked: bool,
+}
+
+/// The anonymized summary of one run, the body of each upload
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunUpload {
+    /// Random per upload, for the receiver to drop duplicates
+    pub upload_id: String,
+    pub app_version: String,
+    pub champion: String,
+    pub champion_path: Option<String>,
+    pub covenant: i32,
+    pub did_win: bool,
+    pub final_ring: Option<i32>,
+    /// In draft order
+    pub cards: Vec<UploadedCard>,
+    pub offers: Vec<UploadedOffer>,
+}
+
+/// Outcome of one pass over the queue
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlushReport {
+    pub sent: u32,
+    pub failed: u32,
+}
+
+pub fn load_config(access: &DatabaseAccess) -> Result<SyncConfig, String> {
+    let json = access
+        .read(|conn| settings::get_setting(conn, SETTINGS_KEY))
+        .map_err(|e| e.to_string())?;
+    match json {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(SyncConfig::default()),
+    }
+}
+
+pub fn save_config(access: &DatabaseAccess, config: &SyncConfig) -> Result<(), String> {
+    let json = serde_json::to_string(config).map_err(|e| e.to_string())?;
+    access
+        .write_and_wait(move |conn| settings::set_setting(conn, SETTINGS_KEY, &json))
+        .map_err(|e| e.to_string())
+}
+
+/// Check that `endpoint` is an HTTP(S) URL
+pub fn validate_endpoint(endpoint: &str) -> AppResult<()> {
+    let valid = ["http://", "https://"]
+        .iter()
+        .any(|scheme| endpoint.len() > scheme.len() && endpoint.starts_with(scheme));
+    if !valid {
+        return Err(AppError::InvalidInput(format!(
+            "Upload endpoint must be an http:// or https:// URL, got '{}'",
+            endpoint
+        )));
+    }
+    Ok(())
+}
+
+/// Summarize a finished run for upload
+pub fn build_upload(conn: &Connection, run_id: &str) -> AppResult<RunUpload> {
+    let run = conn
+        .query_row(
+            "SELECT champion, champion_path, covenant, did_win, final_ring
+             FROM live_runs
+             WHERE run_id = ?1",
+            [run_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, i32>(2)?,
+                    row.get::<_, Option<bool>>(3)?,
+                    row.get::<_, Option<i32>>(4)?,
+                ))
+            },
+        )
+        .optional()?;
+    let Some((champion, champion_path, covenant, did_win, final_ring)) = run else {
+        return Err(AppError::NotFound(format!("Run '{}' not found", run_id)));
+    };
+    let Some(did_win) = did_win else {
+        return Err(AppError::InvalidInput(format!(
+            "Run '{}' has not ended",
+            run_id
+        )));
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT card_id, ring_number, draft_order, score_at_draft
+         FROM deck_history
+         WHERE run_id = ?1
+         ORDER BY draft_order, id",
+    )?;
+    let cards = stmt
+        .query_map([run_id], |row| {
+            Ok(UploadedCard {
+                card_id: row.get(0)?,
+                ring_number: row.get(1)?,
+                draft_order: row.get(2)?,
+                score_at_draft: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT ring_number, card_id, slot, was_picked
+         FROM draft_offers
+         WHERE run_id = ?1
+         ORDER BY id",
+    )?;
+    let offers = stmt
+        .query_map([run_id], |row| {
+            Ok(UploadedOffer {
+                ring_number: row.get(0)?,
+                card_id: row.get(1)?,
+                slot: row.get(2)?,
+                was_picked: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(RunUpload {
+        upload_id: format!("{:032x}", rand::random::<u128>()),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        champion,
+        champion_path,
+        covenant,
+        did_win,
+        final_ring,
+        cards,
+        offers,
+    })
+}
+
+/// Queue a finished run for upload
+///
+/// A run still waiting is retried right away on the next pass. Returns
+/// `false` if the run was already uploaded.
+pub fn enqueue(conn: &Connection, run_id: &str) -> AppResult<bool> {
+    let uploaded: Option<bool> = conn
+        .query_row(
+            "SELECT uploaded_at IS NOT NULL FROM upload_queue WHERE run_id = ?1",
+            [run_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match uploaded {
+        Some(true) => Ok(false),
+        Some(false) => {
+            conn.execute(
+                "UPDATE upload_queue SET next_attempt_at = NULL WHERE run_id = ?1",
+                [run_id],
+            )?;
+            Ok(true)
+        }
+        None => {
+            let payload = serde_json::to_string(&build_upload(conn, run_id)?)
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            conn.execute(
+                "INSERT INTO upload_queue (run_id, payload) VALUES (?1, ?2)",
+                rusqlite::params![run_id, payload],
+            )?;
+            Ok(true)
+        }
+    }
+}
+
+/// Seconds to wait before retrying an upload that has failed `attempts` times
+fn retry_delay_secs(attempts: u32) -> i64 {
+    let doublings = attempts.saturating_sub(1).min(16);
+    (BASE_RETRY_DELAY_SECS << doublings).min(MAX_RETRY_DELAY_SECS)
+}
+
+/// Send every queued upload that is due to `endpoint` with `send`
+///
+/// Stops at the first failure: offline, the rest would fail as well.
+pub fn flush_queue(
+    conn: &Connection,
+    endpoint: &str,
+    send: impl Fn(&str, &str) -> Result<(), String>,
+) -> rusqlite::Result<FlushReport> {
+    let mut stmt = conn.prepare(
+        "SELECT id, payload, attempts
+         FROM upload_queue
+         WHERE uploaded_at IS NULL
+           AND (next_attempt_at IS NULL OR next_attempt_at <= CURRENT_TIMESTAMP)
+         ORDER BY id",
+    )?;
+    let due = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, u32>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut report = FlushReport::default();
+    for (id, payload, attempts) in due {
+        match send(endpoint, &payload) {
+            Ok(()) => {
+                conn.execute(
+                    "UPDATE upload_queue
+                     SET uploaded_at = CURRENT_TIMESTAMP, last_error = NULL
+                     WHERE id = ?1",
+                    [id],
+                )?;
+                report.sent += 1;
+            }
+            Err(e) => {
+                let delay = format!("+{} seconds", retry_delay_secs(attempts + 1));
+                conn.execute(
+                    "UPDATE upload_queue
+                     SET attempts = attempts + 1, last_error = ?2,
+                         next_attempt_at = datetime('now', ?3)
+                     WHERE id = ?1",
+                    rusqlite::params![id, e, delay],
+                )?;
+                report.failed += 1;
+                break;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Queue counts and the last failure, for `config`
+pub fn status(conn: &Connection, config: &SyncConfig) -> AppResult<SyncStatus> {
+    let (pending, uploaded) = conn.query_row(
+        "SELECT COALESCE(SUM(uploaded_at IS NULL), 0), COUNT(uploaded_at) FROM upload_queue",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let last_error = conn
+        .query_row(
+            "SELECT last_error FROM upload_queue
+             WHERE uploaded_at IS NULL AND last_error IS NOT NULL
+             ORDER BY next_attempt_at DESC
+             LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(SyncStatus {
+        available: cfg!(feature = "sync"),
+        endpoint: config.endpoint.clone(),
+        auto_upload: config.auto_upload,
+        pending,
+        uploaded,
+        last_error,
+    })
+}
+
+/// POST one upload's JSON to `endpoint`
+#[cfg(feature = "sync")]
+pub fn send(endpoint: &str, payload: &str) -> Result<(), String> {
+    ureq::post(endpoint)
+        .timeout(UPLOAD_TIMEOUT)
+        .set("Content-Type", "application/json")
+        .send_string(payload)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Stub when the `sync` feature is disabled
+#[cfg(not(feature = "sync"))]
+pub fn send(_endpoint: &str, _payload: &str) -> Result<(), String> {
+    Err(unavailable().to_string())
+}
+
+fn unavailable() -> AppError {
+    AppError::Unavailable(
+        "Run upload is not included in this build. Rebuild with --features sync to use it."
+            .to_string(),
+    )
+}
+
+/// Queue a finished run and try to send everything due
+pub fn upload_run(access: &DatabaseAccess, run_id: &str) -> AppResult<SyncStatus> {
+    if !cfg!(feature = "sync") {
+        return Err(unavailable());
+    }
+    let config = load_config(access)?;
+    let Some(endpoint) = config.endpoint.as_deref() else {
+        return Err(AppError::InvalidInput(
+            "Set an upload endpoint first".to_string(),
+        ));
+    };
+
+    let queued_id = run_id.to_string();
+    if !access.write_and_wait(move |conn| enqueue(conn, &queued_id))? {
+        return Err(AppError::InvalidInput(format!(
+            "Run '{}' was already uploaded",
+            run_id
+        )));
+    }
+    let report = flush(access.db_path(), endpoint)?;
+    if report.failed > 0 {
+        log::warn!("Run upload failed; {} will be retried", run_id);
+    }
+    access.read(|conn| status(conn, &config))
+}
+
+/// Send the queued uploads that are due to `endpoint`, on a connection of
+/// its own
+fn flush(db_path: &Path, endpoint: &str) -> AppResult<FlushReport> {
+    let conn = database::open(db_path)?;
+    Ok(flush_queue(&conn, endpoint, send)?)
+}
+
+/// Send the queued uploads that are due, if an endpoint is set
+fn upload_pending(access: &DatabaseAccess) -> AppResult<FlushReport> {
+    let config = load_config(access)?;
+    let Some(endpoint) = config.endpoint.as_deref() else {
+        return Ok(FlushReport::default());
+    };
+    flush(access.db_path(), endpoint)
+}
+
+/// With auto-upload on, queue a run that just ended and send it off the
+/// calling thread
+pub fn queue_finished_run(access: DatabaseAccess, run_id: String) {
+    if !cfg!(feature = "sync") {
+        return;
+    }
+    std::thread::spawn(move || {
+        let result = load_config(&access)
+            .map_err(AppError::from)
+            .and_then(|config| {
+                if !config.auto_upload || config.endpoint.is_none() {
+                    return Ok(None);
+                }
+                let queued_id = run_id.clone();
+                access.write_and_wait(move |conn| enqueue(conn, &queued_id))?;
+                upload_pending(&access).map(Some)
+            });
+        match result {
+            Ok(Some(report)) if report.failed > 0 => {
+                log::info!("Run upload queued for retry: {}", run_id)
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to queue run upload: {}", e),
+        }
+    });
+}
+
+/// Start the background job that retries queued uploads
+pub fn spawn_retry(access: DatabaseAccess) {
+    if !cfg!(feature = "sync") {
+        return;
+    }
+    std::thread::spawn(move || loop {
+        std::thread::sleep(RETRY_INTERVAL);
+
+        match upload_pending(&access) {
+            Ok(report) if report.sent > 0 => log::info!("Uploaded {} queued runs", report.sent),
+            Ok(_) => {}
+            Err(e) => log::warn!("Background run upload failed: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use tempfile::NamedTempFile;
+
+    fn setup_finished_run() -> (Connection, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let conn = Connection::open(temp_file.path()).unwrap();
+        conn.execute_batch(
+            "INSERT INTO runs (run_id, champion, covenant, did_win, final_ring)
+             VALUES ('run1', 'Fel', 10, 1, 8), ('live', 'Fel', 10, NULL, NULL);
+             INSERT INTO deck_history (run_id, card_id, ring_number, draft_order, champion, covenant)
+             VALUES ('run1', 'banished_cleave', 1, 2, 'Fel', 10),
+                    ('run1', 'banished_fel', 1, 1, 'Fel', 10);
+             INSERT INTO draft_offers (run_id, ring_number, card_id, was_picked, slot)
+             VALUES ('run1', 1, 'banished_cleave', 1, 1), ('run1', 1, 'banished_talos', 0, 2);",
+        )
+        .unwrap();
+        (conn, temp_file)
+    }
+
+    #[test]
+    fn test_build_upload_is_anonymized() {
+        let (conn, _temp) = setup_finished_run();
+
+        let upload = build_upload(&conn, "run1").unwrap();
+        assert_eq!(upload.champion, "Fel");
+        assert!(upload.did_win);
+        assert_eq!(upload.final_ring, Some(8));
+        assert_eq!(upload.cards[0].card_id, "banished_fel");
+        assert_eq!(upload.offers.len(), 2);
+        assert_eq!(upload.offers[1].slot, Some(2));
+        let json = serde_json::to_string(&upload).unwrap();
+        assert!(!json.contains("run1"));
+        assert_ne!(
+            build_upload(&conn, "run1").unwrap().upload_id,
+            upload.upload_id
+        );
+
+        assert!(matches!(
+            build_upload(&conn, "live"),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            build_upload(&conn, "missing"),
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_queue_retries_until_sent() {
+        let (conn, _temp) = setup_finished_run();
+        assert!(enqueue(&conn, "run1").unwrap());
+
+        // Offline: the upload stays queued with a retry delay
+        let report = flush_queue(&conn, "https://example.com", |_, _| {
+            Err("offline".to_string())
+        })
+        .unwrap();
+        assert_eq!(report, FlushReport { sent: 0, failed: 1 });
+        let queue = status(&conn, &SyncConfig::default()).unwrap();
+        assert_eq!((queue.pending, queue.uploaded), (1, 0));
+        assert_eq!(queue.last_error.as_deref(), Some("offline"));
+        let report = flush_queue(&conn, "https://example.com", |_, _| Ok(())).unwrap();
+        assert_eq!(report, FlushReport::default());
+
+        // Uploading again makes it due right away
+        assert!(enqueue(&conn, "run1").unwrap());
+        let sent = RefCell::new(Vec::new());
+        let report = flush_queue(&conn, "https://example.com", |endpoint, payload| {
+            sent.borrow_mut()
+                .push((endpoint.to_string(), payload.to_string()));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(report.sent, 1);
+        let sent = sent.into_inner();
+        assert_eq!(sent[0].0, "https://example.com");
+        let upload: RunUpload = serde_json::from_str(&sent[0].1).unwrap();
+        assert_eq!(upload.cards.len(), 2);
+
+        let queue = status(&conn, &SyncConfig::default()).unwrap();
+        assert_eq!((queue.pending, queue.uploaded), (0, 1));
+        assert!(queue.last_error.is_none());
+        assert!(!enqueue(&conn, "run1").unwrap());
+    }
+
+    #[test]
+    fn test_retry_delay_and_endpoint() {
+        assert_eq!(retry_delay_secs(1), 60);
+        assert_eq!(retry_delay_secs(3), 240);
+        assert_eq!(retry_delay_secs(40), MAX_RETRY_DELAY_SECS);
+
+        assert!(validate_endpoint("https://stats.example.com/runs").is_ok());
+        assert!(validate_endpoint("https://").is_err());
+        assert!(validate_endpoint("ftp://example.com").is_err());
+    }
+}
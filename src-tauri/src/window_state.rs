@@ -0,0 +1,271 @@
+//! Overlay window state persistence
+//!
+//! Saves the overlay's position, size and visibility in the
+//! `overlay_window_state` setting, keyed by the connected monitor layout,
+//! so docking a laptop or unplugging a screen restores the placement last
+//! used with that layout instead of one that may now be off-screen.
+
+use crate::database::access::DatabaseAccess;
+use crate::database::settings;
+use crate::error::{AppError, AppResult};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, Monitor, WebviewWindow};
+
+/// Settings key the saved states are stored under
+const SETTINGS_KEY: &str = "overlay_window_state";
+
+/// Smallest overlap with a monitor, in pixels per axis, for a saved
+/// position to count as reachable
+const MIN_VISIBLE_PIXELS: i64 = 50;
+
+/// Overlay placement and visibility
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub visible: bool,
+}
+
+/// Physical bounds of a connected monitor
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MonitorBounds {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<&Monitor> for MonitorBounds {
+    fn from(monitor: &Monitor) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        }
+    }
+}
+
+/// Stable identifier of a monitor layout, independent of enumeration order
+pub fn layout_key(monitors: &[MonitorBounds]) -> String {
+    let mut parts: Vec<String> = monitors
+        .iter()
+        .map(|m| {
+            format!(
+                "{}:{}x{}@{},{}",
+                m.name.as_deref().unwrap_or("unknown"),
+                m.width,
+                m.height,
+                m.x,
+                m.y
+            )
+        })
+        .collect();
+    parts.sort();
+    parts.join("|")
+}
+
+/// Whether enough of `geometry` lies on some monitor to be grabbed
+pub fn is_reachable(geometry: &WindowGeometry, monitors: &[MonitorBounds]) -> bool {
+    let overlap = |start: i32, len: u32, other_start: i32, other_len: u32| {
+        let end = start as i64 + len as i64;
+        let other_end = other_start as i64 + other_len as i64;
+        end.min(other_end) - (start as i64).max(other_start as i64)
+    };
+
+    monitors.iter().any(|m| {
+        overlap(geometry.x, geometry.width, m.x, m.width) >= MIN_VISIBLE_PIXELS
+            && overlap(geometry.y, geometry.height, m.y, m.height) >= MIN_VISIBLE_PIXELS
+    })
+}
+
+fn load_states(conn: &Connection) -> Result<HashMap<String, WindowGeometry>, String> {
+    match settings::get_setting(conn, SETTINGS_KEY).map_err(|e| e.to_string())? {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(HashMap::new()),
+    }
+}
+
+/// Saved overlay state for a monitor layout
+pub fn load_window_state(
+    access: &DatabaseAccess,
+    layout: &str,
+) -> Result<Option<WindowGeometry>, String> {
+    let mut states = access
+        .read(|conn| -> AppResult<_> { Ok(load_states(conn)?) })
+        .map_err(|e| e.to_string())?;
+    Ok(states.remove(layout))
+}
+
+/// Save the overlay state for a monitor layout, keeping other layouts
+pub fn save_window_state(
+    access: &DatabaseAccess,
+    layout: &str,
+    geometry: &WindowGeometry,
+) -> Result<(), String> {
+    let layout = layout.to_string();
+    let geometry = geometry.clone();
+    // Read and written in one job so concurrent saves can't drop a layout
+    access
+        .write_and_wait(move |conn| {
+            let mut states = load_states(conn)?;
+            states.insert(layout, geometry);
+
+            let json = serde_json::to_string(&states).map_err(|e| e.to_string())?;
+            settings::set_setting(conn, SETTINGS_KEY, &json)?;
+            Ok::<_, AppError>(())
+        })
+        .map_err(|e| e.to_string())
+}
+
+fn monitors(overlay: &WebviewWindow) -> Result<Vec<MonitorBounds>, String> {
+    Ok(overlay
+        .available_monitors()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(MonitorBounds::from)
+        .collect())
+}
+
+/// Save the overlay's current state for the current monitor layout
+pub fn save_overlay_state(app: &AppHandle, access: &DatabaseAccess) -> Result<(), String> {
+    let overlay = match app.get_webview_window("overlay") {
+        Some(overlay) => overlay,
+        None => return Ok(()),
+    };
+
+    let position = overlay.outer_position().map_err(|e| e.to_string())?;
+    let size = overlay.outer_size().map_err(|e| e.to_string())?;
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        visible: overlay.is_visible().map_err(|e| e.to_string())?,
+    };
+
+    save_window_state(access, &layout_key(&monitors(&overlay)?), &geometry)
+}
+
+/// Restore the overlay state saved for the current monitor layout
+///
+/// Saved placements that would land mostly off-screen are ignored, leaving
+/// the configured default position.
+pub fn restore_overlay_state(app: &AppHandle, access: &DatabaseAccess) -> Result<(), String> {
+    let overlay = match app.get_webview_window("overlay") {
+        Some(overlay) => overlay,
+        None => return Ok(()),
+    };
+
+    let monitors = monitors(&overlay)?;
+    let geometry = match load_window_state(access, &layout_key(&monitors))? {
+        Some(geometry) if is_reachable(&geometry, &monitors) => geometry,
+        Some(_) => {
+            log::info!("Saved overlay position is off-screen; using the default");
+            return Ok(());
+        }
+        None => return Ok(()),
+    };
+
+    overlay
+        .set_position(tauri::Position::Physical(tauri::PhysicalPosition {
+            x: geometry.x,
+            y: geometry.y,
+        }))
+        .map_err(|e| e.to_string())?;
+    overlay
+        .set_size(tauri::Size::Physical(tauri::PhysicalSize {
+            width: geometry.width,
+            height: geometry.height,
+        }))
+        .map_err(|e| e.to_string())?;
+    if geometry.visible {
+        overlay.show().map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Save the overlay state after a change, logging instead of failing
+pub fn remember_overlay_state(app: &AppHandle) {
+    if let Some(access) = app.try_state::<DatabaseAccess>() {
+        if let Err(e) = save_overlay_state(app, &access) {
+            log::warn!("Failed to save overlay window state: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn monitor(name: &str, x: i32, width: u32) -> MonitorBounds {
+        MonitorBounds {
+            name: Some(name.to_string()),
+            x,
+            y: 0,
+            width,
+            height: 1080,
+        }
+    }
+
+    fn geometry(x: i32) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y: 400,
+            width: 1000,
+            height: 200,
+            visible: true,
+        }
+    }
+
+    #[test]
+    fn test_layout_key_ignores_order() {
+        let a = monitor("DP-1", 0, 1920);
+        let b = monitor("HDMI-1", 1920, 2560);
+        assert_eq!(
+            layout_key(&[a.clone(), b.clone()]),
+            layout_key(&[b.clone(), a.clone()])
+        );
+        assert_ne!(layout_key(&[a, b.clone()]), layout_key(&[b]));
+    }
+
+    #[test]
+    fn test_is_reachable() {
+        let monitors = [monitor("DP-1", 0, 1920)];
+        assert!(is_reachable(&geometry(460), &monitors));
+        assert!(is_reachable(&geometry(1800), &monitors));
+        assert!(!is_reachable(&geometry(1880), &monitors));
+        assert!(!is_reachable(&geometry(2500), &monitors));
+        assert!(!is_reachable(&geometry(-990), &monitors));
+    }
+
+    #[test]
+    fn test_window_state_per_layout() {
+        let temp_file = NamedTempFile::new().unwrap();
+        crate::database::init(temp_file.path()).unwrap();
+        let access = DatabaseAccess::new(temp_file.path().to_path_buf());
+
+        assert_eq!(load_window_state(&access, "single").unwrap(), None);
+
+        save_window_state(&access, "single", &geometry(100)).unwrap();
+        save_window_state(&access, "docked", &geometry(2000)).unwrap();
+        save_window_state(&access, "single", &geometry(200)).unwrap();
+
+        assert_eq!(
+            load_window_state(&access, "single").unwrap(),
+            Some(geometry(200))
+        );
+        assert_eq!(
+            load_window_state(&access, "docked").unwrap(),
+            Some(geometry(2000))
+        );
+    }
+}
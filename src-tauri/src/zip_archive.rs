@@ -0,0 +1,253 @@
+//! Minimal zip reading and writing
+//!
+//! Enough of the zip format for app data bundles: deflated or stored
+//! entries, no encryption, no zip64 (entries and archives stay under
+//! 4 GiB). Built on `flate2`, which is already used for OCR archives.
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use std::io::{self, Read, Write};
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+const END_OF_CENTRAL_DIRECTORY_LEN: usize = 22;
+
+/// Version 2.0: deflate
+const VERSION: u16 = 20;
+/// Entry names are UTF-8
+const FLAG_UTF8: u16 = 0x0800;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+/// Largest uncompressed entry `read` accepts
+const MAX_ENTRY_SIZE: u64 = 1 << 30;
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn too_large() -> io::Error {
+    invalid("Entry too large for a zip archive")
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Current local time in MS-DOS (time, date) format
+fn dos_timestamp() -> (u16, u16) {
+    use chrono::{Datelike, Local, Timelike};
+
+    let now = Local::now();
+    let time = (now.hour() << 11) | (now.minute() << 5) | (now.second() / 2);
+    let date = ((now.year().clamp(1980, 2107) as u32 - 1980) << 9) | (now.month() << 5) | now.day();
+    (time as u16, date as u16)
+}
+
+/// Build a zip archive of deflated `(name, contents)` entries
+pub fn write(entries: &[(&str, &[u8])]) -> io::Result<Vec<u8>> {
+    let (time, date) = dos_timestamp();
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+
+    for (name, contents) in entries {
+        let mut crc = Crc::new();
+        crc.update(contents);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(contents)?;
+        let compressed = encoder.finish()?;
+
+        let offset = u32::try_from(out.len()).map_err(|_| too_large())?;
+        let compressed_len = u32::try_from(compressed.len()).map_err(|_| too_large())?;
+        let len = u32::try_from(contents.len()).map_err(|_| too_large())?;
+        let name_len = u16::try_from(name.len()).map_err(|_| invalid("Entry name too long"))?;
+
+        put_u32(&mut out, LOCAL_HEADER_SIGNATURE);
+        put_u16(&mut out, VERSION);
+        put_u16(&mut out, FLAG_UTF8);
+        put_u16(&mut out, METHOD_DEFLATED);
+        put_u16(&mut out, time);
+        put_u16(&mut out, date);
+        put_u32(&mut out, crc.sum());
+        put_u32(&mut out, compressed_len);
+        put_u32(&mut out, len);
+        put_u16(&mut out, name_len);
+        put_u16(&mut out, 0);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&compressed);
+
+        put_u32(&mut central, CENTRAL_HEADER_SIGNATURE);
+        put_u16(&mut central, VERSION);
+        put_u16(&mut central, VERSION);
+        put_u16(&mut central, FLAG_UTF8);
+        put_u16(&mut central, METHOD_DEFLATED);
+        put_u16(&mut central, time);
+        put_u16(&mut central, date);
+        put_u32(&mut central, crc.sum());
+        put_u32(&mut central, compressed_len);
+        put_u32(&mut central, len);
+        put_u16(&mut central, name_len);
+        // Extra field, comment, disk number, internal and external attributes
+        put_u16(&mut central, 0);
+        put_u16(&mut central, 0);
+        put_u16(&mut central, 0);
+        put_u16(&mut central, 0);
+        put_u32(&mut central, 0);
+        put_u32(&mut central, offset);
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let count = u16::try_from(entries.len()).map_err(|_| invalid("Too many entries"))?;
+    let central_offset = u32::try_from(out.len()).map_err(|_| too_large())?;
+    let central_len = u32::try_from(central.len()).map_err(|_| too_large())?;
+    out.extend_from_slice(&central);
+
+    put_u32(&mut out, END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+    put_u16(&mut out, 0);
+    put_u16(&mut out, 0);
+    put_u16(&mut out, count);
+    put_u16(&mut out, count);
+    put_u32(&mut out, central_len);
+    put_u32(&mut out, central_offset);
+    put_u16(&mut out, 0);
+    Ok(out)
+}
+
+/// Little-endian reads at fixed offsets, failing on truncated data
+struct Bytes<'a>(&'a [u8]);
+
+impl Bytes<'_> {
+    fn u16(&self, at: usize) -> io::Result<u16> {
+        self.0
+            .get(at..at + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .ok_or_else(|| invalid("Truncated zip archive"))
+    }
+
+    fn u32(&self, at: usize) -> io::Result<u32> {
+        self.0
+            .get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| invalid("Truncated zip archive"))
+    }
+
+    fn slice(&self, at: usize, len: usize) -> io::Result<&[u8]> {
+        self.0
+            .get(at..at + len)
+            .ok_or_else(|| invalid("Truncated zip archive"))
+    }
+}
+
+/// Read every entry of a zip archive as `(name, contents)`
+///
+/// Directory entries are skipped. Fails on unsupported compression,
+/// checksum mismatches and entries over 1 GiB.
+pub fn read(archive: &[u8]) -> io::Result<Vec<(String, Vec<u8>)>> {
+    let bytes = Bytes(archive);
+
+    // The end record sits at the very end, before an optional comment
+    let end = (0..=archive.len().saturating_sub(END_OF_CENTRAL_DIRECTORY_LEN))
+        .rev()
+        .take(u16::MAX as usize + 1)
+        .find(|&at| bytes.u32(at).ok() == Some(END_OF_CENTRAL_DIRECTORY_SIGNATURE))
+        .ok_or_else(|| invalid("Not a zip archive"))?;
+    let count = bytes.u16(end + 10)?;
+    let mut at = bytes.u32(end + 16)? as usize;
+
+    let mut entries = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        if bytes.u32(at)? != CENTRAL_HEADER_SIGNATURE {
+            return Err(invalid("Corrupt zip central directory"));
+        }
+        let method = bytes.u16(at + 10)?;
+        let crc = bytes.u32(at + 16)?;
+        let compressed_len = bytes.u32(at + 20)? as usize;
+        let len = bytes.u32(at + 24)? as u64;
+        let name_len = bytes.u16(at + 28)? as usize;
+        let extra_len = bytes.u16(at + 30)? as usize;
+        let comment_len = bytes.u16(at + 32)? as usize;
+        let offset = bytes.u32(at + 42)? as usize;
+        let name = String::from_utf8_lossy(bytes.slice(at + 46, name_len)?).to_string();
+        at += 46 + name_len + extra_len + comment_len;
+
+        if name.ends_with('/') {
+            continue;
+        }
+        if len > MAX_ENTRY_SIZE {
+            return Err(invalid("Zip entry too large"));
+        }
+
+        if bytes.u32(offset)? != LOCAL_HEADER_SIGNATURE {
+            return Err(invalid("Corrupt zip entry header"));
+        }
+        let data_start =
+            offset + 30 + bytes.u16(offset + 26)? as usize + bytes.u16(offset + 28)? as usize;
+        let data = bytes.slice(data_start, compressed_len)?;
+
+        let contents = match method {
+            METHOD_STORED => data.to_vec(),
+            METHOD_DEFLATED => {
+                let mut contents = Vec::with_capacity(len as usize);
+                DeflateDecoder::new(data)
+                    .take(len + 1)
+                    .read_to_end(&mut contents)?;
+                contents
+            }
+            _ => return Err(invalid("Unsupported zip compression method")),
+        };
+
+        let mut actual = Crc::new();
+        actual.update(&contents);
+        if contents.len() as u64 != len || actual.sum() != crc {
+            return Err(invalid("Zip entry checksum mismatch"));
+        }
+        entries.push((name, contents));
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let large = vec![b'x'; 100_000];
+        let archive = write(&[
+            ("manifest.json", b"{\"format\":1}"),
+            ("empty.txt", b""),
+            ("data/large.bin", &large),
+        ])
+        .unwrap();
+        // Repetitive data is actually compressed
+        assert!(archive.len() < 10_000);
+
+        let entries = read(&archive).unwrap();
+        let names: Vec<_> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["manifest.json", "empty.txt", "data/large.bin"]);
+        assert_eq!(entries[0].1, b"{\"format\":1}");
+        assert!(entries[1].1.is_empty());
+        assert_eq!(entries[2].1, large);
+    }
+
+    #[test]
+    fn test_rejects_corrupt_archives() {
+        assert!(read(b"").is_err());
+        assert!(read(b"definitely not a zip archive").is_err());
+
+        let mut archive = write(&[("a.txt", b"hello hello hello")]).unwrap();
+        // Flip a byte of the compressed data
+        archive[31 + "a.txt".len()] ^= 0xff;
+        assert!(read(&archive).is_err());
+
+        let archive = write(&[("a.txt", b"hello")]).unwrap();
+        assert!(read(&archive[..archive.len() - 4]).is_err());
+    }
+}
@@ -214,6 +214,11 @@ function CardScoreDisplay({ card, getTierColor, getScoreColor, onSelect }: CardS
       <div className={`text-4xl font-bold mb-2 ${getScoreColor(card.score)}`}>
         {card.score}
       </div>
+      {card.percentile !== null && (
+        <div className="text-xs text-gray-500 -mt-2 mb-2">
+          Top {Math.max(1, Math.round(100 - card.percentile))}% for this champion
+        </div>
+      )}
       
       {/* Tier Badge */}
       <div className={`text-lg font-bold mb-1 ${getTierColor(card.tier)}`}>
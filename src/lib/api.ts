@@ -19,11 +19,30 @@ import type {
 // Error Handling
 // ============================================================================
 
-class ApiError extends Error {
+/**
+ * Structured error returned by commands that fail with `AppError`
+ */
+interface CommandError {
+  code: string;
+  message: string;
+}
+
+function isCommandError(error: unknown): error is CommandError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as CommandError).code === 'string' &&
+    typeof (error as CommandError).message === 'string'
+  );
+}
+
+export class ApiError extends Error {
   constructor(
     message: string,
     public readonly command: string,
-    public readonly originalError: unknown
+    public readonly originalError: unknown,
+    /** Error kind such as `not_found` or `invalid_input`, when the command reports one */
+    public readonly code?: string
   ) {
     super(message);
     this.name = 'ApiError';
@@ -35,10 +54,16 @@ async function invokeCommand<T>(command: string, args?: Record<string, unknown>)
     return await invoke<T>(command, args);
   } catch (error) {
     console.error(`API Error [${command}]:`, error);
+    const detail = isCommandError(error)
+      ? error.message
+      : error instanceof Error
+        ? error.message
+        : String(error);
     throw new ApiError(
-      `Failed to execute ${command}: ${error instanceof Error ? error.message : String(error)}`,
+      `Failed to execute ${command}: ${detail}`,
       command,
-      error
+      error,
+      isCommandError(error) ? error.code : undefined
     );
   }
 }
@@ -96,6 +121,10 @@ export async function calculateDraftScore(
     champion: request.champion,
     ring_number: request.ringNumber,
     covenant: request.covenant,
+    champion_path: request.championPath,
+    upcoming_boss: request.upcomingBoss,
+    pact_shards: request.pactShards,
+    upgrade_count: request.upgradeCount,
   };
   
   const response = await invokeCommand<DraftScoreResponse>('calculate_draft_score', {
@@ -120,6 +149,311 @@ export async function getContextModifiers(): Promise<string[]> {
   return response;
 }
 
+export interface OfferForecast {
+  card_id: string;
+  card_name: string;
+  current_score: number;
+  expected_quality: number;
+  /** Expected final deck quality gained over skipping the offer */
+  marginal_value: number;
+}
+
+export interface DraftForecast {
+  simulations: number;
+  remaining_picks: number;
+  current_quality: number;
+  expected_quality: number;
+  /** Highest marginal value first */
+  offers: OfferForecast[];
+  recommended: string | null;
+}
+
+/**
+ * Simulate the rest of the draft to value each offered card.
+ * Empty or zero arguments use the draft session; `offered` defaults to the last detected offer.
+ */
+export async function forecastDraft(
+  deck: string[] = [],
+  champion = '',
+  ring = 0,
+  covenant = 0,
+  n?: number,
+  offered?: string[]
+): Promise<DraftForecast> {
+  return await invokeCommand<DraftForecast>('forecast_draft', {
+    deck,
+    champion,
+    ring,
+    covenant,
+    n,
+    offered,
+  });
+}
+
+export interface DeckMetrics {
+  /** Pairs of deck cards with a synergy between them */
+  synergy_count: number;
+  weaknesses: Weakness[];
+  average_cost: number | null;
+}
+
+export interface PickComparison {
+  card_id: string;
+  card_name: string;
+  /** Draft score against the current deck */
+  score: number;
+  synergies_added: number;
+  /** Weaknesses the deck no longer has */
+  fixes: Weakness[];
+  /** Weaknesses the deck would gain */
+  introduces: Weakness[];
+  average_cost_change: number | null;
+  /** The deck's analysis with the card added */
+  metrics: DeckMetrics;
+}
+
+export interface PickComparisons {
+  current: DeckMetrics;
+  /** Highest score first */
+  picks: PickComparison[];
+}
+
+/**
+ * Compare what picking each card would do to the deck's synergies, weaknesses and curve.
+ * Empty or zero arguments use the draft session; `cardIds` defaults to the last detected offer.
+ */
+export async function comparePicks(
+  cardIds?: string[],
+  deck: string[] = [],
+  champion = '',
+  ring = 0,
+  covenant = 0
+): Promise<PickComparisons> {
+  return await invokeCommand<PickComparisons>('compare_picks', {
+    cardIds,
+    deck,
+    champion,
+    ring,
+    covenant,
+  });
+}
+
+export type ShopOffer =
+  | { kind: 'card'; card_id: string; price: number }
+  | { kind: 'upgrade'; name: string; price: number; target_card_id?: string | null }
+  | { kind: 'removal'; price: number };
+
+export type ShopReason =
+  | { code: 'draft_score'; args: { score: number } }
+  | { code: 'dilutes'; args: { score: number } }
+  | { code: 'upgrades'; args: { card_name: string } }
+  | { code: 'removes'; args: { card_name: string; score: number } }
+  | { code: 'nothing_to_remove' }
+  | { code: 'unaffordable'; args: { short_by: number } }
+  | { code: 'opportunity_cost'; args: { value_lost: number } };
+
+export interface OfferEvaluation {
+  offer: ShopOffer;
+  /** Net value in score points; 0 or less is not worth buying */
+  value: number;
+  /** Value per 100 gold */
+  efficiency: number | null;
+  affordable: boolean;
+  recommended: boolean;
+  reasons: ShopReason[];
+}
+
+export interface ShopEvaluation {
+  gold: number;
+  spent: number;
+  remaining_gold: number;
+  total_value: number;
+  /** Recommended offers first, then by value */
+  offers: OfferEvaluation[];
+}
+
+/**
+ * Value the merchant's offers for the deck and recommend what to buy with `gold`.
+ * An empty deck uses the draft session's.
+ */
+export async function evaluateShop(
+  offers: ShopOffer[],
+  gold: number,
+  deck: string[] = []
+): Promise<ShopEvaluation> {
+  return await invokeCommand<ShopEvaluation>('evaluate_shop', { offers, gold, deck });
+}
+
+export type MapNodeKind = 'shop' | 'unit_banner' | 'spell_banner' | 'pyre_heal';
+
+export interface MapNode {
+  /** Nodes with the same branch form one route */
+  branch: number;
+  kind: MapNodeKind;
+}
+
+export type Weakness =
+  | { code: 'no_frontline' }
+  | { code: 'no_backline_clear' }
+  | { code: 'top_heavy' }
+  | { code: 'few_units' }
+  | { code: 'few_spells' }
+  | { code: 'weak_cards'; args: { count: number } }
+  | { code: 'low_pyre'; args: { health: number } };
+
+export interface NodeEvaluation {
+  kind: MapNodeKind;
+  value: number;
+  /** Weaknesses the node helps with */
+  addresses: Weakness[];
+}
+
+export interface BranchEvaluation {
+  branch: number;
+  value: number;
+  nodes: NodeEvaluation[];
+}
+
+export interface RouteRecommendation {
+  weaknesses: Weakness[];
+  /** Most valuable first */
+  branches: BranchEvaluation[];
+  recommended: number | null;
+}
+
+/**
+ * Value each branch of the map for the deck's weaknesses and recommend one.
+ * An empty deck uses the draft session's; `pyreHealth` is a percentage.
+ */
+export async function recommendRoute(
+  mapNodes: MapNode[],
+  gold: number,
+  deck: string[] = [],
+  pyreHealth?: number
+): Promise<RouteRecommendation> {
+  return await invokeCommand<RouteRecommendation>('recommend_route', {
+    mapNodes,
+    deck,
+    gold,
+    pyreHealth,
+  });
+}
+
+export interface Archetype {
+  id: string;
+  name: string;
+  description: string;
+  /** How much a card with each keyword fits, from 0 to 1 */
+  keyword_weights: Record<string, number>;
+  key_cards: string[];
+  /** Share of cards wanted at costs 0, 1, 2, 3 and 4+, if any */
+  ideal_curve: number[] | null;
+  active: boolean;
+}
+
+export interface ArchetypeMatch {
+  archetype_id: string;
+  name: string;
+  /** From 0 to 1 */
+  confidence: number;
+  matching_cards: string[];
+}
+
+/**
+ * Archetypes a deck fits, best first; an empty deck uses the draft session
+ */
+export async function detectArchetype(deck: string[] = []): Promise<ArchetypeMatch[]> {
+  return await invokeCommand<ArchetypeMatch[]>('detect_archetype', { deck });
+}
+
+export async function getArchetypes(): Promise<Archetype[]> {
+  return await invokeCommand<Archetype[]>('get_archetypes');
+}
+
+/**
+ * Add an archetype definition, or replace the one with the same ID
+ */
+export async function saveArchetype(archetype: Archetype): Promise<void> {
+  await invokeCommand<void>('save_archetype', { archetype });
+}
+
+export async function deleteArchetype(id: string): Promise<void> {
+  await invokeCommand<void>('delete_archetype', { id });
+}
+
+export interface AuditEntry {
+  id: number;
+  /** Kind of data edited, such as 'archetype' or 'expansion' */
+  entity: string;
+  entity_id: string;
+  action: 'create' | 'update' | 'delete' | 'enable' | 'disable' | 'import';
+  before: unknown | null;
+  after: unknown | null;
+  changed_at: string;
+}
+
+export interface AuditFilter {
+  entity?: string;
+  entity_id?: string;
+  /** Maximum number of entries (default 100) */
+  limit?: number;
+}
+
+/**
+ * List logged edits to scoring data, newest first
+ */
+export async function getAuditLog(filter?: AuditFilter): Promise<AuditEntry[]> {
+  return await invokeCommand<AuditEntry[]>('get_audit_log', { filter });
+}
+
+export interface ChampionPath {
+  champion: string;
+  path: string;
+  description: string;
+  /** How much a card with each keyword feeds the path, from 0 to 1 */
+  scaling_keywords: Record<string, number>;
+}
+
+/**
+ * Upgrade paths with scaling data, for one champion or all of them
+ */
+export async function getChampionPaths(champion?: string): Promise<ChampionPath[]> {
+  return await invokeCommand<ChampionPath[]>('get_champion_paths', { champion });
+}
+
+export interface Boss {
+  id: string;
+  name: string;
+  /** Ring the boss is fought at */
+  ring: number;
+  /** Lowest covenant the boss appears at */
+  min_covenant: number;
+  mechanics: string[];
+  description: string;
+}
+
+/**
+ * The boss lineup, or only the bosses a run can still meet from `ring` on
+ * at `covenant` when both are given
+ */
+export async function getBosses(ring?: number, covenant?: number): Promise<Boss[]> {
+  return await invokeCommand<Boss[]>('get_bosses', { ring, covenant });
+}
+
+export interface FixtureReport {
+  name: string;
+  /** Score the candidate got, if it could be scored */
+  score: number | null;
+  passed: boolean;
+  /** What didn't match, empty when passed */
+  failures: string[];
+}
+
+/** Run the scoring fixtures in `dir` (development only) */
+export async function validateScoringFixtures(dir: string): Promise<FixtureReport[]> {
+  return await invokeCommand<FixtureReport[]>('validate_scoring_fixtures', { dir });
+}
+
 // ============================================================================
 // OCR API
 // ============================================================================
@@ -132,6 +466,13 @@ export async function detectCardsOnScreen(): Promise<CardDetectionResult> {
   return response;
 }
 
+/**
+ * Abort the detection pass in flight; resolves to whether one was running
+ */
+export async function cancelDetection(): Promise<boolean> {
+  return await invokeCommand<boolean>('cancel_detection');
+}
+
 /**
  * Calibrate OCR detection regions
  */
@@ -140,6 +481,129 @@ export async function calibrateOcrRegions(): Promise<CalibrationResult> {
   return response;
 }
 
+/** How screen captures are taken; `auto` uses the portal under Wayland */
+export type CaptureBackend = 'auto' | 'screenshots' | 'portal';
+
+export interface BackendAvailability {
+  backend: CaptureBackend;
+  available: boolean;
+  reason: string | null;
+}
+
+export interface CaptureBackendStatus {
+  /** Backend chosen in the OCR settings */
+  requested: CaptureBackend;
+  /** Backend captures go through, with `auto` resolved */
+  active: CaptureBackend;
+  session: 'windows' | 'mac_os' | 'x11' | 'wayland' | 'unknown';
+  available: boolean;
+  reason: string | null;
+  backends: BackendAvailability[];
+  /** Error from the test capture, when `probe` was set and it failed */
+  probe_error: string | null;
+}
+
+/**
+ * Which capture backend is active and which can run here; `probe` also
+ * takes a test capture
+ */
+export async function getCaptureBackendStatus(probe?: boolean): Promise<CaptureBackendStatus> {
+  return await invokeCommand<CaptureBackendStatus>('get_capture_backend_status', { probe });
+}
+
+/** Text recognizer; `template_glyph` reads names without Tesseract */
+export type RecognitionEngine = 'tesseract' | 'template_glyph';
+
+export interface LearnReport {
+  /** Matched regions whose glyphs were added */
+  samples_used: number;
+  /** Matched regions whose glyph count didn't agree with the card name */
+  samples_skipped: number;
+  /** Characters the atlas now has templates for */
+  characters: string;
+}
+
+/**
+ * Teach the template glyph engine from a recorded OCR session folder
+ */
+export async function buildGlyphAtlas(sessionDir: string): Promise<LearnReport> {
+  return await invokeCommand<LearnReport>('build_glyph_atlas', { sessionDir });
+}
+
+export interface ConfusedPair {
+  /** Card OCR detected */
+  detected_card_id: string;
+  detected_card_name: string;
+  /** Card it actually was */
+  actual_card_id: string;
+  actual_card_name: string;
+  corrections: number;
+  last_reported_at: string;
+}
+
+/** Part of the game UI a capture region covers */
+export type RegionRole =
+  | 'card_name1'
+  | 'card_name2'
+  | 'card_name3'
+  | 'card_name4'
+  | 'card_text1'
+  | 'card_text2'
+  | 'card_text3'
+  | 'card_text4'
+  | 'card_upgrades1'
+  | 'card_upgrades2'
+  | 'card_upgrades3'
+  | 'card_upgrades4'
+  | 'champion_name'
+  | 'covenant'
+  | 'gold'
+  | 'ring';
+
+export interface CaptureRegionInfo {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  role: RegionRole | null;
+}
+
+export interface RegionConfidence {
+  region: CaptureRegionInfo;
+  /** Index among the current capture regions, or null if no longer configured */
+  region_index: number | null;
+  detections: number;
+  average_confidence: number;
+  corrected: number;
+}
+
+export interface OcrConfusionReport {
+  confused_pairs: ConfusedPair[];
+  regions: RegionConfidence[];
+}
+
+/**
+ * Cards OCR confuses most often and capture regions with the lowest confidence
+ */
+export async function getOcrConfusionReport(limit?: number): Promise<OcrConfusionReport> {
+  return await invokeCommand<OcrConfusionReport>('get_ocr_confusion_report', { limit });
+}
+
+export interface MatchCandidate {
+  card_id: string;
+  card_name: string;
+  /** Normalized match score (0-100) */
+  match_score: number;
+}
+
+/**
+ * Cards matching typed text, best first, using the same fuzzy matcher as
+ * OCR; a manual fallback when a card can't be read
+ */
+export async function matchTextToCards(text: string, limit?: number): Promise<MatchCandidate[]> {
+  return await invokeCommand<MatchCandidate[]>('match_text_to_cards', { text, limit });
+}
+
 // ============================================================================
 // Window/Overlay API
 // ============================================================================
@@ -235,6 +699,8 @@ export interface DeckExportData {
   covenant: number;
   ring: number;
   cards: ExportedCard[];
+  /** Artifact names, added in deck file version 1.1 */
+  artifacts?: string[];
   metadata: ExportMetadata;
 }
 
@@ -252,6 +718,12 @@ export interface ExportMetadata {
   synergyCount: number;
 }
 
+export interface SharedDeck {
+  champion: string;
+  covenant: number;
+  card_ids: string[];
+}
+
 export interface ExportFormat {
   id: string;
   name: string;
@@ -259,11 +731,24 @@ export interface ExportFormat {
   description: string;
 }
 
+export type DeckFormat = 'json' | 'markdown' | 'text';
+
 /**
- * Export deck to JSON file
+ * Export deck to a file, as JSON unless another format is given
  */
-export async function exportDeck(deckData: DeckExportData, filePath: string): Promise<void> {
-  await invokeCommand('export_deck', { deckData, filePath });
+export async function exportDeck(
+  deckData: DeckExportData,
+  filePath: string,
+  format: DeckFormat = 'json'
+): Promise<void> {
+  await invokeCommand('export_deck', { deckData, filePath, format });
+}
+
+/**
+ * Render the deck list to a PNG file
+ */
+export async function exportDeckImage(deck: DeckExportData, path: string): Promise<void> {
+  await invokeCommand('export_deck_image', { deck, path });
 }
 
 /**
@@ -273,11 +758,285 @@ export async function importDeck(filePath: string): Promise<DeckExportData> {
   return await invokeCommand<DeckExportData>('import_deck', { filePath });
 }
 
+/**
+ * Copy deck to the clipboard, as JSON unless another format is given
+ */
+export async function copyDeckToClipboard(
+  deckData: DeckExportData,
+  format: DeckFormat = 'json'
+): Promise<void> {
+  await invokeCommand('copy_deck_to_clipboard', { deckData, format });
+}
+
+/**
+ * Import deck from JSON on the clipboard
+ */
+export async function importDeckFromClipboard(): Promise<DeckExportData> {
+  return await invokeCommand<DeckExportData>('import_deck_from_clipboard');
+}
+
+/**
+ * Encode a deck as a share code for pasting into chat
+ */
+export async function exportShareCode(deck: SharedDeck): Promise<string> {
+  return await invokeCommand<string>('export_share_code', { deck });
+}
+
+/**
+ * Decode a deck from a share code
+ */
+export async function importShareCode(code: string): Promise<SharedDeck> {
+  return await invokeCommand<SharedDeck>('import_share_code', { code });
+}
+
 /**
  * Export deck history to CSV
+ *
+ * Resolves to false, with nothing written, if cancelExport is called first.
  */
-export async function exportHistoryCsv(filePath: string): Promise<void> {
-  await invokeCommand('export_history_csv', { filePath });
+export async function exportHistoryCsv(filePath: string): Promise<boolean> {
+  try {
+    await invokeCommand('export_history_csv', { filePath });
+    return true;
+  } catch (error) {
+    if (error instanceof ApiError && error.code === 'cancelled') {
+      return false;
+    }
+    throw error;
+  }
+}
+
+/**
+ * Cancel a history export in progress; nothing is written
+ */
+export async function cancelExport(): Promise<void> {
+  await invokeCommand('cancel_export');
+}
+
+export interface ImportIssue {
+  line: number;
+  message: string;
+}
+
+export interface HistoryImportReport {
+  dry_run: boolean;
+  total_rows: number;
+  new_rows: number;
+  duplicates: number;
+  imported: number;
+  runs_created: number;
+  errors: ImportIssue[];
+}
+
+/** Event emitted while seeding a new profile's database or importing history */
+export const LOAD_PROGRESS_EVENT = 'load-progress';
+
+export interface LoadProgress {
+  stage: string;
+  done: number;
+  total: number;
+}
+
+/**
+ * Import deck history from a CSV written by exportHistoryCsv
+ *
+ * Large imports report progress with LOAD_PROGRESS_EVENT.
+ */
+export async function importHistoryCsv(path: string, dryRun = false): Promise<HistoryImportReport> {
+  return await invokeCommand<HistoryImportReport>('import_history_csv', { path, dryRun });
+}
+
+export interface CommunityImportReport {
+  dry_run: boolean;
+  source: string;
+  total_rows: number;
+  /** Cards written, replacing any earlier import */
+  imported: number;
+  errors: ImportIssue[];
+}
+
+/**
+ * Import community-published card stats from a .csv or .json file
+ */
+export async function importCommunityStats(
+  path: string,
+  dryRun = false
+): Promise<CommunityImportReport> {
+  return await invokeCommand<CommunityImportReport>('import_community_stats', { path, dryRun });
+}
+
+export interface ScoringPreferences {
+  use_history_adjustment: boolean;
+  use_community_stats: boolean;
+  /** Share of the community adjustment applied (0-1) */
+  community_weight: number;
+}
+
+/**
+ * Turn the community stats blend on or off, optionally changing its weight
+ */
+export async function setCommunityStatsEnabled(
+  enabled: boolean,
+  weight?: number
+): Promise<ScoringPreferences> {
+  return await invokeCommand<ScoringPreferences>('set_community_stats_enabled', { enabled, weight });
+}
+
+export type ReportFormat = 'json' | 'markdown';
+
+/**
+ * Export a pick-by-pick review of a run, with scores and passed-on cards
+ */
+export async function exportRunReport(
+  runId: string,
+  path: string,
+  format: ReportFormat = 'markdown'
+): Promise<void> {
+  await invokeCommand('export_run_report', { runId, path, format });
+}
+
+export interface PickReview {
+  card_id: string;
+  card_name: string;
+  ring_number: number;
+  draft_order: number;
+  score_at_draft: number | null;
+  /** Score against the rest of the final deck */
+  hindsight_score: number;
+  /** Best-scoring card passed over in the same ring */
+  best_alternative: {
+    card_id: string;
+    card_name: string;
+    /** Score recorded when the card was offered */
+    score_at_draft: number | null;
+    hindsight_score: number;
+  } | null;
+  /** The best alternative beat the pick by a tier or more */
+  missed: boolean;
+}
+
+export interface RunReview {
+  run_id: string;
+  champion: string;
+  covenant: number;
+  did_win: boolean | null;
+  picks: PickReview[];
+  missed_picks: number;
+  /** Hindsight points the missed picks gave up */
+  value_lost: number;
+}
+
+/**
+ * Rescore a finished run's picks against its final deck and flag the ones a passed-over card
+ * would have beaten
+ */
+export async function analyzeRun(runId: string): Promise<RunReview> {
+  return await invokeCommand<RunReview>('analyze_run', { runId });
+}
+
+export interface PickRate {
+  card_id: string;
+  card_name: string;
+  times_offered: number;
+  times_picked: number;
+  pick_rate: number;
+}
+
+export interface SynergyPairStats {
+  card_a_id: string;
+  card_a_name: string;
+  card_b_id: string;
+  card_b_name: string;
+  synergy_type: string;
+  /** Completed runs that drafted both cards */
+  runs: number;
+  wins: number;
+  win_rate: number;
+}
+
+export interface MetaStats {
+  /** Highest pick rate first, among cards offered at least 3 times */
+  most_picked: PickRate[];
+  score_by_outcome: {
+    winning_runs: number;
+    losing_runs: number;
+    /** Mean of each winning deck's average score at draft */
+    winning_average: number | null;
+    losing_average: number | null;
+  };
+  /** Highest win rate first, among pairs drafted in at least 2 completed runs */
+  synergy_pairs: SynergyPairStats[];
+}
+
+/**
+ * Get pick rates, scores by outcome and winning synergy pairs for the stats dashboard
+ */
+export async function getMetaStats(): Promise<MetaStats> {
+  return await invokeCommand<MetaStats>('get_meta_stats');
+}
+
+/**
+ * Hide a run from the run list (still counted in stats); `archived: false` unarchives it
+ */
+export async function archiveRun(runId: string, archived?: boolean): Promise<void> {
+  return await invokeCommand<void>('archive_run', { runId, archived });
+}
+
+/**
+ * Leave a run out of the history and stats until it's restored or purged
+ */
+export async function deleteRun(runId: string): Promise<void> {
+  return await invokeCommand<void>('delete_run', { runId });
+}
+
+export async function restoreRun(runId: string): Promise<void> {
+  return await invokeCommand<void>('restore_run', { runId });
+}
+
+export interface PurgeReport {
+  runs: number;
+  picks: number;
+  offers: number;
+}
+
+/**
+ * Permanently remove deleted runs with their picks and offers
+ */
+export async function purgeDeletedRuns(): Promise<PurgeReport> {
+  return await invokeCommand<PurgeReport>('purge_deleted_runs');
+}
+
+export type ImportConflictPolicy ='replace' | 'keep_existing' | 'prefer_imported';
+
+export interface BundleManifest {
+  format: number;
+  app_version: string;
+  schema_version: number;
+  exported_at: string;
+}
+
+export interface AppDataImportReport {
+  policy: ImportConflictPolicy;
+  manifest: BundleManifest;
+  replaced_database: boolean;
+  merged_rows: number;
+}
+
+/**
+ * Export the database and settings to a single zip file
+ */
+export async function exportAppData(path: string): Promise<void> {
+  await invokeCommand('export_app_data', { path });
+}
+
+/**
+ * Import a zip written by exportAppData, applying its settings
+ */
+export async function importAppData(
+  path: string,
+  policy: ImportConflictPolicy = 'keep_existing'
+): Promise<AppDataImportReport> {
+  return await invokeCommand<AppDataImportReport>('import_app_data', { path, policy });
 }
 
 /**
@@ -286,3 +1045,234 @@ export async function exportHistoryCsv(filePath: string): Promise<void> {
 export async function getExportFormats(): Promise<ExportFormat[]> {
   return await invokeCommand<ExportFormat[]>('get_export_formats');
 }
+
+// ============================================================================
+// Logging API
+// ============================================================================
+
+export interface LogFileInfo {
+  path: string;
+  size: number;
+  modified: string | null;
+  current: boolean;
+}
+
+/**
+ * List the current log file and its rotated archives, newest first
+ */
+export async function getLogFiles(): Promise<LogFileInfo[]> {
+  return await invokeCommand<LogFileInfo[]>('get_log_files');
+}
+
+export type LogLevel = 'off' | 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+export type LogFormat = 'text' | 'json';
+
+export interface LoggingPreferences {
+  level: LogLevel;
+  modules: Record<string, LogLevel>;
+  format: LogFormat;
+}
+
+/**
+ * Set the log level, and optionally per-module levels (e.g. { ocr: 'warn' })
+ */
+export async function setLogLevel(
+  level: LogLevel,
+  modules?: Record<string, LogLevel>
+): Promise<LoggingPreferences> {
+  return await invokeCommand<LoggingPreferences>('set_log_level', { level, modules });
+}
+
+export interface LogEntry {
+  timestamp: string;
+  level: string;
+  target: string;
+  message: string;
+}
+
+export interface LogQuery {
+  /** Keep this level and more severe ones */
+  level?: LogLevel;
+  /** RFC 3339, or a local YYYY-MM-DD[ HH:MM:SS] */
+  since?: string;
+  contains?: string;
+  limit?: number;
+}
+
+/**
+ * Read recent log entries, newest first, for the diagnostics panel
+ */
+export async function queryLogs(query: LogQuery = {}): Promise<LogEntry[]> {
+  return await invokeCommand<LogEntry[]>('query_logs', { ...query });
+}
+
+export interface DiagnosticsSummary {
+  generated_at: string;
+  system: {
+    app_version: string;
+    os: string;
+    os_family: string;
+    arch: string;
+    ocr_feature: boolean;
+    gpus: string[];
+  };
+  displays: {
+    logical_width: number;
+    logical_height: number;
+    physical_width: number;
+    physical_height: number;
+    scale_factor: number;
+    is_primary: boolean;
+  }[];
+  display_error: string | null;
+  schema_version: number | null;
+  log_files: string[];
+  debug_images: string[];
+}
+
+/**
+ * Write a diagnostics zip (logs, system and OCR info) for bug reports
+ */
+export async function generateDiagnosticsBundle(path: string): Promise<DiagnosticsSummary> {
+  return await invokeCommand<DiagnosticsSummary>('generate_diagnostics_bundle', { path });
+}
+
+export interface StageMetrics {
+  stage: string;
+  count: number;
+  total: number;
+  avg_ms: number;
+  p95_ms: number;
+  max_ms: number;
+  last_ms: number;
+}
+
+/**
+ * Rolling average and p95 latency of each OCR and scoring stage
+ */
+export async function getPerfMetrics(): Promise<StageMetrics[]> {
+  return await invokeCommand<StageMetrics[]>('get_perf_metrics');
+}
+
+export interface CrashReport {
+  timestamp: string;
+  app_version: string;
+  os: string;
+  thread: string;
+  message: string;
+  location: string | null;
+  backtrace: string;
+  log_tail: string[];
+}
+
+/**
+ * Crash report left by a previous run, kept until dismissed
+ */
+export async function getLastCrashReport(): Promise<CrashReport | null> {
+  return await invokeCommand<CrashReport | null>('get_last_crash_report');
+}
+
+/**
+ * Delete the stored crash report
+ */
+export async function dismissCrashReport(): Promise<void> {
+  await invokeCommand<void>('dismiss_crash_report');
+}
+
+// ============================================================================
+// Overlay Server API
+// ============================================================================
+
+export interface OverlayServerStatus {
+  /** Whether this build includes the overlay server */
+  available: boolean;
+  running: boolean;
+  port: number;
+  token: string;
+  /** WebSocket address for an OBS browser source, while running */
+  url: string | null;
+  clients: number;
+}
+
+/**
+ * Start the local WebSocket server for OBS overlays (also on later launches)
+ */
+export async function startOverlayServer(
+  port?: number,
+  regenerateToken?: boolean
+): Promise<OverlayServerStatus> {
+  return await invokeCommand<OverlayServerStatus>('start_overlay_server', {
+    port,
+    regenerateToken,
+  });
+}
+
+/**
+ * Stop the overlay server and keep it off on launch
+ */
+export async function stopOverlayServer(): Promise<OverlayServerStatus> {
+  return await invokeCommand<OverlayServerStatus>('stop_overlay_server');
+}
+
+export async function getOverlayServerStatus(): Promise<OverlayServerStatus> {
+  return await invokeCommand<OverlayServerStatus>('get_overlay_server_status');
+}
+
+export interface Profile {
+  id: string;
+  name: string;
+}
+
+export interface ProfileList {
+  /** ID of the profile the app is running as */
+  active: string;
+  profiles: Profile[];
+}
+
+export async function listProfiles(): Promise<ProfileList> {
+  return await invokeCommand<ProfileList>('list_profiles');
+}
+
+/**
+ * Add a profile with its own history and settings (doesn't switch to it)
+ */
+export async function createProfile(name: string): Promise<Profile> {
+  return await invokeCommand<Profile>('create_profile', { name });
+}
+
+/**
+ * Switch profiles; the app restarts on the other profile's database
+ */
+export async function switchProfile(id: string): Promise<ProfileList> {
+  return await invokeCommand<ProfileList>('switch_profile', { id });
+}
+
+export interface SyncStatus {
+  /** Whether this build includes run upload */
+  available: boolean;
+  endpoint: string | null;
+  auto_upload: boolean;
+  /** Queued runs not uploaded yet */
+  pending: number;
+  uploaded: number;
+  last_error: string | null;
+}
+
+/**
+ * Upload a finished run, anonymized; failed uploads are retried in the background
+ */
+export async function uploadRun(runId: string): Promise<SyncStatus> {
+  return await invokeCommand<SyncStatus>('upload_run', { runId });
+}
+
+/**
+ * Turn auto-upload on or off; an empty `endpoint` clears the upload URL
+ */
+export async function setAutoUpload(enabled: boolean, endpoint?: string): Promise<SyncStatus> {
+  return await invokeCommand<SyncStatus>('set_auto_upload', { enabled, endpoint });
+}
+
+export async function getSyncStatus(): Promise<SyncStatus> {
+  return await invokeCommand<SyncStatus>('get_sync_status');
+}
@@ -0,0 +1,59 @@
+/**
+ * MT2 Draft Assistant - Score explanations
+ *
+ * The backend reports why a card scored as it did with structured reason
+ * codes; this turns them into display text.
+ */
+
+import type { PenaltyKind, ScoreReason } from '../types';
+
+const signed = (n: number) => (n >= 0 ? `+${n}` : `${n}`);
+
+function formatPenaltyKind(kind: PenaltyKind): string {
+  switch (kind.type) {
+    case 'capacity':
+      return `Capacity pressure: ${kind.space} of ${kind.capacity} floor space`;
+    case 'boss':
+      return `Boss counter: ${kind.boss}`;
+    case 'pact_shards':
+      return `Endless: ${kind.pact_shards} pact shards`;
+    case 'deck_size':
+      return `Deck of ${kind.cards} cards`;
+    case 'tag':
+      return `Context: ${kind.tag}`;
+  }
+}
+
+/**
+ * English text for a score reason
+ */
+export function formatScoreReason(reason: ScoreReason): string {
+  switch (reason.code) {
+    case 'synergy_bonus':
+      return `Synergy bonus: ${reason.args.percent}%`;
+    case 'context':
+      return `Context: +${reason.args.bonus}`;
+    case 'context_penalty':
+      return `${formatPenaltyKind(reason.args.kind)} (${reason.args.penalty})`;
+    case 'champion_favorite':
+      return `Champion favorite: ${reason.args.champion}`;
+    case 'early_game_tempo':
+      return 'Early game tempo';
+    case 'late_game_value':
+      return 'Late game value';
+    case 'archetype_fit':
+      return `Fits ${reason.args.archetype}: +${reason.args.bonus}`;
+    case 'path_scaling':
+      return `Feeds ${reason.args.path} path: +${reason.args.bonus}`;
+    case 'energy_curve':
+      return `Energy curve: average ${reason.args.average_cost.toFixed(1)} vs ideal ${reason.args.ideal_cost.toFixed(1)} (${signed(reason.args.adjustment)})`;
+    case 'history':
+      return `Your history: ${reason.args.win_rate_percent}% wins over ${reason.args.runs} runs (${signed(reason.args.adjustment)})`;
+    case 'community':
+      return `Community: ${reason.args.win_rate_percent}% wins over ${reason.args.games} games (${signed(reason.args.adjustment)})`;
+    case 'upgraded':
+      return reason.args.duplicate
+        ? `Upgraded copy of a deck card x${reason.args.upgrades}: +${reason.args.bonus}`
+        : `Comes upgraded x${reason.args.upgrades}: +${reason.args.bonus}`;
+  }
+}
@@ -185,6 +185,7 @@ export const useDeckStore = create<DeckStore>()(
             cardType: 'Unknown',
             rarity: 'Common',
             cost: null,
+            size: null,
             baseValue: 0,
             tempoScore: 0,
             valueScore: 0,
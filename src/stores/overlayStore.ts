@@ -12,6 +12,7 @@ import { create } from 'zustand';
 import { devtools } from 'zustand/middleware';
 import type { Card, ScoredCard, DraftScoreResponse, OverlayPosition } from '../types';
 import * as api from '../lib/api';
+import { formatScoreReason } from '../lib/reasons';
 
 // ============================================================================
 // Types
@@ -327,6 +328,7 @@ export const useOverlayStore = create<OverlayStore>()(
             cardType: detected.cardData?.cardType || 'Unknown',
             rarity: detected.cardData?.rarity || 'Common',
             cost: detected.cardData?.cost || null,
+            size: detected.cardData?.size ?? null,
             baseValue: detected.cardData?.baseValue || 0,
             tempoScore: detected.cardData?.tempoScore || 0,
             valueScore: detected.cardData?.valueScore || 0,
@@ -335,7 +337,8 @@ export const useOverlayStore = create<OverlayStore>()(
             expansion: detected.cardData?.expansion || '',
             score: score?.score || 0,
             tier: score?.tier || '?',
-            reasons: score?.reasons || ['No score available'],
+            reasons: score?.reasons.map(formatScoreReason) || ['No score available'],
+            percentile: score?.percentile ?? null,
           } as ScoredCard;
         });
       },
@@ -15,6 +15,8 @@ export interface Card {
   cardType: string;
   rarity: string;
   cost: number | null;
+  /** Floor space the unit takes; null for cards that aren't units */
+  size: number | null;
   baseValue: number;
   tempoScore: number;
   valueScore: number;
@@ -32,10 +34,38 @@ export interface DeckCard extends Card {
 // Scoring Types
 // ============================================================================
 
+/**
+ * One part of a score's explanation, as a stable code with arguments
+ * (see formatScoreReason for the English text)
+ */
+export type ScoreReason =
+  | { code: 'synergy_bonus'; args: { percent: number } }
+  | { code: 'context'; args: { bonus: number } }
+  | { code: 'context_penalty'; args: { kind: PenaltyKind; penalty: number } }
+  | { code: 'champion_favorite'; args: { champion: string } }
+  | { code: 'early_game_tempo' }
+  | { code: 'late_game_value' }
+  | { code: 'archetype_fit'; args: { archetype: string; bonus: number } }
+  | { code: 'path_scaling'; args: { path: string; bonus: number } }
+  | { code: 'energy_curve'; args: { average_cost: number; ideal_cost: number; adjustment: number } }
+  | { code: 'history'; args: { win_rate_percent: number; runs: number; adjustment: number } }
+  | { code: 'community'; args: { win_rate_percent: number; games: number; adjustment: number } }
+  | { code: 'upgraded'; args: { upgrades: number; duplicate: boolean; bonus: number } };
+
+/** What a context penalty held against a card */
+export type PenaltyKind =
+  | { type: 'capacity'; space: number; capacity: number }
+  | { type: 'boss'; boss: string }
+  | { type: 'pact_shards'; pact_shards: number }
+  | { type: 'deck_size'; cards: number }
+  | { type: 'tag'; tag: string };
+
 export interface DraftScore {
   score: number;
   tier: string;
-  reasons: string[];
+  reasons: ScoreReason[];
+  /** Where the score falls among the champion's card pool, 0-100 */
+  percentile: number | null;
 }
 
 export interface DraftScoreRequest {
@@ -44,18 +74,28 @@ export interface DraftScoreRequest {
   champion: string;
   ringNumber: number;
   covenant: number;
+  /** Champion upgrade path; defaults to the draft session's */
+  championPath?: string;
+  /** ID or name of the boss at the end of the ring */
+  upcomingBoss?: string;
+  /** Pact shards in an endless run; defaults to the draft session's */
+  pactShards?: number;
+  /** Upgrades the offered card already has */
+  upgradeCount?: number;
 }
 
 export interface DraftScoreResponse {
   score: number;
   tier: string;
-  reasons: string[];
+  reasons: ScoreReason[];
+  percentile: number | null;
 }
 
 export interface ScoredCard extends Card {
   score: number;
   tier: string;
   reasons: string[];
+  percentile: number | null;
 }
 
 // ============================================================================